@@ -14,13 +14,17 @@ main() {
     (cd capi/bind_gen && cargo run)
 
     cp -r capi/bindings $stage/
-    cp target/$TARGET/max-opt/livesplit_core.dll $stage/livesplit_core.dll 2>/dev/null || :
-    cp target/$TARGET/max-opt/livesplit_core.lib $stage/livesplit_core.lib 2>/dev/null || :
-    cp target/$TARGET/max-opt/liblivesplit_core.a $stage/liblivesplit_core.a 2>/dev/null || :
-    cp target/$TARGET/max-opt/liblivesplit_core.so $stage/liblivesplit_core.so 2>/dev/null || :
-    cp target/$TARGET/max-opt/livesplit*.js* $stage/. 2>/dev/null || :
-    cp target/$TARGET/max-opt/deps/*.wasm $stage/livesplit.wasm 2>/dev/null || :
-    cp target/$TARGET/max-opt/liblivesplit_core.dylib $stage/liblivesplit_core.dylib 2>/dev/null || :
+    local out=target/$TARGET/max-opt-capi
+    if [ ! -d $out ]; then
+        out=target/$TARGET/max-opt
+    fi
+    cp $out/livesplit_core.dll $stage/livesplit_core.dll 2>/dev/null || :
+    cp $out/livesplit_core.lib $stage/livesplit_core.lib 2>/dev/null || :
+    cp $out/liblivesplit_core.a $stage/liblivesplit_core.a 2>/dev/null || :
+    cp $out/liblivesplit_core.so $stage/liblivesplit_core.so 2>/dev/null || :
+    cp $out/livesplit*.js* $stage/. 2>/dev/null || :
+    cp $out/deps/*.wasm $stage/livesplit.wasm 2>/dev/null || :
+    cp $out/liblivesplit_core.dylib $stage/liblivesplit_core.dylib 2>/dev/null || :
 
     cd $stage
     if [ "$OS_NAME" = "windows-latest" ]; then
@@ -6,8 +6,11 @@ main() {
         cargo=cargo
     fi
     local release_flag=""
+    local wasm_release_flag=""
     if [ "$IS_DEPLOY" = "true" ]; then
-        release_flag="--profile max-opt"
+        release_flag="--profile max-opt-capi"
+        # WebAssembly doesn't support unwinding.
+        wasm_release_flag="--profile max-opt"
     fi
 
     if [ "$NO_STD" = "true" ]; then
@@ -17,10 +20,10 @@ main() {
 
     case $TARGET in
         wasm32-unknown-unknown)
-            $cargo rustc -p livesplit-core-capi --crate-type cdylib --target $TARGET $release_flag $FEATURES
+            $cargo rustc -p livesplit-core-capi --crate-type cdylib --target $TARGET $wasm_release_flag $FEATURES
             ;;
         wasm32-wasi)
-            $cargo rustc -p livesplit-core-capi --crate-type cdylib --target $TARGET $release_flag $FEATURES
+            $cargo rustc -p livesplit-core-capi --crate-type cdylib --target $TARGET $wasm_release_flag $FEATURES
             ;;
         *)
             $cargo rustc -p livesplit-core-capi --crate-type staticlib --target $TARGET $release_flag $FEATURES
@@ -15,7 +15,7 @@ main() {
     fi
 
     if [ "$SKIP_NETWORKING" != "skip" ]; then
-        features="$features,networking"
+        features="$features,networking,obs,racetime"
     fi
 
     if [ "$SKIP_SOFTWARE_RENDERING" != "skip" ]; then
@@ -1,4 +1,4 @@
-use crate::{Class, Type, TypeKind};
+use crate::{Class, Type, TypeKind, ENUMS};
 use std::{
     borrow::Cow,
     collections::BTreeMap,
@@ -38,6 +38,85 @@ fn get_type(ty: &Type) -> Cow<'_, str> {
     name
 }
 
+fn get_plain_type(ty: &Type) -> Cow<'_, str> {
+    if ty.is_custom {
+        return Cow::Owned(match ty.kind {
+            TypeKind::Ref => format!("{}_s const*", ty.name),
+            TypeKind::RefMut | TypeKind::Value => format!("{}_s*", ty.name),
+        });
+    }
+    let mut name = Cow::Borrowed(match ty.name.as_str() {
+        "i8" => "int8_t",
+        "i16" => "int16_t",
+        "i32" => "int32_t",
+        "i64" => "int64_t",
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "usize" => "size_t",
+        "isize" => "ptrdiff_t",
+        "f32" => "float",
+        "f64" => "double",
+        "bool" => "bool",
+        "()" => "void",
+        "c_char" => "char",
+        "Json" => "char const*",
+        x => x,
+    });
+    match ty.kind {
+        TypeKind::RefMut => name.to_mut().push('*'),
+        TypeKind::Ref => name.to_mut().push_str(" const*"),
+        TypeKind::Value => {}
+    }
+    if name == "uint8_t const*" {
+        name = Cow::Borrowed("void const*");
+    }
+    name
+}
+
+/// Writes the declarations of all the types and functions without any
+/// preprocessor directives or qualifiers like `restrict`. This is meant for
+/// FFI libraries that parse C declarations at runtime, as they only understand
+/// a subset of C.
+pub fn write_declarations<W: Write>(
+    mut writer: W,
+    classes: &BTreeMap<String, Class>,
+) -> Result<()> {
+    for name in classes.keys() {
+        writeln!(writer, "typedef struct {name}_s {name}_s;")?;
+    }
+
+    for class in classes.values() {
+        for function in class
+            .static_fns
+            .iter()
+            .chain(class.own_fns.iter())
+            .chain(class.shared_fns.iter())
+            .chain(class.mut_fns.iter())
+        {
+            write!(
+                writer,
+                "{} {}(",
+                get_plain_type(&function.output),
+                function.name
+            )?;
+            for (i, (_, ty)) in function.inputs.iter().enumerate() {
+                if i != 0 {
+                    write!(writer, ", ")?;
+                }
+                write!(writer, "{}", get_plain_type(ty))?;
+            }
+            if function.inputs.is_empty() {
+                write!(writer, "void")?;
+            }
+            writeln!(writer, ");")?;
+        }
+    }
+
+    Ok(())
+}
+
 pub fn write<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
     write!(
         writer,
@@ -63,10 +142,70 @@ extern "C" {
 #include <stddef.h>
 #include <stdbool.h>
 
+/**
+A panic inside of any of the functions is reported through the Last Error and
+<NULL>, false or 0 is returned instead. The object the function got called on
+is poisoned by the panic, so calling any further functions on it fails, except
+for disposing it. Functions returning an object that is not nullable or a
+reference to an object have nothing to return in place of it, so they are not
+guarded and abort the process on a panic or when called on a poisoned object
+instead.
+*/
+
+/**
+A listener that gets called with the event code of every event a Timer emits.
+The data is the value that was provided when registering the listener.
+*/
+typedef void (*EventListener)(size_t data, uint32_t event);
+
+struct Timer_s;
+struct Time_s;
+
+/**
+A callback that calculates the state of a Host Component based on the Timer
+and the current time. It returns the state encoded as JSON. The data is the
+value that was provided when registering the callback.
+*/
+typedef char const* (*HostComponentStateCallback)(size_t data, struct Timer_s const* timer, struct Time_s const* current_time);
+
 "#
     )?;
 
-    for name in classes.keys() {
+    for e in ENUMS {
+        write!(writer, "/**")?;
+        for comment in e.comments {
+            write!(writer, "\n{comment}")?;
+        }
+        writeln!(writer, "\n*/\nenum {} {{", e.name)?;
+        for variant in e.variants {
+            write!(writer, "    /**")?;
+            for comment in variant.comments {
+                write!(writer, "\n    {comment}")?;
+            }
+            writeln!(
+                writer,
+                "\n    */\n    {}_{} = {},",
+                e.name, variant.name, variant.value
+            )?;
+        }
+        writeln!(writer, "}};\n")?;
+    }
+
+    for (name, class) in classes {
+        if !class.comments.is_empty() {
+            write!(writer, "/**")?;
+            for comment in class.comments.iter() {
+                write!(
+                    writer,
+                    "\n{}",
+                    comment
+                        .replace("<NULL>", "NULL")
+                        .replace("<TRUE>", "true")
+                        .replace("<FALSE>", "false")
+                )?;
+            }
+            writeln!(writer, "\n*/")?;
+        }
         writeln!(
             writer,
             r#"struct {name}_s;
@@ -148,6 +287,37 @@ already been freed.
         writer,
         "{}",
         r#"
+/**
+Returns the byte length of the last nul-terminated string returned on the
+current thread. The length excludes the nul-terminator.
+*/
+size_t get_buf_len(void);
+/**
+Writes the last nul-terminated string returned on the current thread into
+the buffer provided as UTF-16 and returns its length in UTF-16 code units.
+The nul-terminator is neither written nor counted. If the string doesn't
+fit, the buffer only contains its beginning, so you can pass a buffer of
+length 0 to query the length first.
+
+Every function taking or returning strings also has a UTF-16 variant with
+the `_u16` suffix. Its string parameters are passed as UTF-16 along with
+their length in UTF-16 code units. A string it returns is written into the
+buffer passed as the last two parameters instead, and its length in UTF-16
+code units is returned. If the string doesn't fit, it can be retrieved via
+this function afterwards. If the function it is a variant of returns NULL,
+SIZE_MAX is returned.
+*/
+size_t get_buf_as_utf16(uint16_t* buf, size_t buf_len);
+/**
+Converts the UTF-16 string provided into a nul-terminated UTF-8 string that
+can be passed to any function taking a string. Unpaired surrogates are
+replaced by the replacement character. The converted string is stored in a
+buffer that gets reused, so it stays valid until this function gets called
+again on the current thread. Prefer the `_u16` variant of a function over
+this, especially if it takes more than one string.
+*/
+char const* utf16_to_str(uint16_t const* data, size_t len);
+
 #ifdef __cplusplus
 }
 }
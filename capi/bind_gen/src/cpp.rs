@@ -0,0 +1,421 @@
+use crate::{Class, Function, Type, TypeKind, ENUMS};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io::{Result, Write},
+};
+
+const KEYWORDS: &[&str] = &[
+    "and", "delete", "export", "operator", "or", "not", "register", "template", "typename",
+    "union", "virtual", "xor",
+];
+
+fn get_primitive_type(name: &str) -> &str {
+    match name {
+        "i8" => "int8_t",
+        "i16" => "int16_t",
+        "i32" => "int32_t",
+        "i64" => "int64_t",
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "usize" => "size_t",
+        "isize" => "ptrdiff_t",
+        "f32" => "float",
+        "f64" => "double",
+        "bool" => "bool",
+        "()" => "void",
+        "c_char" => "char",
+        "EventListener" => "::LiveSplit::EventListener",
+        "HostComponentStateCallback" => "::LiveSplit::HostComponentStateCallback",
+        x => x,
+    }
+}
+
+fn get_class_name(ty: &Type) -> String {
+    match ty.kind {
+        TypeKind::Ref => format!("{}Ref", ty.name),
+        TypeKind::RefMut => format!("{}RefMut", ty.name),
+        TypeKind::Value => ty.name.clone(),
+    }
+}
+
+fn get_input_type(ty: &Type) -> String {
+    if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
+        match ty.kind {
+            TypeKind::Value => format!("{}&&", ty.name),
+            _ => get_class_name(ty),
+        }
+    } else if is_string(ty) {
+        String::from("std::string_view")
+    } else {
+        get_raw_type(ty)
+    }
+}
+
+fn get_output_type(ty: &Type) -> String {
+    let name = if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
+        get_class_name(ty)
+    } else if is_string(ty) {
+        String::from("std::string")
+    } else {
+        get_raw_type(ty)
+    };
+    if ty.is_nullable {
+        format!("std::optional<{name}>")
+    } else {
+        name
+    }
+}
+
+fn get_raw_type(ty: &Type) -> String {
+    let name = get_primitive_type(&ty.name);
+    match ty.kind {
+        TypeKind::Ref if name == "uint8_t" => String::from("const void*"),
+        TypeKind::Ref => format!("const {name}*"),
+        TypeKind::RefMut => format!("{name}*"),
+        TypeKind::Value => name.to_string(),
+    }
+}
+
+fn is_string(ty: &Type) -> bool {
+    !ty.is_custom && (ty.name == "Json" || (ty.name == "c_char" && ty.kind == TypeKind::Ref))
+}
+
+fn get_method_name(function: &Function) -> Cow<'_, str> {
+    match &*function.method {
+        "new" => Cow::Borrowed("create"),
+        "default" => Cow::Borrowed("create_default"),
+        method if KEYWORDS.contains(&method) => Cow::Owned(format!("{method}_")),
+        method => Cow::Borrowed(method),
+    }
+}
+
+fn write_comments<W: Write, S: AsRef<str>>(
+    mut writer: W,
+    comments: &[S],
+    indent: &str,
+) -> Result<()> {
+    for comment in comments {
+        writeln!(
+            writer,
+            "{indent}/// {}",
+            comment
+                .as_ref()
+                .replace("<NULL>", "std::nullopt")
+                .replace("<TRUE>", "true")
+                .replace("<FALSE>", "false")
+        )?;
+    }
+    Ok(())
+}
+
+fn write_signature<W: Write>(
+    mut writer: W,
+    function: &Function,
+    class_name: Option<&str>,
+) -> Result<()> {
+    let is_static = function.is_static();
+
+    if class_name.is_none() && is_static {
+        write!(writer, "static ")?;
+    }
+    write!(writer, "{} ", get_output_type(&function.output))?;
+    if let Some(class_name) = class_name {
+        write!(writer, "{class_name}::")?;
+    }
+    write!(writer, "{}(", get_method_name(function))?;
+
+    for (i, (name, ty)) in function
+        .inputs
+        .iter()
+        .skip(usize::from(!is_static))
+        .enumerate()
+    {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        write!(writer, "{} {}", get_input_type(ty), name)?;
+    }
+
+    write!(writer, ")")?;
+
+    if let Some((_, this)) = function.inputs.first().filter(|_| !is_static) {
+        match this.kind {
+            TypeKind::Ref => write!(writer, " const")?,
+            TypeKind::RefMut => {}
+            TypeKind::Value => write!(writer, " &&")?,
+        }
+    }
+
+    Ok(())
+}
+
+fn write_fn_definition<W: Write>(
+    mut writer: W,
+    function: &Function,
+    class_name: &str,
+) -> Result<()> {
+    let output = &function.output;
+
+    write!(writer, "\ninline ")?;
+    write_signature(&mut writer, function, Some(class_name))?;
+    write!(writer, " {{\n    ")?;
+
+    if function.has_return_type() {
+        write!(writer, "auto result = ")?;
+    }
+    write!(writer, "::LiveSplit::{}(", function.name)?;
+
+    for (i, (name, ty)) in function.inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        if name == "this" {
+            write!(writer, "ptr")?;
+        } else if ty.is_custom {
+            write!(writer, "{name}.ptr")?;
+        } else if is_string(ty) {
+            write!(writer, "std::string({name}).c_str()")?;
+        } else if ty.enum_name.is_some() {
+            write!(writer, "static_cast<uint8_t>({name})")?;
+        } else {
+            write!(writer, "{name}")?;
+        }
+    }
+
+    writeln!(writer, ");")?;
+
+    for (name, ty) in &function.inputs {
+        if ty.is_custom && ty.kind == TypeKind::Value {
+            if name == "this" {
+                writeln!(writer, "    ptr = nullptr;")?;
+            } else {
+                writeln!(writer, "    {name}.ptr = nullptr;")?;
+            }
+        }
+    }
+
+    if function.has_return_type() {
+        let value = if output.is_custom {
+            format!("{}(result)", get_class_name(output))
+        } else if is_string(output) {
+            String::from("std::string(result)")
+        } else if let Some(enum_name) = &output.enum_name {
+            format!("static_cast<{enum_name}>(result)")
+        } else {
+            String::from("result")
+        };
+        if output.is_nullable {
+            writeln!(
+                writer,
+                "    if (result == nullptr) {{
+        return std::nullopt;
+    }}"
+            )?;
+        }
+        writeln!(writer, "    return {value};")?;
+    }
+
+    writeln!(writer, "}}")
+}
+
+fn write_declarations<'a, W: Write>(
+    mut writer: W,
+    functions: impl IntoIterator<Item = &'a Function>,
+) -> Result<()> {
+    for function in functions {
+        writeln!(writer)?;
+        write_comments(&mut writer, &function.comments, "    ")?;
+        write!(writer, "    ")?;
+        write_signature(&mut writer, function, None)?;
+        writeln!(writer, ";")?;
+    }
+    Ok(())
+}
+
+pub fn write<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+    write!(
+        writer,
+        "{}",
+        r#"/**
+Bindings for livesplit-core as C++17 wrapper classes around the C API. Every
+class of livesplit-core is represented by three classes. The class named after
+the class owns the object. It can only be moved, not copied, and frees the
+object once it gets destructed. The Ref and RefMut classes only borrow the
+object and can't outlive its owner. Functions that take ownership of an object
+need to be called on an rvalue, such as std::move(object).
+*/
+
+#ifndef LIVESPLIT_CORE_HPP
+#define LIVESPLIT_CORE_HPP
+
+#include "livesplit_core.h"
+
+#include <cstddef>
+#include <cstdint>
+#include <optional>
+#include <string>
+#include <string_view>
+#include <utility>
+
+namespace LiveSplitCore {
+"#
+    )?;
+
+    for e in ENUMS {
+        writeln!(writer)?;
+        write_comments(&mut writer, e.comments, "")?;
+        writeln!(writer, "enum class {} : uint8_t {{", e.name)?;
+        for variant in e.variants {
+            write_comments(&mut writer, variant.comments, "    ")?;
+            writeln!(writer, "    {} = {},", variant.name, variant.value)?;
+        }
+        writeln!(writer, "}};")?;
+    }
+
+    for name in classes.keys() {
+        writeln!(
+            writer,
+            "class {name}Ref;\nclass {name}RefMut;\nclass {name};"
+        )?;
+    }
+
+    for (class_name, class) in classes {
+        let class_name_ref = format!("{class_name}Ref");
+        let class_name_ref_mut = format!("{class_name}RefMut");
+
+        writeln!(writer)?;
+        write_comments(&mut writer, &class.comments, "")?;
+        write!(
+            writer,
+            "class {class_name_ref} {{
+public:
+    ::LiveSplit::{class_name}_s* ptr;
+
+    explicit {class_name_ref}(const ::LiveSplit::{class_name}_s* ptr)
+        : ptr(const_cast<::LiveSplit::{class_name}_s*>(ptr)) {{}}
+"
+        )?;
+        write_declarations(&mut writer, &class.shared_fns)?;
+
+        writeln!(writer, "}};")?;
+
+        writeln!(writer)?;
+        write_comments(&mut writer, &class.comments, "")?;
+        write!(
+            writer,
+            "class {class_name_ref_mut} : public {class_name_ref} {{
+public:
+    explicit {class_name_ref_mut}(::LiveSplit::{class_name}_s* ptr) : {class_name_ref}(ptr) {{}}
+"
+        )?;
+        write_declarations(&mut writer, &class.mut_fns)?;
+
+        writeln!(writer, "}};")?;
+
+        writeln!(writer)?;
+        write_comments(&mut writer, &class.comments, "")?;
+        write!(
+            writer,
+            "class {class_name} : public {class_name_ref_mut} {{
+public:
+    explicit {class_name}(::LiveSplit::{class_name}_s* ptr) : {class_name_ref_mut}(ptr) {{}}
+    {class_name}(const {class_name}&) = delete;
+    {class_name}& operator=(const {class_name}&) = delete;
+    {class_name}({class_name}&& other) noexcept
+        : {class_name_ref_mut}(std::exchange(other.ptr, nullptr)) {{}}
+    {class_name}& operator=({class_name}&& other) noexcept {{
+        if (this != &other) {{
+            dispose();
+            ptr = std::exchange(other.ptr, nullptr);
+        }}
+        return *this;
+    }}
+    ~{class_name}() {{
+        dispose();
+    }}
+
+    /// Frees the object early. This happens automatically once the object
+    /// gets destructed. The object can't be used afterwards.
+    void dispose() noexcept {{
+        if (ptr != nullptr) {{"
+        )?;
+
+        if let Some(function) = class.own_fns.iter().find(|f| f.method == "drop") {
+            write!(
+                writer,
+                "
+            ::LiveSplit::{}(ptr);",
+                function.name
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "
+            ptr = nullptr;
+        }}
+    }}"
+        )?;
+
+        write_declarations(
+            &mut writer,
+            class
+                .static_fns
+                .iter()
+                .chain(class.own_fns.iter())
+                .filter(|f| f.method != "drop"),
+        )?;
+
+        writeln!(writer, "}};")?;
+    }
+
+    for (class_name, class) in classes {
+        for function in &class.shared_fns {
+            write_fn_definition(&mut writer, function, &format!("{class_name}Ref"))?;
+        }
+        for function in &class.mut_fns {
+            write_fn_definition(&mut writer, function, &format!("{class_name}RefMut"))?;
+        }
+        for function in class.static_fns.iter().chain(class.own_fns.iter()) {
+            if function.method != "drop" {
+                write_fn_definition(&mut writer, function, class_name)?;
+            }
+        }
+    }
+
+    write!(
+        writer,
+        "{}",
+        r#"
+/**
+Converts the UTF-16 string provided into a string that can be passed to any
+function taking a string. The conversion happens within livesplit-core and
+the result stays valid until this function gets called again on the current
+thread.
+*/
+inline std::string_view fromUtf16(std::u16string_view text) {
+    return ::LiveSplit::utf16_to_str(reinterpret_cast<const uint16_t*>(text.data()), text.size());
+}
+
+/**
+Returns the string that has been returned last by livesplit-core on the
+current thread as UTF-16. The conversion happens within livesplit-core.
+*/
+inline std::u16string lastStringAsUtf16() {
+    std::u16string text(::LiveSplit::get_buf_as_utf16(nullptr, 0), u'\0');
+    ::LiveSplit::get_buf_as_utf16(reinterpret_cast<uint16_t*>(text.data()), text.size());
+    return text;
+}
+}
+
+#endif
+"#
+    )
+}
@@ -1,36 +1,46 @@
-use crate::{Class, Function, Type, TypeKind};
+use crate::{Class, Function, Type, TypeKind, ENUMS};
 use heck::{ToLowerCamelCase, ToUpperCamelCase};
 use std::{
     collections::BTreeMap,
     io::{Result, Write},
 };
 
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Param {
+    Regular,
+    Span,
+    SpanLength(usize),
+}
+
 fn get_hl_type(ty: &Type) -> String {
-    if ty.is_custom {
+    let name = if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
         match ty.kind {
             TypeKind::Ref => format!("{}Ref", ty.name),
             TypeKind::RefMut => format!("{}RefMut", ty.name),
             TypeKind::Value => ty.name.clone(),
         }
-    } else if ty.name == "bool" {
-        "bool".to_string()
-    } else if ty.name == "usize" {
-        "ulong".to_string()
-    } else if ty.name == "isize" {
-        "long".to_string()
+    } else if is_string(ty) {
+        "string".to_string()
     } else {
-        let ret = get_ll_type(ty, false).to_string();
-        if ret == "LSCoreString" {
-            "string".to_string()
-        } else {
-            ret
+        match ty.name.as_str() {
+            "usize" if ty.kind == TypeKind::Value => "ulong".to_string(),
+            "isize" if ty.kind == TypeKind::Value => "long".to_string(),
+            "bool" if ty.kind == TypeKind::Value => "bool".to_string(),
+            "c_char" if ty.kind == TypeKind::Value => "char".to_string(),
+            _ => get_ll_type(ty).to_string(),
         }
+    };
+    if ty.is_nullable && (ty.is_custom || is_string(ty)) {
+        format!("{name}?")
+    } else {
+        name
     }
 }
 
-fn get_ll_type(ty: &Type, output: bool) -> &str {
+fn get_ll_type(ty: &Type) -> &str {
     match (ty.kind, ty.name.as_str()) {
-        (TypeKind::Ref, "c_char") => "LSCoreString",
         (TypeKind::Ref, _) | (TypeKind::RefMut, _) => "IntPtr",
         (_, t) if !ty.is_custom => match t {
             "i8" => "sbyte",
@@ -41,26 +51,59 @@ fn get_ll_type(ty: &Type, output: bool) -> &str {
             "u16" => "ushort",
             "u32" => "uint",
             "u64" => "ulong",
-            "usize" => "UIntPtr",
-            "isize" => "IntPtr",
+            "usize" => "nuint",
+            "isize" => "nint",
             "f32" => "float",
             "f64" => "double",
-            "bool" => {
-                if output {
-                    "byte"
-                } else {
-                    "bool"
-                }
-            }
+            "bool" => "byte",
             "()" => "void",
-            "c_char" => "char",
-            "Json" => "LSCoreString",
+            "c_char" => "byte",
+            "Json" => "IntPtr",
             x => x,
         },
         _ => "IntPtr",
     }
 }
 
+fn get_fn_ptr_type(function: &Function) -> String {
+    let mut ty = String::from("delegate* unmanaged[Cdecl]<");
+    for (_, input) in &function.inputs {
+        ty.push_str(get_ll_type(input));
+        ty.push_str(", ");
+    }
+    ty.push_str(get_ll_type(&function.output));
+    ty.push('>');
+    ty
+}
+
+fn is_string(ty: &Type) -> bool {
+    !ty.is_custom && (ty.name == "Json" || (ty.name == "c_char" && ty.kind == TypeKind::Ref))
+}
+
+fn is_byte_buffer(ty: &Type) -> bool {
+    !ty.is_custom && ty.kind != TypeKind::Value && ty.name == "u8"
+}
+
+/// Byte buffers that are directly followed by their length are exposed as
+/// spans, with the length getting passed implicitly.
+fn get_params(function: &Function) -> Vec<Param> {
+    let mut params = vec![Param::Regular; function.inputs.len()];
+    for i in 1..function.inputs.len() {
+        let (_, buffer) = &function.inputs[i - 1];
+        let (_, length) = &function.inputs[i];
+        if params[i - 1] == Param::Regular
+            && is_byte_buffer(buffer)
+            && !length.is_custom
+            && length.kind == TypeKind::Value
+            && length.name == "usize"
+        {
+            params[i - 1] = Param::Span;
+            params[i] = Param::SpanLength(i - 1);
+        }
+    }
+    params
+}
+
 fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<()> {
     write!(
         writer,
@@ -87,12 +130,21 @@ fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<
     )
 }
 
-fn write_fn<W: Write>(mut writer: W, function: &Function, class_name: &str) -> Result<()> {
+/// Writes the wrapper of the function. If the function has a UTF-16 variant,
+/// that is called instead, so the strings don't need to be converted on the
+/// managed side.
+fn write_fn<W: Write>(
+    mut writer: W,
+    function: &Function,
+    utf16_variant: Option<&Function>,
+    class_name: &str,
+) -> Result<()> {
     let is_static = function.is_static();
     let has_return_type = function.has_return_type();
-    let return_type = get_hl_type(&function.output);
-    let return_type_ll = get_ll_type(&function.output, true);
-    let is_constructor = function.method == "new" && !function.output.is_nullable;
+    let output = &function.output;
+    let return_type = get_hl_type(output);
+    let is_constructor = function.method == "new" && !output.is_nullable;
+    let params = get_params(function);
 
     if !function.comments.is_empty() {
         write!(
@@ -137,130 +189,206 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, class_name: &str) -> R
         )?;
     }
 
-    for (i, (name, typ)) in function
+    let mut is_first = true;
+    for ((name, typ), param) in function
         .inputs
         .iter()
+        .zip(&params)
         .skip(usize::from(!is_static))
-        .enumerate()
     {
-        if i != 0 {
+        let ty_name = match param {
+            Param::SpanLength(_) => continue,
+            Param::Span if typ.kind == TypeKind::Ref => String::from("ReadOnlySpan<byte>"),
+            Param::Span => String::from("Span<byte>"),
+            Param::Regular => get_hl_type(typ),
+        };
+        if !is_first {
             write!(writer, ", ")?;
         }
-        write!(
-            writer,
-            "{} {}",
-            get_hl_type(typ),
-            name.to_lower_camel_case()
-        )?;
+        is_first = false;
+        write!(writer, "{} {}", ty_name, name.to_lower_camel_case())?;
     }
 
     if is_constructor {
         write!(
             writer,
             r#") : base(IntPtr.Zero)
-        {{
-            "#
+        {{"#
         )?;
     } else {
         write!(
             writer,
             r#")
-        {{
-            "#
+        {{"#
         )?;
     }
 
     for (name, typ) in function.inputs.iter() {
-        if typ.is_custom {
+        if typ.is_custom && !typ.is_nullable {
             write!(
                 writer,
-                r#"if ({name}.ptr == IntPtr.Zero)
+                r#"
+            if ({name}.ptr == IntPtr.Zero)
             {{
                 throw new ObjectDisposedException("{name}");
-            }}
-            "#,
+            }}"#,
                 name = name.to_lower_camel_case()
             )?;
         }
     }
 
-    if has_return_type {
-        if is_constructor {
-            write!(writer, "this.ptr = ")?;
-        } else if function.output.is_custom {
-            write!(writer, r#"var result = new {return_type}("#)?;
+    let mut indent = "            ";
+    for ((name, typ), param) in function.inputs.iter().zip(&params) {
+        let ty = if *param == Param::Span {
+            "byte"
+        } else if utf16_variant.is_some() && is_string(typ) {
+            "char"
         } else {
-            write!(writer, "var result = ")?;
-            if return_type_ll == "UIntPtr" && return_type == "ulong" {
-                write!(writer, "(ulong)")?;
-            } else if return_type_ll == "IntPtr" && return_type == "long" {
-                write!(writer, "(long)")?;
-            }
-        }
+            continue;
+        };
+        write!(
+            writer,
+            r#"
+            fixed ({ty}* {name}Ptr = {name})"#,
+            name = name.to_lower_camel_case()
+        )?;
+        indent = "                ";
+    }
+    if indent.len() > 12 {
+        write!(
+            writer,
+            r#"
+            {{"#
+        )?;
     }
 
-    write!(writer, r#"LiveSplitCoreNative.{}("#, &function.name)?;
+    let is_fallible = has_return_type && output.is_nullable && output.is_custom;
 
-    for (i, (name, typ)) in function.inputs.iter().enumerate() {
-        if i != 0 {
-            write!(writer, ", ")?;
-        }
-        let ty_name = get_ll_type(typ, false);
+    if is_fallible {
         write!(
             writer,
-            "{}",
-            if name == "this" {
-                "this.ptr".to_string()
-            } else if typ.is_custom {
-                format!("{}.ptr", name.to_lower_camel_case())
-            } else if ty_name == "UIntPtr" {
-                format!("(UIntPtr){}", name.to_lower_camel_case())
-            } else if ty_name == "IntPtr" {
-                format!("(IntPtr){}", name.to_lower_camel_case())
-            } else {
-                name.to_lower_camel_case()
-            }
+            r#"
+{indent}LiveSplitCoreNative.LastError_clear();"#
         )?;
     }
 
-    write!(
-        writer,
-        "){}",
-        if return_type == "bool" { " != 0" } else { "" }
-    )?;
+    let name = utf16_variant.map_or(&function.name, |f| &f.name);
+    let returns_utf16 = utf16_variant.is_some() && is_string(output);
 
-    if !is_constructor && has_return_type && function.output.is_custom {
-        write!(writer, r#")"#)?;
-    }
+    if returns_utf16 {
+        write!(
+            writer,
+            r#"
+{indent}var buffer = LiveSplitCoreNative.Utf16Buffer();
+{indent}nuint len;
+{indent}fixed (char* chars = buffer)
+{indent}{{
+{indent}    len = LiveSplitCoreNative.{name}("#
+        )?;
+        write_args(&mut writer, function, &params, true)?;
+        write!(
+            writer,
+            r#", (IntPtr)chars, (nuint)buffer.Length);
+{indent}}}"#
+        )?;
+    } else {
+        write!(writer, "\n{indent}")?;
+        if has_return_type {
+            if is_constructor {
+                write!(writer, "this.ptr = ")?;
+            } else {
+                write!(writer, "var result = ")?;
+            }
+        }
 
-    write!(writer, r#";"#)?;
+        write!(writer, r#"LiveSplitCoreNative.{name}("#)?;
+        write_args(&mut writer, function, &params, utf16_variant.is_some())?;
+        write!(writer, ");")?;
+    }
 
     for (name, typ) in function.inputs.iter() {
         if typ.is_custom && typ.kind == TypeKind::Value {
+            let name = name.to_lower_camel_case();
+            if name == "this" {
+                write!(
+                    writer,
+                    r#"
+{indent}this.ptr = IntPtr.Zero;
+{indent}GC.SuppressFinalize(this);"#
+                )?;
+            } else {
+                write!(
+                    writer,
+                    r#"
+{indent}{name}.ptr = IntPtr.Zero;
+{indent}GC.SuppressFinalize({name});"#
+                )?;
+            }
+        }
+    }
+
+    if returns_utf16 {
+        write!(
+            writer,
+            r#"
+{indent}return LiveSplitCoreNative.Utf16String(buffer, len){};"#,
+            if output.is_nullable { "" } else { "!" }
+        )?;
+    } else if has_return_type && !is_constructor {
+        if output.is_nullable && (output.is_custom || is_string(output)) {
             write!(
                 writer,
                 r#"
-            {}.ptr = IntPtr.Zero;"#,
-                name.to_lower_camel_case()
+{indent}if (result == IntPtr.Zero)
+{indent}{{"#
             )?;
-        }
-    }
-
-    if has_return_type && !is_constructor {
-        if function.output.is_nullable && function.output.is_custom {
+            if is_fallible {
+                write!(
+                    writer,
+                    r#"
+{indent}    var code = LiveSplitCoreNative.LastError_code();
+{indent}    if (code != 0)
+{indent}    {{
+{indent}        throw new LiveSplitCoreException(code, LiveSplitCoreNative.FromNative(LiveSplitCoreNative.LastError_message()) ?? "");
+{indent}    }}"#
+                )?;
+            }
             write!(
                 writer,
                 r#"
-            if (result.ptr == IntPtr.Zero)
-            {{
-                return null;
-            }}"#
+{indent}    return null;
+{indent}}}"#
             )?;
         }
+
+        let value = if output.is_custom {
+            format!("new {}(result)", return_type.trim_end_matches('?'))
+        } else if is_string(output) {
+            String::from("LiveSplitCoreNative.FromNative(result)!")
+        } else if let Some(enum_name) = &output.enum_name {
+            format!("({enum_name})result")
+        } else {
+            match output.name.as_str() {
+                "bool" => String::from("result != 0"),
+                "usize" => String::from("(ulong)result"),
+                "isize" => String::from("(long)result"),
+                "c_char" => String::from("(char)result"),
+                _ => String::from("result"),
+            }
+        };
+
+        write!(
+            writer,
+            r#"
+{indent}return {value};"#
+        )?;
+    }
+
+    if indent.len() > 12 {
         write!(
             writer,
             r#"
-            return result;"#
+            }}"#
         )?;
     }
 
@@ -273,20 +401,115 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, class_name: &str) -> R
     Ok(())
 }
 
+/// Writes the arguments of the call. When calling the UTF-16 variant, the
+/// strings are passed as pinned UTF-16 along with their length.
+fn write_args<W: Write>(
+    mut writer: W,
+    function: &Function,
+    params: &[Param],
+    utf16: bool,
+) -> Result<()> {
+    for (i, ((name, typ), param)) in function.inputs.iter().zip(params).enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        let name = name.to_lower_camel_case();
+        match param {
+            Param::Span => write!(writer, "(IntPtr){name}Ptr")?,
+            Param::SpanLength(span) => write!(
+                writer,
+                "(nuint){}.Length",
+                function.inputs[*span].0.to_lower_camel_case()
+            )?,
+            Param::Regular => {
+                if name == "this" {
+                    write!(writer, "this.ptr")?;
+                } else if typ.is_custom && typ.is_nullable {
+                    write!(writer, "{name}?.ptr ?? IntPtr.Zero")?;
+                } else if typ.is_custom {
+                    write!(writer, "{name}.ptr")?;
+                } else if is_string(typ) && utf16 {
+                    if typ.is_nullable {
+                        write!(writer, "(IntPtr){name}Ptr, (nuint)({name}?.Length ?? 0)")?;
+                    } else {
+                        write!(writer, "(IntPtr){name}Ptr, (nuint){name}.Length")?;
+                    }
+                } else if is_string(typ) {
+                    write!(writer, "LiveSplitCoreNative.ToNative({name})")?;
+                } else if typ.kind != TypeKind::Value {
+                    write!(writer, "{name}")?;
+                } else if typ.enum_name.is_some() {
+                    write!(writer, "(byte){name}")?;
+                } else {
+                    match typ.name.as_str() {
+                        "bool" => write!(writer, "(byte)({name} ? 1 : 0)")?,
+                        "usize" => write!(writer, "(nuint){name}")?,
+                        "isize" => write!(writer, "(nint){name}")?,
+                        "c_char" => write!(writer, "(byte){name}")?,
+                        _ => write!(writer, "{name}")?,
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn write<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
     write!(
         writer,
         "{}",
-        r#"using System;
-using System.Runtime.InteropServices;
-using System.Text;
+        r#"#nullable enable
+
+using System;
 using System.IO;
+using System.Runtime.InteropServices;
 
 namespace LiveSplitCore
 {"#
     )?;
 
+    for e in ENUMS {
+        write!(writer, "\n    /// <summary>")?;
+        for comment in e.comments {
+            write!(writer, "\n    /// {comment}")?;
+        }
+        write!(
+            writer,
+            "\n    /// </summary>\n    public enum {} : byte\n    {{",
+            e.name
+        )?;
+        for variant in e.variants {
+            write!(writer, "\n        /// <summary>")?;
+            for comment in variant.comments {
+                write!(writer, "\n        /// {comment}")?;
+            }
+            write!(
+                writer,
+                "\n        /// </summary>\n        {} = {},",
+                variant.name, variant.value
+            )?;
+        }
+        write!(writer, "\n    }}")?;
+    }
+
     for (class_name, class) in classes {
+        let utf16_variant = |function: &Function| {
+            let name = format!("{}_u16", function.name);
+            class
+                .shared_fns
+                .iter()
+                .chain(&class.mut_fns)
+                .chain(&class.own_fns)
+                .chain(&class.static_fns)
+                .find(|f| f.name == name)
+        };
+        // Functions taking callbacks can't be provided and the UTF-16
+        // variants are only used internally.
+        let is_wrapped =
+            |function: &&Function| !function.has_callback() && !function.is_utf16_variant();
+
         let class_name_ref = format!("{class_name}Ref");
         let class_name_ref_mut = format!("{class_name}RefMut");
 
@@ -295,13 +518,18 @@ namespace LiveSplitCore
         write!(
             writer,
             r#"
-    public class {class_name_ref}
+    public unsafe class {class_name_ref}
     {{
         internal IntPtr ptr;"#
         )?;
 
-        for function in &class.shared_fns {
-            write_fn(&mut writer, function, &class_name_ref)?;
+        for function in class.shared_fns.iter().filter(is_wrapped) {
+            write_fn(
+                &mut writer,
+                function,
+                utf16_variant(function),
+                &class_name_ref,
+            )?;
         }
 
         if class_name == "SharedTimer" {
@@ -342,12 +570,17 @@ namespace LiveSplitCore
         write!(
             writer,
             r#"
-    public class {class_name_ref_mut} : {class_name_ref}
+    public unsafe class {class_name_ref_mut} : {class_name_ref}
     {{"#
         )?;
 
-        for function in &class.mut_fns {
-            write_fn(&mut writer, function, &class_name_ref_mut)?;
+        for function in class.mut_fns.iter().filter(is_wrapped) {
+            write_fn(
+                &mut writer,
+                function,
+                utf16_variant(function),
+                &class_name_ref_mut,
+            )?;
         }
 
         write!(
@@ -363,7 +596,7 @@ namespace LiveSplitCore
         write!(
             writer,
             r#"
-    public class {class_name} : {class_name_ref_mut}, IDisposable
+    public unsafe class {class_name} : {class_name_ref_mut}, IDisposable
     {{
         private void Drop()
         {{
@@ -371,7 +604,9 @@ namespace LiveSplitCore
             {{"#
         )?;
 
-        if let Some(function) = class.own_fns.iter().find(|f| f.method == "drop") {
+        let drop = class.own_fns.iter().find(|f| f.method == "drop");
+
+        if let Some(function) = drop {
             write!(
                 writer,
                 r#"
@@ -385,11 +620,23 @@ namespace LiveSplitCore
             r#"
                 ptr = IntPtr.Zero;
             }}
-        }}
+        }}"#
+        )?;
+
+        if drop.is_some() {
+            write!(
+                writer,
+                r#"
         ~{class_name}()
         {{
             Drop();
-        }}
+        }}"#
+            )?;
+        }
+
+        write!(
+            writer,
+            r#"
         public void Dispose()
         {{
             Drop();
@@ -397,9 +644,14 @@ namespace LiveSplitCore
         }}"#
         )?;
 
-        for function in class.static_fns.iter().chain(class.own_fns.iter()) {
+        for function in class
+            .static_fns
+            .iter()
+            .chain(class.own_fns.iter())
+            .filter(is_wrapped)
+        {
             if function.method != "drop" {
-                write_fn(&mut writer, function, class_name)?;
+                write_fn(&mut writer, function, utf16_variant(function), class_name)?;
             }
         }
 
@@ -410,17 +662,10 @@ namespace LiveSplitCore
                 r#"
         public static ParseRunResult Parse(Stream stream, string loadFilesPath)
         {
-            var data = new byte[stream.Length];
-            stream.Read(data, 0, data.Length);
-            IntPtr pnt = Marshal.AllocHGlobal(data.Length);
-            try
-            {
-                Marshal.Copy(data, 0, pnt, data.Length);
-                return Parse(pnt, (ulong)data.Length, loadFilesPath);
-            }
-            finally
+            using (var memoryStream = new MemoryStream())
             {
-                Marshal.FreeHGlobal(pnt);
+                stream.CopyTo(memoryStream);
+                return Parse(memoryStream.GetBuffer().AsSpan(0, (int)memoryStream.Length), loadFilesPath);
             }
         }"#
             )?;
@@ -436,9 +681,17 @@ namespace LiveSplitCore
 
     write!(
         writer,
+        "{}",
         r#"
-    public static class LiveSplitCoreNative
-    {{"#
+    public static unsafe class LiveSplitCoreNative
+    {
+        private static readonly IntPtr Library = NativeLibrary.Load("livesplit_core", typeof(LiveSplitCoreNative).Assembly, null);
+
+        private static IntPtr Export(string name)
+        {
+            return NativeLibrary.GetExport(Library, name);
+        }
+"#
     )?;
 
     for class in classes.values() {
@@ -448,33 +701,15 @@ namespace LiveSplitCore
             .chain(class.own_fns.iter())
             .chain(class.shared_fns.iter())
             .chain(class.mut_fns.iter())
+            .filter(|f| !f.has_callback())
         {
             write!(
                 writer,
                 r#"
-        [DllImport("livesplit_core", CallingConvention = CallingConvention.Cdecl)]
-        public static extern {} {}("#,
-                get_ll_type(&function.output, true),
-                &function.name
+        public static readonly {ty} {name} = ({ty})Export("{name}");"#,
+                ty = get_fn_ptr_type(function),
+                name = function.name,
             )?;
-
-            for (i, (name, typ)) in function.inputs.iter().enumerate() {
-                if i != 0 {
-                    write!(writer, ", ")?;
-                }
-                write!(
-                    writer,
-                    "{} {}",
-                    get_ll_type(typ, false),
-                    if name == "this" {
-                        String::from("self")
-                    } else {
-                        name.clone()
-                    }
-                )?;
-            }
-
-            write!(writer, ");")?;
         }
     }
 
@@ -482,57 +717,93 @@ namespace LiveSplitCore
         writer,
         "{}",
         r#"
-        [DllImport("livesplit_core", CallingConvention = CallingConvention.Cdecl)]
-        public static extern UIntPtr get_buf_len();
-    }
 
-    public class LSCoreString : SafeHandle
-    {
-        private bool needToFree;
+        public static readonly delegate* unmanaged[Cdecl]<nuint> get_buf_len = (delegate* unmanaged[Cdecl]<nuint>)Export("get_buf_len");
+        public static readonly delegate* unmanaged[Cdecl]<IntPtr, nuint, nuint> get_buf_as_utf16 = (delegate* unmanaged[Cdecl]<IntPtr, nuint, nuint>)Export("get_buf_as_utf16");
+        public static readonly delegate* unmanaged[Cdecl]<IntPtr, nuint, IntPtr> utf16_to_str = (delegate* unmanaged[Cdecl]<IntPtr, nuint, IntPtr>)Export("utf16_to_str");
 
-        public LSCoreString() : base(IntPtr.Zero, false) { }
+        [ThreadStatic]
+        private static char[]? utf16Buffer;
 
-        public override bool IsInvalid
+        /// Returns the buffer that the strings returned by livesplit-core get
+        /// written into as UTF-16 on the current thread.
+        internal static char[] Utf16Buffer()
         {
-            get { return false; }
+            return utf16Buffer ??= new char[256];
         }
 
-        public static implicit operator LSCoreString(string managedString)
+        /// Replaces the buffer of the current thread with one that fits a
+        /// string of the length provided.
+        internal static char[] Utf16Buffer(nuint len)
         {
-            LSCoreString lsCoreString = new LSCoreString();
+            return utf16Buffer = new char[(int)len];
+        }
 
-            int len = Encoding.UTF8.GetByteCount(managedString);
-            byte[] buffer = new byte[len + 1];
-            Encoding.UTF8.GetBytes(managedString, 0, managedString.Length, buffer, 0);
-            IntPtr nativeUtf8 = Marshal.AllocHGlobal(buffer.Length);
-            Marshal.Copy(buffer, 0, nativeUtf8, buffer.Length);
+        internal static IntPtr ToNative(string? managedString)
+        {
+            if (managedString == null)
+            {
+                return IntPtr.Zero;
+            }
 
-            lsCoreString.SetHandle(nativeUtf8);
-            lsCoreString.needToFree = true;
-            return lsCoreString;
+            // The string is converted into a buffer owned by livesplit-core,
+            // which stays valid until the next conversion, so it doesn't need
+            // to be freed.
+            fixed (char* chars = managedString)
+            {
+                return utf16_to_str((IntPtr)chars, (nuint)managedString.Length);
+            }
         }
 
-        /// Unsafely assumes that the length can be retrieved from
-        /// `get_buf_len`. This is only true for strings that have actually been
-        /// retrieved from livesplit-core.
-        public static implicit operator string(LSCoreString lSCoreString)
+        /// Unsafely assumes that the string can be retrieved from
+        /// `get_buf_as_utf16`. This is only true for the string that has been
+        /// returned last by livesplit-core on the current thread.
+        internal static string? FromNative(IntPtr handle)
         {
-            var handle = lSCoreString.handle;
             if (handle == IntPtr.Zero)
+            {
                 return null;
+            }
 
-            byte[] buffer = new byte[(long)LiveSplitCoreNative.get_buf_len()];
-            Marshal.Copy(handle, buffer, 0, buffer.Length);
-            return Encoding.UTF8.GetString(buffer);
+            var buffer = Utf16Buffer();
+            nuint len;
+            fixed (char* chars = buffer)
+            {
+                len = get_buf_as_utf16((IntPtr)chars, (nuint)buffer.Length);
+            }
+            return Utf16String(buffer, len);
         }
 
-        protected override bool ReleaseHandle()
+        /// Creates the string that has been written into the buffer as UTF-16.
+        /// If it didn't fit, it is retrieved from `get_buf_as_utf16` instead,
+        /// which holds the whole string in that case. The largest length
+        /// possible stands for null.
+        internal static string? Utf16String(char[] buffer, nuint len)
         {
-            if (needToFree)
+            if (len == nuint.MaxValue)
+            {
+                return null;
+            }
+
+            if (len > (nuint)buffer.Length)
             {
-                Marshal.FreeHGlobal(handle);
+                buffer = Utf16Buffer(len);
+                fixed (char* chars = buffer)
+                {
+                    get_buf_as_utf16((IntPtr)chars, len);
+                }
             }
-            return true;
+            return new string(buffer, 0, (int)len);
+        }
+    }
+
+    public class LiveSplitCoreException : Exception
+    {
+        public uint Code { get; }
+
+        public LiveSplitCoreException(uint code, string message) : base(message)
+        {
+            Code = code;
         }
     }
 }"#
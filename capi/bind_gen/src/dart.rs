@@ -0,0 +1,554 @@
+use crate::{Class, Function, Type, TypeKind, ENUMS};
+use heck::ToLowerCamelCase;
+use std::{
+    collections::BTreeMap,
+    fs::{self, create_dir_all, File},
+    io::{BufWriter, Result, Write},
+    path::Path,
+};
+
+static PUBSPEC: &str = "name: livesplit_core
+description: Bindings for livesplit-core, a library for creating speedrun timers.
+version: 0.1.0
+
+environment:
+  sdk: '>=3.0.0 <4.0.0'
+
+dependencies:
+  ffi: ^2.0.0
+";
+
+fn get_hl_type(ty: &Type) -> String {
+    let name = if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
+        match ty.kind {
+            TypeKind::Ref => format!("{}Ref", ty.name),
+            TypeKind::RefMut => format!("{}RefMut", ty.name),
+            TypeKind::Value => ty.name.clone(),
+        }
+    } else {
+        match (ty.kind, ty.name.as_str()) {
+            (TypeKind::Ref, "c_char") => "String",
+            (TypeKind::Ref | TypeKind::RefMut, _) => "Pointer<Void>",
+            (_, t) => match t {
+                "f32" | "f64" => "double",
+                "bool" => "bool",
+                "()" => "void",
+                "Json" => "String",
+                _ => "int",
+            },
+        }
+        .to_string()
+    };
+    if ty.is_nullable {
+        format!("{name}?")
+    } else {
+        name
+    }
+}
+
+fn get_ll_type(ty: &Type) -> &str {
+    match (ty.kind, ty.name.as_str()) {
+        (TypeKind::Ref, "c_char") => "Pointer<Utf8>",
+        (TypeKind::Ref | TypeKind::RefMut, _) => "Pointer<Void>",
+        (_, t) if !ty.is_custom => match t {
+            "i8" => "Int8",
+            "i16" => "Int16",
+            "i32" => "Int32",
+            "i64" => "Int64",
+            "u8" => "Uint8",
+            "u16" => "Uint16",
+            "u32" => "Uint32",
+            "u64" => "Uint64",
+            "usize" => "Size",
+            "isize" => "IntPtr",
+            "f32" => "Float",
+            "f64" => "Double",
+            "bool" => "Bool",
+            "()" => "Void",
+            "c_char" => "Uint8",
+            "Json" => "Pointer<Utf8>",
+            x => x,
+        },
+        _ => "Pointer<Void>",
+    }
+}
+
+fn get_dart_ll_type(ty: &Type) -> &str {
+    match get_ll_type(ty) {
+        "Float" | "Double" => "double",
+        "Bool" => "bool",
+        "Void" => "void",
+        t if t.starts_with("Pointer") => t,
+        _ => "int",
+    }
+}
+
+fn is_string(ty: &Type) -> bool {
+    !ty.is_custom && (ty.name == "Json" || (ty.name == "c_char" && ty.kind == TypeKind::Ref))
+}
+
+fn write_comments<W: Write, S: AsRef<str>>(
+    mut writer: W,
+    comments: &[S],
+    indent: &str,
+) -> Result<()> {
+    for comment in comments {
+        write!(
+            writer,
+            "
+{indent}/// {}",
+            comment
+                .as_ref()
+                .replace("<NULL>", "null")
+                .replace("<TRUE>", "true")
+                .replace("<FALSE>", "false")
+        )?;
+    }
+    Ok(())
+}
+
+fn get_method_name(function: &Function) -> String {
+    match &*function.method {
+        "new" => "create".into(),
+        "default" => "createDefault".into(),
+        method => method.to_lower_camel_case(),
+    }
+}
+
+fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
+    let is_static = function.is_static();
+    let has_return_type = function.has_return_type();
+    let output = &function.output;
+    let return_type = get_hl_type(output);
+    let is_constructor = function.method == "new" && !output.is_nullable;
+    let is_fallible = has_return_type && output.is_nullable && output.is_custom;
+
+    write_comments(&mut writer, &function.comments, "  ")?;
+
+    if is_constructor {
+        write!(writer, "\n  factory {}(", function.class)?;
+    } else {
+        write!(
+            writer,
+            "\n  {}{return_type} {}(",
+            if is_static { "static " } else { "" },
+            get_method_name(function),
+        )?;
+    }
+
+    for (i, (name, ty)) in function
+        .inputs
+        .iter()
+        .skip(usize::from(!is_static))
+        .enumerate()
+    {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        write!(writer, "{} {}", get_hl_type(ty), name.to_lower_camel_case())?;
+    }
+
+    writeln!(writer, ") {{")?;
+
+    for (name, ty) in &function.inputs {
+        if ty.is_custom {
+            if name == "this" {
+                writeln!(
+                    writer,
+                    "    if (ptr == nullptr) throw StateError('this is disposed');"
+                )?;
+            } else {
+                writeln!(
+                    writer,
+                    "    if ({}.ptr == nullptr) throw StateError('{} is disposed');",
+                    name.to_lower_camel_case(),
+                    name,
+                )?;
+            }
+        }
+    }
+
+    for (name, ty) in &function.inputs {
+        if is_string(ty) {
+            let name = name.to_lower_camel_case();
+            writeln!(writer, "    final {name}Native = {name}.toNativeUtf8();")?;
+        }
+    }
+
+    if is_fallible {
+        writeln!(writer, "    _LastError_clear();")?;
+    }
+
+    write!(writer, "    ")?;
+    if has_return_type {
+        write!(writer, "final result = ")?;
+    }
+    write!(writer, "_{}(", function.name)?;
+
+    for (i, (name, ty)) in function.inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        if name == "this" {
+            write!(writer, "ptr")?;
+        } else if ty.is_custom {
+            write!(writer, "{}.ptr", name.to_lower_camel_case())?;
+        } else if is_string(ty) {
+            write!(writer, "{}Native", name.to_lower_camel_case())?;
+        } else if ty.enum_name.is_some() {
+            write!(writer, "{}.value", name.to_lower_camel_case())?;
+        } else {
+            write!(writer, "{}", name.to_lower_camel_case())?;
+        }
+    }
+
+    writeln!(writer, ");")?;
+
+    for (name, ty) in &function.inputs {
+        if is_string(ty) {
+            writeln!(
+                writer,
+                "    malloc.free({}Native);",
+                name.to_lower_camel_case()
+            )?;
+        } else if ty.is_custom && ty.kind == TypeKind::Value {
+            if name == "this" {
+                writeln!(writer, "    _release();")?;
+            } else {
+                writeln!(writer, "    {}._release();", name.to_lower_camel_case())?;
+            }
+        }
+    }
+
+    if has_return_type {
+        if output.is_custom {
+            let class = return_type.trim_end_matches('?');
+            if output.is_nullable {
+                writeln!(writer, "    if (result == nullptr) {{")?;
+                if is_fallible {
+                    writeln!(
+                        writer,
+                        "      final code = _LastError_code();
+      if (code != 0) {{
+        throw LiveSplitCoreError(code, _LastError_message().toDartString());
+      }}"
+                    )?;
+                }
+                writeln!(writer, "      return null;\n    }}")?;
+            }
+            writeln!(writer, "    return {class}._(result);")?;
+        } else if is_string(output) {
+            if output.is_nullable {
+                writeln!(
+                    writer,
+                    "    return result == nullptr ? null : result.toDartString();"
+                )?;
+            } else {
+                writeln!(writer, "    return result.toDartString();")?;
+            }
+        } else if let Some(enum_name) = &output.enum_name {
+            writeln!(writer, "    return {enum_name}._fromValue(result);")?;
+        } else {
+            writeln!(writer, "    return result;")?;
+        }
+    }
+
+    write!(writer, "  }}")?;
+
+    if is_string(output) && !output.is_nullable {
+        if let Some(prefix) = function.method.strip_suffix("as_json") {
+            write_decoding_fn(&mut writer, function, prefix)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_decoding_fn<W: Write>(mut writer: W, function: &Function, prefix: &str) -> Result<()> {
+    let is_static = function.is_static();
+    let name = format!("{prefix}decoded").to_lower_camel_case();
+    let inputs = function
+        .inputs
+        .iter()
+        .skip(usize::from(!is_static))
+        .collect::<Vec<_>>();
+
+    write!(
+        writer,
+        "
+
+  /// Decodes the JSON returned by [{}] into maps, lists and primitive values.
+  {}dynamic {name}(",
+        get_method_name(function),
+        if is_static { "static " } else { "" },
+    )?;
+
+    for (i, (name, ty)) in inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        write!(writer, "{} {}", get_hl_type(ty), name.to_lower_camel_case())?;
+    }
+
+    write!(
+        writer,
+        ") =>\n      jsonDecode({}(",
+        get_method_name(function)
+    )?;
+
+    for (i, (name, _)) in inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        write!(writer, "{}", name.to_lower_camel_case())?;
+    }
+
+    write!(writer, "));")
+}
+
+fn write_native_fns<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+    for class in classes.values() {
+        for function in class
+            .static_fns
+            .iter()
+            .chain(class.own_fns.iter())
+            .chain(class.shared_fns.iter())
+            .chain(class.mut_fns.iter())
+        {
+            let native_inputs = function
+                .inputs
+                .iter()
+                .map(|(_, ty)| get_ll_type(ty))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let dart_inputs = function
+                .inputs
+                .iter()
+                .map(|(_, ty)| get_dart_ll_type(ty))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            write!(
+                writer,
+                "
+final _{name} = _lib.lookupFunction<{} Function({native_inputs}),
+    {} Function({dart_inputs})>('{name}');",
+                get_ll_type(&function.output),
+                get_dart_ll_type(&function.output),
+                name = function.name,
+            )?;
+        }
+    }
+
+    writeln!(writer)
+}
+
+fn write_code<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+    write!(
+        writer,
+        "{}",
+        r#"// ignore_for_file: non_constant_identifier_names
+
+/// Bindings for livesplit-core. Every class of livesplit-core is represented
+/// by three Dart classes. The class named after the class owns the object. It
+/// is freed automatically once it gets garbage collected, but can also be
+/// freed early by calling dispose. The Ref and RefMut classes only borrow the
+/// object and can't outlive its owner.
+library livesplit_core;
+
+import 'dart:convert' show jsonDecode;
+import 'dart:ffi';
+import 'dart:io' show Platform;
+
+import 'package:ffi/ffi.dart';
+
+final DynamicLibrary _lib = Platform.isIOS
+    ? DynamicLibrary.process()
+    : DynamicLibrary.open(Platform.isWindows
+        ? 'livesplit_core.dll'
+        : Platform.isMacOS
+            ? 'liblivesplit_core.dylib'
+            : 'liblivesplit_core.so');
+
+/// Thrown when a function of livesplit-core fails. The code describes the
+/// kind of error, while the message describes what went wrong.
+class LiveSplitCoreError extends Error {
+  final int code;
+  final String message;
+
+  LiveSplitCoreError(this.code, this.message);
+
+  @override
+  String toString() => message;
+}
+"#
+    )?;
+
+    for e in ENUMS {
+        writeln!(writer)?;
+        write_comments(&mut writer, e.comments, "")?;
+        write!(writer, "\nenum {} {{", e.name)?;
+        for (i, variant) in e.variants.iter().enumerate() {
+            write_comments(&mut writer, variant.comments, "  ")?;
+            write!(
+                writer,
+                "\n  {}({}){}",
+                variant.name.to_lower_camel_case(),
+                variant.value,
+                if i + 1 == e.variants.len() { ";" } else { "," }
+            )?;
+        }
+        writeln!(
+            writer,
+            r#"
+
+  const {name}(this.value);
+
+  /// The value that represents the variant in the C API.
+  final int value;
+
+  static {name} _fromValue(int value) =>
+      values.firstWhere((e) => e.value == value);
+}}"#,
+            name = e.name
+        )?;
+    }
+
+    write_native_fns(&mut writer, classes)?;
+
+    for (class_name, class) in classes {
+        let class_name_ref = format!("{class_name}Ref");
+        let class_name_ref_mut = format!("{class_name}RefMut");
+        let drop_fn = class.own_fns.iter().find(|f| f.method == "drop");
+
+        writeln!(writer)?;
+        write_comments(&mut writer, &class.comments, "")?;
+        write!(
+            writer,
+            "
+class {class_name_ref} {{
+  Pointer<Void> ptr;
+
+  {class_name_ref}._(this.ptr);"
+        )?;
+
+        for function in &class.shared_fns {
+            writeln!(writer)?;
+            write_fn(&mut writer, function)?;
+        }
+
+        writeln!(writer, "\n}}")?;
+        write_comments(&mut writer, &class.comments, "")?;
+        write!(
+            writer,
+            "
+class {class_name_ref_mut} extends {class_name_ref} {{
+  {class_name_ref_mut}._(Pointer<Void> ptr) : super._(ptr);"
+        )?;
+
+        for function in &class.mut_fns {
+            writeln!(writer)?;
+            write_fn(&mut writer, function)?;
+        }
+
+        writeln!(writer, "\n}}")?;
+        write_comments(&mut writer, &class.comments, "")?;
+
+        if let Some(drop_fn) = drop_fn {
+            write!(
+                writer,
+                "
+class {class_name} extends {class_name_ref_mut} implements Finalizable {{
+  static final _finalizer = NativeFinalizer(
+      _lib.lookup<NativeFinalizerFunction>('{drop_fn}'));
+
+  {class_name}._(Pointer<Void> ptr) : super._(ptr) {{
+    _finalizer.attach(this, ptr, detach: this);
+  }}
+
+  /// Frees the object, allowing it to clean up all of its memory. This
+  /// happens automatically once the object gets garbage collected, but can
+  /// be done early by calling this. The object can't be used afterwards.
+  void dispose() {{
+    if (ptr != nullptr) {{
+      _finalizer.detach(this);
+      _{drop_fn}(ptr);
+      ptr = nullptr;
+    }}
+  }}
+
+  void _release() {{
+    _finalizer.detach(this);
+    ptr = nullptr;
+  }}",
+                drop_fn = drop_fn.name,
+            )?;
+        } else {
+            write!(
+                writer,
+                "
+class {class_name} extends {class_name_ref_mut} {{
+  {class_name}._(Pointer<Void> ptr) : super._(ptr);
+
+  /// Makes the object unusable. This doesn't need to be called, as the
+  /// object doesn't own any memory that needs to be freed.
+  void dispose() {{
+    ptr = nullptr;
+  }}
+
+  void _release() {{
+    ptr = nullptr;
+  }}"
+            )?;
+        }
+
+        for function in class.static_fns.iter().chain(class.own_fns.iter()) {
+            if function.method != "drop" {
+                writeln!(writer)?;
+                write_fn(&mut writer, function)?;
+            }
+        }
+
+        if class_name == "Run" {
+            write!(
+                writer,
+                "{}",
+                r#"
+
+  /// Attempts to parse the bytes of a splits file into a Run.
+  static ParseRunResult parseBytes(List<int> data, String loadFilesPath) {
+    final buffer = malloc<Uint8>(data.length);
+    buffer.asTypedList(data.length).setAll(0, data);
+    final result = parse(buffer.cast(), data.length, loadFilesPath);
+    malloc.free(buffer);
+    return result;
+  }"#
+            )?;
+        }
+
+        writeln!(writer, "\n}}")?;
+    }
+
+    Ok(())
+}
+
+pub fn write<P: AsRef<Path>>(path: P, classes: &BTreeMap<String, Class>) -> Result<()> {
+    let mut path = path.as_ref().to_owned();
+
+    path.push("pubspec.yaml");
+    fs::write(&path, PUBSPEC)?;
+    path.pop();
+
+    path.push("lib");
+    create_dir_all(&path)?;
+
+    path.push("livesplit_core.dart");
+    write_code(BufWriter::new(File::create(&path)?), classes)?;
+    path.pop();
+
+    path.pop();
+
+    Ok(())
+}
@@ -0,0 +1,425 @@
+use crate::{typescript, Class, Function, Type, TypeKind};
+use heck::ToLowerCamelCase;
+use std::{
+    collections::BTreeMap,
+    io::{Result, Write},
+};
+
+fn get_hl_type(ty: &Type) -> String {
+    let mut name = if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
+        match ty.kind {
+            TypeKind::Ref => format!("{}Ref", ty.name),
+            TypeKind::RefMut => format!("{}RefMut", ty.name),
+            TypeKind::Value => format!("Disposable<{}>", ty.name),
+        }
+    } else {
+        match (ty.kind, ty.name.as_str()) {
+            (TypeKind::Ref, "c_char") => "string",
+            (TypeKind::Ref | TypeKind::RefMut, _) => "BufferSource",
+            (_, "i64" | "u64" | "usize" | "isize") => "number | bigint",
+            (_, "bool") => "boolean",
+            (_, "()") => "void",
+            (_, "c_char") => "string",
+            (_, "Json") => "any",
+            _ => "number",
+        }
+        .to_string()
+    };
+    if ty.is_nullable {
+        name.push_str(" | null");
+    }
+    name
+}
+
+fn get_ll_type(ty: &Type, is_output: bool) -> &str {
+    match (ty.kind, ty.name.as_str()) {
+        (TypeKind::Ref, "c_char") | (_, "Json") if !is_output => "\"buffer\"",
+        (TypeKind::Ref | TypeKind::RefMut, _) if !ty.is_custom && !is_output => "\"buffer\"",
+        (TypeKind::Ref | TypeKind::RefMut, _) => "\"pointer\"",
+        (_, t) if !ty.is_custom => match t {
+            "i8" => "\"i8\"",
+            "i16" => "\"i16\"",
+            "i32" => "\"i32\"",
+            "i64" => "\"i64\"",
+            "u8" => "\"u8\"",
+            "u16" => "\"u16\"",
+            "u32" => "\"u32\"",
+            "u64" => "\"u64\"",
+            "usize" => "\"usize\"",
+            "isize" => "\"isize\"",
+            "f32" => "\"f32\"",
+            "f64" => "\"f64\"",
+            "bool" => "\"bool\"",
+            "()" => "\"void\"",
+            "c_char" => "\"u8\"",
+            "Json" => "\"pointer\"",
+            x => x,
+        },
+        _ => "\"pointer\"",
+    }
+}
+
+fn write_comments<W: Write>(mut writer: W, comments: &[String], indent: &str) -> Result<()> {
+    if comments.is_empty() {
+        return Ok(());
+    }
+    write!(writer, "\n{indent}/**")?;
+    for comment in comments {
+        write!(
+            writer,
+            "\n{indent} * {}",
+            comment
+                .replace("<NULL>", "null")
+                .replace("<TRUE>", "true")
+                .replace("<FALSE>", "false")
+        )?;
+    }
+    write!(writer, "\n{indent} */")
+}
+
+fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
+    let is_static = function.is_static();
+    let has_return_type = function.has_return_type();
+    let output = &function.output;
+    let is_fallible = has_return_type && output.is_nullable && output.is_custom;
+
+    write_comments(&mut writer, &function.comments, "    ")?;
+    write!(
+        writer,
+        "\n    {}{}(",
+        if is_static { "static " } else { "" },
+        function.method.to_lower_camel_case(),
+    )?;
+
+    let consumes_this = !is_static && function.inputs[0].1.kind == TypeKind::Value;
+    if consumes_this {
+        write!(writer, "this: Disposable<{}>", function.class)?;
+    }
+
+    for (i, (name, ty)) in function
+        .inputs
+        .iter()
+        .skip(usize::from(!is_static))
+        .enumerate()
+    {
+        if i != 0 || consumes_this {
+            write!(writer, ", ")?;
+        }
+        write!(
+            writer,
+            "{}: {}",
+            name.to_lower_camel_case(),
+            get_hl_type(ty)
+        )?;
+    }
+
+    write!(writer, "): {} {{", get_hl_type(output))?;
+
+    for (name, ty) in &function.inputs {
+        if ty.is_custom {
+            write!(
+                writer,
+                "
+        if ({name}.ptr === null) {{
+            throw \"{name} is disposed\";
+        }}",
+                name = name.to_lower_camel_case()
+            )?;
+        }
+    }
+
+    if is_fallible {
+        write!(writer, "\n        lib.symbols.LastError_clear();")?;
+    }
+
+    write!(writer, "\n        ")?;
+    if has_return_type {
+        write!(writer, "const result = ")?;
+    }
+    write!(writer, "lib.symbols.{}(", function.name)?;
+
+    for (i, (name, ty)) in function.inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        let name = name.to_lower_camel_case();
+        if ty.is_custom {
+            write!(writer, "{name}.ptr")?;
+        } else if ty.name == "Json" {
+            write!(writer, "cString(JSON.stringify({name}))")?;
+        } else if ty.name == "c_char" && ty.kind == TypeKind::Ref {
+            write!(writer, "cString({name})")?;
+        } else {
+            write!(writer, "{name}")?;
+        }
+    }
+
+    write!(writer, ");")?;
+
+    for (name, ty) in &function.inputs {
+        if ty.is_custom && ty.kind == TypeKind::Value {
+            write!(writer, "\n        release({});", name.to_lower_camel_case())?;
+        }
+    }
+
+    if has_return_type {
+        let is_string =
+            output.name == "Json" || output.name == "c_char" && output.kind == TypeKind::Ref;
+        if output.is_nullable && (output.is_custom || is_string) {
+            write!(writer, "\n        if (result === null) {{")?;
+            if is_fallible {
+                write!(writer, "\n            throwLastError();")?;
+            }
+            write!(writer, "\n            return null;\n        }}")?;
+        }
+        if output.is_custom && output.kind == TypeKind::Value {
+            write!(
+                writer,
+                "\n        return new {}(result) as Disposable<{0}>;",
+                output.name
+            )?;
+        } else if output.is_custom {
+            write!(
+                writer,
+                "\n        return new {}(result);",
+                get_hl_type(output).trim_end_matches(" | null")
+            )?;
+        } else if output.name == "Json" {
+            write!(
+                writer,
+                "\n        return JSON.parse(Deno.UnsafePointerView.getCString(result!));"
+            )?;
+        } else if is_string {
+            write!(
+                writer,
+                "\n        return Deno.UnsafePointerView.getCString(result!);"
+            )?;
+        } else if output.name == "c_char" {
+            write!(writer, "\n        return String.fromCharCode(result);")?;
+        } else {
+            write!(writer, "\n        return result;")?;
+        }
+    }
+
+    write!(writer, "\n    }}")
+}
+
+pub fn write<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+    write!(
+        writer,
+        r#"// deno-lint-ignore-file
+// Bindings for livesplit-core using the FFI of Deno. Every class of
+// livesplit-core is represented by three classes. The class named after the
+// class owns the object. It is freed once it gets garbage collected, but can
+// also be freed early by calling dispose or by declaring it with using. The Ref
+// and RefMut classes only borrow the object and can't outlive its owner.
+{}
+
+const libraryName = Deno.build.os === "windows"
+    ? "livesplit_core.dll"
+    : Deno.build.os === "darwin"
+    ? "liblivesplit_core.dylib"
+    : "liblivesplit_core.so";
+
+const lib = Deno.dlopen(libraryName, {{"#,
+        typescript::HEADER
+    )?;
+
+    for class in classes.values() {
+        for function in class
+            .static_fns
+            .iter()
+            .chain(class.own_fns.iter())
+            .chain(class.shared_fns.iter())
+            .chain(class.mut_fns.iter())
+        {
+            write!(writer, "\n    {}: {{ parameters: [", function.name)?;
+            for (i, (_, ty)) in function.inputs.iter().enumerate() {
+                if i != 0 {
+                    write!(writer, ", ")?;
+                }
+                write!(writer, "{}", get_ll_type(ty, false))?;
+            }
+            write!(
+                writer,
+                "], result: {} }},",
+                get_ll_type(&function.output, true)
+            )?;
+        }
+    }
+
+    write!(
+        writer,
+        "{}",
+        r#"
+} as const);
+
+const encoder = new TextEncoder();
+
+/**
+ * Thrown when a function of livesplit-core fails. The code describes the kind
+ * of error, while the message describes what went wrong.
+ */
+export class LiveSplitCoreError extends Error {
+    code: number;
+    constructor(code: number, message: string) {
+        super(message);
+        this.code = code;
+    }
+}
+
+function throwLastError() {
+    const code = lib.symbols.LastError_code();
+    if (code !== 0) {
+        const message = lib.symbols.LastError_message();
+        throw new LiveSplitCoreError(
+            code,
+            message === null ? "" : Deno.UnsafePointerView.getCString(message),
+        );
+    }
+}
+
+function cString(text: string): Uint8Array {
+    return encoder.encode(text + "\0");
+}
+
+type Drop = (ptr: Deno.PointerValue) => void;
+
+const registry = new FinalizationRegistry(
+    ({ drop, ptr }: { drop: Drop; ptr: Deno.PointerValue }) => drop(ptr),
+);
+
+function own(obj: { ptr: Deno.PointerValue }, drop: Drop | null) {
+    if (drop !== null) {
+        registry.register(obj, { drop, ptr: obj.ptr }, obj);
+    }
+}
+
+function release(obj: { ptr: Deno.PointerValue }) {
+    registry.unregister(obj);
+    obj.ptr = null;
+}
+"#
+    )?;
+
+    for (class_name, class) in classes {
+        let class_name_ref = format!("{class_name}Ref");
+        let class_name_ref_mut = format!("{class_name}RefMut");
+        let drop = class
+            .own_fns
+            .iter()
+            .find(|f| f.method == "drop")
+            .map(|f| format!("lib.symbols.{}", f.name));
+
+        write_comments(&mut writer, &class.comments, "")?;
+        write!(
+            writer,
+            "
+export class {class_name_ref} {{
+    ptr: Deno.PointerValue;"
+        )?;
+
+        for function in &class.shared_fns {
+            write_fn(&mut writer, function)?;
+        }
+
+        write!(
+            writer,
+            "
+    constructor(ptr: Deno.PointerValue) {{
+        this.ptr = ptr;
+    }}
+}}
+"
+        )?;
+
+        write_comments(&mut writer, &class.comments, "")?;
+        write!(
+            writer,
+            "
+export class {class_name_ref_mut} extends {class_name_ref} {{"
+        )?;
+
+        for function in &class.mut_fns {
+            write_fn(&mut writer, function)?;
+        }
+
+        write!(writer, "\n}}\n")?;
+
+        write_comments(&mut writer, &class.comments, "")?;
+        write!(
+            writer,
+            "
+export class {class_name} extends {class_name_ref_mut} {{
+    constructor(ptr: Deno.PointerValue) {{
+        super(ptr);
+        own(this, {drop});
+    }}
+    with<T>(this: Disposable<{class_name}>, closure: (obj: Disposable<{class_name}>) => T): T {{
+        try {{
+            return closure(this);
+        }} finally {{
+            this.dispose();
+        }}
+    }}
+    [Symbol.dispose]() {{
+        this.dispose();
+    }}
+    dispose() {{
+        if (this.ptr !== null) {{
+            const ptr = this.ptr;
+            release(this);",
+            drop = drop.as_deref().unwrap_or("null"),
+        )?;
+
+        if let Some(drop) = &drop {
+            write!(writer, "\n            {drop}(ptr);")?;
+        }
+
+        write!(writer, "\n        }}\n    }}")?;
+
+        for function in class.static_fns.iter().chain(class.own_fns.iter()) {
+            if function.method != "drop" {
+                write_fn(&mut writer, function)?;
+            }
+        }
+
+        if class_name == "Run" {
+            write!(
+                writer,
+                "{}",
+                r#"
+    static parseArray(data: Uint8Array, loadFilesPath: string): Disposable<ParseRunResult> {
+        return Run.parse(data, data.byteLength, loadFilesPath);
+    }
+    static parseFile(path: string | URL, loadFilesPath: string): Disposable<ParseRunResult> {
+        const data = Deno.readFileSync(path);
+        return Run.parse(data, data.byteLength, loadFilesPath);
+    }
+    static parseString(text: string, loadFilesPath: string): Disposable<ParseRunResult> {
+        const data = encoder.encode(text);
+        return Run.parse(data, data.byteLength, loadFilesPath);
+    }"#
+            )?;
+        } else if class_name == "Layout" {
+            write!(
+                writer,
+                "{}",
+                r#"
+    static parseOriginalLivesplitArray(data: Uint8Array): Disposable<Layout> | null {
+        return Layout.parseOriginalLivesplit(data, data.byteLength);
+    }
+    static parseOriginalLivesplitString(text: string): Disposable<Layout> | null {
+        const data = encoder.encode(text);
+        return Layout.parseOriginalLivesplit(data, data.byteLength);
+    }"#
+            )?;
+        }
+
+        writeln!(writer, "\n}}")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,441 @@
+use crate::{c, Class, Function, Type, TypeKind, ENUMS};
+use heck::{ToLowerCamelCase, ToUpperCamelCase};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Result, Write},
+    path::Path,
+};
+
+static GO_MOD: &str = "module livesplitcore
+
+go 1.21
+";
+
+fn get_hl_type(ty: &Type) -> Cow<'_, str> {
+    if let Some(enum_name) = &ty.enum_name {
+        Cow::Borrowed(enum_name)
+    } else if ty.is_custom {
+        match ty.kind {
+            TypeKind::Ref => Cow::Owned(format!("*{}Ref", ty.name)),
+            TypeKind::RefMut => Cow::Owned(format!("*{}RefMut", ty.name)),
+            TypeKind::Value => Cow::Owned(format!("*{}", ty.name)),
+        }
+    } else {
+        Cow::Borrowed(match (ty.kind, ty.name.as_str()) {
+            (TypeKind::Ref, "c_char") => "string",
+            (TypeKind::Ref | TypeKind::RefMut, _) => "unsafe.Pointer",
+            (_, t) => match t {
+                "i8" => "int8",
+                "i16" => "int16",
+                "i32" => "int32",
+                "i64" => "int64",
+                "u8" => "uint8",
+                "u16" => "uint16",
+                "u32" => "uint32",
+                "u64" => "uint64",
+                "usize" => "uint",
+                "isize" => "int",
+                "f32" => "float32",
+                "f64" => "float64",
+                "bool" => "bool",
+                "c_char" => "byte",
+                "Json" => "string",
+                x => x,
+            },
+        })
+    }
+}
+
+fn get_ll_type(ty: &Type) -> &str {
+    match ty.name.as_str() {
+        "i8" => "C.int8_t",
+        "i16" => "C.int16_t",
+        "i32" => "C.int32_t",
+        "i64" => "C.int64_t",
+        "u8" => "C.uint8_t",
+        "u16" => "C.uint16_t",
+        "u32" => "C.uint32_t",
+        "u64" => "C.uint64_t",
+        "usize" => "C.size_t",
+        "isize" => "C.ptrdiff_t",
+        "f32" => "C.float",
+        "f64" => "C.double",
+        "bool" => "C.bool",
+        "c_char" => "C.char",
+        x => x,
+    }
+}
+
+fn is_string(ty: &Type) -> bool {
+    !ty.is_custom && (ty.name == "Json" || (ty.name == "c_char" && ty.kind == TypeKind::Ref))
+}
+
+fn get_input_name(name: &str) -> String {
+    if name == "this" {
+        String::from("this")
+    } else {
+        name.to_lower_camel_case()
+    }
+}
+
+fn get_fn_name(function: &Function) -> String {
+    if function.is_static() {
+        if function.method == "new" {
+            format!("New{}", function.class)
+        } else {
+            format!(
+                "{}{}",
+                function.class,
+                function.method.to_upper_camel_case()
+            )
+        }
+    } else {
+        function.method.to_upper_camel_case()
+    }
+}
+
+fn write_comments<W: Write, S: AsRef<str>>(mut writer: W, comments: &[S]) -> Result<()> {
+    for comment in comments {
+        writeln!(
+            writer,
+            "// {}",
+            comment
+                .as_ref()
+                .replace("<NULL>", "nil")
+                .replace("<TRUE>", "true")
+                .replace("<FALSE>", "false")
+        )?;
+    }
+    Ok(())
+}
+
+fn write_fn<W: Write>(mut writer: W, function: &Function, receiver: &str) -> Result<()> {
+    let is_static = function.is_static();
+    let has_return_type = function.has_return_type();
+    let output = &function.output;
+    let return_type = get_hl_type(output);
+    let is_fallible = has_return_type && output.is_nullable;
+    let returns_string = is_string(output);
+
+    writeln!(writer)?;
+    write_comments(&mut writer, &function.comments)?;
+
+    if is_static {
+        write!(writer, "func {}(", get_fn_name(function))?;
+    } else {
+        write!(writer, "func (this *{receiver}) {}(", get_fn_name(function))?;
+    }
+
+    for (i, (name, ty)) in function
+        .inputs
+        .iter()
+        .skip(usize::from(!is_static))
+        .enumerate()
+    {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        write!(writer, "{} {}", get_input_name(name), get_hl_type(ty))?;
+    }
+
+    if is_fallible {
+        if returns_string {
+            writeln!(writer, ") (*string, error) {{")?;
+        } else {
+            writeln!(writer, ") ({return_type}, error) {{")?;
+        }
+    } else if has_return_type {
+        writeln!(writer, ") {return_type} {{")?;
+    } else {
+        writeln!(writer, ") {{")?;
+    }
+
+    for (name, ty) in &function.inputs {
+        if ty.is_custom {
+            writeln!(
+                writer,
+                "\tif {}.ptr == nil {{
+\t\tpanic(\"{} is disposed\")
+\t}}",
+                get_input_name(name),
+                name,
+            )?;
+        }
+    }
+
+    if is_fallible || returns_string {
+        // Both the strings returned and the Last Error are stored per thread,
+        // so the goroutine must not move to another thread in the meantime.
+        writeln!(
+            writer,
+            "\truntime.LockOSThread()
+\tdefer runtime.UnlockOSThread()"
+        )?;
+    }
+    if is_fallible {
+        writeln!(writer, "\tC.LastError_clear()")?;
+    }
+
+    for (name, ty) in &function.inputs {
+        if is_string(ty) {
+            let name = get_input_name(name);
+            writeln!(
+                writer,
+                "\tc{upper} := C.CString({name})
+\tdefer C.free(unsafe.Pointer(c{upper}))",
+                upper = name.to_upper_camel_case(),
+            )?;
+        }
+    }
+
+    write!(writer, "\t")?;
+    if has_return_type {
+        write!(writer, "result := ")?;
+    }
+    write!(writer, "C.{}(", function.name)?;
+
+    for (i, (name, ty)) in function.inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        let name = get_input_name(name);
+        if ty.is_custom {
+            let suffix = match ty.kind {
+                TypeKind::Ref => "Ref",
+                TypeKind::RefMut => "RefMut",
+                TypeKind::Value => "",
+            };
+            write!(writer, "C.{}{}({}.ptr)", ty.name, suffix, name)?;
+        } else if is_string(ty) {
+            write!(writer, "c{}", name.to_upper_camel_case())?;
+        } else if ty.kind != TypeKind::Value {
+            if ty.name == "u8" && ty.kind == TypeKind::Ref {
+                write!(writer, "{name}")?;
+            } else {
+                write!(writer, "(*{})({})", get_ll_type(ty), name)?;
+            }
+        } else {
+            write!(writer, "{}({})", get_ll_type(ty), name)?;
+        }
+    }
+
+    writeln!(writer, ")")?;
+
+    for (name, ty) in &function.inputs {
+        if ty.is_custom && ty.kind == TypeKind::Value {
+            let name = get_input_name(name);
+            writeln!(
+                writer,
+                "\t{name}.ptr = nil
+\truntime.SetFinalizer({name}, nil)"
+            )?;
+        }
+    }
+
+    if !has_return_type {
+        return writeln!(writer, "}}");
+    }
+
+    if is_fallible {
+        writeln!(
+            writer,
+            "\tif result == nil {{
+\t\treturn nil, lastError()
+\t}}"
+        )?;
+    }
+
+    let value = if output.is_custom {
+        let name = &output.name;
+        match output.kind {
+            TypeKind::Ref => format!("&{name}Ref{{unsafe.Pointer(result)}}"),
+            TypeKind::RefMut => {
+                format!("&{name}RefMut{{{name}Ref{{unsafe.Pointer(result)}}}}")
+            }
+            TypeKind::Value => format!("new{name}(unsafe.Pointer(result))"),
+        }
+    } else if returns_string {
+        String::from("C.GoString(result)")
+    } else if output.kind != TypeKind::Value {
+        String::from("unsafe.Pointer(result)")
+    } else {
+        format!("{return_type}(result)")
+    };
+
+    if is_fallible {
+        if returns_string {
+            writeln!(
+                writer,
+                "\tvalue := {value}
+\treturn &value, nil"
+            )?;
+        } else {
+            writeln!(writer, "\treturn {value}, nil")?;
+        }
+    } else {
+        writeln!(writer, "\treturn {value}")?;
+    }
+
+    writeln!(writer, "}}")
+}
+
+fn write_code<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+    write!(
+        writer,
+        "{}",
+        r#"// Package livesplitcore provides bindings for livesplit-core. To compile
+// this, you will need to put the livesplit_core library next to this file.
+//
+// Every class of livesplit-core is represented by three types. The type
+// named after the class owns the object. It is freed automatically by the
+// garbage collector, but can also be freed early by calling Drop. The Ref and
+// RefMut types only borrow the object and can't outlive its owner. The owned
+// type embeds the RefMut type, which in turn embeds the Ref type, so all the
+// methods are available on the owned type.
+package livesplitcore
+
+/*
+#cgo LDFLAGS: -L${SRCDIR} -llivesplit_core
+#include <stdlib.h>
+#include "livesplit_core.h"
+*/
+import "C"
+
+import (
+	"runtime"
+	"unsafe"
+)
+
+// Error is returned when a function of livesplit-core fails. The code
+// describes the kind of error, while the message describes what went wrong.
+type Error struct {
+	Code    uint32
+	Message string
+}
+
+func (e *Error) Error() string {
+	return e.Message
+}
+
+func lastError() error {
+	code := C.LastError_code()
+	if code == 0 {
+		return nil
+	}
+	return &Error{uint32(code), C.GoString(C.LastError_message())}
+}
+"#
+    )?;
+
+    for e in ENUMS {
+        writeln!(writer)?;
+        write_comments(&mut writer, e.comments)?;
+        writeln!(writer, "type {} uint8\n\nconst (", e.name)?;
+        for variant in e.variants {
+            for comment in variant.comments {
+                writeln!(writer, "\t// {comment}")?;
+            }
+            writeln!(
+                writer,
+                "\t{0}{1} {0} = {2}",
+                e.name, variant.name, variant.value
+            )?;
+        }
+        writeln!(writer, ")")?;
+    }
+
+    for (class_name, class) in classes {
+        let class_name_ref = format!("{class_name}Ref");
+        let class_name_ref_mut = format!("{class_name}RefMut");
+
+        writeln!(writer)?;
+        write_comments(&mut writer, &class.comments)?;
+        writeln!(
+            writer,
+            "type {class_name_ref} struct {{
+\tptr unsafe.Pointer
+}}"
+        )?;
+
+        for function in &class.shared_fns {
+            write_fn(&mut writer, function, &class_name_ref)?;
+        }
+
+        writeln!(writer)?;
+        write_comments(&mut writer, &class.comments)?;
+        writeln!(
+            writer,
+            "type {class_name_ref_mut} struct {{
+\t{class_name_ref}
+}}"
+        )?;
+
+        for function in &class.mut_fns {
+            write_fn(&mut writer, function, &class_name_ref_mut)?;
+        }
+
+        writeln!(writer)?;
+        write_comments(&mut writer, &class.comments)?;
+        write!(
+            writer,
+            "type {class_name} struct {{
+\t{class_name_ref_mut}
+}}
+
+func new{class_name}(ptr unsafe.Pointer) *{class_name} {{
+\tthis := &{class_name}{{{class_name_ref_mut}{{{class_name_ref}{{ptr}}}}}}
+\truntime.SetFinalizer(this, (*{class_name}).Drop)
+\treturn this
+}}
+
+// Drop frees the object, allowing it to clean up all of its memory. This
+// happens automatically once the object is garbage collected, but can be
+// done early by calling this. The object can't be used afterwards.
+func (this *{class_name}) Drop() {{
+\tif this.ptr != nil {{"
+        )?;
+
+        if let Some(function) = class.own_fns.iter().find(|f| f.method == "drop") {
+            write!(
+                writer,
+                "
+\t\tC.{}(C.{class_name}(this.ptr))",
+                function.name
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "
+\t\tthis.ptr = nil
+\t}}
+\truntime.SetFinalizer(this, nil)
+}}"
+        )?;
+
+        for function in class.static_fns.iter().chain(class.own_fns.iter()) {
+            if function.method != "drop" {
+                write_fn(&mut writer, function, class_name)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write<P: AsRef<Path>>(path: P, classes: &BTreeMap<String, Class>) -> Result<()> {
+    let mut path = path.as_ref().to_owned();
+
+    path.push("livesplit_core.go");
+    write_code(BufWriter::new(File::create(&path)?), classes)?;
+    path.pop();
+
+    path.push("livesplit_core.h");
+    c::write(BufWriter::new(File::create(&path)?), classes)?;
+    path.pop();
+
+    path.push("go.mod");
+    std::fs::write(&path, GO_MOD)
+}
@@ -9,7 +9,9 @@ use std::{
 };
 
 fn get_hl_type(ty: &Type) -> String {
-    if ty.is_custom {
+    if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
         match ty.kind {
             TypeKind::Ref => format!("{}Ref", ty.name),
             TypeKind::RefMut => format!("{}RefMut", ty.name),
@@ -160,6 +162,8 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, class_name: &str) -> R
             write!(writer, "this.ptr = ")?;
         } else if function.output.is_custom {
             write!(writer, r#"{return_type} result = new {return_type}("#)?;
+        } else if function.output.enum_name.is_some() {
+            write!(writer, r#"{return_type} result = {return_type}.fromValue("#)?;
         } else {
             write!(writer, "{return_type} result = ")?;
         }
@@ -183,6 +187,8 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, class_name: &str) -> R
                 "this.ptr".to_string()
             } else if typ.is_custom {
                 format!("{}.ptr", name.to_lower_camel_case())
+            } else if typ.enum_name.is_some() {
+                format!("{}.getValue()", name.to_lower_camel_case())
             } else {
                 name.to_lower_camel_case()
             }
@@ -191,7 +197,10 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, class_name: &str) -> R
 
     write!(writer, ")")?;
 
-    if !is_constructor && has_return_type && function.output.is_custom {
+    if !is_constructor
+        && has_return_type
+        && (function.output.is_custom || function.output.enum_name.is_some())
+    {
         write!(writer, r#")"#)?;
     }
 
@@ -1,5 +1,6 @@
 use crate::jni_cpp;
-use crate::{Class, Result};
+use crate::{Class, Result, ENUMS};
+use heck::ToShoutySnakeCase;
 use std::collections::BTreeMap;
 use std::fs::{create_dir_all, File};
 use std::io::{BufWriter, Write};
@@ -7,6 +8,7 @@ use std::path::Path;
 
 mod jna;
 mod jni;
+mod panama;
 
 pub fn write<P: AsRef<Path>>(path: P, classes: &BTreeMap<String, Class>) -> Result<()> {
     let mut path = path.as_ref().to_owned();
@@ -14,11 +16,19 @@ pub fn write<P: AsRef<Path>>(path: P, classes: &BTreeMap<String, Class>) -> Resu
     path.push("jna");
     create_dir_all(&path)?;
     jna::write(&path, classes)?;
+    write_enums(&path)?;
     path.pop();
 
     path.push("jni");
     create_dir_all(&path)?;
     jni::write(&path, classes)?;
+    write_enums(&path)?;
+    path.pop();
+
+    path.push("panama");
+    create_dir_all(&path)?;
+    panama::write(&path, classes)?;
+    write_enums(&path)?;
     path.pop();
 
     path.push("LiveSplitCoreJNI.cpp");
@@ -28,7 +38,71 @@ pub fn write<P: AsRef<Path>>(path: P, classes: &BTreeMap<String, Class>) -> Resu
     Ok(())
 }
 
-fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<()> {
+/// Writes every enum of the C API as a Java enum that knows the value it is
+/// represented by in the C API.
+fn write_enums<P: AsRef<Path>>(path: P) -> Result<()> {
+    let mut path = path.as_ref().to_owned();
+
+    for e in ENUMS {
+        path.push(format!("{}.java", e.name));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        path.pop();
+
+        writeln!(writer, "package livesplitcore;")?;
+        write_class_comments(&mut writer, e.comments)?;
+        write!(writer, "\npublic enum {} {{", e.name)?;
+
+        for (i, variant) in e.variants.iter().enumerate() {
+            write!(writer, "\n    /**")?;
+            for comment in variant.comments {
+                write!(writer, "\n     * {comment}")?;
+            }
+            write!(
+                writer,
+                "\n     */\n    {}((byte) {}){}",
+                variant.name.to_shouty_snake_case(),
+                variant.value,
+                if i + 1 == e.variants.len() { ";" } else { "," }
+            )?;
+        }
+
+        writeln!(
+            writer,
+            r#"
+
+    private final byte value;
+
+    {name}(byte value) {{
+        this.value = value;
+    }}
+
+    /**
+     * Returns the value that represents this variant in the C API.
+     */
+    public byte getValue() {{
+        return value;
+    }}
+
+    /**
+     * Returns the variant that is represented by the given value in the C API.
+     */
+    public static {name} fromValue(byte value) {{
+        for ({name} variant : values()) {{
+            if (variant.value == value) {{
+                return variant;
+            }}
+        }}
+        throw new IllegalArgumentException("Unknown {name}: " + value);
+    }}
+}}"#,
+            name = e.name
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_class_comments<W: Write, S: AsRef<str>>(mut writer: W, comments: &[S]) -> Result<()> {
     write!(
         writer,
         r#"
@@ -41,6 +115,7 @@ fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<
             r#"
  * {}"#,
             comment
+                .as_ref()
                 .replace("<NULL>", "null")
                 .replace("<TRUE>", "true")
                 .replace("<FALSE>", "false")
@@ -0,0 +1,556 @@
+use super::write_class_comments;
+use crate::{Class, Function, Type, TypeKind};
+use heck::ToLowerCamelCase;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Result, Write},
+    path::Path,
+};
+
+fn get_hl_type(ty: &Type) -> String {
+    if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
+        match ty.kind {
+            TypeKind::Ref => format!("{}Ref", ty.name),
+            TypeKind::RefMut => format!("{}RefMut", ty.name),
+            TypeKind::Value => ty.name.clone(),
+        }
+    } else if is_string(ty) {
+        "String".to_string()
+    } else {
+        get_ll_type(ty).to_string()
+    }
+}
+
+fn get_ll_type(ty: &Type) -> &str {
+    match (ty.kind, ty.name.as_str()) {
+        (TypeKind::Ref, _) | (TypeKind::RefMut, _) => "MemorySegment",
+        (_, t) if !ty.is_custom => match t {
+            "i8" | "u8" | "c_char" => "byte",
+            "i16" | "u16" => "short",
+            "i32" | "u32" => "int",
+            "i64" | "u64" | "usize" | "isize" => "long",
+            "f32" => "float",
+            "f64" => "double",
+            "bool" => "boolean",
+            "()" => "void",
+            "Json" => "MemorySegment",
+            x => x,
+        },
+        _ => "MemorySegment",
+    }
+}
+
+fn get_layout(ty: &Type) -> &str {
+    match get_ll_type(ty) {
+        "byte" => "ValueLayout.JAVA_BYTE",
+        "short" => "ValueLayout.JAVA_SHORT",
+        "int" => "ValueLayout.JAVA_INT",
+        "long" => "ValueLayout.JAVA_LONG",
+        "float" => "ValueLayout.JAVA_FLOAT",
+        "double" => "ValueLayout.JAVA_DOUBLE",
+        "boolean" => "ValueLayout.JAVA_BOOLEAN",
+        _ => "ValueLayout.ADDRESS",
+    }
+}
+
+fn is_string(ty: &Type) -> bool {
+    !ty.is_custom && (ty.name == "Json" || (ty.name == "c_char" && ty.kind == TypeKind::Ref))
+}
+
+fn write_fn<W: Write>(mut writer: W, function: &Function, class_name: &str) -> Result<()> {
+    let is_static = function.is_static();
+    let has_return_type = function.has_return_type();
+    let output = &function.output;
+    let return_type = get_hl_type(output);
+    let return_type_ll = get_ll_type(output);
+    let is_constructor = function.method == "new" && !output.is_nullable;
+    let needs_arena = function.inputs.iter().any(|(_, ty)| is_string(ty));
+    let mut method = function.method.to_lower_camel_case();
+    if method == "clone" {
+        method = "copy".into();
+    } else if method == "close" {
+        method = "finish".into();
+    } else if method == "new" {
+        method = "create".into();
+    } else if method == "default" {
+        method = "createDefault".into();
+    }
+
+    if !function.comments.is_empty() {
+        write!(
+            writer,
+            r#"
+    /**"#
+        )?;
+
+        for comment in &function.comments {
+            write!(
+                writer,
+                r#"
+     * {}"#,
+                comment
+                    .replace("<NULL>", "null")
+                    .replace("<TRUE>", "true")
+                    .replace("<FALSE>", "false")
+            )?;
+        }
+
+        write!(
+            writer,
+            r#"
+     */"#
+        )?;
+    }
+
+    if is_constructor {
+        write!(
+            writer,
+            r#"
+    public {class_name}("#
+        )?;
+    } else {
+        write!(
+            writer,
+            r#"
+    public{} {} {}("#,
+            if is_static { " static" } else { "" },
+            return_type,
+            method
+        )?;
+    }
+
+    for (i, (name, typ)) in function
+        .inputs
+        .iter()
+        .skip(usize::from(!is_static))
+        .enumerate()
+    {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        write!(
+            writer,
+            "{} {}",
+            get_hl_type(typ),
+            name.to_lower_camel_case()
+        )?;
+    }
+
+    write!(writer, ") {{")?;
+
+    if is_constructor {
+        write!(
+            writer,
+            r#"
+        super(MemorySegment.NULL);"#
+        )?;
+    }
+
+    for (name, typ) in function.inputs.iter() {
+        if typ.is_custom {
+            write!(
+                writer,
+                r#"
+        if (LiveSplitCoreNative.isNull({name}.ptr)) {{
+            throw new IllegalStateException("{name} is disposed");
+        }}"#,
+                name = name.to_lower_camel_case()
+            )?;
+        }
+    }
+
+    if needs_arena {
+        write!(
+            writer,
+            r#"
+        try (Arena arena = Arena.ofConfined()) {{
+            "#
+        )?;
+    } else {
+        write!(
+            writer,
+            r#"
+        try {{
+            "#
+        )?;
+    }
+
+    if has_return_type {
+        if is_constructor {
+            write!(writer, "this.ptr = ({return_type_ll}) ")?;
+        } else {
+            write!(writer, "{return_type_ll} result = ({return_type_ll}) ")?;
+        }
+    }
+
+    write!(
+        writer,
+        r#"LiveSplitCoreNative.{}.invokeExact("#,
+        &function.name
+    )?;
+
+    for (i, (name, typ)) in function.inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        let name = name.to_lower_camel_case();
+        if name == "this" {
+            write!(writer, "this.ptr")?;
+        } else if typ.is_custom {
+            write!(writer, "{name}.ptr")?;
+        } else if typ.enum_name.is_some() {
+            write!(writer, "{name}.getValue()")?;
+        } else if is_string(typ) && typ.is_nullable {
+            write!(
+                writer,
+                "{name} == null ? MemorySegment.NULL : arena.allocateUtf8String({name})"
+            )?;
+        } else if is_string(typ) {
+            write!(writer, "arena.allocateUtf8String({name})")?;
+        } else {
+            write!(writer, "{name}")?;
+        }
+    }
+
+    write!(writer, ");")?;
+
+    for (name, typ) in function.inputs.iter() {
+        if typ.is_custom && typ.kind == TypeKind::Value {
+            write!(
+                writer,
+                r#"
+            {}.ptr = MemorySegment.NULL;"#,
+                name.to_lower_camel_case()
+            )?;
+        }
+    }
+
+    if has_return_type && !is_constructor {
+        let value = if output.is_custom {
+            format!("new {return_type}(result)")
+        } else if is_string(output) {
+            String::from("LiveSplitCoreNative.string(result)")
+        } else if output.enum_name.is_some() {
+            format!("{return_type}.fromValue(result)")
+        } else {
+            String::from("result")
+        };
+        if output.is_nullable && (output.is_custom || is_string(output)) {
+            write!(
+                writer,
+                r#"
+            if (LiveSplitCoreNative.isNull(result)) {{
+                return null;
+            }}"#
+            )?;
+        }
+        write!(
+            writer,
+            r#"
+            return {value};"#
+        )?;
+    }
+
+    write!(
+        writer,
+        r#"
+        }} catch (Throwable e) {{
+            throw LiveSplitCoreNative.rethrow(e);
+        }}
+    }}"#
+    )?;
+
+    Ok(())
+}
+
+fn write_class_ref<P: AsRef<Path>>(path: P, class_name: &str, class: &Class) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let class_name_ref = format!("{class_name}Ref");
+
+    write!(
+        writer,
+        r#"package livesplitcore;
+
+import java.lang.foreign.*;
+"#
+    )?;
+
+    write_class_comments(&mut writer, &class.comments)?;
+
+    write!(
+        writer,
+        r#"
+public class {class_name_ref} {{
+    MemorySegment ptr;"#
+    )?;
+
+    for function in &class.shared_fns {
+        write_fn(&mut writer, function, &class_name_ref)?;
+    }
+
+    if class_name == "SharedTimer" {
+        write!(
+            writer,
+            "{}",
+            r#"
+    public void readWith(java.util.function.Consumer<TimerRef> action) {
+        try (TimerReadLock timerLock = read()) {
+            action.accept(timerLock.timer());
+        }
+    }
+    public void writeWith(java.util.function.Consumer<TimerRefMut> action) {
+        try (TimerWriteLock timerLock = write()) {
+            action.accept(timerLock.timer());
+        }
+    }"#
+        )?;
+    }
+
+    write!(
+        writer,
+        r#"
+    {class_name_ref}(MemorySegment ptr) {{
+        this.ptr = ptr;
+    }}
+}}"#
+    )
+}
+
+fn write_class_ref_mut<P: AsRef<Path>>(path: P, class_name: &str, class: &Class) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let class_name_ref = format!("{class_name}Ref");
+    let class_name_ref_mut = format!("{class_name}RefMut");
+
+    write!(
+        writer,
+        r#"package livesplitcore;
+
+import java.lang.foreign.*;
+"#
+    )?;
+
+    write_class_comments(&mut writer, &class.comments)?;
+
+    write!(
+        writer,
+        r#"
+public class {class_name_ref_mut} extends {class_name_ref} {{"#
+    )?;
+
+    for function in &class.mut_fns {
+        write_fn(&mut writer, function, class_name)?;
+    }
+
+    write!(
+        writer,
+        r#"
+    {class_name_ref_mut}(MemorySegment ptr) {{
+        super(ptr);
+    }}
+}}"#
+    )
+}
+
+fn write_class<P: AsRef<Path>>(path: P, class_name: &str, class: &Class) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let class_name_ref_mut = format!("{class_name}RefMut");
+
+    write!(
+        writer,
+        r#"package livesplitcore;
+
+import java.lang.foreign.*;
+"#
+    )?;
+
+    write_class_comments(&mut writer, &class.comments)?;
+
+    write!(
+        writer,
+        r#"
+public class {class_name} extends {class_name_ref_mut} implements AutoCloseable {{
+    private void drop() {{
+        if (!LiveSplitCoreNative.isNull(ptr)) {{"#
+    )?;
+
+    if let Some(function) = class.own_fns.iter().find(|f| f.method == "drop") {
+        write!(
+            writer,
+            r#"
+            try {{
+                LiveSplitCoreNative.{}.invokeExact(ptr);
+            }} catch (Throwable e) {{
+                throw LiveSplitCoreNative.rethrow(e);
+            }}"#,
+            function.name
+        )?;
+    }
+
+    write!(
+        writer,
+        r#"
+            ptr = MemorySegment.NULL;
+        }}
+    }}
+    /**
+     * Frees the object, allowing it to clean up all of its memory. The object
+     * can't be used afterwards.
+     */
+    public void close() {{
+        drop();
+    }}
+    /**
+     * Ties the lifetime of the object to the arena provided. The object gets
+     * freed once the arena gets closed, unless it got closed or passed to a
+     * function that takes ownership of it before that.
+     */
+    public {class_name} scopedTo(Arena arena) {{
+        MemorySegment.NULL.reinterpret(arena, segment -> drop());
+        return this;
+    }}"#
+    )?;
+
+    for function in class.static_fns.iter().chain(class.own_fns.iter()) {
+        if function.method != "drop" {
+            write_fn(&mut writer, function, class_name)?;
+        }
+    }
+
+    if class_name == "Run" {
+        write!(
+            writer,
+            "{}",
+            r#"
+    public static ParseRunResult parse(java.io.InputStream stream, String loadFilesPath) throws java.io.IOException {
+        byte[] arr = stream.readAllBytes();
+        try (Arena arena = Arena.ofConfined()) {
+            MemorySegment data = arena.allocateArray(ValueLayout.JAVA_BYTE, arr);
+            return Run.parse(data, arr.length, loadFilesPath);
+        }
+    }"#
+        )?;
+    }
+
+    write!(
+        writer,
+        r#"
+    {class_name}(MemorySegment ptr) {{
+        super(ptr);
+    }}
+}}"#
+    )
+}
+
+fn write_native_class<P: AsRef<Path>>(path: P, classes: &BTreeMap<String, Class>) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    write!(
+        writer,
+        "{}",
+        r#"package livesplitcore;
+
+import java.lang.foreign.*;
+import java.lang.invoke.MethodHandle;
+
+final class LiveSplitCoreNative {
+    private static final Linker LINKER = Linker.nativeLinker();
+    private static final SymbolLookup LOOKUP =
+        SymbolLookup.libraryLookup(System.mapLibraryName("livesplit_core"), Arena.global());
+
+    private LiveSplitCoreNative() {}
+
+    private static MethodHandle find(String name, FunctionDescriptor descriptor) {
+        return LINKER.downcallHandle(LOOKUP.find(name).orElseThrow(), descriptor);
+    }
+
+    static boolean isNull(MemorySegment segment) {
+        return segment.address() == 0;
+    }
+
+    static String string(MemorySegment segment) {
+        return segment.reinterpret(Long.MAX_VALUE).getUtf8String(0);
+    }
+
+    static RuntimeException rethrow(Throwable e) {
+        if (e instanceof RuntimeException runtimeException) {
+            return runtimeException;
+        }
+        if (e instanceof Error error) {
+            throw error;
+        }
+        return new RuntimeException(e);
+    }
+"#
+    )?;
+
+    for class in classes.values() {
+        for function in class
+            .static_fns
+            .iter()
+            .chain(class.own_fns.iter())
+            .chain(class.shared_fns.iter())
+            .chain(class.mut_fns.iter())
+        {
+            write!(
+                writer,
+                r#"
+    static final MethodHandle {name} = find("{name}", FunctionDescriptor."#,
+                name = function.name
+            )?;
+
+            if function.has_return_type() {
+                write!(writer, "of({}", get_layout(&function.output))?;
+                for (_, typ) in &function.inputs {
+                    write!(writer, ", {}", get_layout(typ))?;
+                }
+            } else {
+                write!(writer, "ofVoid(")?;
+                for (i, (_, typ)) in function.inputs.iter().enumerate() {
+                    if i != 0 {
+                        write!(writer, ", ")?;
+                    }
+                    write!(writer, "{}", get_layout(typ))?;
+                }
+            }
+
+            write!(writer, "));")?;
+        }
+    }
+
+    writeln!(
+        writer,
+        "{}",
+        r#"
+}"#
+    )
+}
+
+pub fn write<P: AsRef<Path>>(path: P, classes: &BTreeMap<String, Class>) -> Result<()> {
+    let mut path = path.as_ref().to_owned();
+
+    path.push("LiveSplitCoreNative.java");
+    write_native_class(&path, classes)?;
+    path.pop();
+
+    for (class_name, class) in classes {
+        path.push(format!("{class_name}Ref"));
+        path.set_extension("java");
+        write_class_ref(&path, class_name, class)?;
+        path.pop();
+
+        path.push(format!("{class_name}RefMut"));
+        path.set_extension("java");
+        write_class_ref_mut(&path, class_name, class)?;
+        path.pop();
+
+        path.push(class_name);
+        path.set_extension("java");
+        write_class(&path, class_name, class)?;
+        path.pop();
+    }
+
+    Ok(())
+}
@@ -105,8 +105,12 @@ extern "C" JNIEXPORT {} Java_livesplitcore_LiveSplitCoreNative_{}_1{}(JNIEnv* jn
         }
     }
 
+    let returns_nullable_string = return_type == "jstring" && function.output.is_nullable;
+
     if has_return_type {
-        if return_type == "jstring" {
+        if returns_nullable_string {
+            write!(writer, r#"auto cstr_result = ("#)?;
+        } else if return_type == "jstring" {
             write!(writer, r#"auto result = jni_env->NewStringUTF("#)?;
         } else {
             write!(writer, r#"auto result = ({return_type})("#)?;
@@ -142,6 +146,14 @@ extern "C" JNIEXPORT {} Java_livesplitcore_LiveSplitCoreNative_{}_1{}(JNIEnv* jn
 
     write!(writer, r#";"#)?;
 
+    if returns_nullable_string {
+        write!(
+            writer,
+            r#"
+    auto result = cstr_result != nullptr ? jni_env->NewStringUTF(cstr_result) : nullptr;"#
+        )?;
+    }
+
     for (name, typ) in &function.inputs {
         let jni_type = get_jni_type(typ);
         if jni_type == "jstring" {
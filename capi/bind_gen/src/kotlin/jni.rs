@@ -1,5 +1,5 @@
-use crate::{Class, Function, Type, TypeKind};
-use heck::ToLowerCamelCase;
+use crate::{Class, Function, Type, TypeKind, ENUMS};
+use heck::{ToLowerCamelCase, ToShoutySnakeCase};
 use std::{
     collections::BTreeMap,
     fs::File,
@@ -8,7 +8,9 @@ use std::{
 };
 
 fn get_hl_type(ty: &Type) -> String {
-    if ty.is_custom {
+    if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
         match ty.kind {
             TypeKind::Ref => format!("{}Ref", ty.name),
             TypeKind::RefMut => format!("{}RefMut", ty.name),
@@ -50,7 +52,7 @@ fn get_ll_type(ty: &Type) -> &str {
     }
 }
 
-fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<()> {
+fn write_class_comments<W: Write, S: AsRef<str>>(mut writer: W, comments: &[S]) -> Result<()> {
     write!(
         writer,
         r#"
@@ -63,6 +65,7 @@ fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<
             r#"
  * {}"#,
             comment
+                .as_ref()
                 .replace("<NULL>", "null")
                 .replace("<TRUE>", "true")
                 .replace("<FALSE>", "false")
@@ -157,7 +160,7 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
         )?;
     } else if has_return_type {
         write!(writer, r#"): {return_type}"#)?;
-        if function.output.is_nullable && function.output.is_custom {
+        if function.output.is_nullable {
             write!(writer, "?")?;
         }
         write!(
@@ -191,6 +194,8 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
             write!(writer, "this.ptr = ")?;
         } else if function.output.is_custom {
             write!(writer, r#"val result = {return_type}("#)?;
+        } else if function.output.enum_name.is_some() {
+            write!(writer, r#"val result = {return_type}.fromValue("#)?;
         } else {
             write!(writer, "val result = ")?;
         }
@@ -214,6 +219,8 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
                 "this.ptr".to_string()
             } else if typ.is_custom {
                 format!("{}.ptr", name.to_lower_camel_case())
+            } else if typ.enum_name.is_some() {
+                format!("{}.value", name.to_lower_camel_case())
             } else {
                 name.to_lower_camel_case()
             }
@@ -222,7 +229,10 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
 
     write!(writer, ")")?;
 
-    if !is_constructor && has_return_type && function.output.is_custom {
+    if !is_constructor
+        && has_return_type
+        && (function.output.is_custom || function.output.enum_name.is_some())
+    {
         write!(writer, r#")"#)?;
     }
 
@@ -439,7 +449,7 @@ object LiveSplitCoreNative {
     init {
         System.loadLibrary("native-lib")
     }
-    external fun Run_parseString(data: String): Long"#
+    external fun Run_parseString(data: String, loadFilesPath: String): Long"#
     )?;
 
     for class in classes.values() {
@@ -478,6 +488,9 @@ object LiveSplitCoreNative {
 
             if function.has_return_type() {
                 write!(writer, ": {}", get_ll_type(&function.output))?;
+                if function.output.is_nullable && !function.output.is_custom {
+                    write!(writer, "?")?;
+                }
             }
         }
     }
@@ -490,9 +503,55 @@ object LiveSplitCoreNative {
     )
 }
 
+fn write_enums<P: AsRef<Path>>(path: P) -> Result<()> {
+    let mut path = path.as_ref().to_owned();
+
+    for e in ENUMS {
+        path.push(e.name);
+        path.set_extension("kt");
+        let mut writer = BufWriter::new(File::create(&path)?);
+        path.pop();
+
+        writeln!(writer, r#"package livesplitcore"#)?;
+
+        write_class_comments(&mut writer, e.comments)?;
+
+        write!(writer, "\nenum class {}(val value: Byte) {{", e.name)?;
+
+        for (i, variant) in e.variants.iter().enumerate() {
+            write!(writer, "\n    /**")?;
+            for comment in variant.comments {
+                write!(writer, "\n     * {comment}")?;
+            }
+            write!(
+                writer,
+                "\n     */\n    {}({}){}",
+                variant.name.to_shouty_snake_case(),
+                variant.value,
+                if i + 1 == e.variants.len() { ";" } else { "," }
+            )?;
+        }
+
+        writeln!(
+            writer,
+            r#"
+
+    companion object {{
+        fun fromValue(value: Byte): {} = values().first {{ it.value == value }}
+    }}
+}}"#,
+            e.name
+        )?;
+    }
+
+    Ok(())
+}
+
 pub fn write<P: AsRef<Path>>(path: P, classes: &BTreeMap<String, Class>) -> Result<()> {
     let mut path = path.as_ref().to_owned();
 
+    write_enums(&path)?;
+
     path.push("LiveSplitCoreNative.kt");
     write_native_class(&path, classes)?;
     path.pop();
@@ -0,0 +1,289 @@
+use crate::{c, Class, Function, Type, TypeKind, ENUMS};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io::{Result, Write},
+};
+
+const KEYWORDS: &[&str] = &[
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "goto", "if", "in",
+    "local", "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+fn is_string(ty: &Type) -> bool {
+    !ty.is_custom && (ty.name == "Json" || (ty.name == "c_char" && ty.kind == TypeKind::Ref))
+}
+
+fn get_input_name(name: &str) -> Cow<'_, str> {
+    if name == "this" {
+        Cow::Borrowed("self")
+    } else if KEYWORDS.contains(&name) {
+        Cow::Owned(format!("{name}_"))
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+fn get_class_name(ty: &Type) -> String {
+    match ty.kind {
+        TypeKind::Ref => format!("{}Ref", ty.name),
+        TypeKind::RefMut => format!("{}RefMut", ty.name),
+        TypeKind::Value => ty.name.clone(),
+    }
+}
+
+fn write_comments<W: Write, S: AsRef<str>>(mut writer: W, comments: &[S]) -> Result<()> {
+    for comment in comments {
+        writeln!(
+            writer,
+            "-- {}",
+            comment
+                .as_ref()
+                .replace("<NULL>", "nil")
+                .replace("<TRUE>", "true")
+                .replace("<FALSE>", "false")
+        )?;
+    }
+    Ok(())
+}
+
+fn write_fn<W: Write>(mut writer: W, function: &Function, class_name: &str) -> Result<()> {
+    let is_static = function.is_static();
+    let has_return_type = function.has_return_type();
+    let output = &function.output;
+    let is_fallible = has_return_type && output.is_nullable && output.is_custom;
+
+    writeln!(writer)?;
+    write_comments(&mut writer, &function.comments)?;
+    write!(
+        writer,
+        "function M.{class_name}{}{}(",
+        if is_static { "." } else { ":" },
+        function.method,
+    )?;
+
+    for (i, (name, _)) in function
+        .inputs
+        .iter()
+        .skip(usize::from(!is_static))
+        .enumerate()
+    {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        write!(writer, "{}", get_input_name(name))?;
+    }
+
+    writeln!(writer, ")")?;
+
+    for (name, ty) in &function.inputs {
+        if ty.is_custom {
+            writeln!(
+                writer,
+                "    assert({}.ptr ~= nil, \"{} is disposed\")",
+                get_input_name(name),
+                name,
+            )?;
+        }
+    }
+
+    if is_fallible {
+        writeln!(writer, "    native.LastError_clear()")?;
+    }
+
+    write!(writer, "    ")?;
+    if has_return_type {
+        write!(writer, "local result = ")?;
+    }
+    write!(writer, "native.{}(", function.name)?;
+
+    for (i, (name, ty)) in function.inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        if ty.is_custom {
+            write!(writer, "{}.ptr", get_input_name(name))?;
+        } else {
+            write!(writer, "{}", get_input_name(name))?;
+        }
+    }
+
+    writeln!(writer, ")")?;
+
+    for (name, ty) in &function.inputs {
+        if ty.is_custom && ty.kind == TypeKind::Value {
+            writeln!(writer, "    release({})", get_input_name(name))?;
+        }
+    }
+
+    if has_return_type {
+        if output.is_custom {
+            if output.is_nullable {
+                writeln!(writer, "    if result == nil then")?;
+                if is_fallible {
+                    writeln!(
+                        writer,
+                        "        local code = native.LastError_code()
+        if code ~= 0 then
+            error(M.LiveSplitCoreError.new(code, ffi.string(native.LastError_message())))
+        end"
+                    )?;
+                }
+                writeln!(writer, "        return nil\n    end")?;
+            }
+            let class = get_class_name(output);
+            if output.kind == TypeKind::Value {
+                writeln!(writer, "    return own(result, M.{class})")?;
+            } else {
+                writeln!(
+                    writer,
+                    "    return setmetatable({{ ptr = result }}, M.{class})"
+                )?;
+            }
+        } else if is_string(output) {
+            if output.is_nullable {
+                writeln!(
+                    writer,
+                    "    if result == nil then\n        return nil\n    end"
+                )?;
+            }
+            writeln!(writer, "    return ffi.string(result)")?;
+        } else {
+            writeln!(writer, "    return result")?;
+        }
+    }
+
+    writeln!(writer, "end")
+}
+
+pub fn write<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+    write!(
+        writer,
+        "{}",
+        r#"-- Bindings for livesplit-core using the FFI library of LuaJIT. Every class
+-- of livesplit-core is represented by three classes. The class named after
+-- the class owns the object. It is freed automatically once it gets garbage
+-- collected, but can also be freed early by calling dispose. The Ref and
+-- RefMut classes only borrow the object and can't outlive its owner.
+
+local ffi = require("ffi")
+
+ffi.cdef[[
+"#
+    )?;
+
+    c::write_declarations(&mut writer, classes)?;
+
+    write!(
+        writer,
+        "{}",
+        r#"]]
+
+local native = ffi.load("livesplit_core")
+local M = {}
+
+-- Raised when a function of livesplit-core fails. The code describes the kind
+-- of error, while the message describes what went wrong.
+M.LiveSplitCoreError = {}
+M.LiveSplitCoreError.__index = M.LiveSplitCoreError
+M.LiveSplitCoreError.__tostring = function(self)
+    return self.message
+end
+
+function M.LiveSplitCoreError.new(code, message)
+    return setmetatable({ code = code, message = message }, M.LiveSplitCoreError)
+end
+
+local function own(ptr, class)
+    if class.drop_fn ~= nil then
+        ptr = ffi.gc(ptr, class.drop_fn)
+    end
+    return setmetatable({ ptr = ptr }, class)
+end
+
+local function release(object)
+    if getmetatable(object).drop_fn ~= nil then
+        ffi.gc(object.ptr, nil)
+    end
+    object.ptr = nil
+end
+"#
+    )?;
+
+    for e in ENUMS {
+        writeln!(writer)?;
+        write_comments(&mut writer, e.comments)?;
+        writeln!(writer, "M.{} = {{", e.name)?;
+        for variant in e.variants {
+            for comment in variant.comments {
+                writeln!(writer, "    -- {comment}")?;
+            }
+            writeln!(writer, "    {} = {},", variant.name, variant.value)?;
+        }
+        writeln!(writer, "}}")?;
+    }
+
+    for class_name in classes.keys() {
+        writeln!(
+            writer,
+            "
+M.{class_name}Ref = {{}}
+M.{class_name}Ref.__index = M.{class_name}Ref
+M.{class_name}RefMut = setmetatable({{}}, {{ __index = M.{class_name}Ref }})
+M.{class_name}RefMut.__index = M.{class_name}RefMut
+M.{class_name} = setmetatable({{}}, {{ __index = M.{class_name}RefMut }})
+M.{class_name}.__index = M.{class_name}"
+        )?;
+    }
+
+    for (class_name, class) in classes {
+        writeln!(writer)?;
+        write_comments(&mut writer, &class.comments)?;
+
+        if let Some(function) = class.own_fns.iter().find(|f| f.method == "drop") {
+            writeln!(writer, "M.{class_name}.drop_fn = native.{}", function.name)?;
+        }
+
+        write!(
+            writer,
+            "
+-- Frees the object, allowing it to clean up all of its memory. This happens
+-- automatically once the object gets garbage collected, but can be done early
+-- by calling this. The object can't be used afterwards.
+function M.{class_name}:dispose()
+    if self.ptr ~= nil then
+        local ptr = self.ptr
+        release(self)
+        if M.{class_name}.drop_fn ~= nil then
+            M.{class_name}.drop_fn(ptr)
+        end
+    end
+end
+"
+        )?;
+
+        for (function, receiver) in class
+            .shared_fns
+            .iter()
+            .map(|f| (f, format!("{class_name}Ref")))
+            .chain(
+                class
+                    .mut_fns
+                    .iter()
+                    .map(|f| (f, format!("{class_name}RefMut"))),
+            )
+            .chain(
+                class
+                    .static_fns
+                    .iter()
+                    .chain(class.own_fns.iter())
+                    .filter(|f| f.method != "drop")
+                    .map(|f| (f, class_name.clone())),
+            )
+        {
+            write_fn(&mut writer, function, &receiver)?;
+        }
+    }
+
+    writeln!(writer, "\nreturn M")
+}
@@ -1,23 +1,34 @@
 #![allow(clippy::write_literal)]
 
 mod c;
+mod cpp;
 mod csharp;
+mod dart;
+mod deno;
+mod go;
 mod java;
 mod jni_cpp;
 mod kotlin;
+mod lua;
+mod napi;
 mod node;
+mod php;
 mod python;
 mod ruby;
+mod smoke_tests;
 mod swift;
+#[cfg(test)]
+mod tests;
 mod typescript;
 mod wasm_bindgen;
+mod zig;
 
 use clap::Parser;
 use std::{
     collections::BTreeMap,
     fs::{self, create_dir_all, remove_dir_all, File},
     io::{BufWriter, Read, Result},
-    path::PathBuf,
+    path::{Path, PathBuf},
     rc::Rc,
 };
 use syn::{
@@ -34,6 +45,11 @@ pub struct Opt {
         default_value = "../liblivesplit_core.so"
     )]
     ruby_lib_path: String,
+    #[clap(
+        long = "python-handles",
+        help = "Refer to objects by handles in the Python bindings, so that using an object after it got dropped raises an error"
+    )]
+    python_handles: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -43,15 +59,91 @@ pub enum TypeKind {
     RefMut,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Type {
     kind: TypeKind,
     is_custom: bool,
     is_nullable: bool,
     name: String,
+    enum_name: Option<String>,
+}
+
+/// An enum of the C API. It is passed across the boundary as its underlying
+/// `u8` representation.
+pub struct Enum {
+    name: &'static str,
+    comments: &'static [&'static str],
+    variants: &'static [Variant],
+}
+
+pub struct Variant {
+    name: &'static str,
+    value: u8,
+    comments: &'static [&'static str],
 }
 
-#[derive(Debug)]
+/// The C function pointer types that the functions may take.
+const CALLBACKS: &[&str] = &["EventListener", "HostComponentStateCallback"];
+
+static ENUMS: &[Enum] = &[
+    Enum {
+        name: "TimingMethod",
+        comments: &[
+            "A Timing Method describes which form of timing is used. This can either be",
+            "Real Time or Game Time.",
+        ],
+        variants: &[
+            Variant {
+                name: "RealTime",
+                value: 0,
+                comments: &[
+                    "Real Time is the unmodified timing that is as close to an atomic clock as",
+                    "possible.",
+                ],
+            },
+            Variant {
+                name: "GameTime",
+                value: 1,
+                comments: &[
+                    "Game Time describes the timing that is provided by the game that is being",
+                    "run. This is entirely optional and may either be Real Time with loading",
+                    "times removed or some time provided by the game.",
+                ],
+            },
+        ],
+    },
+    Enum {
+        name: "TimerPhase",
+        comments: &[
+            "Describes which phase the timer is currently in. This tells you if there's an",
+            "active speedrun attempt and whether it is paused or it ended.",
+        ],
+        variants: &[
+            Variant {
+                name: "NotRunning",
+                value: 0,
+                comments: &["There's currently no active attempt."],
+            },
+            Variant {
+                name: "Running",
+                value: 1,
+                comments: &["There's an active attempt that didn't end yet and isn't paused."],
+            },
+            Variant {
+                name: "Ended",
+                value: 2,
+                comments: &["There's an attempt that already ended, but didn't get reset yet."],
+            },
+            Variant {
+                name: "Paused",
+                value: 3,
+                comments: &["There's an active attempt that is currently paused."],
+            },
+        ],
+    },
+];
+
+#[derive(Debug, Clone)]
 pub struct Function {
     name: String,
     class: String,
@@ -74,6 +166,21 @@ impl Function {
     fn has_return_type(&self) -> bool {
         self.output.name != "()"
     }
+
+    /// Whether the function takes a C function pointer. Those can only be
+    /// provided through the C and C++ bindings.
+    fn has_callback(&self) -> bool {
+        self.inputs
+            .iter()
+            .any(|(_, ty)| CALLBACKS.contains(&&*ty.name))
+    }
+
+    /// Whether the function is the UTF-16 variant of the function of the same
+    /// name without the `_u16` suffix. Those write their string into a buffer
+    /// provided by the caller and are only used by the C, C++ and C# bindings.
+    fn is_utf16_variant(&self) -> bool {
+        self.name.ends_with("_u16")
+    }
 }
 
 #[derive(Debug, Default)]
@@ -85,6 +192,26 @@ pub struct Class {
     own_fns: Vec<Function>,
 }
 
+impl Class {
+    /// The class without the functions that only the C, C++ and C# bindings
+    /// make use of.
+    fn portable(&self) -> Class {
+        let filter = |fns: &[Function]| {
+            fns.iter()
+                .filter(|f| !f.has_callback() && !f.is_utf16_variant())
+                .cloned()
+                .collect()
+        };
+        Class {
+            comments: self.comments.clone(),
+            static_fns: filter(&self.static_fns),
+            shared_fns: filter(&self.shared_fns),
+            mut_fns: filter(&self.mut_fns),
+            own_fns: filter(&self.own_fns),
+        }
+    }
+}
+
 fn get_type(ty: &SynType) -> Type {
     match ty {
         SynType::Reference(reference) => {
@@ -118,10 +245,11 @@ fn get_type(ty: &SynType) -> Type {
             if let Some(rest) = name.strip_prefix("Owned") {
                 name = rest.to_string();
             }
-            if name == "TimingMethod" || name == "TimerPhase" {
-                name.clear();
-                name += "u8";
-            }
+            let enum_name = if ENUMS.iter().any(|e| e.name == name) {
+                Some(std::mem::replace(&mut name, String::from("u8")))
+            } else {
+                None
+            };
             let is_custom = !matches!(
                 &*name,
                 "u8" | "u16"
@@ -139,12 +267,15 @@ fn get_type(ty: &SynType) -> Type {
                     | "f32"
                     | "f64"
                     | "Json"
+                    | "EventListener"
+                    | "HostComponentStateCallback"
             );
             Type {
                 kind: TypeKind::Value,
                 is_custom,
                 is_nullable,
                 name,
+                enum_name,
             }
         }
         _ => panic!("Weird type"),
@@ -170,102 +301,123 @@ fn get_comment(attrs: &[syn::Attribute]) -> Vec<String> {
 fn main() {
     let opt = Opt::parse();
 
-    let mut contents = fs::read_to_string("../src/lib.rs").unwrap();
+    let functions = read_functions(Path::new("../src"));
+
+    let classes = fns_to_classes(functions);
+    let portable = classes
+        .iter()
+        .map(|(name, class)| (name.clone(), class.portable()))
+        .collect();
+
+    write_files(&classes, &portable, &opt).unwrap();
+}
+
+/// Collects the functions of the C API from the source code of all of its
+/// modules in the directory provided.
+fn read_functions(src: &Path) -> Vec<Function> {
+    let mut contents = fs::read_to_string(src.join("lib.rs")).unwrap();
     let file = parse_file(&contents).unwrap();
 
     let mut functions = Vec::new();
 
     for item in &file.items {
         let module = match item {
-            Item::Mod(m) => m,
+            Item::Mod(m) if m.content.is_none() => m,
             _ => continue,
         };
 
         contents.clear();
-        File::open(format!("../src/{}.rs", module.ident))
+        File::open(src.join(format!("{}.rs", module.ident)))
             .unwrap()
             .read_to_string(&mut contents)
             .unwrap();
-        let file = parse_file(&contents).unwrap();
-
-        let class_comments = Rc::new(get_comment(&file.attrs));
-
-        for item in &file.items {
-            let ItemFn {
-                attrs,
-                sig:
-                    Signature {
-                        abi,
-                        ident,
-                        inputs,
-                        output,
-                        ..
-                    },
-                ..
-            } = match item {
-                Item::Fn(i) if matches!(i.vis, Visibility::Public(_)) => i,
-                _ => continue,
-            };
+        parse_module(&contents, &mut functions);
+    }
 
-            if abi
-                .as_ref()
-                .and_then(|a| a.name.as_ref())
-                .map_or(true, |n| n.value() != "C")
-                || attrs.iter().all(|a| match &a.meta {
-                    Meta::Path(w) => !w.is_ident("no_mangle"),
-                    _ => true,
-                })
-            {
-                // Not `extern "C"` or not `#[no_mangle]`.
-                continue;
-            }
+    functions
+}
 
-            let comments = get_comment(attrs);
+/// Collects the functions of the C API that are declared in the source code of
+/// a module.
+fn parse_module(contents: &str, functions: &mut Vec<Function>) {
+    let file = parse_file(contents).unwrap();
 
-            let output = if let ReturnType::Type(_, ty) = output {
-                get_type(ty)
-            } else {
-                Type {
-                    kind: TypeKind::Value,
-                    is_custom: false,
-                    is_nullable: false,
-                    name: String::from("()"),
-                }
-            };
+    let class_comments = Rc::new(get_comment(&file.attrs));
 
-            let inputs = inputs
-                .iter()
-                .map(|i| match i {
-                    FnArg::Typed(c) => c,
-                    _ => panic!("Found a weird fn argument"),
-                })
-                .map(|c| {
-                    let name = match &*c.pat {
-                        Pat::Ident(ident) => ident.ident.to_string(),
-                        _ => String::from("parameter"),
-                    };
-                    (name, get_type(&c.ty))
-                })
-                .collect();
-
-            let name = ident.to_string();
-            let (class, method) = name.split_once('_').unwrap();
-            let class = class.to_string();
-            let method = method.to_string();
-
-            functions.push(Function {
-                name,
-                class,
-                method,
-                output,
-                inputs,
-                comments,
-                class_comments: class_comments.clone(),
-            });
+    for item in &file.items {
+        let ItemFn {
+            attrs,
+            sig:
+                Signature {
+                    abi,
+                    ident,
+                    inputs,
+                    output,
+                    ..
+                },
+            ..
+        } = match item {
+            Item::Fn(i) if matches!(i.vis, Visibility::Public(_)) => i,
+            _ => continue,
+        };
+
+        if abi
+            .as_ref()
+            .and_then(|a| a.name.as_ref())
+            .map_or(true, |n| n.value() != "C")
+            || attrs.iter().all(|a| match &a.meta {
+                Meta::Path(w) => !w.is_ident("no_mangle"),
+                _ => true,
+            })
+        {
+            // Not `extern "C"` or not `#[no_mangle]`.
+            continue;
         }
-    }
 
-    write_files(&fns_to_classes(functions), &opt).unwrap();
+        let comments = get_comment(attrs);
+
+        let output = if let ReturnType::Type(_, ty) = output {
+            get_type(ty)
+        } else {
+            Type {
+                kind: TypeKind::Value,
+                is_custom: false,
+                is_nullable: false,
+                name: String::from("()"),
+                enum_name: None,
+            }
+        };
+
+        let inputs = inputs
+            .iter()
+            .map(|i| match i {
+                FnArg::Typed(c) => c,
+                _ => panic!("Found a weird fn argument"),
+            })
+            .map(|c| {
+                let name = match &*c.pat {
+                    Pat::Ident(ident) => ident.ident.to_string(),
+                    _ => String::from("parameter"),
+                };
+                (name, get_type(&c.ty))
+            })
+            .collect();
+
+        let name = ident.to_string();
+        let (class, method) = name.split_once('_').unwrap();
+        let class = class.to_string();
+        let method = method.to_string();
+
+        functions.push(Function {
+            name,
+            class,
+            method,
+            output,
+            inputs,
+            comments,
+            class_comments: class_comments.clone(),
+        });
+    }
 }
 
 fn fns_to_classes(functions: Vec<Function>) -> BTreeMap<String, Class> {
@@ -289,7 +441,11 @@ fn fns_to_classes(functions: Vec<Function>) -> BTreeMap<String, Class> {
     classes
 }
 
-fn write_files(classes: &BTreeMap<String, Class>, opt: &Opt) -> Result<()> {
+fn write_files(
+    all_classes: &BTreeMap<String, Class>,
+    classes: &BTreeMap<String, Class>,
+    opt: &Opt,
+) -> Result<()> {
     let mut path = PathBuf::from("..");
     path.push("bindings");
 
@@ -306,6 +462,15 @@ fn write_files(classes: &BTreeMap<String, Class>, opt: &Opt) -> Result<()> {
         path.push("livesplit_core.ts");
         node::write(BufWriter::new(File::create(&path)?), classes, true)?;
         path.pop();
+
+        napi::write(&path, classes)?;
+
+        path.push("test");
+        create_dir_all(&path)?;
+        path.push("smoke.test.js");
+        smoke_tests::write_node(BufWriter::new(File::create(&path)?))?;
+        path.pop();
+        path.pop();
     }
     path.pop();
 
@@ -313,17 +478,57 @@ fn write_files(classes: &BTreeMap<String, Class>, opt: &Opt) -> Result<()> {
     create_dir_all(&path)?;
     {
         path.push("index.js");
-        wasm_bindgen::write(BufWriter::new(File::create(&path)?), classes, false)?;
+        wasm_bindgen::write(BufWriter::new(File::create(&path)?), classes, false, false)?;
         path.pop();
 
         path.push("index.ts");
-        wasm_bindgen::write(BufWriter::new(File::create(&path)?), classes, true)?;
+        wasm_bindgen::write(BufWriter::new(File::create(&path)?), classes, true, false)?;
+        path.pop();
+
+        path.push("web");
+        create_dir_all(&path)?;
+        {
+            path.push("index.js");
+            wasm_bindgen::write(BufWriter::new(File::create(&path)?), classes, false, true)?;
+            path.pop();
+
+            path.push("index.ts");
+            wasm_bindgen::write(BufWriter::new(File::create(&path)?), classes, true, true)?;
+            path.pop();
+
+            path.push("package.json");
+            wasm_bindgen::write_web_package(BufWriter::new(File::create(&path)?))?;
+            path.pop();
+        }
         path.pop();
     }
     path.pop();
 
     path.push("LiveSplitCore.cs");
-    csharp::write(BufWriter::new(File::create(&path)?), classes)?;
+    csharp::write(BufWriter::new(File::create(&path)?), all_classes)?;
+    path.pop();
+
+    path.push("LiveSplitCore.Tests.cs");
+    smoke_tests::write_csharp(BufWriter::new(File::create(&path)?))?;
+    path.pop();
+
+    path.push("dart");
+    create_dir_all(&path)?;
+    dart::write(&path, classes)?;
+    path.pop();
+
+    path.push("deno");
+    create_dir_all(&path)?;
+    {
+        path.push("livesplit_core.ts");
+        deno::write(BufWriter::new(File::create(&path)?), classes)?;
+        path.pop();
+    }
+    path.pop();
+
+    path.push("go");
+    create_dir_all(&path)?;
+    go::write(&path, classes)?;
     path.pop();
 
     path.push("java");
@@ -336,16 +541,44 @@ fn write_files(classes: &BTreeMap<String, Class>, opt: &Opt) -> Result<()> {
     kotlin::write(&path, classes)?;
     path.pop();
 
+    path.push("livesplit_core.lua");
+    lua::write(BufWriter::new(File::create(&path)?), classes)?;
+    path.pop();
+
     path.push("LiveSplitCore.rb");
     ruby::write(BufWriter::new(File::create(&path)?), classes, opt)?;
     path.pop();
 
     path.push("livesplit_core.h");
-    c::write(BufWriter::new(File::create(&path)?), classes)?;
+    c::write(BufWriter::new(File::create(&path)?), all_classes)?;
+    path.pop();
+
+    path.push("LiveSplitCore.php");
+    php::write(BufWriter::new(File::create(&path)?), classes)?;
+    path.pop();
+
+    path.push("livesplit_core.hpp");
+    cpp::write(BufWriter::new(File::create(&path)?), all_classes)?;
     path.pop();
 
     path.push("livesplit_core.py");
-    python::write(BufWriter::new(File::create(&path)?), classes)?;
+    python::write(
+        BufWriter::new(File::create(&path)?),
+        classes,
+        opt.python_handles,
+    )?;
+    path.pop();
+
+    path.push("livesplit_core.pyi");
+    python::write_stubs(BufWriter::new(File::create(&path)?), classes)?;
+    path.pop();
+
+    path.push("test_livesplit_core.py");
+    smoke_tests::write_python(BufWriter::new(File::create(&path)?), opt.python_handles)?;
+    path.pop();
+
+    path.push("livesplit_core.zig");
+    zig::write(BufWriter::new(File::create(&path)?), classes)?;
     path.pop();
 
     path.push("swift");
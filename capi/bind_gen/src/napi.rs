@@ -0,0 +1,399 @@
+use crate::{c, Class, Function, Type, TypeKind};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufWriter, Result, Write},
+    path::Path,
+};
+
+static BINDING_GYP: &str = r#"{
+    "targets": [
+        {
+            "target_name": "livesplit_core",
+            "sources": ["livesplit_core_addon.cc"],
+            "include_dirs": ["<!(node -p \"require('node-addon-api').include_dir\")"],
+            "defines": ["NAPI_VERSION=8", "NAPI_DISABLE_CPP_EXCEPTIONS"],
+            "conditions": [
+                ["OS == 'win'", {
+                    "libraries": [
+                        "<(module_root_dir)/livesplit_core.lib",
+                        "ws2_32.lib",
+                        "userenv.lib",
+                        "bcrypt.lib",
+                        "ntdll.lib"
+                    ]
+                }],
+                ["OS == 'mac'", {
+                    "libraries": [
+                        "<(module_root_dir)/liblivesplit_core.a",
+                        "-framework CoreFoundation"
+                    ]
+                }],
+                ["OS != 'win' and OS != 'mac'", {
+                    "libraries": [
+                        "<(module_root_dir)/liblivesplit_core.a",
+                        "-lpthread",
+                        "-ldl",
+                        "-lm"
+                    ]
+                }]
+            ]
+        }
+    ]
+}
+"#;
+
+static PACKAGE_JSON: &str = r#"{
+    "name": "livesplit-core",
+    "version": "0.11.0",
+    "description": "livesplit-core is a library that provides a lot of functionality for creating a speedrun timer.",
+    "main": "livesplit_core.js",
+    "types": "livesplit_core.ts",
+    "license": "MIT",
+    "repository": {
+        "type": "git",
+        "url": "git+https://github.com/LiveSplit/livesplit-core.git"
+    },
+    "gypfile": true,
+    "binary": {
+        "napi_versions": [8]
+    },
+    "scripts": {
+        "install": "node-gyp-build",
+        "prebuild": "prebuildify --napi --strip",
+        "test": "mocha"
+    },
+    "dependencies": {
+        "node-addon-api": "^7.0.0",
+        "node-gyp-build": "^4.8.0"
+    },
+    "devDependencies": {
+        "mocha": "^10.0.0",
+        "prebuildify": "^6.0.0"
+    }
+}
+"#;
+
+fn get_primitive_type(name: &str) -> &str {
+    match name {
+        "i8" => "int8_t",
+        "i16" => "int16_t",
+        "i32" => "int32_t",
+        "i64" => "int64_t",
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "usize" => "size_t",
+        "isize" => "ptrdiff_t",
+        "f32" => "float",
+        "f64" => "double",
+        "bool" => "bool",
+        "()" => "void",
+        "c_char" => "char",
+        x => x,
+    }
+}
+
+fn is_string(ty: &Type) -> bool {
+    !ty.is_custom && (ty.name == "Json" || (ty.name == "c_char" && ty.kind == TypeKind::Ref))
+}
+
+fn write_input<W: Write>(mut writer: W, index: usize, name: &str, ty: &Type) -> Result<()> {
+    let value = format!("info[{index}]");
+    if ty.is_custom {
+        writeln!(
+            writer,
+            "    auto {name} = static_cast<::LiveSplit::{}_s*>(pointer({value}));",
+            ty.name
+        )
+    } else if is_string(ty) {
+        writeln!(writer, "    std::string {name} = string({value});")
+    } else {
+        let primitive = get_primitive_type(&ty.name);
+        match ty.kind {
+            TypeKind::Ref | TypeKind::RefMut => {
+                writeln!(writer, "    auto {name} = buffer<{primitive}>({value});")
+            }
+            TypeKind::Value => {
+                let conversion = match primitive {
+                    "bool" => format!("{value}.As<Napi::Boolean>().Value()"),
+                    "float" => format!("{value}.As<Napi::Number>().FloatValue()"),
+                    "double" => format!("{value}.As<Napi::Number>().DoubleValue()"),
+                    "int8_t" | "int16_t" | "int32_t" => {
+                        format!("static_cast<{primitive}>({value}.As<Napi::Number>().Int32Value())")
+                    }
+                    "uint8_t" | "uint16_t" | "uint32_t" => format!(
+                        "static_cast<{primitive}>({value}.As<Napi::Number>().Uint32Value())"
+                    ),
+                    "char" => format!("{value}.As<Napi::String>().Utf8Value()[0]"),
+                    _ => {
+                        format!("static_cast<{primitive}>({value}.As<Napi::Number>().Int64Value())")
+                    }
+                };
+                writeln!(writer, "    {primitive} {name} = {conversion};")
+            }
+        }
+    }
+}
+
+fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
+    let output = &function.output;
+
+    writeln!(
+        writer,
+        "
+Napi::Value {}(const Napi::CallbackInfo& info) {{
+    Napi::Env env = info.Env();",
+        function.name
+    )?;
+
+    for (index, (name, ty)) in function.inputs.iter().enumerate() {
+        write_input(&mut writer, index, &format!("arg_{name}"), ty)?;
+    }
+
+    write!(writer, "    ")?;
+    if function.has_return_type() {
+        write!(writer, "auto result = ")?;
+    }
+    write!(writer, "::LiveSplit::{}(", function.name)?;
+
+    for (index, (name, ty)) in function.inputs.iter().enumerate() {
+        if index != 0 {
+            write!(writer, ", ")?;
+        }
+        if is_string(ty) && ty.is_nullable {
+            write!(
+                writer,
+                "info[{index}].IsNull() ? nullptr : arg_{name}.c_str()"
+            )?;
+        } else if is_string(ty) {
+            write!(writer, "arg_{name}.c_str()")?;
+        } else {
+            write!(writer, "arg_{name}")?;
+        }
+    }
+
+    writeln!(writer, ");")?;
+
+    let value = if !function.has_return_type() {
+        String::from("env.Undefined()")
+    } else if output.is_custom {
+        String::from("external(env, result)")
+    } else if is_string(output) {
+        String::from("string(env, result)")
+    } else {
+        match get_primitive_type(&output.name) {
+            "bool" => String::from("Napi::Boolean::New(env, result)"),
+            "char" => String::from("Napi::String::New(env, &result, 1)"),
+            _ => String::from("Napi::Number::New(env, static_cast<double>(result))"),
+        }
+    };
+
+    writeln!(writer, "    return {value};\n}}")
+}
+
+fn write_addon<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+    write!(
+        writer,
+        "{}",
+        r#"// The glue code of the native addon that exposes the C API of livesplit-core
+// to Node.js through N-API. Objects are passed around as externals that wrap
+// the raw pointers, with null representing a null pointer.
+
+#include <napi.h>
+
+#include <cstddef>
+#include <cstdint>
+#include <string>
+
+#include "livesplit_core.h"
+
+namespace {
+
+void* pointer(const Napi::Value& value) {
+    if (value.IsNull() || value.IsUndefined()) {
+        return nullptr;
+    }
+    return value.As<Napi::External<void>>().Data();
+}
+
+Napi::Value external(Napi::Env env, const void* ptr) {
+    if (ptr == nullptr) {
+        return env.Null();
+    }
+    return Napi::External<void>::New(env, const_cast<void*>(ptr));
+}
+
+std::string string(const Napi::Value& value) {
+    if (value.IsNull() || value.IsUndefined()) {
+        return std::string();
+    }
+    return value.As<Napi::String>().Utf8Value();
+}
+
+Napi::Value string(Napi::Env env, const char* str) {
+    if (str == nullptr) {
+        return env.Null();
+    }
+    return Napi::String::New(env, str);
+}
+
+template <typename T>
+T* buffer(const Napi::Value& value) {
+    if (value.IsNull() || value.IsUndefined()) {
+        return nullptr;
+    }
+    Napi::TypedArray array = value.As<Napi::TypedArray>();
+    return reinterpret_cast<T*>(
+        static_cast<uint8_t*>(array.ArrayBuffer().Data()) + array.ByteOffset());
+}
+"#
+    )?;
+
+    for class in classes.values() {
+        for function in class
+            .static_fns
+            .iter()
+            .chain(class.own_fns.iter())
+            .chain(class.shared_fns.iter())
+            .chain(class.mut_fns.iter())
+        {
+            write_fn(&mut writer, function)?;
+        }
+    }
+
+    write!(
+        writer,
+        "{}",
+        r#"
+// Parses a run on a worker thread. The buffer is kept alive by a reference
+// until the worker is done with it.
+class ParseRunWorker : public Napi::AsyncWorker {
+public:
+    explicit ParseRunWorker(const Napi::CallbackInfo& info)
+        : Napi::AsyncWorker(info.Env()),
+          deferred(Napi::Promise::Deferred::New(info.Env())),
+          data_ref(Napi::Persistent(info[0].As<Napi::Object>())),
+          data(buffer<uint8_t>(info[0])),
+          length(static_cast<size_t>(info[1].As<Napi::Number>().Int64Value())),
+          load_files_path(string(info[2])) {}
+
+    Napi::Promise Promise() const {
+        return deferred.Promise();
+    }
+
+protected:
+    void Execute() override {
+        result = ::LiveSplit::Run_parse(data, length, load_files_path.c_str());
+    }
+
+    void OnOK() override {
+        deferred.Resolve(external(Env(), result));
+    }
+
+private:
+    Napi::Promise::Deferred deferred;
+    Napi::ObjectReference data_ref;
+    const uint8_t* data;
+    size_t length;
+    std::string load_files_path;
+    ::LiveSplit::ParseRunResult_s* result = nullptr;
+};
+
+// Saves a run as a LiveSplit splits file on a worker thread. The string is
+// copied on the worker thread, as the one returned is only valid on the
+// thread that produced it.
+class SaveRunAsLssWorker : public Napi::AsyncWorker {
+public:
+    explicit SaveRunAsLssWorker(const Napi::CallbackInfo& info)
+        : Napi::AsyncWorker(info.Env()),
+          deferred(Napi::Promise::Deferred::New(info.Env())),
+          run(static_cast<const ::LiveSplit::Run_s*>(pointer(info[0]))) {}
+
+    Napi::Promise Promise() const {
+        return deferred.Promise();
+    }
+
+protected:
+    void Execute() override {
+        result = ::LiveSplit::Run_save_as_lss(run);
+    }
+
+    void OnOK() override {
+        deferred.Resolve(Napi::String::New(Env(), result));
+    }
+
+private:
+    Napi::Promise::Deferred deferred;
+    const ::LiveSplit::Run_s* run;
+    std::string result;
+};
+
+template <typename Worker>
+Napi::Value queue(const Napi::CallbackInfo& info) {
+    auto worker = new Worker(info);
+    Napi::Promise promise = worker->Promise();
+    worker->Queue();
+    return promise;
+}
+
+Napi::Object Init(Napi::Env env, Napi::Object exports) {
+    exports.Set("Run_parse_async",
+                Napi::Function::New(env, queue<ParseRunWorker>, "Run_parse_async"));
+    exports.Set("Run_save_as_lss_async",
+                Napi::Function::New(env, queue<SaveRunAsLssWorker>, "Run_save_as_lss_async"));"#
+    )?;
+
+    for class in classes.values() {
+        for function in class
+            .static_fns
+            .iter()
+            .chain(class.own_fns.iter())
+            .chain(class.shared_fns.iter())
+            .chain(class.mut_fns.iter())
+        {
+            write!(
+                writer,
+                r#"
+    exports.Set("{name}", Napi::Function::New(env, {name}, "{name}"));"#,
+                name = function.name
+            )?;
+        }
+    }
+
+    write!(
+        writer,
+        "{}",
+        r#"
+    return exports;
+}
+
+}
+
+NODE_API_MODULE(livesplit_core, Init)
+"#
+    )
+}
+
+pub fn write<P: AsRef<Path>>(path: P, classes: &BTreeMap<String, Class>) -> Result<()> {
+    let mut path = path.as_ref().to_owned();
+
+    path.push("binding.gyp");
+    fs::write(&path, BINDING_GYP)?;
+    path.pop();
+
+    path.push("package.json");
+    fs::write(&path, PACKAGE_JSON)?;
+    path.pop();
+
+    path.push("livesplit_core.h");
+    c::write(BufWriter::new(File::create(&path)?), classes)?;
+    path.pop();
+
+    path.push("livesplit_core_addon.cc");
+    write_addon(BufWriter::new(File::create(&path)?), classes)?;
+    path.pop();
+
+    Ok(())
+}
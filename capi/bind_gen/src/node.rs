@@ -5,8 +5,11 @@ use std::{
     io::{Result, Write},
 };
 
-fn get_hl_type_with_null(ty: &Type) -> String {
+fn get_hl_type_with_null(ty: &Type, type_script: bool) -> String {
     let mut formatted = get_hl_type_without_null(ty);
+    if type_script && ty.is_custom && ty.kind == TypeKind::Value {
+        formatted = format!("Disposable<{formatted}>");
+    }
     if ty.is_nullable {
         formatted.push_str(" | null");
     }
@@ -14,7 +17,9 @@ fn get_hl_type_with_null(ty: &Type) -> String {
 }
 
 fn get_hl_type_without_null(ty: &Type) -> String {
-    if ty.is_custom {
+    if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
         match ty.kind {
             TypeKind::Ref => format!("{}Ref", ty.name),
             TypeKind::RefMut => format!("{}RefMut", ty.name),
@@ -49,32 +54,6 @@ fn get_hl_type_without_null(ty: &Type) -> String {
     }
 }
 
-fn get_ll_type(ty: &Type) -> &str {
-    match (ty.kind, ty.name.as_str()) {
-        (TypeKind::Ref, "c_char") | (_, "Json") => "'CString'",
-        (TypeKind::Ref, _) | (TypeKind::RefMut, _) => "'pointer'",
-        (_, t) if !ty.is_custom => match t {
-            "i8" => "'int8'",
-            "i16" => "'int16'",
-            "i32" => "'int32'",
-            "i64" => "'int64'",
-            "u8" => "'uint8'",
-            "u16" => "'uint16'",
-            "u32" => "'uint32'",
-            "u64" => "'uint64'",
-            "usize" => "'size_t'",
-            "isize" => "'ssize_t'",
-            "f32" => "'float'",
-            "f64" => "'double'",
-            "bool" => "'bool'",
-            "()" => "'void'",
-            "c_char" => "'char'",
-            x => x,
-        },
-        _ => "'pointer'",
-    }
-}
-
 fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<()> {
     write!(
         writer,
@@ -104,7 +83,7 @@ fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<
 fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) -> Result<()> {
     let is_static = function.is_static();
     let has_return_type = function.has_return_type();
-    let return_type_with_null = get_hl_type_with_null(&function.output);
+    let return_type_with_null = get_hl_type_with_null(&function.output, type_script);
     let return_type_without_null = get_hl_type_without_null(&function.output);
     let method = function.method.to_lower_camel_case();
     let is_json = has_return_type && function.output.name == "Json";
@@ -143,7 +122,7 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) ->
                 writer,
                 r#"
      * @param {{{}}} {}"#,
-                get_hl_type_with_null(ty),
+                get_hl_type_with_null(ty, type_script),
                 name.to_lower_camel_case()
             )?;
         }
@@ -171,18 +150,23 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) ->
         method
     )?;
 
+    let consumes_this = !is_static && function.inputs[0].1.kind == TypeKind::Value;
+    if type_script && consumes_this {
+        write!(writer, "this: Disposable<{}>", function.class)?;
+    }
+
     for (i, (name, ty)) in function
         .inputs
         .iter()
         .skip(usize::from(!is_static))
         .enumerate()
     {
-        if i != 0 {
+        if i != 0 || (type_script && consumes_this) {
             write!(writer, ", ")?;
         }
         write!(writer, "{}", name.to_lower_camel_case())?;
         if type_script {
-            write!(writer, ": {}", get_hl_type_with_null(ty))?;
+            write!(writer, ": {}", get_hl_type_with_null(ty, type_script))?;
         }
     }
 
@@ -204,7 +188,7 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) ->
         if typ.is_custom {
             write!(
                 writer,
-                r#"if (ref.isNull({name}.ptr)) {{
+                r#"if ({name}.ptr === null) {{
             throw "{name} is disposed";
         }}
         "#,
@@ -255,7 +239,7 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) ->
             write!(
                 writer,
                 r#"
-        {}.ptr = ref.NULL;"#,
+        {}.ptr = null;"#,
                 name.to_lower_camel_case()
             )?;
         }
@@ -266,7 +250,7 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) ->
             write!(
                 writer,
                 r#"
-        if (ref.isNull(result.ptr)) {{
+        if (result.ptr === null) {{
             return null;
         }}"#
             )?;
@@ -277,6 +261,15 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) ->
                 r#"
         return JSON.parse(result);"#
             )?;
+        } else if type_script
+            && function.output.is_custom
+            && function.output.kind == TypeKind::Value
+        {
+            write!(
+                writer,
+                r#"
+        return result as Disposable<{return_type_without_null}>;"#
+            )?;
         } else {
             write!(
                 writer,
@@ -305,13 +298,12 @@ pub fn write<W: Write>(
             writer,
             r#""use strict";
 // tslint:disable
-import ffi = require('ffi');
 import fs = require('fs');
-import ref = require('ref');
 
 {}
 
-const liveSplitCoreNative = ffi.Library('livesplit_core', {{"#,
+const liveSplitCoreNative = require('node-gyp-build')(__dirname);
+"#,
             typescript::HEADER
         )?;
     } else {
@@ -319,48 +311,14 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {{"#,
             writer,
             "{}",
             r#""use strict";
-const ffi = require('ffi');
 const fs = require('fs');
-const ref = require('ref');
 
-const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
+const liveSplitCoreNative = require('node-gyp-build')(__dirname);
+"#
         )?;
+        typescript::write_js_enums(&mut writer, true)?;
     }
 
-    for class in classes.values() {
-        for function in class
-            .static_fns
-            .iter()
-            .chain(class.own_fns.iter())
-            .chain(class.shared_fns.iter())
-            .chain(class.mut_fns.iter())
-        {
-            write!(
-                writer,
-                r#"
-    '{}': [{}, ["#,
-                function.name,
-                get_ll_type(&function.output)
-            )?;
-
-            for (i, (_, typ)) in function.inputs.iter().enumerate() {
-                if i != 0 {
-                    write!(writer, ", ")?;
-                }
-                write!(writer, "{}", get_ll_type(typ))?;
-            }
-
-            write!(writer, "]],")?;
-        }
-    }
-
-    writeln!(
-        writer,
-        "{}",
-        r#"
-});"#
-    )?;
-
     for (class_name, class) in classes {
         let class_name_ref = format!("{class_name}Ref");
         let class_name_ref_mut = format!("{class_name}RefMut");
@@ -379,7 +337,7 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
             write!(
                 writer,
                 r#"
-    ptr: Buffer;"#
+    ptr: object | null;"#
             )?;
         }
 
@@ -387,6 +345,45 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
             write_fn(&mut writer, function, type_script)?;
         }
 
+        if class_name == "Run" {
+            if type_script {
+                write!(
+                    writer,
+                    "{}",
+                    r#"
+    /**
+     * Saves the Run as a LiveSplit splits file (*.lss) on a worker thread, so
+     * that the event loop isn't blocked. The Run must neither be modified nor
+     * disposed before the Promise settles.
+     */
+    saveAsLssAsync(): Promise<string> {
+        if (this.ptr === null) {
+            throw "this is disposed";
+        }
+        return liveSplitCoreNative.Run_save_as_lss_async(this.ptr);
+    }"#
+                )?;
+            } else {
+                write!(
+                    writer,
+                    "{}",
+                    r#"
+    /**
+     * Saves the Run as a LiveSplit splits file (*.lss) on a worker thread, so
+     * that the event loop isn't blocked. The Run must neither be modified nor
+     * disposed before the Promise settles.
+     * @return {Promise<string>}
+     */
+    saveAsLssAsync() {
+        if (this.ptr === null) {
+            throw "this is disposed";
+        }
+        return liveSplitCoreNative.Run_save_as_lss_async(this.ptr);
+    }"#
+                )?;
+            }
+        }
+
         if class_name == "SharedTimer" {
             if type_script {
                 write!(
@@ -433,14 +430,14 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
             write!(
                 writer,
                 r#"
-    constructor(ptr: Buffer) {{"#
+    constructor(ptr: object | null) {{"#
             )?;
         } else {
             write!(
                 writer,
                 r#"
     /**
-     * @param {{Buffer}} ptr
+     * @param {{object | null}} ptr
      */
     constructor(ptr) {{"#
             )?;
@@ -519,6 +516,37 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
             buf = buf.slice(data.byteOffset, data.byteOffset + data.byteLength);
         }
         this.activeSetIconFromArray(buf, buf.byteLength);
+    }"#
+                )?;
+            }
+        } else if class_name == "RunParser" {
+            if type_script {
+                write!(
+                    writer,
+                    "{}",
+                    r#"
+    feedArray(data: Int8Array) {
+        let buf = Buffer.from(data.buffer);
+        if (data.byteLength !== data.buffer.byteLength) {
+            buf = buf.slice(data.byteOffset, data.byteOffset + data.byteLength);
+        }
+        this.feed(buf, buf.byteLength);
+    }"#
+                )?;
+            } else {
+                write!(
+                    writer,
+                    "{}",
+                    r#"
+    /**
+     * @param {Int8Array} data
+     */
+    feedArray(data) {
+        let buf = Buffer.from(data.buffer);
+        if (data.byteLength !== data.buffer.byteLength) {
+            buf = buf.slice(data.byteOffset, data.byteOffset + data.byteLength);
+        }
+        this.feed(buf, buf.byteLength);
     }"#
                 )?;
             }
@@ -553,7 +581,7 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
             write!(
                 writer,
                 r#"
-    with<T>(closure: (obj: {class_name}) => T): T {{"#
+    with<T>(this: Disposable<{class_name}>, closure: (obj: Disposable<{class_name}>) => T): T {{"#
             )?;
         } else {
             write!(
@@ -576,7 +604,7 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
         }}
     }}
     dispose() {{
-        if (!ref.isNull(this.ptr)) {{"#
+        if (this.ptr !== null) {{"#
         )?;
 
         if let Some(function) = class.own_fns.iter().find(|f| f.method == "drop") {
@@ -591,7 +619,7 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
         write!(
             writer,
             r#"
-            this.ptr = ref.NULL;
+            this.ptr = null;
         }}
     }}"#
         )?;
@@ -608,20 +636,39 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
                     writer,
                     "{}",
                     r#"
-    static parseArray(data: Int8Array, loadFilesPath: string): ParseRunResult {
+    static parseArray(data: Int8Array, loadFilesPath: string): Disposable<ParseRunResult> {
         let buf = Buffer.from(data.buffer);
         if (data.byteLength !== data.buffer.byteLength) {
             buf = buf.slice(data.byteOffset, data.byteOffset + data.byteLength);
         }
         return Run.parse(buf, buf.byteLength, loadFilesPath);
     }
-    static parseFile(file: any, loadFilesPath: string): ParseRunResult {
+    static parseFile(file: any, loadFilesPath: string): Disposable<ParseRunResult> {
         const data = fs.readFileSync(file);
         return Run.parse(data, data.byteLength, loadFilesPath);
     }
-    static parseString(text: string, loadFilesPath: string): ParseRunResult {
-        const data = new Buffer(text);
+    static parseString(text: string, loadFilesPath: string): Disposable<ParseRunResult> {
+        const data = Buffer.from(text);
         return Run.parse(data, data.byteLength, loadFilesPath);
+    }
+    /**
+     * Parses the data on a worker thread, so that parsing large splits files
+     * doesn't block the event loop. The data must not be modified before the
+     * Promise settles.
+     */
+    static parseAsync(data: Buffer, loadFilesPath: string): Promise<Disposable<ParseRunResult>> {
+        return liveSplitCoreNative
+            .Run_parse_async(data, data.byteLength, loadFilesPath)
+            .then(function (ptr: object) {
+                return new ParseRunResult(ptr) as Disposable<ParseRunResult>;
+            });
+    }
+    /**
+     * Reads and parses the file without blocking the event loop.
+     */
+    static async parseFileAsync(file: any, loadFilesPath: string): Promise<Disposable<ParseRunResult>> {
+        const data = await fs.promises.readFile(file);
+        return Run.parseAsync(data, loadFilesPath);
     }"#
                 )?;
             } else {
@@ -656,8 +703,33 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
      * @return {ParseRunResult}
      */
     static parseString(text, loadFilesPath) {
-        const data = new Buffer(text);
+        const data = Buffer.from(text);
         return Run.parse(data, data.byteLength, loadFilesPath);
+    }
+    /**
+     * Parses the data on a worker thread, so that parsing large splits files
+     * doesn't block the event loop. The data must not be modified before the
+     * Promise settles.
+     * @param {Buffer} data
+     * @param {string} loadFilesPath
+     * @return {Promise<ParseRunResult>}
+     */
+    static parseAsync(data, loadFilesPath) {
+        return liveSplitCoreNative
+            .Run_parse_async(data, data.byteLength, loadFilesPath)
+            .then(function (ptr) {
+                return new ParseRunResult(ptr);
+            });
+    }
+    /**
+     * Reads and parses the file without blocking the event loop.
+     * @param {string | Buffer | number} file
+     * @param {string} loadFilesPath
+     * @return {Promise<ParseRunResult>}
+     */
+    static async parseFileAsync(file, loadFilesPath) {
+        const data = await fs.promises.readFile(file);
+        return Run.parseAsync(data, loadFilesPath);
     }"#
                 )?;
             }
@@ -667,15 +739,15 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
                     writer,
                     "{}",
                     r#"
-    static parseOriginalLivesplitArray(data: Int8Array): Layout | null {
+    static parseOriginalLivesplitArray(data: Int8Array): Disposable<Layout> | null {
         let buf = Buffer.from(data.buffer);
         if (data.byteLength !== data.buffer.byteLength) {
             buf = buf.slice(data.byteOffset, data.byteOffset + data.byteLength);
         }
         return Layout.parseOriginalLivesplit(buf, buf.byteLength);
     }
-    static parseOriginalLivesplitString(text: string): Layout | null {
-        const data = new Buffer(text);
+    static parseOriginalLivesplitString(text: string): Disposable<Layout> | null {
+        const data = Buffer.from(text);
         return Layout.parseOriginalLivesplit(data, data.byteLength);
     }"#
                 )?;
@@ -700,7 +772,7 @@ const liveSplitCoreNative = ffi.Library('livesplit_core', {"#
      * @return {Layout | null}
      */
     static parseOriginalLivesplitString(text) {
-        const data = new Buffer(text);
+        const data = Buffer.from(text);
         return Layout.parseOriginalLivesplit(data, data.byteLength);
     }"#
                 )?;
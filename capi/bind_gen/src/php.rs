@@ -0,0 +1,335 @@
+use crate::{c, Class, Function, Type, TypeKind, ENUMS};
+use heck::ToLowerCamelCase;
+use std::{
+    collections::BTreeMap,
+    io::{Result, Write},
+};
+
+fn get_hl_type(ty: &Type) -> String {
+    let name = if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
+        match ty.kind {
+            TypeKind::Ref => format!("{}Ref", ty.name),
+            TypeKind::RefMut => format!("{}RefMut", ty.name),
+            TypeKind::Value => ty.name.clone(),
+        }
+    } else if is_string(ty) {
+        String::from("string")
+    } else {
+        match (ty.kind, ty.name.as_str()) {
+            (TypeKind::Ref | TypeKind::RefMut, _) => "mixed",
+            (_, "f32" | "f64") => "float",
+            (_, "bool") => "bool",
+            (_, "()") => "void",
+            _ => "int",
+        }
+        .to_string()
+    };
+    if ty.is_nullable && name != "mixed" {
+        format!("?{name}")
+    } else {
+        name
+    }
+}
+
+fn is_string(ty: &Type) -> bool {
+    !ty.is_custom && (ty.name == "Json" || (ty.name == "c_char" && ty.kind == TypeKind::Ref))
+}
+
+fn get_input_name(name: &str) -> String {
+    if name == "this" {
+        String::from("$this")
+    } else {
+        format!("${}", name.to_lower_camel_case())
+    }
+}
+
+fn write_comments<W: Write, S: AsRef<str>>(
+    mut writer: W,
+    comments: &[S],
+    indent: &str,
+) -> Result<()> {
+    if comments.is_empty() {
+        return Ok(());
+    }
+    writeln!(writer, "{indent}/**")?;
+    for comment in comments {
+        writeln!(
+            writer,
+            "{indent} * {}",
+            comment
+                .as_ref()
+                .replace("<NULL>", "null")
+                .replace("<TRUE>", "true")
+                .replace("<FALSE>", "false")
+        )?;
+    }
+    writeln!(writer, "{indent} */")
+}
+
+fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
+    let is_static = function.is_static();
+    let has_return_type = function.has_return_type();
+    let output = &function.output;
+    let return_type = get_hl_type(output);
+    let is_fallible = has_return_type && output.is_nullable && output.is_custom;
+
+    writeln!(writer)?;
+    write_comments(&mut writer, &function.comments, "    ")?;
+    write!(
+        writer,
+        "    public {}function {}(",
+        if is_static { "static " } else { "" },
+        function.method.to_lower_camel_case(),
+    )?;
+
+    for (i, (name, ty)) in function
+        .inputs
+        .iter()
+        .skip(usize::from(!is_static))
+        .enumerate()
+    {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        write!(writer, "{} {}", get_hl_type(ty), get_input_name(name))?;
+    }
+
+    writeln!(writer, "): {return_type}\n    {{")?;
+
+    for (name, ty) in &function.inputs {
+        if ty.is_custom {
+            writeln!(
+                writer,
+                "        if ({}->ptr === null) {{
+            throw new \\LogicException('{} is disposed');
+        }}",
+                get_input_name(name),
+                name,
+            )?;
+        }
+    }
+
+    if is_fallible {
+        writeln!(writer, "        Native::get()->LastError_clear();")?;
+    }
+
+    write!(writer, "        ")?;
+    if has_return_type {
+        write!(writer, "$result = ")?;
+    }
+    write!(writer, "Native::get()->{}(", function.name)?;
+
+    for (i, (name, ty)) in function.inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        if ty.is_custom {
+            write!(writer, "{}->ptr", get_input_name(name))?;
+        } else if ty.enum_name.is_some() {
+            write!(writer, "{}->value", get_input_name(name))?;
+        } else {
+            write!(writer, "{}", get_input_name(name))?;
+        }
+    }
+
+    writeln!(writer, ");")?;
+
+    for (name, ty) in &function.inputs {
+        if ty.is_custom && ty.kind == TypeKind::Value {
+            writeln!(writer, "        {}->ptr = null;", get_input_name(name))?;
+        }
+    }
+
+    if has_return_type {
+        if output.is_custom {
+            if output.is_nullable {
+                writeln!(writer, "        if (Native::isNull($result)) {{")?;
+                if is_fallible {
+                    writeln!(writer, "            Native::throwLastError();")?;
+                }
+                writeln!(writer, "            return null;\n        }}")?;
+            }
+            writeln!(
+                writer,
+                "        return new {}($result);",
+                return_type.trim_start_matches('?')
+            )?;
+        } else if is_string(output) {
+            if output.is_nullable {
+                writeln!(
+                    writer,
+                    "        if (Native::isNull($result)) {{
+            return null;
+        }}"
+                )?;
+            }
+            writeln!(writer, "        return \\FFI::string($result);")?;
+        } else if let Some(enum_name) = &output.enum_name {
+            writeln!(writer, "        return {enum_name}::from($result);")?;
+        } else {
+            writeln!(writer, "        return $result;")?;
+        }
+    }
+
+    writeln!(writer, "    }}")
+}
+
+pub fn write<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+    write!(
+        writer,
+        "{}",
+        r#"<?php
+
+/**
+ * Bindings for livesplit-core using the FFI extension. Every class of
+ * livesplit-core is represented by three classes. The class named after the
+ * class owns the object. It is freed once it gets destructed, but can also be
+ * freed early by calling dispose. The Ref and RefMut classes only borrow the
+ * object and can't outlive its owner.
+ */
+
+namespace LiveSplitCore;
+
+/**
+ * Thrown when a function of livesplit-core fails. The code describes the kind
+ * of error, while the message describes what went wrong.
+ */
+class LiveSplitCoreError extends \Exception
+{
+}
+
+final class Native
+{
+    private static ?\FFI $ffi = null;
+
+    public static function get(): \FFI
+    {
+        if (self::$ffi === null) {
+            $library = match (PHP_OS_FAMILY) {
+                'Windows' => 'livesplit_core.dll',
+                'Darwin' => 'liblivesplit_core.dylib',
+                default => 'liblivesplit_core.so',
+            };
+            self::$ffi = \FFI::cdef(self::DECLARATIONS, __DIR__ . '/' . $library);
+        }
+        return self::$ffi;
+    }
+
+    public static function isNull($value): bool
+    {
+        return $value === null || \FFI::isNull($value);
+    }
+
+    public static function throwLastError(): void
+    {
+        $code = self::get()->LastError_code();
+        if ($code !== 0) {
+            $message = \FFI::string(self::get()->LastError_message());
+            throw new LiveSplitCoreError($message, $code);
+        }
+    }
+
+    private const DECLARATIONS = <<<'C'
+"#
+    )?;
+
+    c::write_declarations(&mut writer, classes)?;
+
+    writeln!(writer, "C;\n}}")?;
+
+    for e in ENUMS {
+        writeln!(writer)?;
+        write_comments(&mut writer, e.comments, "")?;
+        writeln!(writer, "enum {}: int\n{{", e.name)?;
+        for variant in e.variants {
+            write_comments(&mut writer, variant.comments, "    ")?;
+            writeln!(writer, "    case {} = {};", variant.name, variant.value)?;
+        }
+        writeln!(writer, "}}")?;
+    }
+
+    for (class_name, class) in classes {
+        let class_name_ref = format!("{class_name}Ref");
+        let class_name_ref_mut = format!("{class_name}RefMut");
+
+        writeln!(writer)?;
+        write_comments(&mut writer, &class.comments, "")?;
+        write!(
+            writer,
+            "class {class_name_ref}
+{{
+    public $ptr;
+
+    public function __construct($ptr)
+    {{
+        $this->ptr = $ptr;
+    }}
+"
+        )?;
+
+        for function in &class.shared_fns {
+            write_fn(&mut writer, function)?;
+        }
+
+        writeln!(writer, "}}\n")?;
+        write_comments(&mut writer, &class.comments, "")?;
+        writeln!(
+            writer,
+            "class {class_name_ref_mut} extends {class_name_ref}\n{{"
+        )?;
+
+        for function in &class.mut_fns {
+            write_fn(&mut writer, function)?;
+        }
+
+        writeln!(writer, "}}\n")?;
+        write_comments(&mut writer, &class.comments, "")?;
+        write!(
+            writer,
+            "class {class_name} extends {class_name_ref_mut}
+{{
+    public function __destruct()
+    {{
+        $this->dispose();
+    }}
+
+    /**
+     * Frees the object, allowing it to clean up all of its memory. This
+     * happens automatically once the object gets destructed, but can be done
+     * early by calling this. The object can't be used afterwards.
+     */
+    public function dispose(): void
+    {{
+        if ($this->ptr !== null) {{"
+        )?;
+
+        if let Some(function) = class.own_fns.iter().find(|f| f.method == "drop") {
+            write!(
+                writer,
+                "
+            Native::get()->{}($this->ptr);",
+                function.name
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "
+            $this->ptr = null;
+        }}
+    }}"
+        )?;
+
+        for function in class.static_fns.iter().chain(class.own_fns.iter()) {
+            if function.method != "drop" {
+                write_fn(&mut writer, function)?;
+            }
+        }
+
+        writeln!(writer, "}}")?;
+    }
+
+    Ok(())
+}
@@ -1,11 +1,13 @@
-use crate::{Class, Function, Type, TypeKind};
+use crate::{Class, Function, Type, TypeKind, ENUMS};
 use std::{
     collections::BTreeMap,
     io::{Result, Write},
 };
 
 fn get_hl_type(ty: &Type) -> String {
-    if ty.is_custom {
+    if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
         match ty.kind {
             TypeKind::Ref => format!("{}Ref", ty.name),
             TypeKind::RefMut => format!("{}RefMut", ty.name),
@@ -43,6 +45,34 @@ fn get_ll_type(ty: &Type) -> &str {
     }
 }
 
+fn get_stub_type(ty: &Type, is_output: bool) -> String {
+    let name = if ty.is_custom || ty.enum_name.is_some() {
+        get_hl_type(ty)
+    } else if is_buffer(ty) && !is_output {
+        String::from("Buffer")
+    } else {
+        match (ty.kind, ty.name.as_str()) {
+            (TypeKind::Ref, "c_char") | (_, "Json") => "str",
+            (TypeKind::Ref, _) | (TypeKind::RefMut, _) => "int",
+            (_, "f32" | "f64") => "float",
+            (_, "bool") => "bool",
+            (_, "()") => "None",
+            (_, "c_char") => "str",
+            _ => "int",
+        }
+        .to_string()
+    };
+    if ty.is_nullable {
+        format!("Optional[{name}]")
+    } else {
+        name
+    }
+}
+
+fn is_buffer(ty: &Type) -> bool {
+    !ty.is_custom && ty.kind != TypeKind::Value && ty.name == "u8"
+}
+
 fn map_var(var: &str) -> &str {
     if var == "this" {
         "self"
@@ -51,7 +81,7 @@ fn map_var(var: &str) -> &str {
     }
 }
 
-fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<()> {
+fn write_class_comments<W: Write, S: AsRef<str>>(mut writer: W, comments: &[S]) -> Result<()> {
     write!(
         writer,
         r#"
@@ -64,6 +94,7 @@ fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<
             r#"{}
     "#,
             comment
+                .as_ref()
                 .replace("<NULL>", "None")
                 .replace("<TRUE>", "True")
                 .replace("<FALSE>", "False")
@@ -73,7 +104,10 @@ fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<
     writeln!(writer, r#"""""#)
 }
 
-fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
+/// Writes the wrapper of the function. With handles, the objects are acquired
+/// for the duration of the call and the objects passed by value are
+/// unregistered, as their ownership is passed to livesplit-core.
+fn write_fn<W: Write>(mut writer: W, function: &Function, handles: bool) -> Result<()> {
     let is_static = function.is_static();
     let has_return_type = function.has_return_type();
     let return_type = get_hl_type(&function.output);
@@ -108,28 +142,30 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
         "#
     )?;
 
-    write!(writer, r#"""""#)?;
+    if !function.comments.is_empty() {
+        write!(writer, r#"""""#)?;
+
+        for comment in &function.comments {
+            write!(
+                writer,
+                r#"{}
+        "#,
+                comment
+                    .replace("<NULL>", "None")
+                    .replace("<TRUE>", "True")
+                    .replace("<FALSE>", "False")
+            )?;
+        }
 
-    for comment in &function.comments {
         write!(
             writer,
-            r#"{}
-        "#,
-            comment
-                .replace("<NULL>", "None")
-                .replace("<TRUE>", "True")
-                .replace("<FALSE>", "False")
+            r#""""
+        "#
         )?;
     }
 
-    write!(
-        writer,
-        r#""""
-        "#
-    )?;
-
     for (name, typ) in function.inputs.iter() {
-        if typ.is_custom {
+        if typ.is_custom && !handles {
             write!(
                 writer,
                 r#"if {name}.ptr == None:
@@ -140,8 +176,37 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
         }
     }
 
+    let is_fallible = has_return_type && function.output.is_nullable && function.output.is_custom;
+
+    if is_fallible {
+        write!(
+            writer,
+            r#"livesplit_core_native.LastError_clear()
+        "#
+        )?;
+    }
+
+    let acquired: Vec<_> = function
+        .inputs
+        .iter()
+        .filter(|(_, typ)| handles && typ.is_custom && typ.kind != TypeKind::Value)
+        .map(|(name, _)| map_var(name))
+        .collect();
+
+    if !acquired.is_empty() {
+        write!(writer, "with _acquire({}) as (", acquired.join(", "))?;
+        for name in &acquired {
+            write!(writer, "{name}_ptr, ")?;
+        }
+        write!(
+            writer,
+            r#"):
+            "#
+        )?;
+    }
+
     if has_return_type {
-        if function.output.is_custom {
+        if function.output.is_custom || function.output.enum_name.is_some() {
             write!(writer, r#"result = {return_type}("#)?;
         } else {
             write!(writer, "result = ")?;
@@ -157,12 +222,18 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
         write!(
             writer,
             "{}",
-            if name == "this" {
+            if handles && typ.is_custom && typ.kind == TypeKind::Value {
+                format!("_take({})", map_var(name))
+            } else if handles && typ.is_custom {
+                format!("{}_ptr", map_var(name))
+            } else if name == "this" {
                 "self.ptr".to_string()
             } else if typ.is_custom {
                 format!("{name}.ptr")
-            } else if typ.name == "c_char" {
+            } else if typ.name == "c_char" || typ.name == "Json" {
                 format!("{name}.encode()")
+            } else if is_buffer(typ) {
+                format!("_buffer({name})")
             } else {
                 name.to_string()
             }
@@ -172,7 +243,15 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
     write!(writer, ")")?;
 
     if has_return_type {
-        if function.output.is_custom {
+        if handles && function.output.is_custom && function.output.kind != TypeKind::Value {
+            // References borrowed from an object can only be used as long as
+            // the handle of that object is valid.
+            if is_static {
+                write!(writer, r#")"#)?;
+            } else {
+                write!(writer, r#", self.handle)"#)?;
+            }
+        } else if function.output.is_custom || function.output.enum_name.is_some() {
             write!(writer, r#")"#)?;
         } else if function.output.name == "c_char" {
             write!(writer, r#".decode()"#)?;
@@ -180,7 +259,7 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
     }
 
     for (name, typ) in function.inputs.iter() {
-        if typ.is_custom && typ.kind == TypeKind::Value {
+        if typ.is_custom && typ.kind == TypeKind::Value && !handles {
             write!(
                 writer,
                 r#"
@@ -191,11 +270,19 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
     }
 
     if has_return_type {
-        if function.output.is_nullable && function.output.is_custom {
+        if is_fallible {
             write!(
                 writer,
                 r#"
-        if result.ptr == None:
+        if result.{} == None:"#,
+                if handles { "handle" } else { "ptr" }
+            )?;
+            write!(
+                writer,
+                r#"
+            code = livesplit_core_native.LastError_code()
+            if code != 0:
+                raise LiveSplitCoreError(code, livesplit_core_native.LastError_message().decode())
             return None"#
             )?;
         }
@@ -211,7 +298,15 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
     Ok(())
 }
 
-pub fn write<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+/// Writes the Python bindings. With handles, the objects are referred to by
+/// handles instead of pointers, so using an object after it got dropped raises
+/// an error instead of accessing freed memory. References borrowed from an
+/// object hold on to the handle of that object.
+pub fn write<W: Write>(
+    mut writer: W,
+    classes: &BTreeMap<String, Class>,
+    handles: bool,
+) -> Result<()> {
     write!(
         writer,
         "{}",
@@ -219,14 +314,89 @@ pub fn write<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Resu
 # coding: utf-8
 
 import sys, ctypes
+from enum import IntEnum
 from ctypes import c_char_p, c_void_p, c_int8, c_int16, c_int32, c_int64, c_uint8, c_uint16, c_uint32, c_uint64, c_size_t, c_ssize_t, c_float, c_double, c_bool, c_char, c_byte
 
 prefix = {'win32': ''}.get(sys.platform, './lib')
 extension = {'darwin': '.dylib', 'win32': '.dll'}.get(sys.platform, '.so')
 livesplit_core_native = ctypes.cdll.LoadLibrary(prefix + "livesplit_core" + extension)
+
+def _buffer(data):
+    """Converts any object implementing the buffer protocol, like bytes,
+    bytearray or memoryview, into a ctypes array that shares its memory.
+    Read-only buffers get copied, as ctypes can only share writable memory.
+    """
+    if data is None or isinstance(data, int):
+        return data
+    view = memoryview(data).cast('B')
+    array_type = c_char * len(view)
+    if view.readonly:
+        return array_type.from_buffer_copy(view)
+    return array_type.from_buffer(view)
+
+class LiveSplitCoreError(Exception):
+    """Raised when a function of livesplit-core fails. The code describes
+    the kind of error, while the message describes what went wrong.
+    """
+    def __init__(self, code, message):
+        super().__init__(message)
+        self.code = code
+        self.message = message
 "#
     )?;
 
+    if handles {
+        write!(
+            writer,
+            "{}",
+            r#"
+from contextlib import contextmanager
+
+def _last_error():
+    return LiveSplitCoreError(livesplit_core_native.LastError_code(), livesplit_core_native.LastError_message().decode())
+
+@contextmanager
+def _acquire(*objects):
+    """Acquires the handles of the objects for the duration of a call and
+    yields the addresses to pass to livesplit-core. References that are not
+    borrowed from an object with a handle are passed as they are.
+    """
+    acquired = []
+    try:
+        addresses = []
+        for obj in objects:
+            if obj == None:
+                addresses.append(None)
+            elif obj.handle == None:
+                if obj.ptr == None:
+                    raise Exception("object is disposed")
+                addresses.append(obj.ptr)
+            else:
+                address = livesplit_core_native.Handles_acquire(obj.handle)
+                if address == 0:
+                    raise _last_error()
+                acquired.append(obj.handle)
+                addresses.append(address if obj.ptr == None else obj.ptr)
+        yield addresses
+    finally:
+        for handle in acquired:
+            livesplit_core_native.Handles_release(handle)
+
+def _take(obj):
+    """Unregisters the handle of the object, as its ownership is passed to
+    livesplit-core, and returns the address of the object.
+    """
+    if obj.handle == None:
+        raise Exception("object is disposed")
+    address = livesplit_core_native.Handles_unregister(obj.handle)
+    obj.handle = None
+    if address == 0:
+        raise _last_error()
+    return address
+"#
+        )?;
+    }
+
     for class in classes.values() {
         for function in class
             .static_fns
@@ -258,6 +428,18 @@ livesplit_core_native.{}.restype = {}"#,
 
     writeln!(writer)?;
 
+    for e in ENUMS {
+        write!(writer, "\nclass {}(IntEnum):", e.name)?;
+        write_class_comments(&mut writer, e.comments)?;
+        for variant in e.variants {
+            for comment in variant.comments {
+                write!(writer, "\n    # {comment}")?;
+            }
+            write!(writer, "\n    {} = {}", variant.name, variant.value)?;
+        }
+        writeln!(writer, "\n")?;
+    }
+
     for (class_name, class) in classes {
         let class_name_ref = format!("{class_name}Ref");
         let class_name_ref_mut = format!("{class_name}RefMut");
@@ -271,14 +453,14 @@ class {class_name_ref}:"#
         write_class_comments(&mut writer, &class.comments)?;
 
         for function in &class.shared_fns {
-            write_fn(&mut writer, function)?;
+            write_fn(&mut writer, function, handles)?;
         }
 
+        write_ref_init(&mut writer, handles)?;
+
         write!(
             writer,
             r#"
-    def __init__(self, ptr):
-        self.ptr = ptr
 
 class {class_name_ref_mut}({class_name_ref}):"#
         )?;
@@ -286,40 +468,71 @@ class {class_name_ref_mut}({class_name_ref}):"#
         write_class_comments(&mut writer, &class.comments)?;
 
         for function in &class.mut_fns {
-            write_fn(&mut writer, function)?;
+            write_fn(&mut writer, function, handles)?;
         }
 
+        write_ref_init(&mut writer, handles)?;
+
         write!(
             writer,
             r#"
-    def __init__(self, ptr):
-        self.ptr = ptr
 
 class {class_name}({class_name_ref_mut}):"#
         )?;
 
         write_class_comments(&mut writer, &class.comments)?;
 
-        write!(
-            writer,
-            r#"
+        let drop_fn = class.own_fns.iter().find(|f| f.method == "drop");
+
+        if handles {
+            write!(
+                writer,
+                r#"
     def drop(self):
-        if self.ptr != None:"#,
-        )?;
+        if self.handle != None:"#,
+            )?;
 
-        if let Some(function) = class.own_fns.iter().find(|f| f.method == "drop") {
+            if let Some(function) = drop_fn {
+                write!(
+                    writer,
+                    r#"
+            livesplit_core_native.{}(_take(self))"#,
+                    function.name
+                )?;
+            } else {
+                write!(
+                    writer,
+                    r#"
+            _take(self)"#
+                )?;
+            }
+        } else {
             write!(
                 writer,
                 r#"
+    def drop(self):
+        if self.ptr != None:"#,
+            )?;
+
+            if let Some(function) = drop_fn {
+                write!(
+                    writer,
+                    r#"
             livesplit_core_native.{}(self.ptr)"#,
-                function.name
+                    function.name
+                )?;
+            }
+
+            write!(
+                writer,
+                r#"
+            self.ptr = None"#
             )?;
         }
 
         write!(
             writer,
             r#"
-            self.ptr = None
 
     def __del__(self):
         self.drop()
@@ -334,7 +547,7 @@ class {class_name}({class_name_ref_mut}):"#
 
         for function in class.static_fns.iter().chain(class.own_fns.iter()) {
             if function.method != "drop" {
-                write_fn(&mut writer, function)?;
+                write_fn(&mut writer, function, handles)?;
             }
         }
 
@@ -345,23 +558,172 @@ class {class_name}({class_name_ref_mut}):"#
     @staticmethod
     def parse_file(file, load_files_path):
         data = file.read()
-        if sys.version_info[0] > 2:
-            if isinstance(data, str):
-                raise TypeError("File must be opened in binary mode!")
-        bytes = bytearray(data)
-        bufferType = c_byte * len(bytes)
-        buffer = bufferType(*bytes)
-        return Run.parse(buffer, len(bytes), load_files_path)"#
+        if isinstance(data, str):
+            raise TypeError("File must be opened in binary mode!")
+        return Run.parse(data, len(data), load_files_path)"#
+            )?;
+        }
+
+        if handles {
+            write!(
+                writer,
+                r#"
+    def __init__(self, ptr):
+        self.ptr = None
+        self.handle = None if ptr == None else livesplit_core_native.Handles_register(ptr)
+"#
+            )?;
+        } else {
+            write!(
+                writer,
+                r#"
+    def __init__(self, ptr):
+        self.ptr = ptr
+"#
             )?;
         }
+    }
+
+    Ok(())
+}
 
+/// Writes the constructor of the classes that borrow the object. With handles,
+/// they also hold on to the handle of the object they got borrowed from.
+fn write_ref_init<W: Write>(mut writer: W, handles: bool) -> Result<()> {
+    if handles {
         write!(
             writer,
             r#"
-    def __init__(self, ptr):
+    def __init__(self, ptr, handle=None):
         self.ptr = ptr
+        self.handle = handle"#
+        )
+    } else {
+        write!(
+            writer,
+            r#"
+    def __init__(self, ptr):
+        self.ptr = ptr"#
+        )
+    }
+}
+
+fn write_stub_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
+    let is_static = function.is_static();
+
+    if is_static {
+        write!(writer, "\n    @staticmethod")?;
+    }
+    write!(writer, "\n    def {}(", function.method)?;
+
+    for (i, (name, typ)) in function.inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        if name == "this" {
+            write!(writer, "self")?;
+        } else {
+            write!(writer, "{}: {}", name, get_stub_type(typ, false))?;
+        }
+    }
+
+    write!(
+        writer,
+        ") -> {}: ...",
+        get_stub_type(&function.output, true)
+    )
+}
+
+/// Writes the type stubs for the Python bindings, so that type checkers and
+/// IDEs know about the types of all the functions.
+pub fn write_stubs<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+    write!(
+        writer,
+        "{}",
+        r#"import ctypes
+from enum import IntEnum
+from types import TracebackType
+from typing import BinaryIO, Optional, Type
+
+from typing_extensions import Buffer
+
+livesplit_core_native: ctypes.CDLL
+
+class LiveSplitCoreError(Exception):
+    code: int
+    message: str
+    def __init__(self, code: int, message: str) -> None: ...
 "#
+    )?;
+
+    for e in ENUMS {
+        write!(writer, "\nclass {}(IntEnum):", e.name)?;
+        for variant in e.variants {
+            write!(writer, "\n    {} = {}", variant.name, variant.value)?;
+        }
+        writeln!(writer)?;
+    }
+
+    for (class_name, class) in classes {
+        write!(
+            writer,
+            r#"
+class {class_name}Ref:
+    ptr: Optional[int]
+    def __init__(self, ptr: Optional[int]) -> None: ..."#
         )?;
+
+        for function in &class.shared_fns {
+            write_stub_fn(&mut writer, function)?;
+        }
+
+        write!(
+            writer,
+            r#"
+
+class {class_name}RefMut({class_name}Ref):"#
+        )?;
+
+        for function in &class.mut_fns {
+            write_stub_fn(&mut writer, function)?;
+        }
+
+        if class.mut_fns.is_empty() {
+            write!(writer, " ...")?;
+        }
+
+        write!(
+            writer,
+            r#"
+
+class {class_name}({class_name}RefMut):
+    def drop(self) -> None: ...
+    def __del__(self) -> None: ...
+    def __enter__(self) -> {class_name}: ...
+    def __exit__(
+        self,
+        exc_type: Optional[Type[BaseException]],
+        exc_val: Optional[BaseException],
+        exc_tb: Optional[TracebackType],
+    ) -> None: ..."#
+        )?;
+
+        for function in class.static_fns.iter().chain(class.own_fns.iter()) {
+            if function.method != "drop" {
+                write_stub_fn(&mut writer, function)?;
+            }
+        }
+
+        if class_name == "Run" {
+            write!(
+                writer,
+                r#"
+    @staticmethod
+    def parse_file(file: BinaryIO, load_files_path: str) -> ParseRunResult: ..."#
+            )?;
+        }
+
+        writeln!(writer)?;
     }
 
     Ok(())
@@ -1,4 +1,5 @@
-use crate::{Class, Function, Opt, Type, TypeKind};
+use crate::{Class, Function, Opt, Type, TypeKind, ENUMS};
+use heck::ToShoutySnakeCase;
 use std::{
     collections::BTreeMap,
     io::{Result, Write},
@@ -75,7 +76,7 @@ fn ptr_of(var: &str) -> String {
     }
 }
 
-fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<()> {
+fn write_class_comments<W: Write, S: AsRef<str>>(mut writer: W, comments: &[S]) -> Result<()> {
     writeln!(writer)?;
 
     for comment in comments {
@@ -84,6 +85,7 @@ fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<
             r#"
     # {}"#,
             comment
+                .as_ref()
                 .replace("<NULL>", "nil")
                 .replace("<TRUE>", "true")
                 .replace("<FALSE>", "false")
@@ -303,6 +305,23 @@ module LiveSplitCore
     end"#
     )?;
 
+    for e in ENUMS {
+        write_class_comments(&mut writer, e.comments)?;
+        write!(writer, "\n    module {}", e.name)?;
+        for variant in e.variants {
+            for comment in variant.comments {
+                write!(writer, "\n        # {comment}")?;
+            }
+            write!(
+                writer,
+                "\n        {} = {}",
+                variant.name.to_shouty_snake_case(),
+                variant.value
+            )?;
+        }
+        write!(writer, "\n    end")?;
+    }
+
     for (class_name, class) in classes {
         let class_name = class_name.to_string();
         let class_name_ref = format!("{class_name}Ref");
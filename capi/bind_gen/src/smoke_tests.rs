@@ -0,0 +1,201 @@
+use std::io::{Result, Write};
+
+static NODE: &str = r#""use strict";
+const assert = require("assert");
+const { Run, Segment, Timer } = require("../livesplit_core");
+
+function createRun() {
+    const run = Run.new();
+    run.setGameName("Game");
+    run.setCategoryName("Any%");
+    run.pushSegment(Segment.new("Split 1"));
+    run.pushSegment(Segment.new("Split 2"));
+    return run;
+}
+
+describe("livesplit-core", function () {
+    it("creates a run with segments", function () {
+        createRun().with(function (run) {
+            assert.strictEqual(run.len(), 2);
+            assert.strictEqual(run.gameName(), "Game");
+            assert.strictEqual(run.segment(1).name(), "Split 2");
+        });
+    });
+
+    it("starts, splits and resets the timer", function () {
+        Timer.new(createRun()).with(function (timer) {
+            assert.strictEqual(timer.currentSplitIndex(), -1);
+            timer.start();
+            assert.strictEqual(timer.currentSplitIndex(), 0);
+            timer.split();
+            assert.strictEqual(timer.currentSplitIndex(), 1);
+            timer.reset(true);
+            assert.strictEqual(timer.currentSplitIndex(), -1);
+        });
+    });
+
+    it("round trips a run through the LiveSplit format", function () {
+        const lss = createRun().with(function (run) {
+            return run.saveAsLss();
+        });
+        const result = Run.parseString(lss, "");
+        assert.ok(result.parsedSuccessfully());
+        result.unwrap().with(function (run) {
+            assert.strictEqual(run.len(), 2);
+            assert.strictEqual(run.categoryName(), "Any%");
+            assert.strictEqual(run.segment(0).name(), "Split 1");
+        });
+    });
+
+    it("round trips a run asynchronously", async function () {
+        const run = createRun();
+        const lss = await run.saveAsLssAsync();
+        run.dispose();
+        const result = await Run.parseAsync(Buffer.from(lss), "");
+        assert.ok(result.parsedSuccessfully());
+        result.unwrap().with(function (run) {
+            assert.strictEqual(run.len(), 2);
+            assert.strictEqual(run.gameName(), "Game");
+        });
+    });
+});
+"#;
+
+static PYTHON: &str = r#"import pytest
+from livesplit_core import LiveSplitCoreError, Run, Segment, Timer
+
+
+def create_run():
+    run = Run.new()
+    run.set_game_name("Game")
+    run.set_category_name("Any%")
+    run.push_segment(Segment.new("Split 1"))
+    run.push_segment(Segment.new("Split 2"))
+    return run
+
+
+def test_run_with_segments():
+    with create_run() as run:
+        assert run.len() == 2
+        assert run.game_name() == "Game"
+        assert run.segment(1).name() == "Split 2"
+
+
+def test_timer_start_split_reset():
+    with Timer.new(create_run()) as timer:
+        assert timer.current_split_index() == -1
+        timer.start()
+        assert timer.current_split_index() == 0
+        timer.split()
+        assert timer.current_split_index() == 1
+        timer.reset(True)
+        assert timer.current_split_index() == -1
+
+
+def test_lss_round_trip():
+    with create_run() as run:
+        lss = run.save_as_lss().encode()
+    result = Run.parse(lss, len(lss), "")
+    assert result.parsed_successfully()
+    with result.unwrap() as run:
+        assert run.len() == 2
+        assert run.category_name() == "Any%"
+        assert run.segment(0).name() == "Split 1"
+"#;
+
+static PYTHON_HANDLES: &str = r#"
+
+def test_stale_handle():
+    timer = Timer.new(create_run())
+    run = timer.get_run()
+    assert run.game_name() == "Game"
+    timer.drop()
+    with pytest.raises(LiveSplitCoreError) as error:
+        run.game_name()
+    assert error.value.code == 6
+"#;
+
+static CSHARP: &str = r#"using System.Text;
+using LiveSplitCore;
+using Xunit;
+
+public class LiveSplitCoreTests
+{
+    private static Run CreateRun()
+    {
+        var run = new Run();
+        run.SetGameName("Game");
+        run.SetCategoryName("Any%");
+        run.PushSegment(new Segment("Split 1"));
+        run.PushSegment(new Segment("Split 2"));
+        return run;
+    }
+
+    [Fact]
+    public void CreatesRunWithSegments()
+    {
+        using (var run = CreateRun())
+        {
+            Assert.Equal(2UL, run.Len());
+            Assert.Equal("Game", run.GameName());
+            Assert.Equal("Split 2", run.Segment(1).Name());
+        }
+    }
+
+    [Fact]
+    public void StartsSplitsAndResetsTimer()
+    {
+        using (var timer = Timer.New(CreateRun()))
+        {
+            Assert.NotNull(timer);
+            Assert.Equal(-1L, timer!.CurrentSplitIndex());
+            timer.Start();
+            Assert.Equal(0L, timer.CurrentSplitIndex());
+            timer.Split();
+            Assert.Equal(1L, timer.CurrentSplitIndex());
+            timer.Reset(true);
+            Assert.Equal(-1L, timer.CurrentSplitIndex());
+        }
+    }
+
+    [Fact]
+    public void RoundTripsRunThroughLss()
+    {
+        string lss;
+        using (var run = CreateRun())
+        {
+            lss = run.SaveAsLss();
+        }
+        using (var result = Run.Parse(Encoding.UTF8.GetBytes(lss), ""))
+        {
+            Assert.True(result.ParsedSuccessfully());
+            using (var run = result.Unwrap())
+            {
+                Assert.Equal(2UL, run.Len());
+                Assert.Equal("Any%", run.CategoryName());
+                Assert.Equal("Split 1", run.Segment(0).Name());
+            }
+        }
+    }
+}
+"#;
+
+/// Writes a mocha test suite for the Node bindings.
+pub fn write_node<W: Write>(mut writer: W) -> Result<()> {
+    writer.write_all(NODE.as_bytes())
+}
+
+/// Writes a pytest test suite for the Python bindings. The bindings with
+/// handles are additionally tested for turning a use after free into an error.
+pub fn write_python<W: Write>(mut writer: W, handles: bool) -> Result<()> {
+    writer.write_all(PYTHON.as_bytes())?;
+    if handles {
+        writer.write_all(PYTHON_HANDLES.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Writes an xUnit test suite for the C# bindings.
+pub fn write_csharp<W: Write>(mut writer: W) -> Result<()> {
+    writer.write_all(CSHARP.as_bytes())
+}
@@ -1,4 +1,4 @@
-use crate::{Class, Function, Type, TypeKind};
+use crate::{Class, Function, Type, TypeKind, ENUMS};
 use heck::ToLowerCamelCase;
 use std::{
     borrow::Cow,
@@ -7,8 +7,10 @@ use std::{
     io::{Result, Write},
 };
 
-fn get_hl_type(ty: &Type) -> Cow<str> {
-    if ty.is_custom {
+fn get_hl_type(ty: &Type) -> Cow<'_, str> {
+    if let Some(enum_name) = &ty.enum_name {
+        Cow::Borrowed(enum_name)
+    } else if ty.is_custom {
         match ty.kind {
             TypeKind::Ref => Cow::Owned(format!("{}Ref", ty.name)),
             TypeKind::RefMut => Cow::Owned(format!("{}RefMut", ty.name)),
@@ -46,7 +48,7 @@ fn get_ll_type(ty: &Type) -> &str {
     }
 }
 
-fn get_input_name(name: &str) -> Cow<str> {
+fn get_input_name(name: &str) -> Cow<'_, str> {
     if name == "this" {
         Cow::Borrowed("self")
     } else {
@@ -181,6 +183,8 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
             write!(writer, "{}", name.to_lower_camel_case())?;
             if ty.is_custom {
                 write!(writer, ".ptr")?;
+            } else if ty.enum_name.is_some() {
+                write!(writer, ".rawValue")?;
             }
         }
     }
@@ -242,6 +246,12 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
                 "
         return {return_type}(ptr: result)",
             )?;
+        } else if function.output.enum_name.is_some() {
+            write!(
+                writer,
+                "
+        return {return_type}(rawValue: result)!",
+            )?;
         } else {
             write!(
                 writer,
@@ -258,8 +268,95 @@ fn write_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
     )
 }
 
+fn write_decoding_fn<W: Write>(mut writer: W, function: &Function) -> Result<()> {
+    let is_static = function.is_static();
+    let json_fn_name = function.method.to_lower_camel_case();
+    let name = match function.method.strip_suffix("as_json") {
+        Some("") => String::from("decoded"),
+        Some(prefix) => format!("{prefix}decoded").to_lower_camel_case(),
+        None => return Ok(()),
+    };
+
+    write!(
+        writer,
+        "
+    /// Decodes the JSON returned by `{json_fn_name}` into the `Decodable` type
+    /// provided. Throws if the JSON doesn't match the type.
+    public{} func {name}<T: Decodable>(as type: T.Type",
+        if is_static { " static" } else { "" },
+    )?;
+
+    for (name, ty) in function.inputs.iter().skip(usize::from(!is_static)) {
+        write!(
+            writer,
+            ", _ {}: {}",
+            name.to_lower_camel_case(),
+            get_hl_type(ty)
+        )?;
+    }
+
+    write!(
+        writer,
+        ") throws -> T {{
+        let json = {}.{json_fn_name}(",
+        if is_static { "Self" } else { "self" },
+    )?;
+
+    for (i, (name, _)) in function
+        .inputs
+        .iter()
+        .skip(usize::from(!is_static))
+        .enumerate()
+    {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        write!(writer, "{}", name.to_lower_camel_case())?;
+    }
+
+    write!(
+        writer,
+        ")
+        return try JSONDecoder().decode(type, from: Data(json.utf8))
+    }}"
+    )
+}
+
+fn write_fns<'a, W: Write>(
+    mut writer: W,
+    functions: impl IntoIterator<Item = &'a Function>,
+) -> Result<()> {
+    for function in functions {
+        write_fn(&mut writer, function)?;
+        if function.output.name == "Json" {
+            write_decoding_fn(&mut writer, function)?;
+        }
+    }
+    Ok(())
+}
+
 pub fn write<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
     writeln!(writer, "import CLiveSplitCore")?;
+    writeln!(writer, "import Foundation")?;
+
+    for e in ENUMS {
+        for comment in e.comments {
+            write!(writer, "\n/// {comment}")?;
+        }
+        write!(writer, "\npublic enum {}: UInt8 {{", e.name)?;
+        for variant in e.variants {
+            for comment in variant.comments {
+                write!(writer, "\n    /// {comment}")?;
+            }
+            write!(
+                writer,
+                "\n    case {} = {}",
+                variant.name.to_lower_camel_case(),
+                variant.value
+            )?;
+        }
+        writeln!(writer, "\n}}")?;
+    }
 
     for (class_name, class) in classes {
         let class_name_ref = format!("{class_name}Ref");
@@ -277,9 +374,7 @@ public class {class_name_ref} {{
     }}"
         )?;
 
-        for function in &class.shared_fns {
-            write_fn(&mut writer, function)?;
-        }
+        write_fns(&mut writer, &class.shared_fns)?;
 
         if class_name == "SharedTimer" {
             write!(
@@ -306,9 +401,7 @@ public class {class_name_ref} {{
 public class {class_name_ref_mut}: {class_name_ref} {{"
         )?;
 
-        for function in &class.mut_fns {
-            write_fn(&mut writer, function)?;
-        }
+        write_fns(&mut writer, &class.mut_fns)?;
 
         write!(
             writer,
@@ -343,11 +436,14 @@ public class {class_name} : {class_name_ref_mut} {{
     }}"
         )?;
 
-        for function in class.static_fns.iter().chain(class.own_fns.iter()) {
-            if function.method != "drop" {
-                write_fn(&mut writer, function)?;
-            }
-        }
+        write_fns(
+            &mut writer,
+            class
+                .static_fns
+                .iter()
+                .chain(class.own_fns.iter())
+                .filter(|f| f.method != "drop"),
+        )?;
 
         writeln!(
             writer,
@@ -0,0 +1,420 @@
+//! Snapshot tests for the generated bindings. The bindings are generated for a
+//! small fixture of the C API that covers every kind of type the generators
+//! need to handle, so the snapshots only change when the generators do. Run
+//! the tests with `UPDATE_SNAPSHOTS=1` to update the snapshots after an
+//! intentional change.
+
+use super::*;
+use heck::ToSnakeCase;
+use std::{collections::BTreeSet, env, io::Write, path::Path};
+
+static FIXTURE: &[&str] = &[
+    r#"
+//! A Run stores the split times for a specific game and category of a runner.
+
+/// type
+pub type OwnedRun = Box<Run>;
+
+/// Creates a new Run object with no segments.
+#[no_mangle]
+pub extern "C" fn Run_new() -> OwnedRun {}
+
+/// drop
+#[no_mangle]
+pub extern "C" fn Run_drop(this: OwnedRun) {}
+
+/// Attempts to parse a splits file from an array by invoking the corresponding
+/// parser for the file format detected.
+#[no_mangle]
+pub unsafe extern "C" fn Run_parse(
+    data: *const u8,
+    length: usize,
+    load_files_path: *const c_char,
+) -> OwnedParseRunResult {}
+
+/// The UTF-16 variant of Run_parse. See get_buf_as_utf16 for how the strings
+/// are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_parse_u16(
+    data: *const u8,
+    length: usize,
+    load_files_path: *const u16,
+    load_files_path_len: usize,
+) -> OwnedParseRunResult {}
+
+/// Clones the Run object.
+#[no_mangle]
+pub extern "C" fn Run_clone(this: &Run) -> OwnedRun {}
+
+/// Accesses the name of the game this Run is for.
+#[no_mangle]
+pub extern "C" fn Run_game_name(this: &Run) -> *const c_char {}
+
+/// The UTF-16 variant of Run_game_name. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_game_name_u16(this: &Run, buf: *mut u16, buf_len: usize) -> usize {}
+
+/// Sets the name of the game this Run is for.
+#[no_mangle]
+pub unsafe extern "C" fn Run_set_game_name(this: &mut Run, game: *const c_char) {}
+
+/// The UTF-16 variant of Run_set_game_name. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_set_game_name_u16(this: &mut Run, game: *const u16, game_len: usize) {}
+
+/// Accesses the length of the game's icon data.
+#[no_mangle]
+pub extern "C" fn Run_game_icon_len(this: &Run) -> usize {}
+
+/// Saves the Run as a LiveSplit splits file.
+#[no_mangle]
+pub extern "C" fn Run_save_as_lss(this: &Run) -> *const c_char {}
+"#,
+    r#"
+//! A run parsed by the Composite Parser. This contains the Run itself and
+//! information about which parser parsed it.
+
+/// drop
+#[no_mangle]
+pub extern "C" fn ParseRunResult_drop(this: OwnedParseRunResult) {}
+
+/// Returns <TRUE> if the Run got parsed successfully. <FALSE> is returned otherwise.
+#[no_mangle]
+pub extern "C" fn ParseRunResult_parsed_successfully(this: &ParseRunResult) -> bool {}
+
+/// Moves the actual Run object out of the Result. You may not call this if the
+/// Run wasn't parsed successfully.
+#[no_mangle]
+pub extern "C" fn ParseRunResult_unwrap(this: OwnedParseRunResult) -> OwnedRun {}
+"#,
+    r#"
+//! A Timer provides all the capabilities necessary for doing speedrun attempts.
+
+/// Creates a new Timer based on a Run object storing all the information
+/// about the splits. The Run object needs to have at least one segment, so
+/// that the Timer can store the final time. If a Run object with no segments
+/// is provided, the Timer creation fails and <NULL> is returned.
+#[no_mangle]
+pub extern "C" fn Timer_new(run: OwnedRun) -> NullableOwnedTimer {}
+
+/// drop
+#[no_mangle]
+pub extern "C" fn Timer_drop(this: OwnedTimer) {}
+
+/// Takes out the Run from the Timer and resets the current attempt if there
+/// is one in progress.
+#[no_mangle]
+pub extern "C" fn Timer_into_run(this: OwnedTimer, update_splits: bool) -> OwnedRun {}
+
+/// Splits the current segment.
+#[no_mangle]
+pub extern "C" fn Timer_split(this: &mut Timer) -> i32 {}
+
+/// Returns the currently selected Timing Method.
+#[no_mangle]
+pub extern "C" fn Timer_current_timing_method(this: &Timer) -> TimingMethod {}
+
+/// Sets the current Timing Method to the Timing Method provided.
+#[no_mangle]
+pub extern "C" fn Timer_set_current_timing_method(this: &mut Timer, method: TimingMethod) {}
+
+/// Returns the current Timer Phase.
+#[no_mangle]
+pub extern "C" fn Timer_current_phase(this: &Timer) -> TimerPhase {}
+
+/// Accesses the Run in use by the Timer.
+#[no_mangle]
+pub extern "C" fn Timer_get_run(this: &Timer) -> &Run {}
+
+/// Sets the game time to the time provided in seconds.
+#[no_mangle]
+pub extern "C" fn Timer_set_game_time_seconds(this: &mut Timer, seconds: f64) {}
+
+/// Registers a listener that gets called with every event the Timer emits.
+#[no_mangle]
+pub extern "C" fn Timer_add_event_listener(
+    this: &mut Timer,
+    listener: EventListener,
+    data: usize,
+) -> u64 {}
+
+/// Removes the event listener with the ID provided.
+#[no_mangle]
+pub extern "C" fn Timer_remove_event_listener(this: &mut Timer, id: u64) -> bool {}
+"#,
+    r#"
+//! Represents the state that a Layout is in at the moment.
+
+/// type
+pub type OwnedLayoutState = Box<LayoutState>;
+
+/// Creates a new empty Layout State. This is usually only needed if you want
+/// to retain the Layout State between calls.
+#[no_mangle]
+pub extern "C" fn LayoutState_new() -> OwnedLayoutState {}
+
+/// drop
+#[no_mangle]
+pub extern "C" fn LayoutState_drop(this: OwnedLayoutState) {}
+
+/// Encodes the Layout State as JSON.
+#[no_mangle]
+pub extern "C" fn LayoutState_as_json(this: &LayoutState) -> Json {}
+
+/// Gets the name of the component at the index provided, or <NULL> if there
+/// is no such component.
+#[no_mangle]
+pub extern "C" fn LayoutState_component_name(this: &LayoutState, index: usize) -> *const Nullablec_char {}
+"#,
+];
+
+/// Returns the classes of the fixture, and the classes without the functions
+/// that only the C, C++ and C# bindings make use of.
+fn classes() -> [BTreeMap<String, Class>; 2] {
+    let mut functions = Vec::new();
+    for module in FIXTURE {
+        parse_module(module, &mut functions);
+    }
+    let classes = fns_to_classes(functions);
+    let portable = classes
+        .iter()
+        .map(|(name, class)| (name.clone(), class.portable()))
+        .collect();
+    [classes, portable]
+}
+
+/// Generates the bindings into a fresh directory and compares all the files
+/// in there with the snapshot of the name provided.
+fn check_dir(name: &str, generate: impl FnOnce(&Path) -> Result<()>) {
+    let dir = env::temp_dir().join(format!("bind_gen_{}_{}", name, std::process::id()));
+    drop(remove_dir_all(&dir));
+    create_dir_all(&dir).unwrap();
+    generate(&dir).unwrap();
+
+    let mut files = Vec::new();
+    collect_files(&dir, &mut files);
+    files.sort();
+
+    let mut output = String::new();
+    for file in files {
+        let relative = file.strip_prefix(&dir).unwrap();
+        let contents = fs::read_to_string(&file).unwrap();
+        output.push_str(&format!(
+            "==> {} <==\n{}\n",
+            relative.to_str().unwrap().replace('\\', "/"),
+            contents,
+        ));
+    }
+    remove_dir_all(&dir).unwrap();
+
+    check(name, output);
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Generates the bindings into a single file and compares it with the
+/// snapshot of the name provided.
+fn check_file(name: &str, generate: impl FnOnce(&mut Vec<u8>) -> Result<()>) {
+    let mut output = Vec::new();
+    generate(&mut output).unwrap();
+    output.flush().unwrap();
+    check(name, String::from_utf8(output).unwrap());
+}
+
+fn check(name: &str, output: String) {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("snapshots");
+    path.push(format!("{}.snap", name));
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, output).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "The snapshot {} is missing. Run the tests with UPDATE_SNAPSHOTS=1 to create it.",
+            path.display(),
+        )
+    });
+    if expected != output {
+        panic!(
+            "The generated bindings don't match the snapshot {}. Run the tests with \
+            UPDATE_SNAPSHOTS=1 to update it if the change is intentional.\n\n{}",
+            path.display(),
+            output,
+        );
+    }
+}
+
+#[test]
+fn go() {
+    let [_, classes] = classes();
+    check_dir("go", |path| go::write(path, &classes));
+}
+
+#[test]
+fn swift() {
+    let [_, classes] = classes();
+    check_dir("swift", |path| swift::write(path, &classes));
+}
+
+#[test]
+fn kotlin() {
+    let [_, classes] = classes();
+    check_dir("kotlin", |path| kotlin::write(path, &classes));
+}
+
+#[test]
+fn dart() {
+    let [_, classes] = classes();
+    check_dir("dart", |path| dart::write(path, &classes));
+}
+
+#[test]
+fn lua() {
+    let [_, classes] = classes();
+    check_file("lua", |writer| lua::write(writer, &classes));
+}
+
+#[test]
+fn zig() {
+    let [_, classes] = classes();
+    check_file("zig", |writer| zig::write(writer, &classes));
+}
+
+#[test]
+fn php() {
+    let [_, classes] = classes();
+    check_file("php", |writer| php::write(writer, &classes));
+}
+
+#[test]
+fn cpp() {
+    let [classes, _] = classes();
+    check_file("cpp", |writer| cpp::write(writer, &classes));
+}
+
+#[test]
+fn deno() {
+    let [_, classes] = classes();
+    check_file("deno", |writer| deno::write(writer, &classes));
+}
+
+#[test]
+fn napi() {
+    let [_, classes] = classes();
+    check_dir("napi", |path| napi::write(path, &classes));
+}
+
+#[test]
+fn python() {
+    let [_, classes] = classes();
+    check_file("python", |writer| python::write(writer, &classes, false));
+    check_file("python_handles", |writer| {
+        python::write(writer, &classes, true)
+    });
+    check_file("python_stubs", |writer| {
+        python::write_stubs(writer, &classes)
+    });
+}
+
+#[test]
+fn java() {
+    let [_, classes] = classes();
+    check_dir("java", |path| java::write(path, &classes));
+}
+
+#[test]
+fn csharp() {
+    let [classes, _] = classes();
+    check_file("csharp", |writer| csharp::write(writer, &classes));
+}
+
+#[test]
+fn wasm_bindgen_web() {
+    let [_, classes] = classes();
+    check_dir("wasm_bindgen_web", |path| {
+        wasm_bindgen::write(File::create(path.join("index.js"))?, &classes, false, true)?;
+        wasm_bindgen::write(File::create(path.join("index.ts"))?, &classes, true, true)?;
+        wasm_bindgen::write_web_package(File::create(path.join("package.json"))?)
+    });
+}
+
+#[test]
+fn c() {
+    let [classes, _] = classes();
+    check_file("c", |writer| c::write(writer, &classes));
+}
+
+/// The smoke tests are written by hand, so this makes sure they only call
+/// functions that the C API actually provides.
+#[test]
+fn smoke_tests_only_call_existing_functions() {
+    let methods: BTreeSet<String> =
+        read_functions(&Path::new(env!("CARGO_MANIFEST_DIR")).join("../src"))
+            .into_iter()
+            .map(|f| f.method)
+            .collect();
+
+    let mut python = Vec::new();
+    smoke_tests::write_python(&mut python, true).unwrap();
+    let mut csharp = Vec::new();
+    smoke_tests::write_csharp(&mut csharp).unwrap();
+
+    for (suite, foreign) in [
+        (python, &["encode", "raises"][..]),
+        (csharp, &["Equal", "GetBytes", "NotNull", "True"][..]),
+    ] {
+        let suite = String::from_utf8(suite).unwrap();
+
+        for rest in suite.split('.').skip(1) {
+            let name = match rest.split_once('(') {
+                Some((name, _)) if name.chars().all(|c| c.is_alphanumeric() || c == '_') => name,
+                _ => continue,
+            };
+            if name.is_empty() || foreign.contains(&name) {
+                continue;
+            }
+            assert!(
+                methods.contains(&name.to_snake_case()),
+                "The smoke tests call {}, which the C API doesn't provide.",
+                name,
+            );
+        }
+    }
+}
+
+#[test]
+fn ruby() {
+    let [_, classes] = classes();
+    let opt = Opt {
+        ruby_lib_path: String::from("../liblivesplit_core.so"),
+        python_handles: false,
+    };
+    check_file("ruby", |writer| ruby::write(writer, &classes, &opt));
+}
+
+#[test]
+fn node_type_script() {
+    let [_, classes] = classes();
+    check_file("node_ts", |writer| node::write(writer, &classes, true));
+}
+
+#[test]
+fn node() {
+    let [_, classes] = classes();
+    check_file("node_js", |writer| node::write(writer, &classes, false));
+}
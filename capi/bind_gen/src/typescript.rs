@@ -1 +1,37 @@
+use crate::ENUMS;
+use std::io::{Result, Write};
+
 pub static HEADER: &str = include_str!("typescript.ts");
+
+/// The TypeScript header already declares the enums of the C API, so the plain
+/// JavaScript outputs get frozen objects with the same values instead.
+pub fn write_js_enums<W: Write>(mut writer: W, common_js: bool) -> Result<()> {
+    for e in ENUMS {
+        write!(writer, "\n/**")?;
+        for comment in e.comments {
+            write!(writer, "\n * {comment}")?;
+        }
+        write!(
+            writer,
+            "\n */\n{}const {} = Object.freeze({{",
+            if common_js { "" } else { "export " },
+            e.name
+        )?;
+        for variant in e.variants {
+            write!(writer, "\n    /**")?;
+            for comment in variant.comments {
+                write!(writer, "\n     * {comment}")?;
+            }
+            write!(
+                writer,
+                "\n     */\n    {}: {},",
+                variant.name, variant.value
+            )?;
+        }
+        writeln!(writer, "\n}});")?;
+        if common_js {
+            writeln!(writer, "exports.{0} = {0};", e.name)?;
+        }
+    }
+    Ok(())
+}
@@ -1,3 +1,14 @@
+declare const owned: unique symbol;
+
+/**
+ * Brands an object that the caller owns. Functions that return a new object
+ * return it with this brand, and functions that take ownership of an object,
+ * including methods that consume the object they are called on, only accept
+ * objects with this brand. Objects that are merely wrapping some pointer don't
+ * carry the brand, so handing them over is caught at compile time.
+ */
+export type Disposable<T> = T & { readonly [owned]: true };
+
 /** The state object for one of the components available. */
 export type ComponentStateJson =
     { BlankSpace: BlankSpaceComponentStateJson } |
@@ -743,6 +754,7 @@ export type SettingsDescriptionValueJson =
     { OptionalString: string | null } |
     { Accuracy: AccuracyJson } |
     { DigitsFormat: DigitsFormatJson } |
+    { OptionalTimePattern: string | null } |
     { OptionalTimingMethod: TimingMethodJson | null } |
     { Color: Color } |
     { OptionalColor: Color | null } |
@@ -758,8 +770,22 @@ export type SettingsDescriptionValueJson =
     { Font: Font | null } |
     { DeltaGradient: DeltaGradient } |
     { LayoutBackground: LayoutBackground } |
+    { PaletteColor: Color | SemanticColor } |
+    { List: SettingsDescriptionListJson } |
+    { Struct: [string, SettingsDescriptionValueJson][] } |
     { CustomCombobox: CustomCombobox };
 
+/**
+ * A list of setting values that all share the same type. The template
+ * describes that type and is the value that newly added items start out with.
+ */
+export interface SettingsDescriptionListJson {
+    /** The value that newly added items start out with. */
+    template: SettingsDescriptionValueJson,
+    /** The items of the list. */
+    items: SettingsDescriptionValueJson[],
+}
+
 /** Describes the kind of a column. */
 export type ColumnKind = "Time" | "Variable";
 
@@ -874,8 +900,33 @@ export interface RunEditorStateJson {
      * game.
      */
     metadata: RunMetadataJson,
+    /** The Segment Import that is currently in progress, if there is one. */
+    segment_import?: SegmentImportJson,
 }
 
+/**
+ * Describes the current state of a Segment Import, so it can be previewed
+ * before it gets finished.
+ */
+export interface SegmentImportJson {
+    /** What each of the columns of the table is imported as. */
+    columns: ImportColumnJson[],
+    /** The header of the table. This is empty if the table has no header. */
+    header: string[],
+    /** The first few rows of the table that are imported as segments. */
+    preview: string[][],
+    /** The total number of rows that are imported as segments. */
+    row_count: number,
+}
+
+/** Describes what a column of the table being imported is used for. */
+export type ImportColumnJson =
+    "Ignored" |
+    "Name" |
+    "SplitTime" |
+    "SegmentTime" |
+    "BestSegmentTime";
+
 /**
  * The Run Metadata stores additional information about a run, like the
  * platform and region of the game. All of this information is optional.
@@ -5,8 +5,11 @@ use std::{
     io::{Result, Write},
 };
 
-fn get_hl_type_with_null(ty: &Type) -> String {
+fn get_hl_type_with_null(ty: &Type, type_script: bool) -> String {
     let mut formatted = get_hl_type_without_null(ty);
+    if type_script && ty.is_custom && ty.kind == TypeKind::Value {
+        formatted = format!("Disposable<{formatted}>");
+    }
     if ty.is_nullable {
         formatted.push_str(" | null");
     }
@@ -14,7 +17,9 @@ fn get_hl_type_with_null(ty: &Type) -> String {
 }
 
 fn get_hl_type_without_null(ty: &Type) -> String {
-    if ty.is_custom {
+    if let Some(enum_name) = &ty.enum_name {
+        enum_name.clone()
+    } else if ty.is_custom {
         match ty.kind {
             TypeKind::Ref => format!("{}Ref", ty.name),
             TypeKind::RefMut => format!("{}RefMut", ty.name),
@@ -77,7 +82,7 @@ fn write_class_comments<W: Write>(mut writer: W, comments: &[String]) -> Result<
 fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) -> Result<()> {
     let is_static = function.is_static();
     let has_return_type = function.has_return_type();
-    let return_type_with_null = get_hl_type_with_null(&function.output);
+    let return_type_with_null = get_hl_type_with_null(&function.output, type_script);
     let return_type_without_null = get_hl_type_without_null(&function.output);
     let method = function.method.to_lower_camel_case();
     let is_json = has_return_type && function.output.name == "Json";
@@ -125,7 +130,7 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) ->
                 writer,
                 r#"
      * @param {{{}}} {}"#,
-                get_hl_type_with_null(ty),
+                get_hl_type_with_null(ty, type_script),
                 name.to_lower_camel_case()
             )?;
         }
@@ -153,18 +158,23 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) ->
         method
     )?;
 
+    let consumes_this = !is_static && function.inputs[0].1.kind == TypeKind::Value;
+    if type_script && consumes_this {
+        write!(writer, "this: Disposable<{}>", function.class)?;
+    }
+
     for (i, (name, ty)) in function
         .inputs
         .iter()
         .skip(usize::from(!is_static))
         .enumerate()
     {
-        if i != 0 {
+        if i != 0 || (type_script && consumes_this) {
             write!(writer, ", ")?;
         }
         write!(writer, "{}", name.to_lower_camel_case())?;
         if type_script {
-            write!(writer, ": {}", get_hl_type_with_null(ty))?;
+            write!(writer, ": {}", get_hl_type_with_null(ty, type_script))?;
         }
     }
 
@@ -318,6 +328,15 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) ->
                 r#"
         return decodeString(result);"#
             )?;
+        } else if type_script
+            && function.output.is_custom
+            && function.output.kind == TypeKind::Value
+        {
+            write!(
+                writer,
+                r#"
+        return result as Disposable<{return_type_without_null}>;"#
+            )?;
         } else {
             write!(
                 writer,
@@ -336,20 +355,90 @@ fn write_fn<W: Write>(mut writer: W, function: &Function, type_script: bool) ->
     Ok(())
 }
 
+static WEB_PACKAGE_JSON: &str = r#"{
+    "name": "livesplit-core",
+    "description": "livesplit-core is a library that provides a lot of functionality for creating a speedrun timer.",
+    "license": "MIT",
+    "type": "module",
+    "main": "index.js",
+    "module": "index.js",
+    "types": "index.ts",
+    "sideEffects": false,
+    "files": [
+        "index.js",
+        "index.ts",
+        "livesplit_core.js",
+        "livesplit_core_bg.wasm"
+    ]
+}
+"#;
+
+/// Writes the package manifest of the web target, so that it can be imported
+/// as an ES module by its package name.
+pub fn write_web_package<W: Write>(mut writer: W) -> Result<()> {
+    writer.write_all(WEB_PACKAGE_JSON.as_bytes())
+}
+
+fn write_web_init<W: Write>(mut writer: W, type_script: bool) -> Result<()> {
+    let (wasm_type, input_type, return_type) = if type_script {
+        (
+            ": any",
+            "?: RequestInfo | URL | Response | BufferSource | WebAssembly.Module",
+            ": Promise<void>",
+        )
+    } else {
+        ("", "", "")
+    };
+
+    writeln!(
+        writer,
+        r#"import initWasmBindgen from "./livesplit_core.js";
+
+let wasm{wasm_type};
+
+/**
+ * Loads and instantiates the WebAssembly module. This needs to be awaited
+ * before anything else can be used. The module is compiled while it is still
+ * being downloaded. By default it is loaded from livesplit_core_bg.wasm next to
+ * this module, but any URL, response, buffer or compiled module can be passed
+ * instead.
+ */
+export async function init(input{input_type}){return_type} {{
+    if (wasm === undefined) {{
+        wasm = await initWasmBindgen(input ?? new URL("./livesplit_core_bg.wasm", import.meta.url));
+    }}
+}}
+"#
+    )
+}
+
 pub fn write<W: Write>(
     mut writer: W,
     classes: &BTreeMap<String, Class>,
     type_script: bool,
+    web: bool,
 ) -> Result<()> {
     if type_script {
+        writeln!(writer, "// tslint:disable")?;
+    }
+
+    if web {
+        write_web_init(&mut writer, type_script)?;
+    } else {
         writeln!(
             writer,
-            "{}{}",
-            r#"// tslint:disable
-import * as wasm from "./livesplit_core_bg.wasm";
+            "{}",
+            r#"import * as wasm from "./livesplit_core_bg.wasm";
 import "./livesplit_core.js";
+"#
+        )?;
+    }
 
-const encoder = new TextEncoder();
+    if type_script {
+        writeln!(
+            writer,
+            "{}{}",
+            r#"const encoder = new TextEncoder();
 const decoder = new TextDecoder();
 
 interface Slice {
@@ -405,10 +494,7 @@ function dealloc(slice: Slice) {
         writeln!(
             writer,
             "{}",
-            r#"import * as wasm from "./livesplit_core_bg.wasm";
-import "./livesplit_core.js";
-
-const encoder = new TextEncoder();
+            r#"const encoder = new TextEncoder();
 const decoder = new TextDecoder();
 
 function allocUint8Array(src) {
@@ -451,6 +537,7 @@ function dealloc(slice) {
     wasm.dealloc(slice.ptr, slice.cap);
 }"#,
         )?;
+        typescript::write_js_enums(&mut writer, false)?;
     }
 
     for (class_name, class) in classes {
@@ -711,6 +798,33 @@ export class {class_name_ref_mut} extends {class_name_ref} {{"#,
         const slice = allocUint8Array(data);
         this.activeSetIcon(slice.ptr, slice.len);
         dealloc(slice);
+    }"#
+                )?;
+            }
+        } else if class_name == "RunParser" {
+            if type_script {
+                write!(
+                    writer,
+                    "{}",
+                    r#"
+    feedArray(data: Uint8Array) {
+        const slice = allocUint8Array(data);
+        this.feed(slice.ptr, slice.len);
+        dealloc(slice);
+    }"#
+                )?;
+            } else {
+                write!(
+                    writer,
+                    "{}",
+                    r#"
+    /**
+     * @param {Uint8Array} data
+     */
+    feedArray(data) {
+        const slice = allocUint8Array(data);
+        this.feed(slice.ptr, slice.len);
+        dealloc(slice);
     }"#
                 )?;
             }
@@ -793,13 +907,13 @@ export class {class_name} extends {class_name_ref_mut} {{
                     writer,
                     "{}",
                     r#"
-    static parseArray(data: Uint8Array, loadFilesPath: string): ParseRunResult {
+    static parseArray(data: Uint8Array, loadFilesPath: string): Disposable<ParseRunResult> {
         const slice = allocUint8Array(data);
         const result = Run.parse(slice.ptr, slice.len, loadFilesPath);
         dealloc(slice);
         return result;
     }
-    static parseString(text: string, loadFilesPath: string): ParseRunResult {
+    static parseString(text: string, loadFilesPath: string): Disposable<ParseRunResult> {
         const slice = allocString(text);
         const result = Run.parse(slice.ptr, slice.len, loadFilesPath);
         dealloc(slice);
@@ -841,13 +955,13 @@ export class {class_name} extends {class_name_ref_mut} {{
                     writer,
                     "{}",
                     r#"
-    static parseOriginalLivesplitArray(data: Uint8Array): Layout | null {
+    static parseOriginalLivesplitArray(data: Uint8Array): Disposable<Layout> | null {
         const slice = allocUint8Array(data);
         const result = Layout.parseOriginalLivesplit(slice.ptr, slice.len);
         dealloc(slice);
         return result;
     }
-    static parseOriginalLivesplitString(text: string): Layout | null {
+    static parseOriginalLivesplitString(text: string): Disposable<Layout> | null {
         const slice = allocString(text);
         const result = Layout.parseOriginalLivesplit(slice.ptr, slice.len);
         dealloc(slice);
@@ -0,0 +1,402 @@
+use crate::{Class, Function, Type, TypeKind, ENUMS};
+use heck::{ToLowerCamelCase, ToSnakeCase};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{Result, Write},
+};
+
+const KEYWORDS: &[&str] = &[
+    "addrspace",
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anytype",
+    "asm",
+    "async",
+    "await",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "linksection",
+    "noalias",
+    "noinline",
+    "nosuspend",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "try",
+    "type",
+    "union",
+    "unreachable",
+    "usingnamespace",
+    "var",
+    "volatile",
+    "while",
+];
+
+fn escape(name: String) -> String {
+    if KEYWORDS.contains(&&*name) {
+        format!("@\"{name}\"")
+    } else {
+        name
+    }
+}
+
+fn get_primitive_type(ty: &Type) -> &str {
+    match ty.name.as_str() {
+        "()" => "void",
+        "c_char" => "u8",
+        x => x,
+    }
+}
+
+fn get_ll_type(ty: &Type) -> String {
+    let nullable = if ty.is_nullable { "?" } else { "" };
+    if ty.is_custom {
+        return match ty.kind {
+            TypeKind::Ref => format!("{nullable}*const {}_s", ty.name),
+            TypeKind::RefMut | TypeKind::Value => format!("{nullable}*{}_s", ty.name),
+        };
+    }
+    if is_string(ty) {
+        return format!("{nullable}[*:0]const u8");
+    }
+    match ty.kind {
+        TypeKind::Ref => format!("{nullable}[*]const {}", get_primitive_type(ty)),
+        TypeKind::RefMut => format!("{nullable}[*]{}", get_primitive_type(ty)),
+        TypeKind::Value => get_primitive_type(ty).to_string(),
+    }
+}
+
+fn get_hl_type(ty: &Type) -> String {
+    let nullable = if ty.is_nullable { "?" } else { "" };
+    if let Some(enum_name) = &ty.enum_name {
+        format!("{nullable}{enum_name}")
+    } else if ty.is_custom {
+        match ty.kind {
+            TypeKind::Ref => format!("{nullable}{}Ref", ty.name),
+            TypeKind::RefMut => format!("{nullable}{}RefMut", ty.name),
+            TypeKind::Value => format!("{nullable}{}", ty.name),
+        }
+    } else if is_string(ty) {
+        format!("{nullable}[]const u8")
+    } else {
+        get_ll_type(ty)
+    }
+}
+
+fn is_string(ty: &Type) -> bool {
+    !ty.is_custom && (ty.name == "Json" || (ty.name == "c_char" && ty.kind == TypeKind::Ref))
+}
+
+fn get_method_name(function: &Function) -> String {
+    escape(function.method.to_lower_camel_case())
+}
+
+fn get_input_name(name: &str, method_names: &BTreeSet<String>) -> String {
+    if method_names.contains(name) {
+        format!("{name}_arg")
+    } else {
+        escape(name.to_string())
+    }
+}
+
+fn write_comments<W: Write, S: AsRef<str>>(
+    mut writer: W,
+    comments: &[S],
+    indent: &str,
+) -> Result<()> {
+    for comment in comments {
+        writeln!(
+            writer,
+            "{indent}/// {}",
+            comment
+                .as_ref()
+                .replace("<NULL>", "null")
+                .replace("<TRUE>", "true")
+                .replace("<FALSE>", "false")
+        )?;
+    }
+    Ok(())
+}
+
+fn write_fn<W: Write>(
+    mut writer: W,
+    function: &Function,
+    receiver: &str,
+    method_names: &BTreeSet<String>,
+) -> Result<()> {
+    let output = &function.output;
+    let return_type = get_hl_type(output);
+
+    writeln!(writer)?;
+    write_comments(&mut writer, &function.comments, "    ")?;
+    write!(writer, "    pub fn {}(", get_method_name(function))?;
+
+    for (i, (name, ty)) in function.inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        if name == "this" {
+            write!(writer, "self: {receiver}")?;
+        } else if is_string(ty) {
+            write!(
+                writer,
+                "{}: {}",
+                get_input_name(name, method_names),
+                get_ll_type(ty)
+            )?;
+        } else {
+            write!(
+                writer,
+                "{}: {}",
+                get_input_name(name, method_names),
+                get_hl_type(ty)
+            )?;
+        }
+    }
+
+    write!(writer, ") {return_type} {{\n        ")?;
+
+    if function.has_return_type() {
+        write!(writer, "const result = ")?;
+    }
+    write!(writer, "c.{}(", function.name)?;
+
+    for (i, (name, ty)) in function.inputs.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ", ")?;
+        }
+        if name == "this" {
+            write!(writer, "self.ptr")?;
+        } else if ty.is_custom {
+            write!(writer, "{}.ptr", get_input_name(name, method_names))?;
+        } else if ty.enum_name.is_some() {
+            write!(
+                writer,
+                "@intFromEnum({})",
+                get_input_name(name, method_names)
+            )?;
+        } else {
+            write!(writer, "{}", get_input_name(name, method_names))?;
+        }
+    }
+
+    writeln!(writer, ");")?;
+
+    if function.has_return_type() {
+        let value = if output.is_custom {
+            let class = return_type.trim_start_matches('?');
+            if output.is_nullable {
+                format!("if (result) |ptr| {class}{{ .ptr = ptr }} else null")
+            } else {
+                format!("{class}{{ .ptr = result }}")
+            }
+        } else if is_string(output) {
+            if output.is_nullable {
+                String::from("if (result) |ptr| std.mem.span(ptr) else null")
+            } else {
+                String::from("std.mem.span(result)")
+            }
+        } else if output.enum_name.is_some() {
+            String::from("@enumFromInt(result)")
+        } else {
+            String::from("result")
+        };
+        writeln!(writer, "        return {value};")?;
+    }
+
+    writeln!(writer, "    }}")
+}
+
+fn write_struct<'a, W: Write>(
+    mut writer: W,
+    name: &str,
+    class: &Class,
+    ptr_type: &str,
+    extra: &str,
+    functions: impl Iterator<Item = &'a Function> + Clone,
+) -> Result<()> {
+    let method_names = functions
+        .clone()
+        .map(get_method_name)
+        .collect::<BTreeSet<_>>();
+
+    writeln!(writer)?;
+    write_comments(&mut writer, &class.comments, "")?;
+    write!(
+        writer,
+        "pub const {name} = struct {{
+    ptr: {ptr_type},
+{extra}"
+    )?;
+
+    for function in functions {
+        write_fn(&mut writer, function, name, &method_names)?;
+    }
+
+    writeln!(writer, "}};")
+}
+
+pub fn write<W: Write>(mut writer: W, classes: &BTreeMap<String, Class>) -> Result<()> {
+    write!(
+        writer,
+        "{}",
+        r#"//! Bindings for livesplit-core. Every class of livesplit-core is represented
+//! by three structs. The struct named after the class owns the object and
+//! needs to be freed by calling deinit, unless it gets passed to a function
+//! that takes ownership of it. The Ref and RefMut structs only borrow the
+//! object and can't outlive its owner. The owned struct and the RefMut struct
+//! provide all the functions of the structs that borrow less. The strings
+//! returned are only valid until the next function call on the same thread.
+
+const std = @import("std");
+
+/// The raw functions of the C API.
+pub const c = struct {
+"#
+    )?;
+
+    for name in classes.keys() {
+        writeln!(writer, "    pub const {name}_s = opaque {{}};")?;
+    }
+
+    for class in classes.values() {
+        for function in class
+            .static_fns
+            .iter()
+            .chain(class.own_fns.iter())
+            .chain(class.shared_fns.iter())
+            .chain(class.mut_fns.iter())
+        {
+            write!(writer, "    pub extern fn {}(", function.name)?;
+            for (i, (name, ty)) in function.inputs.iter().enumerate() {
+                if i != 0 {
+                    write!(writer, ", ")?;
+                }
+                write!(writer, "{}: {}", escape(name.clone()), get_ll_type(ty))?;
+            }
+            writeln!(writer, ") {};", get_ll_type(&function.output))?;
+        }
+    }
+
+    writeln!(writer, "}};")?;
+
+    for e in ENUMS {
+        writeln!(writer)?;
+        write_comments(&mut writer, e.comments, "")?;
+        writeln!(writer, "pub const {} = enum(u8) {{", e.name)?;
+        for variant in e.variants {
+            write_comments(&mut writer, variant.comments, "    ")?;
+            writeln!(
+                writer,
+                "    {} = {},",
+                escape(variant.name.to_snake_case()),
+                variant.value
+            )?;
+        }
+        writeln!(writer, "}};")?;
+    }
+
+    for (class_name, class) in classes {
+        let class_name_ref = format!("{class_name}Ref");
+        let class_name_ref_mut = format!("{class_name}RefMut");
+
+        write_struct(
+            &mut writer,
+            &class_name_ref,
+            class,
+            &format!("*const c.{class_name}_s"),
+            "",
+            class.shared_fns.iter(),
+        )?;
+
+        write_struct(
+            &mut writer,
+            &class_name_ref_mut,
+            class,
+            &format!("*c.{class_name}_s"),
+            &format!(
+                "
+    /// Borrows the object immutably.
+    pub fn asRef(self: {class_name_ref_mut}) {class_name_ref} {{
+        return .{{ .ptr = self.ptr }};
+    }}
+"
+            ),
+            class.shared_fns.iter().chain(class.mut_fns.iter()),
+        )?;
+
+        let drop = class
+            .own_fns
+            .iter()
+            .find(|f| f.method == "drop")
+            .map(|f| {
+                format!(
+                    "
+    /// Frees the object, allowing it to clean up all of its memory. You need
+    /// to call this for every object that you don't use anymore and that
+    /// hasn't been passed to a function that takes ownership of it.
+    pub fn deinit(self: {class_name}) void {{
+        c.{}(self.ptr);
+    }}
+",
+                    f.name
+                )
+            })
+            .unwrap_or_default();
+
+        write_struct(
+            &mut writer,
+            class_name,
+            class,
+            &format!("*c.{class_name}_s"),
+            &format!(
+                "{drop}
+    /// Borrows the object immutably.
+    pub fn asRef(self: {class_name}) {class_name_ref} {{
+        return .{{ .ptr = self.ptr }};
+    }}
+
+    /// Borrows the object mutably.
+    pub fn asMut(self: {class_name}) {class_name_ref_mut} {{
+        return .{{ .ptr = self.ptr }};
+    }}
+"
+            ),
+            class
+                .shared_fns
+                .iter()
+                .chain(class.mut_fns.iter())
+                .chain(class.static_fns.iter())
+                .chain(class.own_fns.iter())
+                .filter(|f| f.method != "drop"),
+        )?;
+    }
+
+    Ok(())
+}
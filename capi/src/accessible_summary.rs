@@ -0,0 +1,48 @@
+//! An Accessible Summary tracks a concise sentence describing the current
+//! state of the timer, such as `Segment 5 of 20, 3.2 seconds ahead of Personal
+//! Best.`, that is suitable for screen readers and text to speech. The
+//! sentence only changes when the timer gets started, splits, gets paused or
+//! resets.
+
+use super::{guard, output_str, output_utf16_from};
+use livesplit_core::{analysis::AccessibleSummary, Timer};
+use std::os::raw::c_char;
+
+/// type
+pub type OwnedAccessibleSummary = Box<AccessibleSummary>;
+
+/// Creates a new Accessible Summary that doesn't describe anything yet.
+#[no_mangle]
+pub extern "C" fn AccessibleSummary_new() -> OwnedAccessibleSummary {
+    guard::abort_on_unwind(move || Box::new(AccessibleSummary::new()))
+}
+
+/// drop
+#[no_mangle]
+pub extern "C" fn AccessibleSummary_drop(this: OwnedAccessibleSummary) {
+    guard::dispose(this);
+}
+
+/// Updates the sentence based on the timer provided. Returns <TRUE> if the
+/// sentence changed and should be announced.
+#[no_mangle]
+pub extern "C" fn AccessibleSummary_update(this: &mut AccessibleSummary, timer: &Timer) -> bool {
+    guard::call(guard::address(this), move || this.update(timer))
+}
+
+/// Accesses the most recent sentence describing the state of the timer.
+#[no_mangle]
+pub extern "C" fn AccessibleSummary_text(this: &AccessibleSummary) -> *const c_char {
+    guard::call(guard::address(this), move || output_str(this.text()))
+}
+
+/// The UTF-16 variant of AccessibleSummary_text. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn AccessibleSummary_text_u16(
+    this: &AccessibleSummary,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(AccessibleSummary_text(this), buf, buf_len)
+}
@@ -1,9 +1,19 @@
 //! The analysis module provides a variety of functions for calculating
 //! information about runs.
 
-use super::time_span::{NullableOwnedTimeSpan, OwnedTimeSpan};
+use super::{
+    guard,
+    segment_statistics::OwnedSegmentStatistics,
+    sum_of_best_cleaner::OwnedSumOfBestCleaner,
+    time_span::{NullableOwnedTimeSpan, OwnedTimeSpan},
+};
 use livesplit_core::{
-    analysis::{sum_of_segments::calculate_best, total_playtime::calculate},
+    analysis::{
+        pb_chance, segment_statistics,
+        sum_of_segments::{calculate_best, calculate_worst},
+        total_playtime::calculate,
+    },
+    run::editor::cleaning::SumOfBestCleaner,
     Run, Timer, TimingMethod,
 };
 
@@ -24,17 +34,70 @@ pub extern "C" fn Analysis_calculate_sum_of_best(
     use_current_run: bool,
     method: TimingMethod,
 ) -> NullableOwnedTimeSpan {
-    calculate_best(run.segments(), simple_calculation, use_current_run, method).map(Box::new)
+    guard::call(0, move || {
+        calculate_best(run.segments(), simple_calculation, use_current_run, method).map(Box::new)
+    })
+}
+
+/// Calculates the Sum of Worst Segments for the timing method provided. This is
+/// the slowest time possible to complete a run of a category, based on
+/// information collected from all the previous attempts. This obviously isn't
+/// really the worst possible time, but may be useful information regardless.
+/// If there's an active attempt, you can choose to take it into account as
+/// well. Can return <NULL>.
+#[no_mangle]
+pub extern "C" fn Analysis_calculate_sum_of_worst(
+    run: &Run,
+    use_current_run: bool,
+    method: TimingMethod,
+) -> NullableOwnedTimeSpan {
+    guard::call(0, move || {
+        calculate_worst(run.segments(), use_current_run, method).map(Box::new)
+    })
+}
+
+/// Calculates the PB chance for the Run provided. The PB chance is the
+/// probability of beating the Personal Best, based on the segment history of
+/// the runner. The value is between 0 and 1.
+#[no_mangle]
+pub extern "C" fn Analysis_calculate_pb_chance(run: &Run, method: TimingMethod) -> f64 {
+    guard::call(0, move || pb_chance::for_run(run, method))
+}
+
+/// Calculates statistics about the segment times the runner achieved for the
+/// segment with the index provided, such as the average or the median segment
+/// time. You may not provide an out of bounds index.
+#[no_mangle]
+pub extern "C" fn Analysis_calculate_segment_statistics(
+    run: &Run,
+    segment_index: usize,
+    method: TimingMethod,
+) -> OwnedSegmentStatistics {
+    guard::abort_on_unwind(move || {
+        Box::new(segment_statistics::calculate(
+            run.segment(segment_index),
+            method,
+        ))
+    })
+}
+
+/// Creates a Sum of Best Cleaner for the Run provided, which allows you to
+/// interactively remove potential issues in its segment history that lead to
+/// an inaccurate Sum of Best. The Run may not be used for anything else until
+/// the Sum of Best Cleaner is disposed.
+#[no_mangle]
+pub extern "C" fn Analysis_clean_sum_of_best(run: &'static mut Run) -> OwnedSumOfBestCleaner {
+    guard::abort_on_unwind(move || Box::new(SumOfBestCleaner::new(run)))
 }
 
 /// Calculates the total playtime of the passed Run.
 #[no_mangle]
 pub extern "C" fn Analysis_calculate_total_playtime_for_run(run: &Run) -> OwnedTimeSpan {
-    Box::new(calculate(run))
+    guard::abort_on_unwind(move || Box::new(calculate(run)))
 }
 
 /// Calculates the total playtime of the passed Timer.
 #[no_mangle]
 pub extern "C" fn Analysis_calculate_total_playtime_for_timer(timer: &Timer) -> OwnedTimeSpan {
-    Box::new(calculate(timer))
+    guard::abort_on_unwind(move || Box::new(calculate(timer)))
 }
@@ -1,7 +1,7 @@
 //! An Atomic Date Time represents a UTC Date Time that tries to be as close to
 //! an atomic clock as possible.
 
-use crate::output_vec;
+use crate::{guard, output_utf16_from, output_vec};
 use livesplit_core::AtomicDateTime;
 use std::os::raw::c_char;
 use time::format_description::well_known::Rfc3339;
@@ -14,7 +14,7 @@ pub type NullableOwnedAtomicDateTime = Option<OwnedAtomicDateTime>;
 /// drop
 #[no_mangle]
 pub extern "C" fn AtomicDateTime_drop(this: OwnedAtomicDateTime) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Represents whether the date time is actually properly derived from an
@@ -22,13 +22,26 @@ pub extern "C" fn AtomicDateTime_drop(this: OwnedAtomicDateTime) {
 /// yet or failed, this is set to <FALSE>.
 #[no_mangle]
 pub extern "C" fn AtomicDateTime_is_synchronized(this: &AtomicDateTime) -> bool {
-    this.synced_with_atomic_clock
+    guard::call(guard::address(this), move || this.synced_with_atomic_clock)
 }
 
 /// Converts this atomic date time into a RFC 3339 formatted date time.
 #[no_mangle]
 pub extern "C" fn AtomicDateTime_to_rfc3339(this: &AtomicDateTime) -> *const c_char {
-    output_vec(|o| {
-        let _ = this.time.format_into(o, &Rfc3339);
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            let _ = this.time.format_into(o, &Rfc3339);
+        })
     })
 }
+
+/// The UTF-16 variant of AtomicDateTime_to_rfc3339. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn AtomicDateTime_to_rfc3339_u16(
+    this: &AtomicDateTime,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(AtomicDateTime_to_rfc3339(this), buf, buf_len)
+}
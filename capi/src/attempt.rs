@@ -2,26 +2,28 @@
 //! by a specific runner in the past. Every time a new attempt is started and
 //! then reset, an Attempt describing general information about it is created.
 
-use super::{output_time, output_time_span};
+use super::{guard, output_time, output_time_span};
 use crate::{atomic_date_time::NullableOwnedAtomicDateTime, time_span::NullableTimeSpan};
 use livesplit_core::{Attempt, Time};
 use std::ptr;
 
 /// type
 pub type OwnedAttempt = Box<Attempt>;
+/// type
+pub type NullableAttempt = Attempt;
 
 /// Accesses the unique index of the attempt. This index is unique for the
 /// Run, not for all of them.
 #[no_mangle]
 pub extern "C" fn Attempt_index(this: &Attempt) -> i32 {
-    this.index()
+    guard::call(guard::address(this), move || this.index())
 }
 
 /// Accesses the split time of the last segment. If the attempt got reset
 /// early and didn't finish, this may be empty.
 #[no_mangle]
 pub extern "C" fn Attempt_time(this: &Attempt) -> *const Time {
-    output_time(this.time())
+    guard::call(guard::address(this), move || output_time(this.time()))
 }
 
 /// Accesses the amount of time the attempt has been paused for. If it is not
@@ -30,23 +32,25 @@ pub extern "C" fn Attempt_time(this: &Attempt) -> *const Time {
 /// wasn't stored.
 #[no_mangle]
 pub extern "C" fn Attempt_pause_time(this: &Attempt) -> *const NullableTimeSpan {
-    if let Some(pause_time) = this.pause_time() {
-        output_time_span(pause_time)
-    } else {
-        ptr::null()
-    }
+    guard::call(guard::address(this), move || {
+        if let Some(pause_time) = this.pause_time() {
+            output_time_span(pause_time)
+        } else {
+            ptr::null()
+        }
+    })
 }
 
 /// Accesses the point in time the attempt was started at. This returns <NULL>
 /// if this information is not known.
 #[no_mangle]
 pub extern "C" fn Attempt_started(this: &Attempt) -> NullableOwnedAtomicDateTime {
-    this.started().map(Box::new)
+    guard::call(guard::address(this), move || this.started().map(Box::new))
 }
 
 /// Accesses the point in time the attempt was ended at. This returns <NULL> if
 /// this information is not known.
 #[no_mangle]
 pub extern "C" fn Attempt_ended(this: &Attempt) -> NullableOwnedAtomicDateTime {
-    this.ended().map(Box::new)
+    guard::call(guard::address(this), move || this.ended().map(Box::new))
 }
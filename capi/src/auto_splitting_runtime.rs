@@ -1,8 +1,11 @@
 //! With an Auto Splitting Runtime, the runner can use an Auto Splitter to
 //! automatically control the timer on systems that are supported.
 
-use super::str;
-use crate::shared_timer::OwnedSharedTimer;
+use super::{guard, str, Utf16Param};
+use crate::{
+    last_error::{self, ErrorCode},
+    shared_timer::OwnedSharedTimer,
+};
 use std::{os::raw::c_char, path::PathBuf};
 
 #[cfg(feature = "auto-splitting")]
@@ -22,12 +25,12 @@ impl AutoSplittingRuntime {
         Self
     }
 
-    pub fn unload(&self) -> Result<(), ()> {
-        Err(())
+    pub fn unload(&self) -> Result<(), &'static str> {
+        Err("Auto splitting is not supported by this build.")
     }
 
-    pub fn load(&self, _: PathBuf, _: SharedTimer) -> Result<(), ()> {
-        Err(())
+    pub fn load(&self, _: PathBuf, _: SharedTimer) -> Result<(), &'static str> {
+        Err("Auto splitting is not supported by this build.")
     }
 }
 
@@ -39,7 +42,7 @@ pub type NullableOwnedAutoSplittingRuntime = Option<OwnedAutoSplittingRuntime>;
 /// Creates a new Auto Splitting Runtime.
 #[no_mangle]
 pub extern "C" fn AutoSplittingRuntime_new() -> OwnedAutoSplittingRuntime {
-    Box::new(AutoSplittingRuntime::new())
+    guard::abort_on_unwind(move || Box::new(AutoSplittingRuntime::new()))
 }
 
 /// Attempts to load an auto splitter. Returns true if successful.
@@ -49,17 +52,38 @@ pub unsafe extern "C" fn AutoSplittingRuntime_load(
     path: *const c_char,
     shared_timer: OwnedSharedTimer,
 ) -> bool {
-    this.load(PathBuf::from(str(path)), *shared_timer).is_ok()
+    guard::consume(&*shared_timer);
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::AutoSplitter,
+            this.load(PathBuf::from(str(path)), *shared_timer),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of AutoSplittingRuntime_load. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn AutoSplittingRuntime_load_u16(
+    this: &AutoSplittingRuntime,
+    path: *const u16,
+    path_len: usize,
+    shared_timer: OwnedSharedTimer,
+) -> bool {
+    AutoSplittingRuntime_load(this, Utf16Param::new(path, path_len).as_ptr(), shared_timer)
 }
 
 /// Attempts to unload the auto splitter. Returns true if successful.
 #[no_mangle]
 pub extern "C" fn AutoSplittingRuntime_unload(this: &AutoSplittingRuntime) -> bool {
-    this.unload().is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(ErrorCode::AutoSplitter, this.unload()).is_some()
+    })
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn AutoSplittingRuntime_drop(this: OwnedAutoSplittingRuntime) {
-    drop(this);
+    guard::dispose(this);
 }
@@ -2,7 +2,7 @@
 //! anything other than a background. It mostly serves as padding between other
 //! components.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::blank_space_component_state::OwnedBlankSpaceComponentState;
 use crate::component::OwnedComponent;
 use livesplit_core::component::blank_space::Component as BlankSpaceComponent;
@@ -13,13 +13,13 @@ pub type OwnedBlankSpaceComponent = Box<BlankSpaceComponent>;
 /// Creates a new Blank Space Component.
 #[no_mangle]
 pub extern "C" fn BlankSpaceComponent_new() -> OwnedBlankSpaceComponent {
-    Box::new(BlankSpaceComponent::new())
+    guard::abort_on_unwind(move || Box::new(BlankSpaceComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn BlankSpaceComponent_drop(this: OwnedBlankSpaceComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
@@ -28,21 +28,34 @@ pub extern "C" fn BlankSpaceComponent_drop(this: OwnedBlankSpaceComponent) {
 pub extern "C" fn BlankSpaceComponent_into_generic(
     this: OwnedBlankSpaceComponent,
 ) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
 #[no_mangle]
 pub extern "C" fn BlankSpaceComponent_state_as_json(this: &mut BlankSpaceComponent) -> Json {
-    output_vec(|o| {
-        this.state().write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state().write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of BlankSpaceComponent_state_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn BlankSpaceComponent_state_as_json_u16(
+    this: &mut BlankSpaceComponent,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(BlankSpaceComponent_state_as_json(this), buf, buf_len)
+}
+
 /// Calculates the component's state.
 #[no_mangle]
 pub extern "C" fn BlankSpaceComponent_state(
     this: &mut BlankSpaceComponent,
 ) -> OwnedBlankSpaceComponentState {
-    Box::new(this.state())
+    guard::call_or_abort(guard::address(this), move || Box::new(this.state()))
 }
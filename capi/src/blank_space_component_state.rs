@@ -1,5 +1,6 @@
 //! The state object describes the information to visualize for this component.
 
+use crate::guard;
 use livesplit_core::component::blank_space::State as BlankSpaceComponentState;
 
 /// type
@@ -8,11 +9,11 @@ pub type OwnedBlankSpaceComponentState = Box<BlankSpaceComponentState>;
 /// drop
 #[no_mangle]
 pub extern "C" fn BlankSpaceComponentState_drop(this: OwnedBlankSpaceComponentState) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// The size of the component.
 #[no_mangle]
 pub extern "C" fn BlankSpaceComponentState_size(this: &BlankSpaceComponentState) -> u32 {
-    this.size
+    guard::call(guard::address(this), move || this.size)
 }
@@ -0,0 +1,57 @@
+//! A Cancellation Token allows aborting long running operations, such as
+//! parsing large splits files. The token can be shared with another thread,
+//! which can then cancel the operation while it is running. Once cancelled, a
+//! token stays cancelled, so a new token needs to be created for every
+//! operation.
+
+use crate::guard;
+use std::sync::{
+    atomic::{self, AtomicBool},
+    Arc,
+};
+
+/// type
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+/// type
+pub type OwnedCancellationToken = Box<CancellationToken>;
+
+impl CancellationToken {
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.0.load(atomic::Ordering::Relaxed)
+    }
+}
+
+/// Creates a new Cancellation Token that is not cancelled yet.
+#[no_mangle]
+pub extern "C" fn CancellationToken_new() -> OwnedCancellationToken {
+    guard::abort_on_unwind(Box::default)
+}
+
+/// drop
+#[no_mangle]
+pub extern "C" fn CancellationToken_drop(this: OwnedCancellationToken) {
+    guard::dispose(this);
+}
+
+/// Creates a new handle to the same Cancellation Token, so it can be cancelled
+/// from another thread. Cancelling either of them cancels both.
+#[no_mangle]
+pub extern "C" fn CancellationToken_share(this: &CancellationToken) -> OwnedCancellationToken {
+    guard::call_or_abort(guard::address(this), move || Box::new(this.clone()))
+}
+
+/// Cancels all the operations that are using this Cancellation Token.
+#[no_mangle]
+pub extern "C" fn CancellationToken_cancel(this: &CancellationToken) {
+    guard::call(guard::address(this), move || {
+        this.0.store(true, atomic::Ordering::Relaxed);
+    })
+}
+
+/// Returns <TRUE> if the Cancellation Token got cancelled.
+#[no_mangle]
+pub extern "C" fn CancellationToken_is_cancelled(this: &CancellationToken) -> bool {
+    guard::call(guard::address(this), move || this.is_cancelled())
+}
@@ -7,15 +7,21 @@
 //! splits or notifying a server about changes happening in the run. After
 //! processing a command, changes to the timer are reported as events. Various
 //! error conditions can occur if the command couldn't be processed.
+//!
+//! The commands are not queued. Each of the functions that sends a command
+//! blocks the calling thread until the command is processed and then returns
+//! its result. On the web the thread can't be blocked, so commands that can't
+//! be processed right away are processed in the background and their result
+//! is reported as unknown.
 
-use std::{borrow::Cow, future::Future, ops::Deref, pin::Pin, sync::Arc};
+use std::{borrow::Cow, future::Future, ops::Deref, os::raw::c_char, pin::Pin, sync::Arc};
 
 use livesplit_core::{
     event::{self, Result},
     TimeSpan, Timer, TimingMethod,
 };
 
-use crate::shared_timer::OwnedSharedTimer;
+use crate::{guard, shared_timer::OwnedSharedTimer, str, timer::convert, Utf16Param};
 
 /// type
 #[derive(Clone)]
@@ -27,13 +33,297 @@ pub type OwnedCommandSink = Box<CommandSink>;
 /// Creates a new Command Sink.
 #[no_mangle]
 pub extern "C" fn CommandSink_from_timer(timer: OwnedSharedTimer) -> OwnedCommandSink {
-    Box::new(CommandSink(Arc::new(*timer)))
+    guard::consume(&*timer);
+    guard::abort_on_unwind(move || Box::new(CommandSink(Arc::new(*timer))))
+}
+
+/// Creates a new handle to the same Command Sink. The Command Sink can be
+/// used from any thread, so the handles can be passed to the threads of the
+/// hotkey system, a network server or an auto splitter.
+#[no_mangle]
+pub extern "C" fn CommandSink_share(this: &CommandSink) -> OwnedCommandSink {
+    guard::call_or_abort(guard::address(this), move || Box::new(this.clone()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn CommandSink_drop(this: OwnedCommandSink) {
-    drop(this);
+    guard::dispose(this);
+}
+
+/// Starts the timer if there is no attempt in progress. If that's not the case,
+/// nothing happens. This blocks the calling thread until the command is
+/// processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_start(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || execute(this.0.dyn_start()))
+}
+
+/// If an attempt is in progress, stores the current time as the time of the
+/// current split. The attempt ends if the last split time is stored. This
+/// blocks the calling thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_split(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || execute(this.0.dyn_split()))
+}
+
+/// Starts a new attempt or stores the current time as the time of the current
+/// split. The attempt ends if the last split time is stored. This blocks the
+/// calling thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_split_or_start(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_split_or_start())
+    })
+}
+
+/// Resets the current attempt if there is one in progress. If the splits are to
+/// be updated, all the information of the current attempt is stored in the
+/// run's history. Otherwise the current attempt's information is discarded.
+/// This blocks the calling thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_reset(this: &CommandSink, save_attempt: bool) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_reset(Some(save_attempt)))
+    })
+}
+
+/// Removes the split time from the last split if an attempt is in progress and
+/// there is a previous split. The Timer Phase also switches to `Running` if it
+/// previously was `Ended`. This blocks the calling thread until the command is
+/// processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_undo_split(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_undo_split())
+    })
+}
+
+/// Skips the current split if an attempt is in progress and the current split
+/// is not the last split. This blocks the calling thread until the command is
+/// processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_skip_split(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_skip_split())
+    })
+}
+
+/// Toggles an active attempt between `Paused` and `Running` or starts an
+/// attempt if there's none in progress. This blocks the calling thread until
+/// the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_toggle_pause_or_start(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_toggle_pause_or_start())
+    })
+}
+
+/// Pauses an active attempt that is not paused. This blocks the calling thread
+/// until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_pause(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || execute(this.0.dyn_pause()))
+}
+
+/// Resumes an attempt that is paused. This blocks the calling thread until the
+/// command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_resume(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || execute(this.0.dyn_resume()))
+}
+
+/// Removes all the pause times from the current time. If the current attempt is
+/// paused, it also resumes that attempt. This blocks the calling thread until
+/// the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_undo_all_pauses(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_undo_all_pauses())
+    })
+}
+
+/// Switches the current comparison to the previous comparison in the list. This
+/// blocks the calling thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_switch_to_previous_comparison(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_switch_to_previous_comparison())
+    })
+}
+
+/// Switches the current comparison to the next comparison in the list. This
+/// blocks the calling thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_switch_to_next_comparison(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_switch_to_next_comparison())
+    })
+}
+
+/// Tries to set the current comparison to the comparison specified. This blocks
+/// the calling thread until the command is processed.
+#[no_mangle]
+pub unsafe extern "C" fn CommandSink_set_current_comparison(
+    this: &CommandSink,
+    comparison: *const c_char,
+) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_set_current_comparison(str(comparison).into()))
+    })
+}
+
+/// The UTF-16 variant of CommandSink_set_current_comparison. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn CommandSink_set_current_comparison_u16(
+    this: &CommandSink,
+    comparison: *const u16,
+    comparison_len: usize,
+) -> i32 {
+    CommandSink_set_current_comparison(this, Utf16Param::new(comparison, comparison_len).as_ptr())
+}
+
+/// Toggles between the Real Time and Game Time timing methods. This blocks the
+/// calling thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_toggle_timing_method(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_toggle_timing_method())
+    })
+}
+
+/// Sets the current Timing Method to the Timing Method provided. This blocks
+/// the calling thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_set_current_timing_method(
+    this: &CommandSink,
+    method: TimingMethod,
+) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_set_current_timing_method(method))
+    })
+}
+
+/// Initializes Game Time for the current attempt. Game Time automatically gets
+/// uninitialized for each new attempt. This blocks the calling thread until the
+/// command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_initialize_game_time(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_initialize_game_time())
+    })
+}
+
+/// Sets the Game Time to the time specified. This also works if the Game Time
+/// is paused, which can be used as a way of updating the Game Timer
+/// periodically without it automatically moving forward. This blocks the
+/// calling thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_set_game_time(this: &CommandSink, time: &TimeSpan) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_set_game_time(*time))
+    })
+}
+
+/// Pauses the Game Timer such that it doesn't automatically increment similar
+/// to Real Time. This blocks the calling thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_pause_game_time(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_pause_game_time())
+    })
+}
+
+/// Resumes the Game Timer such that it automatically increments similar to Real
+/// Time, starting from the Game Time it was paused at. This blocks the calling
+/// thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_resume_game_time(this: &CommandSink) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_resume_game_time())
+    })
+}
+
+/// Instead of setting the Game Time directly, this method can be used to just
+/// specify the amount of time the game has been loading. The Game Time is then
+/// automatically determined by Real Time - Loading Times. This blocks the
+/// calling thread until the command is processed.
+#[no_mangle]
+pub extern "C" fn CommandSink_set_loading_times(this: &CommandSink, time: &TimeSpan) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(this.0.dyn_set_loading_times(*time))
+    })
+}
+
+/// Sets the value of a custom variable with the name specified. If the variable
+/// does not exist, a temporary variable gets created that will not be stored in
+/// the splits file. This blocks the calling thread until the command is
+/// processed.
+#[no_mangle]
+pub unsafe extern "C" fn CommandSink_set_custom_variable(
+    this: &CommandSink,
+    name: *const c_char,
+    value: *const c_char,
+) -> i32 {
+    guard::call(guard::address(this), move || {
+        execute(
+            this.0
+                .dyn_set_custom_variable(str(name).into(), str(value).into()),
+        )
+    })
+}
+
+/// The UTF-16 variant of CommandSink_set_custom_variable. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn CommandSink_set_custom_variable_u16(
+    this: &CommandSink,
+    name: *const u16,
+    name_len: usize,
+    value: *const u16,
+    value_len: usize,
+) -> i32 {
+    CommandSink_set_custom_variable(
+        this,
+        Utf16Param::new(name, name_len).as_ptr(),
+        Utf16Param::new(value, value_len).as_ptr(),
+    )
+}
+
+/// Drives the command to completion and encodes its result the same way the
+/// Timer does. The calling thread is blocked until the command is processed.
+/// The commands sent to a shared timer are applied right away while holding
+/// its lock, so they can be sent from any thread.
+#[cfg(not(all(target_family = "wasm", feature = "wasm-web")))]
+fn execute(future: Fut) -> i32 {
+    convert(event::block_on(future))
+}
+
+/// The thread can't be blocked on the web, so commands that are handled
+/// asynchronously by the web command sink are applied in the background and
+/// reported as an unknown result.
+#[cfg(all(target_family = "wasm", feature = "wasm-web"))]
+fn execute(mut future: Fut) -> i32 {
+    use std::task::{Context, Poll, Wake};
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = Arc::new(NoopWaker).into();
+    let mut context = Context::from_waker(&waker);
+    match future.as_mut().poll(&mut context) {
+        Poll::Ready(result) => convert(result),
+        Poll::Pending => {
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = future.await;
+            });
+            convert(Err(event::Error::Unknown))
+        }
+    }
 }
 
 pub(crate) trait CommandSinkAndQuery: Send + Sync + 'static {
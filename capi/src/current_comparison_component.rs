@@ -1,7 +1,7 @@
 //! The Current Comparison Component is a component that shows the name of the
 //! comparison that is currently selected to be compared against.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::component::OwnedComponent;
 use crate::key_value_component_state::OwnedKeyValueComponentState;
 use livesplit_core::component::current_comparison::Component as CurrentComparisonComponent;
@@ -13,13 +13,13 @@ pub type OwnedCurrentComparisonComponent = Box<CurrentComparisonComponent>;
 /// Creates a new Current Comparison Component.
 #[no_mangle]
 pub extern "C" fn CurrentComparisonComponent_new() -> OwnedCurrentComparisonComponent {
-    Box::new(CurrentComparisonComponent::new())
+    guard::abort_on_unwind(move || Box::new(CurrentComparisonComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn CurrentComparisonComponent_drop(this: OwnedCurrentComparisonComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
@@ -28,7 +28,7 @@ pub extern "C" fn CurrentComparisonComponent_drop(this: OwnedCurrentComparisonCo
 pub extern "C" fn CurrentComparisonComponent_into_generic(
     this: OwnedCurrentComparisonComponent,
 ) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -37,16 +37,34 @@ pub extern "C" fn CurrentComparisonComponent_state_as_json(
     this: &mut CurrentComparisonComponent,
     timer: &Timer,
 ) -> Json {
-    output_vec(|o| {
-        this.state(timer).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(timer).write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of CurrentComparisonComponent_state_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn CurrentComparisonComponent_state_as_json_u16(
+    this: &mut CurrentComparisonComponent,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        CurrentComparisonComponent_state_as_json(this, timer),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer provided.
 #[no_mangle]
 pub extern "C" fn CurrentComparisonComponent_state(
     this: &mut CurrentComparisonComponent,
     timer: &Timer,
 ) -> OwnedKeyValueComponentState {
-    Box::new(this.state(timer))
+    guard::call_or_abort(guard::address(this), move || Box::new(this.state(timer)))
 }
@@ -2,7 +2,7 @@
 //! current attempt's final time, if the current attempt's pace matches the
 //! chosen comparison for the remainder of the run.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::component::OwnedComponent;
 use crate::key_value_component_state::OwnedKeyValueComponentState;
 use livesplit_core::component::current_pace::Component as CurrentPaceComponent;
@@ -14,13 +14,13 @@ pub type OwnedCurrentPaceComponent = Box<CurrentPaceComponent>;
 /// Creates a new Current Pace Component.
 #[no_mangle]
 pub extern "C" fn CurrentPaceComponent_new() -> OwnedCurrentPaceComponent {
-    Box::new(CurrentPaceComponent::new())
+    guard::abort_on_unwind(move || Box::new(CurrentPaceComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn CurrentPaceComponent_drop(this: OwnedCurrentPaceComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
@@ -29,7 +29,7 @@ pub extern "C" fn CurrentPaceComponent_drop(this: OwnedCurrentPaceComponent) {
 pub extern "C" fn CurrentPaceComponent_into_generic(
     this: OwnedCurrentPaceComponent,
 ) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -38,16 +38,36 @@ pub extern "C" fn CurrentPaceComponent_state_as_json(
     this: &mut CurrentPaceComponent,
     timer: &Timer,
 ) -> Json {
-    output_vec(|o| {
-        this.state(&timer.snapshot()).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(&timer.snapshot()).write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of CurrentPaceComponent_state_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn CurrentPaceComponent_state_as_json_u16(
+    this: &mut CurrentPaceComponent,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        CurrentPaceComponent_state_as_json(this, timer),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer provided.
 #[no_mangle]
 pub extern "C" fn CurrentPaceComponent_state(
     this: &mut CurrentPaceComponent,
     timer: &Timer,
 ) -> OwnedKeyValueComponentState {
-    Box::new(this.state(&timer.snapshot()))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(&timer.snapshot()))
+    })
 }
@@ -0,0 +1,45 @@
+//! A Decoded Image is an image that got decoded into its pixels, so native
+//! frontends can directly upload it as a texture without needing to decode
+//! the image themselves. The pixels are stored as RGBA8 (red, green, blue,
+//! alpha with each channel being a byte) with straight alpha, row by row from
+//! the top left to the bottom right.
+
+use crate::guard;
+use std::sync::Arc;
+
+/// type
+pub type DecodedImage = Arc<livesplit_core::settings::DecodedImage>;
+/// type
+pub type OwnedDecodedImage = Box<DecodedImage>;
+/// type
+pub type NullableOwnedDecodedImage = Option<OwnedDecodedImage>;
+
+/// drop
+#[no_mangle]
+pub extern "C" fn DecodedImage_drop(this: OwnedDecodedImage) {
+    guard::dispose(this);
+}
+
+/// The width of the image in pixels.
+#[no_mangle]
+pub extern "C" fn DecodedImage_width(this: &DecodedImage) -> u32 {
+    guard::call(guard::address(this), move || this.width())
+}
+
+/// The height of the image in pixels.
+#[no_mangle]
+pub extern "C" fn DecodedImage_height(this: &DecodedImage) -> u32 {
+    guard::call(guard::address(this), move || this.height())
+}
+
+/// Accesses the pixels of the image. There are 4 bytes for each pixel.
+#[no_mangle]
+pub extern "C" fn DecodedImage_pixels_ptr(this: &DecodedImage) -> *const u8 {
+    guard::call(guard::address(this), move || this.pixels().as_ptr())
+}
+
+/// Accesses the amount of bytes the pixels of the image take up.
+#[no_mangle]
+pub extern "C" fn DecodedImage_pixels_len(this: &DecodedImage) -> usize {
+    guard::call(guard::address(this), move || this.pixels().len())
+}
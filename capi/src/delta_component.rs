@@ -1,7 +1,7 @@
 //! The Delta Component is a component that shows how far ahead or behind the
 //! current attempt is compared to the chosen comparison.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::{component::OwnedComponent, key_value_component_state::OwnedKeyValueComponentState};
 use livesplit_core::{component::delta::Component as DeltaComponent, GeneralLayoutSettings, Timer};
 
@@ -11,20 +11,20 @@ pub type OwnedDeltaComponent = Box<DeltaComponent>;
 /// Creates a new Delta Component.
 #[no_mangle]
 pub extern "C" fn DeltaComponent_new() -> OwnedDeltaComponent {
-    Box::new(DeltaComponent::new())
+    guard::abort_on_unwind(move || Box::new(DeltaComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn DeltaComponent_drop(this: OwnedDeltaComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
 /// layout.
 #[no_mangle]
 pub extern "C" fn DeltaComponent_into_generic(this: OwnedDeltaComponent) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -34,13 +34,32 @@ pub extern "C" fn DeltaComponent_state_as_json(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> Json {
-    output_vec(|o| {
-        this.state(&timer.snapshot(), layout_settings)
-            .write_json(o)
-            .unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(&timer.snapshot(), layout_settings)
+                .write_json(o)
+                .unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of DeltaComponent_state_as_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DeltaComponent_state_as_json_u16(
+    this: &mut DeltaComponent,
+    timer: &Timer,
+    layout_settings: &GeneralLayoutSettings,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        DeltaComponent_state_as_json(this, timer, layout_settings),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer and the layout
 /// settings provided.
 #[no_mangle]
@@ -49,5 +68,7 @@ pub extern "C" fn DeltaComponent_state(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> OwnedKeyValueComponentState {
-    Box::new(this.state(&timer.snapshot(), layout_settings))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(&timer.snapshot(), layout_settings))
+    })
 }
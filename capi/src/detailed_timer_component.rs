@@ -3,7 +3,7 @@
 //! current segment. Other information, like segment times of up to two
 //! comparisons, the segment icon, and the segment's name, can also be shown.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::{
     component::OwnedComponent, detailed_timer_component_state::OwnedDetailedTimerComponentState,
 };
@@ -18,13 +18,13 @@ pub type OwnedDetailedTimerComponent = Box<DetailedTimerComponent>;
 /// Creates a new Detailed Timer Component.
 #[no_mangle]
 pub extern "C" fn DetailedTimerComponent_new() -> OwnedDetailedTimerComponent {
-    Box::new(DetailedTimerComponent::new())
+    guard::abort_on_unwind(move || Box::new(DetailedTimerComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn DetailedTimerComponent_drop(this: OwnedDetailedTimerComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
@@ -33,7 +33,9 @@ pub extern "C" fn DetailedTimerComponent_drop(this: OwnedDetailedTimerComponent)
 pub extern "C" fn DetailedTimerComponent_into_generic(
     this: OwnedDetailedTimerComponent,
 ) -> OwnedComponent {
-    Box::new(Box::new(*this).into())
+    guard::call_or_abort(guard::address(&*this), move || {
+        Box::new(Box::new(*this).into())
+    })
 }
 
 /// Encodes the component's state information as JSON.
@@ -44,13 +46,33 @@ pub extern "C" fn DetailedTimerComponent_state_as_json(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> Json {
-    output_vec(|o| {
-        this.state(image_cache, &timer.snapshot(), layout_settings)
-            .write_json(o)
-            .unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(image_cache, &timer.snapshot(), layout_settings)
+                .write_json(o)
+                .unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of DetailedTimerComponent_state_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponent_state_as_json_u16(
+    this: &mut DetailedTimerComponent,
+    image_cache: &mut ImageCache,
+    timer: &Timer,
+    layout_settings: &GeneralLayoutSettings,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        DetailedTimerComponent_state_as_json(this, image_cache, timer, layout_settings),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer and layout settings
 /// provided.
 #[no_mangle]
@@ -60,5 +82,7 @@ pub extern "C" fn DetailedTimerComponent_state(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> OwnedDetailedTimerComponentState {
-    Box::new(this.state(image_cache, &timer.snapshot(), layout_settings))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(image_cache, &timer.snapshot(), layout_settings))
+    })
 }
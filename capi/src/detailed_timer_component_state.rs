@@ -1,6 +1,6 @@
 //! The state object describes the information to visualize for this component.
 
-use super::{output_str, output_vec, Nullablec_char};
+use super::{guard, output_str, output_utf16_from, output_vec, Nullablec_char};
 use livesplit_core::component::detailed_timer::State as DetailedTimerComponentState;
 use std::{io::Write, os::raw::c_char, ptr};
 
@@ -10,7 +10,7 @@ pub type OwnedDetailedTimerComponentState = Box<DetailedTimerComponentState>;
 /// drop
 #[no_mangle]
 pub extern "C" fn DetailedTimerComponentState_drop(this: OwnedDetailedTimerComponentState) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// The time shown by the component's main timer without the fractional part.
@@ -18,7 +18,18 @@ pub extern "C" fn DetailedTimerComponentState_drop(this: OwnedDetailedTimerCompo
 pub extern "C" fn DetailedTimerComponentState_timer_time(
     this: &DetailedTimerComponentState,
 ) -> *const c_char {
-    output_str(&this.timer.time)
+    guard::call(guard::address(this), move || output_str(&this.timer.time))
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_timer_time. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_timer_time_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(DetailedTimerComponentState_timer_time(this), buf, buf_len)
 }
 
 /// The fractional part of the time shown by the main timer (including the dot).
@@ -26,7 +37,24 @@ pub extern "C" fn DetailedTimerComponentState_timer_time(
 pub extern "C" fn DetailedTimerComponentState_timer_fraction(
     this: &DetailedTimerComponentState,
 ) -> *const c_char {
-    output_str(&this.timer.fraction)
+    guard::call(guard::address(this), move || {
+        output_str(&this.timer.fraction)
+    })
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_timer_fraction. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_timer_fraction_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        DetailedTimerComponentState_timer_fraction(this),
+        buf,
+        buf_len,
+    )
 }
 
 /// The semantic coloring information the main timer's time carries.
@@ -34,7 +62,24 @@ pub extern "C" fn DetailedTimerComponentState_timer_fraction(
 pub extern "C" fn DetailedTimerComponentState_timer_semantic_color(
     this: &DetailedTimerComponentState,
 ) -> *const c_char {
-    output_vec(|f| write!(f, "{:?}", this.timer.semantic_color).unwrap())
+    guard::call(guard::address(this), move || {
+        output_vec(|f| write!(f, "{:?}", this.timer.semantic_color).unwrap())
+    })
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_timer_semantic_color. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_timer_semantic_color_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        DetailedTimerComponentState_timer_semantic_color(this),
+        buf,
+        buf_len,
+    )
 }
 
 /// The time shown by the component's segment timer without the fractional part.
@@ -42,7 +87,24 @@ pub extern "C" fn DetailedTimerComponentState_timer_semantic_color(
 pub extern "C" fn DetailedTimerComponentState_segment_timer_time(
     this: &DetailedTimerComponentState,
 ) -> *const c_char {
-    output_str(&this.segment_timer.time)
+    guard::call(guard::address(this), move || {
+        output_str(&this.segment_timer.time)
+    })
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_segment_timer_time. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_segment_timer_time_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        DetailedTimerComponentState_segment_timer_time(this),
+        buf,
+        buf_len,
+    )
 }
 
 /// The fractional part of the time shown by the segment timer (including the
@@ -51,7 +113,24 @@ pub extern "C" fn DetailedTimerComponentState_segment_timer_time(
 pub extern "C" fn DetailedTimerComponentState_segment_timer_fraction(
     this: &DetailedTimerComponentState,
 ) -> *const c_char {
-    output_str(&this.segment_timer.fraction)
+    guard::call(guard::address(this), move || {
+        output_str(&this.segment_timer.fraction)
+    })
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_segment_timer_fraction.
+/// See get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_segment_timer_fraction_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        DetailedTimerComponentState_segment_timer_fraction(this),
+        buf,
+        buf_len,
+    )
 }
 
 /// Returns whether the first comparison is visible.
@@ -59,7 +138,7 @@ pub extern "C" fn DetailedTimerComponentState_segment_timer_fraction(
 pub extern "C" fn DetailedTimerComponentState_comparison1_visible(
     this: &DetailedTimerComponentState,
 ) -> bool {
-    this.comparison1.is_some()
+    guard::call(guard::address(this), move || this.comparison1.is_some())
 }
 
 /// Returns the name of the first comparison. You may not call this if the first
@@ -68,12 +147,29 @@ pub extern "C" fn DetailedTimerComponentState_comparison1_visible(
 pub extern "C" fn DetailedTimerComponentState_comparison1_name(
     this: &DetailedTimerComponentState,
 ) -> *const c_char {
-    output_str(
-        &this
-            .comparison1
-            .as_ref()
-            .expect("Comparison 1 is not visible")
-            .name,
+    guard::call(guard::address(this), move || {
+        output_str(
+            &this
+                .comparison1
+                .as_ref()
+                .expect("Comparison 1 is not visible")
+                .name,
+        )
+    })
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_comparison1_name. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_comparison1_name_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        DetailedTimerComponentState_comparison1_name(this),
+        buf,
+        buf_len,
     )
 }
 
@@ -83,12 +179,29 @@ pub extern "C" fn DetailedTimerComponentState_comparison1_name(
 pub extern "C" fn DetailedTimerComponentState_comparison1_time(
     this: &DetailedTimerComponentState,
 ) -> *const c_char {
-    output_str(
-        &this
-            .comparison1
-            .as_ref()
-            .expect("Comparison 1 is not visible")
-            .time,
+    guard::call(guard::address(this), move || {
+        output_str(
+            &this
+                .comparison1
+                .as_ref()
+                .expect("Comparison 1 is not visible")
+                .time,
+        )
+    })
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_comparison1_time. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_comparison1_time_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        DetailedTimerComponentState_comparison1_time(this),
+        buf,
+        buf_len,
     )
 }
 
@@ -97,7 +210,7 @@ pub extern "C" fn DetailedTimerComponentState_comparison1_time(
 pub extern "C" fn DetailedTimerComponentState_comparison2_visible(
     this: &DetailedTimerComponentState,
 ) -> bool {
-    this.comparison2.is_some()
+    guard::call(guard::address(this), move || this.comparison2.is_some())
 }
 
 /// Returns the name of the second comparison. You may not call this if the
@@ -106,12 +219,29 @@ pub extern "C" fn DetailedTimerComponentState_comparison2_visible(
 pub extern "C" fn DetailedTimerComponentState_comparison2_name(
     this: &DetailedTimerComponentState,
 ) -> *const c_char {
-    output_str(
-        &this
-            .comparison2
-            .as_ref()
-            .expect("Comparison 2 is not visible")
-            .name,
+    guard::call(guard::address(this), move || {
+        output_str(
+            &this
+                .comparison2
+                .as_ref()
+                .expect("Comparison 2 is not visible")
+                .name,
+        )
+    })
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_comparison2_name. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_comparison2_name_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        DetailedTimerComponentState_comparison2_name(this),
+        buf,
+        buf_len,
     )
 }
 
@@ -121,12 +251,29 @@ pub extern "C" fn DetailedTimerComponentState_comparison2_name(
 pub extern "C" fn DetailedTimerComponentState_comparison2_time(
     this: &DetailedTimerComponentState,
 ) -> *const c_char {
-    output_str(
-        &this
-            .comparison2
-            .as_ref()
-            .expect("Comparison 2 is not visible")
-            .time,
+    guard::call(guard::address(this), move || {
+        output_str(
+            &this
+                .comparison2
+                .as_ref()
+                .expect("Comparison 2 is not visible")
+                .time,
+        )
+    })
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_comparison2_time. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_comparison2_time_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        DetailedTimerComponentState_comparison2_time(this),
+        buf,
+        buf_len,
     )
 }
 
@@ -137,7 +284,20 @@ pub extern "C" fn DetailedTimerComponentState_comparison2_time(
 pub extern "C" fn DetailedTimerComponentState_icon(
     this: &DetailedTimerComponentState,
 ) -> *const c_char {
-    output_str(this.icon.format_str(&mut [0; 64]))
+    guard::call(guard::address(this), move || {
+        output_str(this.icon.format_str(&mut [0; 64]))
+    })
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_icon. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_icon_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(DetailedTimerComponentState_icon(this), buf, buf_len)
 }
 
 /// The name of the segment. This may be <NULL> if it's not supposed to be
@@ -146,7 +306,20 @@ pub extern "C" fn DetailedTimerComponentState_icon(
 pub extern "C" fn DetailedTimerComponentState_segment_name(
     this: &DetailedTimerComponentState,
 ) -> *const Nullablec_char {
-    this.segment_name
-        .as_ref()
-        .map_or_else(ptr::null, output_str)
+    guard::call(guard::address(this), move || {
+        this.segment_name
+            .as_ref()
+            .map_or_else(ptr::null, output_str)
+    })
+}
+
+/// The UTF-16 variant of DetailedTimerComponentState_segment_name. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn DetailedTimerComponentState_segment_name_u16(
+    this: &DetailedTimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(DetailedTimerComponentState_segment_name(this), buf, buf_len)
 }
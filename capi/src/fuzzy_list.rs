@@ -3,7 +3,7 @@
 //! can then execute the actual fuzzy search which returns a list of all the
 //! elements found. This can be used to implement searching in a list of games.
 
-use super::{output_vec, str, Json};
+use super::{guard, output_utf16_from, output_vec, str, Json, Utf16Param};
 use livesplit_core::run::editor::FuzzyList;
 use serde_json::to_writer;
 use std::os::raw::c_char;
@@ -14,19 +14,32 @@ pub type OwnedFuzzyList = Box<FuzzyList>;
 /// Creates a new Fuzzy List.
 #[no_mangle]
 pub extern "C" fn FuzzyList_new() -> OwnedFuzzyList {
-    Box::new(FuzzyList::new())
+    guard::abort_on_unwind(move || Box::new(FuzzyList::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn FuzzyList_drop(this: OwnedFuzzyList) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Adds a new element to the list.
 #[no_mangle]
 pub unsafe extern "C" fn FuzzyList_push(this: &mut FuzzyList, text: *const c_char) {
-    this.push(str(text));
+    guard::call(guard::address(this), move || {
+        this.push(str(text));
+    })
+}
+
+/// The UTF-16 variant of FuzzyList_push. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn FuzzyList_push_u16(
+    this: &mut FuzzyList,
+    text: *const u16,
+    text_len: usize,
+) {
+    FuzzyList_push(this, Utf16Param::new(text, text_len).as_ptr())
 }
 
 /// Searches for the pattern provided in the list. A list of all the
@@ -38,7 +51,27 @@ pub unsafe extern "C" fn FuzzyList_search(
     pattern: *const c_char,
     max: usize,
 ) -> Json {
-    output_vec(|o| {
-        to_writer(o, &this.search(str(pattern), max)).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            to_writer(o, &this.search(str(pattern), max)).unwrap();
+        })
     })
 }
+
+/// The UTF-16 variant of FuzzyList_search. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn FuzzyList_search_u16(
+    this: &FuzzyList,
+    pattern: *const u16,
+    pattern_len: usize,
+    max: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        FuzzyList_search(this, Utf16Param::new(pattern, pattern_len).as_ptr(), max),
+        buf,
+        buf_len,
+    )
+}
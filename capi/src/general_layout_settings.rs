@@ -1,5 +1,6 @@
 //! The general settings of the layout that apply to all components.
 
+use crate::guard;
 use livesplit_core::GeneralLayoutSettings;
 
 /// type
@@ -8,11 +9,11 @@ pub type OwnedGeneralLayoutSettings = Box<GeneralLayoutSettings>;
 /// Creates a default general layout settings configuration.
 #[no_mangle]
 pub extern "C" fn GeneralLayoutSettings_default() -> OwnedGeneralLayoutSettings {
-    Default::default()
+    guard::abort_on_unwind(Default::default)
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn GeneralLayoutSettings_drop(this: OwnedGeneralLayoutSettings) {
-    drop(this);
+    guard::dispose(this);
 }
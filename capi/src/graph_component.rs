@@ -2,7 +2,7 @@
 //! behind the chosen comparison throughout the whole attempt. All the
 //! individual deltas are shown as points in a graph.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::component::OwnedComponent;
 use crate::graph_component_state::OwnedGraphComponentState;
 use livesplit_core::component::graph::Component as GraphComponent;
@@ -14,20 +14,20 @@ pub type OwnedGraphComponent = Box<GraphComponent>;
 /// Creates a new Graph Component.
 #[no_mangle]
 pub extern "C" fn GraphComponent_new() -> OwnedGraphComponent {
-    Box::new(GraphComponent::new())
+    guard::abort_on_unwind(move || Box::new(GraphComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn GraphComponent_drop(this: OwnedGraphComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
 /// layout.
 #[no_mangle]
 pub extern "C" fn GraphComponent_into_generic(this: OwnedGraphComponent) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -37,13 +37,32 @@ pub extern "C" fn GraphComponent_state_as_json(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> Json {
-    output_vec(|o| {
-        this.state(&timer.snapshot(), layout_settings)
-            .write_json(o)
-            .unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(&timer.snapshot(), layout_settings)
+                .write_json(o)
+                .unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of GraphComponent_state_as_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn GraphComponent_state_as_json_u16(
+    this: &GraphComponent,
+    timer: &Timer,
+    layout_settings: &GeneralLayoutSettings,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        GraphComponent_state_as_json(this, timer, layout_settings),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer and layout settings
 /// provided.
 #[no_mangle]
@@ -52,5 +71,7 @@ pub extern "C" fn GraphComponent_state(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> OwnedGraphComponentState {
-    Box::new(this.state(&timer.snapshot(), layout_settings))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(&timer.snapshot(), layout_settings))
+    })
 }
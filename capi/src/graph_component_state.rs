@@ -1,6 +1,7 @@
 //! The state object describes the information to visualize for this component.
 //! All the coordinates are in the range 0..1.
 
+use crate::guard;
 use livesplit_core::component::graph::State as GraphComponentState;
 
 /// type
@@ -9,7 +10,7 @@ pub type OwnedGraphComponentState = Box<GraphComponentState>;
 /// drop
 #[no_mangle]
 pub extern "C" fn GraphComponentState_drop(this: OwnedGraphComponentState) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Returns the amount of points to visualize. Connect all of them to visualize
@@ -18,21 +19,21 @@ pub extern "C" fn GraphComponentState_drop(this: OwnedGraphComponentState) {
 /// color to visualize the region beneath that graph segment.
 #[no_mangle]
 pub extern "C" fn GraphComponentState_points_len(this: &GraphComponentState) -> usize {
-    this.points.len()
+    guard::call(guard::address(this), move || this.points.len())
 }
 
 /// Returns the x coordinate of the point specified. You may not provide an out
 /// of bounds index.
 #[no_mangle]
 pub extern "C" fn GraphComponentState_point_x(this: &GraphComponentState, index: usize) -> f32 {
-    this.points[index].x
+    guard::call(guard::address(this), move || this.points[index].x)
 }
 
 /// Returns the y coordinate of the point specified. You may not provide an out
 /// of bounds index.
 #[no_mangle]
 pub extern "C" fn GraphComponentState_point_y(this: &GraphComponentState, index: usize) -> f32 {
-    this.points[index].y
+    guard::call(guard::address(this), move || this.points[index].y)
 }
 
 /// Describes whether the segment the point specified is visualizing achieved a
@@ -43,7 +44,9 @@ pub extern "C" fn GraphComponentState_point_is_best_segment(
     this: &GraphComponentState,
     index: usize,
 ) -> bool {
-    this.points[index].is_best_segment
+    guard::call(guard::address(this), move || {
+        this.points[index].is_best_segment
+    })
 }
 
 /// Describes how many horizontal grid lines to visualize.
@@ -51,7 +54,9 @@ pub extern "C" fn GraphComponentState_point_is_best_segment(
 pub extern "C" fn GraphComponentState_horizontal_grid_lines_len(
     this: &GraphComponentState,
 ) -> usize {
-    this.horizontal_grid_lines.len()
+    guard::call(guard::address(this), move || {
+        this.horizontal_grid_lines.len()
+    })
 }
 
 /// Accesses the y coordinate of the horizontal grid line specified. You may not
@@ -61,13 +66,15 @@ pub extern "C" fn GraphComponentState_horizontal_grid_line(
     this: &GraphComponentState,
     index: usize,
 ) -> f32 {
-    this.horizontal_grid_lines[index]
+    guard::call(guard::address(this), move || {
+        this.horizontal_grid_lines[index]
+    })
 }
 
 /// Describes how many vertical grid lines to visualize.
 #[no_mangle]
 pub extern "C" fn GraphComponentState_vertical_grid_lines_len(this: &GraphComponentState) -> usize {
-    this.vertical_grid_lines.len()
+    guard::call(guard::address(this), move || this.vertical_grid_lines.len())
 }
 
 /// Accesses the x coordinate of the vertical grid line specified. You may not
@@ -77,14 +84,16 @@ pub extern "C" fn GraphComponentState_vertical_grid_line(
     this: &GraphComponentState,
     index: usize,
 ) -> f32 {
-    this.vertical_grid_lines[index]
+    guard::call(guard::address(this), move || {
+        this.vertical_grid_lines[index]
+    })
 }
 
 /// The y coordinate that separates the region that shows the times that are
 /// ahead of the comparison and those that are behind.
 #[no_mangle]
 pub extern "C" fn GraphComponentState_middle(this: &GraphComponentState) -> f32 {
-    this.middle
+    guard::call(guard::address(this), move || this.middle)
 }
 
 /// If the live delta is active, the last point is to be interpreted as a
@@ -92,7 +101,7 @@ pub extern "C" fn GraphComponentState_middle(this: &GraphComponentState) -> f32
 /// color to visualize the region beneath that graph segment.
 #[no_mangle]
 pub extern "C" fn GraphComponentState_is_live_delta_active(this: &GraphComponentState) -> bool {
-    this.is_live_delta_active
+    guard::call(guard::address(this), move || this.is_live_delta_active)
 }
 
 /// Describes whether the graph is flipped vertically. For visualizing the
@@ -100,5 +109,5 @@ pub extern "C" fn GraphComponentState_is_live_delta_active(this: &GraphComponent
 /// entirely encoded into the other variables.
 #[no_mangle]
 pub extern "C" fn GraphComponentState_is_flipped(this: &GraphComponentState) -> bool {
-    this.is_flipped
+    guard::call(guard::address(this), move || this.is_flipped)
 }
@@ -0,0 +1,204 @@
+//! Every function of the C API is guarded against panics, so a panic inside
+//! livesplit-core doesn't unwind into the caller, which would abort the host
+//! application. Instead the panic is stored as the Last Error and a fallback
+//! value is returned, such as <NULL>, false or 0. The object the function got
+//! called on is poisoned by the panic, as it may be left in an inconsistent
+//! state. Calling any further function on a poisoned object fails without doing
+//! anything, except for disposing it, which is still possible. Objects are
+//! poisoned by their address, so whenever an object is disposed or ownership of
+//! it is passed to a function, it is no longer considered poisoned, as its
+//! memory may be reused by another object.
+//!
+//! Functions that can't return a fallback value, because they return a
+//! reference or an object that is not nullable, are not guarded. They abort the
+//! process if they panic or get called on a poisoned object.
+
+use crate::last_error::{self, ErrorCode};
+use livesplit_core::{TimerPhase, TimingMethod};
+use std::{
+    any::Any,
+    collections::HashSet,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    process,
+    sync::{
+        atomic::{self, AtomicBool},
+        Mutex,
+    },
+};
+
+static POISONED: Mutex<Option<HashSet<usize>>> = Mutex::new(None);
+static ANY_POISONED: AtomicBool = AtomicBool::new(false);
+
+/// A value that is returned by a function of the C API if it panicked.
+pub(crate) trait Fallback {
+    fn fallback() -> Self;
+}
+
+macro_rules! default_fallback {
+    ($($ty:ty),*) => {
+        $(impl Fallback for $ty {
+            fn fallback() -> Self {
+                Self::default()
+            }
+        })*
+    };
+}
+
+default_fallback!((), bool, u8, u32, u64, usize, i32, i64, isize, f32, f64);
+
+impl<T> Fallback for *const T {
+    fn fallback() -> Self {
+        std::ptr::null()
+    }
+}
+
+impl<T> Fallback for *mut T {
+    fn fallback() -> Self {
+        std::ptr::null_mut()
+    }
+}
+
+impl<T> Fallback for Option<T> {
+    fn fallback() -> Self {
+        None
+    }
+}
+
+impl Fallback for TimingMethod {
+    fn fallback() -> Self {
+        TimingMethod::RealTime
+    }
+}
+
+impl Fallback for TimerPhase {
+    fn fallback() -> Self {
+        TimerPhase::NotRunning
+    }
+}
+
+/// Returns the address of the object a function gets called on, which is
+/// used to poison it. Static functions use 0 as the address.
+pub(crate) fn address<T: ?Sized>(object: &T) -> usize {
+    object as *const T as *const () as usize
+}
+
+/// Calls the function, catching any panic that occurs. If the function
+/// panics, the object at the address provided gets poisoned and the fallback
+/// value is returned. If the object is already poisoned, the function isn't
+/// called at all. Any objects the function would've taken ownership of are
+/// leaked in that case.
+pub(crate) fn call<T: Fallback>(address: usize, f: impl FnOnce() -> T) -> T {
+    if address != 0 && is_poisoned(address) {
+        return reject(f);
+    }
+
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => value,
+        Err(payload) => {
+            if address != 0 {
+                ANY_POISONED.store(true, atomic::Ordering::Release);
+                POISONED
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get_or_insert_with(HashSet::new)
+                    .insert(address);
+            }
+            set_panic(payload);
+            T::fallback()
+        }
+    }
+}
+
+/// Calls a function that takes ownership of the object at the address
+/// provided. Just like with [`call`], the function isn't called if the object
+/// is poisoned. A panic doesn't poison the object though, as it's consumed by
+/// the function either way.
+pub(crate) fn call_consuming<T: Fallback>(address: usize, f: impl FnOnce() -> T) -> T {
+    if is_poisoned(address) {
+        return reject(f);
+    }
+    call(0, f)
+}
+
+/// Marks the object as consumed by a function it got passed to. The object
+/// gets moved or dropped by the function, so it is no longer considered
+/// poisoned.
+pub(crate) fn consume<T: ?Sized>(object: &T) {
+    unpoison(address(object));
+}
+
+/// Disposes the object, catching any panic that occurs while dropping it. This
+/// is possible even if the object is poisoned.
+pub(crate) fn dispose<T: ?Sized>(object: Box<T>) {
+    let address = address(&*object);
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(move || drop(object))) {
+        set_panic(payload);
+    }
+    unpoison(address);
+}
+
+/// Calls a function that returns an object that is not nullable or a
+/// reference. There is no value that could be returned in place of it, so the
+/// function isn't guarded. Instead the process is aborted if the function
+/// panics or if the object at the address provided is poisoned.
+pub(crate) fn call_or_abort<T>(address: usize, f: impl FnOnce() -> T) -> T {
+    if is_poisoned(address) {
+        process::abort();
+    }
+    abort_on_unwind(f)
+}
+
+/// Calls the function and aborts the process if it panics. This is necessary
+/// if unwinding would leave memory owned by the caller in an invalid state.
+pub(crate) fn abort_on_unwind<T>(f: impl FnOnce() -> T) -> T {
+    struct Bomb;
+
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            process::abort();
+        }
+    }
+
+    let bomb = Bomb;
+    let value = f();
+    mem::forget(bomb);
+    value
+}
+
+fn reject<T: Fallback>(f: impl FnOnce() -> T) -> T {
+    mem::forget(f);
+    last_error::set(
+        ErrorCode::Poisoned,
+        "The object is poisoned, as a panic occurred while it was in use.",
+    );
+    T::fallback()
+}
+
+fn unpoison(address: usize) {
+    if ANY_POISONED.load(atomic::Ordering::Acquire) {
+        if let Some(poisoned) = &mut *POISONED.lock().unwrap_or_else(|e| e.into_inner()) {
+            poisoned.remove(&address);
+        }
+    }
+}
+
+fn is_poisoned(address: usize) -> bool {
+    ANY_POISONED.load(atomic::Ordering::Acquire)
+        && POISONED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|poisoned| poisoned.contains(&address))
+}
+
+fn set_panic(payload: Box<dyn Any + Send>) {
+    let message: &str = if let Some(&message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "A panic occurred."
+    };
+    last_error::set(ErrorCode::Panic, message);
+}
@@ -0,0 +1,210 @@
+//! Handles provide an alternative way for bindings to refer to objects. Instead
+//! of holding on to the raw pointer of an object, a binding can register the
+//! pointer and only keep the returned handle around. Every handle is
+//! generational, so once it got unregistered, resolving it again fails instead
+//! of yielding a dangling pointer. This allows bindings for memory unsafe host
+//! languages to turn a use after free into an error. A handle of 0 is never
+//! valid.
+//!
+//! A binding opting into this acquires the handle before each call, passes the
+//! address it got to the function and releases the handle once the function
+//! returned. While a handle is acquired, unregistering it waits until it got
+//! released, so the object can't be dropped while a call is still using it.
+//! The binding unregisters the handle when dropping the object and only drops
+//! the object through the address returned by that.
+
+use crate::{
+    guard,
+    last_error::{self, ErrorCode},
+};
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+static HANDLES: Mutex<Table> = Mutex::new(Table {
+    slots: Vec::new(),
+    free: Vec::new(),
+});
+static RELEASED: Condvar = Condvar::new();
+
+struct Table {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+struct Slot {
+    generation: u32,
+    address: usize,
+    acquired: u32,
+    unregistering: bool,
+}
+
+impl Table {
+    fn insert(&mut self, address: usize) -> u64 {
+        let index = if let Some(index) = self.free.pop() {
+            self.slots[index as usize].address = address;
+            index
+        } else {
+            self.slots.push(Slot {
+                generation: 1,
+                address,
+                acquired: 0,
+                unregistering: false,
+            });
+            (self.slots.len() - 1) as u32
+        };
+        let generation = self.slots[index as usize].generation;
+        ((generation as u64) << 32) | index as u64
+    }
+
+    /// Looks up the slot the handle refers to, even if the handle is currently
+    /// being unregistered.
+    fn slot_mut(&mut self, handle: u64) -> Option<&mut Slot> {
+        let slot = self.slots.get_mut(handle as u32 as usize)?;
+        if slot.generation == (handle >> 32) as u32 && slot.address != 0 {
+            Some(slot)
+        } else {
+            None
+        }
+    }
+
+    fn get_mut(&mut self, handle: u64) -> Option<&mut Slot> {
+        self.slot_mut(handle).filter(|slot| !slot.unregistering)
+    }
+}
+
+fn table() -> MutexGuard<'static, Table> {
+    HANDLES.lock().unwrap()
+}
+
+fn stale(address: Option<usize>) -> usize {
+    last_error::track(
+        ErrorCode::StaleHandle,
+        address.ok_or("The handle is stale."),
+    )
+    .unwrap_or(0)
+}
+
+/// Registers the address of an object and returns a handle referring to it.
+/// Registering a null address returns 0.
+#[no_mangle]
+pub extern "C" fn Handles_register(address: usize) -> u64 {
+    guard::call(0, move || {
+        if address == 0 {
+            return 0;
+        }
+        table().insert(address)
+    })
+}
+
+/// Acquires the handle and returns the address of the object it refers to. The
+/// object stays alive until the handle gets released via Handles_release, even
+/// if another thread unregisters the handle in the meantime. Every successful
+/// acquisition needs to be released exactly once. If the handle is stale, was
+/// never valid or is being unregistered, 0 is returned and the Last Error is
+/// set accordingly.
+#[no_mangle]
+pub extern "C" fn Handles_acquire(handle: u64) -> usize {
+    guard::call(0, move || {
+        let mut table = table();
+        let address = table.get_mut(handle).map(|slot| {
+            slot.acquired += 1;
+            slot.address
+        });
+        stale(address)
+    })
+}
+
+/// Releases the handle after it got acquired via Handles_acquire. Returns
+/// <FALSE> if the handle is not acquired.
+#[no_mangle]
+pub extern "C" fn Handles_release(handle: u64) -> bool {
+    guard::call(0, move || {
+        let mut table = table();
+        let Some(slot) = table.slot_mut(handle).filter(|slot| slot.acquired != 0) else {
+            return false;
+        };
+        slot.acquired -= 1;
+        if slot.acquired == 0 && slot.unregistering {
+            RELEASED.notify_all();
+        }
+        true
+    })
+}
+
+/// Resolves the handle to the address of the object it refers to. If the
+/// handle is stale or was never valid, 0 is returned and the Last Error is set
+/// accordingly. The handle is not acquired, so another thread may unregister it
+/// and drop the object right afterwards. Use Handles_acquire instead if the
+/// object is used by more than one thread.
+#[no_mangle]
+pub extern "C" fn Handles_resolve(handle: u64) -> usize {
+    guard::call(0, move || {
+        let address = table().get_mut(handle).map(|slot| slot.address);
+        stale(address)
+    })
+}
+
+/// Unregisters the handle and returns the address of the object it referred
+/// to, so the object can be dropped. Acquiring or resolving the handle fails
+/// from now on. If the handle is currently acquired, this waits until all of
+/// its acquisitions got released, so this must not be called by a thread that
+/// acquired the handle itself. If the handle is stale or was never valid, 0 is
+/// returned and the Last Error is set accordingly.
+#[no_mangle]
+pub extern "C" fn Handles_unregister(handle: u64) -> usize {
+    guard::call(0, move || {
+        let mut table = table();
+        let Some(slot) = table.get_mut(handle) else {
+            return stale(None);
+        };
+        slot.unregistering = true;
+
+        while table
+            .slot_mut(handle)
+            .is_some_and(|slot| slot.acquired != 0)
+        {
+            table = RELEASED.wait(table).unwrap();
+        }
+
+        let index = handle as u32;
+        let slot = &mut table.slots[index as usize];
+        let address = slot.address;
+        slot.address = 0;
+        slot.unregistering = false;
+        // Generation 0 is skipped so that handle 0 never becomes valid.
+        slot.generation = slot.generation.checked_add(1).unwrap_or(1);
+        table.free.push(index);
+        address
+    })
+}
+
+/// Returns <TRUE> if the handle currently refers to an object.
+#[no_mangle]
+pub extern "C" fn Handles_is_valid(handle: u64) -> bool {
+    guard::call(0, move || table().get_mut(handle).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::last_error::LastError_code;
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn unregistering_waits_for_the_handle_to_be_released() {
+        let handle = Handles_register(0x1000);
+        assert_eq!(Handles_acquire(handle), 0x1000);
+
+        let unregister = thread::spawn(move || Handles_unregister(handle));
+        while Handles_is_valid(handle) {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(Handles_acquire(handle), 0);
+        assert!(!unregister.is_finished());
+
+        assert!(Handles_release(handle));
+        assert_eq!(unregister.join().unwrap(), 0x1000);
+        assert!(!Handles_release(handle));
+        assert_eq!(Handles_resolve(handle), 0);
+        assert_eq!(LastError_code(), ErrorCode::StaleHandle as u32);
+    }
+}
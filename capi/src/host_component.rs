@@ -0,0 +1,341 @@
+//! A Host Component is a component whose behavior is defined by the host of
+//! livesplit-core. This allows frontends to extend layouts with their own
+//! components. The host keeps the Host Component around and either registers a
+//! callback that calculates the state to visualize whenever the layout's state
+//! gets updated, or provides the state as JSON whenever it changes. Changes
+//! made to the settings through the Layout Editor are collected by the Host
+//! Component and can be queried by the host. When a layout gets loaded, its
+//! Host Components are not attached to a host, so the host needs to attach a
+//! Host Component to each of them.
+
+use super::{guard, output_str, output_utf16_from, output_vec, str, Json, Utf16Param};
+use crate::component::OwnedComponent;
+use livesplit_core::{
+    component::{
+        blank_space,
+        host::{self, Host},
+    },
+    layout::ComponentState,
+    settings::{SettingsDescription, Value},
+    timing::Snapshot,
+    Component, Layout, Time, Timer,
+};
+use std::{
+    ffi::CStr,
+    os::raw::c_char,
+    sync::{Arc, Mutex},
+};
+
+/// type
+pub struct HostComponent {
+    kind: String,
+    shared: Arc<Shared>,
+}
+
+/// type
+pub type OwnedHostComponent = Box<HostComponent>;
+/// type
+pub type HostComponentStateCallback =
+    unsafe extern "C" fn(data: usize, timer: &Timer, current_time: &Time) -> Json;
+
+struct Shared(Mutex<Inner>);
+
+#[derive(Default)]
+struct Inner {
+    name: String,
+    state: Option<ComponentState>,
+    state_callback: Option<(HostComponentStateCallback, usize)>,
+    settings_description: String,
+    settings: String,
+    changed_settings: Vec<(usize, Value)>,
+}
+
+impl Host for Shared {
+    fn name(&self) -> String {
+        self.0.lock().unwrap().name.clone()
+    }
+
+    fn state(&self, timer: &Snapshot<'_>) -> ComponentState {
+        // The lock is not held while calling the callback, so the callback is
+        // free to call the functions of the Host Component.
+        let state_callback = self.0.lock().unwrap().state_callback;
+        let calculated = state_callback.and_then(|(callback, data)| {
+            // SAFETY: The host guarantees that the callback can be called as
+            // long as it is registered.
+            let json = unsafe { callback(data, timer, &timer.current_time()) };
+            if json.is_null() {
+                return None;
+            }
+            let json = unsafe { CStr::from_ptr(json) }.to_str().ok()?;
+            serde_json::from_str::<ComponentState>(json).ok()
+        });
+
+        let mut inner = self.0.lock().unwrap();
+        if let Some(state) = calculated {
+            inner.state = Some(state);
+        }
+        inner
+            .state
+            .clone()
+            .unwrap_or_else(|| ComponentState::BlankSpace(blank_space::State::default()))
+    }
+
+    fn settings_description(&self) -> SettingsDescription {
+        serde_json::from_str(&self.0.lock().unwrap().settings_description).unwrap_or_default()
+    }
+
+    fn set_value(&self, index: usize, value: Value) {
+        self.0.lock().unwrap().changed_settings.push((index, value));
+    }
+
+    fn settings(&self) -> String {
+        self.0.lock().unwrap().settings.clone()
+    }
+
+    fn load_settings(&self, settings: &str) {
+        settings.clone_into(&mut self.0.lock().unwrap().settings);
+    }
+}
+
+/// Creates a new Host Component of the kind provided. The kind is stored in the
+/// layout, so the host can recognize its components when the layout gets
+/// loaded again.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_new(
+    kind: *const c_char,
+    name: *const c_char,
+) -> OwnedHostComponent {
+    guard::abort_on_unwind(move || {
+        Box::new(HostComponent {
+            kind: str(kind).to_owned(),
+            shared: Arc::new(Shared(Mutex::new(Inner {
+                name: str(name).to_owned(),
+                ..Default::default()
+            }))),
+        })
+    })
+}
+
+/// The UTF-16 variant of HostComponent_new. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_new_u16(
+    kind: *const u16,
+    kind_len: usize,
+    name: *const u16,
+    name_len: usize,
+) -> OwnedHostComponent {
+    HostComponent_new(
+        Utf16Param::new(kind, kind_len).as_ptr(),
+        Utf16Param::new(name, name_len).as_ptr(),
+    )
+}
+
+/// drop
+#[no_mangle]
+pub extern "C" fn HostComponent_drop(this: OwnedHostComponent) {
+    guard::dispose(this);
+}
+
+/// Creates a generic component suitable for using with a layout that is
+/// controlled by this Host Component.
+#[no_mangle]
+pub extern "C" fn HostComponent_create_generic(this: &HostComponent) -> OwnedComponent {
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(host::Component::new(this.kind.clone(), this.shared.clone()).into())
+    })
+}
+
+/// Attaches this Host Component to the first component of the same kind in the
+/// layout that is not attached to a host yet. The settings stored in the
+/// layout for that component are loaded into the Host Component. Returns
+/// <FALSE> if there is no such component.
+#[no_mangle]
+pub extern "C" fn HostComponent_attach(this: &HostComponent, layout: &mut Layout) -> bool {
+    guard::call(guard::address(this), move || {
+        let component = layout.components.iter_mut().find_map(|c| match c {
+            Component::Host(c) if !c.is_attached() && c.kind() == this.kind => Some(c),
+            _ => None,
+        });
+        if let Some(component) = component {
+            component.attach(this.shared.clone());
+            true
+        } else {
+            false
+        }
+    })
+}
+
+/// Sets the name of the component that is shown in the Layout Editor.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_set_name(this: &HostComponent, name: *const c_char) {
+    guard::call(guard::address(this), move || {
+        str(name).clone_into(&mut this.shared.0.lock().unwrap().name);
+    })
+}
+
+/// The UTF-16 variant of HostComponent_set_name. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_set_name_u16(
+    this: &HostComponent,
+    name: *const u16,
+    name_len: usize,
+) {
+    HostComponent_set_name(this, Utf16Param::new(name, name_len).as_ptr())
+}
+
+/// Sets the state to visualize, encoded as the JSON of any of the component
+/// states. The state is used until it gets replaced, so this only needs to be
+/// called whenever the state changes. If a state callback is registered, its
+/// states replace this one. Returns <FALSE> if the JSON is not a valid
+/// component state.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_set_state_json(this: &HostComponent, state: Json) -> bool {
+    guard::call(guard::address(this), move || {
+        match serde_json::from_str::<ComponentState>(str(state)) {
+            Ok(state) => {
+                this.shared.0.lock().unwrap().state = Some(state);
+                true
+            }
+            Err(_) => false,
+        }
+    })
+}
+
+/// The UTF-16 variant of HostComponent_set_state_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_set_state_json_u16(
+    this: &HostComponent,
+    state: *const u16,
+    state_len: usize,
+) -> bool {
+    HostComponent_set_state_json(this, Utf16Param::new(state, state_len).as_ptr())
+}
+
+/// Registers the callback that calculates the state to visualize whenever the
+/// state of the layout gets updated. The callback receives the data provided,
+/// the Timer and the current time the layout's state is based on. It returns
+/// the state encoded as the JSON of any of the component states. The JSON only
+/// needs to stay valid until the callback returns. If the callback returns
+/// <NULL> or invalid JSON, the previous state is visualized again. The
+/// callback is called while the layout's state is being updated, so it may be
+/// called from any thread that updates it and it must not modify the Timer or
+/// the layout. Registering a new callback replaces the previous one.
+#[no_mangle]
+pub extern "C" fn HostComponent_set_state_callback(
+    this: &HostComponent,
+    callback: HostComponentStateCallback,
+    data: usize,
+) {
+    guard::call(guard::address(this), move || {
+        this.shared.0.lock().unwrap().state_callback = Some((callback, data));
+    })
+}
+
+/// Removes the state callback, if one is registered. The last state it
+/// calculated is visualized until a new state is provided.
+#[no_mangle]
+pub extern "C" fn HostComponent_remove_state_callback(this: &HostComponent) {
+    guard::call(guard::address(this), move || {
+        this.shared.0.lock().unwrap().state_callback = None;
+    })
+}
+
+/// Sets the description of the settings that are shown in the Layout Editor,
+/// encoded as JSON. Returns <FALSE> if the JSON is not a valid settings
+/// description.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_set_settings_description_json(
+    this: &HostComponent,
+    settings_description: Json,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        let settings_description = str(settings_description);
+        if serde_json::from_str::<SettingsDescription>(settings_description).is_err() {
+            return false;
+        }
+        settings_description.clone_into(&mut this.shared.0.lock().unwrap().settings_description);
+        true
+    })
+}
+
+/// The UTF-16 variant of HostComponent_set_settings_description_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_set_settings_description_json_u16(
+    this: &HostComponent,
+    settings_description: *const u16,
+    settings_description_len: usize,
+) -> bool {
+    HostComponent_set_settings_description_json(
+        this,
+        Utf16Param::new(settings_description, settings_description_len).as_ptr(),
+    )
+}
+
+/// Sets the settings of the component that are stored in the layout. They can
+/// be encoded in any way the host likes.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_set_settings(this: &HostComponent, settings: *const c_char) {
+    guard::call(guard::address(this), move || {
+        str(settings).clone_into(&mut this.shared.0.lock().unwrap().settings);
+    })
+}
+
+/// The UTF-16 variant of HostComponent_set_settings. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_set_settings_u16(
+    this: &HostComponent,
+    settings: *const u16,
+    settings_len: usize,
+) {
+    HostComponent_set_settings(this, Utf16Param::new(settings, settings_len).as_ptr())
+}
+
+/// Accesses the settings of the component that are stored in the layout. After
+/// attaching the Host Component, these are the settings that were loaded from
+/// the layout.
+#[no_mangle]
+pub extern "C" fn HostComponent_settings(this: &HostComponent) -> *const c_char {
+    guard::call(guard::address(this), move || {
+        output_str(&this.shared.0.lock().unwrap().settings)
+    })
+}
+
+/// The UTF-16 variant of HostComponent_settings. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_settings_u16(
+    this: &HostComponent,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(HostComponent_settings(this), buf, buf_len)
+}
+
+/// Encodes the settings that were changed through the Layout Editor since the
+/// last call as a JSON array of pairs of the Settings Description index and
+/// the new value.
+#[no_mangle]
+pub extern "C" fn HostComponent_changed_settings_as_json(this: &HostComponent) -> Json {
+    guard::call(guard::address(this), move || {
+        let changed_settings = std::mem::take(&mut this.shared.0.lock().unwrap().changed_settings);
+        output_vec(|o| {
+            serde_json::to_writer(o, &changed_settings).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of HostComponent_changed_settings_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HostComponent_changed_settings_as_json_u16(
+    this: &HostComponent,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(HostComponent_changed_settings_as_json(this), buf, buf_len)
+}
@@ -1,10 +1,20 @@
 //! The configuration to use for a Hotkey System. It describes with keys to use
 //! as hotkeys for the different actions.
 
-use super::{get_file, output_vec, str, Json};
-use crate::setting_value::OwnedSettingValue;
-use livesplit_core::HotkeyConfig;
-use std::io::{BufReader, Cursor};
+use super::{
+    get_file, guard, output_str, output_utf16_from, output_vec, str, Json, Nullablec_char,
+    Utf16Param,
+};
+use crate::{
+    last_error::{self, ErrorCode},
+    setting_value::OwnedSettingValue,
+};
+use livesplit_core::{hotkey::Hotkey, HotkeyAction, HotkeyBinding, HotkeyConfig, HotkeyMode};
+use std::{
+    io::{BufReader, Cursor},
+    os::raw::c_char,
+    ptr,
+};
 
 /// type
 pub type OwnedHotkeyConfig = Box<HotkeyConfig>;
@@ -14,24 +24,41 @@ pub type NullableOwnedHotkeyConfig = Option<OwnedHotkeyConfig>;
 /// drop
 #[no_mangle]
 pub extern "C" fn HotkeyConfig_drop(this: OwnedHotkeyConfig) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Creates a new Hotkey Configuration with default settings.
 #[no_mangle]
 pub extern "C" fn HotkeyConfig_new() -> OwnedHotkeyConfig {
-    Default::default()
+    guard::abort_on_unwind(Default::default)
 }
 
 /// Encodes generic description of the settings available for the hotkey
 /// configuration and their current values as JSON.
 #[no_mangle]
 pub extern "C" fn HotkeyConfig_settings_description_as_json(this: &HotkeyConfig) -> Json {
-    output_vec(|o| {
-        serde_json::to_writer(o, &this.settings_description()).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            serde_json::to_writer(o, &this.settings_description()).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of HotkeyConfig_settings_description_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_settings_description_as_json_u16(
+    this: &HotkeyConfig,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        HotkeyConfig_settings_description_as_json(this),
+        buf,
+        buf_len,
+    )
+}
+
 /// Sets a setting's value by its index to the given value.
 ///
 /// <FALSE> is returned if a hotkey is already in use by a different action.
@@ -45,23 +72,363 @@ pub extern "C" fn HotkeyConfig_set_value(
     index: usize,
     value: OwnedSettingValue,
 ) -> bool {
-    this.set_value(index, *value).is_ok()
+    guard::consume(&*value);
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Hotkey,
+            this.set_value(index, *value)
+                .map_err(|()| "The hotkey is already in use."),
+        )
+        .is_some()
+    })
+}
+
+/// Encodes all the actions that can be bound to a hotkey without needing any
+/// additional information as JSON. Each action is encoded as a pair of the
+/// action itself and a name that can be shown to the user. The actions for
+/// switching to a specific comparison or timing method and custom actions need
+/// to be offered separately.
+#[no_mangle]
+pub extern "C" fn HotkeyConfig_available_actions_as_json() -> Json {
+    guard::call(0, move || {
+        output_vec(|o| {
+            let actions = HotkeyAction::SIMPLE_ACTIONS
+                .iter()
+                .map(|action| (action, action.name()))
+                .collect::<Vec<_>>();
+            serde_json::to_writer(o, &actions).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_available_actions_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_available_actions_as_json_u16(
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(HotkeyConfig_available_actions_as_json(), buf, buf_len)
+}
+
+/// Encodes the mode of the hotkey with the given setting index as JSON. The
+/// indices are the same as the ones of the settings description. This panics
+/// if the index is out of bounds.
+#[no_mangle]
+pub extern "C" fn HotkeyConfig_mode_as_json(this: &HotkeyConfig, index: usize) -> Json {
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            serde_json::to_writer(o, &this.mode(index)).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_mode_as_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_mode_as_json_u16(
+    this: &HotkeyConfig,
+    index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(HotkeyConfig_mode_as_json(this, index), buf, buf_len)
+}
+
+/// Sets the mode of the hotkey with the given setting index to the mode
+/// provided as JSON. The indices are the same as the ones of the settings
+/// description. Returns <FALSE> if the mode couldn't be parsed. This panics if
+/// the index is out of bounds.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_set_mode_json(
+    this: &mut HotkeyConfig,
+    index: usize,
+    mode: Json,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Parse,
+            serde_json::from_str::<HotkeyMode>(str(mode)),
+        )
+        .map(|mode| this.set_mode(index, mode))
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_set_mode_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_set_mode_json_u16(
+    this: &mut HotkeyConfig,
+    index: usize,
+    mode: *const u16,
+    mode_len: usize,
+) -> bool {
+    HotkeyConfig_set_mode_json(this, index, Utf16Param::new(mode, mode_len).as_ptr())
+}
+
+/// Encodes the additional bindings that bind hotkeys to arbitrary actions as
+/// JSON.
+#[no_mangle]
+pub extern "C" fn HotkeyConfig_bindings_as_json(this: &HotkeyConfig) -> Json {
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            serde_json::to_writer(o, &this.bindings.additional).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_bindings_as_json. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_bindings_as_json_u16(
+    this: &HotkeyConfig,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(HotkeyConfig_bindings_as_json(this), buf, buf_len)
+}
+
+/// Binds a hotkey to an arbitrary action. The binding is provided as JSON.
+/// Returns <FALSE> if the binding couldn't be parsed or if the hotkey is
+/// already in use by a different action.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_add_binding_json(
+    this: &mut HotkeyConfig,
+    binding: Json,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Parse,
+            serde_json::from_str::<HotkeyBinding>(str(binding)),
+        )
+        .is_some_and(|binding| {
+            last_error::track(
+                ErrorCode::Hotkey,
+                this.add_binding(binding)
+                    .map_err(|()| "The hotkey is already in use."),
+            )
+            .is_some()
+        })
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_add_binding_json. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_add_binding_json_u16(
+    this: &mut HotkeyConfig,
+    binding: *const u16,
+    binding_len: usize,
+) -> bool {
+    HotkeyConfig_add_binding_json(this, Utf16Param::new(binding, binding_len).as_ptr())
+}
+
+/// Removes the additional binding of the hotkey provided, if there is one.
+/// Returns <FALSE> if the hotkey couldn't be parsed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_remove_binding(
+    this: &mut HotkeyConfig,
+    hotkey: *const c_char,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Parse,
+            str(hotkey)
+                .parse::<Hotkey>()
+                .map(|hotkey| this.remove_binding(hotkey))
+                .map_err(|()| "The hotkey couldn't be parsed."),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_remove_binding. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_remove_binding_u16(
+    this: &mut HotkeyConfig,
+    hotkey: *const u16,
+    hotkey_len: usize,
+) -> bool {
+    HotkeyConfig_remove_binding(this, Utf16Param::new(hotkey, hotkey_len).as_ptr())
+}
+
+/// Encodes all the hotkeys that are in conflict with each other as a JSON
+/// array. A hotkey is in conflict if it is bound to more than one action or if
+/// its key is also used for the chord of an action. Settings dialogs can use
+/// this to highlight the hotkeys that need to be changed.
+#[no_mangle]
+pub extern "C" fn HotkeyConfig_conflicts_as_json(this: &HotkeyConfig) -> Json {
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            serde_json::to_writer(o, &this.conflicts()).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_conflicts_as_json. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_conflicts_as_json_u16(
+    this: &HotkeyConfig,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(HotkeyConfig_conflicts_as_json(this), buf, buf_len)
+}
+
+/// Encodes all the profiles that can be switched between as JSON.
+#[no_mangle]
+pub extern "C" fn HotkeyConfig_profiles_as_json(this: &HotkeyConfig) -> Json {
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            serde_json::to_writer(o, &this.profiles).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_profiles_as_json. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_profiles_as_json_u16(
+    this: &HotkeyConfig,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(HotkeyConfig_profiles_as_json(this), buf, buf_len)
+}
+
+/// Accesses the name of the profile that is currently active. <NULL> is
+/// returned if no profile is active.
+#[no_mangle]
+pub extern "C" fn HotkeyConfig_active_profile(this: &HotkeyConfig) -> *const Nullablec_char {
+    guard::call(guard::address(this), move || match &this.active_profile {
+        Some(name) => output_str(name),
+        None => ptr::null(),
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_active_profile. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_active_profile_u16(
+    this: &HotkeyConfig,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(HotkeyConfig_active_profile(this), buf, buf_len)
+}
+
+/// Stores the hotkeys currently in use as a profile with the given name and
+/// makes it the active profile. If there already is a profile with that name,
+/// its hotkeys are replaced.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_save_profile(this: &mut HotkeyConfig, name: *const c_char) {
+    guard::call(guard::address(this), move || {
+        this.save_profile(str(name));
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_save_profile. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_save_profile_u16(
+    this: &mut HotkeyConfig,
+    name: *const u16,
+    name_len: usize,
+) {
+    HotkeyConfig_save_profile(this, Utf16Param::new(name, name_len).as_ptr())
+}
+
+/// Switches to the profile with the given name. The hotkeys currently in use
+/// are stored in the profile that was active before. Returns <FALSE> if there
+/// is no profile with the given name.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_switch_profile(
+    this: &mut HotkeyConfig,
+    name: *const c_char,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::InvalidInput,
+            this.switch_profile(str(name))
+                .map_err(|()| "There is no profile with the given name."),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_switch_profile. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_switch_profile_u16(
+    this: &mut HotkeyConfig,
+    name: *const u16,
+    name_len: usize,
+) -> bool {
+    HotkeyConfig_switch_profile(this, Utf16Param::new(name, name_len).as_ptr())
+}
+
+/// Removes the profile with the given name. If it is the active profile, the
+/// hotkeys currently in use stay the same, but no profile is active anymore.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_remove_profile(this: &mut HotkeyConfig, name: *const c_char) {
+    guard::call(guard::address(this), move || {
+        this.remove_profile(str(name));
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_remove_profile. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_remove_profile_u16(
+    this: &mut HotkeyConfig,
+    name: *const u16,
+    name_len: usize,
+) {
+    HotkeyConfig_remove_profile(this, Utf16Param::new(name, name_len).as_ptr())
 }
 
 /// Encodes the hotkey configuration as JSON.
 #[no_mangle]
 pub extern "C" fn HotkeyConfig_as_json(this: &HotkeyConfig) -> Json {
-    output_vec(|o| {
-        this.write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of HotkeyConfig_as_json. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_as_json_u16(
+    this: &HotkeyConfig,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(HotkeyConfig_as_json(this), buf, buf_len)
+}
+
 /// Parses a hotkey configuration from the given JSON description. <NULL> is
 /// returned if it couldn't be parsed.
 #[no_mangle]
 pub unsafe extern "C" fn HotkeyConfig_parse_json(settings: Json) -> NullableOwnedHotkeyConfig {
-    let settings = Cursor::new(str(settings).as_bytes());
-    HotkeyConfig::from_json(settings).ok().map(Box::new)
+    guard::call(0, move || {
+        let settings = Cursor::new(str(settings).as_bytes());
+        last_error::track(ErrorCode::Parse, HotkeyConfig::from_json(settings)).map(Box::new)
+    })
+}
+
+/// The UTF-16 variant of HotkeyConfig_parse_json. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeyConfig_parse_json_u16(
+    settings: *const u16,
+    settings_len: usize,
+) -> NullableOwnedHotkeyConfig {
+    HotkeyConfig_parse_json(Utf16Param::new(settings, settings_len).as_ptr())
 }
 
 /// Attempts to parse a hotkey configuration from a given file. <NULL> is
@@ -69,9 +436,11 @@ pub unsafe extern "C" fn HotkeyConfig_parse_json(settings: Json) -> NullableOwne
 /// handle.
 #[no_mangle]
 pub unsafe extern "C" fn HotkeyConfig_parse_file_handle(handle: i64) -> NullableOwnedHotkeyConfig {
-    let file = get_file(handle);
+    guard::call(0, move || {
+        let file = get_file(handle);
 
-    let reader = BufReader::new(&*file);
+        let reader = BufReader::new(&*file);
 
-    HotkeyConfig::from_json(reader).ok().map(Box::new)
+        last_error::track(ErrorCode::Parse, HotkeyConfig::from_json(reader)).map(Box::new)
+    })
 }
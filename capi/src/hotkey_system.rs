@@ -6,8 +6,14 @@
 
 use std::{os::raw::c_char, str::FromStr};
 
-use crate::{command_sink::CommandSink, hotkey_config::OwnedHotkeyConfig, output_str, str};
-use livesplit_core::hotkey::KeyCode;
+use crate::{
+    command_sink::CommandSink,
+    guard,
+    hotkey_config::OwnedHotkeyConfig,
+    last_error::{self, ErrorCode},
+    output_str, output_utf16_from, str, Utf16Param,
+};
+use livesplit_core::{hotkey::KeyCode, SwitchProfileError};
 
 type HotkeySystem = livesplit_core::HotkeySystem<CommandSink>;
 
@@ -19,7 +25,9 @@ pub type NullableOwnedHotkeySystem = Option<OwnedHotkeySystem>;
 /// Creates a new Hotkey System for a Timer with the default hotkeys.
 #[no_mangle]
 pub extern "C" fn HotkeySystem_new(command_sink: &CommandSink) -> NullableOwnedHotkeySystem {
-    HotkeySystem::new(command_sink.clone()).ok().map(Box::new)
+    guard::call(0, move || {
+        last_error::track(ErrorCode::Hotkey, HotkeySystem::new(command_sink.clone())).map(Box::new)
+    })
 }
 
 /// Creates a new Hotkey System for a Timer with a custom configuration for the
@@ -29,35 +37,46 @@ pub extern "C" fn HotkeySystem_with_config(
     command_sink: &CommandSink,
     config: OwnedHotkeyConfig,
 ) -> NullableOwnedHotkeySystem {
-    HotkeySystem::with_config(command_sink.clone(), *config)
-        .ok()
+    guard::consume(&*config);
+    guard::call(0, move || {
+        last_error::track(
+            ErrorCode::Hotkey,
+            HotkeySystem::with_config(command_sink.clone(), *config),
+        )
         .map(Box::new)
+    })
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn HotkeySystem_drop(this: OwnedHotkeySystem) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Deactivates the Hotkey System. No hotkeys will go through until it gets
 /// activated again. If it's already deactivated, nothing happens.
 #[no_mangle]
 pub extern "C" fn HotkeySystem_deactivate(this: &mut HotkeySystem) -> bool {
-    this.deactivate().is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(ErrorCode::Hotkey, this.deactivate()).is_some()
+    })
 }
 
 /// Activates a previously deactivated Hotkey System. If it's already
 /// active, nothing happens.
 #[no_mangle]
 pub extern "C" fn HotkeySystem_activate(this: &mut HotkeySystem) -> bool {
-    this.activate().is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(ErrorCode::Hotkey, this.activate()).is_some()
+    })
 }
 
 /// Returns the hotkey configuration currently in use by the Hotkey System.
 #[no_mangle]
 pub extern "C" fn HotkeySystem_config(this: &HotkeySystem) -> OwnedHotkeyConfig {
-    Box::new(this.config())
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.config().clone())
+    })
 }
 
 /// Applies a new hotkey configuration to the Hotkey System. Each hotkey is
@@ -69,7 +88,69 @@ pub extern "C" fn HotkeySystem_set_config(
     this: &mut HotkeySystem,
     config: OwnedHotkeyConfig,
 ) -> bool {
-    this.set_config(*config).is_ok()
+    guard::consume(&*config);
+    guard::call(guard::address(this), move || {
+        last_error::track(ErrorCode::Hotkey, this.set_config(*config)).is_some()
+    })
+}
+
+/// Switches to the hotkey profile with the given name. The hotkeys currently in
+/// use are stored in the profile that was active before. Returns <FALSE> if
+/// there is no profile with the given name or if the hotkeys of the profile
+/// couldn't be registered. In that case the previous hotkeys stay in use.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeySystem_switch_profile(
+    this: &mut HotkeySystem,
+    name: *const c_char,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        let result = this.switch_profile(str(name));
+        let code = match result {
+            Err(SwitchProfileError::UnknownProfile) => ErrorCode::InvalidInput,
+            _ => ErrorCode::Hotkey,
+        };
+        last_error::track(code, result).is_some()
+    })
+}
+
+/// The UTF-16 variant of HotkeySystem_switch_profile. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeySystem_switch_profile_u16(
+    this: &mut HotkeySystem,
+    name: *const u16,
+    name_len: usize,
+) -> bool {
+    HotkeySystem_switch_profile(this, Utf16Param::new(name, name_len).as_ptr())
+}
+
+/// Switches to the hotkey profile that is meant to be used for the game with
+/// the given name. This is meant to be called whenever a different Run is
+/// loaded. If there is no such profile, the hotkeys stay the same. Returns
+/// <FALSE> if the hotkeys of the profile couldn't be registered.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeySystem_switch_profile_for_game(
+    this: &mut HotkeySystem,
+    game_name: *const c_char,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Hotkey,
+            this.switch_profile_for_game(str(game_name)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of HotkeySystem_switch_profile_for_game. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeySystem_switch_profile_for_game_u16(
+    this: &mut HotkeySystem,
+    game_name: *const u16,
+    game_name_len: usize,
+) -> bool {
+    HotkeySystem_switch_profile_for_game(this, Utf16Param::new(game_name, game_name_len).as_ptr())
 }
 
 /// Resolves the key according to the current keyboard layout.
@@ -78,9 +159,28 @@ pub unsafe extern "C" fn HotkeySystem_resolve(
     this: &HotkeySystem,
     key_code: *const c_char,
 ) -> *const c_char {
-    let name = str(key_code);
-    match KeyCode::from_str(name) {
-        Ok(key_code) => output_str(this.resolve(key_code)),
-        _ => output_str(name),
-    }
+    guard::call(guard::address(this), move || {
+        let name = str(key_code);
+        match KeyCode::from_str(name) {
+            Ok(key_code) => output_str(this.resolve(key_code)),
+            _ => output_str(name),
+        }
+    })
+}
+
+/// The UTF-16 variant of HotkeySystem_resolve. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn HotkeySystem_resolve_u16(
+    this: &HotkeySystem,
+    key_code: *const u16,
+    key_code_len: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        HotkeySystem_resolve(this, Utf16Param::new(key_code, key_code_len).as_ptr()),
+        buf,
+        buf_len,
+    )
 }
@@ -8,7 +8,10 @@ use std::{ffi::c_char, ptr, str::FromStr};
 
 use livesplit_core::settings::{HasImageId, Image, ImageCache, ImageId};
 
-use crate::{output_str, slice, str};
+use crate::{
+    decoded_image::NullableOwnedDecodedImage, guard, output_str, output_utf16_from, slice, str,
+    Utf16Param,
+};
 
 /// type
 pub type OwnedImageCache = Box<ImageCache>;
@@ -18,13 +21,13 @@ pub type NullableOwnedImageCache = Option<OwnedImageCache>;
 /// Creates a new image cache.
 #[no_mangle]
 pub extern "C" fn ImageCache_new() -> OwnedImageCache {
-    Box::new(ImageCache::new())
+    guard::abort_on_unwind(move || Box::new(ImageCache::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn ImageCache_drop(this: OwnedImageCache) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Looks up an image in the cache based on its image ID and returns a pointer
@@ -37,12 +40,25 @@ pub unsafe extern "C" fn ImageCache_lookup_data_ptr(
     this: &ImageCache,
     key: *const c_char,
 ) -> *const u8 {
-    ImageId::from_str(str(key))
-        .ok()
-        .and_then(|key| this.lookup(&key))
-        .filter(|image| !image.is_empty())
-        .map(|image| image.data().as_ptr())
-        .unwrap_or(ptr::null())
+    guard::call(guard::address(this), move || {
+        ImageId::from_str(str(key))
+            .ok()
+            .and_then(|key| this.lookup(&key))
+            .filter(|image| !image.is_empty())
+            .map(|image| image.data().as_ptr())
+            .unwrap_or(ptr::null())
+    })
+}
+
+/// The UTF-16 variant of ImageCache_lookup_data_ptr. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn ImageCache_lookup_data_ptr_u16(
+    this: &ImageCache,
+    key: *const u16,
+    key_len: usize,
+) -> *const u8 {
+    ImageCache_lookup_data_ptr(this, Utf16Param::new(key, key_len).as_ptr())
 }
 
 /// Looks up an image in the cache based on its image ID and returns its byte
@@ -53,11 +69,56 @@ pub unsafe extern "C" fn ImageCache_lookup_data_len(
     this: &ImageCache,
     key: *const c_char,
 ) -> usize {
-    ImageId::from_str(str(key))
-        .ok()
-        .and_then(|key| this.lookup(&key))
-        .map(|image| image.data().len())
-        .unwrap_or_default()
+    guard::call(guard::address(this), move || {
+        ImageId::from_str(str(key))
+            .ok()
+            .and_then(|key| this.lookup(&key))
+            .map(|image| image.data().len())
+            .unwrap_or_default()
+    })
+}
+
+/// The UTF-16 variant of ImageCache_lookup_data_len. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn ImageCache_lookup_data_len_u16(
+    this: &ImageCache,
+    key: *const u16,
+    key_len: usize,
+) -> usize {
+    ImageCache_lookup_data_len(this, Utf16Param::new(key, key_len).as_ptr())
+}
+
+/// Looks up an image in the cache based on its image ID and decodes it into
+/// its pixels, scaled down to fit into a square of the maximum size provided.
+/// The decoded image is cached, so this can be called on every frame. If the
+/// image is not in the cache or it couldn't be decoded, <NULL> is returned.
+/// This does not mark the image as visited.
+#[no_mangle]
+pub unsafe extern "C" fn ImageCache_lookup_decoded(
+    this: &ImageCache,
+    key: *const c_char,
+    max_size: u32,
+) -> NullableOwnedDecodedImage {
+    guard::call(guard::address(this), move || {
+        ImageId::from_str(str(key))
+            .ok()
+            .and_then(|key| this.lookup(&key))
+            .and_then(|image| image.decode(max_size))
+            .map(Box::new)
+    })
+}
+
+/// The UTF-16 variant of ImageCache_lookup_decoded. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn ImageCache_lookup_decoded_u16(
+    this: &ImageCache,
+    key: *const u16,
+    key_len: usize,
+    max_size: u32,
+) -> NullableOwnedDecodedImage {
+    ImageCache_lookup_decoded(this, Utf16Param::new(key, key_len).as_ptr(), max_size)
 }
 
 /// Caches an image and returns its image ID. The image is provided as a byte
@@ -73,14 +134,30 @@ pub unsafe extern "C" fn ImageCache_cache(
     len: usize,
     is_large: bool,
 ) -> *const c_char {
-    let image = Image::new(
-        slice(data, len).into(),
-        if is_large { Image::LARGE } else { Image::ICON },
-    );
-    let image_id = *image.image_id();
-    this.cache(&image_id, || image);
-    let mut buf = [0; 64];
-    output_str(image_id.format_str(&mut buf))
+    guard::call(guard::address(this), move || {
+        let image = Image::new(
+            slice(data, len).into(),
+            if is_large { Image::LARGE } else { Image::ICON },
+        );
+        let image_id = *image.image_id();
+        this.cache(&image_id, || image);
+        let mut buf = [0; 64];
+        output_str(image_id.format_str(&mut buf))
+    })
+}
+
+/// The UTF-16 variant of ImageCache_cache. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn ImageCache_cache_u16(
+    this: &mut ImageCache,
+    data: *const u8,
+    len: usize,
+    is_large: bool,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(ImageCache_cache(this, data, len, is_large), buf, buf_len)
 }
 
 /// Runs the garbage collection of the cache. This removes images from the cache
@@ -90,5 +167,5 @@ pub unsafe extern "C" fn ImageCache_cache(
 /// visited or not. Returns the amount of images that got collected.
 #[no_mangle]
 pub extern "C" fn ImageCache_collect(this: &mut ImageCache) -> usize {
-    this.collect()
+    guard::call(guard::address(this), move || this.collect())
 }
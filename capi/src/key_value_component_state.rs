@@ -1,6 +1,6 @@
 //! The state object describes the information to visualize for a key value based component.
 
-use super::{output_str, output_vec};
+use super::{guard, output_str, output_utf16, output_utf16_from, output_vec};
 use livesplit_core::component::key_value::State as KeyValueComponentState;
 use std::io::Write;
 use std::os::raw::c_char;
@@ -11,19 +11,47 @@ pub type OwnedKeyValueComponentState = Box<KeyValueComponentState>;
 /// drop
 #[no_mangle]
 pub extern "C" fn KeyValueComponentState_drop(this: OwnedKeyValueComponentState) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// The key to visualize.
 #[no_mangle]
 pub extern "C" fn KeyValueComponentState_key(this: &KeyValueComponentState) -> *const c_char {
-    output_str(&this.key)
+    guard::call(guard::address(this), move || output_str(&this.key))
+}
+
+/// The key to visualize, written into the buffer provided as UTF-16. Returns
+/// the length of the key in UTF-16 code units. If it doesn't fit, the buffer
+/// only contains its beginning.
+#[no_mangle]
+pub unsafe extern "C" fn KeyValueComponentState_key_u16(
+    this: &KeyValueComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_utf16(buf, buf_len, &this.key)
+    })
 }
 
 /// The value to visualize.
 #[no_mangle]
 pub extern "C" fn KeyValueComponentState_value(this: &KeyValueComponentState) -> *const c_char {
-    output_str(&this.value)
+    guard::call(guard::address(this), move || output_str(&this.value))
+}
+
+/// The value to visualize, written into the buffer provided as UTF-16. Returns
+/// the length of the value in UTF-16 code units. If it doesn't fit, the buffer
+/// only contains its beginning.
+#[no_mangle]
+pub unsafe extern "C" fn KeyValueComponentState_value_u16(
+    this: &KeyValueComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_utf16(buf, buf_len, &this.value)
+    })
 }
 
 /// The semantic coloring information the value carries.
@@ -31,5 +59,18 @@ pub extern "C" fn KeyValueComponentState_value(this: &KeyValueComponentState) ->
 pub extern "C" fn KeyValueComponentState_semantic_color(
     this: &KeyValueComponentState,
 ) -> *const c_char {
-    output_vec(|f| write!(f, "{:?}", this.semantic_color).unwrap())
+    guard::call(guard::address(this), move || {
+        output_vec(|f| write!(f, "{:?}", this.semantic_color).unwrap())
+    })
+}
+
+/// The UTF-16 variant of KeyValueComponentState_semantic_color. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn KeyValueComponentState_semantic_color_u16(
+    this: &KeyValueComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(KeyValueComponentState_semantic_color(this), buf, buf_len)
 }
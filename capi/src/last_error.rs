@@ -0,0 +1,167 @@
+//! The Last Error describes why the most recent call of a fallible function on
+//! the current thread failed. Fallible functions only indicate a failure by
+//! returning <NULL> or false, so the Last Error can be queried afterwards to
+//! find out what went wrong. Successfully calling a fallible function clears
+//! the Last Error again.
+//!
+//! The following error codes are used:
+//!
+//! - `0`: There is no error.
+//! - `1`: The input couldn't be parsed.
+//! - `2`: The run or layout provided is empty.
+//! - `3`: The hotkeys couldn't be registered or unregistered.
+//! - `4`: The auto splitter couldn't be loaded or unloaded.
+//! - `5`: The operation got cancelled through a Cancellation Token.
+//! - `6`: The handle is stale or was never valid.
+//! - `7`: The file system couldn't be accessed.
+//! - `8`: The input got rejected, for example because a name is already in
+//!   use or an index is out of bounds.
+//! - `9`: A panic occurred inside livesplit-core.
+//! - `10`: The object is poisoned, as a panic occurred while it was in use.
+
+use super::{guard, output_str, output_utf16_from};
+use std::{cell::RefCell, fmt, os::raw::c_char};
+
+thread_local! {
+    static LAST_ERROR: RefCell<(u32, String)> = const { RefCell::new((0, String::new())) };
+}
+
+/// The categories of errors that can be stored as the Last Error.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub(crate) enum ErrorCode {
+    Parse = 1,
+    EmptyInput = 2,
+    Hotkey = 3,
+    AutoSplitter = 4,
+    Cancelled = 5,
+    StaleHandle = 6,
+    Io = 7,
+    InvalidInput = 8,
+    Panic = 9,
+    Poisoned = 10,
+}
+
+/// Stores the error as the Last Error of the current thread.
+pub(crate) fn set(code: ErrorCode, error: impl fmt::Display) {
+    LAST_ERROR.with_borrow_mut(|(last_code, message)| {
+        use std::fmt::Write;
+        *last_code = code as u32;
+        message.clear();
+        let _ = write!(message, "{error}");
+    });
+}
+
+/// Clears the Last Error of the current thread.
+pub(crate) fn clear() {
+    LAST_ERROR.with_borrow_mut(|(code, message)| {
+        *code = 0;
+        message.clear();
+    });
+}
+
+/// Stores the error as the Last Error if the result is an error and clears the
+/// Last Error otherwise.
+pub(crate) fn track<T, E: fmt::Display>(code: ErrorCode, result: Result<T, E>) -> Option<T> {
+    match result {
+        Ok(value) => {
+            clear();
+            Some(value)
+        }
+        Err(error) => {
+            set(code, error);
+            None
+        }
+    }
+}
+
+/// Returns the error code of the Last Error. If there is no error, 0 is
+/// returned.
+#[no_mangle]
+pub extern "C" fn LastError_code() -> u32 {
+    guard::call(0, move || LAST_ERROR.with_borrow(|(code, _)| *code))
+}
+
+/// Returns a message describing the Last Error. If there is no error, an empty
+/// string is returned.
+#[no_mangle]
+pub extern "C" fn LastError_message() -> *const c_char {
+    guard::call(0, move || {
+        LAST_ERROR.with_borrow(|(_, message)| output_str(message))
+    })
+}
+
+/// The UTF-16 variant of LastError_message. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn LastError_message_u16(buf: *mut u16, buf_len: usize) -> usize {
+    output_utf16_from(LastError_message(), buf, buf_len)
+}
+
+/// Clears the Last Error of the current thread.
+#[no_mangle]
+pub extern "C" fn LastError_clear() {
+    guard::call(0, move || {
+        clear();
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_run_result::{ParseRunResult_drop, ParseRunResult_parsed_successfully},
+        run::{Run_new, Run_parse, Run_push_segment},
+        run_editor::{RunEditor_add_comparison, RunEditor_close, RunEditor_new},
+        segment::Segment_new,
+    };
+    use std::{ffi::CStr, ptr};
+
+    fn message() -> String {
+        unsafe { CStr::from_ptr(LastError_message()) }
+            .to_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn parse_errors_are_tracked() {
+        let data = b"definitely not a splits file";
+        let result = unsafe { Run_parse(data.as_ptr(), data.len(), ptr::null()) };
+        assert!(!ParseRunResult_parsed_successfully(&result));
+        assert_eq!(LastError_code(), ErrorCode::Parse as u32);
+        assert!(!message().is_empty());
+        ParseRunResult_drop(result);
+
+        LastError_clear();
+        assert_eq!(LastError_code(), 0);
+        assert!(message().is_empty());
+    }
+
+    #[test]
+    fn empty_runs_are_rejected() {
+        assert!(RunEditor_new(Run_new()).is_none());
+        assert_eq!(LastError_code(), ErrorCode::EmptyInput as u32);
+    }
+
+    #[test]
+    fn success_clears_the_last_error() {
+        let mut run = Run_new();
+        unsafe { Run_push_segment(&mut run, Segment_new(c"Segment".as_ptr())) };
+        let mut editor = RunEditor_new(run).unwrap();
+
+        let name = c"Comparison".as_ptr();
+        assert!(unsafe { RunEditor_add_comparison(&mut editor, name) });
+        assert_eq!(LastError_code(), 0);
+
+        assert!(!unsafe { RunEditor_add_comparison(&mut editor, name) });
+        assert_eq!(LastError_code(), ErrorCode::InvalidInput as u32);
+        assert!(!message().is_empty());
+
+        assert!(unsafe { RunEditor_add_comparison(&mut editor, c"Other".as_ptr()) });
+        assert_eq!(LastError_code(), 0);
+        assert!(message().is_empty());
+
+        drop(RunEditor_close(editor));
+    }
+}
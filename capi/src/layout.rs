@@ -1,8 +1,15 @@
 //! A Layout allows you to combine multiple components together to visualize a
 //! variety of information the runner is interested in.
 
-use super::{get_file, output_vec, str, Json};
-use crate::{component::OwnedComponent, layout_state::OwnedLayoutState, slice};
+use super::{
+    get_file, guard, output_into_buf, output_utf16_from, output_vec, str, Json, Utf16Param,
+};
+use crate::{
+    component::OwnedComponent,
+    last_error::{self, ErrorCode},
+    layout_state::OwnedLayoutState,
+    slice,
+};
 use livesplit_core::{
     layout::{parser, LayoutSettings, LayoutState},
     settings::ImageCache,
@@ -18,7 +25,7 @@ pub type NullableOwnedLayout = Option<OwnedLayout>;
 /// Creates a new empty layout with no components.
 #[no_mangle]
 pub extern "C" fn Layout_new() -> OwnedLayout {
-    Box::new(Layout::new())
+    guard::abort_on_unwind(move || Box::new(Layout::new()))
 }
 
 /// Creates a new default layout that contains a default set of components
@@ -27,46 +34,54 @@ pub extern "C" fn Layout_new() -> OwnedLayout {
 /// future.
 #[no_mangle]
 pub extern "C" fn Layout_default_layout() -> OwnedLayout {
-    Box::new(Layout::default_layout())
+    guard::abort_on_unwind(move || Box::new(Layout::default_layout()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn Layout_drop(this: OwnedLayout) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Clones the layout.
 #[no_mangle]
 pub extern "C" fn Layout_clone(this: &Layout) -> OwnedLayout {
-    Box::new(this.clone())
+    guard::call_or_abort(guard::address(this), move || Box::new(this.clone()))
 }
 
 /// Parses a layout from the given JSON description of its settings. <NULL> is
 /// returned if it couldn't be parsed.
 #[no_mangle]
 pub unsafe extern "C" fn Layout_parse_json(settings: Json) -> NullableOwnedLayout {
-    let settings = Cursor::new(str(settings).as_bytes());
-    if let Ok(settings) = LayoutSettings::from_json(settings) {
-        Some(Box::new(Layout::from_settings(settings)))
-    } else {
-        None
-    }
+    guard::call(0, move || {
+        let settings = Cursor::new(str(settings).as_bytes());
+        last_error::track(ErrorCode::Parse, LayoutSettings::from_json(settings))
+            .map(|settings| Box::new(Layout::from_settings(settings)))
+    })
+}
+
+/// The UTF-16 variant of Layout_parse_json. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Layout_parse_json_u16(
+    settings: *const u16,
+    settings_len: usize,
+) -> NullableOwnedLayout {
+    Layout_parse_json(Utf16Param::new(settings, settings_len).as_ptr())
 }
 
 /// Attempts to parse a layout from a given file. <NULL> is returned it couldn't
 /// be parsed. This will not close the file descriptor / handle.
 #[no_mangle]
 pub unsafe extern "C" fn Layout_parse_file_handle(handle: i64) -> NullableOwnedLayout {
-    let file = get_file(handle);
+    guard::call(0, move || {
+        let file = get_file(handle);
 
-    let reader = BufReader::new(&*file);
+        let reader = BufReader::new(&*file);
 
-    if let Ok(settings) = LayoutSettings::from_json(reader) {
-        Some(Box::new(Layout::from_settings(settings)))
-    } else {
-        None
-    }
+        last_error::track(ErrorCode::Parse, LayoutSettings::from_json(reader))
+            .map(|settings| Box::new(Layout::from_settings(settings)))
+    })
 }
 
 /// Parses a layout saved by the original LiveSplit. This is lossy, as not
@@ -77,8 +92,13 @@ pub unsafe extern "C" fn Layout_parse_original_livesplit(
     data: *const u8,
     length: usize,
 ) -> NullableOwnedLayout {
-    let data = simdutf8::basic::from_utf8(slice(data, length)).ok()?;
-    Some(Box::new(parser::parse(data).ok()?))
+    guard::call(0, move || {
+        let data = last_error::track(
+            ErrorCode::Parse,
+            simdutf8::basic::from_utf8(slice(data, length)),
+        )?;
+        last_error::track(ErrorCode::Parse, parser::parse(data)).map(Box::new)
+    })
 }
 
 /// Calculates and returns the layout's state based on the timer provided.
@@ -88,7 +108,9 @@ pub extern "C" fn Layout_state(
     image_cache: &mut ImageCache,
     timer: &Timer,
 ) -> OwnedLayoutState {
-    Box::new(this.state(image_cache, &timer.snapshot()))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(image_cache, &timer.snapshot()))
+    })
 }
 
 /// Updates the layout's state based on the timer provided.
@@ -99,7 +121,9 @@ pub extern "C" fn Layout_update_state(
     image_cache: &mut ImageCache,
     timer: &Timer,
 ) {
-    this.update_state(state, image_cache, &timer.snapshot())
+    guard::call(guard::address(this), move || {
+        this.update_state(state, image_cache, &timer.snapshot())
+    })
 }
 
 /// Updates the layout's state based on the timer provided and encodes it as
@@ -111,9 +135,77 @@ pub extern "C" fn Layout_update_state_as_json(
     image_cache: &mut ImageCache,
     timer: &Timer,
 ) -> Json {
-    this.update_state(state, image_cache, &timer.snapshot());
-    output_vec(|o| {
-        state.write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        this.update_state(state, image_cache, &timer.snapshot());
+        output_vec(|o| {
+            state.write_json(o).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of Layout_update_state_as_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Layout_update_state_as_json_u16(
+    this: &mut Layout,
+    state: &mut LayoutState,
+    image_cache: &mut ImageCache,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        Layout_update_state_as_json(this, state, image_cache, timer),
+        buf,
+        buf_len,
+    )
+}
+
+/// Updates the layout's state based on the timer provided and encodes it as
+/// JSON directly into the buffer provided. The JSON is not nul-terminated. The
+/// length of the JSON is returned. If it is larger than the buffer, the buffer
+/// only contains the beginning of the JSON. The state is already updated at
+/// that point, so use LayoutState_as_json_into_buf with a larger buffer to
+/// encode it again. Reusing the same buffer for every frame avoids any
+/// allocations for the JSON.
+#[no_mangle]
+pub unsafe extern "C" fn Layout_update_state_as_json_into_buf(
+    this: &mut Layout,
+    state: &mut LayoutState,
+    image_cache: &mut ImageCache,
+    timer: &Timer,
+    buf: *mut u8,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        this.update_state(state, image_cache, &timer.snapshot());
+        output_into_buf(buf, buf_len, |o| {
+            state.write_json(o).unwrap();
+        })
+    })
+}
+
+/// Updates the layout's state based on the timer provided and encodes it as
+/// MessagePack directly into the buffer provided. The structure is the same as
+/// the one of the JSON, but it is a lot faster to encode and decode. The length
+/// of the encoded layout state is returned. If it is larger than the buffer,
+/// the buffer only contains its beginning. The state is already updated at
+/// that point, so use LayoutState_as_msgpack_into_buf with a larger buffer to
+/// encode it again.
+#[no_mangle]
+pub unsafe extern "C" fn Layout_update_state_as_msgpack_into_buf(
+    this: &mut Layout,
+    state: &mut LayoutState,
+    image_cache: &mut ImageCache,
+    timer: &Timer,
+    buf: *mut u8,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        this.update_state(state, image_cache, &timer.snapshot());
+        output_into_buf(buf, buf_len, |o| {
+            rmp_serde::encode::write_named(o, state).unwrap();
+        })
     })
 }
 
@@ -125,35 +217,70 @@ pub extern "C" fn Layout_state_as_json(
     image_cache: &mut ImageCache,
     timer: &Timer,
 ) -> Json {
-    output_vec(|o| {
-        this.state(image_cache, &timer.snapshot())
-            .write_json(o)
-            .unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(image_cache, &timer.snapshot())
+                .write_json(o)
+                .unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of Layout_state_as_json. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Layout_state_as_json_u16(
+    this: &mut Layout,
+    image_cache: &mut ImageCache,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Layout_state_as_json(this, image_cache, timer), buf, buf_len)
+}
+
 /// Encodes the settings of the layout as JSON.
 #[no_mangle]
 pub extern "C" fn Layout_settings_as_json(this: &Layout) -> Json {
-    output_vec(|o| {
-        this.settings().write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.settings().write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of Layout_settings_as_json. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Layout_settings_as_json_u16(
+    this: &Layout,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Layout_settings_as_json(this), buf, buf_len)
+}
+
 /// Adds a new component to the end of the layout.
 #[no_mangle]
 pub extern "C" fn Layout_push(this: &mut Layout, component: OwnedComponent) {
-    this.push(*component);
+    guard::consume(&*component);
+    guard::call(guard::address(this), move || {
+        this.push(*component);
+    })
 }
 
 /// Scrolls up all the components in the layout that can be scrolled up.
 #[no_mangle]
 pub extern "C" fn Layout_scroll_up(this: &mut Layout) {
-    this.scroll_up();
+    guard::call(guard::address(this), move || {
+        this.scroll_up();
+    })
 }
 
 /// Scrolls down all the components in the layout that can be scrolled down.
 #[no_mangle]
 pub extern "C" fn Layout_scroll_down(this: &mut Layout) {
-    this.scroll_down();
+    guard::call(guard::address(this), move || {
+        this.scroll_down();
+    })
 }
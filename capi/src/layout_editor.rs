@@ -3,9 +3,12 @@
 //! operations are being applied. It provides the current state of the editor as
 //! state objects that can be visualized by any kind of User Interface.
 
-use super::{output_vec, Json};
+use super::{guard, output_into_buf, output_utf16_from, output_vec, Json};
 use crate::{
-    component::OwnedComponent, layout::OwnedLayout, layout_editor_state::OwnedLayoutEditorState,
+    component::OwnedComponent,
+    last_error::{self, ErrorCode},
+    layout::OwnedLayout,
+    layout_editor_state::OwnedLayoutEditorState,
     setting_value::OwnedSettingValue,
 };
 use livesplit_core::{layout::LayoutState, settings::ImageCache, LayoutEditor, Timer};
@@ -20,7 +23,10 @@ pub type NullableOwnedLayoutEditor = Option<OwnedLayoutEditor>;
 /// that case <NULL> is returned instead.
 #[no_mangle]
 pub extern "C" fn LayoutEditor_new(layout: OwnedLayout) -> NullableOwnedLayoutEditor {
-    LayoutEditor::new(*layout).ok().map(Box::new)
+    guard::consume(&*layout);
+    guard::call(0, move || {
+        last_error::track(ErrorCode::EmptyInput, LayoutEditor::new(*layout)).map(Box::new)
+    })
 }
 
 /// Closes the Layout Editor and gives back access to the modified Layout. In
@@ -28,7 +34,7 @@ pub extern "C" fn LayoutEditor_new(layout: OwnedLayout) -> NullableOwnedLayoutEd
 /// you get here.
 #[no_mangle]
 pub extern "C" fn LayoutEditor_close(this: OwnedLayoutEditor) -> OwnedLayout {
-    Box::new((*this).close())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).close()))
 }
 
 /// Encodes the Layout Editor's state as JSON in order to visualize it.
@@ -37,8 +43,41 @@ pub extern "C" fn LayoutEditor_state_as_json(
     this: &LayoutEditor,
     image_cache: &mut ImageCache,
 ) -> Json {
-    output_vec(|o| {
-        this.state(image_cache).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(image_cache).write_json(o).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of LayoutEditor_state_as_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutEditor_state_as_json_u16(
+    this: &LayoutEditor,
+    image_cache: &mut ImageCache,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(LayoutEditor_state_as_json(this, image_cache), buf, buf_len)
+}
+
+/// Encodes the Layout Editor's state as JSON directly into the buffer provided.
+/// The JSON is not nul-terminated. The length of the JSON is returned. If it is
+/// larger than the buffer, the buffer only contains the beginning of the JSON.
+/// As long as the Layout Editor isn't modified, calling this again with a
+/// larger buffer results in the same JSON.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutEditor_state_as_json_into_buf(
+    this: &LayoutEditor,
+    image_cache: &mut ImageCache,
+    buf: *mut u8,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_into_buf(buf, buf_len, |o| {
+            this.state(image_cache).write_json(o).unwrap();
+        })
     })
 }
 
@@ -48,7 +87,9 @@ pub extern "C" fn LayoutEditor_state(
     this: &LayoutEditor,
     image_cache: &mut ImageCache,
 ) -> OwnedLayoutEditorState {
-    Box::new(this.state(image_cache))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(image_cache))
+    })
 }
 
 /// Encodes the layout's state as JSON based on the timer provided. You can use
@@ -60,13 +101,32 @@ pub extern "C" fn LayoutEditor_layout_state_as_json(
     image_cache: &mut ImageCache,
     timer: &Timer,
 ) -> Json {
-    output_vec(|o| {
-        this.layout_state(image_cache, &timer.snapshot())
-            .write_json(o)
-            .unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.layout_state(image_cache, &timer.snapshot())
+                .write_json(o)
+                .unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of LayoutEditor_layout_state_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutEditor_layout_state_as_json_u16(
+    this: &mut LayoutEditor,
+    image_cache: &mut ImageCache,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        LayoutEditor_layout_state_as_json(this, image_cache, timer),
+        buf,
+        buf_len,
+    )
+}
+
 /// Updates the layout's state based on the timer provided.
 #[no_mangle]
 pub extern "C" fn LayoutEditor_update_layout_state(
@@ -75,7 +135,9 @@ pub extern "C" fn LayoutEditor_update_layout_state(
     image_cache: &mut ImageCache,
     timer: &Timer,
 ) {
-    this.update_layout_state(state, image_cache, &timer.snapshot())
+    guard::call(guard::address(this), move || {
+        this.update_layout_state(state, image_cache, &timer.snapshot())
+    })
 }
 
 /// Updates the layout's state based on the timer provided and encodes it as
@@ -87,9 +149,52 @@ pub extern "C" fn LayoutEditor_update_layout_state_as_json(
     image_cache: &mut ImageCache,
     timer: &Timer,
 ) -> Json {
-    this.update_layout_state(state, image_cache, &timer.snapshot());
-    output_vec(|o| {
-        state.write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        this.update_layout_state(state, image_cache, &timer.snapshot());
+        output_vec(|o| {
+            state.write_json(o).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of LayoutEditor_update_layout_state_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutEditor_update_layout_state_as_json_u16(
+    this: &mut LayoutEditor,
+    state: &mut LayoutState,
+    image_cache: &mut ImageCache,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        LayoutEditor_update_layout_state_as_json(this, state, image_cache, timer),
+        buf,
+        buf_len,
+    )
+}
+
+/// Updates the layout's state based on the timer provided and encodes it as
+/// JSON directly into the buffer provided. The JSON is not nul-terminated. The
+/// length of the JSON is returned. If it is larger than the buffer, the buffer
+/// only contains the beginning of the JSON. The state is already updated at
+/// that point, so use LayoutState_as_json_into_buf with a larger buffer to
+/// encode it again.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutEditor_update_layout_state_as_json_into_buf(
+    this: &mut LayoutEditor,
+    state: &mut LayoutState,
+    image_cache: &mut ImageCache,
+    timer: &Timer,
+    buf: *mut u8,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        this.update_layout_state(state, image_cache, &timer.snapshot());
+        output_into_buf(buf, buf_len, |o| {
+            state.write_json(o).unwrap();
+        })
     })
 }
 
@@ -98,14 +203,19 @@ pub extern "C" fn LayoutEditor_update_layout_state_as_json(
 /// not provide an invalid index.
 #[no_mangle]
 pub extern "C" fn LayoutEditor_select(this: &mut LayoutEditor, index: usize) {
-    this.select(index);
+    guard::call(guard::address(this), move || {
+        this.select(index);
+    })
 }
 
 /// Adds the component provided to the end of the layout. The newly added
 /// component becomes the selected component.
 #[no_mangle]
 pub extern "C" fn LayoutEditor_add_component(this: &mut LayoutEditor, component: OwnedComponent) {
-    this.add_component(*component);
+    guard::consume(&*component);
+    guard::call(guard::address(this), move || {
+        this.add_component(*component);
+    })
 }
 
 /// Removes the currently selected component, unless there's only one
@@ -114,34 +224,89 @@ pub extern "C" fn LayoutEditor_add_component(this: &mut LayoutEditor, component:
 /// component instead.
 #[no_mangle]
 pub extern "C" fn LayoutEditor_remove_component(this: &mut LayoutEditor) {
-    this.remove_component();
+    guard::call(guard::address(this), move || {
+        this.remove_component();
+    })
 }
 
 /// Moves the selected component up, unless the first component is selected.
 #[no_mangle]
 pub extern "C" fn LayoutEditor_move_component_up(this: &mut LayoutEditor) {
-    this.move_component_up();
+    guard::call(guard::address(this), move || {
+        this.move_component_up();
+    })
 }
 
 /// Moves the selected component down, unless the last component is
 /// selected.
 #[no_mangle]
 pub extern "C" fn LayoutEditor_move_component_down(this: &mut LayoutEditor) {
-    this.move_component_down();
+    guard::call(guard::address(this), move || {
+        this.move_component_down();
+    })
 }
 
 /// Moves the selected component to the index provided. You may not provide
 /// an invalid index.
 #[no_mangle]
 pub extern "C" fn LayoutEditor_move_component(this: &mut LayoutEditor, dst_index: usize) {
-    this.move_component(dst_index);
+    guard::call(guard::address(this), move || {
+        this.move_component(dst_index);
+    })
+}
+
+/// Moves the component at the source index to the destination index. The
+/// moved component becomes the selected component. You may not provide invalid
+/// indices.
+#[no_mangle]
+pub extern "C" fn LayoutEditor_move_component_from(
+    this: &mut LayoutEditor,
+    src_index: usize,
+    dst_index: usize,
+) {
+    guard::call(guard::address(this), move || {
+        this.move_component_from(src_index, dst_index);
+    })
+}
+
+/// Checks if the component at the source index can be dropped into the gap
+/// with the given index when reordering the components via drag and drop. The
+/// gaps are numbered from 0, which is before the first component, up to the
+/// number of components, which is after the last component. The gaps directly
+/// before and after the component itself are not valid drop targets, as
+/// dropping it there doesn't change anything.
+#[no_mangle]
+pub extern "C" fn LayoutEditor_can_drop_component(
+    this: &LayoutEditor,
+    src_index: usize,
+    gap_index: usize,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        this.can_drop_component(src_index, gap_index)
+    })
+}
+
+/// Drops the component at the source index into the gap with the given index.
+/// The dropped component becomes the selected component. Nothing happens if
+/// the gap is not a valid drop target.
+#[no_mangle]
+pub extern "C" fn LayoutEditor_drop_component(
+    this: &mut LayoutEditor,
+    src_index: usize,
+    gap_index: usize,
+) {
+    guard::call(guard::address(this), move || {
+        this.drop_component(src_index, gap_index);
+    })
 }
 
 /// Duplicates the currently selected component. The copy gets placed right
 /// after the selected component and becomes the newly selected component.
 #[no_mangle]
 pub extern "C" fn LayoutEditor_duplicate_component(this: &mut LayoutEditor) {
-    this.duplicate_component();
+    guard::call(guard::address(this), move || {
+        this.duplicate_component();
+    })
 }
 
 /// Sets a setting's value of the selected component by its setting index
@@ -156,7 +321,10 @@ pub extern "C" fn LayoutEditor_set_component_settings_value(
     index: usize,
     value: OwnedSettingValue,
 ) {
-    this.set_component_settings_value(index, *value);
+    guard::consume(&*value);
+    guard::call(guard::address(this), move || {
+        this.set_component_settings_value(index, *value);
+    })
 }
 
 /// Sets a setting's value of the general settings by its setting index to
@@ -172,5 +340,8 @@ pub extern "C" fn LayoutEditor_set_general_settings_value(
     value: OwnedSettingValue,
     image_cache: &ImageCache,
 ) {
-    this.set_general_settings_value(index, *value, image_cache);
+    guard::consume(&*value);
+    guard::call(guard::address(this), move || {
+        this.set_general_settings_value(index, *value, image_cache);
+    })
 }
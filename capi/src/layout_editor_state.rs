@@ -1,5 +1,5 @@
 //! Represents the current state of the Layout Editor in order to visualize it properly.
-use crate::{c_char, output_str};
+use crate::{c_char, guard, output_str, output_utf16_from};
 use livesplit_core::{layout::editor::State as LayoutEditorState, settings::Value as SettingValue};
 
 /// type
@@ -10,13 +10,13 @@ pub type NullableOwnedLayoutEditorState = Option<OwnedLayoutEditorState>;
 /// drop
 #[no_mangle]
 pub extern "C" fn LayoutEditorState_drop(this: OwnedLayoutEditorState) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Returns the number of components in the layout.
 #[no_mangle]
 pub extern "C" fn LayoutEditorState_component_len(this: &LayoutEditorState) -> usize {
-    this.components.len()
+    guard::call(guard::address(this), move || this.components.len())
 }
 
 /// Returns the name of the component at the specified index.
@@ -25,7 +25,21 @@ pub extern "C" fn LayoutEditorState_component_text(
     this: &LayoutEditorState,
     index: usize,
 ) -> *const c_char {
-    output_str(&this.components[index])
+    guard::call(guard::address(this), move || {
+        output_str(&this.components[index])
+    })
+}
+
+/// The UTF-16 variant of LayoutEditorState_component_text. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutEditorState_component_text_u16(
+    this: &LayoutEditorState,
+    index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(LayoutEditorState_component_text(this, index), buf, buf_len)
 }
 
 /// Returns a bitfield corresponding to which buttons are active.
@@ -37,15 +51,17 @@ pub extern "C" fn LayoutEditorState_component_text(
 /// * `0x01` - Can move the current component down
 #[no_mangle]
 pub extern "C" fn LayoutEditorState_buttons(this: &LayoutEditorState) -> u8 {
-    (this.buttons.can_remove as u8) << 2
-        | (this.buttons.can_move_up as u8) << 1
-        | this.buttons.can_move_down as u8
+    guard::call(guard::address(this), move || {
+        (this.buttons.can_remove as u8) << 2
+            | (this.buttons.can_move_up as u8) << 1
+            | this.buttons.can_move_down as u8
+    })
 }
 
 /// Returns the index of the currently selected component.
 #[no_mangle]
 pub extern "C" fn LayoutEditorState_selected_component(this: &LayoutEditorState) -> u32 {
-    this.selected_component
+    guard::call(guard::address(this), move || this.selected_component)
 }
 
 /// Returns the number of fields in the layout's settings.
@@ -56,11 +72,13 @@ pub extern "C" fn LayoutEditorState_field_len(
     this: &LayoutEditorState,
     component_settings: bool,
 ) -> usize {
-    if component_settings {
-        this.component_settings.fields.len()
-    } else {
-        this.general_settings.fields.len()
-    }
+    guard::call(guard::address(this), move || {
+        if component_settings {
+            this.component_settings.fields.len()
+        } else {
+            this.general_settings.fields.len()
+        }
+    })
 }
 
 /// Returns the name of the layout's setting at the specified index.
@@ -72,11 +90,30 @@ pub extern "C" fn LayoutEditorState_field_text(
     component_settings: bool,
     index: usize,
 ) -> *const c_char {
-    if component_settings {
-        output_str(&this.component_settings.fields[index].text)
-    } else {
-        output_str(&this.general_settings.fields[index].text)
-    }
+    guard::call(guard::address(this), move || {
+        if component_settings {
+            output_str(&this.component_settings.fields[index].text)
+        } else {
+            output_str(&this.general_settings.fields[index].text)
+        }
+    })
+}
+
+/// The UTF-16 variant of LayoutEditorState_field_text. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutEditorState_field_text_u16(
+    this: &LayoutEditorState,
+    component_settings: bool,
+    index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        LayoutEditorState_field_text(this, component_settings, index),
+        buf,
+        buf_len,
+    )
 }
 
 /// Returns the value of the layout's setting at the specified index.
@@ -88,9 +125,11 @@ pub extern "C" fn LayoutEditorState_field_value(
     component_settings: bool,
     index: usize,
 ) -> &SettingValue {
-    if component_settings {
-        &this.component_settings.fields[index].value
-    } else {
-        &this.general_settings.fields[index].value
-    }
+    guard::call_or_abort(guard::address(this), move || {
+        if component_settings {
+            &this.component_settings.fields[index].value
+        } else {
+            &this.general_settings.fields[index].value
+        }
+    })
 }
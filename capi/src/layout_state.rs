@@ -6,7 +6,7 @@
 //! - Using out of bounds indices.
 //! - Using the wrong getter function on the wrong type of component.
 
-use crate::{output_vec, Json};
+use crate::{guard, output_into_buf, output_utf16_from, output_vec, Json};
 use livesplit_core::{
     component::{
         blank_space::State as BlankSpaceComponentState,
@@ -16,6 +16,7 @@ use livesplit_core::{
         timer::State as TimerComponentState, title::State as TitleComponentState,
     },
     layout::{ComponentState, LayoutState},
+    localization::Localization,
 };
 use std::os::raw::c_char;
 
@@ -26,47 +27,120 @@ pub type OwnedLayoutState = Box<LayoutState>;
 /// layout state that gets updated over time.
 #[no_mangle]
 pub extern "C" fn LayoutState_new() -> OwnedLayoutState {
-    Default::default()
+    guard::abort_on_unwind(Default::default)
+}
+
+/// Localizes the labels that are produced by the components, such as the keys
+/// of the key value based components, the column labels of the Splits
+/// Component and the comparison names of the Detailed Timer Component. This
+/// needs to be done every time after the state got updated.
+#[no_mangle]
+pub extern "C" fn LayoutState_localize(this: &mut LayoutState, localization: &Localization) {
+    guard::call(guard::address(this), move || {
+        this.localize(localization);
+    })
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn LayoutState_drop(this: OwnedLayoutState) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Encodes the layout state as JSON. You can use this to visualize all of the
 /// components of a layout.
 #[no_mangle]
 pub extern "C" fn LayoutState_as_json(this: &LayoutState) -> Json {
-    output_vec(|o| {
-        this.write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.write_json(o).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of LayoutState_as_json. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutState_as_json_u16(
+    this: &LayoutState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(LayoutState_as_json(this), buf, buf_len)
+}
+
+/// Encodes the layout state as JSON directly into the buffer provided. The JSON
+/// is not nul-terminated. The length of the JSON is returned. If it is larger
+/// than the buffer, the buffer only contains the beginning of the JSON and you
+/// need to call this again with a larger buffer.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutState_as_json_into_buf(
+    this: &LayoutState,
+    buf: *mut u8,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_into_buf(buf, buf_len, |o| {
+            this.write_json(o).unwrap();
+        })
+    })
+}
+
+/// Encodes the layout state as MessagePack directly into the buffer provided.
+/// The structure is the same as the one of the JSON, but it is a lot faster to
+/// encode and decode. The length of the encoded layout state is returned. If it
+/// is larger than the buffer, the buffer only contains its beginning and you
+/// need to call this again with a larger buffer.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutState_as_msgpack_into_buf(
+    this: &LayoutState,
+    buf: *mut u8,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_into_buf(buf, buf_len, |o| {
+            rmp_serde::encode::write_named(o, this).unwrap();
+        })
     })
 }
 
 /// Gets the number of Components in the Layout State.
 #[no_mangle]
 pub extern "C" fn LayoutState_len(this: &LayoutState) -> usize {
-    this.components.len()
+    guard::call(guard::address(this), move || this.components.len())
 }
 
 /// Returns a string describing the type of the Component at the specified
 /// index.
 #[no_mangle]
 pub extern "C" fn LayoutState_component_type(this: &LayoutState, index: usize) -> *const c_char {
-    (match this.components[index] {
-        ComponentState::BlankSpace(_) => "BlankSpace\0",
-        ComponentState::DetailedTimer(_) => "DetailedTimer\0",
-        ComponentState::Graph(_) => "Graph\0",
-        ComponentState::KeyValue(_) => "KeyValue\0",
-        ComponentState::Separator(_) => "Separator\0",
-        ComponentState::Splits(_) => "Splits\0",
-        ComponentState::Text(_) => "Text\0",
-        ComponentState::Timer(_) => "Timer\0",
-        ComponentState::Title(_) => "Title\0",
+    guard::call(guard::address(this), move || {
+        (match this.components[index] {
+            ComponentState::BlankSpace(_) => "BlankSpace\0",
+            ComponentState::DetailedTimer(_) => "DetailedTimer\0",
+            ComponentState::Graph(_) => "Graph\0",
+            ComponentState::KeyValue(_) => "KeyValue\0",
+            ComponentState::Separator(_) => "Separator\0",
+            ComponentState::Splits(_) => "Splits\0",
+            ComponentState::Text(_) => "Text\0",
+            ComponentState::Timer(_) => "Timer\0",
+            ComponentState::Title(_) => "Title\0",
+        })
+        .as_ptr()
+        .cast()
     })
-    .as_ptr()
-    .cast()
+}
+
+/// The UTF-16 variant of LayoutState_component_type. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn LayoutState_component_type_u16(
+    this: &LayoutState,
+    index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(LayoutState_component_type(this, index), buf, buf_len)
 }
 
 /// Gets the Blank Space component state at the specified index.
@@ -75,10 +149,12 @@ pub extern "C" fn LayoutState_component_as_blank_space(
     this: &LayoutState,
     index: usize,
 ) -> &BlankSpaceComponentState {
-    match &this.components[index] {
-        ComponentState::BlankSpace(x) => x,
-        _ => panic!("wrong component state type"),
-    }
+    guard::call_or_abort(guard::address(this), move || {
+        match &this.components[index] {
+            ComponentState::BlankSpace(x) => x,
+            _ => panic!("wrong component state type"),
+        }
+    })
 }
 
 /// Gets the Detailed Timer component state at the specified index.
@@ -87,10 +163,12 @@ pub extern "C" fn LayoutState_component_as_detailed_timer(
     this: &LayoutState,
     index: usize,
 ) -> &DetailedTimerComponentState {
-    match &this.components[index] {
-        ComponentState::DetailedTimer(x) => x,
-        _ => panic!("wrong component state type"),
-    }
+    guard::call_or_abort(guard::address(this), move || {
+        match &this.components[index] {
+            ComponentState::DetailedTimer(x) => x,
+            _ => panic!("wrong component state type"),
+        }
+    })
 }
 
 /// Gets the Graph component state at the specified index.
@@ -99,10 +177,12 @@ pub extern "C" fn LayoutState_component_as_graph(
     this: &LayoutState,
     index: usize,
 ) -> &GraphComponentState {
-    match &this.components[index] {
-        ComponentState::Graph(x) => x,
-        _ => panic!("wrong component state type"),
-    }
+    guard::call_or_abort(guard::address(this), move || {
+        match &this.components[index] {
+            ComponentState::Graph(x) => x,
+            _ => panic!("wrong component state type"),
+        }
+    })
 }
 
 /// Gets the Key Value component state at the specified index.
@@ -111,10 +191,12 @@ pub extern "C" fn LayoutState_component_as_key_value(
     this: &LayoutState,
     index: usize,
 ) -> &KeyValueComponentState {
-    match &this.components[index] {
-        ComponentState::KeyValue(x) => x,
-        _ => panic!("wrong component state type"),
-    }
+    guard::call_or_abort(guard::address(this), move || {
+        match &this.components[index] {
+            ComponentState::KeyValue(x) => x,
+            _ => panic!("wrong component state type"),
+        }
+    })
 }
 
 /// Gets the Separator component state at the specified index.
@@ -123,10 +205,12 @@ pub extern "C" fn LayoutState_component_as_separator(
     this: &LayoutState,
     index: usize,
 ) -> &SeparatorComponentState {
-    match &this.components[index] {
-        ComponentState::Separator(x) => x,
-        _ => panic!("wrong component state type"),
-    }
+    guard::call_or_abort(guard::address(this), move || {
+        match &this.components[index] {
+            ComponentState::Separator(x) => x,
+            _ => panic!("wrong component state type"),
+        }
+    })
 }
 
 /// Gets the Splits component state at the specified index.
@@ -135,10 +219,12 @@ pub extern "C" fn LayoutState_component_as_splits(
     this: &LayoutState,
     index: usize,
 ) -> &SplitsComponentState {
-    match &this.components[index] {
-        ComponentState::Splits(x) => x,
-        _ => panic!("wrong component state type"),
-    }
+    guard::call_or_abort(guard::address(this), move || {
+        match &this.components[index] {
+            ComponentState::Splits(x) => x,
+            _ => panic!("wrong component state type"),
+        }
+    })
 }
 
 /// Gets the Text component state at the specified index.
@@ -147,10 +233,12 @@ pub extern "C" fn LayoutState_component_as_text(
     this: &LayoutState,
     index: usize,
 ) -> &TextComponentState {
-    match &this.components[index] {
-        ComponentState::Text(x) => x,
-        _ => panic!("wrong component state type"),
-    }
+    guard::call_or_abort(guard::address(this), move || {
+        match &this.components[index] {
+            ComponentState::Text(x) => x,
+            _ => panic!("wrong component state type"),
+        }
+    })
 }
 
 /// Gets the Timer component state at the specified index.
@@ -159,10 +247,12 @@ pub extern "C" fn LayoutState_component_as_timer(
     this: &LayoutState,
     index: usize,
 ) -> &TimerComponentState {
-    match &this.components[index] {
-        ComponentState::Timer(x) => x,
-        _ => panic!("wrong component state type"),
-    }
+    guard::call_or_abort(guard::address(this), move || {
+        match &this.components[index] {
+            ComponentState::Timer(x) => x,
+            _ => panic!("wrong component state type"),
+        }
+    })
 }
 
 /// Gets the Title component state at the specified index.
@@ -171,8 +261,10 @@ pub extern "C" fn LayoutState_component_as_title(
     this: &LayoutState,
     index: usize,
 ) -> &TitleComponentState {
-    match &this.components[index] {
-        ComponentState::Title(x) => x,
-        _ => panic!("wrong component state type"),
-    }
+    guard::call_or_abort(guard::address(this), move || {
+        match &this.components[index] {
+            ComponentState::Title(x) => x,
+            _ => panic!("wrong component state type"),
+        }
+    })
 }
@@ -15,21 +15,25 @@ use std::{
     cell::{Cell, RefCell},
     ffi::CStr,
     fs::File,
+    io,
     mem::ManuallyDrop,
     os::raw::c_char,
     ptr, slice,
 };
 
+pub mod accessible_summary;
 pub mod analysis;
 pub mod atomic_date_time;
 pub mod attempt;
 pub mod auto_splitting_runtime;
 pub mod blank_space_component;
 pub mod blank_space_component_state;
+pub mod cancellation_token;
 pub mod command_sink;
 pub mod component;
 pub mod current_comparison_component;
 pub mod current_pace_component;
+pub mod decoded_image;
 pub mod delta_component;
 pub mod detailed_timer_component;
 pub mod detailed_timer_component_state;
@@ -37,31 +41,40 @@ pub mod fuzzy_list;
 pub mod general_layout_settings;
 pub mod graph_component;
 pub mod graph_component_state;
+mod guard;
+pub mod handles;
+pub mod host_component;
 pub mod hotkey_config;
 pub mod hotkey_system;
 pub mod image_cache;
 pub mod key_value_component_state;
+pub mod last_error;
 pub mod layout;
 pub mod layout_editor;
 pub mod layout_editor_state;
 pub mod layout_state;
 pub mod linked_layout;
+pub mod localization;
 pub mod parse_run_result;
 pub mod pb_chance_component;
 pub mod possible_time_save_component;
 pub mod potential_clean_up;
 pub mod previous_segment_component;
 pub mod run;
+pub mod run_collection;
 pub mod run_editor;
 pub mod run_metadata;
 pub mod run_metadata_custom_variable;
 pub mod run_metadata_custom_variables_iter;
 pub mod run_metadata_speedrun_com_variable;
 pub mod run_metadata_speedrun_com_variables_iter;
+pub mod run_parser;
 pub mod segment;
+pub mod segment_clipboard;
 pub mod segment_history;
 pub mod segment_history_element;
 pub mod segment_history_iter;
+pub mod segment_statistics;
 pub mod segment_time_component;
 pub mod separator_component;
 pub mod separator_component_state;
@@ -72,6 +85,7 @@ pub mod shared_timer;
 pub mod software_renderer;
 pub mod splits_component;
 pub mod splits_component_state;
+pub mod state_stream;
 pub mod sum_of_best_cleaner;
 pub mod sum_of_best_component;
 pub mod text_component;
@@ -86,6 +100,7 @@ pub mod timer_write_lock;
 pub mod title_component;
 pub mod title_component_state;
 pub mod total_playtime_component;
+pub mod version;
 #[cfg(all(target_family = "wasm", feature = "wasm-web"))]
 pub mod web_command_sink;
 #[cfg(all(target_family = "wasm", feature = "web-rendering"))]
@@ -111,6 +126,7 @@ thread_local! {
     static SEGMENT_HISTORY_ELEMENT: Cell<SegmentHistoryElement> = const { Cell::new((0, Time::new())) };
     static RUN_METADATA_SPEEDRUN_COM_VARIABLE: Cell<RunMetadataSpeedrunComVariable> = const { Cell::new(("", ptr::null())) };
     static RUN_METADATA_CUSTOM_VARIABLE: Cell<RunMetadataCustomVariable> = const { Cell::new(("", ptr::null())) };
+    static UTF16_PARAM: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
 }
 
 fn output_time_span(time_span: TimeSpan) -> *const TimeSpan {
@@ -155,6 +171,43 @@ where
     })
 }
 
+/// Writes the output directly into the buffer provided by the caller and
+/// returns the length of the whole output. If the output doesn't fit, the
+/// buffer only contains its beginning.
+unsafe fn output_into_buf<F>(buf: *mut u8, buf_len: usize, f: F) -> usize
+where
+    F: FnOnce(&mut SliceWriter<'_>),
+{
+    let mut writer = SliceWriter {
+        buf: slice_mut(buf, buf_len),
+        len: 0,
+    };
+    f(&mut writer);
+    writer.len
+}
+
+/// Writes into a slice and counts the bytes of everything written, including
+/// the bytes that didn't fit anymore.
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl io::Write for SliceWriter<'_> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if let Some(dst) = self.buf.get_mut(self.len..) {
+            let n = dst.len().min(data.len());
+            dst[..n].copy_from_slice(&data[..n]);
+        }
+        self.len += data.len();
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 unsafe fn slice<T>(ptr: *const T, len: usize) -> &'static [T] {
     if len == 0 {
         &[]
@@ -219,25 +272,266 @@ unsafe fn get_file(_: i64) -> ManuallyDrop<File> {
 #[cfg(all(target_family = "wasm", not(target_os = "wasi")))]
 #[no_mangle]
 pub extern "C" fn alloc(size: usize) -> *mut u8 {
-    if size == 0 {
-        std::ptr::NonNull::dangling().as_ptr()
-    } else {
-        unsafe { std::alloc::alloc(std::alloc::Layout::from_size_align_unchecked(size, 1)) }
-    }
+    guard::call(0, move || {
+        if size == 0 {
+            std::ptr::NonNull::dangling().as_ptr()
+        } else {
+            unsafe { std::alloc::alloc(std::alloc::Layout::from_size_align_unchecked(size, 1)) }
+        }
+    })
 }
 
 /// Deallocate memory.
 #[cfg(all(target_family = "wasm", not(target_os = "wasi")))]
 #[no_mangle]
 pub unsafe extern "C" fn dealloc(ptr: *mut u8, cap: usize) {
-    if cap != 0 {
-        std::alloc::dealloc(ptr, std::alloc::Layout::from_size_align_unchecked(cap, 1));
-    }
+    guard::call(0, move || {
+        if cap != 0 {
+            std::alloc::dealloc(ptr, std::alloc::Layout::from_size_align_unchecked(cap, 1));
+        }
+    })
 }
 
 /// Returns the byte length of the last nul-terminated string returned on the
 /// current thread. The length excludes the nul-terminator.
 #[no_mangle]
 pub extern "C" fn get_buf_len() -> usize {
-    OUTPUT_VEC.with_borrow(|v| v.len() - 1)
+    guard::call(0, move || {
+        OUTPUT_VEC.with_borrow(|v| v.len().saturating_sub(1))
+    })
+}
+
+/// Writes the last nul-terminated string returned on the current thread into
+/// the buffer provided as UTF-16 and returns its length in UTF-16 code units.
+/// The nul-terminator is neither written nor counted. If the string doesn't
+/// fit, the buffer only contains its beginning, so you can pass a buffer of
+/// length 0 to query the length first.
+///
+/// Every function taking or returning strings also has a UTF-16 variant with
+/// the `_u16` suffix. Its string parameters are passed as UTF-16 along with
+/// their length in UTF-16 code units. A string it returns is written into the
+/// buffer passed as the last two parameters instead, and its length in UTF-16
+/// code units is returned. If the string doesn't fit, it can be retrieved via
+/// this function afterwards. If the function it is a variant of returns
+/// <NULL>, the largest possible length is returned.
+#[no_mangle]
+pub unsafe extern "C" fn get_buf_as_utf16(buf: *mut u16, buf_len: usize) -> usize {
+    guard::call(0, move || {
+        OUTPUT_VEC.with_borrow(|v| {
+            // Nothing may have been returned on this thread yet.
+            let Some((_nul, bytes)) = v.split_last() else {
+                return 0;
+            };
+            // All the strings that are returned are valid UTF-8.
+            let Ok(s) = std::str::from_utf8(bytes) else {
+                return 0;
+            };
+            write_utf16(buf, buf_len, s)
+        })
+    })
+}
+
+/// Writes the string into the buffer provided as UTF-16 and returns its length
+/// in UTF-16 code units. If the string doesn't fit, the buffer only contains
+/// its beginning.
+unsafe fn write_utf16(buf: *mut u16, buf_len: usize, s: &str) -> usize {
+    let buf = slice_mut(buf, buf_len);
+    let mut len = 0;
+    for c in s.encode_utf16() {
+        if let Some(dst) = buf.get_mut(len) {
+            *dst = c;
+        }
+        len += 1;
+    }
+    len
+}
+
+/// Writes the string returned by a `_u16` variant into the buffer provided as
+/// UTF-16 and returns its length in UTF-16 code units. If the string doesn't
+/// fit, it is stored as the last string returned on the current thread, so it
+/// can be retrieved via get_buf_as_utf16.
+unsafe fn output_utf16(buf: *mut u16, buf_len: usize, s: &str) -> usize {
+    let len = write_utf16(buf, buf_len, s);
+    if len > buf_len {
+        output_str(s);
+    }
+    len
+}
+
+/// Writes the nul-terminated string returned by the function a `_u16` variant
+/// forwards to into the buffer provided, just like output_utf16. If the
+/// function returned <NULL>, `usize::MAX` is returned instead.
+unsafe fn output_utf16_from(s: *const c_char, buf: *mut u16, buf_len: usize) -> usize {
+    guard::call(0, move || {
+        if s.is_null() {
+            return usize::MAX;
+        }
+        // All the strings that are returned are valid UTF-8.
+        let s = std::str::from_utf8(CStr::from_ptr(s).to_bytes()).unwrap_or_default();
+        let len = write_utf16(buf, buf_len, s);
+        // Most strings are returned in the output buffer already.
+        let is_output = OUTPUT_VEC.with_borrow(|v| v.as_ptr() == s.as_ptr());
+        if len > buf_len && !is_output {
+            output_str(s);
+        }
+        len
+    })
+}
+
+/// A string passed as UTF-16 to a `_u16` variant, converted into the
+/// nul-terminated UTF-8 string that the function it forwards to takes.
+struct Utf16Param(Option<Vec<u8>>);
+
+impl Utf16Param {
+    unsafe fn new(data: *const u16, len: usize) -> Self {
+        Self((!data.is_null()).then(|| {
+            let mut buf = Vec::new();
+            decode_utf16(&mut buf, slice(data, len));
+            buf
+        }))
+    }
+
+    fn as_ptr(&self) -> *const c_char {
+        self.0
+            .as_ref()
+            .map_or(ptr::null(), |buf| buf.as_ptr() as *const c_char)
+    }
+}
+
+/// Decodes the UTF-16 string into a nul-terminated UTF-8 string. Unpaired
+/// surrogates are replaced by the replacement character.
+fn decode_utf16(buf: &mut Vec<u8>, data: &[u16]) {
+    buf.clear();
+    for c in char::decode_utf16(data.iter().copied()) {
+        let c = c.unwrap_or(char::REPLACEMENT_CHARACTER);
+        buf.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+    buf.push(0);
+}
+
+/// Converts the UTF-16 string provided into a nul-terminated UTF-8 string that
+/// can be passed to any function taking a string. Unpaired surrogates are
+/// replaced by the replacement character. The converted string is stored in a
+/// buffer that gets reused, so it stays valid until this function gets called
+/// again on the current thread. Prefer the `_u16` variant of a function over
+/// this, especially if it takes more than one string.
+#[no_mangle]
+pub unsafe extern "C" fn utf16_to_str(data: *const u16, len: usize) -> *const c_char {
+    guard::call(0, move || {
+        UTF16_PARAM.with_borrow_mut(|buf| {
+            decode_utf16(buf, slice(data, len));
+            buf.as_ptr() as *const c_char
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segment::{Segment_drop, Segment_name, Segment_new};
+
+    fn utf16(s: &str) -> Vec<u16> {
+        s.encode_utf16().collect()
+    }
+
+    #[test]
+    fn utf16_round_trip() {
+        let name = utf16("Forsaken City 🍓");
+        let segment = unsafe { Segment_new(utf16_to_str(name.as_ptr(), name.len())) };
+        Segment_name(&segment);
+
+        assert_eq!(get_buf_len(), "Forsaken City 🍓".len());
+        let len = unsafe { get_buf_as_utf16(ptr::null_mut(), 0) };
+        assert_eq!(len, name.len());
+
+        let mut buf = vec![0; len];
+        assert_eq!(
+            unsafe { get_buf_as_utf16(buf.as_mut_ptr(), buf.len()) },
+            len
+        );
+        assert_eq!(buf, name);
+
+        let mut short = [0; 4];
+        assert_eq!(
+            unsafe { get_buf_as_utf16(short.as_mut_ptr(), short.len()) },
+            len
+        );
+        assert_eq!(short, name[..4]);
+
+        Segment_drop(segment);
+    }
+
+    #[test]
+    fn utf16_variants_write_into_the_buffer() {
+        use crate::key_value_component_state::KeyValueComponentState_value_u16;
+
+        let state = livesplit_core::component::key_value::State {
+            value: "1:23.4 🍓".into(),
+            ..Default::default()
+        };
+        let value = utf16(&state.value);
+
+        let mut buf = [0; 4];
+        assert_eq!(
+            unsafe { KeyValueComponentState_value_u16(&state, buf.as_mut_ptr(), buf.len()) },
+            value.len()
+        );
+        assert_eq!(buf, value[..4]);
+
+        let mut buf = vec![0; value.len()];
+        unsafe { KeyValueComponentState_value_u16(&state, buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(buf, value);
+    }
+
+    #[test]
+    fn output_into_buf_reports_the_whole_length() {
+        use std::io::Write;
+
+        let mut buf = [0; 4];
+        let len = unsafe {
+            output_into_buf(buf.as_mut_ptr(), buf.len(), |o| {
+                o.write_all(b"{\"a\":").unwrap();
+                o.write_all(b"1}").unwrap();
+            })
+        };
+        assert_eq!(len, 7);
+        assert_eq!(&buf, b"{\"a\"");
+    }
+
+    #[test]
+    fn nothing_returned_yet() {
+        std::thread::spawn(|| {
+            assert_eq!(get_buf_len(), 0);
+            assert_eq!(unsafe { get_buf_as_utf16(ptr::null_mut(), 0) }, 0);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn unpaired_surrogates_get_replaced() {
+        let data = [u16::from(b'a'), 0xD83C, u16::from(b'b'), 0xDF53];
+        let s = unsafe { CStr::from_ptr(utf16_to_str(data.as_ptr(), data.len())) };
+        assert_eq!(s.to_str().unwrap(), "a\u{FFFD}b\u{FFFD}");
+    }
+
+    #[test]
+    fn utf16_variants_convert_the_strings() {
+        use crate::run::{Run_drop, Run_game_name_u16, Run_new, Run_set_game_name_u16};
+
+        let mut run = Run_new();
+        let name = utf16("Celeste 🍓");
+        unsafe { Run_set_game_name_u16(&mut run, name.as_ptr(), name.len()) };
+
+        let mut buf = [0; 4];
+        let len = unsafe { Run_game_name_u16(&run, buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(len, name.len());
+        assert_eq!(buf, name[..4]);
+
+        let mut buf = vec![0; len];
+        unsafe { get_buf_as_utf16(buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(buf, name);
+
+        Run_drop(run);
+    }
 }
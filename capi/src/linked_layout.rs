@@ -2,7 +2,7 @@
 //! Layout, it is supposed to be visualized with the Layout that is linked with
 //! it.
 
-use super::{output_str, str};
+use super::{guard, output_str, output_utf16_from, str, Utf16Param};
 use livesplit_core::run::LinkedLayout;
 use std::os::raw::c_char;
 
@@ -15,31 +15,58 @@ pub type NullableOwnedLinkedLayout = Option<OwnedLinkedLayout>;
 /// the default layout is used instead.
 #[no_mangle]
 pub unsafe extern "C" fn LinkedLayout_new(path: *const c_char) -> OwnedLinkedLayout {
-    let path = str(path);
-    Box::new(if path.is_empty() {
-        LinkedLayout::Default
-    } else {
-        LinkedLayout::Path(path.to_owned())
+    guard::abort_on_unwind(move || {
+        let path = str(path);
+        Box::new(if path.is_empty() {
+            LinkedLayout::Default
+        } else {
+            LinkedLayout::Path(path.to_owned())
+        })
     })
 }
 
+/// The UTF-16 variant of LinkedLayout_new. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn LinkedLayout_new_u16(
+    path: *const u16,
+    path_len: usize,
+) -> OwnedLinkedLayout {
+    LinkedLayout_new(Utf16Param::new(path, path_len).as_ptr())
+}
+
 /// drop
 #[no_mangle]
 pub extern "C" fn LinkedLayout_drop(this: OwnedLinkedLayout) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Checks whether the linked layout is the default layout.
 #[no_mangle]
 pub extern "C" fn LinkedLayout_is_default(this: &LinkedLayout) -> bool {
-    matches!(this, LinkedLayout::Default)
+    guard::call(guard::address(this), move || {
+        matches!(this, LinkedLayout::Default)
+    })
 }
 
 /// Returns the path of the linked layout, if it's not the default layout.
 #[no_mangle]
 pub extern "C" fn LinkedLayout_path(this: &LinkedLayout) -> *const c_char {
-    output_str(match this {
-        LinkedLayout::Path(path) => path,
-        _ => "",
+    guard::call(guard::address(this), move || {
+        output_str(match this {
+            LinkedLayout::Path(path) => path,
+            _ => "",
+        })
     })
 }
+
+/// The UTF-16 variant of LinkedLayout_path. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn LinkedLayout_path_u16(
+    this: &LinkedLayout,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(LinkedLayout_path(this), buf, buf_len)
+}
@@ -0,0 +1,203 @@
+//! A Localization stores the language to localize the strings produced by
+//! livesplit-core into and the translation table that maps the English
+//! strings to that language. Strings that are not part of the translation
+//! table stay untranslated.
+
+use super::{guard, output_str, output_utf16_from, output_vec, str, Json, Utf16Param};
+use crate::last_error::{self, ErrorCode};
+use livesplit_core::{localization::Localization, TimerPhase};
+use std::{io::Write, os::raw::c_char};
+
+/// type
+pub type OwnedLocalization = Box<Localization>;
+/// type
+pub type NullableOwnedLocalization = Option<OwnedLocalization>;
+
+/// Creates a new Localization for the language provided without any
+/// translations. The language should be specified as an IETF BCP 47 language
+/// tag, such as `de` or `pt-BR`.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_new(language: *const c_char) -> OwnedLocalization {
+    guard::abort_on_unwind(move || Box::new(Localization::new(str(language))))
+}
+
+/// The UTF-16 variant of Localization_new. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_new_u16(
+    language: *const u16,
+    language_len: usize,
+) -> OwnedLocalization {
+    Localization_new(Utf16Param::new(language, language_len).as_ptr())
+}
+
+/// drop
+#[no_mangle]
+pub extern "C" fn Localization_drop(this: OwnedLocalization) {
+    guard::dispose(this);
+}
+
+/// Decodes a Localization from its JSON representation. The JSON is an object
+/// with the `language` and the `translations`, which is an object mapping the
+/// English strings to their translations. <NULL> is returned if it couldn't be
+/// parsed.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_parse_json(json: Json) -> NullableOwnedLocalization {
+    guard::call(0, move || {
+        last_error::track(ErrorCode::Parse, Localization::from_json(str(json))).map(Box::new)
+    })
+}
+
+/// The UTF-16 variant of Localization_parse_json. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_parse_json_u16(
+    json: *const u16,
+    json_len: usize,
+) -> NullableOwnedLocalization {
+    Localization_parse_json(Utf16Param::new(json, json_len).as_ptr())
+}
+
+/// Encodes the Localization as JSON.
+#[no_mangle]
+pub extern "C" fn Localization_as_json(this: &Localization) -> Json {
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            o.write_all(this.to_json().as_bytes()).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of Localization_as_json. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_as_json_u16(
+    this: &Localization,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Localization_as_json(this), buf, buf_len)
+}
+
+/// Accesses the language the strings are localized into.
+#[no_mangle]
+pub extern "C" fn Localization_language(this: &Localization) -> *const c_char {
+    guard::call(guard::address(this), move || output_str(this.language()))
+}
+
+/// The UTF-16 variant of Localization_language. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_language_u16(
+    this: &Localization,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Localization_language(this), buf, buf_len)
+}
+
+/// Sets the language the strings are localized into.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_set_language(
+    this: &mut Localization,
+    language: *const c_char,
+) {
+    guard::call(guard::address(this), move || {
+        this.set_language(str(language));
+    })
+}
+
+/// The UTF-16 variant of Localization_set_language. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_set_language_u16(
+    this: &mut Localization,
+    language: *const u16,
+    language_len: usize,
+) {
+    Localization_set_language(this, Utf16Param::new(language, language_len).as_ptr())
+}
+
+/// Adds a translation of the English string provided to the translation table.
+/// Any previous translation of the same string is replaced.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_add_translation(
+    this: &mut Localization,
+    source: *const c_char,
+    translation: *const c_char,
+) {
+    guard::call(guard::address(this), move || {
+        this.add_translation(str(source), str(translation));
+    })
+}
+
+/// The UTF-16 variant of Localization_add_translation. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_add_translation_u16(
+    this: &mut Localization,
+    source: *const u16,
+    source_len: usize,
+    translation: *const u16,
+    translation_len: usize,
+) {
+    Localization_add_translation(
+        this,
+        Utf16Param::new(source, source_len).as_ptr(),
+        Utf16Param::new(translation, translation_len).as_ptr(),
+    )
+}
+
+/// Translates the English string provided. Labels that refer to a comparison in
+/// parentheses, like `Current Pace (Best Segments)`, get both of their parts
+/// translated individually if there is no translation for the whole label. If
+/// there is no translation, the string itself is returned.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_translate(
+    this: &Localization,
+    text: *const c_char,
+) -> *const c_char {
+    guard::call(guard::address(this), move || {
+        output_str(this.translate_label(str(text)))
+    })
+}
+
+/// The UTF-16 variant of Localization_translate. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_translate_u16(
+    this: &Localization,
+    text: *const u16,
+    text_len: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        Localization_translate(this, Utf16Param::new(text, text_len).as_ptr()),
+        buf,
+        buf_len,
+    )
+}
+
+/// Returns the translated name of the Timer Phase provided.
+#[no_mangle]
+pub extern "C" fn Localization_timer_phase_name(
+    this: &Localization,
+    phase: TimerPhase,
+) -> *const c_char {
+    guard::call(guard::address(this), move || {
+        output_str(this.translate(phase.name()))
+    })
+}
+
+/// The UTF-16 variant of Localization_timer_phase_name. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Localization_timer_phase_name_u16(
+    this: &Localization,
+    phase: TimerPhase,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Localization_timer_phase_name(this, phase), buf, buf_len)
+}
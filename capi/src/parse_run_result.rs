@@ -1,7 +1,7 @@
 //! A run parsed by the Composite Parser. This contains the Run itself and
 //! information about which parser parsed it.
 
-use super::output_vec;
+use super::{guard, output_utf16_from, output_vec};
 use crate::run::OwnedRun;
 use livesplit_core::run::parser::{composite::ParsedRun, TimerKind};
 use std::{io::Write, os::raw::c_char};
@@ -14,27 +14,42 @@ pub type OwnedParseRunResult = Box<ParseRunResult>;
 /// drop
 #[no_mangle]
 pub extern "C" fn ParseRunResult_drop(this: OwnedParseRunResult) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Returns <TRUE> if the Run got parsed successfully. <FALSE> is returned otherwise.
 #[no_mangle]
 pub extern "C" fn ParseRunResult_parsed_successfully(this: &ParseRunResult) -> bool {
-    this.is_some()
+    guard::call(guard::address(this), move || this.is_some())
 }
 
 /// Moves the actual Run object out of the Result. You may not call this if the
 /// Run wasn't parsed successfully.
 #[no_mangle]
 pub extern "C" fn ParseRunResult_unwrap(this: OwnedParseRunResult) -> OwnedRun {
-    Box::new((*this).unwrap().run)
+    guard::call_or_abort(guard::address(&*this), move || {
+        Box::new((*this).unwrap().run)
+    })
 }
 
 /// Accesses the name of the Parser that parsed the Run. You may not call this
 /// if the Run wasn't parsed successfully.
 #[no_mangle]
 pub extern "C" fn ParseRunResult_timer_kind(this: &ParseRunResult) -> *const c_char {
-    output_vec(|f| write!(f, "{}", this.as_ref().unwrap().kind).unwrap())
+    guard::call(guard::address(this), move || {
+        output_vec(|f| write!(f, "{}", this.as_ref().unwrap().kind).unwrap())
+    })
+}
+
+/// The UTF-16 variant of ParseRunResult_timer_kind. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn ParseRunResult_timer_kind_u16(
+    this: &ParseRunResult,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(ParseRunResult_timer_kind(this), buf, buf_len)
 }
 
 /// Checks whether the Parser parsed a generic timer. Since a generic timer can
@@ -43,11 +58,13 @@ pub extern "C" fn ParseRunResult_timer_kind(this: &ParseRunResult) -> *const c_c
 /// timer format was parsed, instead of one of the more specific timer formats.
 #[no_mangle]
 pub extern "C" fn ParseRunResult_is_generic_timer(this: &ParseRunResult) -> bool {
-    matches!(
-        this,
-        Some(ParsedRun {
-            kind: TimerKind::Generic(_),
-            ..
-        })
-    )
+    guard::call(guard::address(this), move || {
+        matches!(
+            this,
+            Some(ParsedRun {
+                kind: TimerKind::Generic(_),
+                ..
+            })
+        )
+    })
 }
@@ -3,7 +3,7 @@
 //! of beating the Personal Best. During an attempt it actively changes based on
 //! how well the attempt is going.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::component::OwnedComponent;
 use crate::key_value_component_state::OwnedKeyValueComponentState;
 use livesplit_core::component::pb_chance::Component as PbChanceComponent;
@@ -15,35 +15,51 @@ pub type OwnedPbChanceComponent = Box<PbChanceComponent>;
 /// Creates a new PB Chance Component.
 #[no_mangle]
 pub extern "C" fn PbChanceComponent_new() -> OwnedPbChanceComponent {
-    Box::new(PbChanceComponent::new())
+    guard::abort_on_unwind(move || Box::new(PbChanceComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn PbChanceComponent_drop(this: OwnedPbChanceComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
 /// layout.
 #[no_mangle]
 pub extern "C" fn PbChanceComponent_into_generic(this: OwnedPbChanceComponent) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
 #[no_mangle]
 pub extern "C" fn PbChanceComponent_state_as_json(this: &PbChanceComponent, timer: &Timer) -> Json {
-    output_vec(|o| {
-        this.state(&timer.snapshot()).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(&timer.snapshot()).write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of PbChanceComponent_state_as_json. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn PbChanceComponent_state_as_json_u16(
+    this: &PbChanceComponent,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(PbChanceComponent_state_as_json(this, timer), buf, buf_len)
+}
+
 /// Calculates the component's state based on the timer provided.
 #[no_mangle]
 pub extern "C" fn PbChanceComponent_state(
     this: &PbChanceComponent,
     timer: &Timer,
 ) -> OwnedKeyValueComponentState {
-    Box::new(this.state(&timer.snapshot()))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(&timer.snapshot()))
+    })
 }
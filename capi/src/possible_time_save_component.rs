@@ -3,7 +3,7 @@
 //! Segments. This component also allows showing the Total Possible Time Save
 //! for the remainder of the current attempt.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::component::OwnedComponent;
 use crate::key_value_component_state::OwnedKeyValueComponentState;
 use livesplit_core::component::possible_time_save::Component as PossibleTimeSaveComponent;
@@ -15,13 +15,13 @@ pub type OwnedPossibleTimeSaveComponent = Box<PossibleTimeSaveComponent>;
 /// Creates a new Possible Time Save Component.
 #[no_mangle]
 pub extern "C" fn PossibleTimeSaveComponent_new() -> OwnedPossibleTimeSaveComponent {
-    Box::new(PossibleTimeSaveComponent::new())
+    guard::abort_on_unwind(move || Box::new(PossibleTimeSaveComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn PossibleTimeSaveComponent_drop(this: OwnedPossibleTimeSaveComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
@@ -30,7 +30,7 @@ pub extern "C" fn PossibleTimeSaveComponent_drop(this: OwnedPossibleTimeSaveComp
 pub extern "C" fn PossibleTimeSaveComponent_into_generic(
     this: OwnedPossibleTimeSaveComponent,
 ) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -39,16 +39,36 @@ pub extern "C" fn PossibleTimeSaveComponent_state_as_json(
     this: &PossibleTimeSaveComponent,
     timer: &Timer,
 ) -> Json {
-    output_vec(|o| {
-        this.state(&timer.snapshot()).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(&timer.snapshot()).write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of PossibleTimeSaveComponent_state_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn PossibleTimeSaveComponent_state_as_json_u16(
+    this: &PossibleTimeSaveComponent,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        PossibleTimeSaveComponent_state_as_json(this, timer),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer provided.
 #[no_mangle]
 pub extern "C" fn PossibleTimeSaveComponent_state(
     this: &PossibleTimeSaveComponent,
     timer: &Timer,
 ) -> OwnedKeyValueComponentState {
-    Box::new(this.state(&timer.snapshot()))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(&timer.snapshot()))
+    })
 }
@@ -3,7 +3,7 @@
 //! up can then be turned into an actual clean up in order to apply it to the
 //! Run.
 
-use super::output_vec;
+use super::{guard, output_utf16_from, output_vec};
 use livesplit_core::run::editor::cleaning::PotentialCleanUp;
 use std::{io::Write, os::raw::c_char};
 
@@ -15,12 +15,25 @@ pub type NullableOwnedPotentialCleanUp = Option<OwnedPotentialCleanUp>;
 /// drop
 #[no_mangle]
 pub extern "C" fn PotentialCleanUp_drop(this: OwnedPotentialCleanUp) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Accesses the message describing the potential clean up that can be applied
 /// to a Run.
 #[no_mangle]
 pub extern "C" fn PotentialCleanUp_message(this: &PotentialCleanUp<'static>) -> *const c_char {
-    output_vec(|s| write!(s, "{this}").unwrap())
+    guard::call(guard::address(this), move || {
+        output_vec(|s| write!(s, "{this}").unwrap())
+    })
+}
+
+/// The UTF-16 variant of PotentialCleanUp_message. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn PotentialCleanUp_message_u16(
+    this: &PotentialCleanUp<'static>,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(PotentialCleanUp_message(this), buf, buf_len)
 }
@@ -4,7 +4,7 @@
 //! displayed. This component switches to a `Live Segment` view that shows
 //! active time loss whenever the runner is losing time on the current segment.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::component::OwnedComponent;
 use crate::key_value_component_state::OwnedKeyValueComponentState;
 use livesplit_core::component::previous_segment::Component as PreviousSegmentComponent;
@@ -16,13 +16,13 @@ pub type OwnedPreviousSegmentComponent = Box<PreviousSegmentComponent>;
 /// Creates a new Previous Segment Component.
 #[no_mangle]
 pub extern "C" fn PreviousSegmentComponent_new() -> OwnedPreviousSegmentComponent {
-    Box::new(PreviousSegmentComponent::new())
+    guard::abort_on_unwind(move || Box::new(PreviousSegmentComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn PreviousSegmentComponent_drop(this: OwnedPreviousSegmentComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
@@ -31,7 +31,7 @@ pub extern "C" fn PreviousSegmentComponent_drop(this: OwnedPreviousSegmentCompon
 pub extern "C" fn PreviousSegmentComponent_into_generic(
     this: OwnedPreviousSegmentComponent,
 ) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -41,13 +41,32 @@ pub extern "C" fn PreviousSegmentComponent_state_as_json(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> Json {
-    output_vec(|o| {
-        this.state(&timer.snapshot(), layout_settings)
-            .write_json(o)
-            .unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(&timer.snapshot(), layout_settings)
+                .write_json(o)
+                .unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of PreviousSegmentComponent_state_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn PreviousSegmentComponent_state_as_json_u16(
+    this: &PreviousSegmentComponent,
+    timer: &Timer,
+    layout_settings: &GeneralLayoutSettings,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        PreviousSegmentComponent_state_as_json(this, timer, layout_settings),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer and the layout
 /// settings provided.
 #[no_mangle]
@@ -56,5 +75,7 @@ pub extern "C" fn PreviousSegmentComponent_state(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> OwnedKeyValueComponentState {
-    Box::new(this.state(&timer.snapshot(), layout_settings))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(&timer.snapshot(), layout_settings))
+    })
 }
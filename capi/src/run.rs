@@ -1,23 +1,34 @@
 //! A Run stores the split times for a specific game and category of a runner.
 
-use super::{get_file, output_str, output_time_span, output_vec, str};
+use super::{
+    get_file, guard, output_str, output_time_span, output_utf16_from, output_vec, str, Utf16Param,
+};
 use crate::{
-    linked_layout::NullableOwnedLinkedLayout, parse_run_result::OwnedParseRunResult,
-    segment::OwnedSegment, slice, with_vec,
+    attempt::NullableAttempt,
+    cancellation_token::CancellationToken,
+    decoded_image::NullableOwnedDecodedImage,
+    last_error::{self, ErrorCode},
+    linked_layout::NullableOwnedLinkedLayout,
+    parse_run_result::OwnedParseRunResult,
+    segment::{NullableSegment, OwnedSegment},
+    slice, with_vec,
 };
 use livesplit_core::{
     run::{
         parser,
         saver::{self, livesplit::IoWrite},
     },
-    Attempt, Run, RunMetadata, Segment, TimeSpan,
+    Run, RunMetadata, TimeSpan,
 };
 use std::{
-    io::{Read, Write},
+    io::{self, Read, Write},
     os::raw::c_char,
     path::Path,
+    ptr,
 };
 
+const OUT_OF_BOUNDS: &str = "The index is out of bounds.";
+
 /// type
 pub type OwnedRun = Box<Run>;
 /// type
@@ -26,13 +37,13 @@ pub type NullableOwnedRun = Option<OwnedRun>;
 /// Creates a new Run object with no segments.
 #[no_mangle]
 pub extern "C" fn Run_new() -> OwnedRun {
-    Box::new(Run::new())
+    guard::abort_on_unwind(move || Box::new(Run::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn Run_drop(this: OwnedRun) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Attempts to parse a splits file from an array by invoking the corresponding
@@ -41,21 +52,43 @@ pub extern "C" fn Run_drop(this: OwnedRun) {
 /// If you are using livesplit-core in a server-like environment, set this to
 /// <NULL>. Only client-side applications should provide a path here. Unlike the
 /// normal parsing function, it also fixes problems in the Run, such as
-/// decreasing times and missing information.
+/// decreasing times and missing information. If parsing fails, the Last Error
+/// describes what went wrong.
 #[no_mangle]
 pub unsafe extern "C" fn Run_parse(
     data: *const u8,
     length: usize,
     load_files_path: *const c_char,
 ) -> OwnedParseRunResult {
-    let load_files_path = str(load_files_path);
-    let load_files_path = if !load_files_path.is_empty() {
-        Some(Path::new(load_files_path))
-    } else {
-        None
-    };
+    Box::new(guard::call(0, move || {
+        let load_files_path = str(load_files_path);
+        let load_files_path = if !load_files_path.is_empty() {
+            Some(Path::new(load_files_path))
+        } else {
+            None
+        };
+
+        last_error::track(
+            ErrorCode::Parse,
+            parser::composite::parse(slice(data, length), load_files_path),
+        )
+    }))
+}
 
-    Box::new(parser::composite::parse(slice(data, length), load_files_path).ok())
+/// The UTF-16 variant of Run_parse. See get_buf_as_utf16 for how the strings
+/// are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_parse_u16(
+    data: *const u8,
+    length: usize,
+    load_files_path: *const u16,
+    load_files_path_len: usize,
+) -> OwnedParseRunResult {
+    Run_parse(
+        data,
+        length,
+        Utf16Param::new(load_files_path, load_files_path_len).as_ptr(),
+    )
 }
 
 /// Attempts to parse a splits file from a file by invoking the corresponding
@@ -66,78 +99,288 @@ pub unsafe extern "C" fn Run_parse(
 /// normal parsing function, it also fixes problems in the Run, such as
 /// decreasing times and missing information. On Unix you pass a file descriptor
 /// to this function. On Windows you pass a file handle to this function. The
-/// file descriptor / handle does not get closed.
+/// file descriptor / handle does not get closed. If the file can't be read or
+/// parsing fails, the Last Error describes what went wrong.
 #[no_mangle]
 pub unsafe extern "C" fn Run_parse_file_handle(
     handle: i64,
     load_files_path: *const c_char,
 ) -> OwnedParseRunResult {
-    let load_files_path = str(load_files_path);
-    let load_files_path = if !load_files_path.is_empty() {
-        Some(Path::new(load_files_path))
-    } else {
-        None
-    };
+    Box::new(guard::call(0, move || {
+        let load_files_path = str(load_files_path);
+        let load_files_path = if !load_files_path.is_empty() {
+            Some(Path::new(load_files_path))
+        } else {
+            None
+        };
+
+        let mut file = get_file(handle);
+
+        with_vec(|buf| {
+            last_error::track(ErrorCode::Io, file.read_to_end(buf))
+                .and_then(|_| {
+                    last_error::track(
+                        ErrorCode::Parse,
+                        parser::composite::parse(buf, load_files_path),
+                    )
+                })
+                .map(|p| p.into_owned())
+        })
+    }))
+}
+
+/// The UTF-16 variant of Run_parse_file_handle. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_parse_file_handle_u16(
+    handle: i64,
+    load_files_path: *const u16,
+    load_files_path_len: usize,
+) -> OwnedParseRunResult {
+    Run_parse_file_handle(
+        handle,
+        Utf16Param::new(load_files_path, load_files_path_len).as_ptr(),
+    )
+}
 
-    let mut file = get_file(handle);
+/// Attempts to parse a splits file from an array just like Run_parse, but
+/// aborts parsing once the Cancellation Token provided gets cancelled. If
+/// parsing got cancelled, the Last Error is set accordingly.
+#[no_mangle]
+pub unsafe extern "C" fn Run_parse_cancellable(
+    data: *const u8,
+    length: usize,
+    load_files_path: *const c_char,
+    cancellation_token: &CancellationToken,
+) -> OwnedParseRunResult {
+    Box::new(guard::call(0, move || {
+        let load_files_path = str(load_files_path);
+        let load_files_path = if !load_files_path.is_empty() {
+            Some(Path::new(load_files_path))
+        } else {
+            None
+        };
+
+        track_cancellation(parser::composite::parse_cancellable(
+            slice(data, length),
+            load_files_path,
+            || cancellation_token.is_cancelled(),
+        ))
+    }))
+}
+
+/// The UTF-16 variant of Run_parse_cancellable. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_parse_cancellable_u16(
+    data: *const u8,
+    length: usize,
+    load_files_path: *const u16,
+    load_files_path_len: usize,
+    cancellation_token: &CancellationToken,
+) -> OwnedParseRunResult {
+    Run_parse_cancellable(
+        data,
+        length,
+        Utf16Param::new(load_files_path, load_files_path_len).as_ptr(),
+        cancellation_token,
+    )
+}
 
-    with_vec(|buf| {
-        Box::new(
-            file.read_to_end(buf)
-                .ok()
-                .and_then(|_| parser::composite::parse(buf, load_files_path).ok())
-                .map(|p| p.into_owned()),
-        )
-    })
+/// Attempts to parse a splits file from a file just like
+/// Run_parse_file_handle, but aborts reading and parsing the file once the
+/// Cancellation Token provided gets cancelled. If reading or parsing got
+/// cancelled, the Last Error is set accordingly.
+#[no_mangle]
+pub unsafe extern "C" fn Run_parse_file_handle_cancellable(
+    handle: i64,
+    load_files_path: *const c_char,
+    cancellation_token: &CancellationToken,
+) -> OwnedParseRunResult {
+    Box::new(guard::call(0, move || {
+        let load_files_path = str(load_files_path);
+        let load_files_path = if !load_files_path.is_empty() {
+            Some(Path::new(load_files_path))
+        } else {
+            None
+        };
+
+        let mut file = get_file(handle);
+
+        with_vec(|buf| {
+            let mut chunk = [0; 64 << 10];
+            loop {
+                if cancellation_token.is_cancelled() {
+                    last_error::set(ErrorCode::Cancelled, parser::composite::Error::Cancelled);
+                    return None;
+                }
+                match file.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(read) => buf.extend_from_slice(&chunk[..read]),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => {
+                        last_error::set(ErrorCode::Io, e);
+                        return None;
+                    }
+                }
+            }
+
+            track_cancellation(parser::composite::parse_cancellable(
+                buf,
+                load_files_path,
+                || cancellation_token.is_cancelled(),
+            ))
+            .map(|p| p.into_owned())
+        })
+    }))
+}
+
+/// The UTF-16 variant of Run_parse_file_handle_cancellable. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_parse_file_handle_cancellable_u16(
+    handle: i64,
+    load_files_path: *const u16,
+    load_files_path_len: usize,
+    cancellation_token: &CancellationToken,
+) -> OwnedParseRunResult {
+    Run_parse_file_handle_cancellable(
+        handle,
+        Utf16Param::new(load_files_path, load_files_path_len).as_ptr(),
+        cancellation_token,
+    )
+}
+
+fn track_cancellation<T>(result: Result<T, parser::composite::Error>) -> Option<T> {
+    let code = match result {
+        Err(parser::composite::Error::Cancelled) => ErrorCode::Cancelled,
+        _ => ErrorCode::Parse,
+    };
+    last_error::track(code, result)
 }
 
 /// Clones the Run object.
 #[no_mangle]
 pub extern "C" fn Run_clone(this: &Run) -> OwnedRun {
-    Box::new(this.clone())
+    guard::call_or_abort(guard::address(this), move || Box::new(this.clone()))
 }
 
 /// Pushes the segment provided to the end of the list of segments of this Run.
 #[no_mangle]
 pub extern "C" fn Run_push_segment(this: &mut Run, segment: OwnedSegment) {
-    this.push_segment(*segment);
+    guard::consume(&*segment);
+    guard::call(guard::address(this), move || {
+        this.push_segment(*segment);
+    })
 }
 
 /// Accesses the name of the game this Run is for.
 #[no_mangle]
 pub extern "C" fn Run_game_name(this: &Run) -> *const c_char {
-    output_str(this.game_name())
+    guard::call(guard::address(this), move || output_str(this.game_name()))
+}
+
+/// The UTF-16 variant of Run_game_name. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_game_name_u16(this: &Run, buf: *mut u16, buf_len: usize) -> usize {
+    output_utf16_from(Run_game_name(this), buf, buf_len)
 }
 
 /// Sets the name of the game this Run is for.
 #[no_mangle]
 pub unsafe extern "C" fn Run_set_game_name(this: &mut Run, game: *const c_char) {
-    this.set_game_name(str(game));
+    guard::call(guard::address(this), move || {
+        this.set_game_name(str(game));
+    })
+}
+
+/// The UTF-16 variant of Run_set_game_name. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_set_game_name_u16(this: &mut Run, game: *const u16, game_len: usize) {
+    Run_set_game_name(this, Utf16Param::new(game, game_len).as_ptr())
 }
 
 /// Accesses the game icon's data. If there is no game icon, this returns an
 /// empty buffer.
 #[no_mangle]
 pub extern "C" fn Run_game_icon_ptr(this: &Run) -> *const u8 {
-    this.game_icon().data().as_ptr()
+    guard::call(guard::address(this), move || {
+        this.game_icon().data().as_ptr()
+    })
 }
 
 /// Accesses the amount of bytes the game icon's data takes up.
 #[no_mangle]
 pub extern "C" fn Run_game_icon_len(this: &Run) -> usize {
-    this.game_icon().data().len()
+    guard::call(guard::address(this), move || this.game_icon().data().len())
+}
+
+/// Accesses the media type of the game icon's data, such as image/png. The
+/// format is detected from the data itself. If there is no game icon or its
+/// format is not known, this returns an empty string.
+#[no_mangle]
+pub extern "C" fn Run_game_icon_format(this: &Run) -> *const c_char {
+    guard::call(guard::address(this), move || {
+        output_str(this.game_icon().format().map_or("", |f| f.media_type()))
+    })
+}
+
+/// The UTF-16 variant of Run_game_icon_format. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_game_icon_format_u16(
+    this: &Run,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Run_game_icon_format(this), buf, buf_len)
+}
+
+/// Decodes the game icon into its pixels and scales it down to fit into a
+/// square of the maximum size provided, keeping its aspect ratio. The decoded
+/// icon is cached, so this can be called on every frame. <NULL> is returned if
+/// there is no game icon or it couldn't be decoded.
+#[no_mangle]
+pub extern "C" fn Run_decode_game_icon(this: &Run, max_size: u32) -> NullableOwnedDecodedImage {
+    guard::call(guard::address(this), move || {
+        this.game_icon().decode(max_size).map(Box::new)
+    })
 }
 
 /// Accesses the name of the category this Run is for.
 #[no_mangle]
 pub extern "C" fn Run_category_name(this: &Run) -> *const c_char {
-    output_str(this.category_name())
+    guard::call(guard::address(this), move || {
+        output_str(this.category_name())
+    })
+}
+
+/// The UTF-16 variant of Run_category_name. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_category_name_u16(this: &Run, buf: *mut u16, buf_len: usize) -> usize {
+    output_utf16_from(Run_category_name(this), buf, buf_len)
 }
 
 /// Sets the name of the category this Run is for.
 #[no_mangle]
 pub unsafe extern "C" fn Run_set_category_name(this: &mut Run, category: *const c_char) {
-    this.set_category_name(str(category));
+    guard::call(guard::address(this), move || {
+        this.set_category_name(str(category));
+    })
+}
+
+/// The UTF-16 variant of Run_set_category_name. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_set_category_name_u16(
+    this: &mut Run,
+    category: *const u16,
+    category_len: usize,
+) {
+    Run_set_category_name(this, Utf16Param::new(category, category_len).as_ptr())
 }
 
 /// Returns a file name (without the extension) suitable for this Run that
@@ -153,7 +396,25 @@ pub extern "C" fn Run_extended_file_name(
     this: &Run,
     use_extended_category_name: bool,
 ) -> *const c_char {
-    output_str(this.extended_file_name(use_extended_category_name))
+    guard::call(guard::address(this), move || {
+        output_str(this.extended_file_name(use_extended_category_name))
+    })
+}
+
+/// The UTF-16 variant of Run_extended_file_name. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_extended_file_name_u16(
+    this: &Run,
+    use_extended_category_name: bool,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        Run_extended_file_name(this, use_extended_category_name),
+        buf,
+        buf_len,
+    )
 }
 
 /// Returns a name suitable for this Run that is built the following way:
@@ -164,7 +425,25 @@ pub extern "C" fn Run_extended_file_name(
 /// used, the variables of the category are appended in a parenthesis.
 #[no_mangle]
 pub extern "C" fn Run_extended_name(this: &Run, use_extended_category_name: bool) -> *const c_char {
-    output_str(this.extended_name(use_extended_category_name))
+    guard::call(guard::address(this), move || {
+        output_str(this.extended_name(use_extended_category_name))
+    })
+}
+
+/// The UTF-16 variant of Run_extended_name. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_extended_name_u16(
+    this: &Run,
+    use_extended_category_name: bool,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        Run_extended_name(this, use_extended_category_name),
+        buf,
+        buf_len,
+    )
 }
 
 /// Returns an extended category name that possibly includes the region,
@@ -179,80 +458,140 @@ pub extern "C" fn Run_extended_category_name(
     show_platform: bool,
     show_variables: bool,
 ) -> *const c_char {
-    output_vec(|o| {
-        let _ = write!(
-            o,
-            "{}",
-            this.extended_category_name(show_region, show_platform, show_variables),
-        );
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            let _ = write!(
+                o,
+                "{}",
+                this.extended_category_name(show_region, show_platform, show_variables),
+            );
+        })
+    })
+}
+
+/// The UTF-16 variant of Run_extended_category_name. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_extended_category_name_u16(
+    this: &Run,
+    show_region: bool,
+    show_platform: bool,
+    show_variables: bool,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        Run_extended_category_name(this, show_region, show_platform, show_variables),
+        buf,
+        buf_len,
+    )
+}
+
+/// Applies some fixing algorithms on the Run. This includes fixing the
+/// comparison times and history, removing duplicates in the segment histories
+/// and removing empty times.
+#[no_mangle]
+pub extern "C" fn Run_fix_splits(this: &mut Run) {
+    guard::call(guard::address(this), move || {
+        this.fix_splits();
+    })
+}
+
+/// Checks whether fixing the splits would change the Run. This can be used to
+/// find out whether the splits contain any inconsistencies that need to be
+/// repaired.
+#[no_mangle]
+pub extern "C" fn Run_needs_fixing(this: &Run) -> bool {
+    guard::call(guard::address(this), move || this.needs_fixing())
+}
+
+/// Removes all the Custom Comparisons that are listed more than once or that
+/// have the same name as one of the Comparison Generators. Returns whether any
+/// Custom Comparisons got removed.
+#[no_mangle]
+pub extern "C" fn Run_remove_duplicate_comparisons(this: &mut Run) -> bool {
+    guard::call(guard::address(this), move || {
+        this.remove_duplicate_comparisons()
     })
 }
 
 /// Returns the amount of runs that have been attempted with these splits.
 #[no_mangle]
 pub extern "C" fn Run_attempt_count(this: &Run) -> u32 {
-    this.attempt_count()
+    guard::call(guard::address(this), move || this.attempt_count())
 }
 
 /// Accesses additional metadata of this Run, like the platform and region
 /// of the game.
 #[no_mangle]
 pub extern "C" fn Run_metadata(this: &Run) -> &RunMetadata {
-    this.metadata()
+    guard::call_or_abort(guard::address(this), move || this.metadata())
 }
 
 /// Accesses the time an attempt of this Run should start at.
 #[no_mangle]
 pub extern "C" fn Run_offset(this: &Run) -> *const TimeSpan {
-    output_time_span(this.offset())
+    guard::call(guard::address(this), move || {
+        output_time_span(this.offset())
+    })
 }
 
 /// Returns the amount of segments stored in this Run.
 #[no_mangle]
 pub extern "C" fn Run_len(this: &Run) -> usize {
-    this.len()
+    guard::call(guard::address(this), move || this.len())
 }
 
 /// Marks the Run as modified, so that it is known that there are changes
 /// that should be saved.
 #[no_mangle]
 pub extern "C" fn Run_mark_as_modified(this: &mut Run) {
-    this.mark_as_modified();
+    guard::call(guard::address(this), move || {
+        this.mark_as_modified();
+    })
 }
 
 /// Returns whether the Run has been modified and should be saved so that the
 /// changes don't get lost.
 #[no_mangle]
 pub extern "C" fn Run_has_been_modified(this: &Run) -> bool {
-    this.has_been_modified()
+    guard::call(guard::address(this), move || this.has_been_modified())
 }
 
-/// Accesses a certain segment of this Run. You may not provide an out of bounds
-/// index.
+/// Accesses a certain segment of this Run. If the index is out of bounds,
+/// <NULL> is returned and the Last Error is set accordingly.
 #[no_mangle]
-pub extern "C" fn Run_segment(this: &Run, index: usize) -> &Segment {
-    this.segment(index)
+pub extern "C" fn Run_segment(this: &Run, index: usize) -> *const NullableSegment {
+    guard::call(guard::address(this), move || {
+        let segment = this.segments().get(index);
+        last_error::track(ErrorCode::InvalidInput, segment.ok_or(OUT_OF_BOUNDS))
+            .map_or(ptr::null(), |segment| segment)
+    })
 }
 
 /// Returns the amount of segments in this Run.
 #[no_mangle]
 pub extern "C" fn Run_segments_len(this: &Run) -> usize {
-    this.segments().len()
+    guard::call(guard::address(this), move || this.segments().len())
 }
 
 /// Returns the amount attempt history elements are stored in this Run.
 #[no_mangle]
 pub extern "C" fn Run_attempt_history_len(this: &Run) -> usize {
-    this.attempt_history().len()
+    guard::call(guard::address(this), move || this.attempt_history().len())
 }
 
 /// Accesses the an attempt history element by its index. This does not store
 /// the actual segment times, just the overall attempt information. Information
-/// about the individual segments is stored within each segment. You may not
-/// provide an out of bounds index.
-#[no_mangle]
-pub extern "C" fn Run_attempt_history_index(this: &Run, index: usize) -> &Attempt {
-    &this.attempt_history()[index]
+/// about the individual segments is stored within each segment. If the index
+/// is out of bounds, <NULL> is returned and the Last Error is set accordingly.
+#[no_mangle]
+pub extern "C" fn Run_attempt_history_index(this: &Run, index: usize) -> *const NullableAttempt {
+    guard::call(guard::address(this), move || {
+        let attempt = this.attempt_history().get(index);
+        last_error::track(ErrorCode::InvalidInput, attempt.ok_or(OUT_OF_BOUNDS))
+            .map_or(ptr::null(), |attempt| attempt)
+    })
 }
 
 /// Saves a Run as a LiveSplit splits file (*.lss). If the run is actively in
@@ -260,15 +599,26 @@ pub extern "C" fn Run_attempt_history_index(this: &Run, index: usize) -> &Attemp
 /// properly save the current attempt as well.
 #[no_mangle]
 pub extern "C" fn Run_save_as_lss(this: &Run) -> *const c_char {
-    output_vec(|o| {
-        saver::livesplit::save_run(this, IoWrite(o)).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            saver::livesplit::save_run(this, IoWrite(o)).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of Run_save_as_lss. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_save_as_lss_u16(this: &Run, buf: *mut u16, buf_len: usize) -> usize {
+    output_utf16_from(Run_save_as_lss(this), buf, buf_len)
+}
+
 /// Returns the amount of custom comparisons stored in this Run.
 #[no_mangle]
 pub extern "C" fn Run_custom_comparisons_len(this: &Run) -> usize {
-    this.custom_comparisons().len()
+    guard::call(guard::address(this), move || {
+        this.custom_comparisons().len()
+    })
 }
 
 /// Accesses a custom comparison stored in this Run by its index. This includes
@@ -276,13 +626,29 @@ pub extern "C" fn Run_custom_comparisons_len(this: &Run) -> usize {
 /// not provide an out of bounds index.
 #[no_mangle]
 pub extern "C" fn Run_custom_comparison(this: &Run, index: usize) -> *const c_char {
-    output_str(&this.custom_comparisons()[index])
+    guard::call(guard::address(this), move || {
+        output_str(&this.custom_comparisons()[index])
+    })
+}
+
+/// The UTF-16 variant of Run_custom_comparison. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_custom_comparison_u16(
+    this: &Run,
+    index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Run_custom_comparison(this, index), buf, buf_len)
 }
 
 /// Returns the amount of total comparisons stored in this Run.
 #[no_mangle]
 pub extern "C" fn Run_comparisons_len(this: &Run) -> usize {
-    this.custom_comparisons().len() + this.comparison_generators().len()
+    guard::call(guard::address(this), move || {
+        this.custom_comparisons().len() + this.comparison_generators().len()
+    })
 }
 
 /// Accesses a comparison stored in this Run by its index. This includes both
@@ -290,26 +656,106 @@ pub extern "C" fn Run_comparisons_len(this: &Run) -> usize {
 /// provide an out of bounds index.
 #[no_mangle]
 pub extern "C" fn Run_comparison(this: &Run, index: usize) -> *const c_char {
-    let custom_len = this.custom_comparisons().len();
-    output_str(if index < custom_len {
-        &this.custom_comparisons()[index]
-    } else {
-        this.comparison_generators()[index - custom_len].name()
+    guard::call(guard::address(this), move || {
+        let custom_len = this.custom_comparisons().len();
+        output_str(if index < custom_len {
+            &this.custom_comparisons()[index]
+        } else {
+            this.comparison_generators()[index - custom_len].name()
+        })
     })
 }
 
+/// The UTF-16 variant of Run_comparison. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_comparison_u16(
+    this: &Run,
+    index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Run_comparison(this, index), buf, buf_len)
+}
+
 /// Accesses the Auto Splitter Settings that are encoded as XML.
 #[no_mangle]
 pub extern "C" fn Run_auto_splitter_settings(this: &Run) -> *const c_char {
-    output_str(this.auto_splitter_settings())
+    guard::call(guard::address(this), move || {
+        output_str(this.auto_splitter_settings())
+    })
+}
+
+/// The UTF-16 variant of Run_auto_splitter_settings. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Run_auto_splitter_settings_u16(
+    this: &Run,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Run_auto_splitter_settings(this), buf, buf_len)
 }
 
 /// Accesses the linked layout of this Run. If a Layout is linked, it is
 /// supposed to be loaded to visualize the Run.
 #[no_mangle]
 pub extern "C" fn Run_linked_layout(this: &Run) -> NullableOwnedLinkedLayout {
-    // FIXME: Unnecessary clone. The binding generator can't handle optional
-    // references yet.
-    this.linked_layout()
-        .map(|linked_layout| Box::new(linked_layout.clone()))
+    guard::call(guard::address(this), move || {
+        // FIXME: Unnecessary clone. The binding generator can't handle optional
+        // references yet.
+        this.linked_layout()
+            .map(|linked_layout| Box::new(linked_layout.clone()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        last_error::LastError_code,
+        parse_run_result::ParseRunResult_unwrap,
+        segment::{Segment_icon_format, Segment_icon_len, Segment_icon_ptr},
+    };
+    use std::ffi::CStr;
+
+    fn read_str(s: *const c_char) -> String {
+        unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn icon_formats() {
+        let lss = include_bytes!("../../tests/run_files/Celeste - Any% (1.2.1.5).lss");
+        let result = unsafe { Run_parse(lss.as_ptr(), lss.len(), ptr::null()) };
+        let run = ParseRunResult_unwrap(result);
+
+        assert_eq!(read_str(Run_game_icon_format(&run)), "image/png");
+        let icon = unsafe { slice(Run_game_icon_ptr(&run), Run_game_icon_len(&run)) };
+        assert!(icon.starts_with(b"\x89PNG"));
+
+        let segment = unsafe { &*Run_segment(&run, 0) };
+        assert_eq!(read_str(Segment_icon_format(segment)), "image/png");
+        let icon = unsafe { slice(Segment_icon_ptr(segment), Segment_icon_len(segment)) };
+        assert!(icon.starts_with(b"\x89PNG"));
+
+        Run_drop(run);
+    }
+
+    #[test]
+    fn out_of_bounds_indices_return_null() {
+        let run = Run_new();
+        assert!(Run_segment(&run, 0).is_null());
+        assert_eq!(LastError_code(), ErrorCode::InvalidInput as u32);
+        assert!(Run_attempt_history_index(&run, 0).is_null());
+        assert_eq!(LastError_code(), ErrorCode::InvalidInput as u32);
+        Run_drop(run);
+    }
+
+    #[test]
+    fn missing_icons_have_no_format() {
+        let run = Run_new();
+        assert_eq!(Run_game_icon_len(&run), 0);
+        assert_eq!(read_str(Run_game_icon_format(&run)), "");
+        Run_drop(run);
+    }
 }
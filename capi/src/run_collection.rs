@@ -0,0 +1,143 @@
+//! A Run Collection aggregates statistics across a whole collection of runs,
+//! such as all the splits files of a runner. This includes the total playtime,
+//! the amount of attempts per game and category and a timeline of all the
+//! Personal Bests that got achieved.
+
+use super::{guard, output_str, output_utf16_from, str, Nullablec_char, Utf16Param};
+use crate::{
+    last_error::{self, ErrorCode},
+    run::OwnedRun,
+};
+use livesplit_core::{analysis::run_collection::RunCollection, DateTime, TimingMethod};
+use std::{os::raw::c_char, path::Path};
+
+/// type
+pub type OwnedRunCollection = Box<RunCollection>;
+/// type
+pub type NullableOwnedRunCollection = Option<OwnedRunCollection>;
+
+/// Creates a new empty Run Collection.
+#[no_mangle]
+pub extern "C" fn RunCollection_new() -> OwnedRunCollection {
+    guard::abort_on_unwind(move || Box::new(RunCollection::new()))
+}
+
+/// Loads all the splits files stored directly in the directory provided.
+/// Files that can't be read or that are not splits files in any of the
+/// supported formats are skipped. Subdirectories are not searched. <NULL> is
+/// returned if the directory couldn't be read.
+#[no_mangle]
+pub unsafe extern "C" fn RunCollection_load_directory(
+    path: *const c_char,
+) -> NullableOwnedRunCollection {
+    guard::call(0, move || {
+        last_error::track(
+            ErrorCode::Io,
+            RunCollection::load_directory(Path::new(str(path))),
+        )
+        .map(Box::new)
+    })
+}
+
+/// The UTF-16 variant of RunCollection_load_directory. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunCollection_load_directory_u16(
+    path: *const u16,
+    path_len: usize,
+) -> NullableOwnedRunCollection {
+    RunCollection_load_directory(Utf16Param::new(path, path_len).as_ptr())
+}
+
+/// drop
+#[no_mangle]
+pub extern "C" fn RunCollection_drop(this: OwnedRunCollection) {
+    guard::dispose(this);
+}
+
+/// Adds a run to the collection.
+#[no_mangle]
+pub extern "C" fn RunCollection_push(this: &mut RunCollection, run: OwnedRun) {
+    guard::consume(&*run);
+    guard::call(guard::address(this), move || {
+        this.push(*run);
+    })
+}
+
+/// Returns the amount of runs in the collection.
+#[no_mangle]
+pub extern "C" fn RunCollection_len(this: &RunCollection) -> usize {
+    guard::call(guard::address(this), move || this.len())
+}
+
+/// Aggregates the statistics across all the runs of the collection and
+/// encodes them as JSON. The timing method is used for determining the
+/// Personal Bests. All the times are encoded as seconds and all the dates are
+/// encoded as Unix timestamps in seconds.
+#[no_mangle]
+pub extern "C" fn RunCollection_statistics_as_json(
+    this: &RunCollection,
+    method: TimingMethod,
+) -> *const c_char {
+    guard::call(guard::address(this), move || {
+        output_str(this.statistics(method, None, None).to_json())
+    })
+}
+
+/// The UTF-16 variant of RunCollection_statistics_as_json. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunCollection_statistics_as_json_u16(
+    this: &RunCollection,
+    method: TimingMethod,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(RunCollection_statistics_as_json(this, method), buf, buf_len)
+}
+
+/// Aggregates the statistics across all the attempts of the collection that
+/// got started within the period of time provided and encodes them as JSON.
+/// The period is specified as Unix timestamps in seconds, where the start is
+/// inclusive and the end is exclusive. Attempts without a known start date are
+/// not considered. <NULL> is returned if one of the timestamps is out of the
+/// supported range.
+#[no_mangle]
+pub extern "C" fn RunCollection_statistics_in_period_as_json(
+    this: &RunCollection,
+    method: TimingMethod,
+    since: i64,
+    until: i64,
+) -> *const Nullablec_char {
+    guard::call(guard::address(this), move || {
+        let period = last_error::track(
+            ErrorCode::Parse,
+            DateTime::from_unix_timestamp(since)
+                .and_then(|since| Ok((since, DateTime::from_unix_timestamp(until)?))),
+        );
+        match period {
+            Some((since, until)) => {
+                output_str(this.statistics(method, Some(since), Some(until)).to_json())
+            }
+            None => std::ptr::null(),
+        }
+    })
+}
+
+/// The UTF-16 variant of RunCollection_statistics_in_period_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunCollection_statistics_in_period_as_json_u16(
+    this: &RunCollection,
+    method: TimingMethod,
+    since: i64,
+    until: i64,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        RunCollection_statistics_in_period_as_json(this, method, since, until),
+        buf,
+        buf_len,
+    )
+}
@@ -3,14 +3,19 @@
 //! are being applied to the Run. It provides the current state of the editor as
 //! state objects that can be visualized by any kind of User Interface.
 
-use super::{output_vec, str, Json};
+use super::{guard, output_into_buf, output_utf16_from, output_vec, str, Json, Utf16Param};
 use crate::{
-    linked_layout::OwnedLinkedLayout, run::OwnedRun, slice,
+    last_error::{self, ErrorCode},
+    linked_layout::OwnedLinkedLayout,
+    run::OwnedRun,
+    segment_clipboard::{NullableOwnedSegmentClipboard, OwnedSegmentClipboard},
+    slice,
     sum_of_best_cleaner::OwnedSumOfBestCleaner,
 };
 use livesplit_core::{
-    settings::{Image, ImageCache},
-    Run, RunEditor, TimingMethod,
+    run::editor::{ImportColumn, SegmentClipboard},
+    settings::{Image, ImageCache, ImageTransform},
+    Run, RunEditor, TimeSpan, TimingMethod,
 };
 use std::os::raw::c_char;
 
@@ -25,7 +30,10 @@ pub type NullableOwnedRunEditor = Option<OwnedRunEditor>;
 /// returned.
 #[no_mangle]
 pub extern "C" fn RunEditor_new(run: OwnedRun) -> NullableOwnedRunEditor {
-    RunEditor::new(*run).ok().map(Box::new)
+    guard::consume(&*run);
+    guard::call(0, move || {
+        last_error::track(ErrorCode::EmptyInput, RunEditor::new(*run)).map(Box::new)
+    })
 }
 
 /// Closes the Run Editor and gives back access to the modified Run object. In
@@ -33,22 +41,57 @@ pub extern "C" fn RunEditor_new(run: OwnedRun) -> NullableOwnedRunEditor {
 /// get here.
 #[no_mangle]
 pub extern "C" fn RunEditor_close(this: OwnedRunEditor) -> OwnedRun {
-    Box::new((*this).close())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).close()))
 }
 
 /// Calculates the Run Editor's state and encodes it as
 /// JSON in order to visualize it.
 #[no_mangle]
 pub extern "C" fn RunEditor_state_as_json(this: &RunEditor, image_cache: &mut ImageCache) -> Json {
-    output_vec(|o| {
-        this.state(image_cache).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(image_cache).write_json(o).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of RunEditor_state_as_json. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_state_as_json_u16(
+    this: &RunEditor,
+    image_cache: &mut ImageCache,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(RunEditor_state_as_json(this, image_cache), buf, buf_len)
+}
+
+/// Calculates the Run Editor's state and encodes it as JSON directly into the
+/// buffer provided. The JSON is not nul-terminated. The length of the JSON is
+/// returned. If it is larger than the buffer, the buffer only contains the
+/// beginning of the JSON. As long as the Run Editor isn't modified, calling
+/// this again with a larger buffer results in the same JSON.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_state_as_json_into_buf(
+    this: &RunEditor,
+    image_cache: &mut ImageCache,
+    buf: *mut u8,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_into_buf(buf, buf_len, |o| {
+            this.state(image_cache).write_json(o).unwrap();
+        })
     })
 }
 
 /// Selects a different timing method for being modified.
 #[no_mangle]
 pub extern "C" fn RunEditor_select_timing_method(this: &mut RunEditor, method: TimingMethod) {
-    this.select_timing_method(method);
+    guard::call(guard::address(this), move || {
+        this.select_timing_method(method);
+    })
 }
 
 /// Unselects the segment with the given index. If it's not selected or the
@@ -58,7 +101,9 @@ pub extern "C" fn RunEditor_select_timing_method(this: &mut RunEditor, method: T
 /// active segment.
 #[no_mangle]
 pub extern "C" fn RunEditor_unselect(this: &mut RunEditor, index: usize) {
-    this.unselect(index);
+    guard::call(guard::address(this), move || {
+        this.unselect(index);
+    })
 }
 
 /// In addition to the segments that are already selected, the segment with
@@ -68,7 +113,9 @@ pub extern "C" fn RunEditor_unselect(this: &mut RunEditor, index: usize) {
 /// This panics if the index of the segment provided is out of bounds.
 #[no_mangle]
 pub extern "C" fn RunEditor_select_additionally(this: &mut RunEditor, index: usize) {
-    this.select_additionally(index);
+    guard::call(guard::address(this), move || {
+        this.select_additionally(index);
+    })
 }
 
 /// Selects the segment with the given index. All other segments are
@@ -77,13 +124,28 @@ pub extern "C" fn RunEditor_select_additionally(this: &mut RunEditor, index: usi
 /// This panics if the index of the segment provided is out of bounds.
 #[no_mangle]
 pub extern "C" fn RunEditor_select_only(this: &mut RunEditor, index: usize) {
-    this.select_only(index);
+    guard::call(guard::address(this), move || {
+        this.select_only(index);
+    })
 }
 
 /// Sets the name of the game.
 #[no_mangle]
 pub unsafe extern "C" fn RunEditor_set_game_name(this: &mut RunEditor, game: *const c_char) {
-    this.set_game_name(str(game));
+    guard::call(guard::address(this), move || {
+        this.set_game_name(str(game));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_set_game_name. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_game_name_u16(
+    this: &mut RunEditor,
+    game: *const u16,
+    game_len: usize,
+) {
+    RunEditor_set_game_name(this, Utf16Param::new(game, game_len).as_ptr())
 }
 
 /// Sets the name of the category.
@@ -92,7 +154,20 @@ pub unsafe extern "C" fn RunEditor_set_category_name(
     this: &mut RunEditor,
     category: *const c_char,
 ) {
-    this.set_category_name(str(category));
+    guard::call(guard::address(this), move || {
+        this.set_category_name(str(category));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_set_category_name. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_category_name_u16(
+    this: &mut RunEditor,
+    category: *const u16,
+    category_len: usize,
+) {
+    RunEditor_set_category_name(this, Utf16Param::new(category, category_len).as_ptr())
 }
 
 /// Parses and sets the timer offset from the string provided. The timer
@@ -103,7 +178,20 @@ pub unsafe extern "C" fn RunEditor_parse_and_set_offset(
     this: &mut RunEditor,
     offset: *const c_char,
 ) -> bool {
-    this.parse_and_set_offset(str(offset)).is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(ErrorCode::Parse, this.parse_and_set_offset(str(offset))).is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_parse_and_set_offset. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_parse_and_set_offset_u16(
+    this: &mut RunEditor,
+    offset: *const u16,
+    offset_len: usize,
+) -> bool {
+    RunEditor_parse_and_set_offset(this, Utf16Param::new(offset, offset_len).as_ptr())
 }
 
 /// Parses and sets the attempt count from the string provided. Changing
@@ -114,7 +202,24 @@ pub unsafe extern "C" fn RunEditor_parse_and_set_attempt_count(
     this: &mut RunEditor,
     attempts: *const c_char,
 ) -> bool {
-    this.parse_and_set_attempt_count(str(attempts)).is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Parse,
+            this.parse_and_set_attempt_count(str(attempts)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_parse_and_set_attempt_count. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_parse_and_set_attempt_count_u16(
+    this: &mut RunEditor,
+    attempts: *const u16,
+    attempts_len: usize,
+) -> bool {
+    RunEditor_parse_and_set_attempt_count(this, Utf16Param::new(attempts, attempts_len).as_ptr())
 }
 
 /// Sets the game's icon.
@@ -124,13 +229,43 @@ pub unsafe extern "C" fn RunEditor_set_game_icon(
     data: *const u8,
     length: usize,
 ) {
-    this.set_game_icon(Image::new(slice(data, length).into(), Image::ICON));
+    guard::call(guard::address(this), move || {
+        this.set_game_icon(Image::new(slice(data, length).into(), Image::ICON));
+    })
+}
+
+/// Sets the game's icon after transforming it. The image can be cropped to the
+/// largest square in its center and it gets scaled down to fit into a square of
+/// the maximum size provided. Stripping the metadata re-encodes the image
+/// without any of its metadata.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_game_icon_transformed(
+    this: &mut RunEditor,
+    data: *const u8,
+    length: usize,
+    crop_to_square: bool,
+    max_size: u32,
+    strip_metadata: bool,
+) {
+    guard::call(guard::address(this), move || {
+        let transform = ImageTransform {
+            crop_to_square,
+            max_size,
+            strip_metadata,
+        };
+        this.set_game_icon(Image::with_transform(
+            slice(data, length).into(),
+            &transform,
+        ));
+    })
 }
 
 /// Removes the game's icon.
 #[no_mangle]
 pub extern "C" fn RunEditor_remove_game_icon(this: &mut RunEditor) {
-    this.remove_game_icon();
+    guard::call(guard::address(this), move || {
+        this.remove_game_icon();
+    })
 }
 
 /// Sets the Linked Layout of the Run. If a Layout is linked, it is supposed to
@@ -140,14 +275,19 @@ pub extern "C" fn RunEditor_set_linked_layout(
     this: &mut RunEditor,
     linked_layout: OwnedLinkedLayout,
 ) {
-    this.set_linked_layout(Some(*linked_layout));
+    guard::consume(&*linked_layout);
+    guard::call(guard::address(this), move || {
+        this.set_linked_layout(Some(*linked_layout));
+    })
 }
 
 /// Removes the Linked Layout of the Run if there is one. If a Layout is linked,
 /// it is supposed to be loaded to visualize the Run.
 #[no_mangle]
 pub extern "C" fn RunEditor_remove_linked_layout(this: &mut RunEditor) {
-    this.set_linked_layout(None);
+    guard::call(guard::address(this), move || {
+        this.set_linked_layout(None);
+    })
 }
 
 /// Sets the speedrun.com Run ID of the run. You need to ensure that the
@@ -155,28 +295,69 @@ pub extern "C" fn RunEditor_remove_linked_layout(this: &mut RunEditor) {
 /// This may be empty if there's no association.
 #[no_mangle]
 pub unsafe extern "C" fn RunEditor_set_run_id(this: &mut RunEditor, name: *const c_char) {
-    this.set_run_id(str(name));
+    guard::call(guard::address(this), move || {
+        this.set_run_id(str(name));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_set_run_id. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_run_id_u16(
+    this: &mut RunEditor,
+    name: *const u16,
+    name_len: usize,
+) {
+    RunEditor_set_run_id(this, Utf16Param::new(name, name_len).as_ptr())
 }
 
 /// Sets the name of the region this game is from. This may be empty if it's
 /// not specified.
 #[no_mangle]
 pub unsafe extern "C" fn RunEditor_set_region_name(this: &mut RunEditor, name: *const c_char) {
-    this.set_region_name(str(name));
+    guard::call(guard::address(this), move || {
+        this.set_region_name(str(name));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_set_region_name. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_region_name_u16(
+    this: &mut RunEditor,
+    name: *const u16,
+    name_len: usize,
+) {
+    RunEditor_set_region_name(this, Utf16Param::new(name, name_len).as_ptr())
 }
 
 /// Sets the name of the platform this game is run on. This may be empty if
 /// it's not specified.
 #[no_mangle]
 pub unsafe extern "C" fn RunEditor_set_platform_name(this: &mut RunEditor, name: *const c_char) {
-    this.set_platform_name(str(name));
+    guard::call(guard::address(this), move || {
+        this.set_platform_name(str(name));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_set_platform_name. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_platform_name_u16(
+    this: &mut RunEditor,
+    name: *const u16,
+    name_len: usize,
+) {
+    RunEditor_set_platform_name(this, Utf16Param::new(name, name_len).as_ptr())
 }
 
 /// Specifies whether this speedrun is done on an emulator. Keep in mind
 /// that <FALSE> may also mean that this information is simply not known.
 #[no_mangle]
 pub extern "C" fn RunEditor_set_emulator_usage(this: &mut RunEditor, uses_emulator: bool) {
-    this.set_emulator_usage(uses_emulator);
+    guard::call(guard::address(this), move || {
+        this.set_emulator_usage(uses_emulator);
+    })
 }
 
 /// Sets the speedrun.com variable with the name specified to the value specified. A
@@ -190,7 +371,26 @@ pub unsafe extern "C" fn RunEditor_set_speedrun_com_variable(
     name: *const c_char,
     value: *const c_char,
 ) {
-    this.set_speedrun_com_variable(str(name), str(value));
+    guard::call(guard::address(this), move || {
+        this.set_speedrun_com_variable(str(name), str(value));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_set_speedrun_com_variable. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_speedrun_com_variable_u16(
+    this: &mut RunEditor,
+    name: *const u16,
+    name_len: usize,
+    value: *const u16,
+    value_len: usize,
+) {
+    RunEditor_set_speedrun_com_variable(
+        this,
+        Utf16Param::new(name, name_len).as_ptr(),
+        Utf16Param::new(value, value_len).as_ptr(),
+    )
 }
 
 /// Removes the speedrun.com variable with the name specified.
@@ -199,7 +399,20 @@ pub unsafe extern "C" fn RunEditor_remove_speedrun_com_variable(
     this: &mut RunEditor,
     name: *const c_char,
 ) {
-    this.remove_speedrun_com_variable(str(name));
+    guard::call(guard::address(this), move || {
+        this.remove_speedrun_com_variable(str(name));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_remove_speedrun_com_variable. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_remove_speedrun_com_variable_u16(
+    this: &mut RunEditor,
+    name: *const u16,
+    name_len: usize,
+) {
+    RunEditor_remove_speedrun_com_variable(this, Utf16Param::new(name, name_len).as_ptr())
 }
 
 /// Adds a new permanent custom variable. If there's a temporary variable with
@@ -207,7 +420,20 @@ pub unsafe extern "C" fn RunEditor_remove_speedrun_com_variable(
 /// If a permanent variable with the name already exists, nothing happens.
 #[no_mangle]
 pub unsafe extern "C" fn RunEditor_add_custom_variable(this: &mut RunEditor, name: *const c_char) {
-    this.add_custom_variable(str(name));
+    guard::call(guard::address(this), move || {
+        this.add_custom_variable(str(name));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_add_custom_variable. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_add_custom_variable_u16(
+    this: &mut RunEditor,
+    name: *const u16,
+    name_len: usize,
+) {
+    RunEditor_add_custom_variable(this, Utf16Param::new(name, name_len).as_ptr())
 }
 
 /// Sets the value of a custom variable with the name specified. If the custom
@@ -218,7 +444,26 @@ pub unsafe extern "C" fn RunEditor_set_custom_variable(
     name: *const c_char,
     value: *const c_char,
 ) {
-    this.set_custom_variable(str(name), str(value));
+    guard::call(guard::address(this), move || {
+        this.set_custom_variable(str(name), str(value));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_set_custom_variable. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_custom_variable_u16(
+    this: &mut RunEditor,
+    name: *const u16,
+    name_len: usize,
+    value: *const u16,
+    value_len: usize,
+) {
+    RunEditor_set_custom_variable(
+        this,
+        Utf16Param::new(name, name_len).as_ptr(),
+        Utf16Param::new(value, value_len).as_ptr(),
+    )
 }
 
 /// Removes the custom variable with the name specified. If the custom variable
@@ -228,13 +473,28 @@ pub unsafe extern "C" fn RunEditor_remove_custom_variable(
     this: &mut RunEditor,
     name: *const c_char,
 ) {
-    this.remove_custom_variable(str(name));
+    guard::call(guard::address(this), move || {
+        this.remove_custom_variable(str(name));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_remove_custom_variable. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_remove_custom_variable_u16(
+    this: &mut RunEditor,
+    name: *const u16,
+    name_len: usize,
+) {
+    RunEditor_remove_custom_variable(this, Utf16Param::new(name, name_len).as_ptr())
 }
 
 /// Resets all the Metadata Information.
 #[no_mangle]
 pub extern "C" fn RunEditor_clear_metadata(this: &mut RunEditor) {
-    this.clear_metadata();
+    guard::call(guard::address(this), move || {
+        this.clear_metadata();
+    })
 }
 
 /// Inserts a new empty segment above the active segment and adjusts the
@@ -242,7 +502,9 @@ pub extern "C" fn RunEditor_clear_metadata(this: &mut RunEditor) {
 /// the only selected segment and also the active segment.
 #[no_mangle]
 pub extern "C" fn RunEditor_insert_segment_above(this: &mut RunEditor) {
-    this.insert_segment_above();
+    guard::call(guard::address(this), move || {
+        this.insert_segment_above();
+    })
 }
 
 /// Inserts a new empty segment below the active segment and adjusts the
@@ -250,7 +512,9 @@ pub extern "C" fn RunEditor_insert_segment_above(this: &mut RunEditor) {
 /// the only selected segment and also the active segment.
 #[no_mangle]
 pub extern "C" fn RunEditor_insert_segment_below(this: &mut RunEditor) {
-    this.insert_segment_below();
+    guard::call(guard::address(this), move || {
+        this.insert_segment_below();
+    })
 }
 
 /// Removes all the selected segments, unless all of them are selected. The
@@ -260,7 +524,9 @@ pub extern "C" fn RunEditor_insert_segment_below(this: &mut RunEditor) {
 /// before the active segment, becomes the new active segment.
 #[no_mangle]
 pub extern "C" fn RunEditor_remove_segments(this: &mut RunEditor) {
-    this.remove_segments();
+    guard::call(guard::address(this), move || {
+        this.remove_segments();
+    })
 }
 
 /// Moves all the selected segments up, unless the first segment is
@@ -268,7 +534,9 @@ pub extern "C" fn RunEditor_remove_segments(this: &mut RunEditor) {
 /// active segment stays the active segment.
 #[no_mangle]
 pub extern "C" fn RunEditor_move_segments_up(this: &mut RunEditor) {
-    this.move_segments_up();
+    guard::call(guard::address(this), move || {
+        this.move_segments_up();
+    })
 }
 
 /// Moves all the selected segments down, unless the last segment is
@@ -276,7 +544,91 @@ pub extern "C" fn RunEditor_move_segments_up(this: &mut RunEditor) {
 /// active segment stays the active segment.
 #[no_mangle]
 pub extern "C" fn RunEditor_move_segments_down(this: &mut RunEditor) {
-    this.move_segments_down();
+    guard::call(guard::address(this), move || {
+        this.move_segments_down();
+    })
+}
+
+/// Checks if the currently selected segments can be moved by the given number
+/// of positions. Negative offsets move the segments up, positive offsets move
+/// them down. The segments can't be moved if any one of them would be moved
+/// beyond the first or the last segment.
+#[no_mangle]
+pub extern "C" fn RunEditor_can_move_segments_by(this: &RunEditor, offset: i32) -> bool {
+    guard::call(guard::address(this), move || {
+        this.can_move_segments_by(offset as isize)
+    })
+}
+
+/// Moves all the selected segments by the given number of positions, unless
+/// any one of them would be moved beyond the first or the last segment.
+/// Negative offsets move the segments up, positive offsets move them down. The
+/// run's information is automatically adjusted properly. The active segment
+/// stays the active segment.
+#[no_mangle]
+pub extern "C" fn RunEditor_move_segments_by(this: &mut RunEditor, offset: i32) {
+    guard::call(guard::address(this), move || {
+        this.move_segments_by(offset as isize);
+    })
+}
+
+/// Removes all the segments with the given indices, encoded as a JSON array of
+/// numbers, unless that would remove all the segments or any of the indices is
+/// out of bounds. The last index provided is treated as the active segment for
+/// choosing the new active segment. Returns <FALSE> if the JSON is not an array
+/// of indices.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_remove_segments_at_json(
+    this: &mut RunEditor,
+    indices: Json,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        if let Ok(indices) = serde_json::from_str::<Vec<usize>>(str(indices)) {
+            this.remove_segments_at(&indices);
+            true
+        } else {
+            false
+        }
+    })
+}
+
+/// The UTF-16 variant of RunEditor_remove_segments_at_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_remove_segments_at_json_u16(
+    this: &mut RunEditor,
+    indices: *const u16,
+    indices_len: usize,
+) -> bool {
+    RunEditor_remove_segments_at_json(this, Utf16Param::new(indices, indices_len).as_ptr())
+}
+
+/// Copies the selected segments into a Segment Clipboard, so they can be
+/// pasted into this or any other Run Editor.
+#[no_mangle]
+pub extern "C" fn RunEditor_copy_segments(this: &RunEditor) -> OwnedSegmentClipboard {
+    guard::call_or_abort(guard::address(this), move || Box::new(this.copy_segments()))
+}
+
+/// Copies the selected segments into a Segment Clipboard and removes them
+/// afterwards. If all the segments are selected, they can't be removed, so
+/// nothing happens and <NULL> is returned.
+#[no_mangle]
+pub extern "C" fn RunEditor_cut_segments(this: &mut RunEditor) -> NullableOwnedSegmentClipboard {
+    guard::call(guard::address(this), move || {
+        this.cut_segments().map(Box::new)
+    })
+}
+
+/// Pastes the segments stored in the Segment Clipboard below the active
+/// segment. The split times of all the following segments are shifted by the
+/// Personal Best segment times of the pasted segments. The pasted segments
+/// become the selected segments.
+#[no_mangle]
+pub extern "C" fn RunEditor_paste_segments(this: &mut RunEditor, clipboard: &SegmentClipboard) {
+    guard::call(guard::address(this), move || {
+        this.paste_segments(clipboard);
+    })
 }
 
 /// Sets the icon of the active segment.
@@ -286,20 +638,73 @@ pub unsafe extern "C" fn RunEditor_active_set_icon(
     data: *const u8,
     length: usize,
 ) {
-    this.active_segment()
-        .set_icon(Image::new(slice(data, length).into(), Image::ICON));
+    guard::call(guard::address(this), move || {
+        this.active_segment()
+            .set_icon(Image::new(slice(data, length).into(), Image::ICON));
+    })
+}
+
+/// Sets the icon of the active segment after transforming it. The image can be
+/// cropped to the largest square in its center and it gets scaled down to fit
+/// into a square of the maximum size provided. Stripping the metadata
+/// re-encodes the image without any of its metadata.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_active_set_icon_transformed(
+    this: &mut RunEditor,
+    data: *const u8,
+    length: usize,
+    crop_to_square: bool,
+    max_size: u32,
+    strip_metadata: bool,
+) {
+    guard::call(guard::address(this), move || {
+        let transform = ImageTransform {
+            crop_to_square,
+            max_size,
+            strip_metadata,
+        };
+        this.active_segment().set_icon(Image::with_transform(
+            slice(data, length).into(),
+            &transform,
+        ));
+    })
+}
+
+/// Crops the game's icon and the icons of all the segments to squares, scales
+/// them down to the default icon size and strips their metadata. This can be
+/// used to shrink icons that were set without being transformed.
+#[no_mangle]
+pub extern "C" fn RunEditor_transform_icons(this: &mut RunEditor) {
+    guard::call(guard::address(this), move || {
+        this.transform_icons(&ImageTransform::ICON);
+    })
 }
 
 /// Removes the icon of the active segment.
 #[no_mangle]
 pub extern "C" fn RunEditor_active_remove_icon(this: &mut RunEditor) {
-    this.active_segment().remove_icon();
+    guard::call(guard::address(this), move || {
+        this.active_segment().remove_icon();
+    })
 }
 
 /// Sets the name of the active segment.
 #[no_mangle]
 pub unsafe extern "C" fn RunEditor_active_set_name(this: &mut RunEditor, name: *const c_char) {
-    this.active_segment().set_name(str(name));
+    guard::call(guard::address(this), move || {
+        this.active_segment().set_name(str(name));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_active_set_name. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_active_set_name_u16(
+    this: &mut RunEditor,
+    name: *const u16,
+    name_len: usize,
+) {
+    RunEditor_active_set_name(this, Utf16Param::new(name, name_len).as_ptr())
 }
 
 /// Parses a split time from a string and sets it for the active segment with
@@ -309,9 +714,24 @@ pub unsafe extern "C" fn RunEditor_active_parse_and_set_split_time(
     this: &mut RunEditor,
     time: *const c_char,
 ) -> bool {
-    this.active_segment()
-        .parse_and_set_split_time(str(time))
-        .is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Parse,
+            this.active_segment().parse_and_set_split_time(str(time)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_active_parse_and_set_split_time. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_active_parse_and_set_split_time_u16(
+    this: &mut RunEditor,
+    time: *const u16,
+    time_len: usize,
+) -> bool {
+    RunEditor_active_parse_and_set_split_time(this, Utf16Param::new(time, time_len).as_ptr())
 }
 
 /// Parses a segment time from a string and sets it for the active segment with
@@ -321,9 +741,24 @@ pub unsafe extern "C" fn RunEditor_active_parse_and_set_segment_time(
     this: &mut RunEditor,
     time: *const c_char,
 ) -> bool {
-    this.active_segment()
-        .parse_and_set_segment_time(str(time))
-        .is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Parse,
+            this.active_segment().parse_and_set_segment_time(str(time)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_active_parse_and_set_segment_time. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_active_parse_and_set_segment_time_u16(
+    this: &mut RunEditor,
+    time: *const u16,
+    time_len: usize,
+) -> bool {
+    RunEditor_active_parse_and_set_segment_time(this, Utf16Param::new(time, time_len).as_ptr())
 }
 
 /// Parses a best segment time from a string and sets it for the active segment
@@ -333,9 +768,25 @@ pub unsafe extern "C" fn RunEditor_active_parse_and_set_best_segment_time(
     this: &mut RunEditor,
     time: *const c_char,
 ) -> bool {
-    this.active_segment()
-        .parse_and_set_best_segment_time(str(time))
-        .is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Parse,
+            this.active_segment()
+                .parse_and_set_best_segment_time(str(time)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_active_parse_and_set_best_segment_time. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_active_parse_and_set_best_segment_time_u16(
+    this: &mut RunEditor,
+    time: *const u16,
+    time_len: usize,
+) -> bool {
+    RunEditor_active_parse_and_set_best_segment_time(this, Utf16Param::new(time, time_len).as_ptr())
 }
 
 /// Parses a comparison time for the provided comparison and sets it for the
@@ -346,9 +797,31 @@ pub unsafe extern "C" fn RunEditor_active_parse_and_set_comparison_time(
     comparison: *const c_char,
     time: *const c_char,
 ) -> bool {
-    this.active_segment()
-        .parse_and_set_comparison_time(str(comparison), str(time))
-        .is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Parse,
+            this.active_segment()
+                .parse_and_set_comparison_time(str(comparison), str(time)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_active_parse_and_set_comparison_time. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_active_parse_and_set_comparison_time_u16(
+    this: &mut RunEditor,
+    comparison: *const u16,
+    comparison_len: usize,
+    time: *const u16,
+    time_len: usize,
+) -> bool {
+    RunEditor_active_parse_and_set_comparison_time(
+        this,
+        Utf16Param::new(comparison, comparison_len).as_ptr(),
+        Utf16Param::new(time, time_len).as_ptr(),
+    )
 }
 
 /// Adds a new custom comparison. It can't be added if it starts with
@@ -358,7 +831,24 @@ pub unsafe extern "C" fn RunEditor_add_comparison(
     this: &mut RunEditor,
     comparison: *const c_char,
 ) -> bool {
-    this.add_comparison(str(comparison)).is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::InvalidInput,
+            this.add_comparison(str(comparison)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_add_comparison. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_add_comparison_u16(
+    this: &mut RunEditor,
+    comparison: *const u16,
+    comparison_len: usize,
+) -> bool {
+    RunEditor_add_comparison(this, Utf16Param::new(comparison, comparison_len).as_ptr())
 }
 
 /// Imports the Personal Best from the provided run as a comparison. The
@@ -370,7 +860,29 @@ pub unsafe extern "C" fn RunEditor_import_comparison(
     run: &Run,
     comparison: *const c_char,
 ) -> bool {
-    this.import_comparison(run, str(comparison)).is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::InvalidInput,
+            this.import_comparison(run, str(comparison)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_import_comparison. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_import_comparison_u16(
+    this: &mut RunEditor,
+    run: &Run,
+    comparison: *const u16,
+    comparison_len: usize,
+) -> bool {
+    RunEditor_import_comparison(
+        this,
+        run,
+        Utf16Param::new(comparison, comparison_len).as_ptr(),
+    )
 }
 
 /// Removes the chosen custom comparison. You can't remove a Comparison
@@ -380,7 +892,20 @@ pub unsafe extern "C" fn RunEditor_remove_comparison(
     this: &mut RunEditor,
     comparison: *const c_char,
 ) {
-    this.remove_comparison(str(comparison));
+    guard::call(guard::address(this), move || {
+        this.remove_comparison(str(comparison));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_remove_comparison. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_remove_comparison_u16(
+    this: &mut RunEditor,
+    comparison: *const u16,
+    comparison_len: usize,
+) {
+    RunEditor_remove_comparison(this, Utf16Param::new(comparison, comparison_len).as_ptr())
 }
 
 /// Renames a comparison. The comparison can't be renamed if the new name of
@@ -391,7 +916,30 @@ pub unsafe extern "C" fn RunEditor_rename_comparison(
     old_name: *const c_char,
     new_name: *const c_char,
 ) -> bool {
-    this.rename_comparison(str(old_name), str(new_name)).is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::InvalidInput,
+            this.rename_comparison(str(old_name), str(new_name)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_rename_comparison. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_rename_comparison_u16(
+    this: &mut RunEditor,
+    old_name: *const u16,
+    old_name_len: usize,
+    new_name: *const u16,
+    new_name_len: usize,
+) -> bool {
+    RunEditor_rename_comparison(
+        this,
+        Utf16Param::new(old_name, old_name_len).as_ptr(),
+        Utf16Param::new(new_name, new_name_len).as_ptr(),
+    )
 }
 
 /// Reorders the custom comparisons by moving the comparison with the source
@@ -404,7 +952,31 @@ pub extern "C" fn RunEditor_move_comparison(
     src_index: usize,
     dst_index: usize,
 ) -> bool {
-    this.move_comparison(src_index, dst_index).is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::InvalidInput,
+            this.move_comparison(src_index, dst_index)
+                .map_err(|()| "The comparison index is out of bounds."),
+        )
+        .is_some()
+    })
+}
+
+/// Generates a custom goal comparison based on the goal time provided. The
+/// comparison's times are automatically balanced based on the runner's history
+/// such that it roughly represents what split times for the goal time would
+/// roughly look like. Since it is populated by the runner's history, only goal
+/// times within the sum of the best segments and the sum of the worst segments
+/// are supported. Everything else is automatically capped by that range. The
+/// comparison is only populated for the selected timing method. The other
+/// timing method's comparison times are not modified by this, so you can call
+/// this again with the other timing method to generate the comparison times
+/// for both timing methods.
+#[no_mangle]
+pub extern "C" fn RunEditor_generate_goal_comparison(this: &mut RunEditor, time: &TimeSpan) {
+    guard::call(guard::address(this), move || {
+        this.generate_goal_comparison(*time);
+    })
 }
 
 /// Parses a goal time and generates a custom goal comparison based on the
@@ -422,7 +994,80 @@ pub unsafe extern "C" fn RunEditor_parse_and_generate_goal_comparison(
     this: &mut RunEditor,
     time: *const c_char,
 ) -> bool {
-    this.parse_and_generate_goal_comparison(str(time)).is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Parse,
+            this.parse_and_generate_goal_comparison(str(time)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_parse_and_generate_goal_comparison. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_parse_and_generate_goal_comparison_u16(
+    this: &mut RunEditor,
+    time: *const u16,
+    time_len: usize,
+) -> bool {
+    RunEditor_parse_and_generate_goal_comparison(this, Utf16Param::new(time, time_len).as_ptr())
+}
+
+/// Returns whether the Comparison Generator with the name provided is active
+/// for the Run.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_is_comparison_generator_active(
+    this: &RunEditor,
+    name: *const c_char,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        this.is_comparison_generator_active(str(name))
+    })
+}
+
+/// The UTF-16 variant of RunEditor_is_comparison_generator_active. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_is_comparison_generator_active_u16(
+    this: &RunEditor,
+    name: *const u16,
+    name_len: usize,
+) -> bool {
+    RunEditor_is_comparison_generator_active(this, Utf16Param::new(name, name_len).as_ptr())
+}
+
+/// Activates or deactivates the Comparison Generator with the name provided.
+/// Only the Comparison Generators that are active by default are known. The
+/// comparison times of a Comparison Generator that gets activated are
+/// generated right away. Returns <FALSE> if there is no such Comparison
+/// Generator or if a custom comparison with the same name prevents it from
+/// being activated.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_comparison_generator_active(
+    this: &mut RunEditor,
+    name: *const c_char,
+    active: bool,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        this.set_comparison_generator_active(str(name), active)
+    })
+}
+
+/// The UTF-16 variant of RunEditor_set_comparison_generator_active. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_comparison_generator_active_u16(
+    this: &mut RunEditor,
+    name: *const u16,
+    name_len: usize,
+    active: bool,
+) -> bool {
+    RunEditor_set_comparison_generator_active(
+        this,
+        Utf16Param::new(name, name_len).as_ptr(),
+        active,
+    )
 }
 
 /// Copies a comparison with the given name as a new custom comparison with the
@@ -434,14 +1079,39 @@ pub unsafe extern "C" fn RunEditor_copy_comparison(
     old_name: *const c_char,
     new_name: *const c_char,
 ) -> bool {
-    this.copy_comparison(str(old_name), str(new_name)).is_ok()
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::InvalidInput,
+            this.copy_comparison(str(old_name), str(new_name)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_copy_comparison. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_copy_comparison_u16(
+    this: &mut RunEditor,
+    old_name: *const u16,
+    old_name_len: usize,
+    new_name: *const u16,
+    new_name_len: usize,
+) -> bool {
+    RunEditor_copy_comparison(
+        this,
+        Utf16Param::new(old_name, old_name_len).as_ptr(),
+        Utf16Param::new(new_name, new_name_len).as_ptr(),
+    )
 }
 
 /// Clears out the Attempt History and the Segment Histories of all the
 /// segments.
 #[no_mangle]
 pub extern "C" fn RunEditor_clear_history(this: &mut RunEditor) {
-    this.clear_history();
+    guard::call(guard::address(this), move || {
+        this.clear_history();
+    })
 }
 
 /// Clears out the Attempt History, the Segment Histories, all the times,
@@ -450,7 +1120,9 @@ pub extern "C" fn RunEditor_clear_history(this: &mut RunEditor) {
 /// deleted as well.
 #[no_mangle]
 pub extern "C" fn RunEditor_clear_times(this: &mut RunEditor) {
-    this.clear_times();
+    guard::call(guard::address(this), move || {
+        this.clear_times();
+    })
 }
 
 /// Creates a Sum of Best Cleaner which allows you to interactively remove
@@ -463,5 +1135,230 @@ pub extern "C" fn RunEditor_clear_times(this: &mut RunEditor) {
 pub extern "C" fn RunEditor_clean_sum_of_best(
     this: &'static mut RunEditor,
 ) -> OwnedSumOfBestCleaner {
-    Box::new(this.clean_sum_of_best())
+    guard::call_or_abort(guard::address(this), move || {
+        // The block moves the reference out of the closure, which allows the
+        // result to borrow from it for as long as the original reference.
+        Box::new({ this }.clean_sum_of_best())
+    })
+}
+
+/// Encodes the segment times of the attempt with the given index from the
+/// Attempt History as JSON. The JSON is an array with an element for each
+/// segment. If the attempt didn't reach a segment, its element is `null`.
+/// Otherwise it is a pair of the real time and the game time in seconds, each
+/// of which may be `null` if the segment got skipped. If there is no such
+/// attempt, an empty array is returned.
+#[no_mangle]
+pub extern "C" fn RunEditor_attempt_segment_times_as_json(
+    this: &RunEditor,
+    attempt_index: i32,
+) -> Json {
+    guard::call(guard::address(this), move || {
+        let times = this
+            .attempt_segment_times(attempt_index)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|time| {
+                time.map(|time| {
+                    [time.real_time, time.game_time].map(|t| t.map(|t| t.total_seconds()))
+                })
+            })
+            .collect::<Vec<_>>();
+        output_vec(|o| {
+            serde_json::to_writer(o, &times).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of RunEditor_attempt_segment_times_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_attempt_segment_times_as_json_u16(
+    this: &RunEditor,
+    attempt_index: i32,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        RunEditor_attempt_segment_times_as_json(this, attempt_index),
+        buf,
+        buf_len,
+    )
+}
+
+/// Removes the attempt with the given index from the Attempt History. Its
+/// times are removed from the Segment Histories as well. Returns <FALSE> if
+/// there is no such attempt.
+#[no_mangle]
+pub extern "C" fn RunEditor_remove_attempt(this: &mut RunEditor, attempt_index: i32) -> bool {
+    guard::call(guard::address(this), move || {
+        last_error::track(ErrorCode::InvalidInput, this.remove_attempt(attempt_index)).is_some()
+    })
+}
+
+/// Marks the attempt with the given index as invalid. The attempt stays in the
+/// Attempt History, but its times are removed from the Segment Histories and
+/// its final time is cleared. Returns <FALSE> if there is no such attempt.
+#[no_mangle]
+pub extern "C" fn RunEditor_invalidate_attempt(this: &mut RunEditor, attempt_index: i32) -> bool {
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::InvalidInput,
+            this.invalidate_attempt(attempt_index),
+        )
+        .is_some()
+    })
+}
+
+/// Parses a segment time from a string and sets it as the segment time of the
+/// segment with the given index in the attempt with the given index for the
+/// currently selected timing method. An empty string marks the segment as
+/// skipped. Returns <FALSE> if the time couldn't be parsed, there is no such
+/// attempt or the attempt didn't reach the segment. You may not provide an out
+/// of bounds segment index.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_parse_and_set_attempt_segment_time(
+    this: &mut RunEditor,
+    attempt_index: i32,
+    segment_index: usize,
+    time: *const c_char,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::InvalidInput,
+            this.parse_and_set_attempt_segment_time(attempt_index, segment_index, str(time)),
+        )
+        .is_some()
+    })
+}
+
+/// The UTF-16 variant of RunEditor_parse_and_set_attempt_segment_time. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_parse_and_set_attempt_segment_time_u16(
+    this: &mut RunEditor,
+    attempt_index: i32,
+    segment_index: usize,
+    time: *const u16,
+    time_len: usize,
+) -> bool {
+    RunEditor_parse_and_set_attempt_segment_time(
+        this,
+        attempt_index,
+        segment_index,
+        Utf16Param::new(time, time_len).as_ptr(),
+    )
+}
+
+/// Starts importing segments from a table. The table can be provided as comma
+/// separated values (CSV), tab separated values (TSV) or text pasted from a
+/// spreadsheet application. What each of the columns is imported as is guessed
+/// based on the table and can be changed afterwards. The state of the Run
+/// Editor contains a preview of the import until it gets finished or
+/// cancelled.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_start_segment_import(this: &mut RunEditor, text: *const c_char) {
+    guard::call(guard::address(this), move || {
+        this.start_segment_import(str(text));
+    })
+}
+
+/// The UTF-16 variant of RunEditor_start_segment_import. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_start_segment_import_u16(
+    this: &mut RunEditor,
+    text: *const u16,
+    text_len: usize,
+) {
+    RunEditor_start_segment_import(this, Utf16Param::new(text, text_len).as_ptr())
+}
+
+/// Sets what the column with the given index of the Segment Import in progress
+/// is imported as. The column may be `Ignored`, `Name`, `SplitTime`,
+/// `SegmentTime` or `BestSegmentTime`. Only a single column can be imported as
+/// each kind, so any other column of the same kind is ignored from now on.
+/// Returns <FALSE> if there is no Segment Import in progress, the index is
+/// invalid or the column is unknown.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_segment_import_column(
+    this: &mut RunEditor,
+    index: usize,
+    column: *const c_char,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        let column = match str(column) {
+            "Ignored" => ImportColumn::Ignored,
+            "Name" => ImportColumn::Name,
+            "SplitTime" => ImportColumn::SplitTime,
+            "SegmentTime" => ImportColumn::SegmentTime,
+            "BestSegmentTime" => ImportColumn::BestSegmentTime,
+            _ => return false,
+        };
+        match this.segment_import_mut() {
+            Some(import) if index < import.columns().len() => {
+                import.set_column(index, column);
+                true
+            }
+            _ => false,
+        }
+    })
+}
+
+/// The UTF-16 variant of RunEditor_set_segment_import_column. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunEditor_set_segment_import_column_u16(
+    this: &mut RunEditor,
+    index: usize,
+    column: *const u16,
+    column_len: usize,
+) -> bool {
+    RunEditor_set_segment_import_column(this, index, Utf16Param::new(column, column_len).as_ptr())
+}
+
+/// Sets whether the first row of the table of the Segment Import in progress
+/// is a header that is not imported as a segment. Returns <FALSE> if there is
+/// no Segment Import in progress.
+#[no_mangle]
+pub extern "C" fn RunEditor_set_segment_import_has_header(
+    this: &mut RunEditor,
+    has_header: bool,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        if let Some(import) = this.segment_import_mut() {
+            import.set_has_header(has_header);
+            true
+        } else {
+            false
+        }
+    })
+}
+
+/// Cancels the Segment Import in progress without changing the Run.
+#[no_mangle]
+pub extern "C" fn RunEditor_cancel_segment_import(this: &mut RunEditor) {
+    guard::call(guard::address(this), move || {
+        this.cancel_segment_import();
+    })
+}
+
+/// Finishes the Segment Import in progress by adding a segment for each row of
+/// the table. The segments are either added below the last segment or replace
+/// all the existing segments. Returns <FALSE> if there is no Segment Import in
+/// progress, the table doesn't contain any segments or any of the times
+/// couldn't be parsed. In that case the Run stays unchanged and the Last Error
+/// describes what went wrong.
+#[no_mangle]
+pub extern "C" fn RunEditor_finish_segment_import(
+    this: &mut RunEditor,
+    replace_segments: bool,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        last_error::track(
+            ErrorCode::Parse,
+            this.finish_segment_import(replace_segments),
+        )
+        .is_some()
+    })
 }
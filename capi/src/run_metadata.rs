@@ -1,7 +1,7 @@
 //! The Run Metadata stores additional information about a run, like the
 //! platform and region of the game. All of this information is optional.
 
-use super::output_str;
+use super::{guard, output_str, output_utf16_from};
 use crate::run_metadata_custom_variables_iter::OwnedRunMetadataCustomVariablesIter;
 use crate::run_metadata_speedrun_com_variables_iter::OwnedRunMetadataSpeedrunComVariablesIter;
 use livesplit_core::RunMetadata;
@@ -16,28 +16,63 @@ pub type OwnedRunMetadata = Box<RunMetadata>;
 /// anymore. This may be empty if there's no association.
 #[no_mangle]
 pub extern "C" fn RunMetadata_run_id(this: &RunMetadata) -> *const c_char {
-    output_str(this.run_id())
+    guard::call(guard::address(this), move || output_str(this.run_id()))
+}
+
+/// The UTF-16 variant of RunMetadata_run_id. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunMetadata_run_id_u16(
+    this: &RunMetadata,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(RunMetadata_run_id(this), buf, buf_len)
 }
 
 /// Accesses the name of the platform this game is run on. This may be empty
 /// if it's not specified.
 #[no_mangle]
 pub extern "C" fn RunMetadata_platform_name(this: &RunMetadata) -> *const c_char {
-    output_str(this.platform_name())
+    guard::call(guard::address(this), move || {
+        output_str(this.platform_name())
+    })
+}
+
+/// The UTF-16 variant of RunMetadata_platform_name. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunMetadata_platform_name_u16(
+    this: &RunMetadata,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(RunMetadata_platform_name(this), buf, buf_len)
 }
 
 /// Returns <TRUE> if this speedrun is done on an emulator. However <FALSE>
 /// may also indicate that this information is simply not known.
 #[no_mangle]
 pub extern "C" fn RunMetadata_uses_emulator(this: &RunMetadata) -> bool {
-    this.uses_emulator()
+    guard::call(guard::address(this), move || this.uses_emulator())
 }
 
 /// Accesses the name of the region this game is from. This may be empty if
 /// it's not specified.
 #[no_mangle]
 pub extern "C" fn RunMetadata_region_name(this: &RunMetadata) -> *const c_char {
-    output_str(this.region_name())
+    guard::call(guard::address(this), move || output_str(this.region_name()))
+}
+
+/// The UTF-16 variant of RunMetadata_region_name. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunMetadata_region_name_u16(
+    this: &RunMetadata,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(RunMetadata_region_name(this), buf, buf_len)
 }
 
 /// Returns an iterator iterating over all the speedrun.com variables and their
@@ -46,7 +81,9 @@ pub extern "C" fn RunMetadata_region_name(this: &RunMetadata) -> *const c_char {
 pub extern "C" fn RunMetadata_speedrun_com_variables(
     this: &'static RunMetadata,
 ) -> OwnedRunMetadataSpeedrunComVariablesIter {
-    Box::new(this.speedrun_com_variables())
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.speedrun_com_variables())
+    })
 }
 
 /// Returns an iterator iterating over all the custom variables and their
@@ -55,5 +92,7 @@ pub extern "C" fn RunMetadata_speedrun_com_variables(
 pub extern "C" fn RunMetadata_custom_variables(
     this: &'static RunMetadata,
 ) -> OwnedRunMetadataCustomVariablesIter {
-    Box::new(this.custom_variables())
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.custom_variables())
+    })
 }
@@ -5,7 +5,7 @@
 //! may provide temporary custom variables that are not stored in the splits
 //! files.
 
-use super::output_str;
+use super::{guard, output_str, output_utf16_from};
 use livesplit_core::run::CustomVariable;
 use std::os::raw::c_char;
 
@@ -19,7 +19,7 @@ pub type OwnedRunMetadataCustomVariable = Box<RunMetadataCustomVariable>;
 /// drop
 #[no_mangle]
 pub extern "C" fn RunMetadataCustomVariable_drop(this: OwnedRunMetadataCustomVariable) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Accesses the name of this custom variable.
@@ -27,7 +27,18 @@ pub extern "C" fn RunMetadataCustomVariable_drop(this: OwnedRunMetadataCustomVar
 pub unsafe extern "C" fn RunMetadataCustomVariable_name(
     this: &RunMetadataCustomVariable,
 ) -> *const c_char {
-    output_str(&*this.0)
+    guard::call(guard::address(this), move || output_str(&*this.0))
+}
+
+/// The UTF-16 variant of RunMetadataCustomVariable_name. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunMetadataCustomVariable_name_u16(
+    this: &RunMetadataCustomVariable,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(RunMetadataCustomVariable_name(this), buf, buf_len)
 }
 
 /// Accesses the value of this custom variable.
@@ -35,7 +46,18 @@ pub unsafe extern "C" fn RunMetadataCustomVariable_name(
 pub unsafe extern "C" fn RunMetadataCustomVariable_value(
     this: &RunMetadataCustomVariable,
 ) -> *const c_char {
-    output_str(&(*this.1).value)
+    guard::call(guard::address(this), move || output_str(&(*this.1).value))
+}
+
+/// The UTF-16 variant of RunMetadataCustomVariable_value. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunMetadataCustomVariable_value_u16(
+    this: &RunMetadataCustomVariable,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(RunMetadataCustomVariable_value(this), buf, buf_len)
 }
 
 /// Returns <TRUE> if the custom variable is permanent. Permanent variables get
@@ -45,5 +67,5 @@ pub unsafe extern "C" fn RunMetadataCustomVariable_value(
 pub unsafe extern "C" fn RunMetadataCustomVariable_is_permanent(
     this: &RunMetadataCustomVariable,
 ) -> bool {
-    (*this.1).is_permanent
+    guard::call(guard::address(this), move || (*this.1).is_permanent)
 }
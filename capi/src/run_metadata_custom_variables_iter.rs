@@ -1,7 +1,7 @@
 //! An iterator iterating over all the custom variables and their values
 //! that have been specified.
 
-use super::RUN_METADATA_CUSTOM_VARIABLE;
+use super::{guard, RUN_METADATA_CUSTOM_VARIABLE};
 use crate::run_metadata_custom_variable::{
     NullableRunMetadataCustomVariable, RunMetadataCustomVariable,
 };
@@ -16,7 +16,7 @@ pub type OwnedRunMetadataCustomVariablesIter = Box<RunMetadataCustomVariablesIte
 /// drop
 #[no_mangle]
 pub extern "C" fn RunMetadataCustomVariablesIter_drop(this: OwnedRunMetadataCustomVariablesIter) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Accesses the next custom variable. Returns <NULL> if there are no more
@@ -25,12 +25,14 @@ pub extern "C" fn RunMetadataCustomVariablesIter_drop(this: OwnedRunMetadataCust
 pub extern "C" fn RunMetadataCustomVariablesIter_next(
     this: &mut RunMetadataCustomVariablesIter,
 ) -> *const NullableRunMetadataCustomVariable {
-    if let Some((name, value)) = this.next() {
-        RUN_METADATA_CUSTOM_VARIABLE.with(|output| {
-            output.set((name, value));
-            output.as_ptr() as *const RunMetadataCustomVariable
-        })
-    } else {
-        ptr::null()
-    }
+    guard::call(guard::address(this), move || {
+        if let Some((name, value)) = this.next() {
+            RUN_METADATA_CUSTOM_VARIABLE.with(|output| {
+                output.set((name, value));
+                output.as_ptr() as *const RunMetadataCustomVariable
+            })
+        } else {
+            ptr::null()
+        }
+    })
 }
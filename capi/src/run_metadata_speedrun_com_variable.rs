@@ -2,7 +2,7 @@
 //! information about the category. An example of this may be whether Amiibos
 //! are used in the category.
 
-use super::output_str;
+use super::{guard, output_str, output_utf16_from};
 use std::os::raw::c_char;
 
 /// type
@@ -15,7 +15,7 @@ pub type OwnedRunMetadataSpeedrunComVariable = Box<RunMetadataSpeedrunComVariabl
 /// drop
 #[no_mangle]
 pub extern "C" fn RunMetadataSpeedrunComVariable_drop(this: OwnedRunMetadataSpeedrunComVariable) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Accesses the name of this speedrun.com variable.
@@ -23,7 +23,18 @@ pub extern "C" fn RunMetadataSpeedrunComVariable_drop(this: OwnedRunMetadataSpee
 pub unsafe extern "C" fn RunMetadataSpeedrunComVariable_name(
     this: &RunMetadataSpeedrunComVariable,
 ) -> *const c_char {
-    output_str(&*this.0)
+    guard::call(guard::address(this), move || output_str(&*this.0))
+}
+
+/// The UTF-16 variant of RunMetadataSpeedrunComVariable_name. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunMetadataSpeedrunComVariable_name_u16(
+    this: &RunMetadataSpeedrunComVariable,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(RunMetadataSpeedrunComVariable_name(this), buf, buf_len)
 }
 
 /// Accesses the value of this speedrun.com variable.
@@ -31,5 +42,16 @@ pub unsafe extern "C" fn RunMetadataSpeedrunComVariable_name(
 pub unsafe extern "C" fn RunMetadataSpeedrunComVariable_value(
     this: &RunMetadataSpeedrunComVariable,
 ) -> *const c_char {
-    output_str(&*this.1)
+    guard::call(guard::address(this), move || output_str(&*this.1))
+}
+
+/// The UTF-16 variant of RunMetadataSpeedrunComVariable_value. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunMetadataSpeedrunComVariable_value_u16(
+    this: &RunMetadataSpeedrunComVariable,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(RunMetadataSpeedrunComVariable_value(this), buf, buf_len)
 }
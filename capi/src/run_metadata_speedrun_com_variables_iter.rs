@@ -1,7 +1,7 @@
 //! An iterator iterating over all the speedrun.com variables and their values
 //! that have been specified.
 
-use super::RUN_METADATA_SPEEDRUN_COM_VARIABLE;
+use super::{guard, RUN_METADATA_SPEEDRUN_COM_VARIABLE};
 use crate::run_metadata_speedrun_com_variable::{
     NullableRunMetadataSpeedrunComVariable, RunMetadataSpeedrunComVariable,
 };
@@ -18,7 +18,7 @@ pub type OwnedRunMetadataSpeedrunComVariablesIter = Box<RunMetadataSpeedrunComVa
 pub extern "C" fn RunMetadataSpeedrunComVariablesIter_drop(
     this: OwnedRunMetadataSpeedrunComVariablesIter,
 ) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Accesses the next speedrun.com variable. Returns <NULL> if there are no more
@@ -27,12 +27,14 @@ pub extern "C" fn RunMetadataSpeedrunComVariablesIter_drop(
 pub extern "C" fn RunMetadataSpeedrunComVariablesIter_next(
     this: &mut RunMetadataSpeedrunComVariablesIter,
 ) -> *const NullableRunMetadataSpeedrunComVariable {
-    if let Some((name, value)) = this.next() {
-        RUN_METADATA_SPEEDRUN_COM_VARIABLE.with(|output| {
-            output.set((name, value));
-            output.as_ptr() as *const RunMetadataSpeedrunComVariable
-        })
-    } else {
-        ptr::null()
-    }
+    guard::call(guard::address(this), move || {
+        if let Some((name, value)) = this.next() {
+            RUN_METADATA_SPEEDRUN_COM_VARIABLE.with(|output| {
+                output.set((name, value));
+                output.as_ptr() as *const RunMetadataSpeedrunComVariable
+            })
+        } else {
+            ptr::null()
+        }
+    })
 }
@@ -0,0 +1,130 @@
+//! A Run Parser allows parsing a splits file that is provided in chunks, such
+//! as when it is being downloaded or read from a stream. The chunks get fed to
+//! the parser as they arrive and the splits file gets parsed once all of them
+//! are provided. The parsers need the whole splits file, so the chunks are
+//! buffered until then. This means the whole splits file is held in memory,
+//! just like when parsing it from an array.
+
+use super::{guard, slice, str, Utf16Param};
+use crate::{
+    last_error::{self, ErrorCode},
+    parse_run_result::OwnedParseRunResult,
+};
+use livesplit_core::run::parser;
+use std::{os::raw::c_char, path::PathBuf};
+
+/// type
+pub struct RunParser {
+    data: Vec<u8>,
+    load_files_path: Option<PathBuf>,
+}
+
+/// type
+pub type OwnedRunParser = Box<RunParser>;
+
+/// Creates a new Run Parser that has no data yet. Additionally you can provide
+/// the path of the splits file so additional files, like external images, can
+/// be loaded. If you are using livesplit-core in a server-like environment,
+/// set this to <NULL>. Only client-side applications should provide a path
+/// here.
+#[no_mangle]
+pub unsafe extern "C" fn RunParser_new(load_files_path: *const c_char) -> OwnedRunParser {
+    guard::abort_on_unwind(move || {
+        let load_files_path = str(load_files_path);
+        Box::new(RunParser {
+            data: Vec::new(),
+            load_files_path: if !load_files_path.is_empty() {
+                Some(PathBuf::from(load_files_path))
+            } else {
+                None
+            },
+        })
+    })
+}
+
+/// The UTF-16 variant of RunParser_new. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn RunParser_new_u16(
+    load_files_path: *const u16,
+    load_files_path_len: usize,
+) -> OwnedRunParser {
+    RunParser_new(Utf16Param::new(load_files_path, load_files_path_len).as_ptr())
+}
+
+/// drop
+#[no_mangle]
+pub extern "C" fn RunParser_drop(this: OwnedRunParser) {
+    guard::dispose(this);
+}
+
+/// Feeds the next chunk of the splits file to the Run Parser. The chunk is
+/// copied into the buffer of the Run Parser, so the array can be reused once
+/// this function returns.
+#[no_mangle]
+pub unsafe extern "C" fn RunParser_feed(this: &mut RunParser, data: *const u8, length: usize) {
+    guard::call(guard::address(this), move || {
+        this.data.extend_from_slice(slice(data, length));
+    })
+}
+
+/// Finishes feeding chunks to the Run Parser and attempts to parse the splits
+/// file by invoking the corresponding parser for the file format detected. The
+/// Run Parser is consumed by this. Just like Run_parse, it also fixes problems
+/// in the Run, such as decreasing times and missing information. If parsing
+/// fails, the Last Error describes what went wrong.
+#[no_mangle]
+pub extern "C" fn RunParser_finish(this: OwnedRunParser) -> OwnedParseRunResult {
+    Box::new(guard::call_consuming(guard::address(&*this), move || {
+        let RunParser {
+            data,
+            load_files_path,
+        } = *this;
+
+        last_error::track(
+            ErrorCode::Parse,
+            parser::composite::parse(&data, load_files_path.as_deref()),
+        )
+        .map(|p| p.into_owned())
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        last_error::LastError_code,
+        parse_run_result::{ParseRunResult_parsed_successfully, ParseRunResult_unwrap},
+        run::{Run_drop, Run_game_name, Run_len},
+    };
+    use std::{ffi::CStr, ptr};
+
+    static LSS: &[u8] = include_bytes!("../../tests/run_files/Celeste - Any% (1.2.1.5).lss");
+
+    #[test]
+    fn parses_chunks() {
+        let mut parser = unsafe { RunParser_new(ptr::null()) };
+        for chunk in LSS.chunks(997) {
+            unsafe { RunParser_feed(&mut parser, chunk.as_ptr(), chunk.len()) };
+        }
+        let result = RunParser_finish(parser);
+        assert!(ParseRunResult_parsed_successfully(&result));
+        assert_eq!(LastError_code(), 0);
+
+        let run = ParseRunResult_unwrap(result);
+        let game_name = unsafe { CStr::from_ptr(Run_game_name(&run)) };
+        assert_eq!(game_name.to_str().unwrap(), "Celeste");
+        assert_eq!(Run_len(&run), 33);
+        Run_drop(run);
+    }
+
+    #[test]
+    fn reports_incomplete_files() {
+        let mut parser = unsafe { RunParser_new(ptr::null()) };
+        let chunk = &LSS[..LSS.len() / 2];
+        unsafe { RunParser_feed(&mut parser, chunk.as_ptr(), chunk.len()) };
+        let result = RunParser_finish(parser);
+        assert!(!ParseRunResult_parsed_successfully(&result));
+        assert_eq!(LastError_code(), ErrorCode::Parse as u32);
+    }
+}
@@ -2,42 +2,93 @@
 //! split time. This stores the name of that segment, an icon, the split times
 //! of different comparisons, and a history of segment times.
 
-use super::{output_str, output_time, str};
+use super::{
+    decoded_image::NullableOwnedDecodedImage, guard, output_str, output_time, output_utf16_from,
+    str, Utf16Param,
+};
 use livesplit_core::{Segment, SegmentHistory, Time};
 use std::os::raw::c_char;
 
 /// type
 pub type OwnedSegment = Box<Segment>;
+/// type
+pub type NullableSegment = Segment;
 
 /// Creates a new Segment with the name given.
 #[no_mangle]
 pub unsafe extern "C" fn Segment_new(name: *const c_char) -> OwnedSegment {
-    Box::new(Segment::new(str(name)))
+    guard::abort_on_unwind(move || Box::new(Segment::new(str(name))))
+}
+
+/// The UTF-16 variant of Segment_new. See get_buf_as_utf16 for how the strings
+/// are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Segment_new_u16(name: *const u16, name_len: usize) -> OwnedSegment {
+    Segment_new(Utf16Param::new(name, name_len).as_ptr())
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn Segment_drop(this: OwnedSegment) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Accesses the name of the segment.
 #[no_mangle]
 pub extern "C" fn Segment_name(this: &Segment) -> *const c_char {
-    output_str(this.name())
+    guard::call(guard::address(this), move || output_str(this.name()))
+}
+
+/// The UTF-16 variant of Segment_name. See get_buf_as_utf16 for how the strings
+/// are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Segment_name_u16(this: &Segment, buf: *mut u16, buf_len: usize) -> usize {
+    output_utf16_from(Segment_name(this), buf, buf_len)
 }
 
 /// Accesses the segment icon's data. If there is no segment icon, this returns
 /// an empty buffer.
 #[no_mangle]
 pub extern "C" fn Segment_icon_ptr(this: &Segment) -> *const u8 {
-    this.icon().data().as_ptr()
+    guard::call(guard::address(this), move || this.icon().data().as_ptr())
 }
 
 /// Accesses the amount of bytes the segment icon's data takes up.
 #[no_mangle]
 pub extern "C" fn Segment_icon_len(this: &Segment) -> usize {
-    this.icon().data().len()
+    guard::call(guard::address(this), move || this.icon().data().len())
+}
+
+/// Accesses the media type of the segment icon's data, such as image/png. The
+/// format is detected from the data itself. If there is no segment icon or its
+/// format is not known, this returns an empty string.
+#[no_mangle]
+pub extern "C" fn Segment_icon_format(this: &Segment) -> *const c_char {
+    guard::call(guard::address(this), move || {
+        output_str(this.icon().format().map_or("", |f| f.media_type()))
+    })
+}
+
+/// The UTF-16 variant of Segment_icon_format. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Segment_icon_format_u16(
+    this: &Segment,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Segment_icon_format(this), buf, buf_len)
+}
+
+/// Decodes the segment icon into its pixels and scales it down to fit into a
+/// square of the maximum size provided, keeping its aspect ratio. The decoded
+/// icon is cached, so this can be called on every frame. <NULL> is returned if
+/// there is no segment icon or it couldn't be decoded.
+#[no_mangle]
+pub extern "C" fn Segment_decode_icon(this: &Segment, max_size: u32) -> NullableOwnedDecodedImage {
+    guard::call(guard::address(this), move || {
+        this.icon().decode(max_size).map(Box::new)
+    })
 }
 
 /// Accesses the specified comparison's time. If there's none for this
@@ -48,24 +99,41 @@ pub unsafe extern "C" fn Segment_comparison(
     this: &Segment,
     comparison: *const c_char,
 ) -> *const Time {
-    output_time(this.comparison(str(comparison)))
+    guard::call(guard::address(this), move || {
+        output_time(this.comparison(str(comparison)))
+    })
+}
+
+/// The UTF-16 variant of Segment_comparison. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Segment_comparison_u16(
+    this: &Segment,
+    comparison: *const u16,
+    comparison_len: usize,
+) -> *const Time {
+    Segment_comparison(this, Utf16Param::new(comparison, comparison_len).as_ptr())
 }
 
 /// Accesses the split time of the Personal Best for this segment. If it
 /// doesn't exist, an empty time is returned.
 #[no_mangle]
 pub extern "C" fn Segment_personal_best_split_time(this: &Segment) -> *const Time {
-    output_time(this.personal_best_split_time())
+    guard::call(guard::address(this), move || {
+        output_time(this.personal_best_split_time())
+    })
 }
 
 /// Accesses the Best Segment Time.
 #[no_mangle]
 pub extern "C" fn Segment_best_segment_time(this: &Segment) -> *const Time {
-    output_time(this.best_segment_time())
+    guard::call(guard::address(this), move || {
+        output_time(this.best_segment_time())
+    })
 }
 
 /// Accesses the Segment History of this segment.
 #[no_mangle]
 pub extern "C" fn Segment_segment_history(this: &Segment) -> &SegmentHistory {
-    this.segment_history()
+    guard::call_or_abort(guard::address(this), move || this.segment_history())
 }
@@ -0,0 +1,91 @@
+//! The Segment Clipboard stores segments that got copied out of a Run Editor,
+//! so they can be pasted into the same or any other Run Editor. Alongside the
+//! names and icons of the segments, their Personal Best segment times, their
+//! best segment times and their Segment Histories are copied.
+
+use super::{guard, output_str, output_utf16_from, output_vec, str, Json, Utf16Param};
+use crate::last_error::{self, ErrorCode};
+use livesplit_core::run::editor::SegmentClipboard;
+use std::{io::Write, os::raw::c_char};
+
+/// type
+pub type OwnedSegmentClipboard = Box<SegmentClipboard>;
+/// type
+pub type NullableOwnedSegmentClipboard = Option<OwnedSegmentClipboard>;
+
+/// drop
+#[no_mangle]
+pub extern "C" fn SegmentClipboard_drop(this: OwnedSegmentClipboard) {
+    guard::dispose(this);
+}
+
+/// Returns the number of segments stored in the clipboard.
+#[no_mangle]
+pub extern "C" fn SegmentClipboard_len(this: &SegmentClipboard) -> usize {
+    guard::call(guard::address(this), move || this.len())
+}
+
+/// Accesses the name of the segment with the given index. You may not provide
+/// an out of bounds index.
+#[no_mangle]
+pub extern "C" fn SegmentClipboard_segment_name(
+    this: &SegmentClipboard,
+    index: usize,
+) -> *const c_char {
+    guard::call(guard::address(this), move || {
+        output_str(this.segment_names().nth(index).unwrap())
+    })
+}
+
+/// The UTF-16 variant of SegmentClipboard_segment_name. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SegmentClipboard_segment_name_u16(
+    this: &SegmentClipboard,
+    index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(SegmentClipboard_segment_name(this, index), buf, buf_len)
+}
+
+/// Encodes the clipboard as JSON, so it can be stored or transferred between
+/// different instances of an application.
+#[no_mangle]
+pub extern "C" fn SegmentClipboard_as_json(this: &SegmentClipboard) -> Json {
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            o.write_all(this.to_json().as_bytes()).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of SegmentClipboard_as_json. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SegmentClipboard_as_json_u16(
+    this: &SegmentClipboard,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(SegmentClipboard_as_json(this), buf, buf_len)
+}
+
+/// Decodes a clipboard from its JSON representation. <NULL> is returned if it
+/// couldn't be parsed.
+#[no_mangle]
+pub unsafe extern "C" fn SegmentClipboard_parse_json(json: Json) -> NullableOwnedSegmentClipboard {
+    guard::call(0, move || {
+        last_error::track(ErrorCode::Parse, SegmentClipboard::from_json(str(json))).map(Box::new)
+    })
+}
+
+/// The UTF-16 variant of SegmentClipboard_parse_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SegmentClipboard_parse_json_u16(
+    json: *const u16,
+    json_len: usize,
+) -> NullableOwnedSegmentClipboard {
+    SegmentClipboard_parse_json(Utf16Param::new(json, json_len).as_ptr())
+}
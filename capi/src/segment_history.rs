@@ -3,7 +3,7 @@
 //! considered times actually achieved by the runner, while the others are
 //! artifacts of route changes and similar algorithmic changes.
 
-use crate::segment_history_iter::OwnedSegmentHistoryIter;
+use crate::{guard, segment_history_iter::OwnedSegmentHistoryIter};
 use livesplit_core::SegmentHistory;
 
 /// type
@@ -12,5 +12,5 @@ pub type OwnedSegmentHistory = Box<SegmentHistory>;
 /// Iterates over all the segment times and their indices.
 #[no_mangle]
 pub extern "C" fn SegmentHistory_iter(this: &'static SegmentHistory) -> OwnedSegmentHistoryIter {
-    Box::new(this.iter())
+    guard::call_or_abort(guard::address(this), move || Box::new(this.iter()))
 }
@@ -3,7 +3,7 @@
 //! achieved by the runner, while the others are artifacts of route changes and
 //! similar algorithmic changes.
 
-use super::output_time;
+use super::{guard, output_time};
 use livesplit_core::Time;
 
 /// type
@@ -16,11 +16,11 @@ pub type OwnedSegmentHistoryElement = Box<SegmentHistoryElement>;
 /// Accesses the index of the segment history element.
 #[no_mangle]
 pub extern "C" fn SegmentHistoryElement_index(this: &SegmentHistoryElement) -> i32 {
-    this.0
+    guard::call(guard::address(this), move || this.0)
 }
 
 /// Accesses the segment time of the segment history element.
 #[no_mangle]
 pub extern "C" fn SegmentHistoryElement_time(this: &SegmentHistoryElement) -> *const Time {
-    output_time(this.1)
+    guard::call(guard::address(this), move || output_time(this.1))
 }
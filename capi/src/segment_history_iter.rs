@@ -1,6 +1,6 @@
 //! Iterates over all the segment times of a segment and their indices.
 
-use super::SEGMENT_HISTORY_ELEMENT;
+use super::{guard, SEGMENT_HISTORY_ELEMENT};
 use crate::segment_history_element::{NullableSegmentHistoryElement, SegmentHistoryElement};
 use livesplit_core::Time;
 use std::{ptr, slice};
@@ -13,7 +13,7 @@ pub type OwnedSegmentHistoryIter = Box<SegmentHistoryIter>;
 /// drop
 #[no_mangle]
 pub extern "C" fn SegmentHistoryIter_drop(this: OwnedSegmentHistoryIter) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Accesses the next Segment History element. Returns <NULL> if there are no
@@ -22,12 +22,14 @@ pub extern "C" fn SegmentHistoryIter_drop(this: OwnedSegmentHistoryIter) {
 pub extern "C" fn SegmentHistoryIter_next(
     this: &mut SegmentHistoryIter,
 ) -> *const NullableSegmentHistoryElement {
-    if let Some(&element) = this.next() {
-        SEGMENT_HISTORY_ELEMENT.with(|output| {
-            output.set(element);
-            output.as_ptr() as *const SegmentHistoryElement
-        })
-    } else {
-        ptr::null()
-    }
+    guard::call(guard::address(this), move || {
+        if let Some(&element) = this.next() {
+            SEGMENT_HISTORY_ELEMENT.with(|output| {
+                output.set(element);
+                output.as_ptr() as *const SegmentHistoryElement
+            })
+        } else {
+            ptr::null()
+        }
+    })
 }
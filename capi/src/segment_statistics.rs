@@ -0,0 +1,53 @@
+//! Segment Statistics describe the segment times a runner achieved for a single
+//! segment, such as the average or the median segment time.
+
+use crate::{guard, time_span::NullableTimeSpan};
+use livesplit_core::{analysis::segment_statistics::SegmentStatistics, TimeSpan};
+use std::ptr;
+
+/// type
+pub type OwnedSegmentStatistics = Box<SegmentStatistics>;
+
+/// drop
+#[no_mangle]
+pub extern "C" fn SegmentStatistics_drop(this: OwnedSegmentStatistics) {
+    guard::dispose(this);
+}
+
+/// Returns the amount of segment times the statistics are based on.
+#[no_mangle]
+pub extern "C" fn SegmentStatistics_count(this: &SegmentStatistics) -> usize {
+    guard::call(guard::address(this), move || this.count)
+}
+
+/// Returns the fastest segment time. If there are no segment times, <NULL> is
+/// returned.
+#[no_mangle]
+pub extern "C" fn SegmentStatistics_best(this: &SegmentStatistics) -> *const NullableTimeSpan {
+    guard::call(guard::address(this), move || output(&this.best))
+}
+
+/// Returns the slowest segment time. If there are no segment times, <NULL> is
+/// returned.
+#[no_mangle]
+pub extern "C" fn SegmentStatistics_worst(this: &SegmentStatistics) -> *const NullableTimeSpan {
+    guard::call(guard::address(this), move || output(&this.worst))
+}
+
+/// Returns the average of all the segment times. If there are no segment
+/// times, <NULL> is returned.
+#[no_mangle]
+pub extern "C" fn SegmentStatistics_average(this: &SegmentStatistics) -> *const NullableTimeSpan {
+    guard::call(guard::address(this), move || output(&this.average))
+}
+
+/// Returns the median of all the segment times. If there are no segment times,
+/// <NULL> is returned.
+#[no_mangle]
+pub extern "C" fn SegmentStatistics_median(this: &SegmentStatistics) -> *const NullableTimeSpan {
+    guard::call(guard::address(this), move || output(&this.median))
+}
+
+fn output(time: &Option<TimeSpan>) -> *const NullableTimeSpan {
+    time.as_ref().map_or(ptr::null(), |time| time)
+}
@@ -2,7 +2,7 @@
 //! segment in a comparison of your choosing. If no comparison is specified it
 //! uses the timer's current comparison.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::component::OwnedComponent;
 use crate::key_value_component_state::OwnedKeyValueComponentState;
 use livesplit_core::component::segment_time::Component as SegmentTimeComponent;
@@ -14,13 +14,13 @@ pub type OwnedSegmentTimeComponent = Box<SegmentTimeComponent>;
 /// Creates a new Segment Time Component.
 #[no_mangle]
 pub extern "C" fn SegmentTimeComponent_new() -> OwnedSegmentTimeComponent {
-    Box::new(SegmentTimeComponent::new())
+    guard::abort_on_unwind(move || Box::new(SegmentTimeComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn SegmentTimeComponent_drop(this: OwnedSegmentTimeComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
@@ -29,7 +29,7 @@ pub extern "C" fn SegmentTimeComponent_drop(this: OwnedSegmentTimeComponent) {
 pub extern "C" fn SegmentTimeComponent_into_generic(
     this: OwnedSegmentTimeComponent,
 ) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -38,16 +38,34 @@ pub extern "C" fn SegmentTimeComponent_state_as_json(
     this: &SegmentTimeComponent,
     timer: &Timer,
 ) -> Json {
-    output_vec(|o| {
-        this.state(timer).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(timer).write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of SegmentTimeComponent_state_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SegmentTimeComponent_state_as_json_u16(
+    this: &SegmentTimeComponent,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        SegmentTimeComponent_state_as_json(this, timer),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer provided.
 #[no_mangle]
 pub extern "C" fn SegmentTimeComponent_state(
     this: &SegmentTimeComponent,
     timer: &Timer,
 ) -> OwnedKeyValueComponentState {
-    Box::new(this.state(timer))
+    guard::call_or_abort(guard::address(this), move || Box::new(this.state(timer)))
 }
@@ -1,8 +1,8 @@
 //! The Separator Component is a simple component that only serves to render
 //! separators between components.
 
-use crate::component::OwnedComponent;
 use crate::separator_component_state::OwnedSeparatorComponentState;
+use crate::{component::OwnedComponent, guard};
 use livesplit_core::component::separator::Component as SeparatorComponent;
 
 /// type
@@ -11,20 +11,20 @@ pub type OwnedSeparatorComponent = Box<SeparatorComponent>;
 /// Creates a new Separator Component.
 #[no_mangle]
 pub extern "C" fn SeparatorComponent_new() -> OwnedSeparatorComponent {
-    Box::new(SeparatorComponent::new())
+    guard::abort_on_unwind(move || Box::new(SeparatorComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn SeparatorComponent_drop(this: OwnedSeparatorComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
 /// layout.
 #[no_mangle]
 pub extern "C" fn SeparatorComponent_into_generic(this: OwnedSeparatorComponent) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Calculates the component's state.
@@ -32,5 +32,5 @@ pub extern "C" fn SeparatorComponent_into_generic(this: OwnedSeparatorComponent)
 pub extern "C" fn SeparatorComponent_state(
     this: &mut SeparatorComponent,
 ) -> OwnedSeparatorComponentState {
-    Box::new(this.state())
+    guard::call_or_abort(guard::address(this), move || Box::new(this.state()))
 }
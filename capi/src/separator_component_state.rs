@@ -1,5 +1,6 @@
 //! The state object describes the information to visualize for this component.
 
+use crate::guard;
 use livesplit_core::component::separator::State as SeparatorComponentState;
 
 /// type
@@ -8,5 +9,5 @@ pub type OwnedSeparatorComponentState = Box<SeparatorComponentState>;
 /// drop
 #[no_mangle]
 pub extern "C" fn SeparatorComponentState_drop(this: OwnedSeparatorComponentState) {
-    drop(this);
+    guard::dispose(this);
 }
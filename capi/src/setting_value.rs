@@ -1,18 +1,20 @@
 //! Describes a setting's value. Such a value can be of a variety of different
 //! types.
 
-use crate::{output_vec, str, Json};
+use crate::{guard, output_utf16_from, output_vec, str, Json, Utf16Param};
 use livesplit_core::{
     component::{
         splits::{ColumnStartWith, ColumnUpdateTrigger, ColumnUpdateWith},
         timer::DeltaGradient,
     },
+    hotkey::Hotkey,
     layout::LayoutDirection,
     settings::{
         Alignment, BackgroundImage, Color, ColumnKind, Font, FontStretch, FontStyle, FontWeight,
-        Gradient, ImageId, LayoutBackground, ListGradient, Value as SettingValue,
+        Gradient, ImageId, LayoutBackground, List, ListGradient, PaletteColor, SemanticColor,
+        Value as SettingValue,
     },
-    timing::formatter::{Accuracy, DigitsFormat},
+    timing::formatter::{Accuracy, DigitsFormat, TimePattern},
     TimingMethod,
 };
 use std::{os::raw::c_char, str::FromStr};
@@ -25,39 +27,62 @@ pub type NullableOwnedSettingValue = Option<OwnedSettingValue>;
 /// drop
 #[no_mangle]
 pub extern "C" fn SettingValue_drop(this: OwnedSettingValue) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Encodes this Setting Value's state as JSON.
 #[no_mangle]
 pub extern "C" fn SettingValue_as_json(this: &SettingValue) -> Json {
-    output_vec(|o| {
-        serde_json::to_writer(o, this).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            serde_json::to_writer(o, this).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of SettingValue_as_json. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_as_json_u16(
+    this: &SettingValue,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(SettingValue_as_json(this), buf, buf_len)
+}
+
 /// Creates a new setting value from a boolean value.
 #[no_mangle]
 pub extern "C" fn SettingValue_from_bool(value: bool) -> OwnedSettingValue {
-    Box::new(value.into())
+    guard::abort_on_unwind(move || Box::new(value.into()))
 }
 
 /// Creates a new setting value from an unsigned integer.
 #[no_mangle]
 pub extern "C" fn SettingValue_from_uint(value: u32) -> OwnedSettingValue {
-    Box::new((value as u64).into())
+    guard::abort_on_unwind(move || Box::new((value as u64).into()))
 }
 
 /// Creates a new setting value from a signed integer.
 #[no_mangle]
 pub extern "C" fn SettingValue_from_int(value: i32) -> OwnedSettingValue {
-    Box::new((value as i64).into())
+    guard::abort_on_unwind(move || Box::new((value as i64).into()))
 }
 
 /// Creates a new setting value from a string.
 #[no_mangle]
 pub unsafe extern "C" fn SettingValue_from_string(value: *const c_char) -> OwnedSettingValue {
-    Box::new(str(value).to_string().into())
+    guard::abort_on_unwind(move || Box::new(str(value).to_string().into()))
+}
+
+/// The UTF-16 variant of SettingValue_from_string. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_string_u16(
+    value: *const u16,
+    value_len: usize,
+) -> OwnedSettingValue {
+    SettingValue_from_string(Utf16Param::new(value, value_len).as_ptr())
 }
 
 /// Creates a new setting value from a string that has the type `optional string`.
@@ -65,18 +90,30 @@ pub unsafe extern "C" fn SettingValue_from_string(value: *const c_char) -> Owned
 pub unsafe extern "C" fn SettingValue_from_optional_string(
     value: *const c_char,
 ) -> OwnedSettingValue {
-    let value = if value.is_null() {
-        None::<String>.into()
-    } else {
-        Some(str(value).to_string()).into()
-    };
-    Box::new(value)
+    guard::abort_on_unwind(move || {
+        let value = if value.is_null() {
+            None::<String>.into()
+        } else {
+            Some(str(value).to_string()).into()
+        };
+        Box::new(value)
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_optional_string. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_optional_string_u16(
+    value: *const u16,
+    value_len: usize,
+) -> OwnedSettingValue {
+    SettingValue_from_optional_string(Utf16Param::new(value, value_len).as_ptr())
 }
 
 /// Creates a new empty setting value that has the type `optional string`.
 #[no_mangle]
 pub extern "C" fn SettingValue_from_optional_empty_string() -> OwnedSettingValue {
-    Box::new(None::<String>.into())
+    guard::abort_on_unwind(move || Box::new(None::<String>.into()))
 }
 
 /// Creates a new setting value from an accuracy name. If it doesn't match a
@@ -85,15 +122,27 @@ pub extern "C" fn SettingValue_from_optional_empty_string() -> OwnedSettingValue
 pub unsafe extern "C" fn SettingValue_from_accuracy(
     value: *const c_char,
 ) -> NullableOwnedSettingValue {
-    let value = str(value);
-    let value = match value {
-        "Seconds" => Accuracy::Seconds,
-        "Tenths" => Accuracy::Tenths,
-        "Hundredths" => Accuracy::Hundredths,
-        "Milliseconds" => Accuracy::Milliseconds,
-        _ => return None,
-    };
-    Some(Box::new(value.into()))
+    guard::call(0, move || {
+        let value = str(value);
+        let value = match value {
+            "Seconds" => Accuracy::Seconds,
+            "Tenths" => Accuracy::Tenths,
+            "Hundredths" => Accuracy::Hundredths,
+            "Milliseconds" => Accuracy::Milliseconds,
+            _ => return None,
+        };
+        Some(Box::new(value.into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_accuracy. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_accuracy_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_accuracy(Utf16Param::new(value, value_len).as_ptr())
 }
 
 /// Creates a new setting value from a digits format name. If it doesn't match a
@@ -102,17 +151,61 @@ pub unsafe extern "C" fn SettingValue_from_accuracy(
 pub unsafe extern "C" fn SettingValue_from_digits_format(
     value: *const c_char,
 ) -> NullableOwnedSettingValue {
-    let value = str(value);
-    let value = match value {
-        "SingleDigitSeconds" => DigitsFormat::SingleDigitSeconds,
-        "DoubleDigitSeconds" => DigitsFormat::DoubleDigitSeconds,
-        "SingleDigitMinutes" => DigitsFormat::SingleDigitMinutes,
-        "DoubleDigitMinutes" => DigitsFormat::DoubleDigitMinutes,
-        "SingleDigitHours" => DigitsFormat::SingleDigitHours,
-        "DoubleDigitHours" => DigitsFormat::DoubleDigitHours,
-        _ => return None,
-    };
-    Some(Box::new(value.into()))
+    guard::call(0, move || {
+        let value = str(value);
+        let value = match value {
+            "SingleDigitSeconds" => DigitsFormat::SingleDigitSeconds,
+            "DoubleDigitSeconds" => DigitsFormat::DoubleDigitSeconds,
+            "SingleDigitMinutes" => DigitsFormat::SingleDigitMinutes,
+            "DoubleDigitMinutes" => DigitsFormat::DoubleDigitMinutes,
+            "SingleDigitHours" => DigitsFormat::SingleDigitHours,
+            "DoubleDigitHours" => DigitsFormat::DoubleDigitHours,
+            _ => return None,
+        };
+        Some(Box::new(value.into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_digits_format. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_digits_format_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_digits_format(Utf16Param::new(value, value_len).as_ptr())
+}
+
+/// Creates a new setting value from a time pattern with the type `optional
+/// time pattern`. If the pattern is invalid, <NULL> is returned.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_optional_time_pattern(
+    value: *const c_char,
+) -> NullableOwnedSettingValue {
+    guard::call(0, move || {
+        if value.is_null() {
+            Some(Box::new(None::<TimePattern>.into()))
+        } else {
+            let value = str(value).parse::<TimePattern>().ok()?;
+            Some(Box::new(Some(value).into()))
+        }
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_optional_time_pattern. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_optional_time_pattern_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_optional_time_pattern(Utf16Param::new(value, value_len).as_ptr())
+}
+
+/// Creates a new empty setting value with the type `optional time pattern`.
+#[no_mangle]
+pub extern "C" fn SettingValue_from_optional_empty_time_pattern() -> OwnedSettingValue {
+    guard::abort_on_unwind(move || Box::new(None::<TimePattern>.into()))
 }
 
 /// Creates a new setting value from a timing method name with the type
@@ -122,29 +215,41 @@ pub unsafe extern "C" fn SettingValue_from_digits_format(
 pub unsafe extern "C" fn SettingValue_from_optional_timing_method(
     value: *const c_char,
 ) -> NullableOwnedSettingValue {
-    if value.is_null() {
-        Some(Box::new(None::<TimingMethod>.into()))
-    } else {
-        let value = str(value);
-        let value = match value {
-            "RealTime" => TimingMethod::RealTime,
-            "GameTime" => TimingMethod::GameTime,
-            _ => return None,
-        };
-        Some(Box::new(Some(value).into()))
-    }
+    guard::call(0, move || {
+        if value.is_null() {
+            Some(Box::new(None::<TimingMethod>.into()))
+        } else {
+            let value = str(value);
+            let value = match value {
+                "RealTime" => TimingMethod::RealTime,
+                "GameTime" => TimingMethod::GameTime,
+                _ => return None,
+            };
+            Some(Box::new(Some(value).into()))
+        }
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_optional_timing_method. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_optional_timing_method_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_optional_timing_method(Utf16Param::new(value, value_len).as_ptr())
 }
 
 /// Creates a new empty setting value with the type `optional timing method`.
 #[no_mangle]
 pub extern "C" fn SettingValue_from_optional_empty_timing_method() -> OwnedSettingValue {
-    Box::new(None::<TimingMethod>.into())
+    guard::abort_on_unwind(move || Box::new(None::<TimingMethod>.into()))
 }
 
 /// Creates a new setting value from the color provided as RGBA.
 #[no_mangle]
 pub extern "C" fn SettingValue_from_color(r: f32, g: f32, b: f32, a: f32) -> OwnedSettingValue {
-    Box::new(Color::rgba(r, g, b, a).into())
+    guard::abort_on_unwind(move || Box::new(Color::rgba(r, g, b, a).into()))
 }
 
 /// Creates a new setting value from the color provided as RGBA with the type
@@ -156,19 +261,19 @@ pub extern "C" fn SettingValue_from_optional_color(
     b: f32,
     a: f32,
 ) -> OwnedSettingValue {
-    Box::new(Some(Color::rgba(r, g, b, a)).into())
+    guard::abort_on_unwind(move || Box::new(Some(Color::rgba(r, g, b, a)).into()))
 }
 
 /// Creates a new empty setting value with the type `optional color`.
 #[no_mangle]
 pub extern "C" fn SettingValue_from_optional_empty_color() -> OwnedSettingValue {
-    Box::new(None::<Color>.into())
+    guard::abort_on_unwind(move || Box::new(None::<Color>.into()))
 }
 
 /// Creates a new setting value that is a transparent gradient.
 #[no_mangle]
 pub extern "C" fn SettingValue_from_transparent_gradient() -> OwnedSettingValue {
-    Box::new(Gradient::Transparent.into())
+    guard::abort_on_unwind(move || Box::new(Gradient::Transparent.into()))
 }
 
 /// Creates a new setting value from the vertical gradient provided as two RGBA colors.
@@ -183,7 +288,11 @@ pub extern "C" fn SettingValue_from_vertical_gradient(
     b2: f32,
     a2: f32,
 ) -> OwnedSettingValue {
-    Box::new(Gradient::Vertical(Color::rgba(r1, g1, b1, a1), Color::rgba(r2, g2, b2, a2)).into())
+    guard::abort_on_unwind(move || {
+        Box::new(
+            Gradient::Vertical(Color::rgba(r1, g1, b1, a1), Color::rgba(r2, g2, b2, a2)).into(),
+        )
+    })
 }
 
 /// Creates a new setting value from the horizontal gradient provided as two RGBA colors.
@@ -198,7 +307,56 @@ pub extern "C" fn SettingValue_from_horizontal_gradient(
     b2: f32,
     a2: f32,
 ) -> OwnedSettingValue {
-    Box::new(Gradient::Horizontal(Color::rgba(r1, g1, b1, a1), Color::rgba(r2, g2, b2, a2)).into())
+    guard::abort_on_unwind(move || {
+        Box::new(
+            Gradient::Horizontal(Color::rgba(r1, g1, b1, a1), Color::rgba(r2, g2, b2, a2)).into(),
+        )
+    })
+}
+
+/// Creates a new setting value from the linear gradient provided as an angle in
+/// degrees and two RGBA colors. An angle of 0° goes from the bottom to the top
+/// and 90° goes from the left to the right.
+#[no_mangle]
+pub extern "C" fn SettingValue_from_linear_gradient(
+    angle: f32,
+    r1: f32,
+    g1: f32,
+    b1: f32,
+    a1: f32,
+    r2: f32,
+    g2: f32,
+    b2: f32,
+    a2: f32,
+) -> OwnedSettingValue {
+    guard::abort_on_unwind(move || {
+        Box::new(
+            Gradient::Linear(
+                angle,
+                Color::rgba(r1, g1, b1, a1),
+                Color::rgba(r2, g2, b2, a2),
+            )
+            .into(),
+        )
+    })
+}
+
+/// Creates a new setting value from the radial gradient provided as two RGBA
+/// colors for the center and the edge.
+#[no_mangle]
+pub extern "C" fn SettingValue_from_radial_gradient(
+    r1: f32,
+    g1: f32,
+    b1: f32,
+    a1: f32,
+    r2: f32,
+    g2: f32,
+    b2: f32,
+    a2: f32,
+) -> OwnedSettingValue {
+    guard::abort_on_unwind(move || {
+        Box::new(Gradient::Radial(Color::rgba(r1, g1, b1, a1), Color::rgba(r2, g2, b2, a2)).into())
+    })
 }
 
 /// Creates a new setting value from the alternating gradient provided as two RGBA colors.
@@ -213,9 +371,12 @@ pub extern "C" fn SettingValue_from_alternating_gradient(
     b2: f32,
     a2: f32,
 ) -> OwnedSettingValue {
-    Box::new(
-        ListGradient::Alternating(Color::rgba(r1, g1, b1, a1), Color::rgba(r2, g2, b2, a2)).into(),
-    )
+    guard::abort_on_unwind(move || {
+        Box::new(
+            ListGradient::Alternating(Color::rgba(r1, g1, b1, a1), Color::rgba(r2, g2, b2, a2))
+                .into(),
+        )
+    })
 }
 
 /// Creates a new setting value from the alignment name provided. If it doesn't
@@ -224,14 +385,26 @@ pub extern "C" fn SettingValue_from_alternating_gradient(
 pub unsafe extern "C" fn SettingValue_from_alignment(
     value: *const c_char,
 ) -> NullableOwnedSettingValue {
-    let value = str(value);
-    let value = match value {
-        "Center" => Alignment::Center,
-        "Left" => Alignment::Left,
-        "Auto" => Alignment::Auto,
-        _ => return None,
-    };
-    Some(Box::new(value.into()))
+    guard::call(0, move || {
+        let value = str(value);
+        let value = match value {
+            "Center" => Alignment::Center,
+            "Left" => Alignment::Left,
+            "Auto" => Alignment::Auto,
+            _ => return None,
+        };
+        Some(Box::new(value.into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_alignment. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_alignment_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_alignment(Utf16Param::new(value, value_len).as_ptr())
 }
 
 /// Creates a new setting value from the column kind with the name provided. If
@@ -240,13 +413,25 @@ pub unsafe extern "C" fn SettingValue_from_alignment(
 pub unsafe extern "C" fn SettingValue_from_column_kind(
     value: *const c_char,
 ) -> NullableOwnedSettingValue {
-    let value = str(value);
-    let value = match value {
-        "Time" => ColumnKind::Time,
-        "Variable" => ColumnKind::Variable,
-        _ => return None,
-    };
-    Some(Box::new(value.into()))
+    guard::call(0, move || {
+        let value = str(value);
+        let value = match value {
+            "Time" => ColumnKind::Time,
+            "Variable" => ColumnKind::Variable,
+            _ => return None,
+        };
+        Some(Box::new(value.into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_column_kind. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_column_kind_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_column_kind(Utf16Param::new(value, value_len).as_ptr())
 }
 
 /// Creates a new setting value from the column start with the name provided. If
@@ -255,15 +440,27 @@ pub unsafe extern "C" fn SettingValue_from_column_kind(
 pub unsafe extern "C" fn SettingValue_from_column_start_with(
     value: *const c_char,
 ) -> NullableOwnedSettingValue {
-    let value = str(value);
-    let value = match value {
-        "Empty" => ColumnStartWith::Empty,
-        "ComparisonTime" => ColumnStartWith::ComparisonTime,
-        "ComparisonSegmentTime" => ColumnStartWith::ComparisonSegmentTime,
-        "PossibleTimeSave" => ColumnStartWith::PossibleTimeSave,
-        _ => return None,
-    };
-    Some(Box::new(value.into()))
+    guard::call(0, move || {
+        let value = str(value);
+        let value = match value {
+            "Empty" => ColumnStartWith::Empty,
+            "ComparisonTime" => ColumnStartWith::ComparisonTime,
+            "ComparisonSegmentTime" => ColumnStartWith::ComparisonSegmentTime,
+            "PossibleTimeSave" => ColumnStartWith::PossibleTimeSave,
+            _ => return None,
+        };
+        Some(Box::new(value.into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_column_start_with. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_column_start_with_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_column_start_with(Utf16Param::new(value, value_len).as_ptr())
 }
 
 /// Creates a new setting value from the column update with the name provided.
@@ -272,18 +469,30 @@ pub unsafe extern "C" fn SettingValue_from_column_start_with(
 pub unsafe extern "C" fn SettingValue_from_column_update_with(
     value: *const c_char,
 ) -> NullableOwnedSettingValue {
-    let value = str(value);
-    let value = match value {
-        "DontUpdate" => ColumnUpdateWith::DontUpdate,
-        "SplitTime" => ColumnUpdateWith::SplitTime,
-        "Delta" => ColumnUpdateWith::Delta,
-        "DeltaWithFallback" => ColumnUpdateWith::DeltaWithFallback,
-        "SegmentTime" => ColumnUpdateWith::SegmentTime,
-        "SegmentDelta" => ColumnUpdateWith::SegmentDelta,
-        "SegmentDeltaWithFallback" => ColumnUpdateWith::SegmentDeltaWithFallback,
-        _ => return None,
-    };
-    Some(Box::new(value.into()))
+    guard::call(0, move || {
+        let value = str(value);
+        let value = match value {
+            "DontUpdate" => ColumnUpdateWith::DontUpdate,
+            "SplitTime" => ColumnUpdateWith::SplitTime,
+            "Delta" => ColumnUpdateWith::Delta,
+            "DeltaWithFallback" => ColumnUpdateWith::DeltaWithFallback,
+            "SegmentTime" => ColumnUpdateWith::SegmentTime,
+            "SegmentDelta" => ColumnUpdateWith::SegmentDelta,
+            "SegmentDeltaWithFallback" => ColumnUpdateWith::SegmentDeltaWithFallback,
+            _ => return None,
+        };
+        Some(Box::new(value.into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_column_update_with. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_column_update_with_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_column_update_with(Utf16Param::new(value, value_len).as_ptr())
 }
 
 /// Creates a new setting value from the column update trigger. If it doesn't
@@ -292,14 +501,26 @@ pub unsafe extern "C" fn SettingValue_from_column_update_with(
 pub unsafe extern "C" fn SettingValue_from_column_update_trigger(
     value: *const c_char,
 ) -> NullableOwnedSettingValue {
-    let value = str(value);
-    let value = match value {
-        "OnStartingSegment" => ColumnUpdateTrigger::OnStartingSegment,
-        "Contextual" => ColumnUpdateTrigger::Contextual,
-        "OnEndingSegment" => ColumnUpdateTrigger::OnEndingSegment,
-        _ => return None,
-    };
-    Some(Box::new(value.into()))
+    guard::call(0, move || {
+        let value = str(value);
+        let value = match value {
+            "OnStartingSegment" => ColumnUpdateTrigger::OnStartingSegment,
+            "Contextual" => ColumnUpdateTrigger::Contextual,
+            "OnEndingSegment" => ColumnUpdateTrigger::OnEndingSegment,
+            _ => return None,
+        };
+        Some(Box::new(value.into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_column_update_trigger. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_column_update_trigger_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_column_update_trigger(Utf16Param::new(value, value_len).as_ptr())
 }
 
 /// Creates a new setting value from the layout direction. If it doesn't
@@ -308,13 +529,55 @@ pub unsafe extern "C" fn SettingValue_from_column_update_trigger(
 pub unsafe extern "C" fn SettingValue_from_layout_direction(
     value: *const c_char,
 ) -> NullableOwnedSettingValue {
-    let value = str(value);
-    let value = match value {
-        "Vertical" => LayoutDirection::Vertical,
-        "Horizontal" => LayoutDirection::Horizontal,
-        _ => return None,
-    };
-    Some(Box::new(value.into()))
+    guard::call(0, move || {
+        let value = str(value);
+        let value = match value {
+            "Vertical" => LayoutDirection::Vertical,
+            "Horizontal" => LayoutDirection::Horizontal,
+            _ => return None,
+        };
+        Some(Box::new(value.into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_layout_direction. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_layout_direction_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_layout_direction(Utf16Param::new(value, value_len).as_ptr())
+}
+
+/// Creates a new setting value with the type `hotkey`. The hotkey is described
+/// by the name of its key code, optionally preceded by the modifiers that need
+/// to be held down, such as `Ctrl + Shift + Numpad1`. If it doesn't describe a
+/// valid hotkey, <NULL> is returned.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_hotkey(
+    value: *const c_char,
+) -> NullableOwnedSettingValue {
+    guard::call(0, move || {
+        let hotkey = Hotkey::from_str(str(value)).ok()?;
+        Some(Box::new(Some(hotkey).into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_hotkey. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_hotkey_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_hotkey(Utf16Param::new(value, value_len).as_ptr())
+}
+
+/// Creates a new empty setting value with the type `hotkey`.
+#[no_mangle]
+pub extern "C" fn SettingValue_from_empty_hotkey() -> OwnedSettingValue {
+    guard::abort_on_unwind(move || Box::new(None::<Hotkey>.into()))
 }
 
 /// Creates a new setting value with the type `font`.
@@ -325,49 +588,72 @@ pub unsafe extern "C" fn SettingValue_from_font(
     weight: *const c_char,
     stretch: *const c_char,
 ) -> NullableOwnedSettingValue {
-    Some(Box::new(
-        Some(Font {
-            family: str(family).to_owned(),
-            style: match str(style) {
-                "normal" => FontStyle::Normal,
-                "italic" => FontStyle::Italic,
-                _ => return None,
-            },
-            weight: match str(weight) {
-                "thin" => FontWeight::Thin,
-                "extra-light" => FontWeight::ExtraLight,
-                "light" => FontWeight::Light,
-                "semi-light" => FontWeight::SemiLight,
-                "normal" => FontWeight::Normal,
-                "medium" => FontWeight::Medium,
-                "semi-bold" => FontWeight::SemiBold,
-                "bold" => FontWeight::Bold,
-                "extra-bold" => FontWeight::ExtraBold,
-                "black" => FontWeight::Black,
-                "extra-black" => FontWeight::ExtraBlack,
-                _ => return None,
-            },
-            stretch: match str(stretch) {
-                "ultra-condensed" => FontStretch::UltraCondensed,
-                "extra-condensed" => FontStretch::ExtraCondensed,
-                "condensed" => FontStretch::Condensed,
-                "semi-condensed" => FontStretch::SemiCondensed,
-                "normal" => FontStretch::Normal,
-                "semi-expanded" => FontStretch::SemiExpanded,
-                "expanded" => FontStretch::Expanded,
-                "extra-expanded" => FontStretch::ExtraExpanded,
-                "ultra-expanded" => FontStretch::UltraExpanded,
-                _ => return None,
-            },
-        })
-        .into(),
-    ))
+    guard::call(0, move || {
+        Some(Box::new(
+            Some(Font {
+                family: str(family).to_owned(),
+                style: match str(style) {
+                    "normal" => FontStyle::Normal,
+                    "italic" => FontStyle::Italic,
+                    _ => return None,
+                },
+                weight: match str(weight) {
+                    "thin" => FontWeight::Thin,
+                    "extra-light" => FontWeight::ExtraLight,
+                    "light" => FontWeight::Light,
+                    "semi-light" => FontWeight::SemiLight,
+                    "normal" => FontWeight::Normal,
+                    "medium" => FontWeight::Medium,
+                    "semi-bold" => FontWeight::SemiBold,
+                    "bold" => FontWeight::Bold,
+                    "extra-bold" => FontWeight::ExtraBold,
+                    "black" => FontWeight::Black,
+                    "extra-black" => FontWeight::ExtraBlack,
+                    _ => return None,
+                },
+                stretch: match str(stretch) {
+                    "ultra-condensed" => FontStretch::UltraCondensed,
+                    "extra-condensed" => FontStretch::ExtraCondensed,
+                    "condensed" => FontStretch::Condensed,
+                    "semi-condensed" => FontStretch::SemiCondensed,
+                    "normal" => FontStretch::Normal,
+                    "semi-expanded" => FontStretch::SemiExpanded,
+                    "expanded" => FontStretch::Expanded,
+                    "extra-expanded" => FontStretch::ExtraExpanded,
+                    "ultra-expanded" => FontStretch::UltraExpanded,
+                    _ => return None,
+                },
+            })
+            .into(),
+        ))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_font. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_font_u16(
+    family: *const u16,
+    family_len: usize,
+    style: *const u16,
+    style_len: usize,
+    weight: *const u16,
+    weight_len: usize,
+    stretch: *const u16,
+    stretch_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_font(
+        Utf16Param::new(family, family_len).as_ptr(),
+        Utf16Param::new(style, style_len).as_ptr(),
+        Utf16Param::new(weight, weight_len).as_ptr(),
+        Utf16Param::new(stretch, stretch_len).as_ptr(),
+    )
 }
 
 /// Creates a new empty setting value with the type `font`.
 #[no_mangle]
 pub extern "C" fn SettingValue_from_empty_font() -> OwnedSettingValue {
-    Box::new(None::<Font>.into())
+    guard::abort_on_unwind(move || Box::new(None::<Font>.into()))
 }
 
 /// Creates a new setting value from the delta gradient with the name provided.
@@ -376,14 +662,26 @@ pub extern "C" fn SettingValue_from_empty_font() -> OwnedSettingValue {
 pub unsafe extern "C" fn SettingValue_from_delta_gradient(
     value: *const c_char,
 ) -> NullableOwnedSettingValue {
-    let value = str(value);
-    let value = match value {
-        "DeltaPlain" => DeltaGradient::DeltaPlain,
-        "DeltaVertical" => DeltaGradient::DeltaVertical,
-        "DeltaHorizontal" => DeltaGradient::DeltaHorizontal,
-        _ => return None,
-    };
-    Some(Box::new(value.into()))
+    guard::call(0, move || {
+        let value = str(value);
+        let value = match value {
+            "DeltaPlain" => DeltaGradient::DeltaPlain,
+            "DeltaVertical" => DeltaGradient::DeltaVertical,
+            "DeltaHorizontal" => DeltaGradient::DeltaHorizontal,
+            _ => return None,
+        };
+        Some(Box::new(value.into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_delta_gradient. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_delta_gradient_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_delta_gradient(Utf16Param::new(value, value_len).as_ptr())
 }
 
 /// Creates a new setting value from the background image with the image ID and
@@ -396,13 +694,143 @@ pub unsafe extern "C" fn SettingValue_from_background_image(
     opacity: f32,
     blur: f32,
 ) -> NullableOwnedSettingValue {
-    Some(Box::new(
-        LayoutBackground::Image(BackgroundImage {
-            image: ImageId::from_str(str(image_id)).ok()?,
-            brightness,
-            opacity,
-            blur,
-        })
-        .into(),
-    ))
+    guard::call(0, move || {
+        Some(Box::new(
+            LayoutBackground::Image(BackgroundImage {
+                image: ImageId::from_str(str(image_id)).ok()?,
+                brightness,
+                opacity,
+                blur,
+            })
+            .into(),
+        ))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_background_image. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_background_image_u16(
+    image_id: *const u16,
+    image_id_len: usize,
+    brightness: f32,
+    opacity: f32,
+    blur: f32,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_background_image(
+        Utf16Param::new(image_id, image_id_len).as_ptr(),
+        brightness,
+        opacity,
+        blur,
+    )
+}
+
+/// Creates a new palette color setting value that refers to the named color of
+/// the layout's palette provided. If it doesn't match a known name, <NULL> is
+/// returned.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_palette_color_name(
+    value: *const c_char,
+) -> NullableOwnedSettingValue {
+    guard::call(0, move || {
+        let value = str(value);
+        let value = match value {
+            "Default" => SemanticColor::Default,
+            "AheadGainingTime" => SemanticColor::AheadGainingTime,
+            "AheadLosingTime" => SemanticColor::AheadLosingTime,
+            "BehindLosingTime" => SemanticColor::BehindLosingTime,
+            "BehindGainingTime" => SemanticColor::BehindGainingTime,
+            "BestSegment" => SemanticColor::BestSegment,
+            "NotRunning" => SemanticColor::NotRunning,
+            "Paused" => SemanticColor::Paused,
+            "PersonalBest" => SemanticColor::PersonalBest,
+            _ => return None,
+        };
+        Some(Box::new(PaletteColor::Named(value).into()))
+    })
+}
+
+/// The UTF-16 variant of SettingValue_from_palette_color_name. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_from_palette_color_name_u16(
+    value: *const u16,
+    value_len: usize,
+) -> NullableOwnedSettingValue {
+    SettingValue_from_palette_color_name(Utf16Param::new(value, value_len).as_ptr())
+}
+
+/// Creates a new palette color setting value from the literal color provided
+/// as RGBA.
+#[no_mangle]
+pub extern "C" fn SettingValue_from_palette_color(
+    r: f32,
+    g: f32,
+    b: f32,
+    a: f32,
+) -> OwnedSettingValue {
+    guard::abort_on_unwind(move || Box::new(PaletteColor::Color(Color::rgba(r, g, b, a)).into()))
+}
+
+/// Creates a new empty list setting value whose items have the same type as
+/// the template provided. The template is the value that newly added items
+/// start out with.
+#[no_mangle]
+pub extern "C" fn SettingValue_from_list(template: OwnedSettingValue) -> OwnedSettingValue {
+    guard::consume(&*template);
+    guard::abort_on_unwind(move || Box::new(List::new(*template).into()))
+}
+
+/// Appends the item provided to the end of this list setting value. Returns
+/// <FALSE> if this is not a list or the item's type doesn't match the type of
+/// the list.
+#[no_mangle]
+pub extern "C" fn SettingValue_list_push(this: &mut SettingValue, item: OwnedSettingValue) -> bool {
+    guard::consume(&*item);
+    guard::call(guard::address(this), move || match this {
+        SettingValue::List(list) => list.push(*item).is_ok(),
+        _ => false,
+    })
+}
+
+/// Creates a new struct setting value without any fields.
+#[no_mangle]
+pub extern "C" fn SettingValue_from_struct() -> OwnedSettingValue {
+    guard::abort_on_unwind(move || Box::new(Vec::<(String, SettingValue)>::new().into()))
+}
+
+/// Sets the value of the field with the given name of this struct setting
+/// value. If there is no such field yet, it gets added as the last field.
+/// Returns <FALSE> if this is not a struct.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_struct_set_field(
+    this: &mut SettingValue,
+    name: *const c_char,
+    value: OwnedSettingValue,
+) -> bool {
+    guard::consume(&*value);
+    guard::call(guard::address(this), move || {
+        let SettingValue::Struct(fields) = this else {
+            return false;
+        };
+        let name = str(name);
+        if let Some((_, field)) = fields.iter_mut().find(|(n, _)| n == name) {
+            *field = *value;
+        } else {
+            fields.push((name.to_owned(), *value));
+        }
+        true
+    })
+}
+
+/// The UTF-16 variant of SettingValue_struct_set_field. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SettingValue_struct_set_field_u16(
+    this: &mut SettingValue,
+    name: *const u16,
+    name_len: usize,
+    value: OwnedSettingValue,
+) -> bool {
+    SettingValue_struct_set_field(this, Utf16Param::new(name, name_len).as_ptr(), value)
 }
@@ -1,9 +1,12 @@
 //! A Shared Timer that can be used to share a single timer object with multiple
 //! owners.
 
-use crate::timer::OwnedTimer;
 use crate::timer_read_lock::OwnedTimerReadLock;
 use crate::timer_write_lock::OwnedTimerWriteLock;
+use crate::{
+    guard,
+    timer::{self, EventListener, OwnedTimer},
+};
 use livesplit_core::SharedTimer;
 
 /// type
@@ -13,13 +16,13 @@ pub type OwnedSharedTimer = Box<SharedTimer>;
 /// timer object only gets disposed when the final handle gets disposed.
 #[no_mangle]
 pub extern "C" fn SharedTimer_share(this: &SharedTimer) -> OwnedSharedTimer {
-    Box::new(this.clone())
+    guard::call_or_abort(guard::address(this), move || Box::new(this.clone()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn SharedTimer_drop(this: OwnedSharedTimer) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Requests read access to the timer that is being shared. This blocks the
@@ -27,7 +30,7 @@ pub extern "C" fn SharedTimer_drop(this: OwnedSharedTimer) {
 /// you are done using the timer.
 #[no_mangle]
 pub extern "C" fn SharedTimer_read(this: &'static SharedTimer) -> OwnedTimerReadLock {
-    Box::new(this.read().unwrap())
+    guard::call_or_abort(guard::address(this), move || Box::new(this.read().unwrap()))
 }
 
 /// Requests write access to the timer that is being shared. This blocks the
@@ -35,7 +38,9 @@ pub extern "C" fn SharedTimer_read(this: &'static SharedTimer) -> OwnedTimerRead
 /// lock when you are done using the timer.
 #[no_mangle]
 pub extern "C" fn SharedTimer_write(this: &'static SharedTimer) -> OwnedTimerWriteLock {
-    Box::new(this.write().unwrap())
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.write().unwrap())
+    })
 }
 
 /// Replaces the timer that is being shared by the timer provided. This blocks
@@ -44,5 +49,34 @@ pub extern "C" fn SharedTimer_write(this: &'static SharedTimer) -> OwnedTimerWri
 /// completion.
 #[no_mangle]
 pub extern "C" fn SharedTimer_replace_inner(this: &SharedTimer, timer: OwnedTimer) {
-    *this.write().unwrap() = *timer;
+    guard::consume(&*timer);
+    guard::call(guard::address(this), move || {
+        *this.write().unwrap() = *timer;
+    })
+}
+
+/// Registers a listener that gets called with every event the timer that is
+/// being shared emits. This blocks the thread as long as there are active
+/// write or read locks. The listener is called while the write lock is held,
+/// so it must not lock the timer itself. Otherwise this works just like
+/// Timer_add_event_listener.
+#[no_mangle]
+pub extern "C" fn SharedTimer_add_event_listener(
+    this: &SharedTimer,
+    listener: EventListener,
+    data: usize,
+) -> u64 {
+    guard::call(guard::address(this), move || {
+        timer::add_event_listener(&mut this.write().unwrap(), listener, data)
+    })
+}
+
+/// Removes the event listener with the ID provided from the timer that is
+/// being shared. This blocks the thread as long as there are active write or
+/// read locks. Returns <FALSE> if there is no such listener.
+#[no_mangle]
+pub extern "C" fn SharedTimer_remove_event_listener(this: &SharedTimer, id: u64) -> bool {
+    guard::call(guard::address(this), move || {
+        this.write().unwrap().remove_event_listener(id.into())
+    })
 }
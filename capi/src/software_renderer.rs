@@ -4,28 +4,33 @@
 use livesplit_core::{layout::LayoutState, settings::ImageCache};
 
 #[cfg(feature = "software-rendering")]
-use livesplit_core::rendering::software::BorrowedRenderer as SoftwareRenderer;
+use livesplit_core::rendering::software::BorrowedRenderer;
 
-use crate::slice_mut;
+use crate::{guard, slice, slice_mut};
+
+/// type
+pub struct SoftwareRenderer {
+    renderer: BorrowedRenderer,
+    png: Vec<u8>,
+}
 
 #[cfg(not(feature = "software-rendering"))]
 /// dummy
-pub struct SoftwareRenderer;
+pub struct BorrowedRenderer;
 #[cfg(not(feature = "software-rendering"))]
-impl SoftwareRenderer {
+impl BorrowedRenderer {
     fn new() -> Self {
         panic!("The software renderer is not compiled in.")
     }
 
-    fn render(
-        &mut self,
-        _: &LayoutState,
-        _: &ImageCache,
-        _: &mut [u8],
-        _: [u32; 2],
-        _: u32,
-        _: bool,
-    ) {
+    fn render(&self, _: &LayoutState, _: &ImageCache, _: &mut [u8], _: [u32; 2], _: u32, _: bool) {}
+
+    fn render_png(&self, _: &LayoutState, _: &ImageCache, _: [u32; 2]) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn load_font(&self, _: Vec<u8>) -> bool {
+        false
     }
 }
 
@@ -35,13 +40,18 @@ pub type OwnedSoftwareRenderer = Box<SoftwareRenderer>;
 /// Creates a new software renderer.
 #[no_mangle]
 pub extern "C" fn SoftwareRenderer_new() -> OwnedSoftwareRenderer {
-    Box::new(SoftwareRenderer::new())
+    guard::abort_on_unwind(move || {
+        Box::new(SoftwareRenderer {
+            renderer: BorrowedRenderer::new(),
+            png: Vec::new(),
+        })
+    })
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn SoftwareRenderer_drop(this: OwnedSoftwareRenderer) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Renders the layout state provided into the image buffer provided. The image
@@ -64,12 +74,63 @@ pub unsafe extern "C" fn SoftwareRenderer_render(
     stride: u32,
     force_redraw: bool,
 ) {
-    this.render(
-        layout_state,
-        image_cache,
-        slice_mut(data, stride as usize * height as usize * 4),
-        [width, height],
-        stride,
-        force_redraw,
-    );
+    guard::call(guard::address(this), move || {
+        this.renderer.render(
+            layout_state,
+            image_cache,
+            slice_mut(data, stride as usize * height as usize * 4),
+            [width, height],
+            stride,
+            force_redraw,
+        );
+    })
+}
+
+/// Renders the layout state provided with the chosen resolution and encodes the
+/// image as a PNG file. The whole image is always redrawn. The PNG file is
+/// stored in the software renderer until the next time this is called. It can
+/// be accessed via SoftwareRenderer_png_ptr and SoftwareRenderer_png_len.
+#[no_mangle]
+pub extern "C" fn SoftwareRenderer_render_png(
+    this: &mut SoftwareRenderer,
+    layout_state: &LayoutState,
+    image_cache: &ImageCache,
+    width: u32,
+    height: u32,
+) {
+    guard::call(guard::address(this), move || {
+        this.png = this
+            .renderer
+            .render_png(layout_state, image_cache, [width, height]);
+    })
+}
+
+/// Returns a pointer to the bytes of the PNG file that was last encoded by
+/// SoftwareRenderer_render_png.
+#[no_mangle]
+pub extern "C" fn SoftwareRenderer_png_ptr(this: &SoftwareRenderer) -> *const u8 {
+    guard::call(guard::address(this), move || this.png.as_ptr())
+}
+
+/// Returns the byte length of the PNG file that was last encoded by
+/// SoftwareRenderer_render_png.
+#[no_mangle]
+pub extern "C" fn SoftwareRenderer_png_len(this: &SoftwareRenderer) -> usize {
+    guard::call(guard::address(this), move || this.png.len())
+}
+
+/// Loads the font data provided, so that its fonts can be referred to by their
+/// family name in the font settings of a layout. This way a layout can bundle
+/// its fonts and render the same on every machine. TrueType and OpenType fonts
+/// and font collections are supported. Returns whether the data contained any
+/// fonts.
+#[no_mangle]
+pub unsafe extern "C" fn SoftwareRenderer_load_font(
+    this: &mut SoftwareRenderer,
+    data: *const u8,
+    len: usize,
+) -> bool {
+    guard::call(guard::address(this), move || {
+        this.renderer.load_font(slice(data, len).to_vec())
+    })
 }
@@ -4,7 +4,7 @@
 //! time. The list provides scrolling functionality, so not every segment needs
 //! to be shown all the time.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::{component::OwnedComponent, splits_component_state::OwnedSplitsComponentState};
 use livesplit_core::{
     component::splits::Component as SplitsComponent, settings::ImageCache, GeneralLayoutSettings,
@@ -17,20 +17,20 @@ pub type OwnedSplitsComponent = Box<SplitsComponent>;
 /// Creates a new Splits Component.
 #[no_mangle]
 pub extern "C" fn SplitsComponent_new() -> OwnedSplitsComponent {
-    Box::new(SplitsComponent::new())
+    guard::abort_on_unwind(move || Box::new(SplitsComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn SplitsComponent_drop(this: OwnedSplitsComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
 /// layout.
 #[no_mangle]
 pub extern "C" fn SplitsComponent_into_generic(this: OwnedSplitsComponent) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -41,13 +41,33 @@ pub extern "C" fn SplitsComponent_state_as_json(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> Json {
-    output_vec(|o| {
-        this.state(image_cache, &timer.snapshot(), layout_settings)
-            .write_json(o)
-            .unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(image_cache, &timer.snapshot(), layout_settings)
+                .write_json(o)
+                .unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of SplitsComponent_state_as_json. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SplitsComponent_state_as_json_u16(
+    this: &mut SplitsComponent,
+    image_cache: &mut ImageCache,
+    timer: &Timer,
+    layout_settings: &GeneralLayoutSettings,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        SplitsComponent_state_as_json(this, image_cache, timer, layout_settings),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer and layout settings
 /// provided.
 #[no_mangle]
@@ -57,21 +77,27 @@ pub extern "C" fn SplitsComponent_state(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> OwnedSplitsComponentState {
-    Box::new(this.state(image_cache, &timer.snapshot(), layout_settings))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(image_cache, &timer.snapshot(), layout_settings))
+    })
 }
 
 /// Scrolls up the window of the segments that are shown. Doesn't move the
 /// scroll window if it reaches the top of the segments.
 #[no_mangle]
 pub extern "C" fn SplitsComponent_scroll_up(this: &mut SplitsComponent) {
-    this.scroll_up();
+    guard::call(guard::address(this), move || {
+        this.scroll_up();
+    })
 }
 
 /// Scrolls down the window of the segments that are shown. Doesn't move the
 /// scroll window if it reaches the bottom of the segments.
 #[no_mangle]
 pub extern "C" fn SplitsComponent_scroll_down(this: &mut SplitsComponent) {
-    this.scroll_down();
+    guard::call(guard::address(this), move || {
+        this.scroll_down();
+    })
 }
 
 /// The amount of segments to show in the list at any given time. If this is
@@ -80,7 +106,9 @@ pub extern "C" fn SplitsComponent_scroll_down(this: &mut SplitsComponent) {
 /// segments is shown. This window can scroll up or down.
 #[no_mangle]
 pub extern "C" fn SplitsComponent_set_visual_split_count(this: &mut SplitsComponent, count: usize) {
-    this.settings_mut().visual_split_count = count;
+    guard::call(guard::address(this), move || {
+        this.settings_mut().visual_split_count = count;
+    })
 }
 
 /// If there's more segments than segments that are shown, the window
@@ -93,7 +121,9 @@ pub extern "C" fn SplitsComponent_set_split_preview_count(
     this: &mut SplitsComponent,
     count: usize,
 ) {
-    this.settings_mut().split_preview_count = count;
+    guard::call(guard::address(this), move || {
+        this.settings_mut().split_preview_count = count;
+    })
 }
 
 /// If not every segment is shown in the scrolling window of segments, then
@@ -105,7 +135,9 @@ pub extern "C" fn SplitsComponent_set_always_show_last_split(
     this: &mut SplitsComponent,
     always_show_last_split: bool,
 ) {
-    this.settings_mut().always_show_last_split = always_show_last_split;
+    guard::call(guard::address(this), move || {
+        this.settings_mut().always_show_last_split = always_show_last_split;
+    })
 }
 
 /// If the last segment is to always be shown, this determines whether to
@@ -117,5 +149,7 @@ pub extern "C" fn SplitsComponent_set_separator_last_split(
     this: &mut SplitsComponent,
     separator_last_split: bool,
 ) {
-    this.settings_mut().separator_last_split = separator_last_split;
+    guard::call(guard::address(this), move || {
+        this.settings_mut().separator_last_split = separator_last_split;
+    })
 }
@@ -1,6 +1,6 @@
 //! The state object that describes a single segment's information to visualize.
 
-use super::{output_str, output_vec};
+use super::{guard, output_str, output_utf16, output_utf16_from, output_vec};
 use livesplit_core::component::splits::State as SplitsComponentState;
 use std::{io::Write, os::raw::c_char};
 
@@ -10,20 +10,20 @@ pub type OwnedSplitsComponentState = Box<SplitsComponentState>;
 /// drop
 #[no_mangle]
 pub extern "C" fn SplitsComponentState_drop(this: OwnedSplitsComponentState) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Describes whether a more pronounced separator should be shown in front of
 /// the last segment provided.
 #[no_mangle]
 pub extern "C" fn SplitsComponentState_final_separator_shown(this: &SplitsComponentState) -> bool {
-    this.show_final_separator
+    guard::call(guard::address(this), move || this.show_final_separator)
 }
 
 /// Returns the amount of segments to visualize.
 #[no_mangle]
 pub extern "C" fn SplitsComponentState_len(this: &SplitsComponentState) -> usize {
-    this.splits.len()
+    guard::call(guard::address(this), move || this.splits.len())
 }
 
 /// The icon of the segment. The associated image can be looked up in the image
@@ -34,7 +34,21 @@ pub extern "C" fn SplitsComponentState_icon(
     this: &SplitsComponentState,
     index: usize,
 ) -> *const c_char {
-    output_str(this.splits[index].icon.format_str(&mut [0; 64]))
+    guard::call(guard::address(this), move || {
+        output_str(this.splits[index].icon.format_str(&mut [0; 64]))
+    })
+}
+
+/// The UTF-16 variant of SplitsComponentState_icon. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SplitsComponentState_icon_u16(
+    this: &SplitsComponentState,
+    index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(SplitsComponentState_icon(this, index), buf, buf_len)
 }
 
 /// The name of the segment with the specified index. You may not provide an out
@@ -44,7 +58,25 @@ pub extern "C" fn SplitsComponentState_name(
     this: &SplitsComponentState,
     index: usize,
 ) -> *const c_char {
-    output_str(&this.splits[index].name)
+    guard::call(guard::address(this), move || {
+        output_str(&this.splits[index].name)
+    })
+}
+
+/// The name of the segment with the specified index, written into the buffer
+/// provided as UTF-16. Returns the length of the name in UTF-16 code units. If
+/// it doesn't fit, the buffer only contains its beginning. You may not provide
+/// an out of bounds index.
+#[no_mangle]
+pub unsafe extern "C" fn SplitsComponentState_name_u16(
+    this: &SplitsComponentState,
+    index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_utf16(buf, buf_len, &this.splits[index].name)
+    })
 }
 
 /// The amount of columns to visualize for the segment with the specified index.
@@ -56,7 +88,9 @@ pub extern "C" fn SplitsComponentState_columns_len(
     this: &SplitsComponentState,
     index: usize,
 ) -> usize {
-    this.splits[index].columns.len()
+    guard::call(guard::address(this), move || {
+        this.splits[index].columns.len()
+    })
 }
 
 /// The column's value to show for the split and column with the specified
@@ -68,7 +102,31 @@ pub extern "C" fn SplitsComponentState_column_value(
     index: usize,
     column_index: usize,
 ) -> *const c_char {
-    output_str(&this.splits[index].columns[column_index].value)
+    guard::call(guard::address(this), move || {
+        output_str(&this.splits[index].columns[column_index].value)
+    })
+}
+
+/// The column's value to show for the split and column with the specified
+/// index, written into the buffer provided as UTF-16. Returns the length of the
+/// value in UTF-16 code units. If it doesn't fit, the buffer only contains its
+/// beginning. The columns are specified from right to left. You may not provide
+/// an out of bounds index.
+#[no_mangle]
+pub unsafe extern "C" fn SplitsComponentState_column_value_u16(
+    this: &SplitsComponentState,
+    index: usize,
+    column_index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_utf16(
+            buf,
+            buf_len,
+            &this.splits[index].columns[column_index].value,
+        )
+    })
 }
 
 /// The semantic coloring information the column's value carries of the segment
@@ -80,16 +138,35 @@ pub extern "C" fn SplitsComponentState_column_semantic_color(
     index: usize,
     column_index: usize,
 ) -> *const c_char {
-    output_vec(|f| {
-        write!(
-            f,
-            "{:?}",
-            this.splits[index].columns[column_index].semantic_color
-        )
-        .unwrap()
+    guard::call(guard::address(this), move || {
+        output_vec(|f| {
+            write!(
+                f,
+                "{:?}",
+                this.splits[index].columns[column_index].semantic_color
+            )
+            .unwrap()
+        })
     })
 }
 
+/// The UTF-16 variant of SplitsComponentState_column_semantic_color. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SplitsComponentState_column_semantic_color_u16(
+    this: &SplitsComponentState,
+    index: usize,
+    column_index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        SplitsComponentState_column_semantic_color(this, index, column_index),
+        buf,
+        buf_len,
+    )
+}
+
 /// Describes if the segment with the specified index is the segment the active
 /// attempt is currently on.
 #[no_mangle]
@@ -97,14 +174,16 @@ pub extern "C" fn SplitsComponentState_is_current_split(
     this: &SplitsComponentState,
     index: usize,
 ) -> bool {
-    this.splits[index].is_current_split
+    guard::call(guard::address(this), move || {
+        this.splits[index].is_current_split
+    })
 }
 
 /// Describes if the columns have labels that are meant to be shown. If this is
 /// `false`, no labels are supposed to be visualized.
 #[no_mangle]
 pub extern "C" fn SplitsComponentState_has_column_labels(this: &SplitsComponentState) -> bool {
-    this.column_labels.is_some()
+    guard::call(guard::address(this), move || this.column_labels.is_some())
 }
 
 /// Returns the label of the column specified. The list is specified from right
@@ -114,9 +193,23 @@ pub extern "C" fn SplitsComponentState_column_label(
     this: &SplitsComponentState,
     index: usize,
 ) -> *const c_char {
-    output_str(if let Some(labels) = &this.column_labels {
-        &labels[index]
-    } else {
-        ""
+    guard::call(guard::address(this), move || {
+        output_str(if let Some(labels) = &this.column_labels {
+            &labels[index]
+        } else {
+            ""
+        })
     })
 }
+
+/// The UTF-16 variant of SplitsComponentState_column_label. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SplitsComponentState_column_label_u16(
+    this: &SplitsComponentState,
+    index: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(SplitsComponentState_column_label(this, index), buf, buf_len)
+}
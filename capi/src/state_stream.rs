@@ -0,0 +1,112 @@
+//! A State Stream turns the states pushed into it into a stream of updates
+//! encoded as JSON. The first update is a full snapshot of the state, while
+//! all the following updates only contain the changes as a JSON Merge Patch
+//! (RFC 7386). Every update is tagged with a generation that increases by one
+//! for each update, so consumers can detect whether they missed an update and
+//! need to request a new snapshot.
+
+use super::{guard, output_str, output_utf16_from, str, Json, Nullablec_char, Utf16Param};
+use crate::last_error::{self, ErrorCode};
+use livesplit_core::{layout::LayoutState, util::state_stream::StateStream};
+use std::ptr;
+
+/// type
+pub type OwnedStateStream = Box<StateStream>;
+
+/// Creates a new State Stream that doesn't have any state yet.
+#[no_mangle]
+pub extern "C" fn StateStream_new() -> OwnedStateStream {
+    guard::abort_on_unwind(move || Box::new(StateStream::new()))
+}
+
+/// drop
+#[no_mangle]
+pub extern "C" fn StateStream_drop(this: OwnedStateStream) {
+    guard::dispose(this);
+}
+
+/// Pushes the current state of the layout into the stream. Returns the update
+/// to send to the consumers as JSON. <NULL> is returned if the state didn't
+/// change.
+#[no_mangle]
+pub extern "C" fn StateStream_push_layout_state(
+    this: &mut StateStream,
+    state: &LayoutState,
+) -> *const Nullablec_char {
+    guard::call(guard::address(this), move || match this.push(state) {
+        Some(update) => output_str(update.to_json()),
+        None => ptr::null(),
+    })
+}
+
+/// The UTF-16 variant of StateStream_push_layout_state. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn StateStream_push_layout_state_u16(
+    this: &mut StateStream,
+    state: &LayoutState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(StateStream_push_layout_state(this, state), buf, buf_len)
+}
+
+/// Pushes any other state that is encoded as JSON into the stream. Returns the
+/// update to send to the consumers as JSON. <NULL> is returned if the state
+/// didn't change or if it couldn't be parsed.
+#[no_mangle]
+pub unsafe extern "C" fn StateStream_push_json(
+    this: &mut StateStream,
+    state: Json,
+) -> *const Nullablec_char {
+    guard::call(guard::address(this), move || {
+        let update = last_error::track(
+            ErrorCode::Parse,
+            serde_json::from_str::<serde_json::Value>(str(state)),
+        )
+        .and_then(|state| this.push_value(state));
+        match update {
+            Some(update) => output_str(update.to_json()),
+            None => ptr::null(),
+        }
+    })
+}
+
+/// The UTF-16 variant of StateStream_push_json. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn StateStream_push_json_u16(
+    this: &mut StateStream,
+    state: *const u16,
+    state_len: usize,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        StateStream_push_json(this, Utf16Param::new(state, state_len).as_ptr()),
+        buf,
+        buf_len,
+    )
+}
+
+/// Creates a snapshot of the most recent state as JSON. This is meant to be
+/// sent to consumers that connect later on or that missed an update. <NULL> is
+/// returned if no state has been pushed yet.
+#[no_mangle]
+pub extern "C" fn StateStream_snapshot(this: &StateStream) -> *const Nullablec_char {
+    guard::call(guard::address(this), move || match this.snapshot() {
+        Some(update) => output_str(update.to_json()),
+        None => ptr::null(),
+    })
+}
+
+/// The UTF-16 variant of StateStream_snapshot. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn StateStream_snapshot_u16(
+    this: &StateStream,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(StateStream_snapshot(this), buf, buf_len)
+}
@@ -5,7 +5,10 @@
 //! Cleaner will point out all of occurrences of this and allows you to delete
 //! them individually if any of them seem wrong.
 
-use crate::potential_clean_up::{NullableOwnedPotentialCleanUp, OwnedPotentialCleanUp};
+use crate::{
+    guard,
+    potential_clean_up::{NullableOwnedPotentialCleanUp, OwnedPotentialCleanUp},
+};
 use livesplit_core::run::editor::cleaning::SumOfBestCleaner;
 
 /// type
@@ -14,7 +17,7 @@ pub type OwnedSumOfBestCleaner = Box<SumOfBestCleaner<'static>>;
 /// drop
 #[no_mangle]
 pub extern "C" fn SumOfBestCleaner_drop(this: OwnedSumOfBestCleaner) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Returns the next potential clean up. If there are no more potential
@@ -23,7 +26,11 @@ pub extern "C" fn SumOfBestCleaner_drop(this: OwnedSumOfBestCleaner) {
 pub extern "C" fn SumOfBestCleaner_next_potential_clean_up(
     this: &'static mut SumOfBestCleaner<'static>,
 ) -> NullableOwnedPotentialCleanUp {
-    this.next_potential_clean_up().map(Box::new)
+    guard::call(guard::address(this), move || {
+        // The block moves the reference out of the closure, which allows the
+        // result to borrow from it for as long as the original reference.
+        { this }.next_potential_clean_up().map(Box::new)
+    })
 }
 
 /// Applies a clean up to the Run.
@@ -32,5 +39,8 @@ pub extern "C" fn SumOfBestCleaner_apply(
     this: &'static mut SumOfBestCleaner<'static>,
     clean_up: OwnedPotentialCleanUp,
 ) {
-    this.apply((*clean_up).into());
+    guard::consume(&*clean_up);
+    guard::call(guard::address(this), move || {
+        this.apply((*clean_up).into());
+    })
 }
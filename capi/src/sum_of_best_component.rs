@@ -6,7 +6,7 @@
 //! sum of their best segment times. The name is therefore a bit misleading, but
 //! sticks around for historical reasons.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::component::OwnedComponent;
 use crate::key_value_component_state::OwnedKeyValueComponentState;
 use livesplit_core::component::sum_of_best::Component as SumOfBestComponent;
@@ -18,20 +18,20 @@ pub type OwnedSumOfBestComponent = Box<SumOfBestComponent>;
 /// Creates a new Sum of Best Segments Component.
 #[no_mangle]
 pub extern "C" fn SumOfBestComponent_new() -> OwnedSumOfBestComponent {
-    Box::new(SumOfBestComponent::new())
+    guard::abort_on_unwind(move || Box::new(SumOfBestComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn SumOfBestComponent_drop(this: OwnedSumOfBestComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
 /// layout.
 #[no_mangle]
 pub extern "C" fn SumOfBestComponent_into_generic(this: OwnedSumOfBestComponent) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -40,16 +40,30 @@ pub extern "C" fn SumOfBestComponent_state_as_json(
     this: &SumOfBestComponent,
     timer: &Timer,
 ) -> Json {
-    output_vec(|o| {
-        this.state(timer).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(timer).write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of SumOfBestComponent_state_as_json. See get_buf_as_utf16
+/// for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn SumOfBestComponent_state_as_json_u16(
+    this: &SumOfBestComponent,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(SumOfBestComponent_state_as_json(this, timer), buf, buf_len)
+}
+
 /// Calculates the component's state based on the timer provided.
 #[no_mangle]
 pub extern "C" fn SumOfBestComponent_state(
     this: &SumOfBestComponent,
     timer: &Timer,
 ) -> OwnedKeyValueComponentState {
-    Box::new(this.state(timer))
+    guard::call_or_abort(guard::address(this), move || Box::new(this.state(timer)))
 }
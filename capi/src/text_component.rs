@@ -2,7 +2,7 @@
 //! single centered text, or split up into a left and right text, which is
 //! suitable for a situation where you have a label and a value.
 
-use super::{output_vec, str, Json};
+use super::{guard, output_utf16_from, output_vec, str, Json, Utf16Param};
 use crate::component::OwnedComponent;
 use crate::text_component_state::OwnedTextComponentState;
 use livesplit_core::{component::text::Component as TextComponent, Timer};
@@ -14,49 +14,102 @@ pub type OwnedTextComponent = Box<TextComponent>;
 /// Creates a new Text Component.
 #[no_mangle]
 pub extern "C" fn TextComponent_new() -> OwnedTextComponent {
-    Box::new(TextComponent::new())
+    guard::abort_on_unwind(move || Box::new(TextComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn TextComponent_drop(this: OwnedTextComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
 /// layout.
 #[no_mangle]
 pub extern "C" fn TextComponent_into_generic(this: OwnedTextComponent) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
 #[no_mangle]
 pub extern "C" fn TextComponent_state_as_json(this: &TextComponent, timer: &Timer) -> Json {
-    output_vec(|o| {
-        this.state(timer).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(timer).write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of TextComponent_state_as_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TextComponent_state_as_json_u16(
+    this: &TextComponent,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(TextComponent_state_as_json(this, timer), buf, buf_len)
+}
+
 /// Sets the centered text. If the current mode is split, it is switched to
 /// centered mode.
 #[no_mangle]
 pub unsafe extern "C" fn TextComponent_set_center(this: &mut TextComponent, text: *const c_char) {
-    this.settings_mut().text.set_center(str(text));
+    guard::call(guard::address(this), move || {
+        this.settings_mut().text.set_center(str(text));
+    })
+}
+
+/// The UTF-16 variant of TextComponent_set_center. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TextComponent_set_center_u16(
+    this: &mut TextComponent,
+    text: *const u16,
+    text_len: usize,
+) {
+    TextComponent_set_center(this, Utf16Param::new(text, text_len).as_ptr())
 }
 
 /// Sets the left text. If the current mode is centered, it is switched to
 /// split mode, with the right text being empty.
 #[no_mangle]
 pub unsafe extern "C" fn TextComponent_set_left(this: &mut TextComponent, text: *const c_char) {
-    this.settings_mut().text.set_left(str(text));
+    guard::call(guard::address(this), move || {
+        this.settings_mut().text.set_left(str(text));
+    })
+}
+
+/// The UTF-16 variant of TextComponent_set_left. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TextComponent_set_left_u16(
+    this: &mut TextComponent,
+    text: *const u16,
+    text_len: usize,
+) {
+    TextComponent_set_left(this, Utf16Param::new(text, text_len).as_ptr())
 }
 
 /// Sets the right text. If the current mode is centered, it is switched to
 /// split mode, with the left text being empty.
 #[no_mangle]
 pub unsafe extern "C" fn TextComponent_set_right(this: &mut TextComponent, text: *const c_char) {
-    this.settings_mut().text.set_right(str(text));
+    guard::call(guard::address(this), move || {
+        this.settings_mut().text.set_right(str(text));
+    })
+}
+
+/// The UTF-16 variant of TextComponent_set_right. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TextComponent_set_right_u16(
+    this: &mut TextComponent,
+    text: *const u16,
+    text_len: usize,
+) {
+    TextComponent_set_right(this, Utf16Param::new(text, text_len).as_ptr())
 }
 
 /// Calculates the component's state.
@@ -65,5 +118,5 @@ pub extern "C" fn TextComponent_state(
     this: &TextComponent,
     timer: &Timer,
 ) -> OwnedTextComponentState {
-    Box::new(this.state(timer))
+    guard::call_or_abort(guard::address(this), move || Box::new(this.state(timer)))
 }
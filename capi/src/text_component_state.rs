@@ -1,6 +1,6 @@
 //! The state object describes the information to visualize for this component.
 
-use super::output_str;
+use super::{guard, output_str, output_utf16_from};
 use livesplit_core::component::text::{State as TextComponentState, TextState};
 use std::os::raw::c_char;
 
@@ -10,44 +10,85 @@ pub type OwnedTextComponentState = Box<TextComponentState>;
 /// drop
 #[no_mangle]
 pub extern "C" fn TextComponentState_drop(this: OwnedTextComponentState) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Accesses the left part of the text. If the text isn't split up, an empty
 /// string is returned instead.
 #[no_mangle]
 pub extern "C" fn TextComponentState_left(this: &TextComponentState) -> *const c_char {
-    if let TextState::Split(left, _) = &this.text {
-        output_str(left)
-    } else {
-        output_str("")
-    }
+    guard::call(guard::address(this), move || {
+        if let TextState::Split(left, _) = &this.text {
+            output_str(left)
+        } else {
+            output_str("")
+        }
+    })
+}
+
+/// The UTF-16 variant of TextComponentState_left. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TextComponentState_left_u16(
+    this: &TextComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(TextComponentState_left(this), buf, buf_len)
 }
 
 /// Accesses the right part of the text. If the text isn't split up, an empty
 /// string is returned instead.
 #[no_mangle]
 pub extern "C" fn TextComponentState_right(this: &TextComponentState) -> *const c_char {
-    if let TextState::Split(_, right) = &this.text {
-        output_str(right)
-    } else {
-        output_str("")
-    }
+    guard::call(guard::address(this), move || {
+        if let TextState::Split(_, right) = &this.text {
+            output_str(right)
+        } else {
+            output_str("")
+        }
+    })
+}
+
+/// The UTF-16 variant of TextComponentState_right. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TextComponentState_right_u16(
+    this: &TextComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(TextComponentState_right(this), buf, buf_len)
 }
 
 /// Accesses the centered text. If the text isn't centered, an empty string is
 /// returned instead.
 #[no_mangle]
 pub extern "C" fn TextComponentState_center(this: &TextComponentState) -> *const c_char {
-    if let TextState::Center(center) = &this.text {
-        output_str(center)
-    } else {
-        output_str("")
-    }
+    guard::call(guard::address(this), move || {
+        if let TextState::Center(center) = &this.text {
+            output_str(center)
+        } else {
+            output_str("")
+        }
+    })
+}
+
+/// The UTF-16 variant of TextComponentState_center. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TextComponentState_center_u16(
+    this: &TextComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(TextComponentState_center(this), buf, buf_len)
 }
 
 /// Returns whether the text is split up into a left and right part.
 #[no_mangle]
 pub extern "C" fn TextComponentState_is_split(this: &TextComponentState) -> bool {
-    matches!(this.text, TextState::Split(_, _))
+    guard::call(guard::address(this), move || {
+        matches!(this.text, TextState::Split(_, _))
+    })
 }
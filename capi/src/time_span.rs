@@ -1,6 +1,7 @@
 //! A Time Span represents a certain span of time.
 
-use super::str;
+use super::{guard, str, Utf16Param};
+use crate::last_error::{self, ErrorCode};
 use livesplit_core::TimeSpan;
 use std::os::raw::c_char;
 
@@ -14,45 +15,61 @@ pub type NullableOwnedTimeSpan = Option<OwnedTimeSpan>;
 /// Clones the Time Span.
 #[no_mangle]
 pub extern "C" fn TimeSpan_clone(this: &TimeSpan) -> OwnedTimeSpan {
-    Box::new(*this)
+    guard::call_or_abort(guard::address(this), move || Box::new(*this))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn TimeSpan_drop(this: OwnedTimeSpan) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Creates a new Time Span from a given amount of seconds.
 #[no_mangle]
 pub extern "C" fn TimeSpan_from_seconds(seconds: f64) -> OwnedTimeSpan {
-    Box::new(TimeSpan::from_seconds(seconds))
+    guard::abort_on_unwind(move || Box::new(TimeSpan::from_seconds(seconds)))
 }
 
 /// Parses a Time Span from a string. Returns <NULL> if the time can't be
 /// parsed.
 #[no_mangle]
 pub unsafe extern "C" fn TimeSpan_parse(text: *const c_char) -> NullableOwnedTimeSpan {
-    str(text).parse().ok().map(Box::new)
+    guard::call(0, move || {
+        last_error::track(ErrorCode::Parse, str(text).parse::<TimeSpan>()).map(Box::new)
+    })
+}
+
+/// The UTF-16 variant of TimeSpan_parse. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TimeSpan_parse_u16(
+    text: *const u16,
+    text_len: usize,
+) -> NullableOwnedTimeSpan {
+    TimeSpan_parse(Utf16Param::new(text, text_len).as_ptr())
 }
 
 /// Returns the total amount of seconds (including decimals) this Time Span
 /// represents.
 #[no_mangle]
 pub extern "C" fn TimeSpan_total_seconds(this: &TimeSpan) -> f64 {
-    this.total_seconds()
+    guard::call(guard::address(this), move || this.total_seconds())
 }
 
 /// Returns the total amount of whole seconds (excluding decimals) this Time
 /// Span represents.
 #[no_mangle]
 pub extern "C" fn TimeSpan_whole_seconds(this: &TimeSpan) -> i64 {
-    this.to_seconds_and_subsec_nanoseconds().0
+    guard::call(guard::address(this), move || {
+        this.to_seconds_and_subsec_nanoseconds().0
+    })
 }
 
 /// Returns the number of nanoseconds past the last full second that makes up
 /// the Time Span.
 #[no_mangle]
 pub extern "C" fn TimeSpan_subsec_nanoseconds(this: &TimeSpan) -> i32 {
-    this.to_seconds_and_subsec_nanoseconds().1
+    guard::call(guard::address(this), move || {
+        this.to_seconds_and_subsec_nanoseconds().1
+    })
 }
@@ -1,13 +1,19 @@
 //! A Timer provides all the capabilities necessary for doing speedrun attempts.
 
-use super::{output_str, output_time, output_time_span, output_vec, str};
+use super::{
+    guard, output_str, output_time, output_time_span, output_utf16_from, output_vec, str,
+    Utf16Param,
+};
 use crate::{
+    last_error::{self, ErrorCode},
     run::{NullableOwnedRun, OwnedRun},
     shared_timer::OwnedSharedTimer,
+    time_span::NullableTimeSpan,
 };
 use livesplit_core::{
     event::{Error, Event},
     run::saver::{self, livesplit::IoWrite},
+    timing::{formatter::Accuracy, TimerCreationError},
     Run, Time, TimeSpan, Timer, TimerPhase, TimingMethod,
 };
 use std::{os::raw::c_char, ptr};
@@ -16,6 +22,8 @@ use std::{os::raw::c_char, ptr};
 pub type OwnedTimer = Box<Timer>;
 /// type
 pub type NullableOwnedTimer = Option<OwnedTimer>;
+/// type
+pub type EventListener = unsafe extern "C" fn(data: usize, event: u32);
 
 /// Creates a new Timer based on a Run object storing all the information
 /// about the splits. The Run object needs to have at least one segment, so
@@ -23,14 +31,19 @@ pub type NullableOwnedTimer = Option<OwnedTimer>;
 /// segments is provided, the Timer creation fails and <NULL> is returned.
 #[no_mangle]
 pub extern "C" fn Timer_new(run: OwnedRun) -> NullableOwnedTimer {
-    Timer::new(*run).ok().map(Box::new)
+    guard::consume(&*run);
+    guard::call(0, move || {
+        last_error::track(ErrorCode::EmptyInput, Timer::new(*run)).map(Box::new)
+    })
 }
 
 /// Consumes the Timer and creates a Shared Timer that can be shared across
 /// multiple threads with multiple owners.
 #[no_mangle]
 pub extern "C" fn Timer_into_shared(this: OwnedTimer) -> OwnedSharedTimer {
-    Box::new((*this).into_shared())
+    guard::call_or_abort(guard::address(&*this), move || {
+        Box::new((*this).into_shared())
+    })
 }
 
 /// Takes out the Run from the Timer and resets the current attempt if there
@@ -39,13 +52,15 @@ pub extern "C" fn Timer_into_shared(this: OwnedTimer) -> OwnedSharedTimer {
 /// current attempt's information is discarded.
 #[no_mangle]
 pub extern "C" fn Timer_into_run(this: OwnedTimer, update_splits: bool) -> OwnedRun {
-    Box::new((*this).into_run(update_splits))
+    guard::call_or_abort(guard::address(&*this), move || {
+        Box::new((*this).into_run(update_splits))
+    })
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn Timer_drop(this: OwnedTimer) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Replaces the Run object used by the Timer with the Run object provided. If
@@ -61,15 +76,19 @@ pub unsafe extern "C" fn Timer_replace_run(
     run: &mut Run,
     update_splits: bool,
 ) -> bool {
-    // This working correctly relies on panic = "abort",
-    // as a panic would leave the run in an uninitialized state.
-    let result = this.replace_run(ptr::read(run), update_splits);
-    let was_successful = result.is_ok();
-    let result = match result {
-        Ok(r) | Err(r) => r,
-    };
-    ptr::write(run, result);
-    was_successful
+    guard::call(guard::address(this), move || {
+        // A panic would leave the run in an uninitialized state, so this
+        // can't unwind.
+        guard::abort_on_unwind(|| {
+            let result = this.replace_run(ptr::read(run), update_splits);
+            let was_successful = result.is_ok();
+            let result = match result {
+                Ok(r) | Err(r) => r,
+            };
+            ptr::write(run, result);
+            was_successful
+        })
+    })
 }
 
 /// Sets the Run object used by the Timer with the Run object provided. If the
@@ -78,7 +97,17 @@ pub unsafe extern "C" fn Timer_replace_run(
 /// by the Timer is disposed by this method and <NULL> is returned.
 #[no_mangle]
 pub extern "C" fn Timer_set_run(this: &mut Timer, run: OwnedRun) -> NullableOwnedRun {
-    this.set_run(*run).err().map(Box::new)
+    guard::consume(&*run);
+    guard::call(guard::address(this), move || match this.set_run(*run) {
+        Ok(()) => {
+            last_error::clear();
+            None
+        }
+        Err(run) => {
+            last_error::set(ErrorCode::EmptyInput, TimerCreationError::EmptyRun);
+            Some(Box::new(run))
+        }
+    })
 }
 
 /// Accesses the index of the split the attempt is currently on. If there's
@@ -88,10 +117,12 @@ pub extern "C" fn Timer_set_run(this: &mut Timer, run: OwnedRun) -> NullableOwne
 /// this value for indexing.
 #[no_mangle]
 pub extern "C" fn Timer_current_split_index(this: &Timer) -> isize {
-    this.current_split_index().map_or(-1, |i| i as isize)
+    guard::call(guard::address(this), move || {
+        this.current_split_index().map_or(-1, |i| i as isize)
+    })
 }
 
-fn convert(result: Result<Event, Error>) -> i32 {
+pub(crate) fn convert(result: Result<Event, Error>) -> i32 {
     match result {
         Ok(a) => a as i32,
         Err(e) => -1 - (e as i32),
@@ -102,28 +133,28 @@ fn convert(result: Result<Event, Error>) -> i32 {
 /// case, nothing happens.
 #[no_mangle]
 pub extern "C" fn Timer_start(this: &mut Timer) -> i32 {
-    convert(this.start())
+    guard::call(guard::address(this), move || convert(this.start()))
 }
 
 /// If an attempt is in progress, stores the current time as the time of the
 /// current split. The attempt ends if the last split time is stored.
 #[no_mangle]
 pub extern "C" fn Timer_split(this: &mut Timer) -> i32 {
-    convert(this.split())
+    guard::call(guard::address(this), move || convert(this.split()))
 }
 
 /// Starts a new attempt or stores the current time as the time of the
 /// current split. The attempt ends if the last split time is stored.
 #[no_mangle]
 pub extern "C" fn Timer_split_or_start(this: &mut Timer) -> i32 {
-    convert(this.split_or_start())
+    guard::call(guard::address(this), move || convert(this.split_or_start()))
 }
 
 /// Skips the current split if an attempt is in progress and the
 /// current split is not the last split.
 #[no_mangle]
 pub extern "C" fn Timer_skip_split(this: &mut Timer) -> i32 {
-    convert(this.skip_split())
+    guard::call(guard::address(this), move || convert(this.skip_split()))
 }
 
 /// Removes the split time from the last split if an attempt is in progress
@@ -131,7 +162,7 @@ pub extern "C" fn Timer_skip_split(this: &mut Timer) -> i32 {
 /// `Running` if it previously was `Ended`.
 #[no_mangle]
 pub extern "C" fn Timer_undo_split(this: &mut Timer) -> i32 {
-    convert(this.undo_split())
+    guard::call(guard::address(this), move || convert(this.undo_split()))
 }
 
 /// Checks whether the current attempt has new best segment times in any of the
@@ -140,7 +171,9 @@ pub extern "C" fn Timer_undo_split(this: &mut Timer) -> i32 {
 /// when resetting.
 #[no_mangle]
 pub extern "C" fn Timer_current_attempt_has_new_best_times(this: &Timer) -> bool {
-    this.current_attempt_has_new_best_times()
+    guard::call(guard::address(this), move || {
+        this.current_attempt_has_new_best_times()
+    })
 }
 
 /// Resets the current attempt if there is one in progress. If the splits
@@ -149,7 +182,9 @@ pub extern "C" fn Timer_current_attempt_has_new_best_times(this: &Timer) -> bool
 /// discarded.
 #[no_mangle]
 pub extern "C" fn Timer_reset(this: &mut Timer, update_splits: bool) -> i32 {
-    convert(this.reset(update_splits))
+    guard::call(guard::address(this), move || {
+        convert(this.reset(update_splits))
+    })
 }
 
 /// Resets the current attempt if there is one in progress. The splits are
@@ -157,32 +192,36 @@ pub extern "C" fn Timer_reset(this: &mut Timer, update_splits: bool) -> i32 {
 /// the new Personal Best.
 #[no_mangle]
 pub extern "C" fn Timer_reset_and_set_attempt_as_pb(this: &mut Timer) -> i32 {
-    convert(this.reset_and_set_attempt_as_pb())
+    guard::call(guard::address(this), move || {
+        convert(this.reset_and_set_attempt_as_pb())
+    })
 }
 
 /// Pauses an active attempt that is not paused.
 #[no_mangle]
 pub extern "C" fn Timer_pause(this: &mut Timer) -> i32 {
-    convert(this.pause())
+    guard::call(guard::address(this), move || convert(this.pause()))
 }
 
 /// Resumes an attempt that is paused.
 #[no_mangle]
 pub extern "C" fn Timer_resume(this: &mut Timer) -> i32 {
-    convert(this.resume())
+    guard::call(guard::address(this), move || convert(this.resume()))
 }
 
 /// Toggles an active attempt between `Paused` and `Running`.
 #[no_mangle]
 pub extern "C" fn Timer_toggle_pause(this: &mut Timer) -> i32 {
-    convert(this.toggle_pause())
+    guard::call(guard::address(this), move || convert(this.toggle_pause()))
 }
 
 /// Toggles an active attempt between `Paused` and `Running` or starts an
 /// attempt if there's none in progress.
 #[no_mangle]
 pub extern "C" fn Timer_toggle_pause_or_start(this: &mut Timer) -> i32 {
-    convert(this.toggle_pause_or_start())
+    guard::call(guard::address(this), move || {
+        convert(this.toggle_pause_or_start())
+    })
 }
 
 /// Removes all the pause times from the current time. If the current
@@ -197,32 +236,51 @@ pub extern "C" fn Timer_toggle_pause_or_start(this: &mut Timer) -> i32 {
 /// may not be what actually happened during the run.
 #[no_mangle]
 pub extern "C" fn Timer_undo_all_pauses(this: &mut Timer) -> i32 {
-    convert(this.undo_all_pauses())
+    guard::call(guard::address(this), move || {
+        convert(this.undo_all_pauses())
+    })
 }
 
 /// Returns the currently selected Timing Method.
 #[no_mangle]
 pub extern "C" fn Timer_current_timing_method(this: &Timer) -> TimingMethod {
-    this.current_timing_method()
+    guard::call(guard::address(this), move || this.current_timing_method())
 }
 
 /// Sets the current Timing Method to the Timing Method provided.
 #[no_mangle]
 pub extern "C" fn Timer_set_current_timing_method(this: &mut Timer, method: TimingMethod) {
-    this.set_current_timing_method(method);
+    guard::call(guard::address(this), move || {
+        this.set_current_timing_method(method);
+    })
 }
 
 /// Toggles between the Real Time and Game Time timing methods.
 #[no_mangle]
 pub extern "C" fn Timer_toggle_timing_method(this: &mut Timer) {
-    this.toggle_timing_method();
+    guard::call(guard::address(this), move || {
+        this.toggle_timing_method();
+    })
 }
 
 /// Returns the current comparison that is being compared against. This may
 /// be a custom comparison or one of the Comparison Generators.
 #[no_mangle]
 pub extern "C" fn Timer_current_comparison(this: &Timer) -> *const c_char {
-    output_str(this.current_comparison())
+    guard::call(guard::address(this), move || {
+        output_str(this.current_comparison())
+    })
+}
+
+/// The UTF-16 variant of Timer_current_comparison. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Timer_current_comparison_u16(
+    this: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Timer_current_comparison(this), buf, buf_len)
 }
 
 /// Tries to set the current comparison to the comparison specified. If the
@@ -232,60 +290,87 @@ pub unsafe extern "C" fn Timer_set_current_comparison(
     this: &mut Timer,
     comparison: *const c_char,
 ) -> i32 {
-    convert(this.set_current_comparison(str(comparison)))
+    guard::call(guard::address(this), move || {
+        convert(this.set_current_comparison(str(comparison)))
+    })
+}
+
+/// The UTF-16 variant of Timer_set_current_comparison. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Timer_set_current_comparison_u16(
+    this: &mut Timer,
+    comparison: *const u16,
+    comparison_len: usize,
+) -> i32 {
+    Timer_set_current_comparison(this, Utf16Param::new(comparison, comparison_len).as_ptr())
 }
 
 /// Switches the current comparison to the next comparison in the list.
 #[no_mangle]
 pub extern "C" fn Timer_switch_to_next_comparison(this: &mut Timer) {
-    this.switch_to_next_comparison();
+    guard::call(guard::address(this), move || {
+        this.switch_to_next_comparison();
+    })
 }
 
 /// Switches the current comparison to the previous comparison in the list.
 #[no_mangle]
 pub extern "C" fn Timer_switch_to_previous_comparison(this: &mut Timer) {
-    this.switch_to_previous_comparison();
+    guard::call(guard::address(this), move || {
+        this.switch_to_previous_comparison();
+    })
 }
 
 /// Returns whether Game Time is currently initialized. Game Time
 /// automatically gets uninitialized for each new attempt.
 #[no_mangle]
 pub extern "C" fn Timer_is_game_time_initialized(this: &Timer) -> bool {
-    this.is_game_time_initialized()
+    guard::call(guard::address(this), move || {
+        this.is_game_time_initialized()
+    })
 }
 
 /// Initializes Game Time for the current attempt. Game Time automatically
 /// gets uninitialized for each new attempt.
 #[no_mangle]
 pub extern "C" fn Timer_initialize_game_time(this: &mut Timer) -> i32 {
-    convert(this.initialize_game_time())
+    guard::call(guard::address(this), move || {
+        convert(this.initialize_game_time())
+    })
 }
 
 /// Deinitializes Game Time for the current attempt.
 #[no_mangle]
 pub extern "C" fn Timer_deinitialize_game_time(this: &mut Timer) {
-    this.deinitialize_game_time();
+    guard::call(guard::address(this), move || {
+        this.deinitialize_game_time();
+    })
 }
 
 /// Returns whether the Game Timer is currently paused. If the Game Timer is
 /// not paused, it automatically increments similar to Real Time.
 #[no_mangle]
 pub extern "C" fn Timer_is_game_time_paused(this: &Timer) -> bool {
-    this.is_game_time_paused()
+    guard::call(guard::address(this), move || this.is_game_time_paused())
 }
 
 /// Pauses the Game Timer such that it doesn't automatically increment
 /// similar to Real Time.
 #[no_mangle]
 pub extern "C" fn Timer_pause_game_time(this: &mut Timer) -> i32 {
-    convert(this.pause_game_time())
+    guard::call(guard::address(this), move || {
+        convert(this.pause_game_time())
+    })
 }
 
 /// Resumes the Game Timer such that it automatically increments similar to
 /// Real Time, starting from the Game Time it was paused at.
 #[no_mangle]
 pub extern "C" fn Timer_resume_game_time(this: &mut Timer) -> i32 {
-    convert(this.resume_game_time())
+    guard::call(guard::address(this), move || {
+        convert(this.resume_game_time())
+    })
 }
 
 /// Sets the Game Time to the time specified. This also works if the Game
@@ -294,13 +379,17 @@ pub extern "C" fn Timer_resume_game_time(this: &mut Timer) -> i32 {
 /// the Game Timer never shows any time that is not coming from the game.
 #[no_mangle]
 pub extern "C" fn Timer_set_game_time(this: &mut Timer, time: &TimeSpan) -> i32 {
-    convert(this.set_game_time(*time))
+    guard::call(guard::address(this), move || {
+        convert(this.set_game_time(*time))
+    })
 }
 
 /// Accesses the loading times. Loading times are defined as Game Time - Real Time.
 #[no_mangle]
 pub extern "C" fn Timer_loading_times(this: &Timer) -> *const TimeSpan {
-    output_time_span(this.loading_times())
+    guard::call(guard::address(this), move || {
+        output_time_span(this.loading_times())
+    })
 }
 
 /// Instead of setting the Game Time directly, this method can be used to
@@ -308,7 +397,9 @@ pub extern "C" fn Timer_loading_times(this: &Timer) -> *const TimeSpan {
 /// is then automatically determined by Real Time - Loading Times.
 #[no_mangle]
 pub extern "C" fn Timer_set_loading_times(this: &mut Timer, time: &TimeSpan) -> i32 {
-    convert(this.set_loading_times(*time))
+    guard::call(guard::address(this), move || {
+        convert(this.set_loading_times(*time))
+    })
 }
 
 /// Sets the value of a custom variable with the name specified. If the variable
@@ -320,51 +411,216 @@ pub unsafe extern "C" fn Timer_set_custom_variable(
     name: *const c_char,
     value: *const c_char,
 ) {
-    this.set_custom_variable(str(name), str(value));
+    guard::call(guard::address(this), move || {
+        this.set_custom_variable(str(name), str(value));
+    })
+}
+
+/// The UTF-16 variant of Timer_set_custom_variable. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Timer_set_custom_variable_u16(
+    this: &mut Timer,
+    name: *const u16,
+    name_len: usize,
+    value: *const u16,
+    value_len: usize,
+) {
+    Timer_set_custom_variable(
+        this,
+        Utf16Param::new(name, name_len).as_ptr(),
+        Utf16Param::new(value, value_len).as_ptr(),
+    )
 }
 
 /// Returns the current Timer Phase.
 #[no_mangle]
 pub extern "C" fn Timer_current_phase(this: &Timer) -> TimerPhase {
-    this.current_phase()
+    guard::call(guard::address(this), move || this.current_phase())
 }
 
 /// Accesses the Run in use by the Timer.
 #[no_mangle]
 pub extern "C" fn Timer_get_run(this: &Timer) -> &Run {
-    this.run()
+    guard::call_or_abort(guard::address(this), move || this.run())
 }
 
 /// Saves the Run in use by the Timer as a LiveSplit splits file (*.lss).
 #[no_mangle]
 pub extern "C" fn Timer_save_as_lss(this: &Timer) -> *const c_char {
-    output_vec(|o| {
-        saver::livesplit::save_timer(this, IoWrite(o)).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            saver::livesplit::save_timer(this, IoWrite(o)).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of Timer_save_as_lss. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Timer_save_as_lss_u16(
+    this: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(Timer_save_as_lss(this), buf, buf_len)
+}
+
 /// Marks the Run as unmodified, so that it is known that all the changes
 /// have been saved.
 #[no_mangle]
 pub extern "C" fn Timer_mark_as_unmodified(this: &mut Timer) {
-    this.mark_as_unmodified();
+    guard::call(guard::address(this), move || {
+        this.mark_as_unmodified();
+    })
 }
 
 /// Prints out debug information representing the whole state of the Timer. This
 /// is being written to stdout.
 #[no_mangle]
 pub extern "C" fn Timer_print_debug(#[allow(unused)] this: &Timer) {
-    // WebAssembly doesn't have stdout unless we use WASI or Emscripten.
-    #[cfg(not(all(
-        target_family = "wasm",
-        not(any(target_os = "wasi", target_os = "emscripten"))
-    )))]
-    println!("{this:#?}");
+    guard::call(0, move || {
+        // WebAssembly doesn't have stdout unless we use WASI or Emscripten.
+        #[cfg(not(all(
+            target_family = "wasm",
+            not(any(target_os = "wasi", target_os = "emscripten"))
+        )))]
+        println!("{this:#?}");
+    })
+}
+
+/// Returns how long it takes until the times shown with the accuracy provided
+/// visually change next. This allows frontends to only render when something
+/// actually changes, instead of rendering continuously. The accuracy is one of
+/// "Seconds", "Tenths", "Hundredths" or "Milliseconds". While the timer is not
+/// running, the times only change when the timer is interacted with, so <NULL>
+/// is returned. <NULL> is also returned if the accuracy is unknown.
+#[no_mangle]
+pub unsafe extern "C" fn Timer_time_until_next_change(
+    this: &Timer,
+    accuracy: *const c_char,
+) -> *const NullableTimeSpan {
+    guard::call(guard::address(this), move || {
+        let accuracy = match str(accuracy) {
+            "Seconds" => Accuracy::Seconds,
+            "Tenths" => Accuracy::Tenths,
+            "Hundredths" => Accuracy::Hundredths,
+            "Milliseconds" => Accuracy::Milliseconds,
+            _ => return ptr::null(),
+        };
+        if let Some(time_span) = this.snapshot().time_until_next_change(accuracy) {
+            output_time_span(time_span)
+        } else {
+            ptr::null()
+        }
+    })
+}
+
+/// The UTF-16 variant of Timer_time_until_next_change. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Timer_time_until_next_change_u16(
+    this: &Timer,
+    accuracy: *const u16,
+    accuracy_len: usize,
+) -> *const NullableTimeSpan {
+    Timer_time_until_next_change(this, Utf16Param::new(accuracy, accuracy_len).as_ptr())
 }
 
 /// Returns the current time of the Timer. The Game Time is <NULL> if the Game
 /// Time has not been initialized.
 #[no_mangle]
 pub extern "C" fn Timer_current_time(this: &Timer) -> *const Time {
-    output_time(this.snapshot().current_time())
+    guard::call(guard::address(this), move || {
+        output_time(this.snapshot().current_time())
+    })
+}
+
+/// Registers a listener that gets called with every event the Timer emits,
+/// such as a split happening or the Timer getting paused. The event is passed
+/// to the listener as its event code, just like the Timer functions return it.
+/// The data provided is passed to the listener as well, so it can find its
+/// state again. The listener is called while the Timer is being modified, so
+/// it must not access the Timer itself. The ID returned can be used to remove
+/// the listener again.
+#[no_mangle]
+pub extern "C" fn Timer_add_event_listener(
+    this: &mut Timer,
+    listener: EventListener,
+    data: usize,
+) -> u64 {
+    guard::call(guard::address(this), move || {
+        add_event_listener(this, listener, data)
+    })
+}
+
+/// Removes the event listener with the ID provided. Returns <FALSE> if there
+/// is no such listener.
+#[no_mangle]
+pub extern "C" fn Timer_remove_event_listener(this: &mut Timer, id: u64) -> bool {
+    guard::call(guard::address(this), move || {
+        this.remove_event_listener(id.into())
+    })
+}
+
+pub(crate) fn add_event_listener(this: &mut Timer, listener: EventListener, data: usize) -> u64 {
+    this.add_event_listener(move |event| unsafe { listener(data, event as u32) })
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        run::{Run_new, Run_push_segment},
+        segment::Segment_new,
+    };
+    use std::cell::RefCell;
+
+    unsafe extern "C" fn record(data: usize, event: u32) {
+        (*(data as *const RefCell<Vec<u32>>))
+            .borrow_mut()
+            .push(event);
+    }
+
+    fn timer() -> OwnedTimer {
+        let mut run = Run_new();
+        for name in [c"A", c"B"] {
+            Run_push_segment(&mut run, unsafe { Segment_new(name.as_ptr()) });
+        }
+        Timer_new(run).unwrap()
+    }
+
+    #[test]
+    fn event_listeners() {
+        let mut timer = timer();
+        let events = RefCell::new(Vec::<u32>::new());
+        let data = &events as *const RefCell<Vec<u32>> as usize;
+
+        let first = Timer_add_event_listener(&mut timer, record, data);
+        let second = Timer_add_event_listener(&mut timer, record, data);
+        assert_ne!(first, second);
+
+        Timer_start(&mut timer);
+        assert_eq!(*events.borrow(), [Event::Started as u32; 2]);
+
+        assert!(Timer_remove_event_listener(&mut timer, first));
+        assert!(!Timer_remove_event_listener(&mut timer, first));
+
+        Timer_split(&mut timer);
+        assert_eq!(
+            *events.borrow(),
+            [
+                Event::Started as u32,
+                Event::Started as u32,
+                Event::Splitted as u32,
+            ],
+        );
+
+        assert!(Timer_remove_event_listener(&mut timer, second));
+        Timer_reset(&mut timer, true);
+        assert_eq!(events.borrow().len(), 3);
+
+        Timer_drop(timer);
+    }
 }
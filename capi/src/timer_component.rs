@@ -2,7 +2,7 @@
 //! attempt as a digital clock. The color of the time shown is based on a how
 //! well the current attempt is doing compared to the chosen comparison.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::component::OwnedComponent;
 use crate::timer_component_state::OwnedTimerComponentState;
 use livesplit_core::component::timer::Component as TimerComponent;
@@ -14,20 +14,20 @@ pub type OwnedTimerComponent = Box<TimerComponent>;
 /// Creates a new Timer Component.
 #[no_mangle]
 pub extern "C" fn TimerComponent_new() -> OwnedTimerComponent {
-    Box::new(TimerComponent::new())
+    guard::abort_on_unwind(move || Box::new(TimerComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn TimerComponent_drop(this: OwnedTimerComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
 /// layout.
 #[no_mangle]
 pub extern "C" fn TimerComponent_into_generic(this: OwnedTimerComponent) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -37,13 +37,32 @@ pub extern "C" fn TimerComponent_state_as_json(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> Json {
-    output_vec(|o| {
-        this.state(&timer.snapshot(), layout_settings)
-            .write_json(o)
-            .unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(&timer.snapshot(), layout_settings)
+                .write_json(o)
+                .unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of TimerComponent_state_as_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TimerComponent_state_as_json_u16(
+    this: &TimerComponent,
+    timer: &Timer,
+    layout_settings: &GeneralLayoutSettings,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        TimerComponent_state_as_json(this, timer, layout_settings),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer and the layout
 /// settings provided.
 #[no_mangle]
@@ -52,5 +71,7 @@ pub extern "C" fn TimerComponent_state(
     timer: &Timer,
     layout_settings: &GeneralLayoutSettings,
 ) -> OwnedTimerComponentState {
-    Box::new(this.state(&timer.snapshot(), layout_settings))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(&timer.snapshot(), layout_settings))
+    })
 }
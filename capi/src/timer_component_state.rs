@@ -1,6 +1,6 @@
 //! The state object describes the information to visualize for this component.
 
-use super::{output_str, output_vec};
+use super::{guard, output_str, output_utf16, output_utf16_from, output_vec};
 use livesplit_core::component::timer::State as TimerComponentState;
 use std::io::Write;
 use std::os::raw::c_char;
@@ -11,23 +11,65 @@ pub type OwnedTimerComponentState = Box<TimerComponentState>;
 /// drop
 #[no_mangle]
 pub extern "C" fn TimerComponentState_drop(this: OwnedTimerComponentState) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// The time shown by the component without the fractional part.
 #[no_mangle]
 pub extern "C" fn TimerComponentState_time(this: &TimerComponentState) -> *const c_char {
-    output_str(&this.time)
+    guard::call(guard::address(this), move || output_str(&this.time))
+}
+
+/// The time shown by the component without the fractional part, written into
+/// the buffer provided as UTF-16. Returns the length of the time in UTF-16 code
+/// units. If it doesn't fit, the buffer only contains its beginning.
+#[no_mangle]
+pub unsafe extern "C" fn TimerComponentState_time_u16(
+    this: &TimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_utf16(buf, buf_len, &this.time)
+    })
 }
 
 /// The fractional part of the time shown (including the dot).
 #[no_mangle]
 pub extern "C" fn TimerComponentState_fraction(this: &TimerComponentState) -> *const c_char {
-    output_str(&this.fraction)
+    guard::call(guard::address(this), move || output_str(&this.fraction))
+}
+
+/// The fractional part of the time shown (including the dot), written into the
+/// buffer provided as UTF-16. Returns the length of the fractional part in
+/// UTF-16 code units. If it doesn't fit, the buffer only contains its
+/// beginning.
+#[no_mangle]
+pub unsafe extern "C" fn TimerComponentState_fraction_u16(
+    this: &TimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_utf16(buf, buf_len, &this.fraction)
+    })
 }
 
 /// The semantic coloring information the time carries.
 #[no_mangle]
 pub extern "C" fn TimerComponentState_semantic_color(this: &TimerComponentState) -> *const c_char {
-    output_vec(|f| write!(f, "{:?}", this.semantic_color).unwrap())
+    guard::call(guard::address(this), move || {
+        output_vec(|f| write!(f, "{:?}", this.semantic_color).unwrap())
+    })
+}
+
+/// The UTF-16 variant of TimerComponentState_semantic_color. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TimerComponentState_semantic_color_u16(
+    this: &TimerComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(TimerComponentState_semantic_color(this), buf, buf_len)
 }
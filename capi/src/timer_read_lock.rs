@@ -3,6 +3,7 @@
 
 use std::sync::RwLockReadGuard;
 
+use crate::guard;
 use livesplit_core::Timer;
 
 /// type
@@ -13,11 +14,11 @@ pub type OwnedTimerReadLock = Box<TimerReadLock>;
 /// drop
 #[no_mangle]
 pub extern "C" fn TimerReadLock_drop(this: OwnedTimerReadLock) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Accesses the timer.
 #[no_mangle]
 pub extern "C" fn TimerReadLock_timer(this: &TimerReadLock) -> &Timer {
-    this
+    guard::call_or_abort(guard::address(this), move || this)
 }
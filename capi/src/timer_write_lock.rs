@@ -3,6 +3,7 @@
 
 use std::sync::RwLockWriteGuard;
 
+use crate::guard;
 use livesplit_core::Timer;
 
 /// type
@@ -13,11 +14,15 @@ pub type OwnedTimerWriteLock = Box<TimerWriteLock>;
 /// drop
 #[no_mangle]
 pub extern "C" fn TimerWriteLock_drop(this: OwnedTimerWriteLock) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Accesses the timer.
 #[no_mangle]
 pub extern "C" fn TimerWriteLock_timer(this: &mut TimerWriteLock) -> &mut Timer {
-    &mut *this
+    guard::call_or_abort(guard::address(this), move || {
+        // The block moves the reference out of the closure, which allows the
+        // result to borrow from it for as long as the original reference.
+        &mut **{ this }
+    })
 }
@@ -2,7 +2,7 @@
 //! category that is being run. Additionally, the game icon, the attempt count,
 //! and the total number of successfully finished runs can be shown.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::{component::OwnedComponent, title_component_state::OwnedTitleComponentState};
 use livesplit_core::{component::title::Component as TitleComponent, settings::ImageCache, Timer};
 
@@ -12,20 +12,20 @@ pub type OwnedTitleComponent = Box<TitleComponent>;
 /// Creates a new Title Component.
 #[no_mangle]
 pub extern "C" fn TitleComponent_new() -> OwnedTitleComponent {
-    Box::new(TitleComponent::new())
+    guard::abort_on_unwind(move || Box::new(TitleComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn TitleComponent_drop(this: OwnedTitleComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
 /// layout.
 #[no_mangle]
 pub extern "C" fn TitleComponent_into_generic(this: OwnedTitleComponent) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -35,11 +35,30 @@ pub extern "C" fn TitleComponent_state_as_json(
     image_cache: &mut ImageCache,
     timer: &Timer,
 ) -> Json {
-    output_vec(|o| {
-        this.state(image_cache, timer).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(image_cache, timer).write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of TitleComponent_state_as_json. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TitleComponent_state_as_json_u16(
+    this: &mut TitleComponent,
+    image_cache: &mut ImageCache,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        TitleComponent_state_as_json(this, image_cache, timer),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer provided.
 #[no_mangle]
 pub extern "C" fn TitleComponent_state(
@@ -47,5 +66,7 @@ pub extern "C" fn TitleComponent_state(
     image_cache: &mut ImageCache,
     timer: &Timer,
 ) -> OwnedTitleComponentState {
-    Box::new(this.state(image_cache, timer))
+    guard::call_or_abort(guard::address(this), move || {
+        Box::new(this.state(image_cache, timer))
+    })
 }
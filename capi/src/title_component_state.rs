@@ -1,6 +1,6 @@
 //! The state object describes the information to visualize for this component.
 
-use super::{output_str, Nullablec_char};
+use super::{guard, output_str, output_utf16, output_utf16_from, Nullablec_char};
 use livesplit_core::component::title::State as TitleComponentState;
 use std::{os::raw::c_char, ptr};
 
@@ -10,7 +10,7 @@ pub type OwnedTitleComponentState = Box<TitleComponentState>;
 /// drop
 #[no_mangle]
 pub extern "C" fn TitleComponentState_drop(this: OwnedTitleComponentState) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// The game icon to show. The associated image can be looked up in the image
@@ -18,15 +18,44 @@ pub extern "C" fn TitleComponentState_drop(this: OwnedTitleComponentState) {
 /// icon.
 #[no_mangle]
 pub extern "C" fn TitleComponentState_icon(this: &TitleComponentState) -> *const c_char {
-    output_str(this.icon.format_str(&mut [0; 64]))
+    guard::call(guard::address(this), move || {
+        output_str(this.icon.format_str(&mut [0; 64]))
+    })
+}
+
+/// The UTF-16 variant of TitleComponentState_icon. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TitleComponentState_icon_u16(
+    this: &TitleComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(TitleComponentState_icon(this), buf, buf_len)
 }
 
 /// The first title line to show. This is either the game's name, or a
 /// combination of the game's name and the category.
 #[no_mangle]
 pub extern "C" fn TitleComponentState_line1(this: &TitleComponentState) -> *const c_char {
-    // FIXME: Add API for querying the abbreviations.
-    output_str(this.line1.last().unwrap())
+    guard::call(guard::address(this), move || {
+        // FIXME: Add API for querying the abbreviations.
+        output_str(this.line1.last().unwrap())
+    })
+}
+
+/// The first title line to show, written into the buffer provided as UTF-16.
+/// Returns the length of the line in UTF-16 code units. If it doesn't fit, the
+/// buffer only contains its beginning.
+#[no_mangle]
+pub unsafe extern "C" fn TitleComponentState_line1_u16(
+    this: &TitleComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    guard::call(guard::address(this), move || {
+        output_utf16(buf, buf_len, this.line1.last().unwrap())
+    })
 }
 
 /// By default the category name is shown on the second line. Based on the
@@ -34,38 +63,55 @@ pub extern "C" fn TitleComponentState_line1(this: &TitleComponentState) -> *cons
 /// the game name. In that case <NULL> is returned instead.
 #[no_mangle]
 pub extern "C" fn TitleComponentState_line2(this: &TitleComponentState) -> *const Nullablec_char {
-    // FIXME: Add API for querying the abbreviations.
-    this.line2.last().map_or_else(ptr::null, output_str)
+    guard::call(guard::address(this), move || {
+        // FIXME: Add API for querying the abbreviations.
+        this.line2.last().map_or_else(ptr::null, output_str)
+    })
+}
+
+/// The UTF-16 variant of TitleComponentState_line2. See get_buf_as_utf16 for
+/// how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TitleComponentState_line2_u16(
+    this: &TitleComponentState,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(TitleComponentState_line2(this), buf, buf_len)
 }
 
 /// Specifies whether the title should centered or aligned to the left
 /// instead.
 #[no_mangle]
 pub extern "C" fn TitleComponentState_is_centered(this: &TitleComponentState) -> bool {
-    this.is_centered
+    guard::call(guard::address(this), move || this.is_centered)
 }
 
 /// Returns whether the amount of successfully finished attempts is supposed to
 /// be shown.
 #[no_mangle]
 pub extern "C" fn TitleComponentState_shows_finished_runs(this: &TitleComponentState) -> bool {
-    this.finished_runs.is_some()
+    guard::call(guard::address(this), move || this.finished_runs.is_some())
 }
 
 /// Returns the amount of successfully finished attempts.
 #[no_mangle]
 pub extern "C" fn TitleComponentState_finished_runs(this: &TitleComponentState) -> u32 {
-    this.finished_runs.unwrap_or_default()
+    guard::call(guard::address(this), move || {
+        this.finished_runs.unwrap_or_default()
+    })
 }
 
 /// Returns whether the amount of total attempts is supposed to be shown.
 #[no_mangle]
 pub extern "C" fn TitleComponentState_shows_attempts(this: &TitleComponentState) -> bool {
-    this.attempts.is_some()
+    guard::call(guard::address(this), move || this.attempts.is_some())
 }
 
 /// Returns the amount of total attempts.
 #[no_mangle]
 pub extern "C" fn TitleComponentState_attempts(this: &TitleComponentState) -> u32 {
-    this.attempts.unwrap_or_default()
+    guard::call(guard::address(this), move || {
+        this.attempts.unwrap_or_default()
+    })
 }
@@ -1,7 +1,7 @@
 //! The Total Playtime Component is a component that shows the total amount of
 //! time that the current category has been played for.
 
-use super::{output_vec, Json};
+use super::{guard, output_utf16_from, output_vec, Json};
 use crate::component::OwnedComponent;
 use crate::key_value_component_state::OwnedKeyValueComponentState;
 use livesplit_core::component::total_playtime::Component as TotalPlaytimeComponent;
@@ -13,13 +13,13 @@ pub type OwnedTotalPlaytimeComponent = Box<TotalPlaytimeComponent>;
 /// Creates a new Total Playtime Component.
 #[no_mangle]
 pub extern "C" fn TotalPlaytimeComponent_new() -> OwnedTotalPlaytimeComponent {
-    Box::new(TotalPlaytimeComponent::new())
+    guard::abort_on_unwind(move || Box::new(TotalPlaytimeComponent::new()))
 }
 
 /// drop
 #[no_mangle]
 pub extern "C" fn TotalPlaytimeComponent_drop(this: OwnedTotalPlaytimeComponent) {
-    drop(this);
+    guard::dispose(this);
 }
 
 /// Converts the component into a generic component suitable for using with a
@@ -28,7 +28,7 @@ pub extern "C" fn TotalPlaytimeComponent_drop(this: OwnedTotalPlaytimeComponent)
 pub extern "C" fn TotalPlaytimeComponent_into_generic(
     this: OwnedTotalPlaytimeComponent,
 ) -> OwnedComponent {
-    Box::new((*this).into())
+    guard::call_or_abort(guard::address(&*this), move || Box::new((*this).into()))
 }
 
 /// Encodes the component's state information as JSON.
@@ -37,16 +37,34 @@ pub extern "C" fn TotalPlaytimeComponent_state_as_json(
     this: &mut TotalPlaytimeComponent,
     timer: &Timer,
 ) -> Json {
-    output_vec(|o| {
-        this.state(timer).write_json(o).unwrap();
+    guard::call(guard::address(this), move || {
+        output_vec(|o| {
+            this.state(timer).write_json(o).unwrap();
+        })
     })
 }
 
+/// The UTF-16 variant of TotalPlaytimeComponent_state_as_json. See
+/// get_buf_as_utf16 for how the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn TotalPlaytimeComponent_state_as_json_u16(
+    this: &mut TotalPlaytimeComponent,
+    timer: &Timer,
+    buf: *mut u16,
+    buf_len: usize,
+) -> usize {
+    output_utf16_from(
+        TotalPlaytimeComponent_state_as_json(this, timer),
+        buf,
+        buf_len,
+    )
+}
+
 /// Calculates the component's state based on the timer provided.
 #[no_mangle]
 pub extern "C" fn TotalPlaytimeComponent_state(
     this: &mut TotalPlaytimeComponent,
     timer: &Timer,
 ) -> OwnedKeyValueComponentState {
-    Box::new(this.state(timer))
+    guard::call_or_abort(guard::address(this), move || Box::new(this.state(timer)))
 }
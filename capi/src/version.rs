@@ -0,0 +1,83 @@
+//! The version module allows querying which version of livesplit-core is used
+//! and which optional features it got compiled with. This allows frontends to
+//! adapt at runtime instead of relying on symbols that may be missing.
+
+use super::{guard, output_str, output_utf16_from, output_vec, str, Json, Utf16Param};
+use std::os::raw::c_char;
+
+/// The ABI level of the C API. It gets increased whenever a function gets
+/// removed or changes its signature or semantics in an incompatible way.
+const ABI_LEVEL: u32 = 1;
+
+const FEATURES: &[(&str, bool)] = &[
+    ("auto-splitting", cfg!(feature = "auto-splitting")),
+    ("image-shrinking", cfg!(feature = "image-shrinking")),
+    ("software-rendering", cfg!(feature = "software-rendering")),
+    ("wasm-web", cfg!(feature = "wasm-web")),
+    ("web-rendering", cfg!(feature = "web-rendering")),
+];
+
+/// Returns the version of livesplit-core, such as `0.13.0`.
+#[no_mangle]
+pub extern "C" fn Version_crate() -> *const c_char {
+    guard::call(0, move || output_str(livesplit_core::VERSION))
+}
+
+/// The UTF-16 variant of Version_crate. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Version_crate_u16(buf: *mut u16, buf_len: usize) -> usize {
+    output_utf16_from(Version_crate(), buf, buf_len)
+}
+
+/// Returns the ABI level of the C API. Functions are only ever removed or
+/// changed in an incompatible way when the ABI level gets increased, so
+/// frontends can compare it with the level they were written for.
+#[no_mangle]
+pub extern "C" fn Version_abi_level() -> u32 {
+    guard::call(0, move || ABI_LEVEL)
+}
+
+/// Returns <TRUE> if the optional feature with the name provided got compiled
+/// in. The features are `auto-splitting`, `image-shrinking`,
+/// `software-rendering`, `wasm-web` and `web-rendering`. Unknown features are
+/// never compiled in.
+#[no_mangle]
+pub unsafe extern "C" fn Version_has_feature(name: *const c_char) -> bool {
+    guard::call(0, move || {
+        let name = str(name);
+        FEATURES
+            .iter()
+            .any(|&(feature, enabled)| enabled && feature == name)
+    })
+}
+
+/// The UTF-16 variant of Version_has_feature. See get_buf_as_utf16 for how the
+/// strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Version_has_feature_u16(name: *const u16, name_len: usize) -> bool {
+    Version_has_feature(Utf16Param::new(name, name_len).as_ptr())
+}
+
+/// Encodes the names of all the optional features that got compiled in as a
+/// JSON array.
+#[no_mangle]
+pub extern "C" fn Version_features_as_json() -> Json {
+    guard::call(0, move || {
+        output_vec(|o| {
+            let features = FEATURES
+                .iter()
+                .filter(|&&(_, enabled)| enabled)
+                .map(|&(feature, _)| feature)
+                .collect::<Vec<_>>();
+            serde_json::to_writer(o, &features).unwrap();
+        })
+    })
+}
+
+/// The UTF-16 variant of Version_features_as_json. See get_buf_as_utf16 for how
+/// the strings are passed.
+#[no_mangle]
+pub unsafe extern "C" fn Version_features_as_json_u16(buf: *mut u16, buf_len: usize) -> usize {
+    output_utf16_from(Version_features_as_json(), buf, buf_len)
+}
@@ -0,0 +1,216 @@
+//! Provides access to the memory of other processes, independent of the
+//! WebAssembly runtime. This allows custom tooling, such as memory viewers or
+//! auto splitters that are not written as WebAssembly modules, to share the
+//! same implementation.
+
+use std::io;
+
+use bytemuck::Pod;
+use snafu::{ResultExt, Snafu};
+
+use crate::{
+    process::{self, Address},
+    runtime::ProcessList,
+    Process,
+};
+
+/// An error that can occur when accessing the memory of a process.
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum Error {
+    /// Failed to attach to the process.
+    Attach {
+        /// The underlying error.
+        source: process::OpenError,
+    },
+    /// Failed to find the module.
+    Module {
+        /// The underlying error.
+        source: process::ModuleError,
+    },
+    /// Failed to read the memory of the process.
+    Read {
+        /// The address that was read from.
+        address: Address,
+        /// The underlying error.
+        source: io::Error,
+    },
+}
+
+/// The size of the pointers of a process.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PointerSize {
+    /// The pointers are 4 bytes large.
+    Bit32,
+    /// The pointers are 8 bytes large.
+    Bit64,
+}
+
+/// Allows reading the memory of a process that got attached to by its name.
+pub trait ProcessHook: Sized {
+    /// The error that can occur when accessing the process.
+    type Error;
+
+    /// Attaches to the process with the name provided. If there are multiple
+    /// processes with the name, the one started most recently is chosen.
+    fn attach(name: &str) -> Result<Self, Self::Error>;
+
+    /// Returns whether the process is still running.
+    fn is_open(&mut self) -> bool;
+
+    /// Returns the address that the module with the name provided is loaded
+    /// at.
+    fn module_base(&mut self, module: &str) -> Result<Address, Self::Error>;
+
+    /// Reads the memory at the address into the buffer.
+    fn read_into(&self, address: Address, buf: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Reads a value of the type provided at the address.
+    fn read<T: Pod>(&self, address: Address) -> Result<T, Self::Error> {
+        let mut value = T::zeroed();
+        self.read_into(address, bytemuck::bytes_of_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads a pointer of the size provided at the address.
+    fn read_pointer(
+        &self,
+        address: Address,
+        pointer_size: PointerSize,
+    ) -> Result<Address, Self::Error> {
+        Ok(match pointer_size {
+            PointerSize::Bit32 => self.read::<u32>(address)? as Address,
+            PointerSize::Bit64 => self.read::<u64>(address)?,
+        })
+    }
+
+    /// Resolves a pointer path. The first offset is added to the base
+    /// address. For every other offset, a pointer is read at the current
+    /// address and the offset is added to it.
+    fn resolve_pointer_path(
+        &self,
+        base: Address,
+        offsets: &[i64],
+        pointer_size: PointerSize,
+    ) -> Result<Address, Self::Error> {
+        let Some((&first, rest)) = offsets.split_first() else {
+            return Ok(base);
+        };
+        let mut address = base.wrapping_add_signed(first);
+        for &offset in rest {
+            address = self
+                .read_pointer(address, pointer_size)?
+                .wrapping_add_signed(offset);
+        }
+        Ok(address)
+    }
+
+    /// Reads a value of the type provided at the end of the pointer path.
+    fn read_pointer_path<T: Pod>(
+        &self,
+        base: Address,
+        offsets: &[i64],
+        pointer_size: PointerSize,
+    ) -> Result<T, Self::Error> {
+        self.read(self.resolve_pointer_path(base, offsets, pointer_size)?)
+    }
+}
+
+/// A process of the operating system that got attached to. This is supported
+/// on Windows, Linux and macOS.
+pub struct NativeProcess {
+    process: Process,
+    process_list: ProcessList,
+}
+
+impl std::fmt::Debug for NativeProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.process.fmt(f)
+    }
+}
+
+impl NativeProcess {
+    /// Accesses the underlying process.
+    pub const fn process(&self) -> &Process {
+        &self.process
+    }
+}
+
+impl ProcessHook for NativeProcess {
+    type Error = Error;
+
+    fn attach(name: &str) -> Result<Self, Self::Error> {
+        let mut process_list = ProcessList::new();
+        let process = Process::with_name(name, &mut process_list).context(Attach)?;
+        Ok(Self {
+            process,
+            process_list,
+        })
+    }
+
+    fn is_open(&mut self) -> bool {
+        self.process.is_open(&mut self.process_list)
+    }
+
+    fn module_base(&mut self, module: &str) -> Result<Address, Self::Error> {
+        self.process.module_address(module).context(Module)
+    }
+
+    fn read_into(&self, address: Address, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.process
+            .read_mem(address, buf)
+            .context(Read { address })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A process whose memory starts at address 0.
+    struct Memory(Vec<u8>);
+
+    impl ProcessHook for Memory {
+        type Error = ();
+
+        fn attach(_: &str) -> Result<Self, Self::Error> {
+            Err(())
+        }
+
+        fn is_open(&mut self) -> bool {
+            true
+        }
+
+        fn module_base(&mut self, _: &str) -> Result<Address, Self::Error> {
+            Ok(0)
+        }
+
+        fn read_into(&self, address: Address, buf: &mut [u8]) -> Result<(), Self::Error> {
+            let start = usize::try_from(address).map_err(drop)?;
+            let memory = self.0.get(start..start + buf.len()).ok_or(())?;
+            buf.copy_from_slice(memory);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn pointer_path() {
+        let mut memory = vec![0; 32];
+        memory[4..8].copy_from_slice(&16u32.to_ne_bytes());
+        memory[20..24].copy_from_slice(&1234i32.to_ne_bytes());
+        let memory = Memory(memory);
+
+        assert_eq!(
+            memory.resolve_pointer_path(2, &[2, 8], PointerSize::Bit32),
+            Ok(24),
+        );
+        assert_eq!(
+            memory.read_pointer_path::<i32>(0, &[4, 4], PointerSize::Bit32),
+            Ok(1234),
+        );
+        assert_eq!(
+            memory.read_pointer_path::<i32>(0, &[30, 0], PointerSize::Bit32),
+            Err(()),
+        );
+    }
+}
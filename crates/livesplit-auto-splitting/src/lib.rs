@@ -297,6 +297,22 @@
 //!         option_description_ptr: *const u8,
 //!         option_description_len: usize,
 //!     ) -> bool;
+//!     /// Adds a new number setting that the user can modify. The key is used to
+//!     /// store the setting in the settings map and needs to be unique across all
+//!     /// types of settings. The description is what's shown to the user. The
+//!     /// user can only choose values between the minimum and the maximum. The
+//!     /// pointers need to point to valid UTF-8 encoded text with the respective
+//!     /// given length. Returns the value that the user chose, or the default
+//!     /// value if they didn't choose one.
+//!     pub fn user_settings_add_number(
+//!         key_ptr: *const u8,
+//!         key_len: usize,
+//!         description_ptr: *const u8,
+//!         description_len: usize,
+//!         default_value: f64,
+//!         min: f64,
+//!         max: f64,
+//!     ) -> f64;
 //!     /// Adds a new file select setting that the user can modify. This allows the
 //!     /// user to choose a file from the file system. The key is used to store the
 //!     /// path of the file in the settings map and needs to be unique across all
@@ -563,12 +579,14 @@
 )]
 #![forbid(clippy::incompatible_msrv)]
 
+pub mod hook;
 mod process;
 mod runtime;
 pub mod settings;
 mod timer;
 pub mod wasi_path;
 
+pub use hook::{NativeProcess, ProcessHook};
 pub use process::Process;
 pub use runtime::{
     AutoSplitter, CompiledAutoSplitter, Config, CreationError, ExecutionGuard, InterruptHandle,
@@ -581,9 +599,7 @@ const _: () = {
     const fn assert_send_sync<T: Send + Sync>() {}
     assert_send_sync::<Config>();
     assert_send_sync::<Process>();
+    assert_send_sync::<NativeProcess>();
     assert_send_sync::<Runtime>();
     assert_send_sync::<CompiledAutoSplitter>();
-    const fn with_timer<T: Send + Sync + Timer>() {
-        assert_send_sync::<AutoSplitter<T>>();
-    }
 };
@@ -124,6 +124,43 @@ pub fn bind<T: Timer>(linker: &mut Linker<Context<T>>) -> Result<(), CreationErr
             source,
             name: "user_settings_add_choice_option",
         })?
+        .func_wrap("env", "user_settings_add_number", {
+            |mut caller: Caller<'_, Context<T>>,
+             key_ptr: u32,
+             key_len: u32,
+             description_ptr: u32,
+             description_len: u32,
+             default_value: f64,
+             min: f64,
+             max: f64| {
+                if min.is_nan() || max.is_nan() || min > max {
+                    bail!("The range of the number setting is invalid.");
+                }
+                let (memory, context) = memory_and_context(&mut caller);
+                let key = Arc::<str>::from(get_str(memory, key_ptr, key_len)?);
+                let description = get_str(memory, description_ptr, description_len)?.into();
+                let value_in_map = match context.shared_data.get_settings_map().get(&key) {
+                    Some(settings::Value::F64(v)) => *v,
+                    Some(settings::Value::I64(v)) => *v as f64,
+                    _ => default_value,
+                };
+                Arc::make_mut(&mut context.settings_widgets).push(settings::Widget {
+                    key,
+                    description,
+                    tooltip: None,
+                    kind: settings::WidgetKind::Number {
+                        default_value,
+                        min,
+                        max,
+                    },
+                });
+                Ok(value_in_map.clamp(min, max))
+            }
+        })
+        .map_err(|source| CreationError::LinkFunction {
+            source,
+            name: "user_settings_add_number",
+        })?
         .func_wrap("env", "user_settings_add_file_select", {
             |mut caller: Caller<'_, Context<T>>,
              key_ptr: u32,
@@ -114,7 +114,7 @@ pub struct ProcessList {
 }
 
 impl ProcessList {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             system: System::new_with_specifics(
                 RefreshKind::new().with_processes(multiple_processes()),
@@ -43,6 +43,16 @@ pub enum WidgetKind {
         /// The available options for the setting.
         options: Arc<Vec<ChoiceOption>>,
     },
+    /// A number setting. This could be shown as a spin box or a slider.
+    Number {
+        /// The default value of the setting, if it's not available in the
+        /// settings [`Map`](super::Map) yet.
+        default_value: f64,
+        /// The smallest value that the user can choose.
+        min: f64,
+        /// The largest value that the user can choose.
+        max: f64,
+    },
     /// A file selection. This could be a button that opens a File Dialog.
     FileSelect {
         /// The filters that are used to filter the files that can be selected.
@@ -27,6 +27,11 @@ impl Timer for DummyTimer {
     fn log_runtime(&mut self, _message: fmt::Arguments<'_>, _log_level: LogLevel) {}
 }
 
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<AutoSplitter<DummyTimer>>();
+};
+
 #[track_caller]
 fn compile(crate_name: &str) -> anyhow::Result<AutoSplitter<DummyTimer>> {
     let mut path = PathBuf::from("tests");
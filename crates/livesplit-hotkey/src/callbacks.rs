@@ -0,0 +1,26 @@
+use alloc::boxed::Box;
+
+/// The callbacks that are registered for a single hotkey.
+// The stubbed out implementation never calls any of the callbacks.
+#[allow(dead_code)]
+pub(crate) struct Callbacks {
+    /// Called when the hotkey is pressed.
+    pub press: Box<dyn FnMut() + Send + 'static>,
+    /// Called when the key of the hotkey is released again, if the hotkey was
+    /// registered with a release callback.
+    pub release: Option<Box<dyn FnMut() + Send + 'static>>,
+    /// Whether the key events of the hotkey should be consumed, so they don't
+    /// get passed on to the application in focus. Only some of the platforms
+    /// support this.
+    pub consume: bool,
+}
+
+#[allow(dead_code)]
+impl Callbacks {
+    /// Calls the release callback if there is one.
+    pub fn release(&mut self) {
+        if let Some(release) = &mut self.release {
+            release();
+        }
+    }
+}
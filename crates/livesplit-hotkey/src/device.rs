@@ -0,0 +1,412 @@
+//! Foot pedals and other DIY split buttons that don't show up as keyboards can
+//! be used as an additional source of hotkeys. These are either raw HID
+//! devices, where each bit of the input reports is considered a button, or
+//! serial devices (such as microcontrollers connected via USB) that send a line
+//! of text whenever a button is pressed. The devices are identified by the
+//! vendor and product id of their USB connection. This requires the `devices`
+//! feature to actually open any devices, the triggers themselves are always
+//! available so they can be stored in a configuration.
+
+use alloc::string::String;
+#[cfg(feature = "devices")]
+use alloc::vec::Vec;
+use core::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// A button press on a HID or serial device that can be used to trigger an
+/// action.
+#[derive(Eq, PartialEq, Hash, Clone)]
+pub enum DeviceTrigger {
+    /// A button of a raw HID device is pressed. The buttons are numbered by
+    /// the bits of the device's input reports, starting at the least
+    /// significant bit of the first byte.
+    Hid {
+        /// The USB vendor id of the device.
+        vendor_id: u16,
+        /// The USB product id of the device.
+        product_id: u16,
+        /// The number of the button.
+        button: u16,
+    },
+    /// A serial device sends the given line of text. Leading and trailing
+    /// whitespace is ignored.
+    Serial {
+        /// The USB vendor id of the device.
+        vendor_id: u16,
+        /// The USB product id of the device.
+        product_id: u16,
+        /// The line of text the device sends.
+        message: String,
+    },
+}
+
+/// Returns the buttons that are pressed in the new input report, but weren't
+/// pressed in the previous one.
+#[cfg(feature = "devices")]
+pub(crate) fn newly_pressed(report: &[u8], previous: &[u8]) -> Vec<u16> {
+    let mut buttons = Vec::new();
+    for (index, &byte) in report.iter().enumerate() {
+        let pressed = byte & !previous.get(index).copied().unwrap_or_default();
+        for bit in 0..8 {
+            if pressed & (1 << bit) != 0 {
+                buttons.push(8 * index as u16 + bit);
+            }
+        }
+    }
+    buttons
+}
+
+impl fmt::Debug for DeviceTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for DeviceTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hid {
+                vendor_id,
+                product_id,
+                button,
+            } => write!(f, "HID {vendor_id:04x}:{product_id:04x} {button}"),
+            Self::Serial {
+                vendor_id,
+                product_id,
+                message,
+            } => write!(f, "Serial {vendor_id:04x}:{product_id:04x} {message}"),
+        }
+    }
+}
+
+impl FromStr for DeviceTrigger {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, rest) = s.trim().split_once(' ').ok_or(())?;
+        let (ids, rest) = rest.trim_start().split_once(' ').ok_or(())?;
+        let (vendor_id, product_id) = ids.split_once(':').ok_or(())?;
+        let vendor_id = u16::from_str_radix(vendor_id, 16).map_err(drop)?;
+        let product_id = u16::from_str_radix(product_id, 16).map_err(drop)?;
+        let rest = rest.trim();
+        match kind {
+            "HID" => Ok(Self::Hid {
+                vendor_id,
+                product_id,
+                button: rest.parse().map_err(drop)?,
+            }),
+            "Serial" if !rest.is_empty() => Ok(Self::Serial {
+                vendor_id,
+                product_id,
+                message: rest.into(),
+            }),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Serialize for DeviceTrigger {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceTrigger {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(DeviceTriggerVisitor)
+    }
+}
+
+struct DeviceTriggerVisitor;
+
+impl<'de> serde::de::Visitor<'de> for DeviceTriggerVisitor {
+    type Value = DeviceTrigger;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a valid device trigger")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        DeviceTrigger::from_str(v).map_err(|()| serde::de::Error::custom("invalid device trigger"))
+    }
+}
+
+#[cfg(feature = "devices")]
+pub use self::hook::*;
+
+#[cfg(feature = "devices")]
+mod hook {
+    use std::{
+        collections::hash_map::{Entry, HashMap},
+        fmt,
+        io::{self, BufRead, BufReader},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        thread,
+        time::Duration,
+    };
+
+    use hidapi::{HidApi, HidDevice};
+    use serialport::{SerialPort, SerialPortType};
+
+    use super::{newly_pressed, DeviceTrigger};
+
+    type Callback = Box<dyn FnMut() + Send + 'static>;
+    type Triggers = Arc<Mutex<HashMap<DeviceTrigger, Callback>>>;
+
+    /// The baud rate the serial devices need to use.
+    const SERIAL_BAUD_RATE: u32 = 9600;
+    /// How often the threads reading from the devices check whether the hook
+    /// got dropped.
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+    /// How often a device that got disconnected is looked for again.
+    const RESCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// The error type for the [`DeviceHook`].
+    #[derive(Debug)]
+    #[non_exhaustive]
+    pub enum Error {
+        /// The HID or serial subsystem of the platform could not be accessed.
+        Init,
+        /// There is no device with the vendor and product id, or it could not
+        /// be opened.
+        Open,
+        /// The trigger was already registered.
+        AlreadyRegistered,
+        /// The trigger to unregister was not registered.
+        NotRegistered,
+    }
+
+    impl std::error::Error for Error {}
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self {
+                Self::Init => "The HID or serial subsystem could not be accessed.",
+                Self::Open => "The device could not be found or opened.",
+                Self::AlreadyRegistered => "The device trigger was already registered.",
+                Self::NotRegistered => "The device trigger to unregister was not registered.",
+            })
+        }
+    }
+
+    #[derive(Copy, Clone, Eq, PartialEq)]
+    enum Device {
+        Hid(u16, u16),
+        Serial(u16, u16),
+    }
+
+    impl Device {
+        const fn of(trigger: &DeviceTrigger) -> Self {
+            match *trigger {
+                DeviceTrigger::Hid {
+                    vendor_id,
+                    product_id,
+                    ..
+                } => Self::Hid(vendor_id, product_id),
+                DeviceTrigger::Serial {
+                    vendor_id,
+                    product_id,
+                    ..
+                } => Self::Serial(vendor_id, product_id),
+            }
+        }
+    }
+
+    /// A device hook opens the HID and serial devices that triggers are
+    /// registered for and calls the callbacks of the [`DeviceTrigger`]s it
+    /// receives. Each device gets opened the first time a trigger for it is
+    /// registered and stays open until the hook is dropped. If a device gets
+    /// disconnected, it is opened again as soon as it shows up again.
+    pub struct DeviceHook {
+        triggers: Triggers,
+        devices: Mutex<Vec<Device>>,
+        stop: Arc<AtomicBool>,
+    }
+
+    impl Drop for DeviceHook {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::Relaxed);
+        }
+    }
+
+    impl Default for DeviceHook {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl DeviceHook {
+        /// Creates a new device hook that doesn't have any devices opened yet.
+        pub fn new() -> Self {
+            Self {
+                triggers: Default::default(),
+                devices: Default::default(),
+                stop: Default::default(),
+            }
+        }
+
+        /// Registers a device trigger to listen to. This opens the device if
+        /// it's not open yet.
+        pub fn register<F>(&self, trigger: DeviceTrigger, callback: F) -> Result<(), Error>
+        where
+            F: FnMut() + Send + 'static,
+        {
+            let mut triggers = self.triggers.lock().unwrap();
+            let Entry::Vacant(vacant) = triggers.entry(trigger) else {
+                return Err(Error::AlreadyRegistered);
+            };
+            self.open(Device::of(vacant.key()))?;
+            vacant.insert(Box::new(callback));
+            Ok(())
+        }
+
+        /// Unregisters a previously registered device trigger.
+        pub fn unregister(&self, trigger: &DeviceTrigger) -> Result<(), Error> {
+            self.triggers
+                .lock()
+                .unwrap()
+                .remove(trigger)
+                .map(drop)
+                .ok_or(Error::NotRegistered)
+        }
+
+        fn open(&self, device: Device) -> Result<(), Error> {
+            let mut devices = self.devices.lock().unwrap();
+            if devices.contains(&device) {
+                return Ok(());
+            }
+            match device {
+                Device::Hid(vendor_id, product_id) => self.open_hid(vendor_id, product_id)?,
+                Device::Serial(vendor_id, product_id) => self.open_serial(vendor_id, product_id)?,
+            }
+            devices.push(device);
+            Ok(())
+        }
+
+        fn open_hid(&self, vendor_id: u16, product_id: u16) -> Result<(), Error> {
+            let mut device = Some(connect_hid(vendor_id, product_id)?);
+            let (triggers, stop) = (self.triggers.clone(), self.stop.clone());
+
+            thread::spawn(move || {
+                let mut buf = [0; 64];
+                let mut previous = Vec::new();
+                while !stop.load(Ordering::Relaxed) {
+                    let Some(connected) = &device else {
+                        thread::sleep(RESCAN_INTERVAL);
+                        device = connect_hid(vendor_id, product_id).ok();
+                        previous.clear();
+                        continue;
+                    };
+                    let Ok(len) = connected.read_timeout(&mut buf, POLL_INTERVAL.as_millis() as _)
+                    else {
+                        // The device got disconnected, so we drop it and look
+                        // for it again.
+                        device = None;
+                        continue;
+                    };
+                    if len == 0 {
+                        continue;
+                    }
+                    let report = &buf[..len];
+                    let mut triggers = triggers.lock().unwrap();
+                    for button in newly_pressed(report, &previous) {
+                        let trigger = DeviceTrigger::Hid {
+                            vendor_id,
+                            product_id,
+                            button,
+                        };
+                        if let Some(callback) = triggers.get_mut(&trigger) {
+                            callback();
+                        }
+                    }
+                    previous.clear();
+                    previous.extend_from_slice(report);
+                }
+            });
+
+            Ok(())
+        }
+
+        fn open_serial(&self, vendor_id: u16, product_id: u16) -> Result<(), Error> {
+            let mut reader = Some(connect_serial(vendor_id, product_id)?);
+            let (triggers, stop) = (self.triggers.clone(), self.stop.clone());
+
+            thread::spawn(move || {
+                let mut line = String::new();
+                while !stop.load(Ordering::Relaxed) {
+                    let Some(connected) = &mut reader else {
+                        thread::sleep(RESCAN_INTERVAL);
+                        reader = connect_serial(vendor_id, product_id).ok();
+                        line.clear();
+                        continue;
+                    };
+                    match connected.read_line(&mut line) {
+                        Ok(0) => reader = None,
+                        Ok(_) => {
+                            let trigger = DeviceTrigger::Serial {
+                                vendor_id,
+                                product_id,
+                                message: line.trim().into(),
+                            };
+                            if let Some(callback) = triggers.lock().unwrap().get_mut(&trigger) {
+                                callback();
+                            }
+                            line.clear();
+                        }
+                        // Whatever got read so far stays in the line, so we
+                        // can just continue reading.
+                        Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+                        // The device got disconnected, so we drop it and look
+                        // for it again.
+                        Err(_) => reader = None,
+                    }
+                }
+            });
+
+            Ok(())
+        }
+    }
+
+    fn connect_hid(vendor_id: u16, product_id: u16) -> Result<HidDevice, Error> {
+        let api = HidApi::new().map_err(|_| Error::Init)?;
+        api.open(vendor_id, product_id).map_err(|_| Error::Open)
+    }
+
+    fn connect_serial(
+        vendor_id: u16,
+        product_id: u16,
+    ) -> Result<BufReader<Box<dyn SerialPort>>, Error> {
+        let port_name = serialport::available_ports()
+            .map_err(|_| Error::Init)?
+            .into_iter()
+            .find(|port| {
+                matches!(
+                    &port.port_type,
+                    SerialPortType::UsbPort(info)
+                        if info.vid == vendor_id && info.pid == product_id
+                )
+            })
+            .ok_or(Error::Open)?
+            .port_name;
+
+        let port = serialport::new(port_name, SERIAL_BAUD_RATE)
+            .timeout(POLL_INTERVAL)
+            .open()
+            .map_err(|_| Error::Open)?;
+
+        Ok(BufReader::new(port))
+    }
+}
@@ -39,11 +39,25 @@ cfg_if::cfg_if! {
     }
 }
 
+mod callbacks;
+pub mod device;
 mod hotkey;
 mod key_code;
+pub mod midi;
 mod modifiers;
+#[cfg(all(
+    feature = "std",
+    any(
+        test,
+        all(target_family = "wasm", target_os = "unknown", feature = "wasm-web"),
+    ),
+))]
+mod observed_layout;
+use alloc::boxed::Box;
 use core::fmt;
 
+use self::callbacks::Callbacks;
+
 pub use self::{hotkey::*, key_code::*, modifiers::*};
 
 /// A hook allows you to listen to hotkeys.
@@ -86,7 +100,86 @@ impl Hook {
     where
         F: FnMut() + Send + 'static,
     {
-        self.0.register(hotkey, callback)
+        self.0.register(
+            hotkey,
+            Callbacks {
+                press: Box::new(callback),
+                release: None,
+                consume: false,
+            },
+        )
+    }
+
+    /// Registers a hotkey to listen to. In addition to the callback that gets
+    /// called when the hotkey is pressed, there is a second callback that gets
+    /// called when the key of the hotkey is released again. This allows you to
+    /// detect for how long a hotkey is held down. The release callback is
+    /// called even if the modifiers are released before the key itself.
+    pub fn register_with_release<F, R>(
+        &self,
+        hotkey: Hotkey,
+        on_press: F,
+        on_release: R,
+    ) -> Result<()>
+    where
+        F: FnMut() + Send + 'static,
+        R: FnMut() + Send + 'static,
+    {
+        self.0.register(
+            hotkey,
+            Callbacks {
+                press: Box::new(on_press),
+                release: Some(Box::new(on_release)),
+                consume: false,
+            },
+        )
+    }
+
+    /// Registers a hotkey to listen to, just like [`register`](Self::register),
+    /// but the key events of this hotkey are additionally consumed, so they
+    /// don't get passed on to the application in focus. This way the hotkey
+    /// doesn't also type into a game or chat window. This is independent of
+    /// the [`ConsumePreference`] of the hook and is only supported on Windows
+    /// and on Linux when evdev is used, where it requires write access to
+    /// `/dev/uinput`. On the other platforms the key events are consumed
+    /// according to the [`ConsumePreference`] of the hook.
+    pub fn register_consuming<F>(&self, hotkey: Hotkey, callback: F) -> Result<()>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        self.0.register(
+            hotkey,
+            Callbacks {
+                press: Box::new(callback),
+                release: None,
+                consume: true,
+            },
+        )
+    }
+
+    /// Registers a hotkey to listen to, just like
+    /// [`register_with_release`](Self::register_with_release), but the key
+    /// events of this hotkey are additionally consumed. See
+    /// [`register_consuming`](Self::register_consuming) for the platforms that
+    /// support this.
+    pub fn register_consuming_with_release<F, R>(
+        &self,
+        hotkey: Hotkey,
+        on_press: F,
+        on_release: R,
+    ) -> Result<()>
+    where
+        F: FnMut() + Send + 'static,
+        R: FnMut() + Send + 'static,
+    {
+        self.0.register(
+            hotkey,
+            Callbacks {
+                press: Box::new(on_press),
+                release: Some(Box::new(on_release)),
+                consume: true,
+            },
+        )
     }
 
     /// Unregisters a previously registered hotkey.
@@ -168,6 +261,116 @@ mod tests {
         hook.unregister(KeyCode::Numpad1.into()).unwrap();
     }
 
+    #[test]
+    fn parse_modifier_combinations() {
+        assert_eq!(
+            "Ctrl + Shift + Numpad1".parse::<Hotkey>(),
+            Ok(KeyCode::Numpad1.with_modifiers(Modifiers::CONTROL | Modifiers::SHIFT)),
+        );
+        assert_eq!(
+            "control+alt+KeyS".parse::<Hotkey>(),
+            Ok(KeyCode::KeyS.with_modifiers(Modifiers::CONTROL | Modifiers::ALT)),
+        );
+        assert_eq!(
+            "Cmd + Option + F1".parse::<Hotkey>(),
+            Ok(KeyCode::F1.with_modifiers(Modifiers::META | Modifiers::ALT)),
+        );
+        assert_eq!(
+            "Ctrl + Alt + Meta + Shift + Space"
+                .parse::<Hotkey>()
+                .unwrap()
+                .to_string(),
+            "Ctrl + Alt + Meta + Shift + Space",
+        );
+        assert!("Hyper + KeyA".parse::<Hotkey>().is_err());
+    }
+
+    #[test]
+    fn midi_triggers() {
+        use midi::MidiTrigger;
+
+        let mut controllers = [0; 128];
+        assert_eq!(
+            MidiTrigger::from_message(&[0x93, 60, 100], &mut controllers),
+            Some(MidiTrigger::Note(60)),
+        );
+        assert_eq!(
+            MidiTrigger::from_message(&[0x93, 60, 0], &mut controllers),
+            None,
+        );
+        assert_eq!(
+            MidiTrigger::from_message(&[0xB0, 64, 127], &mut controllers),
+            Some(MidiTrigger::ControlChange(64)),
+        );
+        assert_eq!(
+            MidiTrigger::from_message(&[0xB0, 64, 120], &mut controllers),
+            None,
+        );
+        assert_eq!(
+            MidiTrigger::from_message(&[0xB0, 64, 0], &mut controllers),
+            None,
+        );
+        assert_eq!(
+            MidiTrigger::from_message(&[0xB0, 64, 127], &mut controllers),
+            Some(MidiTrigger::ControlChange(64)),
+        );
+        assert_eq!("CC 64".parse(), Ok(MidiTrigger::ControlChange(64)));
+        assert_eq!(MidiTrigger::Note(60).to_string(), "Note 60");
+        assert!("Note 128".parse::<MidiTrigger>().is_err());
+    }
+
+    #[cfg(feature = "devices")]
+    #[test]
+    fn newly_pressed_buttons() {
+        assert_eq!(device::newly_pressed(&[0b101, 0], &[0b001, 0]), [2]);
+        assert_eq!(device::newly_pressed(&[0, 0b10], &[]), [9]);
+        assert!(device::newly_pressed(&[0b1], &[0b11]).is_empty());
+    }
+
+    #[test]
+    fn device_triggers() {
+        use device::DeviceTrigger;
+
+        let pedal = DeviceTrigger::Hid {
+            vendor_id: 0x05f3,
+            product_id: 0x00ff,
+            button: 2,
+        };
+        assert_eq!(pedal.to_string(), "HID 05f3:00ff 2");
+        assert_eq!("HID 05f3:00ff 2".parse(), Ok(pedal));
+        assert_eq!(
+            "Serial 2341:0043 SPLIT".parse(),
+            Ok(DeviceTrigger::Serial {
+                vendor_id: 0x2341,
+                product_id: 0x0043,
+                message: "SPLIT".into(),
+            }),
+        );
+        assert!("Serial 2341:0043".parse::<DeviceTrigger>().is_err());
+        assert!("HID 2341 1".parse::<DeviceTrigger>().is_err());
+    }
+
+    #[test]
+    fn observed_layout() {
+        use observed_layout::ObservedLayout;
+
+        let mut layout = ObservedLayout::default();
+        layout.observe(KeyCode::KeyY, Modifiers::empty(), "z".into());
+        layout.observe(KeyCode::Minus, Modifiers::SHIFT, "?".into());
+        layout.observe(KeyCode::Enter, Modifiers::empty(), "Enter".into());
+        assert_eq!(layout.get(KeyCode::KeyY), Some("z"));
+        assert_eq!(layout.get(KeyCode::Minus), None);
+        assert_eq!(layout.get(KeyCode::Enter), None);
+
+        layout.observe(KeyCode::Minus, Modifiers::empty(), "ß".into());
+        layout.observe(KeyCode::KeyY, Modifiers::empty(), "y".into());
+        assert_eq!(layout.get(KeyCode::Minus), Some("ß"));
+        assert_eq!(layout.get(KeyCode::KeyY), Some("y"));
+
+        layout.clear();
+        assert_eq!(layout.get(KeyCode::KeyY), None);
+    }
+
     #[test]
     fn resolve() {
         let hook = Hook::new().unwrap();
@@ -1,11 +1,19 @@
-use std::{collections::hash_map::HashMap, os::unix::prelude::AsRawFd, ptr, thread};
+use std::{
+    collections::{hash_map::HashMap, HashSet},
+    mem::MaybeUninit,
+    os::unix::prelude::AsRawFd,
+    ptr, thread,
+};
 
-use evdev::{Device, EventType, InputEventKind, Key};
+use evdev::{
+    uinput::{VirtualDevice, VirtualDeviceBuilder},
+    Device, EventType, InputEventKind, Key,
+};
 use mio::{unix::SourceFd, Events, Interest, Poll, Token, Waker};
-use x11_dl::xlib::{Xlib, _XDisplay};
+use x11_dl::xlib::{MappingNotify, XEvent, Xlib, _XDisplay};
 
 use super::{x11_impl, Error, Hook, Message};
-use crate::{KeyCode, Modifiers, Result};
+use crate::{callbacks::Callbacks, KeyCode, Modifiers, Result};
 
 // Low numbered tokens are allocated to devices.
 const PING_TOKEN: Token = Token(usize::MAX);
@@ -235,6 +243,54 @@ pub const fn code_for(key: KeyCode) -> Option<Key> {
     })
 }
 
+/// Keyboards are the only devices that get grabbed. Other devices that emit
+/// key events, such as mice and gamepads, would also need all their other
+/// events to be forwarded.
+fn is_keyboard(device: &Device) -> bool {
+    let events = device.supported_events();
+    !events.contains(EventType::RELATIVE) && !events.contains(EventType::ABSOLUTE)
+}
+
+fn grab_keyboard(device: &mut Device) -> Result<VirtualDevice> {
+    let keys = device.supported_keys().ok_or(Error::UInput)?;
+    let forwarder = VirtualDeviceBuilder::new()
+        .and_then(|builder| builder.name("livesplit-hotkey").with_keys(keys))
+        .and_then(|builder| builder.build())
+        .map_err(|_| Error::UInput)?;
+    device.grab().map_err(|_| Error::EvDev)?;
+    Ok(forwarder)
+}
+
+/// Grabs all the keyboards, so none of their key events reach any other
+/// application anymore. For each keyboard a virtual keyboard is created that
+/// the key events that are not supposed to be consumed get forwarded to.
+fn grab(devices: &mut [Device]) -> Result<Vec<Option<VirtualDevice>>> {
+    let mut forwarders = Vec::with_capacity(devices.len());
+    for i in 0..devices.len() {
+        let forwarder = if is_keyboard(&devices[i]) {
+            match grab_keyboard(&mut devices[i]) {
+                Ok(forwarder) => Some(forwarder),
+                Err(e) => {
+                    ungrab(devices, &mut forwarders);
+                    return Err(e);
+                }
+            }
+        } else {
+            None
+        };
+        forwarders.push(forwarder);
+    }
+    Ok(forwarders)
+}
+
+fn ungrab(devices: &mut [Device], forwarders: &mut Vec<Option<VirtualDevice>>) {
+    for (device, forwarder) in devices.iter_mut().zip(forwarders.drain(..)) {
+        if forwarder.is_some() {
+            let _ = device.ungrab();
+        }
+    }
+}
+
 pub fn new() -> Result<Hook> {
     let (sender, receiver) = crossbeam_channel::unbounded();
     let mut poll = Poll::new().map_err(|_| Error::EPoll)?;
@@ -254,8 +310,13 @@ pub fn new() -> Result<Hook> {
     let join_handle = thread::spawn(move || -> Result<()> {
         let mut result = Ok(());
         let mut events = Events::with_capacity(1024);
-        let mut hotkeys: HashMap<(Key, Modifiers), Box<dyn FnMut() + Send>> = HashMap::new();
+        let mut hotkeys: HashMap<(Key, Modifiers), Callbacks> = HashMap::new();
+        let mut held_keys: HashMap<Key, Modifiers> = HashMap::new();
         let mut modifiers = Modifiers::empty();
+        // The devices are only grabbed while there are hotkeys whose key
+        // events need to be consumed.
+        let mut forwarders: Vec<Option<VirtualDevice>> = Vec::new();
+        let mut consumed_keys: HashSet<Key> = HashSet::new();
 
         let (mut xlib, mut display) = (None, None);
 
@@ -268,14 +329,21 @@ pub fn new() -> Result<Hook> {
             for mio_event in &events {
                 if mio_event.token().0 < devices.len() {
                     let idx = mio_event.token().0;
+                    let mut forwarder = forwarders.get_mut(idx).and_then(Option::as_mut);
                     for ev in devices[idx].fetch_events().map_err(|_| Error::EvDev)? {
                         if let InputEventKind::Key(k) = ev.kind() {
                             const RELEASED: i32 = 0;
                             const PRESSED: i32 = 1;
-                            match ev.value() {
+                            let consume = match ev.value() {
                                 PRESSED => {
-                                    if let Some(callback) = hotkeys.get_mut(&(k, modifiers)) {
-                                        callback();
+                                    let mut consume = false;
+                                    if let Some(callbacks) = hotkeys.get_mut(&(k, modifiers)) {
+                                        (callbacks.press)();
+                                        held_keys.insert(k, modifiers);
+                                        if callbacks.consume {
+                                            consumed_keys.insert(k);
+                                            consume = true;
+                                        }
                                     }
                                     match k {
                                         Key::KEY_LEFTALT | Key::KEY_RIGHTALT => {
@@ -292,23 +360,42 @@ pub fn new() -> Result<Hook> {
                                         }
                                         _ => {}
                                     }
+                                    consume
                                 }
-                                RELEASED => match k {
-                                    Key::KEY_LEFTALT | Key::KEY_RIGHTALT => {
-                                        modifiers.remove(Modifiers::ALT);
-                                    }
-                                    Key::KEY_LEFTCTRL | Key::KEY_RIGHTCTRL => {
-                                        modifiers.remove(Modifiers::CONTROL);
-                                    }
-                                    Key::KEY_LEFTMETA | Key::KEY_RIGHTMETA => {
-                                        modifiers.remove(Modifiers::META);
+                                RELEASED => {
+                                    if let Some(pressed_modifiers) = held_keys.remove(&k) {
+                                        if let Some(callbacks) =
+                                            hotkeys.get_mut(&(k, pressed_modifiers))
+                                        {
+                                            callbacks.release();
+                                        }
                                     }
-                                    Key::KEY_LEFTSHIFT | Key::KEY_RIGHTSHIFT => {
-                                        modifiers.remove(Modifiers::SHIFT);
+                                    match k {
+                                        Key::KEY_LEFTALT | Key::KEY_RIGHTALT => {
+                                            modifiers.remove(Modifiers::ALT);
+                                        }
+                                        Key::KEY_LEFTCTRL | Key::KEY_RIGHTCTRL => {
+                                            modifiers.remove(Modifiers::CONTROL);
+                                        }
+                                        Key::KEY_LEFTMETA | Key::KEY_RIGHTMETA => {
+                                            modifiers.remove(Modifiers::META);
+                                        }
+                                        Key::KEY_LEFTSHIFT | Key::KEY_RIGHTSHIFT => {
+                                            modifiers.remove(Modifiers::SHIFT);
+                                        }
+                                        _ => {}
                                     }
-                                    _ => {}
-                                },
-                                _ => {} // Ignore repeating
+                                    consumed_keys.remove(&k)
+                                }
+                                // Repeating keys don't trigger any hotkeys, but
+                                // still need to be consumed.
+                                _ => consumed_keys.contains(&k),
+                            };
+
+                            if !consume {
+                                if let Some(forwarder) = &mut forwarder {
+                                    let _ = forwarder.emit(&[ev]);
+                                }
                             }
                         }
                     }
@@ -316,6 +403,15 @@ pub fn new() -> Result<Hook> {
                     for message in receiver.try_iter() {
                         match message {
                             Message::Register(key, callback, promise) => {
+                                if callback.consume && forwarders.is_empty() {
+                                    match grab(&mut devices) {
+                                        Ok(grabbed) => forwarders = grabbed,
+                                        Err(e) => {
+                                            promise.set(Err(e));
+                                            continue;
+                                        }
+                                    }
+                                }
                                 promise.set(
                                     if code_for(key.key_code)
                                         .and_then(|k| hotkeys.insert((k, key.modifiers), callback))
@@ -340,6 +436,10 @@ pub fn new() -> Result<Hook> {
                             }
                         }
                     }
+
+                    if !forwarders.is_empty() && !hotkeys.values().any(|c| c.consume) {
+                        ungrab(&mut devices, &mut forwarders);
+                    }
                 }
             }
         }
@@ -376,5 +476,19 @@ fn resolve(
         }
     }
     let xdisplay = (*display)?;
+    // Xlib only updates its copy of the keyboard mapping while it reads the
+    // events of the connection. Nothing else reads them here, so we need to
+    // do that ourselves to notice the keyboard layout changing.
+    unsafe {
+        while (xlib.XPending)(xdisplay) != 0 {
+            let mut event = MaybeUninit::uninit();
+            if (xlib.XNextEvent)(xdisplay, event.as_mut_ptr()) == 0 {
+                let mut event: XEvent = event.assume_init();
+                if event.get_type() == MappingNotify {
+                    (xlib.XRefreshKeyboardMapping)(event.as_mut());
+                }
+            }
+        }
+    }
     x11_impl::resolve(xlib, xdisplay, key_code)
 }
@@ -1,6 +1,6 @@
 use std::{fmt, thread::JoinHandle};
 
-use crate::{ConsumePreference, Hotkey, KeyCode, Result};
+use crate::{callbacks::Callbacks, ConsumePreference, Hotkey, KeyCode, Result};
 use crossbeam_channel::Sender;
 use mio::Waker;
 use nix::unistd::{getgroups, Group};
@@ -17,6 +17,7 @@ pub enum Error {
     NoXLib,
     OpenXServerConnection,
     ThreadStopped,
+    UInput,
 }
 
 impl From<Error> for crate::Error {
@@ -33,16 +34,15 @@ impl fmt::Display for Error {
             Self::NoXLib => "Failed dynamically linking to X11.",
             Self::OpenXServerConnection => "Failed opening a connection to the X11 server.",
             Self::ThreadStopped => "The background thread stopped unexpectedly.",
+            Self::UInput => {
+                "Failed creating a virtual keyboard for forwarding the keys that aren't consumed."
+            }
         })
     }
 }
 
 enum Message {
-    Register(
-        Hotkey,
-        Box<dyn FnMut() + Send + 'static>,
-        Promise<Result<()>>,
-    ),
+    Register(Hotkey, Callbacks, Promise<Result<()>>),
     Unregister(Hotkey, Promise<Result<()>>),
     Resolve(KeyCode, Promise<Option<char>>),
     End,
@@ -79,25 +79,22 @@ impl Hook {
         }
 
         if !matches!(consume, ConsumePreference::MustConsume) && can_use_evdev().is_some() {
-            evdev_impl::new().map_err(Into::into)
+            evdev_impl::new()
         } else if !matches!(
             consume,
             ConsumePreference::MustNotConsume | ConsumePreference::PreferConsume
         ) {
-            x11_impl::new().map_err(Into::into)
+            x11_impl::new()
         } else {
             Err(crate::Error::UnmatchedPreference)
         }
     }
 
-    pub fn register<F>(&self, hotkey: Hotkey, callback: F) -> Result<()>
-    where
-        F: FnMut() + Send + 'static,
-    {
+    pub fn register(&self, hotkey: Hotkey, callbacks: Callbacks) -> Result<()> {
         let (future, promise) = future_promise();
 
         self.sender
-            .send(Message::Register(hotkey, Box::new(callback), promise))
+            .send(Message::Register(hotkey, callbacks, promise))
             .map_err(|_| Error::ThreadStopped)?;
 
         self.waker.wake().map_err(|_| Error::ThreadStopped)?;
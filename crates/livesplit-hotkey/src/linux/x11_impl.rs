@@ -7,12 +7,13 @@ use std::{
 
 use mio::{unix::SourceFd, Events, Interest, Poll, Token, Waker};
 use x11_dl::xlib::{
-    AnyKey, AnyModifier, ControlMask, Display, GrabModeAsync, KeyPress, LockMask, Mod1Mask,
-    Mod2Mask, Mod3Mask, Mod4Mask, ShiftMask, XErrorEvent, XKeyEvent, Xlib, _XDisplay,
+    AnyKey, AnyModifier, ControlMask, Display, GrabModeAsync, KeyPress, KeyRelease, LockMask,
+    Mod1Mask, Mod2Mask, Mod3Mask, Mod4Mask, ShiftMask, XErrorEvent, XKeyEvent, XkbStateRec, Xlib,
+    _XDisplay,
 };
 
 use super::{Error, Hook, Message};
-use crate::{KeyCode, Modifiers, Result};
+use crate::{callbacks::Callbacks, KeyCode, Modifiers, Result};
 
 unsafe fn ungrab_all(xlib: &Xlib, display: *mut Display) {
     let screencount = (xlib.XScreenCount)(display);
@@ -150,12 +151,18 @@ pub fn new() -> Result<Hook> {
 
             let mut result = Ok(());
             let mut events = Events::with_capacity(1024);
-            let mut hotkeys = HashMap::new();
+            let mut hotkeys: HashMap<(c_uint, Modifiers), Callbacks> = HashMap::new();
+            let mut held_keys: HashMap<c_uint, Modifiers> = HashMap::new();
 
             // For some reason we need to call this once for any KeyGrabs to
             // actually do anything.
             (xlib.XKeysymToKeycode)(display, 0);
 
+            // By default X11 emulates auto repeat by sending a release event
+            // before every repeated press event. This would make it look like
+            // the key got released, so we ask the server to not do that.
+            (xlib.XkbSetDetectableAutoRepeat)(display, 1, ptr::null_mut());
+
             'event_loop: loop {
                 if poll.poll(&mut events, None).is_err() {
                     result = Err(Error::EPoll);
@@ -208,7 +215,8 @@ pub fn new() -> Result<Hook> {
                             let err_code = (xlib.XNextEvent)(display, event.as_mut_ptr());
                             if err_code == 0 {
                                 let event = event.assume_init();
-                                if event.get_type() == KeyPress {
+                                let event_type = event.get_type();
+                                if event_type == KeyPress {
                                     let event: &XKeyEvent = event.as_ref();
 
                                     let mut modifiers = Modifiers::empty();
@@ -225,10 +233,21 @@ pub fn new() -> Result<Hook> {
                                         modifiers.insert(Modifiers::META);
                                     }
 
-                                    if let Some(callback) =
+                                    if let Some(callbacks) =
                                         hotkeys.get_mut(&(event.keycode, modifiers))
                                     {
-                                        callback();
+                                        (callbacks.press)();
+                                        held_keys.insert(event.keycode, modifiers);
+                                    }
+                                } else if event_type == KeyRelease {
+                                    let event: &XKeyEvent = event.as_ref();
+
+                                    if let Some(modifiers) = held_keys.remove(&event.keycode) {
+                                        if let Some(callbacks) =
+                                            hotkeys.get_mut(&(event.keycode, modifiers))
+                                        {
+                                            callbacks.release();
+                                        }
                                     }
                                 }
                             }
@@ -252,8 +271,21 @@ pub fn new() -> Result<Hook> {
     }
 }
 
+const XKB_USE_CORE_KBD: c_uint = 0x0100;
+
 pub(super) fn resolve(xlib: &Xlib, display: *mut _XDisplay, key_code: KeyCode) -> Option<char> {
-    let key_sym = unsafe { (xlib.XKeycodeToKeysym)(display, code_for(key_code)? as _, 0) };
+    // X11 supports multiple keyboard layouts at the same time, which it calls
+    // groups. We want to resolve the key according to the layout that is
+    // currently in use, so the names stay correct when switching between them.
+    let group = unsafe {
+        let mut state = MaybeUninit::<XkbStateRec>::uninit();
+        if (xlib.XkbGetState)(display, XKB_USE_CORE_KBD, state.as_mut_ptr()) == 0 {
+            state.assume_init().group as c_int
+        } else {
+            0
+        }
+    };
+    let key_sym = unsafe { (xlib.XkbKeycodeToKeysym)(display, code_for(key_code)? as _, group, 0) };
     if key_sym == 0 {
         return None;
     }
@@ -20,7 +20,7 @@ use self::{
         EventTapLocation, EventTapOptions, EventTapPlacement, EventTapProxy, EventType,
     },
 };
-use crate::{ConsumePreference, Hotkey, KeyCode, Modifiers, Result};
+use crate::{callbacks::Callbacks, ConsumePreference, Hotkey, KeyCode, Modifiers, Result};
 use core::ptr::null_mut;
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -75,7 +75,14 @@ unsafe impl Send for RunLoop {}
 unsafe impl Sync for RunLoop {}
 
 struct State {
-    hotkeys: Mutex<HashMap<Hotkey, Box<dyn FnMut() + Send + 'static>>>,
+    hotkeys: Mutex<HashMap<Hotkey, Callbacks>>,
+    // The modifiers may change while a key is held down, so we need to
+    // remember which hotkey got pressed in order to notify the same hotkey
+    // about the key being released.
+    held_keys: Mutex<HashMap<KeyCode, Hotkey>>,
+    // Whether the event tap is able to consume events at all. Listen-only
+    // taps need to pass on the events unchanged.
+    is_consuming: bool,
 }
 
 /// A hook allows you to listen to hotkeys.
@@ -105,6 +112,8 @@ impl Hook {
 
         let state = Arc::new(State {
             hotkeys: Mutex::new(HashMap::new()),
+            held_keys: Mutex::new(HashMap::new()),
+            is_consuming,
         });
         let thread_state = state.clone();
 
@@ -131,7 +140,7 @@ impl Hook {
                 } else {
                     EventTapOptions::LISTEN_ONLY
                 },
-                EventMask::KEY_DOWN,
+                EventMask::KEY_DOWN | EventMask::KEY_UP,
                 Some(callback),
                 state_ptr as *mut c_void,
             );
@@ -172,12 +181,9 @@ impl Hook {
         Ok(Hook { event_loop, state })
     }
 
-    pub fn register<F>(&self, hotkey: Hotkey, callback: F) -> Result<()>
-    where
-        F: FnMut() + Send + 'static,
-    {
+    pub fn register(&self, hotkey: Hotkey, callbacks: Callbacks) -> Result<()> {
         if let Entry::Vacant(vacant) = self.state.hotkeys.lock().unwrap().entry(hotkey) {
-            vacant.insert(Box::new(callback));
+            vacant.insert(callbacks);
             Ok(())
         } else {
             Err(crate::Error::AlreadyRegistered)
@@ -303,7 +309,7 @@ impl Hook {
 
 unsafe extern "C" fn callback(
     _: EventTapProxy,
-    _: EventType,
+    event_type: EventType,
     event: EventRef,
     user_info: *mut c_void,
 ) -> EventRef {
@@ -451,6 +457,24 @@ unsafe extern "C" fn callback(
     let state = user_info as *const State;
     let state = &*state;
 
+    if event_type == EventType::KEY_UP {
+        let Some(hotkey) = state.held_keys.lock().unwrap().remove(&key_code) else {
+            return event;
+        };
+
+        if let Some(callbacks) = state.hotkeys.lock().unwrap().get_mut(&hotkey) {
+            callbacks.release();
+        }
+
+        // If the key down event got consumed, we consume the key up event as
+        // well.
+        return if state.is_consuming {
+            null_mut()
+        } else {
+            event
+        };
+    }
+
     let modifier_flags = CGEventGetFlags(event);
     let mut modifiers = Modifiers::empty();
 
@@ -478,18 +502,20 @@ unsafe extern "C" fn callback(
         modifiers.insert(Modifiers::META);
     }
 
-    if let Some(callback) = state
-        .hotkeys
-        .lock()
-        .unwrap()
-        .get_mut(&key_code.with_modifiers(modifiers))
-    {
-        callback();
+    let hotkey = key_code.with_modifiers(modifiers);
+
+    if let Some(callbacks) = state.hotkeys.lock().unwrap().get_mut(&hotkey) {
+        (callbacks.press)();
+        state.held_keys.lock().unwrap().insert(key_code, hotkey);
 
         // If we handled the event and the hook is consuming, we should return
-        // null so the system deletes the event. If the hook is not consuming
-        // the return value will be ignored, so return null anyway.
-        null_mut()
+        // null so the system deletes the event. Otherwise the event is passed
+        // on unchanged.
+        if state.is_consuming {
+            null_mut()
+        } else {
+            event
+        }
     } else {
         event
     }
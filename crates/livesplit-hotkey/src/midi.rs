@@ -0,0 +1,231 @@
+//! MIDI devices such as stream decks, drum pads, and foot controllers can be
+//! used as an additional source of hotkeys. Notes and control changes can be
+//! registered as [`MidiTrigger`]s, which are then listened to on all the MIDI
+//! input devices that are connected. This requires the `midi` feature to
+//! actually connect to any devices, the triggers themselves are always
+//! available so they can be stored in a configuration.
+
+use core::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// A MIDI message that can be used to trigger an action. The channel the
+/// message is sent on is ignored.
+#[derive(Eq, PartialEq, Hash, Copy, Clone)]
+pub enum MidiTrigger {
+    /// A note with the given number is played.
+    Note(u8),
+    /// The controller with the given number is turned on. A controller counts
+    /// as turned on once its value reaches the upper half of its range, which
+    /// is how foot switches and buttons usually report being pressed.
+    ControlChange(u8),
+}
+
+impl MidiTrigger {
+    /// Parses a raw MIDI message and returns the trigger it represents, if
+    /// any. The previous value of each controller needs to be tracked, so a
+    /// controller only triggers once when it gets turned on.
+    pub fn from_message(message: &[u8], controllers: &mut [u8; 128]) -> Option<Self> {
+        const NOTE_ON: u8 = 0x90;
+        const CONTROL_CHANGE: u8 = 0xB0;
+        const ON_THRESHOLD: u8 = 64;
+
+        let [status, number, value, ..] = *message else {
+            return None;
+        };
+        let number = number & 0x7F;
+
+        match status & 0xF0 {
+            // A note on message with a velocity of 0 is a note off message.
+            NOTE_ON if value != 0 => Some(Self::Note(number)),
+            CONTROL_CHANGE => {
+                let previous = core::mem::replace(&mut controllers[number as usize], value & 0x7F);
+                (previous < ON_THRESHOLD && value >= ON_THRESHOLD)
+                    .then_some(Self::ControlChange(number))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for MidiTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for MidiTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Note(number) => write!(f, "Note {number}"),
+            Self::ControlChange(number) => write!(f, "CC {number}"),
+        }
+    }
+}
+
+impl FromStr for MidiTrigger {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, number) = s.trim().split_once(' ').ok_or(())?;
+        let number: u8 = number.trim().parse().map_err(drop)?;
+        if number > 127 {
+            return Err(());
+        }
+        match kind {
+            "Note" => Ok(Self::Note(number)),
+            "CC" => Ok(Self::ControlChange(number)),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Serialize for MidiTrigger {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MidiTrigger {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(MidiTriggerVisitor)
+    }
+}
+
+struct MidiTriggerVisitor;
+
+impl<'de> serde::de::Visitor<'de> for MidiTriggerVisitor {
+    type Value = MidiTrigger;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a valid MIDI trigger")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        MidiTrigger::from_str(v).map_err(|()| serde::de::Error::custom("invalid MIDI trigger"))
+    }
+}
+
+#[cfg(feature = "midi")]
+pub use self::hook::*;
+
+#[cfg(feature = "midi")]
+mod hook {
+    use std::{
+        collections::hash_map::{Entry, HashMap},
+        fmt,
+        sync::{Arc, Mutex},
+    };
+
+    use midir::{MidiInput, MidiInputConnection};
+
+    use super::MidiTrigger;
+
+    type Callback = Box<dyn FnMut() + Send + 'static>;
+
+    /// The error type for the [`MidiHook`].
+    #[derive(Debug)]
+    #[non_exhaustive]
+    pub enum Error {
+        /// The MIDI subsystem of the platform could not be initialized.
+        Init,
+        /// Connecting to a MIDI input device failed.
+        Connect,
+        /// The trigger was already registered.
+        AlreadyRegistered,
+        /// The trigger to unregister was not registered.
+        NotRegistered,
+    }
+
+    impl std::error::Error for Error {}
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self {
+                Self::Init => "The MIDI subsystem could not be initialized.",
+                Self::Connect => "Failed connecting to a MIDI input device.",
+                Self::AlreadyRegistered => "The MIDI trigger was already registered.",
+                Self::NotRegistered => "The MIDI trigger to unregister was not registered.",
+            })
+        }
+    }
+
+    /// A MIDI hook listens to all the MIDI input devices that are connected
+    /// when it is created and calls the callbacks registered for the
+    /// [`MidiTrigger`]s it receives.
+    pub struct MidiHook {
+        triggers: Arc<Mutex<HashMap<MidiTrigger, Callback>>>,
+        _connections: Vec<MidiInputConnection<[u8; 128]>>,
+    }
+
+    impl MidiHook {
+        /// Creates a new MIDI hook that listens to all the MIDI input devices
+        /// that are currently connected.
+        pub fn new() -> Result<Self, Error> {
+            let triggers = Arc::new(Mutex::new(HashMap::<MidiTrigger, Callback>::new()));
+
+            let ports = MidiInput::new("livesplit-hotkey")
+                .map_err(|_| Error::Init)?
+                .ports();
+
+            let mut connections = Vec::with_capacity(ports.len());
+            for port in &ports {
+                // Connecting consumes the input, so we need one per device.
+                let input = MidiInput::new("livesplit-hotkey").map_err(|_| Error::Init)?;
+                let triggers = triggers.clone();
+                let connection = input
+                    .connect(
+                        port,
+                        "livesplit-hotkey",
+                        move |_, message, controllers| {
+                            if let Some(trigger) = MidiTrigger::from_message(message, controllers) {
+                                if let Some(callback) = triggers.lock().unwrap().get_mut(&trigger) {
+                                    callback();
+                                }
+                            }
+                        },
+                        [0; 128],
+                    )
+                    .map_err(|_| Error::Connect)?;
+                connections.push(connection);
+            }
+
+            Ok(Self {
+                triggers,
+                _connections: connections,
+            })
+        }
+
+        /// Registers a MIDI trigger to listen to.
+        pub fn register<F>(&self, trigger: MidiTrigger, callback: F) -> Result<(), Error>
+        where
+            F: FnMut() + Send + 'static,
+        {
+            if let Entry::Vacant(vacant) = self.triggers.lock().unwrap().entry(trigger) {
+                vacant.insert(Box::new(callback));
+                Ok(())
+            } else {
+                Err(Error::AlreadyRegistered)
+            }
+        }
+
+        /// Unregisters a previously registered MIDI trigger.
+        pub fn unregister(&self, trigger: MidiTrigger) -> Result<(), Error> {
+            self.triggers
+                .lock()
+                .unwrap()
+                .remove(&trigger)
+                .map(drop)
+                .ok_or(Error::NotRegistered)
+        }
+    }
+}
@@ -48,19 +48,40 @@ impl fmt::Display for Modifiers {
     }
 }
 
+impl Modifiers {
+    /// Parses a single modifier. Apart from the names used when formatting
+    /// modifiers, the common names used on the different platforms are
+    /// accepted as well. The names are matched case insensitively.
+    fn parse_single(modifier: &str) -> Option<Self> {
+        const NAMES: [(&str, Modifiers); 12] = [
+            ("Ctrl", Modifiers::CONTROL),
+            ("Control", Modifiers::CONTROL),
+            ("Alt", Modifiers::ALT),
+            ("Option", Modifiers::ALT),
+            ("Opt", Modifiers::ALT),
+            ("Meta", Modifiers::META),
+            ("Cmd", Modifiers::META),
+            ("Command", Modifiers::META),
+            ("Super", Modifiers::META),
+            ("Win", Modifiers::META),
+            ("Windows", Modifiers::META),
+            ("Shift", Modifiers::SHIFT),
+        ];
+
+        NAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(modifier))
+            .map(|&(_, modifier)| modifier)
+    }
+}
+
 impl FromStr for Modifiers {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut modifiers = Modifiers::empty();
         for modifier in s.split('+').map(str::trim) {
-            match modifier {
-                "Ctrl" => modifiers.insert(Modifiers::CONTROL),
-                "Alt" => modifiers.insert(Modifiers::ALT),
-                "Meta" => modifiers.insert(Modifiers::META),
-                "Shift" => modifiers.insert(Modifiers::SHIFT),
-                _ => return Err(()),
-            }
+            modifiers.insert(Modifiers::parse_single(modifier).ok_or(())?);
         }
         Ok(modifiers)
     }
@@ -0,0 +1,38 @@
+//! Not every platform allows querying the keyboard layout that is currently in
+//! use. The characters that the keys produce when they get pressed can however
+//! be used to learn the layout instead.
+
+use std::collections::HashMap;
+
+use crate::{KeyCode, Modifiers};
+
+/// Remembers the character each key produced the last time it got pressed
+/// without any modifiers. This only describes the current keyboard layout as
+/// long as it didn't change since, so it needs to be cleared whenever the
+/// layout may have changed.
+#[derive(Default)]
+pub struct ObservedLayout {
+    keys: HashMap<KeyCode, String>,
+}
+
+impl ObservedLayout {
+    /// Observes the key that the key code produced when it got pressed. Keys
+    /// that are named, such as `Enter`, or that are pressed with modifiers
+    /// don't describe the layout and are ignored.
+    pub fn observe(&mut self, key_code: KeyCode, modifiers: Modifiers, key: String) {
+        if modifiers.is_empty() && key.chars().count() == 1 {
+            self.keys.insert(key_code, key);
+        }
+    }
+
+    /// Accesses the character the key code produced the last time it got
+    /// pressed.
+    pub fn get(&self, key_code: KeyCode) -> Option<&str> {
+        self.keys.get(&key_code).map(String::as_str)
+    }
+
+    /// Forgets all the keys that got observed.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+}
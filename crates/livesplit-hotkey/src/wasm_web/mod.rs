@@ -1,7 +1,10 @@
-use crate::{ConsumePreference, Hotkey, KeyCode, Modifiers, Result};
+use crate::{
+    callbacks::Callbacks, observed_layout::ObservedLayout, ConsumePreference, Hotkey, KeyCode,
+    Modifiers, Result,
+};
 use js_sys::{Function, Promise, Reflect};
 use wasm_bindgen::{prelude::*, JsCast};
-use web_sys::{window, Event, Gamepad, GamepadButton, KeyboardEvent};
+use web_sys::{window, Event, EventTarget, Gamepad, GamepadButton, KeyboardEvent};
 
 use std::{
     cell::{Cell, RefCell},
@@ -26,12 +29,15 @@ impl fmt::Display for Error {
 }
 
 pub struct Hook {
-    hotkeys: Arc<Mutex<HashMap<Hotkey, Box<dyn FnMut() + Send + 'static>>>>,
+    hotkeys: Arc<Mutex<HashMap<Hotkey, Callbacks>>>,
     keyboard_callback: Closure<dyn FnMut(Event)>,
+    keyboard_release_callback: Closure<dyn FnMut(Event)>,
     gamepad_callback: Closure<dyn FnMut()>,
     interval_id: Cell<Option<i32>>,
     keyboard_layout_resolver: Rc<RefCell<Option<(JsValue, Function)>>>,
-    _keyboard_layout_closure: Option<Closure<dyn FnMut(JsValue)>>,
+    observed_keyboard_layout: Rc<RefCell<ObservedLayout>>,
+    keyboard: Option<EventTarget>,
+    layout_change_callback: Closure<dyn FnMut()>,
 }
 
 impl Drop for Hook {
@@ -41,10 +47,24 @@ impl Drop for Hook {
                 "keydown",
                 self.keyboard_callback.as_ref().unchecked_ref(),
             );
+            let _ = window.remove_event_listener_with_callback(
+                "keyup",
+                self.keyboard_release_callback.as_ref().unchecked_ref(),
+            );
+            let _ = window.remove_event_listener_with_callback(
+                "blur",
+                self.layout_change_callback.as_ref().unchecked_ref(),
+            );
             if let Some(interval_id) = self.interval_id.get() {
                 window.clear_interval_with_handle(interval_id);
             }
         }
+        if let Some(keyboard) = &self.keyboard {
+            let _ = keyboard.remove_event_listener_with_callback(
+                "layoutchange",
+                self.layout_change_callback.as_ref().unchecked_ref(),
+            );
+        }
     }
 }
 
@@ -79,14 +99,23 @@ impl Hook {
             ConsumePreference::PreferConsume | ConsumePreference::MustConsume
         );
 
-        let hotkeys = Arc::new(Mutex::new(HashMap::<
-            Hotkey,
-            Box<dyn FnMut() + Send + 'static>,
-        >::new()));
+        let hotkeys = Arc::new(Mutex::new(HashMap::<Hotkey, Callbacks>::new()));
+
+        // The modifiers may change while a key is held down, so we need to
+        // remember which hotkey got pressed in order to notify the same hotkey
+        // about the key being released.
+        let held_keys = Rc::new(RefCell::new(HashMap::<KeyCode, Hotkey>::new()));
 
         let window = window().ok_or(crate::Error::Platform(Error::FailedToCreateHook))?;
 
+        // Not every browser supports querying the keyboard layout. So we
+        // additionally remember which character each key produces whenever
+        // it's pressed and fall back to that.
+        let observed_keyboard_layout = Rc::new(RefCell::new(ObservedLayout::default()));
+
         let hotkey_map = hotkeys.clone();
+        let held = held_keys.clone();
+        let observed_layout = observed_keyboard_layout.clone();
         let keyboard_callback = Closure::wrap(Box::new(move |event: Event| {
             // Despite all sorts of documentation claiming that `keydown` events
             // pass you a `KeyboardEvent`, this is not actually always the case
@@ -116,12 +145,15 @@ impl Hook {
                             modifiers.insert(Modifiers::META);
                         }
 
-                        if let Some(callback) = hotkey_map
-                            .lock()
-                            .unwrap()
-                            .get_mut(&code.with_modifiers(modifiers))
-                        {
-                            callback();
+                        observed_layout
+                            .borrow_mut()
+                            .observe(code, modifiers, event.key());
+
+                        let hotkey = code.with_modifiers(modifiers);
+
+                        if let Some(callbacks) = hotkey_map.lock().unwrap().get_mut(&hotkey) {
+                            (callbacks.press)();
+                            held.borrow_mut().insert(code, hotkey);
                             if prevent_default {
                                 event.prevent_default();
                             }
@@ -135,40 +167,88 @@ impl Hook {
             .add_event_listener_with_callback("keydown", keyboard_callback.as_ref().unchecked_ref())
             .map_err(|_| crate::Error::Platform(Error::FailedToCreateHook))?;
 
+        let hotkey_map = hotkeys.clone();
+        let keyboard_release_callback = Closure::wrap(Box::new(move |event: Event| {
+            if let Ok(event) = event.dyn_into::<KeyboardEvent>() {
+                if let Ok(code) = event.code().parse::<KeyCode>() {
+                    let Some(hotkey) = held_keys.borrow_mut().remove(&code) else {
+                        return;
+                    };
+                    if let Some(callbacks) = hotkey_map.lock().unwrap().get_mut(&hotkey) {
+                        callbacks.release();
+                        if prevent_default {
+                            event.prevent_default();
+                        }
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(Event)>);
+
+        window
+            .add_event_listener_with_callback(
+                "keyup",
+                keyboard_release_callback.as_ref().unchecked_ref(),
+            )
+            .map_err(|_| crate::Error::Platform(Error::FailedToCreateHook))?;
+
         let hotkey_map = hotkeys.clone();
 
         let mut states = Vec::new();
         let navigator = window.navigator();
 
         let keyboard_layout_resolver = Rc::new(RefCell::new(None));
-        let _keyboard_layout_closure = (|| {
-            let keyboard = Reflect::get(navigator.as_ref(), &JsValue::from_str("keyboard")).ok()?;
-            if keyboard.is_undefined() {
-                return None;
-            }
-
-            let get_layout_map =
-                Reflect::get(&keyboard, &JsValue::from_str("getLayoutMap")).ok()?;
+        let keyboard = Reflect::get(navigator.as_ref(), &JsValue::from_str("keyboard"))
+            .ok()
+            .and_then(|keyboard| keyboard.dyn_into::<EventTarget>().ok());
 
-            let layout_map_promise = get_layout_map
-                .dyn_ref::<Function>()?
-                .call0(&keyboard)
-                .ok()?;
+        let resolver = keyboard_layout_resolver.clone();
+        let keyboard_layout_closure = Closure::wrap(Box::new(move |layout_map| {
+            if let Ok(get_fn) = Reflect::get(&layout_map, &JsValue::from_str("get")) {
+                if let Ok(get_fn) = get_fn.dyn_into::<Function>() {
+                    *resolver.borrow_mut() = Some((layout_map, get_fn));
+                }
+            }
+        }) as Box<dyn FnMut(JsValue)>);
 
-            let keyboard_layout_resolver = keyboard_layout_resolver.clone();
+        // The layout map is only a snapshot of the layout at the time it got
+        // queried, so it needs to be queried again whenever the layout
+        // changes. Not every browser notifies us about that though, so we
+        // also assume it may have changed while the page wasn't focused.
+        let query_layout_map = {
+            let keyboard = keyboard.clone();
+            move || {
+                let keyboard = keyboard.as_ref()?;
+                let get_layout_map =
+                    Reflect::get(keyboard, &JsValue::from_str("getLayoutMap")).ok()?;
+                let layout_map_promise =
+                    get_layout_map.dyn_ref::<Function>()?.call0(keyboard).ok()?;
+                let _ = layout_map_promise
+                    .dyn_ref::<Promise>()?
+                    .then(&keyboard_layout_closure);
+                Some(())
+            }
+        };
+        query_layout_map();
 
-            let closure = Closure::wrap(Box::new(move |layout_map| {
-                if let Ok(get_fn) = Reflect::get(&layout_map, &JsValue::from_str("get")) {
-                    if let Ok(get_fn) = get_fn.dyn_into::<Function>() {
-                        *keyboard_layout_resolver.borrow_mut() = Some((layout_map, get_fn));
-                    }
-                }
-            }) as Box<dyn FnMut(JsValue)>);
+        let observed_layout = observed_keyboard_layout.clone();
+        let layout_change_callback = Closure::wrap(Box::new(move || {
+            observed_layout.borrow_mut().clear();
+            query_layout_map();
+        }) as Box<dyn FnMut()>);
 
-            let _ = layout_map_promise.dyn_ref::<Promise>()?.then(&closure);
+        window
+            .add_event_listener_with_callback(
+                "blur",
+                layout_change_callback.as_ref().unchecked_ref(),
+            )
+            .map_err(|_| crate::Error::Platform(Error::FailedToCreateHook))?;
 
-            Some(closure)
-        })();
+        if let Some(keyboard) = &keyboard {
+            let _ = keyboard.add_event_listener_with_callback(
+                "layoutchange",
+                layout_change_callback.as_ref().unchecked_ref(),
+            );
+        }
 
         let gamepad_callback = Closure::wrap(Box::new(move || {
             if let Ok(gamepads) = navigator.get_gamepads() {
@@ -186,11 +266,15 @@ impl Hook {
                         {
                             if let Ok(button) = button.dyn_into::<GamepadButton>() {
                                 let pressed = button.pressed();
-                                if pressed && !*state {
-                                    if let Some(callback) =
+                                if pressed != *state {
+                                    if let Some(callbacks) =
                                         hotkey_map.lock().unwrap().get_mut(&code.into())
                                     {
-                                        callback();
+                                        if pressed {
+                                            (callbacks.press)();
+                                        } else {
+                                            callbacks.release();
+                                        }
                                     }
                                 }
                                 *state = pressed;
@@ -204,17 +288,17 @@ impl Hook {
         Ok(Hook {
             hotkeys,
             keyboard_callback,
+            keyboard_release_callback,
             gamepad_callback,
             interval_id: Cell::new(None),
             keyboard_layout_resolver,
-            _keyboard_layout_closure,
+            observed_keyboard_layout,
+            keyboard,
+            layout_change_callback,
         })
     }
 
-    pub fn register<F>(&self, hotkey: Hotkey, callback: F) -> Result<()>
-    where
-        F: FnMut() + Send + 'static,
-    {
+    pub fn register(&self, hotkey: Hotkey, callbacks: Callbacks) -> Result<()> {
         if let Entry::Vacant(vacant) = self.hotkeys.lock().unwrap().entry(hotkey) {
             if GAMEPAD_BUTTONS.contains(&hotkey.key_code) && self.interval_id.get().is_none() {
                 let interval_id = window()
@@ -226,7 +310,7 @@ impl Hook {
                     .map_err(|_| crate::Error::Platform(Error::FailedToCreateHook))?;
                 self.interval_id.set(Some(interval_id));
             }
-            vacant.insert(Box::new(callback));
+            vacant.insert(callbacks);
             Ok(())
         } else {
             Err(crate::Error::AlreadyRegistered)
@@ -242,12 +326,21 @@ impl Hook {
     }
 
     pub fn try_resolve(&self, key_code: KeyCode) -> Option<String> {
-        let keyboard_layout_resolver = self.keyboard_layout_resolver.borrow();
-        let (layout, resolve_fn) = keyboard_layout_resolver.as_ref()?;
+        let from_layout_map = (|| {
+            let keyboard_layout_resolver = self.keyboard_layout_resolver.borrow();
+            let (layout, resolve_fn) = keyboard_layout_resolver.as_ref()?;
+
+            resolve_fn
+                .call1(layout, &JsValue::from_str(key_code.name()))
+                .ok()?
+                .as_string()
+        })();
 
-        resolve_fn
-            .call1(layout, &JsValue::from_str(key_code.name()))
-            .ok()?
-            .as_string()
+        from_layout_map.or_else(|| {
+            self.observed_keyboard_layout
+                .borrow()
+                .get(key_code)
+                .map(Into::into)
+        })
     }
 }
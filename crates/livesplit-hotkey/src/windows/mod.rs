@@ -1,7 +1,10 @@
-use crate::{ConsumePreference, Hotkey, KeyCode, Modifiers, Result};
+use crate::{callbacks::Callbacks, ConsumePreference, Hotkey, KeyCode, Modifiers, Result};
 use std::{
     cell::RefCell,
-    collections::hash_map::{Entry, HashMap},
+    collections::{
+        hash_map::{Entry, HashMap},
+        HashSet,
+    },
     fmt, mem, ptr,
     sync::{
         mpsc::{channel, Sender},
@@ -45,11 +48,10 @@ impl fmt::Display for Error {
     }
 }
 
-type Callback = Box<dyn FnMut() + Send + 'static>;
-
 pub struct Hook {
     thread_id: u32,
-    hotkeys: Arc<Mutex<HashMap<Hotkey, Callback>>>,
+    hotkeys: Arc<Mutex<HashMap<Hotkey, Callbacks>>>,
+    consumed: Arc<Mutex<HashSet<Hotkey>>>,
 }
 
 impl Drop for Hook {
@@ -60,13 +62,24 @@ impl Drop for Hook {
     }
 }
 
+enum KeyEvent {
+    Pressed(Hotkey),
+    Released(KeyCode),
+}
+
 struct State {
     hook: HHOOK,
-    events: Sender<Hotkey>,
+    events: Sender<KeyEvent>,
     modifiers: Modifiers,
     // FIXME: Use variant count when it's stable.
     // https://github.com/rust-lang/rust/issues/73662
     key_state: [u8; 256 / 8],
+    // The hotkeys whose key events should not be passed on to the other
+    // applications.
+    consumed: Arc<Mutex<HashSet<Hotkey>>>,
+    // The keys that are currently held down and got consumed. Their repeated
+    // key down events and the key up event need to be consumed as well.
+    consumed_keys: [u8; 256 / 8],
 }
 
 // This static assert ensures we have enough states to represent all key codes.
@@ -253,8 +266,9 @@ const fn parse_scan_code(value: u32) -> Option<KeyCode> {
 }
 
 unsafe extern "system" fn callback_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
-    let hook = STATE.with_borrow_mut(|state| {
+    let (hook, consume) = STATE.with_borrow_mut(|state| {
         let state = state.as_mut().expect("State should be initialized by now");
+        let mut consume = false;
 
         if code >= 0 {
             let hook_struct = &*(lparam as *const KBDLLHOOKSTRUCT);
@@ -289,12 +303,19 @@ unsafe extern "system" fn callback_proc(code: i32, wparam: WPARAM, lparam: LPARA
                     if state.key_state[idx as usize] & bit == 0 {
                         state.key_state[idx as usize] |= bit;
 
+                        let hotkey = Hotkey {
+                            key_code,
+                            modifiers: state.modifiers,
+                        };
+
+                        if state.consumed.lock().unwrap().contains(&hotkey) {
+                            state.consumed_keys[idx as usize] |= bit;
+                            consume = true;
+                        }
+
                         state
                             .events
-                            .send(Hotkey {
-                                key_code,
-                                modifiers: state.modifiers,
-                            })
+                            .send(KeyEvent::Pressed(hotkey))
                             .expect("Callback Thread disconnected");
 
                         match key_code {
@@ -312,6 +333,9 @@ unsafe extern "system" fn callback_proc(code: i32, wparam: WPARAM, lparam: LPARA
                             }
                             _ => {}
                         }
+                    } else if state.consumed_keys[idx as usize] & bit != 0 {
+                        // The key is repeating while being held down.
+                        consume = true;
                     }
                 }
             } else if event == WM_KEYUP || event == WM_SYSKEYUP {
@@ -341,7 +365,19 @@ unsafe extern "system" fn callback_proc(code: i32, wparam: WPARAM, lparam: LPARA
 
                 if let Some(key_code) = parse_scan_code(scan_code) {
                     let (idx, bit) = key_idx(key_code);
-                    state.key_state[idx as usize] &= !bit;
+                    if state.consumed_keys[idx as usize] & bit != 0 {
+                        state.consumed_keys[idx as usize] &= !bit;
+                        consume = true;
+                    }
+
+                    if state.key_state[idx as usize] & bit != 0 {
+                        state.key_state[idx as usize] &= !bit;
+
+                        state
+                            .events
+                            .send(KeyEvent::Released(key_code))
+                            .expect("Callback Thread disconnected");
+                    }
 
                     match key_code {
                         KeyCode::AltLeft | KeyCode::AltRight => {
@@ -362,9 +398,15 @@ unsafe extern "system" fn callback_proc(code: i32, wparam: WPARAM, lparam: LPARA
             }
         }
 
-        state.hook
+        (state.hook, consume)
     });
 
+    if consume {
+        // Returning a non-zero value prevents the event from being passed on
+        // to the rest of the hook chain and the target window.
+        return 1;
+    }
+
     CallNextHookEx(hook, code, wparam, lparam)
 }
 
@@ -380,10 +422,9 @@ impl Hook {
             return Err(crate::Error::UnmatchedPreference);
         }
 
-        let hotkeys = Arc::new(Mutex::new(HashMap::<
-            Hotkey,
-            Box<dyn FnMut() + Send + 'static>,
-        >::new()));
+        let hotkeys = Arc::new(Mutex::new(HashMap::<Hotkey, Callbacks>::new()));
+        let consumed = Arc::new(Mutex::new(HashSet::new()));
+        let thread_consumed = consumed.clone();
 
         let (initialized_tx, initialized_rx) = channel();
         let (events_tx, events_rx) = channel();
@@ -416,6 +457,8 @@ impl Hook {
                     events: events_tx,
                     modifiers: Modifiers::empty(),
                     key_state: Default::default(),
+                    consumed: thread_consumed,
+                    consumed_keys: Default::default(),
                 });
 
                 Ok(())
@@ -442,9 +485,26 @@ impl Hook {
         let hotkey_map = hotkeys.clone();
 
         thread::spawn(move || {
-            while let Ok(key) = events_rx.recv() {
-                if let Some(callback) = hotkey_map.lock().unwrap().get_mut(&key) {
-                    callback();
+            // The modifiers may change while a key is held down, so we need to
+            // remember which hotkey got pressed in order to notify the same
+            // hotkey about the key being released.
+            let mut held_keys = HashMap::new();
+
+            while let Ok(event) = events_rx.recv() {
+                match event {
+                    KeyEvent::Pressed(key) => {
+                        if let Some(callbacks) = hotkey_map.lock().unwrap().get_mut(&key) {
+                            (callbacks.press)();
+                            held_keys.insert(key.key_code, key);
+                        }
+                    }
+                    KeyEvent::Released(key_code) => {
+                        if let Some(key) = held_keys.remove(&key_code) {
+                            if let Some(callbacks) = hotkey_map.lock().unwrap().get_mut(&key) {
+                                callbacks.release();
+                            }
+                        }
+                    }
                 }
             }
         });
@@ -453,15 +513,19 @@ impl Hook {
             .recv()
             .map_err(|_| crate::Error::Platform(Error::ThreadStopped))??;
 
-        Ok(Hook { thread_id, hotkeys })
+        Ok(Hook {
+            thread_id,
+            hotkeys,
+            consumed,
+        })
     }
 
-    pub fn register<F>(&self, hotkey: Hotkey, callback: F) -> Result<()>
-    where
-        F: FnMut() + Send + 'static,
-    {
+    pub fn register(&self, hotkey: Hotkey, callbacks: Callbacks) -> Result<()> {
         if let Entry::Vacant(vacant) = self.hotkeys.lock().unwrap().entry(hotkey) {
-            vacant.insert(Box::new(callback));
+            if callbacks.consume {
+                self.consumed.lock().unwrap().insert(hotkey);
+            }
+            vacant.insert(callbacks);
             Ok(())
         } else {
             Err(crate::Error::AlreadyRegistered)
@@ -470,6 +534,7 @@ impl Hook {
 
     pub fn unregister(&self, hotkey: Hotkey) -> Result<()> {
         if self.hotkeys.lock().unwrap().remove(&hotkey).is_some() {
+            self.consumed.lock().unwrap().remove(&hotkey);
             Ok(())
         } else {
             Err(crate::Error::NotRegistered)
@@ -0,0 +1,139 @@
+//! Produces a concise sentence describing the current state of the timer, such
+//! as `Segment 5 of 20, 3.2 seconds ahead of Personal Best.`, that is suitable
+//! for screen readers and text to speech. The sentence only changes when the
+//! timer gets started, splits, gets paused or resets, so it doesn't flood the
+//! user with announcements while the timer is running.
+
+use crate::{analysis, platform::prelude::*, TimeSpan, Timer, TimerPhase};
+use core::fmt::{self, Write};
+
+/// Tracks the sentence describing the current state of the timer, so that
+/// frontends can find out when it changed and needs to be announced again.
+#[derive(Debug, Default, Clone)]
+pub struct AccessibleSummary {
+    text: String,
+}
+
+impl AccessibleSummary {
+    /// Creates a new Accessible Summary that doesn't describe anything yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the sentence based on the timer provided. Returns `true` if
+    /// the sentence changed and should be announced.
+    pub fn update(&mut self, timer: &Timer) -> bool {
+        let text = describe(timer);
+        if text != self.text {
+            self.text = text;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Accesses the most recent sentence describing the state of the timer.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Describes the current state of the timer as a concise sentence. The delta
+/// to the current comparison is the one of the most recent split, so the
+/// sentence stays the same while the timer is running.
+pub fn describe(timer: &Timer) -> String {
+    let mut text = String::new();
+    let _ = write_description(&mut text, timer);
+    text
+}
+
+fn write_description(text: &mut String, timer: &Timer) -> fmt::Result {
+    let run = timer.run();
+    let len = run.len();
+    let comparison = timer.current_comparison();
+    let method = timer.current_timing_method();
+
+    let (index, delta) = match timer.current_phase() {
+        TimerPhase::NotRunning => {
+            write!(text, "Not running, {len} ")?;
+            text.push_str(if len == 1 { "segment" } else { "segments" });
+            return write!(text, ", comparing against {comparison}.");
+        }
+        TimerPhase::Ended => {
+            text.push_str("Finished");
+            let delta = analysis::last_delta(run, len - 1, comparison, method);
+            (len, delta)
+        }
+        phase => {
+            let index = timer.current_split_index().unwrap();
+            text.push_str(if phase == TimerPhase::Paused {
+                "Paused at segment"
+            } else {
+                "Segment"
+            });
+            write!(text, " {} of {len}", index + 1)?;
+            let delta = index
+                .checked_sub(1)
+                .and_then(|i| analysis::last_delta(run, i, comparison, method));
+            (index, delta)
+        }
+    };
+
+    if let Some(delta) = delta {
+        text.push_str(", ");
+        let tenths = round_to_tenths(delta);
+        if tenths == 0 {
+            write!(text, "even with {comparison}")?;
+        } else {
+            write_duration(text, TimeSpan::from_seconds(tenths.abs() as f64 / 10.0))?;
+            let direction = if tenths < 0 { "ahead of" } else { "behind" };
+            write!(text, " {direction} {comparison}")?;
+        }
+    } else if index > 0 && timer.current_phase() != TimerPhase::Ended {
+        write!(text, ", no comparison time")?;
+    }
+
+    text.push('.');
+    Ok(())
+}
+
+fn round_to_tenths(time: TimeSpan) -> i64 {
+    // f64::round is not available without std, so this rounds half away from
+    // zero by hand.
+    let tenths = time.total_seconds() * 10.0;
+    (if tenths < 0.0 {
+        tenths - 0.5
+    } else {
+        tenths + 0.5
+    }) as i64
+}
+
+fn write_duration(text: &mut String, duration: TimeSpan) -> fmt::Result {
+    let tenths = round_to_tenths(duration).unsigned_abs();
+    let hours = tenths / 36_000;
+    let minutes = tenths / 600 % 60;
+    let seconds = tenths % 600;
+
+    let mut separator = "";
+    for (value, unit) in [(hours, "hour"), (minutes, "minute")] {
+        if value > 0 {
+            write!(text, "{separator}{value} {unit}")?;
+            if value != 1 {
+                text.push('s');
+            }
+            separator = " ";
+        }
+    }
+
+    if seconds > 0 || separator.is_empty() {
+        text.push_str(separator);
+        if seconds % 10 == 0 {
+            write!(text, "{}", seconds / 10)?;
+        } else {
+            write!(text, "{}.{}", seconds / 10, seconds % 10)?;
+        }
+        text.push_str(if seconds == 10 { " second" } else { " seconds" });
+    }
+
+    Ok(())
+}
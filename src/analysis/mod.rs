@@ -1,15 +1,19 @@
 //! The analysis module provides a variety of functions for calculating
 //! information about a [`Run`](crate::run::Run).
 
+pub mod accessible_summary;
 pub mod current_pace;
 pub mod delta;
 pub mod pb_chance;
 pub mod possible_time_save;
+pub mod run_collection;
+pub mod segment_statistics;
 mod skill_curve;
 pub mod state_helper;
 pub mod sum_of_segments;
 pub mod total_playtime;
 
+pub use self::accessible_summary::AccessibleSummary;
 pub use self::skill_curve::SkillCurve;
 pub use self::state_helper::*;
 
@@ -0,0 +1,291 @@
+//! Provides functionality for aggregating statistics across a whole collection
+//! of [`Runs`](Run), such as all the splits files of a runner. This includes
+//! the total playtime, the amount of attempts per game and category and a
+//! timeline of all the Personal Bests that got achieved. The statistics can be
+//! limited to a specific period of time, which allows building features like a
+//! yearly review of all the speedruns done.
+
+use super::total_playtime;
+use crate::{platform::prelude::*, DateTime, Run, TimeSpan, TimingMethod};
+use core::cmp::Reverse;
+use serde::Serializer;
+use serde_derive::Serialize;
+
+/// A collection of runs to aggregate statistics for. The runs don't need to be
+/// related in any way.
+#[derive(Debug, Clone, Default)]
+pub struct RunCollection {
+    runs: Vec<Run>,
+}
+
+/// The statistics aggregated across all the runs of a [`RunCollection`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CollectionStatistics {
+    /// The total playtime across all the games.
+    #[serde(serialize_with = "serialize_seconds")]
+    pub total_playtime: TimeSpan,
+    /// The amount of attempts across all the games.
+    pub attempt_count: u32,
+    /// The amount of finished attempts across all the games.
+    pub finished_attempt_count: u32,
+    /// The statistics for each game, sorted by the total playtime, with the
+    /// most played game first.
+    pub games: Vec<GameStatistics>,
+    /// All the Personal Bests that got achieved, sorted by the date they got
+    /// achieved at. Personal Bests without a known date come first.
+    pub personal_bests: Vec<PersonalBest>,
+}
+
+/// The statistics aggregated for a single game.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameStatistics {
+    /// The name of the game.
+    pub game_name: String,
+    /// The total playtime across all the categories of the game.
+    #[serde(serialize_with = "serialize_seconds")]
+    pub total_playtime: TimeSpan,
+    /// The amount of attempts across all the categories of the game.
+    pub attempt_count: u32,
+    /// The amount of finished attempts across all the categories of the game.
+    pub finished_attempt_count: u32,
+    /// The statistics for each category of the game, sorted by the total
+    /// playtime, with the most played category first.
+    pub categories: Vec<CategoryStatistics>,
+}
+
+/// The statistics aggregated for a single category of a game.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CategoryStatistics {
+    /// The name of the category.
+    pub category_name: String,
+    /// The total playtime of the category.
+    #[serde(serialize_with = "serialize_seconds")]
+    pub total_playtime: TimeSpan,
+    /// The amount of attempts of the category.
+    pub attempt_count: u32,
+    /// The amount of finished attempts of the category.
+    pub finished_attempt_count: u32,
+    /// The fastest finished attempt of the category. This is `None` if there
+    /// are no finished attempts.
+    #[serde(serialize_with = "serialize_optional_seconds")]
+    pub personal_best: Option<TimeSpan>,
+}
+
+/// A Personal Best that got achieved in one of the runs of the collection.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PersonalBest {
+    /// The name of the game.
+    pub game_name: String,
+    /// The name of the category.
+    pub category_name: String,
+    /// The final time of the attempt.
+    #[serde(serialize_with = "serialize_seconds")]
+    pub time: TimeSpan,
+    /// The point in time the attempt ended. If that is not known, the point
+    /// in time it started is used instead. This is `None` if neither is known.
+    #[serde(serialize_with = "serialize_optional_date")]
+    pub date: Option<DateTime>,
+}
+
+impl RunCollection {
+    /// Creates a new empty collection of runs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a run to the collection.
+    pub fn push(&mut self, run: Run) {
+        self.runs.push(run);
+    }
+
+    /// Accesses all the runs of the collection.
+    pub fn runs(&self) -> &[Run] {
+        &self.runs
+    }
+
+    /// Returns the amount of runs in the collection.
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Returns `true` if there are no runs in the collection.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Loads all the splits files stored directly in the directory provided.
+    /// Files that can't be read or that are not splits files in any of the
+    /// supported formats are skipped. Subdirectories are not searched.
+    #[cfg(feature = "std")]
+    pub fn load_directory(path: &std::path::Path) -> std::io::Result<Self> {
+        use crate::run::parser::composite;
+
+        let mut collection = Self::new();
+        let mut paths = std::fs::read_dir(path)?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.is_file())
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        for path in paths {
+            let Ok(source) = std::fs::read(&path) else {
+                continue;
+            };
+            if let Ok(parsed) = composite::parse_and_fix(&source, Some(&path)) {
+                collection.push(parsed.run);
+            }
+        }
+
+        Ok(collection)
+    }
+
+    /// Aggregates the statistics across all the runs of the collection. Only
+    /// the attempts stored in the attempt histories are considered. Runs of the
+    /// same game and category are combined. The timing method is used for
+    /// determining the Personal Bests. If a period of time is specified, only
+    /// the attempts started within it are considered. The start of the period
+    /// is inclusive, while the end is exclusive. Attempts without a known start
+    /// date are only considered if no period is specified.
+    pub fn statistics(
+        &self,
+        method: TimingMethod,
+        since: Option<DateTime>,
+        until: Option<DateTime>,
+    ) -> CollectionStatistics {
+        let mut games = Vec::<GameStatistics>::new();
+        let mut finished_attempts = Vec::new();
+
+        for run in &self.runs {
+            let game_index = match games.iter().position(|g| g.game_name == run.game_name()) {
+                Some(index) => index,
+                None => {
+                    games.push(GameStatistics {
+                        game_name: run.game_name().into(),
+                        total_playtime: TimeSpan::zero(),
+                        attempt_count: 0,
+                        finished_attempt_count: 0,
+                        categories: Vec::new(),
+                    });
+                    games.len() - 1
+                }
+            };
+
+            let categories = &mut games[game_index].categories;
+            let category_index = match categories
+                .iter()
+                .position(|c| c.category_name == run.category_name())
+            {
+                Some(index) => index,
+                None => {
+                    categories.push(CategoryStatistics {
+                        category_name: run.category_name().into(),
+                        total_playtime: TimeSpan::zero(),
+                        attempt_count: 0,
+                        finished_attempt_count: 0,
+                        personal_best: None,
+                    });
+                    categories.len() - 1
+                }
+            };
+            let category = &mut categories[category_index];
+
+            for attempt in run.attempt_history() {
+                if since.is_some() || until.is_some() {
+                    let Some(started) = attempt.started() else {
+                        continue;
+                    };
+                    if since.is_some_and(|since| started.time < since)
+                        || until.is_some_and(|until| started.time >= until)
+                    {
+                        continue;
+                    }
+                }
+
+                category.total_playtime += total_playtime::attempt_playtime(run, attempt);
+                category.attempt_count += 1;
+
+                if let Some(time) = attempt.time()[method] {
+                    category.finished_attempt_count += 1;
+                    let date = attempt.ended().or(attempt.started()).map(|d| d.time);
+                    finished_attempts.push((game_index, category_index, date, time));
+                }
+            }
+        }
+
+        // The runs of the same category may be listed in any order and their
+        // attempt histories may overlap, so the Personal Bests are only
+        // determined once all the attempts of a category are known and sorted
+        // chronologically. Attempts without a known date are considered first.
+        finished_attempts.sort_by_key(|&(game, category, date, _)| (game, category, date));
+
+        let mut personal_bests = Vec::new();
+        for (game_index, category_index, date, time) in finished_attempts {
+            let game = &mut games[game_index];
+            let category = &mut game.categories[category_index];
+            if category.personal_best.map_or(true, |pb| time < pb) {
+                category.personal_best = Some(time);
+                personal_bests.push(PersonalBest {
+                    game_name: game.game_name.clone(),
+                    category_name: category.category_name.clone(),
+                    time,
+                    date,
+                });
+            }
+        }
+
+        let mut statistics = CollectionStatistics {
+            total_playtime: TimeSpan::zero(),
+            attempt_count: 0,
+            finished_attempt_count: 0,
+            games,
+            personal_bests,
+        };
+
+        for game in &mut statistics.games {
+            for category in &game.categories {
+                game.total_playtime += category.total_playtime;
+                game.attempt_count += category.attempt_count;
+                game.finished_attempt_count += category.finished_attempt_count;
+            }
+            game.categories
+                .sort_by_key(|category| Reverse(category.total_playtime));
+
+            statistics.total_playtime += game.total_playtime;
+            statistics.attempt_count += game.attempt_count;
+            statistics.finished_attempt_count += game.finished_attempt_count;
+        }
+
+        statistics
+            .games
+            .sort_by_key(|game| Reverse(game.total_playtime));
+        statistics.personal_bests.sort_by_key(|pb| pb.date);
+
+        statistics
+    }
+}
+
+impl CollectionStatistics {
+    /// Encodes the statistics as JSON. All the times are encoded as seconds and
+    /// all the dates are encoded as Unix timestamps in seconds.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+fn serialize_seconds<S: Serializer>(time: &TimeSpan, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(time.total_seconds())
+}
+
+fn serialize_optional_seconds<S: Serializer>(
+    time: &Option<TimeSpan>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&time.map(|t| t.total_seconds()), serializer)
+}
+
+fn serialize_optional_date<S: Serializer>(
+    date: &Option<DateTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&date.map(|d| d.unix_timestamp()), serializer)
+}
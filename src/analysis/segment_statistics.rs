@@ -0,0 +1,56 @@
+//! Provides functionality to calculate statistics about the segment times a
+//! runner achieved for a single [`Segment`], such as the average or the median
+//! segment time. Only the segment times that were actually achieved by the
+//! runner are taken into account.
+
+use crate::{platform::prelude::*, Segment, TimeSpan, TimingMethod};
+
+/// Statistics about the segment times of a [`Segment`].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct SegmentStatistics {
+    /// The amount of segment times the statistics are based on.
+    pub count: usize,
+    /// The fastest segment time.
+    pub best: Option<TimeSpan>,
+    /// The slowest segment time.
+    pub worst: Option<TimeSpan>,
+    /// The average of all the segment times.
+    pub average: Option<TimeSpan>,
+    /// The median of all the segment times. If there's an even amount of
+    /// segment times, this is the average of the two in the middle.
+    pub median: Option<TimeSpan>,
+}
+
+/// Calculates the statistics of the segment times of the segment for the
+/// timing method provided.
+pub fn calculate(segment: &Segment, method: TimingMethod) -> SegmentStatistics {
+    let mut times = segment
+        .segment_history()
+        .iter_actual_runs()
+        .filter_map(|(_, time)| time[method])
+        .collect::<Vec<_>>();
+
+    if times.is_empty() {
+        return SegmentStatistics::default();
+    }
+
+    times.sort_unstable();
+
+    let count = times.len();
+    let total = times.iter().map(TimeSpan::total_seconds).sum::<f64>();
+    let median = if count % 2 == 0 {
+        TimeSpan::from_seconds(
+            0.5 * (times[count / 2 - 1].total_seconds() + times[count / 2].total_seconds()),
+        )
+    } else {
+        times[count / 2]
+    };
+
+    SegmentStatistics {
+        count,
+        best: times.first().copied(),
+        worst: times.last().copied(),
+        average: Some(TimeSpan::from_seconds(total / count as f64)),
+        median: Some(median),
+    }
+}
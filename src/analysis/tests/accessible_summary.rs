@@ -0,0 +1,62 @@
+use crate::{
+    analysis::{accessible_summary::describe, AccessibleSummary},
+    util::tests_helper::{
+        create_timer, make_progress_run_with_splits_opt, run_with_splits, start_run,
+    },
+    TimeSpan,
+};
+
+#[test]
+fn describes_progress_against_comparison() {
+    let mut timer = create_timer(&["A", "B", "C"]);
+    assert_eq!(
+        describe(&timer),
+        "Not running, 3 segments, comparing against Personal Best."
+    );
+
+    run_with_splits(&mut timer, &[10.0, 20.0, 30.0]);
+
+    start_run(&mut timer);
+    assert_eq!(describe(&timer), "Segment 1 of 3.");
+
+    make_progress_run_with_splits_opt(&mut timer, &[Some(7.0)]);
+    assert_eq!(
+        describe(&timer),
+        "Segment 2 of 3, 3 seconds ahead of Personal Best."
+    );
+
+    make_progress_run_with_splits_opt(&mut timer, &[Some(23.5)]);
+    assert_eq!(
+        describe(&timer),
+        "Segment 3 of 3, 3.5 seconds behind Personal Best."
+    );
+
+    timer.pause().unwrap();
+    assert_eq!(
+        describe(&timer),
+        "Paused at segment 3 of 3, 3.5 seconds behind Personal Best."
+    );
+    timer.resume().unwrap();
+
+    make_progress_run_with_splits_opt(&mut timer, &[Some(91.0)]);
+    assert_eq!(
+        describe(&timer),
+        "Finished, 1 minute 1 second behind Personal Best."
+    );
+}
+
+#[test]
+fn only_reports_changes() {
+    let mut timer = create_timer(&["A", "B"]);
+    run_with_splits(&mut timer, &[10.0, 20.0]);
+
+    let mut summary = AccessibleSummary::new();
+    assert!(summary.update(&timer));
+    assert!(!summary.update(&timer));
+
+    start_run(&mut timer);
+    assert!(summary.update(&timer));
+    timer.set_game_time(TimeSpan::from_seconds(5.0)).unwrap();
+    assert!(!summary.update(&timer));
+    assert_eq!(summary.text(), "Segment 1 of 2.");
+}
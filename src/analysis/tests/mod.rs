@@ -1,2 +1,5 @@
+mod accessible_summary;
 mod empty_run;
+mod run_collection;
+mod segment_statistics;
 mod semantic_colors;
@@ -0,0 +1,130 @@
+use crate::{
+    analysis::run_collection::RunCollection,
+    util::tests_helper::{create_timer, run_with_splits_opt, span},
+    AtomicDateTime, Run, Time, TimingMethod,
+};
+use time::Duration;
+
+fn create_run(game: &str, category: &str, attempts: &[&[Option<f64>]]) -> Run {
+    let mut timer = create_timer(&["A", "B"]);
+    for splits in attempts {
+        run_with_splits_opt(&mut timer, splits);
+    }
+    let mut run = timer.into_run(true);
+    run.set_game_name(game);
+    run.set_category_name(category);
+    run
+}
+
+fn create_collection() -> RunCollection {
+    let mut collection = RunCollection::new();
+    collection.push(create_run(
+        "Game",
+        "Any%",
+        &[
+            &[Some(3.0), Some(6.0)],
+            &[Some(2.0)],
+            &[Some(2.0), Some(5.0)],
+        ],
+    ));
+    collection.push(create_run("Other Game", "100%", &[&[Some(1.0), Some(9.0)]]));
+    collection.push(create_run("Game", "Any%", &[&[Some(1.0), Some(4.0)]]));
+    collection
+}
+
+#[test]
+fn aggregates_attempts_per_game_and_category() {
+    let statistics = create_collection().statistics(TimingMethod::GameTime, None, None);
+
+    assert_eq!(statistics.attempt_count, 5);
+    assert_eq!(statistics.finished_attempt_count, 4);
+    assert_eq!(statistics.games.len(), 2);
+
+    let game = statistics
+        .games
+        .iter()
+        .find(|g| g.game_name == "Game")
+        .unwrap();
+    assert_eq!(game.attempt_count, 4);
+    assert_eq!(game.finished_attempt_count, 3);
+    assert_eq!(game.categories.len(), 1);
+    assert_eq!(game.categories[0].personal_best, Some(span(4.0)));
+}
+
+#[test]
+fn tracks_the_personal_best_timeline() {
+    let statistics = create_collection().statistics(TimingMethod::GameTime, None, None);
+
+    let mut times = statistics
+        .personal_bests
+        .iter()
+        .map(|pb| (pb.game_name.as_str(), pb.time))
+        .collect::<Vec<_>>();
+    times.sort_by_key(|&(_, time)| core::cmp::Reverse(time));
+
+    assert_eq!(
+        times,
+        [
+            ("Other Game", span(9.0)),
+            ("Game", span(6.0)),
+            ("Game", span(5.0)),
+            ("Game", span(4.0)),
+        ],
+    );
+    assert!(statistics.personal_bests.iter().all(|pb| pb.date.is_some()));
+}
+
+#[test]
+fn determines_personal_bests_chronologically_across_runs() {
+    let now = crate::platform::utc_now();
+    let create_run = |attempts: &[(i64, f64)]| {
+        let mut run = Run::new();
+        run.set_game_name("Game");
+        run.set_category_name("Any%");
+        for &(days_ago, time) in attempts {
+            let ended = AtomicDateTime::new(now - Duration::days(days_ago), false);
+            let time = Time::new().with_game_time(Some(span(time)));
+            run.add_attempt(time, Some(ended), Some(ended), None);
+        }
+        run
+    };
+
+    let mut collection = RunCollection::new();
+    collection.push(create_run(&[(2, 5.0), (1, 3.0)]));
+    collection.push(create_run(&[(4, 6.0), (3, 4.0)]));
+
+    let statistics = collection.statistics(TimingMethod::GameTime, None, None);
+    let times = statistics
+        .personal_bests
+        .iter()
+        .map(|pb| pb.time)
+        .collect::<Vec<_>>();
+    assert_eq!(times, [span(6.0), span(4.0), span(3.0)]);
+}
+
+#[test]
+fn only_considers_attempts_within_the_period() {
+    let collection = create_collection();
+    let now = crate::platform::utc_now();
+
+    let statistics =
+        collection.statistics(TimingMethod::GameTime, Some(now + Duration::days(1)), None);
+    assert_eq!(statistics.attempt_count, 0);
+    assert!(statistics.personal_bests.is_empty());
+
+    let statistics = collection.statistics(
+        TimingMethod::GameTime,
+        Some(now - Duration::days(1)),
+        Some(now + Duration::days(1)),
+    );
+    assert_eq!(statistics.attempt_count, 5);
+}
+
+#[test]
+fn encodes_the_statistics_as_json() {
+    let json = create_collection()
+        .statistics(TimingMethod::GameTime, None, None)
+        .to_json();
+    assert!(json.contains(r#""game_name":"Other Game""#));
+    assert!(json.contains(r#""personal_best":9.0"#));
+}
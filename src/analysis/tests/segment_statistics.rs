@@ -0,0 +1,39 @@
+use super::super::segment_statistics::calculate;
+use crate::{Segment, Time, TimeSpan, TimingMethod};
+
+fn time(seconds: f64) -> Time {
+    Time::new().with_real_time(Some(TimeSpan::from_seconds(seconds)))
+}
+
+#[test]
+fn empty_history() {
+    let segment = Segment::new("Segment");
+    let statistics = calculate(&segment, TimingMethod::RealTime);
+    assert_eq!(statistics.count, 0);
+    assert_eq!(statistics.best, None);
+    assert_eq!(statistics.median, None);
+}
+
+#[test]
+fn statistics() {
+    let mut segment = Segment::new("Segment");
+    let history = segment.segment_history_mut();
+    // Segment times with an index below 1 are not actually achieved by the
+    // runner, so they are ignored.
+    history.insert(0, time(1.0));
+    history.insert(1, time(12.0));
+    history.insert(2, time(10.0));
+    history.insert(3, Time::new());
+    history.insert(4, time(20.0));
+    history.insert(5, time(14.0));
+
+    let statistics = calculate(&segment, TimingMethod::RealTime);
+    assert_eq!(statistics.count, 4);
+    assert_eq!(statistics.best, Some(TimeSpan::from_seconds(10.0)));
+    assert_eq!(statistics.worst, Some(TimeSpan::from_seconds(20.0)));
+    assert_eq!(statistics.average, Some(TimeSpan::from_seconds(14.0)));
+    assert_eq!(statistics.median, Some(TimeSpan::from_seconds(13.0)));
+
+    let statistics = calculate(&segment, TimingMethod::GameTime);
+    assert_eq!(statistics.count, 0);
+}
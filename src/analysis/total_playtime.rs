@@ -3,7 +3,7 @@
 //! history are summed together. For a [`Timer`], the current attempt's duration
 //! is also factored in.
 
-use crate::{Run, TimeSpan, Timer, TimingMethod};
+use crate::{Attempt, Run, TimeSpan, Timer, TimingMethod};
 /// Allows calculating the total playtime.
 pub trait TotalPlaytime {
     /// Calculates the total playtime.
@@ -15,31 +15,35 @@ impl TotalPlaytime for Run {
         let mut total_playtime = TimeSpan::zero();
 
         for attempt in self.attempt_history() {
-            if let Some(duration) = attempt.duration() {
-                // Either >= 1.6.0 or a finished run
-                total_playtime += duration;
-                if let Some(pause_time) = attempt.pause_time() {
-                    total_playtime -= pause_time;
-                }
-            } else {
-                // Must be < 1.6.0 and a reset
-                // Calculate the sum of the segments for that run
-                for segment in self.segments() {
-                    if let Some(segment_time) = segment
-                        .segment_history()
-                        .get(attempt.index())
-                        .and_then(|s| s[TimingMethod::RealTime])
-                    {
-                        total_playtime += segment_time;
-                    }
-                }
-            }
+            total_playtime += attempt_playtime(self, attempt);
         }
 
         total_playtime
     }
 }
 
+/// Calculates the playtime of a single attempt of the run.
+pub(super) fn attempt_playtime(run: &Run, attempt: &Attempt) -> TimeSpan {
+    if let Some(duration) = attempt.duration() {
+        // Either >= 1.6.0 or a finished run
+        duration - attempt.pause_time().unwrap_or_default()
+    } else {
+        // Must be < 1.6.0 and a reset
+        // Calculate the sum of the segments for that run
+        let mut playtime = TimeSpan::zero();
+        for segment in run.segments() {
+            if let Some(segment_time) = segment
+                .segment_history()
+                .get(attempt.index())
+                .and_then(|s| s[TimingMethod::RealTime])
+            {
+                playtime += segment_time;
+            }
+        }
+        playtime
+    }
+}
+
 impl TotalPlaytime for Timer {
     fn total_playtime(&self) -> TimeSpan {
         let timer_play_time =
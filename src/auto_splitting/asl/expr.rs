@@ -0,0 +1,725 @@
+//! The subset of C# that is supported within the action blocks of ASL
+//! scripts. Most action blocks only compare the current and old values of the
+//! state variables, so statements like `return current.level != old.level;`,
+//! `if` statements, assignments to `vars` and locals, and the usual operators
+//! are supported. Everything else results in an error, either while parsing or
+//! while evaluating the block.
+
+use std::collections::HashMap;
+
+use snafu::Snafu;
+
+use crate::TimeSpan;
+
+/// An error that can occur while parsing an ASL script.
+#[derive(Debug, Snafu)]
+pub enum ParseError {
+    /// The script contains a character that is not valid at this point.
+    UnexpectedCharacter {
+        /// The line the character is in.
+        line: usize,
+    },
+    /// The script contains a token that is not valid at this point or that is
+    /// not supported.
+    UnexpectedToken {
+        /// The line the token is in.
+        line: usize,
+    },
+    /// The script ended unexpectedly.
+    UnexpectedEnd,
+    /// The type of a state variable is not supported.
+    UnsupportedType {
+        /// The line the state variable is declared in.
+        line: usize,
+    },
+}
+
+/// An error that can occur while executing an action block of an ASL script.
+#[derive(Debug, Snafu)]
+pub enum EvalError {
+    /// The variable is not known.
+    UnknownVariable {
+        /// The name of the variable.
+        name: String,
+    },
+    /// The function is not supported.
+    UnknownFunction {
+        /// The name of the function.
+        name: String,
+    },
+    /// An operation was applied to values of types that it doesn't support.
+    TypeMismatch,
+    /// An integer got divided by zero.
+    DivisionByZero,
+}
+
+/// A value that the scripts work with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// A boolean.
+    Bool(bool),
+    /// Any kind of integer.
+    Int(i64),
+    /// Any kind of floating point number.
+    Float(f64),
+    /// A string.
+    String(String),
+    /// A duration, as used for the game time.
+    TimeSpan(TimeSpan),
+}
+
+impl Value {
+    pub(super) fn as_bool(&self) -> Result<bool, EvalError> {
+        match self {
+            Value::Bool(value) => Ok(*value),
+            _ => Err(EvalError::TypeMismatch),
+        }
+    }
+
+    fn as_float(&self) -> Result<f64, EvalError> {
+        match *self {
+            Value::Int(value) => Ok(value as f64),
+            Value::Float(value) => Ok(value),
+            _ => Err(EvalError::TypeMismatch),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token {
+    Ident(String),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Punct(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Lexed {
+    pub token: Token,
+    pub line: usize,
+}
+
+/// Longer punctuation needs to come first, so it's matched greedily.
+const PUNCTUATION: [&str; 30] = [
+    "&&", "||", "==", "!=", "<=", ">=", "+=", "-=", "++", "--", "(", ")", "{", "}", "[", "]", ";",
+    ",", ".", ":", "?", "!", "<", ">", "+", "-", "*", "/", "%", "=",
+];
+
+pub(super) fn tokenize(source: &str) -> Result<Vec<Lexed>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut rest = source;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '\n' {
+            line += 1;
+            rest = &rest[1..];
+        } else if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if let Some(comment) = rest.strip_prefix("//") {
+            rest = comment.find('\n').map_or("", |end| &comment[end..]);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let end = comment.find("*/").ok_or(ParseError::UnexpectedEnd)?;
+            line += comment[..end].matches('\n').count();
+            rest = &comment[end + 2..];
+        } else if c == '"' {
+            let mut text = String::new();
+            let mut chars = rest[1..].char_indices();
+            loop {
+                match chars.next().ok_or(ParseError::UnexpectedEnd)? {
+                    (i, '"') => {
+                        rest = &rest[i + 2..];
+                        break;
+                    }
+                    (_, '\\') => match chars.next().ok_or(ParseError::UnexpectedEnd)?.1 {
+                        'n' => text.push('\n'),
+                        't' => text.push('\t'),
+                        c => text.push(c),
+                    },
+                    (_, '\n') => return Err(ParseError::UnexpectedCharacter { line }),
+                    (_, c) => text.push(c),
+                }
+            }
+            tokens.push(Lexed {
+                token: Token::Str(text),
+                line,
+            });
+        } else if c.is_ascii_digit() {
+            let (token, len) = number(rest).ok_or(ParseError::UnexpectedCharacter { line })?;
+            tokens.push(Lexed { token, line });
+            rest = &rest[len..];
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            tokens.push(Lexed {
+                token: Token::Ident(rest[..len].into()),
+                line,
+            });
+            rest = &rest[len..];
+        } else {
+            let punct = PUNCTUATION
+                .into_iter()
+                .find(|p| rest.starts_with(p))
+                .ok_or(ParseError::UnexpectedCharacter { line })?;
+            tokens.push(Lexed {
+                token: Token::Punct(punct),
+                line,
+            });
+            rest = &rest[punct.len()..];
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn number(text: &str) -> Option<(Token, usize)> {
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        let len = hex
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(hex.len());
+        let value = u64::from_str_radix(&hex[..len], 16).ok()?;
+        return Some((Token::Int(value as i64), len + 2));
+    }
+
+    let mut len = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let is_float =
+        text[len..].starts_with('.') && text[len + 1..].starts_with(|c: char| c.is_ascii_digit());
+    if is_float {
+        len += 1 + text[len + 1..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len() - len - 1);
+    }
+    let number = &text[..len];
+    // The suffixes only specify the type of the literal.
+    let suffix = text[len..]
+        .find(|c: char| !matches!(c, 'f' | 'F' | 'd' | 'D' | 'u' | 'U' | 'l' | 'L'))
+        .unwrap_or(text.len() - len);
+    let is_float = is_float || text[len..len + suffix].contains(['f', 'F', 'd', 'D']);
+
+    let token = if is_float {
+        Token::Float(number.parse().ok()?)
+    } else {
+        Token::Int(number.parse().ok()?)
+    };
+    Some((token, len + suffix))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum UnaryOp {
+    Not,
+    Neg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinaryOp {
+    fn from_punct(punct: &str) -> Option<(Self, u8)> {
+        Some(match punct {
+            "||" => (Self::Or, 1),
+            "&&" => (Self::And, 2),
+            "==" => (Self::Eq, 3),
+            "!=" => (Self::Ne, 3),
+            "<" => (Self::Lt, 4),
+            "<=" => (Self::Le, 4),
+            ">" => (Self::Gt, 4),
+            ">=" => (Self::Ge, 4),
+            "+" => (Self::Add, 5),
+            "-" => (Self::Sub, 5),
+            "*" => (Self::Mul, 6),
+            "/" => (Self::Div, 6),
+            "%" => (Self::Rem, 6),
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Expr {
+    Literal(Value),
+    Path(Vec<String>),
+    Index(Vec<String>, Box<Expr>),
+    Call(Vec<String>, Vec<Expr>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Stmt {
+    Return(Option<Expr>),
+    If(Expr, Box<Stmt>, Option<Box<Stmt>>),
+    Block(Vec<Stmt>),
+    Assign(Vec<String>, Expr),
+    Expr(Expr),
+}
+
+pub(super) struct Parser<'a> {
+    tokens: &'a [Lexed],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(tokens: &'a [Lexed]) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.tokens.len()
+    }
+
+    pub fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    pub fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(0, |t| t.line)
+    }
+
+    pub fn unexpected(&self) -> ParseError {
+        if self.is_at_end() {
+            ParseError::UnexpectedEnd
+        } else {
+            ParseError::UnexpectedToken { line: self.line() }
+        }
+    }
+
+    pub fn next(&mut self) -> Result<&'a Token, ParseError> {
+        let token = self.peek().ok_or(ParseError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    pub fn eat(&mut self, punct: &str) -> bool {
+        let is_match = matches!(self.peek(), Some(Token::Punct(p)) if *p == punct);
+        if is_match {
+            self.pos += 1;
+        }
+        is_match
+    }
+
+    pub fn expect(&mut self, punct: &str) -> Result<(), ParseError> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    pub fn ident(&mut self) -> Result<&'a str, ParseError> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    pub fn string(&mut self) -> Result<&'a str, ParseError> {
+        match self.peek() {
+            Some(Token::Str(text)) => {
+                self.pos += 1;
+                Ok(text)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    pub fn int(&mut self) -> Result<i64, ParseError> {
+        let negative = self.eat("-");
+        match self.peek() {
+            Some(&Token::Int(value)) => {
+                self.pos += 1;
+                Ok(if negative {
+                    value.wrapping_neg()
+                } else {
+                    value
+                })
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// Parses the statements of a block until its closing brace.
+    pub fn block(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        self.expect("{")?;
+        let mut statements = Vec::new();
+        while !self.eat("}") {
+            statements.push(self.statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Stmt, ParseError> {
+        if matches!(self.peek(), Some(Token::Punct("{"))) {
+            return Ok(Stmt::Block(self.block()?));
+        }
+        if self.eat(";") {
+            return Ok(Stmt::Block(Vec::new()));
+        }
+
+        match self.peek() {
+            Some(Token::Ident(ident)) if ident == "return" => {
+                self.pos += 1;
+                let value = if self.eat(";") {
+                    None
+                } else {
+                    let value = self.expr()?;
+                    self.expect(";")?;
+                    Some(value)
+                };
+                return Ok(Stmt::Return(value));
+            }
+            Some(Token::Ident(ident)) if ident == "if" => {
+                self.pos += 1;
+                self.expect("(")?;
+                let condition = self.expr()?;
+                self.expect(")")?;
+                let then = Box::new(self.statement()?);
+                let otherwise = if matches!(self.peek(), Some(Token::Ident(i)) if i == "else") {
+                    self.pos += 1;
+                    Some(Box::new(self.statement()?))
+                } else {
+                    None
+                };
+                return Ok(Stmt::If(condition, then, otherwise));
+            }
+            Some(Token::Ident(ident)) if ident == "var" => {
+                self.pos += 1;
+                let name = self.ident()?;
+                self.expect("=")?;
+                let value = self.expr()?;
+                self.expect(";")?;
+                return Ok(Stmt::Assign(vec![name.into()], value));
+            }
+            _ => {}
+        }
+
+        let line = self.line();
+        let expr = self.expr()?;
+        let assignment = if self.eat("=") {
+            Some(self.expr()?)
+        } else if self.eat("+=") {
+            Some(Expr::Binary(
+                BinaryOp::Add,
+                Box::new(expr.clone()),
+                Box::new(self.expr()?),
+            ))
+        } else if self.eat("-=") {
+            Some(Expr::Binary(
+                BinaryOp::Sub,
+                Box::new(expr.clone()),
+                Box::new(self.expr()?),
+            ))
+        } else if self.eat("++") {
+            Some(Expr::Binary(
+                BinaryOp::Add,
+                Box::new(expr.clone()),
+                Box::new(Expr::Literal(Value::Int(1))),
+            ))
+        } else if self.eat("--") {
+            Some(Expr::Binary(
+                BinaryOp::Sub,
+                Box::new(expr.clone()),
+                Box::new(Expr::Literal(Value::Int(1))),
+            ))
+        } else {
+            None
+        };
+        self.expect(";")?;
+
+        Ok(match (assignment, expr) {
+            (Some(value), Expr::Path(path)) => Stmt::Assign(path, value),
+            (Some(_), _) => return Err(ParseError::UnexpectedToken { line }),
+            (None, expr) => Stmt::Expr(expr),
+        })
+    }
+
+    pub fn expr(&mut self) -> Result<Expr, ParseError> {
+        let condition = self.binary(0)?;
+        if !self.eat("?") {
+            return Ok(condition);
+        }
+        let then = self.expr()?;
+        self.expect(":")?;
+        let otherwise = self.expr()?;
+        Ok(Expr::Conditional(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> Result<Expr, ParseError> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Punct(punct)) = self.peek() {
+            let Some((op, precedence)) = BinaryOp::from_punct(punct) else {
+                break;
+            };
+            if precedence <= min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.binary(precedence)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat("!") {
+            Ok(Expr::Unary(UnaryOp::Not, Box::new(self.unary()?)))
+        } else if self.eat("-") {
+            Ok(Expr::Unary(UnaryOp::Neg, Box::new(self.unary()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat("(") {
+            let expr = self.expr()?;
+            self.expect(")")?;
+            return Ok(expr);
+        }
+
+        let ident = match self.next()? {
+            Token::Int(value) => return Ok(Expr::Literal(Value::Int(*value))),
+            Token::Float(value) => return Ok(Expr::Literal(Value::Float(*value))),
+            Token::Str(text) => return Ok(Expr::Literal(Value::String(text.clone()))),
+            Token::Ident(ident) if ident == "true" => return Ok(Expr::Literal(Value::Bool(true))),
+            Token::Ident(ident) if ident == "false" => {
+                return Ok(Expr::Literal(Value::Bool(false)))
+            }
+            Token::Ident(ident) => ident,
+            Token::Punct(_) => {
+                self.pos -= 1;
+                return Err(self.unexpected());
+            }
+        };
+
+        let mut path = vec![ident.clone()];
+        while self.eat(".") {
+            path.push(self.ident()?.into());
+        }
+
+        if self.eat("(") {
+            let mut args = Vec::new();
+            if !self.eat(")") {
+                loop {
+                    args.push(self.expr()?);
+                    if self.eat(")") {
+                        break;
+                    }
+                    self.expect(",")?;
+                }
+            }
+            Ok(Expr::Call(path, args))
+        } else if self.eat("[") {
+            let index = self.expr()?;
+            self.expect("]")?;
+            Ok(Expr::Index(path, Box::new(index)))
+        } else {
+            Ok(Expr::Path(path))
+        }
+    }
+}
+
+/// The environment the statements are executed in.
+pub(super) trait Env {
+    fn get(&mut self, path: &[String]) -> Result<Value, EvalError>;
+    fn index(&mut self, path: &[String], index: Value) -> Result<Value, EvalError>;
+    fn set(&mut self, path: &[String], value: Value) -> Result<(), EvalError>;
+    fn call(&mut self, path: &[String], args: Vec<Value>) -> Result<Value, EvalError>;
+}
+
+/// Executes the statements and returns the value of the `return` statement
+/// that got executed, if any.
+pub(super) fn exec(
+    statements: &[Stmt],
+    env: &mut dyn Env,
+    locals: &mut HashMap<String, Value>,
+) -> Result<Option<Option<Value>>, EvalError> {
+    for statement in statements {
+        let returned = match statement {
+            Stmt::Return(value) => {
+                return Ok(Some(match value {
+                    Some(value) => Some(eval(value, env, locals)?),
+                    None => None,
+                }))
+            }
+            Stmt::If(condition, then, otherwise) => {
+                if eval(condition, env, locals)?.as_bool()? {
+                    exec(core::slice::from_ref(&**then), env, locals)?
+                } else if let Some(otherwise) = otherwise {
+                    exec(core::slice::from_ref(&**otherwise), env, locals)?
+                } else {
+                    None
+                }
+            }
+            Stmt::Block(statements) => exec(statements, env, locals)?,
+            Stmt::Assign(path, value) => {
+                let value = eval(value, env, locals)?;
+                match &path[..] {
+                    [name] if locals.contains_key(name) || !is_global(name) => {
+                        locals.insert(name.clone(), value);
+                    }
+                    path => env.set(path, value)?,
+                }
+                None
+            }
+            Stmt::Expr(expr) => {
+                eval(expr, env, locals)?;
+                None
+            }
+        };
+        if returned.is_some() {
+            return Ok(returned);
+        }
+    }
+    Ok(None)
+}
+
+fn is_global(name: &str) -> bool {
+    matches!(name, "version" | "refreshRate")
+}
+
+fn eval(
+    expr: &Expr,
+    env: &mut dyn Env,
+    locals: &mut HashMap<String, Value>,
+) -> Result<Value, EvalError> {
+    Ok(match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Path(path) => match &path[..] {
+            [name] if locals.contains_key(name) => locals[name].clone(),
+            path => env.get(path)?,
+        },
+        Expr::Index(path, index) => {
+            let index = eval(index, env, locals)?;
+            env.index(path, index)?
+        }
+        Expr::Call(path, args) => {
+            let args = args
+                .iter()
+                .map(|arg| eval(arg, env, locals))
+                .collect::<Result<_, _>>()?;
+            env.call(path, args)?
+        }
+        Expr::Unary(op, value) => match (op, eval(value, env, locals)?) {
+            (UnaryOp::Not, Value::Bool(value)) => Value::Bool(!value),
+            (UnaryOp::Neg, Value::Int(value)) => Value::Int(value.wrapping_neg()),
+            (UnaryOp::Neg, Value::Float(value)) => Value::Float(-value),
+            (UnaryOp::Neg, Value::TimeSpan(value)) => Value::TimeSpan(-value),
+            _ => return Err(EvalError::TypeMismatch),
+        },
+        Expr::Binary(BinaryOp::And, lhs, rhs) => {
+            Value::Bool(eval(lhs, env, locals)?.as_bool()? && eval(rhs, env, locals)?.as_bool()?)
+        }
+        Expr::Binary(BinaryOp::Or, lhs, rhs) => {
+            Value::Bool(eval(lhs, env, locals)?.as_bool()? || eval(rhs, env, locals)?.as_bool()?)
+        }
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs = eval(lhs, env, locals)?;
+            let rhs = eval(rhs, env, locals)?;
+            binary(*op, lhs, rhs)?
+        }
+        Expr::Conditional(condition, then, otherwise) => {
+            if eval(condition, env, locals)?.as_bool()? {
+                eval(then, env, locals)?
+            } else {
+                eval(otherwise, env, locals)?
+            }
+        }
+    })
+}
+
+fn binary(op: BinaryOp, lhs: Value, rhs: Value) -> Result<Value, EvalError> {
+    use core::cmp::Ordering;
+
+    let ordering = |lhs: &Value, rhs: &Value| -> Result<Option<Ordering>, EvalError> {
+        Ok(match (lhs, rhs) {
+            (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+            (Value::TimeSpan(a), Value::TimeSpan(b)) => Some(a.cmp(b)),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            (a, b) => a.as_float()?.partial_cmp(&b.as_float()?),
+        })
+    };
+
+    Ok(match op {
+        BinaryOp::Eq => Value::Bool(ordering(&lhs, &rhs)? == Some(Ordering::Equal)),
+        BinaryOp::Ne => Value::Bool(ordering(&lhs, &rhs)? != Some(Ordering::Equal)),
+        BinaryOp::Lt => Value::Bool(ordering(&lhs, &rhs)? == Some(Ordering::Less)),
+        BinaryOp::Le => Value::Bool(matches!(
+            ordering(&lhs, &rhs)?,
+            Some(Ordering::Less | Ordering::Equal)
+        )),
+        BinaryOp::Gt => Value::Bool(ordering(&lhs, &rhs)? == Some(Ordering::Greater)),
+        BinaryOp::Ge => Value::Bool(matches!(
+            ordering(&lhs, &rhs)?,
+            Some(Ordering::Greater | Ordering::Equal)
+        )),
+        BinaryOp::Add => match (lhs, rhs) {
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_add(b)),
+            (Value::TimeSpan(a), Value::TimeSpan(b)) => Value::TimeSpan(a + b),
+            (Value::String(a), b) => Value::String(a + &display(&b)),
+            (a, Value::String(b)) => Value::String(display(&a) + &b),
+            (a, b) => Value::Float(a.as_float()? + b.as_float()?),
+        },
+        BinaryOp::Sub => match (lhs, rhs) {
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_sub(b)),
+            (Value::TimeSpan(a), Value::TimeSpan(b)) => Value::TimeSpan(a - b),
+            (a, b) => Value::Float(a.as_float()? - b.as_float()?),
+        },
+        BinaryOp::Mul => match (lhs, rhs) {
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_mul(b)),
+            (a, b) => Value::Float(a.as_float()? * b.as_float()?),
+        },
+        BinaryOp::Div => match (lhs, rhs) {
+            (Value::Int(_), Value::Int(0)) => return Err(EvalError::DivisionByZero),
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_div(b)),
+            (a, b) => Value::Float(a.as_float()? / b.as_float()?),
+        },
+        BinaryOp::Rem => match (lhs, rhs) {
+            (Value::Int(_), Value::Int(0)) => return Err(EvalError::DivisionByZero),
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_rem(b)),
+            (a, b) => Value::Float(a.as_float()? % b.as_float()?),
+        },
+        BinaryOp::And | BinaryOp::Or => unreachable!("short-circuiting is handled by eval"),
+    })
+}
+
+/// Formats a value the way C# would when concatenating it to a string.
+pub(super) fn display(value: &Value) -> String {
+    match value {
+        Value::Bool(true) => "True".into(),
+        Value::Bool(false) => "False".into(),
+        Value::Int(value) => value.to_string(),
+        Value::Float(value) => value.to_string(),
+        Value::String(value) => value.clone(),
+        Value::TimeSpan(value) => value.total_seconds().to_string(),
+    }
+}
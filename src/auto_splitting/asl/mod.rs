@@ -0,0 +1,698 @@
+//! The asl module allows running auto splitters that are written as ASL
+//! scripts, which is the format used by the original LiveSplit. This way the
+//! large existing library of scripts keeps working without having to be
+//! rewritten as WebAssembly modules.
+//!
+//! The `state` blocks that describe the memory of the game are fully
+//! supported. The action blocks (`startup`, `init`, `update`, `start`, `split`,
+//! `reset`, `isLoading`, `gameTime` and `exit`) are written in C#, of which
+//! only a subset is supported: `return`, `if` and `var` statements, assignments
+//! to `vars` and `version`, the usual operators, `settings.Add`,
+//! `settings["..."]`, `print` and `TimeSpan.FromSeconds` /
+//! `TimeSpan.FromMilliseconds`.
+//!
+//! The [`Runner`] doesn't access the memory of the game by itself. The host
+//! attaches to one of the [`process_names`](Runner::process_names) and reads
+//! the [`Variable`]s of the chosen state whenever the runner asks for them.
+//! Any [`ProcessHook`] can be used for this via [`Variable::read`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! state("Game.exe") {
+//!     int level : "Game.exe", 0x1234, 0x10;
+//!     bool isLoading : 0x5678;
+//! }
+//!
+//! startup {
+//!     settings.Add("levels", true, "Split on every level");
+//! }
+//!
+//! start { return current.level == 1 && old.level == 0; }
+//! split { return settings["levels"] && current.level > old.level; }
+//! isLoading { return current.isLoading; }
+//! ```
+
+mod expr;
+
+use std::collections::HashMap;
+
+use snafu::Snafu;
+
+use self::expr::{exec, tokenize, Env, Parser, Stmt, Token};
+pub use self::expr::{EvalError, ParseError, Value};
+use super::hook::{PointerSize, ProcessHook};
+use crate::{
+    event::{CommandSink, TimerQuery},
+    TimeSpan, TimerPhase,
+};
+
+/// An error that can occur while running an ASL script.
+#[derive(Debug, Snafu)]
+pub enum Error {
+    /// An action block failed to execute.
+    Block {
+        /// The name of the block.
+        block: &'static str,
+        /// The underlying error.
+        source: EvalError,
+    },
+}
+
+/// The type of a state variable.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Type {
+    /// `bool`
+    Bool,
+    /// `sbyte`
+    I8,
+    /// `byte`
+    U8,
+    /// `short`
+    I16,
+    /// `ushort`
+    U16,
+    /// `int`
+    I32,
+    /// `uint`
+    U32,
+    /// `long`
+    I64,
+    /// `ulong`
+    U64,
+    /// `float`
+    F32,
+    /// `double`
+    F64,
+    /// `stringN`, a UTF-8 string that takes up at most N bytes and ends at the
+    /// first null byte.
+    String(usize),
+}
+
+impl Type {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "bool" => Self::Bool,
+            "sbyte" => Self::I8,
+            "byte" => Self::U8,
+            "short" => Self::I16,
+            "ushort" => Self::U16,
+            "int" => Self::I32,
+            "uint" => Self::U32,
+            "long" => Self::I64,
+            "ulong" => Self::U64,
+            "float" => Self::F32,
+            "double" => Self::F64,
+            _ => Self::String(name.strip_prefix("string")?.parse().ok()?),
+        })
+    }
+
+    /// The amount of bytes that need to be read for a value of the type.
+    pub const fn size(self) -> usize {
+        match self {
+            Self::Bool | Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::I64 | Self::U64 | Self::F64 => 8,
+            Self::String(len) => len,
+        }
+    }
+
+    /// Decodes a value of the type from the little endian bytes read from the
+    /// memory of the game. Returns [`None`] if not enough bytes are provided.
+    pub fn decode(self, bytes: &[u8]) -> Option<Value> {
+        let bytes = bytes.get(..self.size())?;
+        Some(match self {
+            Self::Bool => Value::Bool(bytes[0] != 0),
+            Self::I8 => Value::Int(bytes[0] as i8 as i64),
+            Self::U8 => Value::Int(bytes[0] as i64),
+            Self::I16 => Value::Int(i16::from_le_bytes(bytes.try_into().ok()?) as i64),
+            Self::U16 => Value::Int(u16::from_le_bytes(bytes.try_into().ok()?) as i64),
+            Self::I32 => Value::Int(i32::from_le_bytes(bytes.try_into().ok()?) as i64),
+            Self::U32 => Value::Int(u32::from_le_bytes(bytes.try_into().ok()?) as i64),
+            Self::I64 => Value::Int(i64::from_le_bytes(bytes.try_into().ok()?)),
+            Self::U64 => Value::Int(u64::from_le_bytes(bytes.try_into().ok()?) as i64),
+            Self::F32 => Value::Float(f32::from_le_bytes(bytes.try_into().ok()?) as f64),
+            Self::F64 => Value::Float(f64::from_le_bytes(bytes.try_into().ok()?)),
+            Self::String(_) => {
+                let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                Value::String(String::from_utf8_lossy(&bytes[..len]).into_owned())
+            }
+        })
+    }
+}
+
+/// A variable of a state block that is read from the memory of the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    /// The name of the variable.
+    pub name: String,
+    /// The type of the variable.
+    pub ty: Type,
+    /// The module the first offset is relative to. If there is none, the main
+    /// module of the process is used.
+    pub module: Option<String>,
+    /// The offsets of the pointer path. The first offset is relative to the
+    /// module. Every other offset is added to the pointer read at the previous
+    /// address.
+    pub offsets: Vec<i64>,
+}
+
+impl Variable {
+    /// Reads the variable from the memory of the process. The main module is
+    /// used if the variable doesn't specify a module, which is usually the
+    /// name of the process.
+    pub fn read<P: ProcessHook>(
+        &self,
+        process: &mut P,
+        main_module: &str,
+        pointer_size: PointerSize,
+    ) -> Option<Value> {
+        let module = self.module.as_deref().unwrap_or(main_module);
+        let base = process.module_base(module).ok()?;
+        let address = process
+            .resolve_pointer_path(base, &self.offsets, pointer_size)
+            .ok()?;
+        let mut buf = vec![0; self.ty.size()];
+        process.read_into(address, &mut buf).ok()?;
+        self.ty.decode(&buf)
+    }
+}
+
+/// A state block that describes the memory of a specific version of a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    /// The name of the process, such as `Game.exe`.
+    pub process: String,
+    /// The version of the game that the state applies to. This is empty if
+    /// the state applies to every version.
+    pub version: String,
+    /// The variables that are read from the memory of the game.
+    pub variables: Vec<Variable>,
+}
+
+/// A setting that the script declared via `settings.Add`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setting {
+    /// The identifier of the setting that the script uses to access it.
+    pub id: String,
+    /// The value of the setting if the user didn't change it.
+    pub default: bool,
+    /// The label that is shown to the user.
+    pub label: String,
+    /// The setting this setting is nested in, if any.
+    pub parent: Option<String>,
+    /// The tooltip that is shown to the user, if any.
+    pub tooltip: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Blocks {
+    startup: Vec<Stmt>,
+    shutdown: Vec<Stmt>,
+    init: Vec<Stmt>,
+    exit: Vec<Stmt>,
+    update: Vec<Stmt>,
+    start: Vec<Stmt>,
+    split: Vec<Stmt>,
+    reset: Vec<Stmt>,
+    is_loading: Vec<Stmt>,
+    game_time: Vec<Stmt>,
+}
+
+/// A parsed ASL script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    states: Vec<State>,
+    blocks: Blocks,
+}
+
+impl Script {
+    /// Parses an ASL script.
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser::new(&tokens);
+        let mut states = Vec::new();
+        let mut blocks = Blocks::default();
+
+        while !parser.is_at_end() {
+            let line = parser.line();
+            let block = match parser.ident()? {
+                "state" => {
+                    states.push(parse_state(&mut parser)?);
+                    continue;
+                }
+                "startup" => &mut blocks.startup,
+                "shutdown" => &mut blocks.shutdown,
+                "init" => &mut blocks.init,
+                "exit" => &mut blocks.exit,
+                "update" => &mut blocks.update,
+                "start" => &mut blocks.start,
+                "split" => &mut blocks.split,
+                "reset" => &mut blocks.reset,
+                "isLoading" => &mut blocks.is_loading,
+                "gameTime" => &mut blocks.game_time,
+                _ => return Err(ParseError::UnexpectedToken { line }),
+            };
+            *block = parser.block()?;
+        }
+
+        Ok(Self { states, blocks })
+    }
+
+    /// Accesses the state blocks of the script.
+    pub fn states(&self) -> &[State] {
+        &self.states
+    }
+}
+
+fn parse_state(parser: &mut Parser<'_>) -> Result<State, ParseError> {
+    parser.expect("(")?;
+    let process = parser.string()?.into();
+    let version = if parser.eat(",") {
+        parser.string()?.into()
+    } else {
+        String::new()
+    };
+    parser.expect(")")?;
+    parser.expect("{")?;
+
+    let mut variables = Vec::new();
+    while !parser.eat("}") {
+        let line = parser.line();
+        let ty = Type::parse(parser.ident()?).ok_or(ParseError::UnsupportedType { line })?;
+        let name = parser.ident()?.into();
+        parser.expect(":")?;
+        let module = match parser.peek() {
+            Some(Token::Str(_)) => {
+                let module = parser.string()?.into();
+                parser.expect(",")?;
+                Some(module)
+            }
+            _ => None,
+        };
+        let mut offsets = vec![parser.int()?];
+        while parser.eat(",") {
+            offsets.push(parser.int()?);
+        }
+        parser.expect(";")?;
+        variables.push(Variable {
+            name,
+            ty,
+            module,
+            offsets,
+        });
+    }
+
+    Ok(State {
+        process,
+        version,
+        variables,
+    })
+}
+
+/// Runs an ASL script. The host needs to attach to a process of the game and
+/// then regularly [`update`](Self::update) the runner, for example 60 times
+/// per second, which is the default of the original LiveSplit.
+#[derive(Debug)]
+pub struct Runner {
+    script: Script,
+    settings: Vec<Setting>,
+    setting_values: HashMap<String, bool>,
+    state: Option<usize>,
+    version: String,
+    current: HashMap<String, Value>,
+    old: HashMap<String, Value>,
+    vars: HashMap<String, Value>,
+}
+
+struct RunnerEnv<'a> {
+    settings: &'a mut Vec<Setting>,
+    setting_values: &'a HashMap<String, bool>,
+    version: &'a mut String,
+    current: &'a HashMap<String, Value>,
+    old: &'a HashMap<String, Value>,
+    vars: &'a mut HashMap<String, Value>,
+}
+
+impl Env for RunnerEnv<'_> {
+    fn get(&mut self, path: &[String]) -> Result<Value, EvalError> {
+        let value = match path {
+            [version] if version == "version" => Some(Value::String(self.version.clone())),
+            [map, name] if map == "current" => self.current.get(name).cloned(),
+            [map, name] if map == "old" => self.old.get(name).cloned(),
+            [map, name] if map == "vars" => self.vars.get(name).cloned(),
+            _ => None,
+        };
+        value.ok_or_else(|| EvalError::UnknownVariable {
+            name: path.join("."),
+        })
+    }
+
+    fn index(&mut self, path: &[String], index: Value) -> Result<Value, EvalError> {
+        match (path, index) {
+            ([settings], Value::String(id)) if settings == "settings" => {
+                let value = self
+                    .setting_values
+                    .get(&id)
+                    .copied()
+                    .or_else(|| self.settings.iter().find(|s| s.id == id).map(|s| s.default));
+                value
+                    .map(Value::Bool)
+                    .ok_or(EvalError::UnknownVariable { name: id })
+            }
+            (path, _) => Err(EvalError::UnknownVariable {
+                name: path.join("."),
+            }),
+        }
+    }
+
+    fn set(&mut self, path: &[String], value: Value) -> Result<(), EvalError> {
+        match (path, value) {
+            ([version], Value::String(value)) if version == "version" => *self.version = value,
+            ([map, name], value) if map == "vars" => {
+                self.vars.insert(name.clone(), value);
+            }
+            // The refresh rate is up to the host.
+            ([rate], _) if rate == "refreshRate" => {}
+            (path, _) => {
+                return Err(EvalError::UnknownVariable {
+                    name: path.join("."),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    fn call(&mut self, path: &[String], args: Vec<Value>) -> Result<Value, EvalError> {
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+        match (&path[..], &args[..]) {
+            (["print"], [message]) => {
+                log::info!(target: "Auto Splitter", "{}", expr::display(message));
+            }
+            (["TimeSpan", "FromSeconds"], [seconds]) => {
+                let seconds = expr::display(seconds)
+                    .parse()
+                    .map_err(|_| EvalError::TypeMismatch)?;
+                return Ok(Value::TimeSpan(TimeSpan::from_seconds(seconds)));
+            }
+            (["TimeSpan", "FromMilliseconds"], [milliseconds]) => {
+                let milliseconds = expr::display(milliseconds)
+                    .parse()
+                    .map_err(|_| EvalError::TypeMismatch)?;
+                return Ok(Value::TimeSpan(TimeSpan::from_milliseconds(milliseconds)));
+            }
+            (["settings", "Add"], [Value::String(id), rest @ ..]) => {
+                let default = match rest.first() {
+                    Some(default) => default.as_bool()?,
+                    None => true,
+                };
+                let label = match rest.get(1) {
+                    Some(label) => expr::display(label),
+                    None => id.clone(),
+                };
+                let parent = match rest.get(2) {
+                    Some(Value::String(parent)) => Some(parent.clone()),
+                    _ => None,
+                };
+                self.settings.retain(|s| &s.id != id);
+                self.settings.push(Setting {
+                    id: id.clone(),
+                    default,
+                    label,
+                    parent,
+                    tooltip: None,
+                });
+            }
+            (["settings", "SetToolTip"], [Value::String(id), tooltip]) => {
+                if let Some(setting) = self.settings.iter_mut().find(|s| &s.id == id) {
+                    setting.tooltip = Some(expr::display(tooltip));
+                }
+            }
+            _ => {
+                return Err(EvalError::UnknownFunction {
+                    name: path.join("."),
+                })
+            }
+        }
+        // The functions that don't return anything are only used as
+        // statements, so the value is never looked at.
+        Ok(Value::Bool(true))
+    }
+}
+
+impl Runner {
+    /// Creates a runner for the script and runs its `startup` block.
+    pub fn new(script: Script) -> Result<Self, Error> {
+        let mut runner = Self {
+            script,
+            settings: Vec::new(),
+            setting_values: HashMap::new(),
+            state: None,
+            version: String::new(),
+            current: HashMap::new(),
+            old: HashMap::new(),
+            vars: HashMap::new(),
+        };
+        runner.run("startup", |b| &b.startup)?;
+        Ok(runner)
+    }
+
+    fn run(
+        &mut self,
+        block: &'static str,
+        select: fn(&Blocks) -> &Vec<Stmt>,
+    ) -> Result<Option<Value>, Error> {
+        let mut env = RunnerEnv {
+            settings: &mut self.settings,
+            setting_values: &self.setting_values,
+            version: &mut self.version,
+            current: &self.current,
+            old: &self.old,
+            vars: &mut self.vars,
+        };
+        let result = exec(select(&self.script.blocks), &mut env, &mut HashMap::new())
+            .map_err(|source| Error::Block { block, source })?;
+        Ok(result.flatten())
+    }
+
+    fn run_condition(
+        &mut self,
+        block: &'static str,
+        select: fn(&Blocks) -> &Vec<Stmt>,
+    ) -> Result<bool, Error> {
+        Ok(matches!(self.run(block, select)?, Some(Value::Bool(true))))
+    }
+
+    /// Accesses the settings that the script declared.
+    pub fn settings(&self) -> &[Setting] {
+        &self.settings
+    }
+
+    /// Changes the value of a setting.
+    pub fn set_setting(&mut self, id: &str, value: bool) {
+        self.setting_values.insert(id.into(), value);
+    }
+
+    /// Returns the names of the processes the script can attach to.
+    pub fn process_names(&self) -> impl Iterator<Item = &str> + '_ {
+        let mut names: Vec<&str> = Vec::new();
+        for state in &self.script.states {
+            if !names.contains(&state.process.as_str()) {
+                names.push(&state.process);
+            }
+        }
+        names.into_iter()
+    }
+
+    /// Returns the version of the game that the `init` block detected.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Returns the state that is used for the process that is attached, if
+    /// any.
+    pub fn state(&self) -> Option<&State> {
+        self.script.states.get(self.state?)
+    }
+
+    /// Runs the `init` block after the host attached to the process with the
+    /// name provided. Afterwards the state that matches the process and the
+    /// version the `init` block detected is used.
+    pub fn attach(&mut self, process_name: &str) -> Result<(), Error> {
+        self.version.clear();
+        self.current.clear();
+        self.old.clear();
+        self.run("init", |b| &b.init)?;
+
+        // States for the detected version are preferred over the ones that
+        // apply to every version.
+        let candidates = || {
+            self.script
+                .states
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| s.process.eq_ignore_ascii_case(process_name))
+        };
+        self.state = candidates()
+            .find(|(_, s)| s.version == self.version)
+            .or_else(|| candidates().find(|(_, s)| s.version.is_empty()))
+            .or_else(|| candidates().next())
+            .map(|(i, _)| i);
+        Ok(())
+    }
+
+    /// Runs the `exit` block after the process exited.
+    pub fn detach(&mut self) -> Result<(), Error> {
+        self.state = None;
+        self.run("exit", |b| &b.exit)?;
+        Ok(())
+    }
+
+    /// Runs the `shutdown` block when the script is no longer used.
+    pub fn shutdown(mut self) -> Result<(), Error> {
+        self.run("shutdown", |b| &b.shutdown)?;
+        Ok(())
+    }
+
+    /// Reads the variables of the state via the function provided and runs
+    /// the action blocks, applying their results to the timer. Variables that
+    /// couldn't be read keep their previous value.
+    pub fn update<S, R>(&mut self, mut read: R, timer: &S) -> Result<(), Error>
+    where
+        S: CommandSink + TimerQuery,
+        R: FnMut(&Variable) -> Option<Value>,
+    {
+        let Some(state) = self.state() else {
+            return Ok(());
+        };
+        let mut current = self.current.clone();
+        for variable in &state.variables {
+            if let Some(value) = read(variable) {
+                current.insert(variable.name.clone(), value);
+            }
+        }
+        // The first update has no old values yet.
+        self.old = if self.current.is_empty() {
+            current.clone()
+        } else {
+            core::mem::replace(&mut self.current, HashMap::new())
+        };
+        self.current = current;
+
+        if matches!(self.run("update", |b| &b.update)?, Some(Value::Bool(false))) {
+            return Ok(());
+        }
+
+        let phase = timer.get_timer().current_phase();
+        match phase {
+            TimerPhase::NotRunning => {
+                if self.run_condition("start", |b| &b.start)? {
+                    drop(timer.start());
+                }
+            }
+            TimerPhase::Running | TimerPhase::Paused => {
+                if let Some(Value::Bool(is_loading)) = self.run("isLoading", |b| &b.is_loading)? {
+                    if is_loading {
+                        drop(timer.pause_game_time());
+                    } else {
+                        drop(timer.resume_game_time());
+                    }
+                }
+                if let Some(Value::TimeSpan(game_time)) = self.run("gameTime", |b| &b.game_time)? {
+                    drop(timer.set_game_time(game_time));
+                }
+                if self.run_condition("reset", |b| &b.reset)? {
+                    drop(timer.reset(None));
+                } else if self.run_condition("split", |b| &b.split)? {
+                    drop(timer.split());
+                }
+            }
+            TimerPhase::Ended => {
+                if self.run_condition("reset", |b| &b.reset)? {
+                    drop(timer.reset(None));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::create_timer;
+
+    const SCRIPT: &str = r#"
+        state("Game.exe") {
+            int level : "Game.exe", 0x1234, -0x10;
+            string16 name : 0x10;
+        }
+
+        state("Game.exe", "1.1") {
+            int level : 0x4321;
+        }
+
+        startup {
+            settings.Add("levels", true, "Split on every level");
+        }
+
+        init {
+            version = "1.1";
+        }
+
+        // The game starts at level 1.
+        start { return current.level == 1 && old.level == 0; }
+
+        split {
+            if (!settings["levels"]) return false;
+            var next = old.level + 1;
+            return current.level == next;
+        }
+    "#;
+
+    #[test]
+    fn parses_states() {
+        let script = Script::parse(SCRIPT).unwrap();
+        assert_eq!(
+            script.states()[0].variables[0],
+            Variable {
+                name: "level".into(),
+                ty: Type::I32,
+                module: Some("Game.exe".into()),
+                offsets: vec![0x1234, -0x10],
+            },
+        );
+        assert_eq!(script.states()[0].variables[1].ty, Type::String(16));
+        assert_eq!(script.states()[1].version, "1.1");
+    }
+
+    #[test]
+    fn controls_timer() {
+        let timer = create_timer(&["A", "B"]).into_shared();
+        let mut runner = Runner::new(Script::parse(SCRIPT).unwrap()).unwrap();
+        assert_eq!(runner.settings()[0].id, "levels");
+        assert_eq!(runner.process_names().collect::<Vec<_>>(), ["Game.exe"]);
+
+        runner.attach("game.exe").unwrap();
+        assert_eq!(runner.version(), "1.1");
+        assert_eq!(runner.state().unwrap().variables[0].offsets, [0x4321]);
+
+        let mut level = 0;
+        let mut tick = |runner: &mut Runner, value| {
+            level = value;
+            runner.update(|_| Some(Value::Int(level)), &timer).unwrap();
+        };
+
+        tick(&mut runner, 0);
+        tick(&mut runner, 1);
+        assert_eq!(timer.read().unwrap().current_phase(), TimerPhase::Running);
+        tick(&mut runner, 2);
+        assert_eq!(timer.read().unwrap().current_split_index(), Some(1));
+
+        runner.set_setting("levels", false);
+        tick(&mut runner, 3);
+        assert_eq!(timer.read().unwrap().current_split_index(), Some(1));
+    }
+}
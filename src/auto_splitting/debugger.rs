@@ -0,0 +1,183 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use serde_derive::Serialize;
+
+/// The amount of log entries that are kept around. Older entries are dropped.
+const MAX_LOG_ENTRIES: usize = 1000;
+
+/// The kind of a [`LogEntry`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum LogKind {
+    /// A message that the auto splitter printed.
+    AutoSplitter,
+    /// A trace message of the runtime.
+    Trace,
+    /// A debug message of the runtime.
+    Debug,
+    /// An informational message of the runtime.
+    Info,
+    /// A warning of the runtime.
+    Warning,
+    /// An error of the runtime.
+    Error,
+}
+
+/// A message that got logged while the auto splitter was running.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    /// The tick the message got logged in.
+    pub tick: u64,
+    /// The kind of the message.
+    pub kind: LogKind,
+    /// The message itself.
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    tick: u64,
+    paused: bool,
+    #[serde(skip)]
+    pending_steps: u64,
+    variables: BTreeMap<String, String>,
+    logs: VecDeque<LogEntry>,
+}
+
+/// Allows inspecting and controlling the auto splitter while it is running,
+/// which is meant for debugger UIs that help with writing auto splitters. It
+/// collects the messages the auto splitter and the runtime log and the values
+/// of the variables the auto splitter sets. The auto splitter can also be
+/// paused and then stepped through tick by tick. It can be cloned cheaply, as
+/// all the clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct Debugger(Arc<Mutex<State>>);
+
+impl Debugger {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.0.lock().unwrap()
+    }
+
+    /// Pauses the auto splitter. It doesn't get updated until it is resumed
+    /// or stepped.
+    pub fn pause(&self) {
+        self.state().paused = true;
+    }
+
+    /// Resumes the auto splitter after it got paused.
+    pub fn resume(&self) {
+        let mut state = self.state();
+        state.paused = false;
+        state.pending_steps = 0;
+    }
+
+    /// Runs a single tick of the auto splitter while it is paused.
+    pub fn step(&self) {
+        let mut state = self.state();
+        if state.paused {
+            state.pending_steps += 1;
+        }
+    }
+
+    /// Returns whether the auto splitter is paused.
+    pub fn is_paused(&self) -> bool {
+        self.state().paused
+    }
+
+    /// Returns the amount of ticks the auto splitter ran for.
+    pub fn tick(&self) -> u64 {
+        self.state().tick
+    }
+
+    /// Returns the current values of the variables that the auto splitter
+    /// set.
+    pub fn variables(&self) -> BTreeMap<String, String> {
+        self.state().variables.clone()
+    }
+
+    /// Removes all the log entries that were collected so far and returns
+    /// them.
+    pub fn take_logs(&self) -> Vec<LogEntry> {
+        self.state().logs.drain(..).collect()
+    }
+
+    /// Encodes the entire state of the debugger as JSON. This includes the
+    /// current tick, whether the auto splitter is paused, the variables and
+    /// the log entries.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&*self.state()).unwrap()
+    }
+
+    /// Forgets about everything that is related to the previous auto
+    /// splitter.
+    pub(super) fn clear(&self) {
+        let mut state = self.state();
+        state.tick = 0;
+        state.variables.clear();
+        state.logs.clear();
+    }
+
+    /// Decides whether the auto splitter should be updated now and counts the
+    /// tick if it should.
+    pub(super) fn begin_tick(&self) -> bool {
+        let mut state = self.state();
+        if state.paused {
+            if state.pending_steps == 0 {
+                return false;
+            }
+            state.pending_steps -= 1;
+        }
+        state.tick += 1;
+        true
+    }
+
+    pub(super) fn log(&self, kind: LogKind, message: String) {
+        let mut state = self.state();
+        if state.logs.len() == MAX_LOG_ENTRIES {
+            state.logs.pop_front();
+        }
+        let tick = state.tick;
+        state.logs.push_back(LogEntry {
+            tick,
+            kind,
+            message,
+        });
+    }
+
+    pub(super) fn set_variable(&self, name: &str, value: &str) {
+        self.state().variables.insert(name.into(), value.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pause_and_step() {
+        let debugger = Debugger::default();
+        assert!(debugger.begin_tick());
+
+        debugger.pause();
+        assert!(!debugger.begin_tick());
+        debugger.step();
+        assert!(debugger.begin_tick());
+        assert!(!debugger.begin_tick());
+
+        debugger.set_variable("level", "2");
+        debugger.log(LogKind::AutoSplitter, "Split".into());
+        assert_eq!(
+            debugger.to_json(),
+            r#"{"tick":2,"paused":true,"variables":{"level":"2"},"logs":[{"tick":2,"kind":"AutoSplitter","message":"Split"}]}"#,
+        );
+
+        debugger.resume();
+        assert!(debugger.begin_tick());
+        assert_eq!(debugger.take_logs().len(), 1);
+        assert!(debugger.take_logs().is_empty());
+    }
+}
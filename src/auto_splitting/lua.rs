@@ -0,0 +1,426 @@
+//! The lua module allows running auto splitters that are written as Lua
+//! scripts. Unlike WebAssembly modules, they don't need to be compiled, and
+//! changes to the script are picked up automatically while it is running, so
+//! they are well suited for quickly writing an auto splitter for a game.
+//!
+//! The script needs to define a global `update` function that is called
+//! periodically at the tick rate. The following functions are provided to the
+//! script:
+//!
+//! ```lua
+//! -- The timer
+//! timer.state()                  -- "NotRunning", "Running", "Paused" or "Ended"
+//! timer.start()
+//! timer.split()
+//! timer.skip_split()
+//! timer.undo_split()
+//! timer.reset()
+//! timer.set_game_time(seconds)
+//! timer.pause_game_time()
+//! timer.resume_game_time()
+//! timer.set_variable(name, value)
+//!
+//! -- The process of the game
+//! process.attach(name)           -- true if it attached
+//! process.detach()
+//! process.is_attached()          -- false once the process exited
+//! process.set_pointer_size(bytes) -- 4 or 8, which is the default
+//! process.module_address(name)   -- nil if the module doesn't exist
+//! process.read(type, address, offset, ...) -- nil if the read failed
+//!
+//! -- The runtime
+//! runtime.set_tick_rate(ticks_per_second)
+//! print(message)
+//!
+//! -- The settings
+//! settings.get(key, default)
+//! ```
+//!
+//! The types that can be read are `bool`, `i8`, `u8`, `i16`, `u16`, `i32`,
+//! `u32`, `i64`, `u64`, `f32`, `f64` and `stringN`, where N is the maximum
+//! amount of bytes of the string. The offsets form a pointer path: the first
+//! offset is added to the address and for every other offset a pointer is read
+//! at the current address and the offset is added to it.
+//!
+//! # Example
+//!
+//! ```lua
+//! local level = 0
+//!
+//! function update()
+//!     if not process.is_attached() and not process.attach("Game.exe") then
+//!         return
+//!     end
+//!     local base = process.module_address("Game.exe")
+//!     local new_level = process.read("i32", base, 0x1234, 0x10)
+//!     if new_level and new_level > level then
+//!         timer.split()
+//!     end
+//!     level = new_level or level
+//! end
+//! ```
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+use mlua::{Function, Lua, Value as LuaValue, Variadic};
+use snafu::{ResultExt, Snafu};
+
+use super::{
+    asl::{Type, Value},
+    hook::{NativeProcess, PointerSize, ProcessHook},
+    settings,
+};
+use crate::{
+    event::{CommandSink, TimerQuery},
+    TimeSpan, TimerPhase,
+};
+
+/// How often the script is checked for changes.
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An error that can occur while running a Lua auto splitter.
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum Error {
+    /// Failed reading the script.
+    ReadFile {
+        /// The underlying error.
+        source: io::Error,
+    },
+    /// The script failed to execute.
+    Script {
+        /// The underlying error.
+        source: mlua::Error,
+    },
+}
+
+struct Shared {
+    process: Option<NativeProcess>,
+    pointer_size: PointerSize,
+    tick_rate: Duration,
+}
+
+/// An auto splitter that is written as a Lua script. The script is reloaded
+/// automatically whenever it changes.
+pub struct LuaAutoSplitter<T> {
+    lua: Lua,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    next_reload_check: Instant,
+    shared: Arc<Mutex<Shared>>,
+    timer: T,
+    settings: settings::Map,
+}
+
+impl<T: CommandSink + TimerQuery + Clone + Send + 'static> LuaAutoSplitter<T> {
+    /// Loads the script at the path provided. The settings are what the
+    /// script can access via `settings.get`.
+    pub fn load(path: PathBuf, timer: T, settings: settings::Map) -> Result<Self, Error> {
+        let shared = Arc::new(Mutex::new(Shared {
+            process: None,
+            pointer_size: PointerSize::Bit64,
+            tick_rate: Duration::from_secs(1) / 120,
+        }));
+        let modified = modified(&path);
+        let lua = instantiate(&path, &timer, &shared, &settings)?;
+        Ok(Self {
+            lua,
+            path,
+            modified,
+            next_reload_check: Instant::now() + RELOAD_CHECK_INTERVAL,
+            shared,
+            timer,
+            settings,
+        })
+    }
+
+    /// Returns how long to wait between calls to [`update`](Self::update).
+    pub fn tick_rate(&self) -> Duration {
+        self.shared.lock().unwrap().tick_rate
+    }
+
+    /// Reloads the script if it changed and then calls its `update`
+    /// function. If the changed script fails to load, the previous version
+    /// keeps running and the error is returned.
+    pub fn update(&mut self) -> Result<(), Error> {
+        let now = Instant::now();
+        if now >= self.next_reload_check {
+            self.next_reload_check = now + RELOAD_CHECK_INTERVAL;
+            let modified = modified(&self.path);
+            if modified != self.modified {
+                // A broken script is only attempted to be loaded once.
+                self.modified = modified;
+                self.lua = instantiate(&self.path, &self.timer, &self.shared, &self.settings)?;
+                log::info!(target: "Auto Splitter", "Reloaded auto splitter");
+            }
+        }
+
+        if let Ok(update) = self.lua.globals().get::<Function>("update") {
+            update.call::<()>(()).context(Script)?;
+        }
+        Ok(())
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn instantiate<T: CommandSink + TimerQuery + Clone + Send + 'static>(
+    path: &Path,
+    timer: &T,
+    shared: &Arc<Mutex<Shared>>,
+    settings: &settings::Map,
+) -> Result<Lua, Error> {
+    let source = fs::read_to_string(path).context(ReadFile)?;
+    let lua = Lua::new();
+    bind(&lua, timer, shared, settings).context(Script)?;
+    lua.load(source)
+        .set_name(path.to_string_lossy())
+        .exec()
+        .context(Script)?;
+    Ok(lua)
+}
+
+fn bind<T: CommandSink + TimerQuery + Clone + Send + 'static>(
+    lua: &Lua,
+    timer: &T,
+    shared: &Arc<Mutex<Shared>>,
+    settings: &settings::Map,
+) -> mlua::Result<()> {
+    let globals = lua.globals();
+
+    let table = lua.create_table()?;
+    table.set("state", {
+        let timer = timer.clone();
+        lua.create_function(move |_, ()| {
+            Ok(match timer.get_timer().current_phase() {
+                TimerPhase::NotRunning => "NotRunning",
+                TimerPhase::Running => "Running",
+                TimerPhase::Paused => "Paused",
+                TimerPhase::Ended => "Ended",
+            })
+        })?
+    })?;
+    macro_rules! commands {
+        ($($name:literal => $command:ident($($arg:expr)?),)*) => {
+            $(table.set($name, {
+                let timer = timer.clone();
+                lua.create_function(move |_, ()| {
+                    drop(timer.$command($($arg)?));
+                    Ok(())
+                })?
+            })?;)*
+        };
+    }
+    commands! {
+        "start" => start(),
+        "split" => split(),
+        "skip_split" => skip_split(),
+        "undo_split" => undo_split(),
+        "reset" => reset(None),
+        "pause_game_time" => pause_game_time(),
+        "resume_game_time" => resume_game_time(),
+    }
+    table.set("set_game_time", {
+        let timer = timer.clone();
+        lua.create_function(move |_, seconds: f64| {
+            drop(timer.set_game_time(TimeSpan::from_seconds(seconds)));
+            Ok(())
+        })?
+    })?;
+    table.set("set_variable", {
+        let timer = timer.clone();
+        lua.create_function(move |_, (name, value): (String, String)| {
+            drop(timer.set_custom_variable(name, value));
+            Ok(())
+        })?
+    })?;
+    globals.set("timer", table)?;
+
+    let table = lua.create_table()?;
+    table.set("attach", {
+        let shared = shared.clone();
+        lua.create_function(move |_, name: String| {
+            let process = NativeProcess::attach(&name).ok();
+            let attached = process.is_some();
+            shared.lock().unwrap().process = process;
+            Ok(attached)
+        })?
+    })?;
+    table.set("detach", {
+        let shared = shared.clone();
+        lua.create_function(move |_, ()| {
+            shared.lock().unwrap().process = None;
+            Ok(())
+        })?
+    })?;
+    table.set("is_attached", {
+        let shared = shared.clone();
+        lua.create_function(move |_, ()| {
+            let mut shared = shared.lock().unwrap();
+            let is_open = shared.process.as_mut().is_some_and(|p| p.is_open());
+            if !is_open {
+                shared.process = None;
+            }
+            Ok(is_open)
+        })?
+    })?;
+    table.set("set_pointer_size", {
+        let shared = shared.clone();
+        lua.create_function(move |_, bytes: u32| {
+            shared.lock().unwrap().pointer_size = match bytes {
+                4 => PointerSize::Bit32,
+                8 => PointerSize::Bit64,
+                _ => return Err(mlua::Error::runtime("The pointer size needs to be 4 or 8.")),
+            };
+            Ok(())
+        })?
+    })?;
+    table.set("module_address", {
+        let shared = shared.clone();
+        lua.create_function(move |_, name: String| {
+            let mut shared = shared.lock().unwrap();
+            Ok(shared
+                .process
+                .as_mut()
+                .and_then(|p| p.module_base(&name).ok())
+                .map(|address| address as i64))
+        })?
+    })?;
+    table.set("read", {
+        let shared = shared.clone();
+        lua.create_function(
+            move |lua, (ty, address, offsets): (String, i64, Variadic<i64>)| {
+                let ty = parse_type(&ty).ok_or_else(|| {
+                    mlua::Error::runtime(format!("The type {ty} is not supported."))
+                })?;
+                let shared = shared.lock().unwrap();
+                let Some(process) = &shared.process else {
+                    return Ok(LuaValue::Nil);
+                };
+                let mut buf = vec![0; ty.size()];
+                let value = process
+                    .resolve_pointer_path(address as u64, &offsets, shared.pointer_size)
+                    .and_then(|address| process.read_into(address, &mut buf))
+                    .ok()
+                    .and_then(|_| ty.decode(&buf));
+                Ok(match value {
+                    Some(Value::Bool(v)) => LuaValue::Boolean(v),
+                    Some(Value::Int(v)) => LuaValue::Integer(v),
+                    Some(Value::Float(v)) => LuaValue::Number(v),
+                    Some(Value::String(v)) => LuaValue::String(lua.create_string(v)?),
+                    _ => LuaValue::Nil,
+                })
+            },
+        )?
+    })?;
+    globals.set("process", table)?;
+
+    let table = lua.create_table()?;
+    table.set("set_tick_rate", {
+        let shared = shared.clone();
+        lua.create_function(move |_, ticks_per_second: f64| {
+            if ticks_per_second > 0.0 && ticks_per_second.is_finite() {
+                shared.lock().unwrap().tick_rate = Duration::from_secs_f64(1.0 / ticks_per_second);
+            }
+            Ok(())
+        })?
+    })?;
+    globals.set("runtime", table)?;
+
+    globals.set(
+        "print",
+        lua.create_function(|_, message: Variadic<LuaValue>| {
+            let message = message
+                .iter()
+                .map(|v| v.to_string().unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("\t");
+            log::info!(target: "Auto Splitter", "{message}");
+            Ok(())
+        })?,
+    )?;
+
+    let table = lua.create_table()?;
+    table.set("get", {
+        let settings = settings.clone();
+        lua.create_function(move |lua, (key, default): (String, LuaValue)| {
+            Ok(match settings.get(&key) {
+                Some(settings::Value::Bool(v)) => LuaValue::Boolean(*v),
+                Some(settings::Value::I64(v)) => LuaValue::Integer(*v),
+                Some(settings::Value::F64(v)) => LuaValue::Number(*v),
+                Some(settings::Value::String(v)) => LuaValue::String(lua.create_string(&**v)?),
+                _ => default,
+            })
+        })?
+    })?;
+    globals.set("settings", table)?;
+
+    Ok(())
+}
+
+fn parse_type(name: &str) -> Option<Type> {
+    Some(match name {
+        "bool" => Type::Bool,
+        "i8" => Type::I8,
+        "u8" => Type::U8,
+        "i16" => Type::I16,
+        "u16" => Type::U16,
+        "i32" => Type::I32,
+        "u32" => Type::U32,
+        "i64" => Type::I64,
+        "u64" => Type::U64,
+        "f32" => Type::F32,
+        "f64" => Type::F64,
+        _ => Type::String(name.strip_prefix("string")?.parse().ok()?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::create_timer;
+
+    #[test]
+    fn controls_timer() {
+        let path =
+            std::env::temp_dir().join(format!("lua-auto-splitter-{}.lua", std::process::id()));
+        fs::write(
+            &path,
+            r#"
+                local ticks = 0
+                runtime.set_tick_rate(60)
+                function update()
+                    ticks = ticks + 1
+                    if timer.state() == "NotRunning" then
+                        timer.start()
+                    elseif ticks == 2 and settings.get("split", false) then
+                        timer.split()
+                    end
+                end
+            "#,
+        )
+        .unwrap();
+
+        let timer = create_timer(&["A", "B"]).into_shared();
+        let mut map = settings::Map::new();
+        map.insert("split".into(), settings::Value::Bool(true));
+        let mut auto_splitter = LuaAutoSplitter::load(path.clone(), timer.clone(), map).unwrap();
+        assert_eq!(
+            auto_splitter.tick_rate(),
+            Duration::from_secs_f64(1.0 / 60.0)
+        );
+
+        auto_splitter.update().unwrap();
+        auto_splitter.update().unwrap();
+        assert_eq!(timer.read().unwrap().current_split_index(), Some(1));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+//! The manifest module provides a format for listing the auto splitters that
+//! are available for games, so frontends can offer to enable an auto splitter
+//! as soon as the user opens splits for a game that has one. The manifest is
+//! encoded as JSON:
+//!
+//! ```json
+//! {
+//!     "autoSplitters": [
+//!         {
+//!             "games": ["Celeste"],
+//!             "url": "https://example.com/celeste.wasm",
+//!             "sha256": "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08",
+//!             "version": "1.2.0",
+//!             "minimumRuntimeVersion": "0.13.0",
+//!             "description": "Splits on every chapter and removes loads."
+//!         }
+//!     ]
+//! }
+//! ```
+
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::Run;
+
+/// The version of this runtime that the minimum runtime versions of the
+/// entries are compared against.
+const RUNTIME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A list of auto splitters and the games they are for.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    /// The auto splitters that are available.
+    pub auto_splitters: Vec<Entry>,
+}
+
+/// An auto splitter that is listed in a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    /// The names of the games the auto splitter is for.
+    pub games: Vec<String>,
+    /// The URL the WebAssembly module of the auto splitter can be downloaded
+    /// from.
+    pub url: String,
+    /// The SHA-256 hash of the WebAssembly module, encoded as hexadecimal.
+    pub sha256: String,
+    /// The version of the auto splitter.
+    #[serde(default)]
+    pub version: String,
+    /// The oldest version of the runtime that the auto splitter works with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum_runtime_version: Option<String>,
+    /// A description of what the auto splitter does that is shown to the user.
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Describes an error that happened when downloading an auto splitter.
+#[cfg(feature = "networking")]
+#[derive(Debug, snafu::Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum DownloadError {
+    /// Failed to send the request or to receive the response.
+    Request {
+        /// The underlying error.
+        source: reqwest::Error,
+    },
+    /// The response indicated that the request was unsuccessful.
+    Status {
+        /// The HTTP status code of the response.
+        status: u16,
+    },
+    /// The module that got downloaded doesn't match the hash in the manifest.
+    HashMismatch,
+}
+
+impl Manifest {
+    /// Parses the manifest from its JSON representation.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Encodes the manifest as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// Looks up the auto splitter for the game with the name provided. The
+    /// names are compared case-insensitively. Auto splitters that need a newer
+    /// version of the runtime are skipped.
+    pub fn find(&self, game_name: &str) -> Option<&Entry> {
+        let game_name = game_name.trim();
+        if game_name.is_empty() {
+            return None;
+        }
+        self.auto_splitters.iter().find(|entry| {
+            entry.is_compatible()
+                && entry
+                    .games
+                    .iter()
+                    .any(|game| game.trim().eq_ignore_ascii_case(game_name))
+        })
+    }
+
+    /// Looks up the auto splitter for the game of the run.
+    pub fn find_for_run(&self, run: &Run) -> Option<&Entry> {
+        self.find(run.game_name())
+    }
+}
+
+impl Entry {
+    /// Returns whether the auto splitter works with this version of the
+    /// runtime.
+    pub fn is_compatible(&self) -> bool {
+        self.minimum_runtime_version
+            .as_deref()
+            .map_or(true, |minimum| {
+                parse_version(minimum) <= parse_version(RUNTIME_VERSION)
+            })
+    }
+
+    /// Checks whether the WebAssembly module matches the hash in the
+    /// manifest.
+    pub fn verify(&self, module: &[u8]) -> bool {
+        let hash = Sha256::digest(module);
+        let expected = self.sha256.as_bytes();
+        expected.len() == 2 * hash.len()
+            && hash.iter().zip(expected.chunks(2)).all(|(byte, hex)| {
+                core::str::from_utf8(hex)
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    == Some(*byte)
+            })
+    }
+
+    /// Downloads the WebAssembly module of the auto splitter and verifies
+    /// that it matches the hash in the manifest.
+    #[cfg(feature = "networking")]
+    pub async fn download(&self) -> Result<Vec<u8>, DownloadError> {
+        use snafu::{ensure, ResultExt};
+
+        let response = reqwest::get(&self.url).await.context(Request)?;
+        let status = response.status();
+        ensure!(
+            status.is_success(),
+            Status {
+                status: status.as_u16()
+            }
+        );
+        let module = response.bytes().await.context(Request)?;
+        ensure!(self.verify(&module), HashMismatch);
+        Ok(module.to_vec())
+    }
+}
+
+/// Parses the numeric components of a version such as `1.2.3`. Pre-release
+/// and build suffixes are ignored.
+fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.trim().parse().unwrap_or(0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find() {
+        let manifest = Manifest::from_json(
+            r#"{
+                "autoSplitters": [
+                    {
+                        "games": ["Celeste"],
+                        "url": "https://example.com/future.wasm",
+                        "sha256": "",
+                        "minimumRuntimeVersion": "999.0.0"
+                    },
+                    {
+                        "games": ["Celeste", "Celeste Classic"],
+                        "url": "https://example.com/celeste.wasm",
+                        "sha256": "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let entry = manifest.find(" celeste ").unwrap();
+        assert_eq!(entry.url, "https://example.com/celeste.wasm");
+        assert!(entry.verify(b"test"));
+        assert!(!entry.verify(b"tset"));
+        assert!(manifest.find("Super Mario 64").is_none());
+        assert!(manifest.find("").is_none());
+    }
+}
@@ -14,11 +14,16 @@
 //!
 //! This function is called periodically by the runtime at the configured tick
 //! rate. The tick rate is 120 Hz by default, but can be changed by the auto
-//! splitter.
+//! splitter. The host may limit the tick rate via
+//! [`Runtime::set_max_tick_rate`], for example to save power on battery.
 //!
 //! In addition the WebAssembly module is expected to export a memory called
 //! `memory`.
 //!
+//! Auto splitters written as ASL scripts for the original LiveSplit can be run
+//! via the [`asl`] module instead. The [`manifest`] module allows finding the
+//! auto splitter that is available for a game.
+//!
 //! # API exposed to the Auto Splitters
 //!
 //! The following functions are provided to the auto splitters in the module
@@ -297,6 +302,22 @@
 //!         option_description_ptr: *const u8,
 //!         option_description_len: usize,
 //!     ) -> bool;
+//!     /// Adds a new number setting that the user can modify. The key is used to
+//!     /// store the setting in the settings map and needs to be unique across all
+//!     /// types of settings. The description is what's shown to the user. The
+//!     /// user can only choose values between the minimum and the maximum. The
+//!     /// pointers need to point to valid UTF-8 encoded text with the respective
+//!     /// given length. Returns the value that the user chose, or the default
+//!     /// value if they didn't choose one.
+//!     pub fn user_settings_add_number(
+//!         key_ptr: *const u8,
+//!         key_len: usize,
+//!         description_ptr: *const u8,
+//!         description_len: usize,
+//!         default_value: f64,
+//!         min: f64,
+//!         max: f64,
+//!     ) -> f64;
 //!     /// Adds a new file select setting that the user can modify. This allows the
 //!     /// user to choose a file from the file system. The key is used to store the
 //!     /// path of the file in the settings map and needs to be unique across all
@@ -551,16 +572,26 @@
 //! - There is no threading.
 //! - Time and random numbers are available.
 
+pub mod asl;
+mod debugger;
+#[cfg(feature = "lua-auto-splitting")]
+pub mod lua;
+pub mod manifest;
+mod run_settings;
+
 use crate::{
     event::{self, TimerQuery},
     platform::Arc,
-    timing::TimerPhase,
+    timing::{GameTimeSmoother, TimerPhase},
 };
-pub use livesplit_auto_splitting::{settings, wasi_path};
+use core::sync::atomic::{self, AtomicBool, AtomicU64};
+pub use debugger::{Debugger, LogEntry, LogKind};
+pub use livesplit_auto_splitting::{hook, settings, wasi_path};
 use livesplit_auto_splitting::{
     AutoSplitter, Config, CreationError, InterruptHandle, LogLevel, Timer as AutoSplitTimer,
     TimerState,
 };
+pub use run_settings::{parse_settings_map, store_settings_map};
 use snafu::Snafu;
 use std::{fmt, fs, io, path::PathBuf, thread, time::Duration};
 use tokio::{
@@ -598,6 +629,9 @@ pub struct Runtime<T> {
     interrupt_receiver: watch::Receiver<Option<InterruptHandle>>,
     auto_splitter: watch::Sender<Option<AutoSplitter<Timer<T>>>>,
     runtime: livesplit_auto_splitting::Runtime,
+    min_tick_interval: Arc<AtomicU64>,
+    smooth_game_time: Arc<AtomicBool>,
+    debugger: Debugger,
 }
 
 impl<T> Drop for Runtime<T> {
@@ -621,15 +655,27 @@ impl<T: event::CommandSink + TimerQuery + Send + 'static> Runtime<T> {
         let (sender, receiver) = watch::channel(None);
         let (interrupt_sender, interrupt_receiver) = watch::channel(None);
         let (timeout_sender, timeout_receiver) = watch::channel(None);
+        let min_tick_interval = Arc::new(AtomicU64::new(0));
+        let debugger = Debugger::default();
 
         thread::Builder::new()
             .name("Auto Splitting Runtime".into())
-            .spawn(move || {
-                runtime::Builder::new_current_thread()
-                    .enable_time()
-                    .build()
-                    .unwrap()
-                    .block_on(run(receiver, timeout_sender, interrupt_sender))
+            .spawn({
+                let min_tick_interval = min_tick_interval.clone();
+                let debugger = debugger.clone();
+                move || {
+                    runtime::Builder::new_current_thread()
+                        .enable_time()
+                        .build()
+                        .unwrap()
+                        .block_on(run(
+                            receiver,
+                            timeout_sender,
+                            interrupt_sender,
+                            min_tick_interval,
+                            debugger,
+                        ))
+                }
             })
             .unwrap();
 
@@ -652,18 +698,63 @@ impl<T: event::CommandSink + TimerQuery + Send + 'static> Runtime<T> {
             auto_splitter: sender,
             // TODO: unwrap?
             runtime: livesplit_auto_splitting::Runtime::new(Config::default()).unwrap(),
+            min_tick_interval,
+            smooth_game_time: Arc::new(AtomicBool::new(false)),
+            debugger,
         }
     }
 
-    /// Attempts to load a wasm file containing an auto splitter module.
+    /// Accesses the debugger that allows inspecting and controlling the auto
+    /// splitter while it is running.
+    pub const fn debugger(&self) -> &Debugger {
+        &self.debugger
+    }
+
+    /// Limits how often per second the auto splitter gets updated, regardless
+    /// of the tick rate the auto splitter asks for. Passing [`None`] removes
+    /// the limit again. This applies to the currently loaded auto splitter as
+    /// well as all the auto splitters loaded in the future.
+    pub fn set_max_tick_rate(&self, ticks_per_second: Option<f64>) {
+        let interval = ticks_per_second
+            .filter(|&ticks| ticks > 0.0)
+            .map_or(0, |ticks| (1_000_000_000.0 / ticks) as u64);
+        self.min_tick_interval
+            .store(interval, atomic::Ordering::Relaxed);
+    }
+
+    /// Enables or disables smoothing the game time that the auto splitter
+    /// reports. Auto splitters usually read the game time from the game's
+    /// memory at a different rate than the game updates it, which can cause
+    /// the game time to stutter or jump backwards slightly. See
+    /// [`GameTimeSmoother`] for details. This is disabled by default and
+    /// applies to the currently loaded auto splitter as well as all the auto
+    /// splitters loaded in the future.
+    pub fn set_game_time_smoothing(&self, enabled: bool) {
+        self.smooth_game_time
+            .store(enabled, atomic::Ordering::Relaxed);
+    }
+
+    /// Attempts to load a wasm file containing an auto splitter module. The
+    /// settings that got stored in the run of the timer via
+    /// [`store_settings_map`] are handed to the auto splitter.
     pub fn load(&self, path: PathBuf, timer: T) -> Result<(), Error> {
         let data = fs::read(path).map_err(|e| Error::ReadFileFailed { source: e })?;
+        let settings_map = parse_settings_map(timer.get_timer().run());
 
         let auto_splitter = self
             .runtime
             .compile(&data)
             .map_err(|e| Error::LoadFailed { source: e })?
-            .instantiate(Timer(timer), None, None)
+            .instantiate(
+                Timer {
+                    timer,
+                    game_time_smoother: GameTimeSmoother::new(),
+                    smooth_game_time: self.smooth_game_time.clone(),
+                    debugger: self.debugger.clone(),
+                },
+                settings_map,
+                None,
+            )
             .map_err(|e| Error::LoadFailed { source: e })?;
 
         self.auto_splitter
@@ -728,11 +819,16 @@ impl<T: event::CommandSink + TimerQuery + Send + 'static> Runtime<T> {
 
 // This newtype is required because [`SharedTimer`](crate::timing::SharedTimer)
 // is an Arc<RwLock<T>>, so we can't implement the trait directly on it.
-struct Timer<E>(E);
+struct Timer<E> {
+    timer: E,
+    game_time_smoother: GameTimeSmoother,
+    smooth_game_time: Arc<AtomicBool>,
+    debugger: Debugger,
+}
 
 impl<E: event::CommandSink + TimerQuery + Send> AutoSplitTimer for Timer<E> {
     fn state(&self) -> TimerState {
-        match self.0.get_timer().current_phase() {
+        match self.timer.get_timer().current_phase() {
             TimerPhase::NotRunning => TimerState::NotRunning,
             TimerPhase::Running => TimerState::Running,
             TimerPhase::Paused => TimerState::Paused,
@@ -741,54 +837,66 @@ impl<E: event::CommandSink + TimerQuery + Send> AutoSplitTimer for Timer<E> {
     }
 
     fn start(&mut self) {
-        drop(self.0.start());
+        self.game_time_smoother.reset();
+        drop(self.timer.start());
     }
 
     fn split(&mut self) {
-        drop(self.0.split());
+        drop(self.timer.split());
     }
 
     fn skip_split(&mut self) {
-        drop(self.0.skip_split());
+        drop(self.timer.skip_split());
     }
 
     fn undo_split(&mut self) {
-        drop(self.0.undo_split());
+        drop(self.timer.undo_split());
     }
 
     fn reset(&mut self) {
-        drop(self.0.reset(None));
+        drop(self.timer.reset(None));
     }
 
     fn set_game_time(&mut self, time: time::Duration) {
-        drop(self.0.set_game_time(time.into()));
+        let mut game_time = time.into();
+        if self.smooth_game_time.load(atomic::Ordering::Relaxed) {
+            let real_time = self.timer.get_timer().snapshot().current_time().real_time;
+            game_time = self
+                .game_time_smoother
+                .push(real_time.unwrap_or_default(), game_time);
+        }
+        drop(self.timer.set_game_time(game_time));
     }
 
     fn pause_game_time(&mut self) {
-        drop(self.0.pause_game_time());
+        drop(self.timer.pause_game_time());
     }
 
     fn resume_game_time(&mut self) {
-        drop(self.0.resume_game_time());
+        drop(self.timer.resume_game_time());
     }
 
     fn set_variable(&mut self, name: &str, value: &str) {
-        drop(self.0.set_custom_variable(name.into(), value.into()));
+        self.debugger.set_variable(name, value);
+        drop(self.timer.set_custom_variable(name.into(), value.into()));
     }
 
     fn log_auto_splitter(&mut self, message: fmt::Arguments<'_>) {
         log::info!(target: "Auto Splitter", "{message}");
+        self.debugger
+            .log(LogKind::AutoSplitter, message.to_string());
     }
 
     fn log_runtime(&mut self, message: fmt::Arguments<'_>, log_level: LogLevel) {
-        let level = match log_level {
-            LogLevel::Trace => log::Level::Trace,
-            LogLevel::Debug => log::Level::Debug,
-            LogLevel::Info => log::Level::Info,
-            LogLevel::Warning => log::Level::Warn,
-            LogLevel::Error => log::Level::Error,
+        let (level, kind) = match log_level {
+            LogLevel::Trace => (log::Level::Trace, LogKind::Trace),
+            LogLevel::Debug => (log::Level::Debug, LogKind::Debug),
+            LogLevel::Info => (log::Level::Info, LogKind::Info),
+            LogLevel::Warning => (log::Level::Warn, LogKind::Warning),
+            LogLevel::Error => (log::Level::Error, LogKind::Error),
         };
         log::log!(target: "Auto Splitter", level, "{message}");
+        self.debugger.log(kind, message.to_string());
     }
 }
 
@@ -796,6 +904,8 @@ async fn run<T: event::CommandSink + TimerQuery + Send>(
     mut auto_splitter: watch::Receiver<Option<AutoSplitter<Timer<T>>>>,
     timeout_sender: watch::Sender<Option<Instant>>,
     interrupt_sender: watch::Sender<Option<InterruptHandle>>,
+    min_tick_interval: Arc<AtomicU64>,
+    debugger: Debugger,
 ) {
     'back_to_not_having_an_auto_splitter: loop {
         interrupt_sender.send(None).ok();
@@ -806,6 +916,7 @@ async fn run<T: event::CommandSink + TimerQuery + Send>(
                 Ok(()) => {
                     if let Some(auto_splitter) = &*auto_splitter.borrow() {
                         log::info!(target: "Auto Splitter", "Loaded auto splitter");
+                        debugger.clear();
                         let next_step = Instant::now();
                         interrupt_sender
                             .send(Some(auto_splitter.interrupt_handle()))
@@ -828,6 +939,7 @@ async fn run<T: event::CommandSink + TimerQuery + Send>(
             match result {
                 Ok(Ok(())) => {
                     log::info!(target: "Auto Splitter", "Replaced auto splitter");
+                    debugger.clear();
                     next_step = Instant::now();
                     interrupt_sender
                         .send(Some(auto_splitter.interrupt_handle()))
@@ -836,18 +948,29 @@ async fn run<T: event::CommandSink + TimerQuery + Send>(
                 }
                 Ok(Err(_)) => return,
                 Err(_) => {
-                    let result = auto_splitter.lock().update();
+                    let result = if debugger.begin_tick() {
+                        auto_splitter.lock().update()
+                    } else {
+                        Ok(())
+                    };
                     match result {
                         Ok(()) => {
+                            let min_interval = Duration::from_nanos(
+                                min_tick_interval.load(atomic::Ordering::Relaxed),
+                            );
                             next_step = next_step
                                 .into_std()
-                                .checked_add(auto_splitter.tick_rate())
+                                .checked_add(auto_splitter.tick_rate().max(min_interval))
                                 .map_or(next_step, |t| t.into());
 
                             timeout_sender.send(Some(next_step)).ok();
                         }
                         Err(e) => {
                             log::error!(target: "Auto Splitter", "Unloaded, because the script trapped: {:?}", e);
+                            debugger.log(
+                                LogKind::Error,
+                                format!("Unloaded, because the script trapped: {e:?}"),
+                            );
                             continue 'back_to_not_having_an_auto_splitter;
                         }
                     }
@@ -0,0 +1,212 @@
+//! Stores the settings map of an auto splitter in the auto splitter settings
+//! of a [`Run`], so the choices of the user are saved alongside the splits.
+//! The settings are stored in a `CustomSettings` element, similar to how the
+//! original LiveSplit stores the settings of its auto splitters:
+//!
+//! ```xml
+//! <CustomSettings>
+//!     <Setting id="levels" type="bool">True</Setting>
+//!     <Setting id="delay" type="float">1.5</Setting>
+//! </CustomSettings>
+//! ```
+
+use core::fmt;
+
+use super::settings::{List, Map, Value};
+use crate::{
+    platform::prelude::*,
+    util::xml::{
+        helper::{end_tag, parse_base, parse_children, text, Error},
+        Attributes, Reader, Writer, NO_ATTRIBUTES,
+    },
+    Run,
+};
+
+const CUSTOM_SETTINGS: &str = "CustomSettings";
+
+/// Parses the settings map that got stored in the run via
+/// [`store_settings_map`]. Returns [`None`] if the run doesn't contain any
+/// settings or they can't be parsed.
+pub fn parse_settings_map(run: &Run) -> Option<Map> {
+    let source = format!("<Root>{}</Root>", run.auto_splitter_settings());
+    let mut reader = Reader::new(&source);
+    let mut map = None;
+    parse_base::<_, Error>(&mut reader, "Root", |reader, _| {
+        parse_children(reader, |reader, tag, _| {
+            if tag.name() == CUSTOM_SETTINGS && map.is_none() {
+                map = Some(parse_map(reader)?);
+                Ok(())
+            } else {
+                end_tag(reader)
+            }
+        })
+    })
+    .ok()?;
+    map
+}
+
+/// Stores the settings map in the run. Any settings that were stored before
+/// are replaced. Everything else in the auto splitter settings, such as the
+/// settings of auto splitters of the original LiveSplit, is kept.
+pub fn store_settings_map(run: &mut Run, map: &Map) {
+    let settings = run.auto_splitter_settings_mut();
+    let start = settings.find("<CustomSettings");
+    let end = settings
+        .find("</CustomSettings>")
+        .map(|end| end + "</CustomSettings>".len());
+
+    let mut encoded = String::new();
+    let mut writer = Writer::new_skip_header(&mut encoded);
+    // Writing into a String can't fail.
+    let _ = writer.tag_with_content(CUSTOM_SETTINGS, NO_ATTRIBUTES, |writer| {
+        write_map(writer, map)
+    });
+
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => {
+            settings.replace_range(start..end, &encoded);
+        }
+        _ => settings.push_str(&encoded),
+    }
+}
+
+fn id_and_type(attributes: Attributes<'_>) -> (Option<String>, String) {
+    let (mut id, mut ty) = (None, String::new());
+    for (key, value) in attributes.iter() {
+        match key {
+            "id" => id = Some(value.unescape_str()),
+            "type" => ty = value.unescape_str(),
+            _ => {}
+        }
+    }
+    (id, ty)
+}
+
+fn parse_value(reader: &mut Reader<'_>, ty: &str) -> Result<Option<Value>, Error> {
+    let mut value = None;
+    match ty {
+        "map" => value = Some(Value::Map(parse_map(reader)?)),
+        "list" => value = Some(Value::List(parse_list(reader)?)),
+        "bool" => text(reader, |t| {
+            value = match &*t {
+                "True" => Some(Value::Bool(true)),
+                "False" => Some(Value::Bool(false)),
+                _ => None,
+            }
+        })?,
+        "int" => text(reader, |t| value = t.parse().ok().map(Value::I64))?,
+        "float" => text(reader, |t| value = t.parse().ok().map(Value::F64))?,
+        "string" => text(reader, |t| value = Some(Value::String(t.into())))?,
+        _ => end_tag(reader)?,
+    }
+    Ok(value)
+}
+
+fn parse_map(reader: &mut Reader<'_>) -> Result<Map, Error> {
+    let mut map = Map::new();
+    parse_children(reader, |reader, tag, attributes| {
+        if tag.name() != "Setting" {
+            return end_tag(reader);
+        }
+        let (id, ty) = id_and_type(attributes);
+        let value = parse_value(reader, &ty)?;
+        if let (Some(id), Some(value)) = (id, value) {
+            map.insert(id.into(), value);
+        }
+        Ok(())
+    })?;
+    Ok(map)
+}
+
+fn parse_list(reader: &mut Reader<'_>) -> Result<List, Error> {
+    let mut list = List::new();
+    parse_children(reader, |reader, tag, attributes| {
+        if tag.name() != "Setting" {
+            return end_tag(reader);
+        }
+        let (_, ty) = id_and_type(attributes);
+        if let Some(value) = parse_value(reader, &ty)? {
+            list.push(value);
+        }
+        Ok(())
+    })?;
+    Ok(list)
+}
+
+fn write_value<W: fmt::Write>(
+    writer: &mut Writer<W>,
+    id: Option<&str>,
+    value: &Value,
+) -> fmt::Result {
+    let ty = match value {
+        Value::Map(_) => "map",
+        Value::List(_) => "list",
+        Value::Bool(_) => "bool",
+        Value::I64(_) => "int",
+        Value::F64(_) => "float",
+        Value::String(_) => "string",
+        // Values of types that are added in the future can't be stored yet.
+        _ => return Ok(()),
+    };
+    writer.tag("Setting", |mut tag| {
+        if let Some(id) = id {
+            tag.attribute("id", id)?;
+        }
+        tag.attribute("type", ty)?;
+        match value {
+            Value::Map(map) => tag.content(|writer| write_map(writer, map)),
+            Value::List(list) => tag.content(|writer| {
+                for value in list.iter() {
+                    write_value(writer, None, value)?;
+                }
+                Ok(())
+            }),
+            Value::Bool(v) => tag.text_content(if *v { "True" } else { "False" }),
+            Value::I64(v) => tag.text_content(&*v.to_string()),
+            Value::F64(v) => tag.text_content(&*v.to_string()),
+            Value::String(v) => tag.text_content(&**v),
+            _ => Ok(()),
+        }
+    })
+}
+
+fn write_map<W: fmt::Write>(writer: &mut Writer<W>, map: &Map) -> fmt::Result {
+    for (id, value) in map.iter() {
+        write_value(writer, Some(id), value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::create_run;
+
+    #[test]
+    fn roundtrip() {
+        let mut run = create_run(&["A"]);
+        *run.auto_splitter_settings_mut() = "<Version>1.0</Version>".into();
+
+        let mut list = List::new();
+        list.push(Value::I64(3));
+        list.push(Value::String("a < b".into()));
+        let mut map = Map::new();
+        map.insert("levels".into(), Value::Bool(true));
+        map.insert("delay".into(), Value::F64(1.5));
+        map.insert("route".into(), Value::List(list));
+
+        store_settings_map(&mut run, &map);
+        store_settings_map(&mut run, &map);
+        assert!(run
+            .auto_splitter_settings()
+            .starts_with("<Version>1.0</Version>"));
+        assert_eq!(
+            run.auto_splitter_settings()
+                .matches(CUSTOM_SETTINGS)
+                .count(),
+            2
+        );
+
+        assert_eq!(parse_settings_map(&run), Some(map));
+    }
+}
@@ -37,7 +37,7 @@ impl Default for Settings {
 }
 
 /// The state object describes the information to visualize for this component.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct State {
     /// The background shown behind the component.
     pub background: Gradient,
@@ -10,7 +10,7 @@ use crate::{
     platform::prelude::*,
     settings::{Color, Field, Gradient, SettingsDescription, Value},
     timing::{
-        formatter::{Accuracy, Regular, TimeFormatter},
+        formatter::{Accuracy, Regular, TimeFormatter, TimePattern},
         Snapshot,
     },
     TimerPhase,
@@ -47,6 +47,9 @@ pub struct Settings {
     pub value_color: Option<Color>,
     /// The accuracy of the time shown.
     pub accuracy: Accuracy,
+    /// The pattern to format the time with. If set to `None` the accuracy is
+    /// used instead.
+    pub time_pattern: Option<TimePattern>,
 }
 
 impl Default for Settings {
@@ -58,6 +61,7 @@ impl Default for Settings {
             label_color: None,
             value_color: None,
             accuracy: Accuracy::Seconds,
+            time_pattern: None,
         }
     }
 }
@@ -85,54 +89,53 @@ impl Component {
 
     /// Accesses the name of the component.
     pub fn name(&self) -> Cow<'static, str> {
-        self.text(self.settings.comparison_override.as_deref())
+        self.key(self.settings.comparison_override.as_deref())
+            .name()
     }
 
-    fn text(&self, comparison: Option<&str>) -> Cow<'static, str> {
-        if let Some(comparison) = comparison {
-            match comparison {
-                comparison::personal_best::NAME => "Current Pace".into(),
-                comparison::best_segments::NAME => "Best Possible Time".into(),
-                comparison::worst_segments::NAME => "Worst Possible Time".into(),
-                comparison::average_segments::NAME => "Predicted Time".into(),
-                comparison => format!("Current Pace ({})", comparison::shorten(comparison)).into(),
-            }
-        } else {
-            "Current Pace".into()
-        }
+    fn key<'a>(&self, comparison: Option<&'a str>) -> key_value::Key<'a> {
+        let (label, comparison) = match comparison {
+            None | Some(comparison::personal_best::NAME) => ("Current Pace", None),
+            Some(comparison::best_segments::NAME) => ("Best Possible Time", None),
+            Some(comparison::worst_segments::NAME) => ("Worst Possible Time", None),
+            Some(comparison::average_segments::NAME) => ("Predicted Time", None),
+            comparison => ("Current Pace", comparison),
+        };
+        key_value::Key { label, comparison }
     }
 
     /// Updates the component's state based on the timer provided.
     pub fn update_state(&self, state: &mut key_value::State, timer: &Snapshot<'_>) {
         let comparison = comparison::resolve(&self.settings.comparison_override, timer);
         let comparison = comparison::or_current(comparison, timer);
-        let key = self.text(Some(comparison));
+        self.key(Some(comparison)).update(&mut state.key);
 
-        let (current_pace, updates_frequently) =
-            if timer.current_phase() == TimerPhase::NotRunning && key.starts_with("Current Pace") {
-                (None, false)
-            } else {
-                current_pace::calculate(timer, comparison)
-            };
+        let (current_pace, updates_frequently) = if timer.current_phase() == TimerPhase::NotRunning
+            && state.key.starts_with("Current Pace")
+        {
+            (None, false)
+        } else {
+            current_pace::calculate(timer, comparison)
+        };
 
         state.background = self.settings.background;
         state.key_color = self.settings.label_color;
         state.value_color = self.settings.value_color;
         state.semantic_color = Default::default();
 
-        state.key.clear();
-        state.key.push_str(&key); // FIXME: Uncow this
-
         state.value.clear();
-        let _ = write!(
-            state.value,
-            "{}",
-            Regular::with_accuracy(self.settings.accuracy).format(current_pace)
-        );
+        let _ = if let Some(pattern) = &self.settings.time_pattern {
+            write!(state.value, "{}", pattern.format(current_pace))
+        } else {
+            write!(
+                state.value,
+                "{}",
+                Regular::with_accuracy(self.settings.accuracy).format(current_pace)
+            )
+        };
 
         state.key_abbreviations.clear();
-        // FIXME: This &* probably is different when key is uncowed
-        match &*key {
+        match state.key.as_str() {
             "Best Possible Time" => {
                 state.key_abbreviations.push("Best Poss. Time".into());
                 state.key_abbreviations.push("Best Time".into());
@@ -201,6 +204,11 @@ impl Component {
                 "The accuracy of the predicted time shown.".into(),
                 self.settings.accuracy.into(),
             ),
+            Field::new(
+                "Time Pattern".into(),
+                "The pattern to format the predicted time with, like \"H:mm:ss.ff\". If not specified, the accuracy is used.".into(),
+                self.settings.time_pattern.clone().into(),
+            ),
         ])
     }
 
@@ -219,6 +227,7 @@ impl Component {
             3 => self.settings.label_color = value.into(),
             4 => self.settings.value_color = value.into(),
             5 => self.settings.accuracy = value.into(),
+            6 => self.settings.time_pattern = value.into(),
             _ => panic!("Unsupported Setting Index"),
         }
     }
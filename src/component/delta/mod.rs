@@ -9,7 +9,7 @@ use crate::{
     platform::prelude::*,
     settings::{Color, Field, Gradient, SemanticColor, SettingsDescription, Value},
     timing::{
-        formatter::{Accuracy, Delta, TimeFormatter},
+        formatter::{Accuracy, Delta, TimeFormatter, TimePattern},
         Snapshot,
     },
     GeneralLayoutSettings,
@@ -48,6 +48,9 @@ pub struct Settings {
     pub drop_decimals: bool,
     /// The accuracy of the time shown.
     pub accuracy: Accuracy,
+    /// The pattern to format the time with. If set to `None` the accuracy is
+    /// used instead.
+    pub time_pattern: Option<TimePattern>,
 }
 
 impl Default for Settings {
@@ -59,6 +62,7 @@ impl Default for Settings {
             label_color: None,
             drop_decimals: true,
             accuracy: Accuracy::Tenths,
+            time_pattern: None,
         }
     }
 }
@@ -136,11 +140,15 @@ impl Component {
         state.key.push_str(text);
 
         state.value.clear();
-        let _ = write!(
-            state.value,
-            "{}",
-            Delta::custom(self.settings.drop_decimals, self.settings.accuracy).format(delta),
-        );
+        let _ = if let Some(pattern) = &self.settings.time_pattern {
+            write!(state.value, "{}", pattern.format_delta(delta))
+        } else {
+            write!(
+                state.value,
+                "{}",
+                Delta::custom(self.settings.drop_decimals, self.settings.accuracy).format(delta),
+            )
+        };
 
         state.key_abbreviations.clear();
         if let Some(abbreviation) = comparison::try_shorten(text) {
@@ -197,6 +205,11 @@ impl Component {
                 "The accuracy of the delta shown.".into(),
                 self.settings.accuracy.into()
             ),
+            Field::new(
+                "Time Pattern".into(),
+                "The pattern to format the delta with, like \"H:mm:ss.ff\". If not specified, the accuracy is used.".into(),
+                self.settings.time_pattern.clone().into(),
+            ),
         ])
     }
 
@@ -215,6 +228,7 @@ impl Component {
             3 => self.settings.label_color = value.into(),
             4 => self.settings.drop_decimals = value.into(),
             5 => self.settings.accuracy = value.into(),
+            6 => self.settings.time_pattern = value.into(),
             _ => panic!("Unsupported Setting Index"),
         }
     }
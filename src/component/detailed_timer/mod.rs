@@ -11,7 +11,7 @@ use crate::{
     platform::prelude::*,
     settings::{Color, Field, Gradient, Image, ImageCache, ImageId, SettingsDescription, Value},
     timing::{
-        formatter::{Accuracy, DigitsFormat, SegmentTime, TimeFormatter},
+        formatter::{Accuracy, DigitsFormat, SegmentTime, TimeFormatter, TimePattern},
         Snapshot,
     },
     GeneralLayoutSettings, TimeSpan, TimerPhase,
@@ -70,7 +70,7 @@ pub struct Settings {
 }
 
 /// The state object describes the information to visualize for this component.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct State {
     /// The background shown behind the component.
     pub background: Gradient,
@@ -101,7 +101,7 @@ pub struct State {
 }
 
 /// The state object describing a comparison to visualize.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ComparisonState {
     /// The name of the comparison.
     pub name: String,
@@ -463,6 +463,16 @@ impl Component {
                 "Specifies whether the segment icon should be shown.".into(),
                 self.settings.display_icon.into(),
             ),
+            Field::new(
+                "Timer Time Pattern".into(),
+                "The pattern to format the time of the main timer with, like \"H:mm:ss.ff\". If not specified, the pattern specified in the general settings for the layout is used. If that is not specified either, the digits format and accuracy are used.".into(),
+                self.settings.timer.time_pattern.clone().into(),
+            ),
+            Field::new(
+                "Segment Timer Time Pattern".into(),
+                "The pattern to format the time of the segment timer with, like \"m:ss.ff\". If not specified, the pattern specified in the general settings for the layout is used. If that is not specified either, the digits format and accuracy are used.".into(),
+                self.settings.segment_timer.time_pattern.clone().into(),
+            ),
         ])
     }
 
@@ -540,6 +550,16 @@ impl Component {
             18 => self.settings.show_segment_name = value.into(),
             19 => self.settings.segment_name_color = value.into(),
             20 => self.settings.display_icon = value.into(),
+            21 => {
+                let value: Option<TimePattern> = value.into();
+                self.settings.timer.time_pattern.clone_from(&value);
+                self.timer.settings_mut().time_pattern = value;
+            }
+            22 => {
+                let value: Option<TimePattern> = value.into();
+                self.settings.segment_timer.time_pattern.clone_from(&value);
+                self.segment_timer.settings_mut().time_pattern = value;
+            }
             _ => panic!("Unsupported Setting Index"),
         }
     }
@@ -0,0 +1,142 @@
+//! Provides the Host Component and relevant types for using it. The Host
+//! Component is a component whose behavior is entirely defined by the host of
+//! this library, such as a frontend that wants to extend layouts with its own
+//! components without forking livesplit-core. The host provides the state to
+//! visualize and handles the settings of the component.
+
+use super::blank_space;
+use crate::{
+    layout::ComponentState,
+    platform::{prelude::*, Arc},
+    settings::{Gradient, SettingsDescription, Value},
+    timing::Snapshot,
+};
+use serde_derive::{Deserialize, Serialize};
+
+/// Implemented by the host to define the behavior of a Host Component. The
+/// same host may be shared by multiple clones of the component, so any
+/// changes need to be handled through interior mutability.
+pub trait Host: Send + Sync {
+    /// The name of the component.
+    fn name(&self) -> String;
+    /// Calculates the state to visualize based on the timer provided.
+    fn state(&self, timer: &Snapshot<'_>) -> ComponentState;
+    /// Provides a general description of the settings of the component.
+    fn settings_description(&self) -> SettingsDescription;
+    /// Changes a setting of the component based on its Settings Description
+    /// index.
+    fn set_value(&self, index: usize, value: Value);
+    /// Encodes the settings of the component, so they can be stored as part of
+    /// the layout.
+    fn settings(&self) -> String;
+    /// Loads the settings that were previously encoded by
+    /// [`settings`](Self::settings).
+    fn load_settings(&self, settings: &str);
+}
+
+/// The Host Component is a component whose behavior is entirely defined by
+/// the host of this library. When a layout containing a Host Component is
+/// loaded, the component is not attached to a host yet and doesn't show
+/// anything until the host gets attached.
+#[derive(Clone)]
+pub struct Component {
+    settings: Settings,
+    host: Option<Arc<dyn Host>>,
+}
+
+/// The Settings for this component.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The kind of the component. The host uses this to recognize its
+    /// components when a layout gets loaded.
+    pub kind: String,
+    /// The settings of the component as encoded by the host.
+    pub settings: String,
+}
+
+impl Component {
+    /// Creates a new Host Component of the kind provided that is attached to
+    /// the host provided.
+    pub fn new(kind: String, host: Arc<dyn Host>) -> Self {
+        let settings = host.settings();
+        Self {
+            settings: Settings { kind, settings },
+            host: Some(host),
+        }
+    }
+
+    /// Creates a new Host Component with the given settings that is not
+    /// attached to any host yet.
+    pub const fn with_settings(settings: Settings) -> Self {
+        Self {
+            settings,
+            host: None,
+        }
+    }
+
+    /// Accesses the kind of the component.
+    pub fn kind(&self) -> &str {
+        &self.settings.kind
+    }
+
+    /// Returns whether the component is attached to a host.
+    pub const fn is_attached(&self) -> bool {
+        self.host.is_some()
+    }
+
+    /// Attaches the component to the host provided. The host is asked to load
+    /// the settings that are stored in the component.
+    pub fn attach(&mut self, host: Arc<dyn Host>) {
+        host.load_settings(&self.settings.settings);
+        self.host = Some(host);
+    }
+
+    /// Accesses the settings of the component.
+    pub fn settings(&self) -> Settings {
+        match &self.host {
+            Some(host) => Settings {
+                kind: self.settings.kind.clone(),
+                settings: host.settings(),
+            },
+            None => self.settings.clone(),
+        }
+    }
+
+    /// Accesses the name of the component.
+    pub fn name(&self) -> String {
+        match &self.host {
+            Some(host) => host.name(),
+            None => self.settings.kind.clone(),
+        }
+    }
+
+    /// Calculates the component's state based on the timer provided. If the
+    /// component is not attached to a host, an empty state is returned.
+    pub fn state(&self, timer: &Snapshot<'_>) -> ComponentState {
+        match &self.host {
+            Some(host) => host.state(timer),
+            None => ComponentState::BlankSpace(blank_space::State {
+                background: Gradient::Transparent,
+                size: 0,
+            }),
+        }
+    }
+
+    /// Provides a general description of the settings. If the component is
+    /// not attached to a host, there are no settings.
+    pub fn settings_description(&self) -> SettingsDescription {
+        match &self.host {
+            Some(host) => host.settings_description(),
+            None => SettingsDescription::default(),
+        }
+    }
+
+    /// Changes a setting of the component based on its Settings Description
+    /// index. This does nothing if the component is not attached to a host.
+    pub fn set_value(&mut self, index: usize, value: Value) {
+        if let Some(host) = &self.host {
+            host.set_value(index, value);
+        }
+    }
+}
@@ -4,15 +4,17 @@
 //! state object representation.
 
 use crate::{
+    comparison,
     platform::prelude::*,
     settings::{Color, Gradient, SemanticColor},
 };
 use alloc::borrow::Cow;
+use core::fmt::Write;
 use serde_derive::{Deserialize, Serialize};
 
 /// The state object describes the information to visualize for a key value
 /// based component.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct State {
     /// The background shown behind the component.
     pub background: Gradient,
@@ -49,6 +51,49 @@ impl State {
     }
 }
 
+/// The key of a component that consists of a label and optionally the
+/// comparison it refers to, like `Possible Time Save (PB)`. Most keys don't
+/// refer to a comparison, so their name doesn't need to be allocated and the
+/// key in the state only needs to be rewritten when it changes.
+pub(super) struct Key<'a> {
+    pub label: &'static str,
+    pub comparison: Option<&'a str>,
+}
+
+impl Key<'_> {
+    /// Turns the key into the name of the component. This only allocates if
+    /// the key refers to a comparison.
+    pub fn name(&self) -> Cow<'static, str> {
+        match self.comparison {
+            None => Cow::Borrowed(self.label),
+            Some(comparison) => Cow::Owned(format!(
+                "{} ({})",
+                self.label,
+                comparison::shorten(comparison),
+            )),
+        }
+    }
+
+    /// Writes the key into the key of the state, unless it is already there.
+    pub fn update(&self, key: &mut String) {
+        let unchanged = match self.comparison {
+            None => key == self.label,
+            Some(comparison) => key
+                .strip_prefix(self.label)
+                .and_then(|rem| rem.strip_prefix(" ("))
+                .and_then(|rem| rem.strip_suffix(')'))
+                .is_some_and(|rem| rem == comparison::shorten(comparison)),
+        };
+        if !unchanged {
+            key.clear();
+            key.push_str(self.label);
+            if let Some(comparison) = self.comparison {
+                let _ = write!(key, " ({})", comparison::shorten(comparison));
+            }
+        }
+    }
+}
+
 pub(super) const DEFAULT_GRADIENT: Gradient = Gradient::Vertical(
     Color {
         red: 1.0,
@@ -9,6 +9,7 @@ pub mod current_pace;
 pub mod delta;
 pub mod detailed_timer;
 pub mod graph;
+pub mod host;
 pub mod pb_chance;
 pub mod possible_time_save;
 pub mod previous_segment;
@@ -29,6 +30,7 @@ pub use current_pace::Component as CurrentPace;
 pub use delta::Component as Delta;
 pub use detailed_timer::Component as DetailedTimer;
 pub use graph::Component as Graph;
+pub use host::Component as Host;
 pub use pb_chance::Component as PbChance;
 pub use possible_time_save::Component as PossibleTimeSave;
 pub use previous_segment::Component as PreviousSegment;
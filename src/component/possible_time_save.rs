@@ -11,7 +11,7 @@ use crate::{
     platform::prelude::*,
     settings::{Color, Field, Gradient, SettingsDescription, Value},
     timing::{
-        formatter::{Accuracy, SegmentTime, TimeFormatter},
+        formatter::{Accuracy, SegmentTime, TimeFormatter, TimePattern},
         Snapshot,
     },
     TimerPhase,
@@ -53,6 +53,9 @@ pub struct Settings {
     pub value_color: Option<Color>,
     /// The accuracy of the time shown.
     pub accuracy: Accuracy,
+    /// The pattern to format the time with. If set to `None` the accuracy is
+    /// used instead.
+    pub time_pattern: Option<TimePattern>,
 }
 
 impl Default for Settings {
@@ -65,6 +68,7 @@ impl Default for Settings {
             label_color: None,
             value_color: None,
             accuracy: Accuracy::Hundredths,
+            time_pattern: None,
         }
     }
 }
@@ -92,34 +96,27 @@ impl Component {
 
     /// Accesses the name of the component.
     pub fn name(&self) -> Cow<'static, str> {
-        self.text(
-            self.settings
-                .comparison_override
-                .as_ref()
-                .map(String::as_ref),
-        )
+        self.key(self.settings.comparison_override.as_deref())
+            .name()
     }
 
-    fn text(&self, comparison: Option<&str>) -> Cow<'static, str> {
-        let text = if self.settings.total_possible_time_save {
-            "Total Possible Time Save"
-        } else {
-            "Possible Time Save"
-        };
-        let mut text = Cow::from(text);
-        if let Some(comparison) = comparison {
-            write!(text.to_mut(), " ({})", comparison::shorten(comparison)).unwrap();
+    const fn key<'a>(&self, comparison: Option<&'a str>) -> key_value::Key<'a> {
+        key_value::Key {
+            label: if self.settings.total_possible_time_save {
+                "Total Possible Time Save"
+            } else {
+                "Possible Time Save"
+            },
+            comparison,
         }
-        text
     }
 
     /// Updates the component's state based on the timer provided.
     pub fn update_state(&self, state: &mut key_value::State, timer: &Snapshot<'_>) {
         let segment_index = timer.current_split_index();
         let current_phase = timer.current_phase();
-        let comparison = comparison::resolve(&self.settings.comparison_override, timer);
-        let text = self.text(comparison);
-        let comparison = comparison::or_current(comparison, timer);
+        let resolved_comparison = comparison::resolve(&self.settings.comparison_override, timer);
+        let comparison = comparison::or_current(resolved_comparison, timer);
 
         let (time, updates_frequently) = if self.settings.total_possible_time_save {
             let (time, updates_frequently) =
@@ -136,15 +133,18 @@ impl Component {
         state.value_color = self.settings.value_color;
         state.semantic_color = Default::default();
 
-        state.key.clear();
-        state.key.push_str(&text); // FIXME: Uncow
+        self.key(resolved_comparison).update(&mut state.key);
 
         state.value.clear();
-        let _ = write!(
-            state.value,
-            "{}",
-            SegmentTime::with_accuracy(self.settings.accuracy).format(time)
-        );
+        let _ = if let Some(pattern) = &self.settings.time_pattern {
+            write!(state.value, "{}", pattern.format(time))
+        } else {
+            write!(
+                state.value,
+                "{}",
+                SegmentTime::with_accuracy(self.settings.accuracy).format(time)
+            )
+        };
 
         state.key_abbreviations.clear();
         if self.settings.total_possible_time_save {
@@ -206,6 +206,11 @@ impl Component {
                 "The accuracy of the possible time save shown.".into(),
                 self.settings.accuracy.into(),
             ),
+            Field::new(
+                "Time Pattern".into(),
+                "The pattern to format the possible time save with, like \"H:mm:ss.ff\". If not specified, the accuracy is used.".into(),
+                self.settings.time_pattern.clone().into(),
+            ),
         ])
     }
 
@@ -225,6 +230,7 @@ impl Component {
             4 => self.settings.label_color = value.into(),
             5 => self.settings.value_color = value.into(),
             6 => self.settings.accuracy = value.into(),
+            7 => self.settings.time_pattern = value.into(),
             _ => panic!("Unsupported Setting Index"),
         }
     }
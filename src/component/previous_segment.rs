@@ -12,7 +12,7 @@ use crate::{
     platform::prelude::*,
     settings::{Color, Field, Gradient, SemanticColor, SettingsDescription, Value},
     timing::{
-        formatter::{Accuracy, Delta, SegmentTime, TimeFormatter},
+        formatter::{Accuracy, Delta, SegmentTime, TimeFormatter, TimePattern},
         Snapshot,
     },
     GeneralLayoutSettings, TimerPhase,
@@ -52,6 +52,9 @@ pub struct Settings {
     pub drop_decimals: bool,
     /// The accuracy of the time shown.
     pub accuracy: Accuracy,
+    /// The pattern to format the time with. If set to `None` the accuracy is
+    /// used instead.
+    pub time_pattern: Option<TimePattern>,
     /// Determines if the time save that could've been saved is shown in
     /// addition to the previous segment.
     pub show_possible_time_save: bool,
@@ -66,6 +69,7 @@ impl Default for Settings {
             label_color: None,
             drop_decimals: true,
             accuracy: Accuracy::Tenths,
+            time_pattern: None,
             show_possible_time_save: false,
         }
     }
@@ -94,26 +98,19 @@ impl Component {
 
     /// Accesses the name of the component.
     pub fn name(&self) -> Cow<'static, str> {
-        self.text(
-            false,
-            self.settings
-                .comparison_override
-                .as_ref()
-                .map(String::as_ref),
-        )
+        self.key(false, self.settings.comparison_override.as_deref())
+            .name()
     }
 
-    fn text(&self, live: bool, comparison: Option<&str>) -> Cow<'static, str> {
-        let text = if live {
-            "Live Segment"
-        } else {
-            "Previous Segment"
-        };
-        let mut text = Cow::from(text);
-        if let Some(comparison) = comparison {
-            write!(text.to_mut(), " ({})", comparison::shorten(comparison)).unwrap();
+    const fn key<'a>(&self, live: bool, comparison: Option<&'a str>) -> key_value::Key<'a> {
+        key_value::Key {
+            label: if live {
+                "Live Segment"
+            } else {
+                "Previous Segment"
+            },
+            comparison,
         }
-        text
     }
 
     /// Updates the component's state based on the timer and layout settings
@@ -203,29 +200,36 @@ impl Component {
 
         let value_color = Some(semantic_color.visualize(layout_settings));
 
-        let text = self.text(live_segment.is_some(), resolved_comparison);
-
         state.background = self.settings.background;
         state.key_color = self.settings.label_color;
         state.value_color = value_color;
         state.semantic_color = semantic_color;
 
-        state.key.clear();
-        state.key.push_str(&text); // FIXME: Uncow
+        self.key(live_segment.is_some(), resolved_comparison)
+            .update(&mut state.key);
 
         state.value.clear();
-        let _ = write!(
-            state.value,
-            "{}",
-            Delta::custom(self.settings.drop_decimals, self.settings.accuracy).format(time_change),
-        );
+        if let Some(pattern) = &self.settings.time_pattern {
+            let _ = write!(state.value, "{}", pattern.format_delta(time_change));
 
-        if self.settings.show_possible_time_save {
+            if self.settings.show_possible_time_save {
+                let _ = write!(state.value, " / {}", pattern.format(previous_possible));
+            }
+        } else {
             let _ = write!(
                 state.value,
-                " / {}",
-                SegmentTime::with_accuracy(self.settings.accuracy).format(previous_possible),
+                "{}",
+                Delta::custom(self.settings.drop_decimals, self.settings.accuracy)
+                    .format(time_change),
             );
+
+            if self.settings.show_possible_time_save {
+                let _ = write!(
+                    state.value,
+                    " / {}",
+                    SegmentTime::with_accuracy(self.settings.accuracy).format(previous_possible),
+                );
+            }
         }
 
         state.key_abbreviations.clear();
@@ -293,6 +297,11 @@ impl Component {
                 "Specifies whether to show how much time could've been saved for the previous segment in addition to the time saved or lost.".into(),
                 self.settings.show_possible_time_save.into(),
             ),
+            Field::new(
+                "Time Pattern".into(),
+                "The pattern to format the time with, like \"H:mm:ss.ff\". If not specified, the accuracy is used.".into(),
+                self.settings.time_pattern.clone().into(),
+            ),
         ])
     }
 
@@ -312,6 +321,7 @@ impl Component {
             4 => self.settings.drop_decimals = value.into(),
             5 => self.settings.accuracy = value.into(),
             6 => self.settings.show_possible_time_save = value.into(),
+            7 => self.settings.time_pattern = value.into(),
             _ => panic!("Unsupported Setting Index"),
         }
     }
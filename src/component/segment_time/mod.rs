@@ -9,7 +9,7 @@ use crate::{
     comparison,
     platform::prelude::*,
     settings::{Color, Field, Gradient, SettingsDescription, Value},
-    timing::formatter::{Accuracy, SegmentTime, TimeFormatter},
+    timing::formatter::{Accuracy, SegmentTime, TimeFormatter, TimePattern},
     Timer, TimerPhase,
 };
 use alloc::borrow::Cow;
@@ -47,6 +47,9 @@ pub struct Settings {
     pub value_color: Option<Color>,
     /// The accuracy of the time shown.
     pub accuracy: Accuracy,
+    /// The pattern to format the time with. If set to `None` the accuracy is
+    /// used instead.
+    pub time_pattern: Option<TimePattern>,
 }
 
 impl Default for Settings {
@@ -58,6 +61,7 @@ impl Default for Settings {
             label_color: None,
             value_color: None,
             accuracy: Accuracy::Hundredths,
+            time_pattern: None,
         }
     }
 }
@@ -85,29 +89,26 @@ impl Component {
 
     /// Accesses the name of the component.
     pub fn name(&self) -> Cow<'static, str> {
-        self.text(self.settings.comparison_override.as_deref())
+        self.key(self.settings.comparison_override.as_deref())
+            .name()
     }
 
-    fn text(&self, comparison: Option<&str>) -> Cow<'static, str> {
-        if let Some(comparison) = comparison {
-            match comparison {
-                comparison::best_segments::NAME => "Best Segment Time".into(),
-                comparison::worst_segments::NAME => "Worst Segment Time".into(),
-                comparison::average_segments::NAME => "Average Segment Time".into(),
-                comparison::median_segments::NAME => "Median Segment Time".into(),
-                comparison::latest_run::NAME => "Latest Segment Time".into(),
-                comparison => format!("Segment Time ({})", comparison::shorten(comparison)).into(),
-            }
-        } else {
-            "Segment Time".into()
-        }
+    fn key<'a>(&self, comparison: Option<&'a str>) -> key_value::Key<'a> {
+        let (label, comparison) = match comparison {
+            Some(comparison::best_segments::NAME) => ("Best Segment Time", None),
+            Some(comparison::worst_segments::NAME) => ("Worst Segment Time", None),
+            Some(comparison::average_segments::NAME) => ("Average Segment Time", None),
+            Some(comparison::median_segments::NAME) => ("Median Segment Time", None),
+            Some(comparison::latest_run::NAME) => ("Latest Segment Time", None),
+            comparison => ("Segment Time", comparison),
+        };
+        key_value::Key { label, comparison }
     }
 
     /// Updates the component's state based on the timer provided.
     pub fn update_state(&self, state: &mut key_value::State, timer: &Timer) {
         let resolved_comparison = comparison::resolve(&self.settings.comparison_override, timer);
         let comparison = comparison::or_current(resolved_comparison, timer);
-        let key = self.text(resolved_comparison); // FIXME: Uncow
 
         let time = catch! {
             // FIXME: We shouldn't need to manually do this "bounds check".
@@ -128,18 +129,21 @@ impl Component {
         state.value_color = self.settings.value_color;
         state.semantic_color = Default::default();
 
-        state.key.clear();
-        state.key.push_str(&key);
+        self.key(resolved_comparison).update(&mut state.key);
 
         state.value.clear();
-        let _ = write!(
-            state.value,
-            "{}",
-            SegmentTime::with_accuracy(self.settings.accuracy).format(time),
-        );
+        let _ = if let Some(pattern) = &self.settings.time_pattern {
+            write!(state.value, "{}", pattern.format(time))
+        } else {
+            write!(
+                state.value,
+                "{}",
+                SegmentTime::with_accuracy(self.settings.accuracy).format(time),
+            )
+        };
 
         state.key_abbreviations.clear();
-        match &*key {
+        match state.key.as_str() {
             "Best Segment Time" => {
                 state.key_abbreviations.push("Best Seg. Time".into());
                 state.key_abbreviations.push("Best Segment".into());
@@ -214,6 +218,11 @@ impl Component {
                 "The accuracy of the segment time shown.".into(),
                 self.settings.accuracy.into(),
             ),
+            Field::new(
+                "Time Pattern".into(),
+                "The pattern to format the segment time with, like \"H:mm:ss.ff\". If not specified, the accuracy is used.".into(),
+                self.settings.time_pattern.clone().into(),
+            ),
         ])
     }
 
@@ -232,6 +241,7 @@ impl Component {
             3 => self.settings.label_color = value.into(),
             4 => self.settings.value_color = value.into(),
             5 => self.settings.accuracy = value.into(),
+            6 => self.settings.time_pattern = value.into(),
             _ => panic!("Unsupported Setting Index"),
         }
     }
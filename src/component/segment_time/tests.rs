@@ -3,6 +3,7 @@ use crate::{
     util::tests_helper::{self, make_progress_run_with_splits_opt, run_with_splits, start_run},
     Timer,
 };
+use alloc::borrow::Cow;
 
 fn create_timer() -> Timer {
     let mut timer = tests_helper::create_timer(&["A", "B"]);
@@ -48,3 +49,21 @@ fn is_empty_when_attempt_is_finished() {
     let state = component.state(&timer);
     assert_eq!(&*state.value, "—");
 }
+
+#[test]
+fn key_is_rewritten_in_place_when_the_comparison_changes() {
+    let mut component = Component::new();
+    let timer = create_timer();
+    let mut state = component.state(&timer);
+    assert_eq!(&*state.key, "Segment Time");
+    assert!(matches!(component.name(), Cow::Borrowed("Segment Time")));
+
+    component.settings_mut().comparison_override = Some("Best Segments".into());
+    component.update_state(&mut state, &timer);
+    assert_eq!(&*state.key, "Best Segment Time");
+
+    component.settings_mut().comparison_override = Some("Personal Best".into());
+    component.update_state(&mut state, &timer);
+    assert_eq!(&*state.key, "Segment Time (PB)");
+    assert_eq!(component.name(), "Segment Time (PB)");
+}
@@ -12,7 +12,7 @@ use crate::settings::{SettingsDescription, Value};
 pub struct Component;
 
 /// The state object describes the information to visualize for this component.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct State;
 
 #[cfg(feature = "std")]
@@ -5,7 +5,7 @@ use crate::{
     platform::prelude::*,
     settings::{Color, SemanticColor},
     timing::{
-        formatter::{Delta, Regular, SegmentTime, TimeFormatter},
+        formatter::{Delta, Regular, SegmentTime, TimeFormatter, TimePattern},
         Snapshot,
     },
     util::Clear,
@@ -57,6 +57,10 @@ pub struct TimeColumn {
     /// of the Timer is used for showing the time. Otherwise the Timing Method
     /// provided is used.
     pub timing_method: Option<TimingMethod>,
+    /// The pattern to format the time with. Deltas are prefixed with a plus
+    /// if they are positive. If set to `None` the accuracies of the Splits
+    /// Component are used instead.
+    pub time_pattern: Option<TimePattern>,
 }
 
 /// A column that shows a variable.
@@ -147,12 +151,13 @@ impl Default for TimeColumn {
             update_trigger: ColumnUpdateTrigger::Contextual,
             comparison_override: None,
             timing_method: None,
+            time_pattern: None,
         }
     }
 }
 
 /// Describes the state of a single segment's column to visualize.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ColumnState {
     /// The value shown in the column.
     pub value: String,
@@ -289,13 +294,17 @@ fn update_time_column(
     state.value.clear();
 
     if !is_empty {
-        let _ = match formatter {
-            ColumnFormatter::Time => write!(
+        let _ = match (formatter, &column_settings.time_pattern) {
+            (ColumnFormatter::Delta, Some(pattern)) => {
+                write!(state.value, "{}", pattern.format_delta(column_value))
+            }
+            (_, Some(pattern)) => write!(state.value, "{}", pattern.format(column_value)),
+            (ColumnFormatter::Time, None) => write!(
                 state.value,
                 "{}",
                 Regular::with_accuracy(splits_settings.split_time_accuracy).format(column_value)
             ),
-            ColumnFormatter::Delta => write!(
+            (ColumnFormatter::Delta, None) => write!(
                 state.value,
                 "{}",
                 Delta::custom(
@@ -304,7 +313,7 @@ fn update_time_column(
                 )
                 .format(column_value)
             ),
-            ColumnFormatter::SegmentTime => {
+            (ColumnFormatter::SegmentTime, None) => {
                 write!(
                     state.value,
                     "{}",
@@ -28,7 +28,7 @@ pub use column::{
 };
 
 const SETTINGS_BEFORE_COLUMNS: usize = 15;
-const SETTINGS_PER_TIME_COLUMN: usize = 6;
+const SETTINGS_PER_TIME_COLUMN: usize = 7;
 const SETTINGS_PER_VARIABLE_COLUMN: usize = 2;
 
 /// The Splits Component is the main component for visualizing all the split
@@ -103,7 +103,7 @@ pub struct Settings {
 }
 
 /// The state object that describes a single segment's information to visualize.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SplitState {
     /// The icon of the segment. The associated image can be looked up in the
     /// image cache. The image may be the empty image. This indicates that there
@@ -133,7 +133,7 @@ impl Clear for SplitState {
 }
 
 /// The state object describes the information to visualize for this component.
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct State {
     /// The background shown behind the splits.
     pub background: ListGradient,
@@ -194,6 +194,7 @@ impl Default for Settings {
                         update_trigger: ColumnUpdateTrigger::OnEndingSegment,
                         comparison_override: None,
                         timing_method: None,
+                        time_pattern: None,
                     }),
                 },
                 ColumnSettings {
@@ -204,6 +205,7 @@ impl Default for Settings {
                         update_trigger: ColumnUpdateTrigger::Contextual,
                         comparison_override: None,
                         timing_method: None,
+                        time_pattern: None,
                     }),
                 },
             ],
@@ -337,16 +339,17 @@ impl Component {
             state.column_labels = None;
         }
 
-        state.splits.clear();
-        for (i, segment) in run
+        // Only the visible segments are visited, so runs with thousands of
+        // segments don't slow down the calculation of the state.
+        let visible_segments = run.segments().iter().enumerate().skip(skip_count);
+        let last_segment = run
             .segments()
-            .iter()
-            .enumerate()
-            .skip(skip_count)
-            .filter(|&(i, _)| {
-                i - skip_count < take_count || (always_show_last_split && i + 1 == run.len())
-            })
-        {
+            .last()
+            .map(|segment| (run.len() - 1, segment))
+            .filter(|&(i, _)| always_show_last_split && i >= skip_count + take_count);
+
+        state.splits.clear();
+        for (i, segment) in visible_segments.take(take_count).chain(last_segment) {
             let state = state.splits.push_with(|| SplitState {
                 icon: *ImageId::EMPTY,
                 name: String::new(),
@@ -570,6 +573,11 @@ impl Component {
                         "Specifies the timing method to use for this column. If not specified, the current timing method is used.".into(),
                         column.timing_method.into(),
                     ));
+                    settings.fields.push(Field::new(
+                        "Time Pattern".into(),
+                        "The pattern to format the times in this column with, like \"H:mm:ss.ff\". Deltas are prefixed with a plus if they are positive. If not specified, the accuracies specified above are used.".into(),
+                        column.time_pattern.clone().into(),
+                    ));
                 }
             }
         }
@@ -633,17 +641,18 @@ impl Component {
                             index -= 1;
                         }
                         ColumnKind::Time(column) => {
-                            if index < 5 {
+                            if index < 6 {
                                 match index {
                                     0 => column.start_with = value.into(),
                                     1 => column.update_with = value.into(),
                                     2 => column.update_trigger = value.into(),
                                     3 => column.comparison_override = value.into(),
-                                    _ => column.timing_method = value.into(),
+                                    4 => column.timing_method = value.into(),
+                                    _ => column.time_pattern = value.into(),
                                 }
                                 return;
                             }
-                            index -= 5;
+                            index -= 6;
                         }
                     }
                 }
@@ -1354,3 +1354,63 @@ fn check_column_color(state: &State, split_index: usize, expected_color: Semanti
         expected_color
     );
 }
+
+#[test]
+fn time_pattern() {
+    let mut timer = timer();
+
+    let layout_settings = Default::default();
+    let mut component = Component::with_settings(Settings {
+        columns: vec![ColumnSettings {
+            kind: ColumnKind::Time(TimeColumn {
+                start_with: ColumnStartWith::ComparisonTime,
+                update_with: ColumnUpdateWith::DeltaWithFallback,
+                time_pattern: Some("[m:]ss.ff".parse().unwrap()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }],
+        fill_with_blank_space: false,
+        ..Default::default()
+    });
+
+    let mut image_cache = ImageCache::new();
+
+    start_run(&mut timer);
+    make_progress_run_with_splits_opt(
+        &mut timer,
+        &[
+            Some(5.0),
+            Some(6.0),
+            Some(15.0),
+            Some(20.0),
+            Some(25.0),
+            Some(70.0),
+        ],
+    );
+    // Split times use the pattern without a sign
+    check_column_state(
+        &component.state(&mut image_cache, &timer.snapshot(), &layout_settings),
+        0,
+        &[(
+            ["05.00", "06.00", "15.00", "20.00", "25.00", "1:10.00"],
+            [Best, Best, Best, Best, Best, Best],
+        )],
+    );
+    timer.reset(true).unwrap();
+
+    start_run(&mut timer);
+    make_progress_run_with_splits_opt(
+        &mut timer,
+        &[Some(4.0), None, Some(16.5), Some(20.0), None, Some(60.0)],
+    );
+    // Deltas use the pattern with a plus or minus in front
+    check_column_state(
+        &component.state(&mut image_cache, &timer.snapshot(), &layout_settings),
+        0,
+        &[(
+            ["−01.00", "—", "+01.50", "00.00", "—", "−10.00"],
+            [Best, Text, BehindLosing, Best, Text, Best],
+        )],
+    );
+}
@@ -12,7 +12,7 @@ use crate::{
     analysis::sum_of_segments::calculate_best,
     platform::prelude::*,
     settings::{Color, Field, Gradient, SettingsDescription, Value},
-    timing::formatter::{Accuracy, Regular, TimeFormatter},
+    timing::formatter::{Accuracy, Regular, TimeFormatter, TimePattern},
     Timer,
 };
 use core::fmt::Write;
@@ -47,6 +47,9 @@ pub struct Settings {
     pub value_color: Option<Color>,
     /// The accuracy of the time shown.
     pub accuracy: Accuracy,
+    /// The pattern to format the time with. If set to `None` the accuracy is
+    /// used instead.
+    pub time_pattern: Option<TimePattern>,
 }
 
 impl Default for Settings {
@@ -57,6 +60,7 @@ impl Default for Settings {
             label_color: None,
             value_color: None,
             accuracy: Accuracy::Seconds,
+            time_pattern: None,
         }
     }
 }
@@ -105,11 +109,15 @@ impl Component {
         state.key.push_str("Sum of Best Segments");
 
         state.value.clear();
-        let _ = write!(
-            state.value,
-            "{}",
-            Regular::with_accuracy(self.settings.accuracy).format(time),
-        );
+        let _ = if let Some(pattern) = &self.settings.time_pattern {
+            write!(state.value, "{}", pattern.format(time))
+        } else {
+            write!(
+                state.value,
+                "{}",
+                Regular::with_accuracy(self.settings.accuracy).format(time),
+            )
+        };
 
         state.key_abbreviations.clear();
         state.key_abbreviations.push("Sum of Best".into());
@@ -155,6 +163,11 @@ impl Component {
                 "The accuracy of the sum of best segments shown.".into(),
                 self.settings.accuracy.into(),
             ),
+            Field::new(
+                "Time Pattern".into(),
+                "The pattern to format the sum of best segments with, like \"H:mm:ss.ff\". If not specified, the accuracy is used.".into(),
+                self.settings.time_pattern.clone().into(),
+            ),
         ])
     }
 
@@ -172,6 +185,7 @@ impl Component {
             2 => self.settings.label_color = value.into(),
             3 => self.settings.value_color = value.into(),
             4 => self.settings.accuracy = value.into(),
+            5 => self.settings.time_pattern = value.into(),
             _ => panic!("Unsupported Setting Index"),
         }
     }
@@ -8,7 +8,7 @@ use crate::{
     platform::prelude::*,
     settings::{Color, Field, Gradient, SemanticColor, SettingsDescription, Value},
     timing::{
-        formatter::{timer as formatter, Accuracy, DigitsFormat, TimeFormatter},
+        formatter::{timer as formatter, Accuracy, DigitsFormat, TimeFormatter, TimePattern},
         Snapshot,
     },
     GeneralLayoutSettings, TimeSpan, TimerPhase, TimingMethod,
@@ -103,6 +103,10 @@ pub struct Settings {
     pub digits_format: DigitsFormat,
     /// The accuracy of the time shown.
     pub accuracy: Accuracy,
+    /// The pattern to format the time with. If set to `None` the pattern
+    /// specified in the layout's general settings is used. If that is not set
+    /// either, the digits format and accuracy are used.
+    pub time_pattern: Option<TimePattern>,
     /// Specifies whether to show how much time has passed since the start
     /// current segment, rather than how much time has passed since the start of
     /// the current attempt.
@@ -119,13 +123,14 @@ impl Default for Settings {
             show_gradient: true,
             digits_format: DigitsFormat::SingleDigitSeconds,
             accuracy: Accuracy::Hundredths,
+            time_pattern: None,
             is_segment_timer: false,
         }
     }
 }
 
 /// The state object describes the information to visualize for this component.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct State {
     /// The background shown behind the component.
     pub background: Gradient,
@@ -283,18 +288,28 @@ impl Component {
             .gradient(not_overwritten_visual_color);
 
         state.time.clear();
-        let _ = write!(
-            state.time,
-            "{}",
-            formatter::Time::with_digits_format(self.settings.digits_format).format(time),
-        );
-
         state.fraction.clear();
-        let _ = write!(
-            state.fraction,
-            "{}",
-            formatter::Fraction::with_accuracy(self.settings.accuracy).format(time),
-        );
+
+        if let Some(pattern) = self
+            .settings
+            .time_pattern
+            .as_ref()
+            .or(layout_settings.time_pattern.as_ref())
+        {
+            let _ = write!(state.time, "{}", pattern.format_main(time));
+            let _ = write!(state.fraction, "{}", pattern.format_fraction(time));
+        } else {
+            let _ = write!(
+                state.time,
+                "{}",
+                formatter::Time::with_digits_format(self.settings.digits_format).format(time),
+            );
+            let _ = write!(
+                state.fraction,
+                "{}",
+                formatter::Fraction::with_accuracy(self.settings.accuracy).format(time),
+            );
+        }
 
         state.updates_frequently = phase.updates_frequently(method) && time.is_some();
         state.semantic_color = semantic_color;
@@ -353,6 +368,11 @@ impl Component {
                 "The accuracy of the time shown.".into(),
                 self.settings.accuracy.into(),
             ),
+            Field::new(
+                "Time Pattern".into(),
+                "The pattern to format the time with, like \"H:mm:ss.ff\". If not specified, the pattern specified in the general settings for the layout is used. If that is not specified either, the digits format and accuracy are used.".into(),
+                self.settings.time_pattern.clone().into(),
+            ),
         ])
     }
 
@@ -373,6 +393,7 @@ impl Component {
             5 => self.settings.show_gradient = value.into(),
             6 => self.settings.digits_format = value.into(),
             7 => self.settings.accuracy = value.into(),
+            8 => self.settings.time_pattern = value.into(),
             _ => panic!("Unsupported Setting Index"),
         }
     }
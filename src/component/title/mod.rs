@@ -69,7 +69,7 @@ pub struct Settings {
 }
 
 /// The state object describes the information to visualize for this component.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct State {
     /// The background shown behind the component.
     pub background: Gradient,
@@ -326,8 +326,10 @@ impl Component {
                 state.line2.clear();
             }
             (false, false) => {
-                state.line1.clear();
-                state.line1.push("Untitled".into());
+                if !matches!(&*state.line1, [title] if &**title == "Untitled") {
+                    state.line1.clear();
+                    state.line1.push("Untitled".into());
+                }
                 state.line2.clear();
             }
         }
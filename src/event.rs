@@ -517,3 +517,38 @@ impl<T: TimerQuery + ?Sized> TimerQuery for Arc<T> {
         TimerQuery::get_timer(&**self)
     }
 }
+
+/// Blocks the current thread until the future is done and returns its output.
+/// This is a minimal executor for driving the futures of a [`CommandSink`] to
+/// completion from threads that have nothing else to do in the meantime, such
+/// as the threads of the servers. The future is polled on the current thread
+/// and the thread is parked whenever the future is pending.
+#[cfg(feature = "std")]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    use core::{
+        pin::pin,
+        task::{Context, Poll},
+    };
+    use std::{
+        task::{Wake, Waker},
+        thread,
+    };
+
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
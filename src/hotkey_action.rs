@@ -0,0 +1,210 @@
+use alloc::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+use crate::{event::CommandSink, hotkey::Hotkey, platform::prelude::*, HotkeyMode, TimingMethod};
+use serde_derive::{Deserialize, Serialize};
+
+/// An action that can be triggered by a hotkey. Apart from the actions that
+/// are directly applied to the timer, there are custom actions which are
+/// forwarded to the application. These can be used for actions that the timer
+/// doesn't know about, such as toggling a specific component of the layout or
+/// incrementing a counter.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum HotkeyAction {
+    /// Starts a new attempt if there is none in progress.
+    Start,
+    /// Stores the current time as the time of the current split.
+    Split,
+    /// Starts a new attempt or stores the current time as the time of the
+    /// current split.
+    SplitOrStart,
+    /// Resets the current attempt.
+    Reset,
+    /// Undoes the last split.
+    UndoSplit,
+    /// Skips the current split.
+    SkipSplit,
+    /// Toggles between pausing and resuming the current attempt or starts a
+    /// new attempt if there is none in progress.
+    TogglePauseOrStart,
+    /// Pauses the current attempt.
+    Pause,
+    /// Resumes the current attempt.
+    Resume,
+    /// Removes all the pause times from the current time.
+    UndoAllPauses,
+    /// Switches to the previous comparison.
+    PreviousComparison,
+    /// Switches to the next comparison.
+    NextComparison,
+    /// Switches to the comparison with the given name.
+    SetComparison(String),
+    /// Toggles between the `Real Time` and `Game Time` timing methods.
+    ToggleTimingMethod,
+    /// Switches to the given timing method.
+    SetTimingMethod(TimingMethod),
+    /// Initializes the game time for the current attempt.
+    InitializeGameTime,
+    /// Pauses the game time.
+    PauseGameTime,
+    /// Resumes the game time.
+    ResumeGameTime,
+    /// A custom action with the given name that is forwarded to the
+    /// application.
+    Custom(String),
+}
+
+/// A hotkey that is bound to an arbitrary [`HotkeyAction`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct HotkeyBinding {
+    /// The hotkey that triggers the action.
+    pub hotkey: Hotkey,
+    /// The mode that describes how the hotkey needs to be pressed.
+    #[serde(default)]
+    pub mode: HotkeyMode,
+    /// Whether the key events of the hotkey are consumed, so they don't get
+    /// passed on to the application in focus.
+    #[serde(default)]
+    pub consume: bool,
+    /// The action to trigger.
+    pub action: HotkeyAction,
+}
+
+/// The handler that custom actions are forwarded to.
+pub(crate) type CustomActionHandler = Arc<Mutex<Option<Box<dyn FnMut(&str) + Send + 'static>>>>;
+
+impl HotkeyAction {
+    /// All the actions that don't need any additional information. Settings
+    /// UIs can use this to list the actions that can be bound. The actions
+    /// [`SetComparison`](Self::SetComparison),
+    /// [`SetTimingMethod`](Self::SetTimingMethod) and
+    /// [`Custom`](Self::Custom) need to be offered separately.
+    pub const SIMPLE_ACTIONS: &'static [HotkeyAction] = &[
+        HotkeyAction::Start,
+        HotkeyAction::Split,
+        HotkeyAction::SplitOrStart,
+        HotkeyAction::Reset,
+        HotkeyAction::UndoSplit,
+        HotkeyAction::SkipSplit,
+        HotkeyAction::TogglePauseOrStart,
+        HotkeyAction::Pause,
+        HotkeyAction::Resume,
+        HotkeyAction::UndoAllPauses,
+        HotkeyAction::PreviousComparison,
+        HotkeyAction::NextComparison,
+        HotkeyAction::ToggleTimingMethod,
+        HotkeyAction::InitializeGameTime,
+        HotkeyAction::PauseGameTime,
+        HotkeyAction::ResumeGameTime,
+    ];
+
+    /// Returns a name for the action that can be shown to the user.
+    pub fn name(&self) -> Cow<'_, str> {
+        match self {
+            HotkeyAction::Start => "Start".into(),
+            HotkeyAction::Split => "Split".into(),
+            HotkeyAction::SplitOrStart => "Start / Split".into(),
+            HotkeyAction::Reset => "Reset".into(),
+            HotkeyAction::UndoSplit => "Undo Split".into(),
+            HotkeyAction::SkipSplit => "Skip Split".into(),
+            HotkeyAction::TogglePauseOrStart => "Pause".into(),
+            HotkeyAction::Pause => "Pause Only".into(),
+            HotkeyAction::Resume => "Resume".into(),
+            HotkeyAction::UndoAllPauses => "Undo All Pauses".into(),
+            HotkeyAction::PreviousComparison => "Previous Comparison".into(),
+            HotkeyAction::NextComparison => "Next Comparison".into(),
+            HotkeyAction::SetComparison(comparison) => {
+                format!("Switch to Comparison \"{comparison}\"").into()
+            }
+            HotkeyAction::ToggleTimingMethod => "Toggle Timing Method".into(),
+            HotkeyAction::SetTimingMethod(TimingMethod::RealTime) => "Switch to Real Time".into(),
+            HotkeyAction::SetTimingMethod(TimingMethod::GameTime) => "Switch to Game Time".into(),
+            HotkeyAction::InitializeGameTime => "Initialize Game Time".into(),
+            HotkeyAction::PauseGameTime => "Pause Game Time".into(),
+            HotkeyAction::ResumeGameTime => "Resume Game Time".into(),
+            HotkeyAction::Custom(name) => name.as_str().into(),
+        }
+    }
+
+    pub(crate) fn callback<S: CommandSink + Send + 'static>(
+        self,
+        command_sink: S,
+        custom_action_handler: CustomActionHandler,
+    ) -> Box<dyn FnMut() + Send + 'static> {
+        Box::new(move || match &self {
+            HotkeyAction::Start => drop(command_sink.start()),
+            HotkeyAction::Split => drop(command_sink.split()),
+            HotkeyAction::SplitOrStart => drop(command_sink.split_or_start()),
+            HotkeyAction::Reset => drop(command_sink.reset(None)),
+            HotkeyAction::UndoSplit => drop(command_sink.undo_split()),
+            HotkeyAction::SkipSplit => drop(command_sink.skip_split()),
+            HotkeyAction::TogglePauseOrStart => drop(command_sink.toggle_pause_or_start()),
+            HotkeyAction::Pause => drop(command_sink.pause()),
+            HotkeyAction::Resume => drop(command_sink.resume()),
+            HotkeyAction::UndoAllPauses => drop(command_sink.undo_all_pauses()),
+            HotkeyAction::PreviousComparison => drop(command_sink.switch_to_previous_comparison()),
+            HotkeyAction::NextComparison => drop(command_sink.switch_to_next_comparison()),
+            HotkeyAction::SetComparison(comparison) => {
+                drop(command_sink.set_current_comparison(comparison.as_str().into()))
+            }
+            HotkeyAction::ToggleTimingMethod => drop(command_sink.toggle_timing_method()),
+            HotkeyAction::SetTimingMethod(method) => {
+                drop(command_sink.set_current_timing_method(*method))
+            }
+            HotkeyAction::InitializeGameTime => drop(command_sink.initialize_game_time()),
+            HotkeyAction::PauseGameTime => drop(command_sink.pause_game_time()),
+            HotkeyAction::ResumeGameTime => drop(command_sink.resume_game_time()),
+            HotkeyAction::Custom(name) => {
+                if let Some(handler) = &mut *custom_action_handler.lock().unwrap() {
+                    handler(name);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hotkey::KeyCode, util::tests_helper::create_timer, TimerPhase};
+
+    #[test]
+    fn additional_bindings_dispatch_their_actions() {
+        let timer = create_timer(&["A", "B"]).into_shared();
+        let custom_actions = Arc::new(Mutex::new(Vec::new()));
+        let custom_action_handler = CustomActionHandler::default();
+        let handled = custom_actions.clone();
+        *custom_action_handler.lock().unwrap() = Some(Box::new(move |name: &str| {
+            handled.lock().unwrap().push(name.to_owned())
+        }));
+
+        let split = HotkeyBinding {
+            hotkey: KeyCode::KeyS.into(),
+            mode: HotkeyMode::Press,
+            consume: false,
+            action: HotkeyAction::SplitOrStart,
+        };
+        let counter = HotkeyBinding {
+            hotkey: KeyCode::KeyC.into(),
+            mode: HotkeyMode::Press,
+            consume: false,
+            action: HotkeyAction::Custom("Counter".into()),
+        };
+
+        let mut split = split
+            .action
+            .callback(timer.clone(), custom_action_handler.clone());
+        let mut counter = counter
+            .action
+            .callback(timer.clone(), custom_action_handler.clone());
+
+        split();
+        assert_eq!(timer.read().unwrap().current_phase(), TimerPhase::Running);
+        assert!(custom_actions.lock().unwrap().is_empty());
+
+        counter();
+        counter();
+        assert_eq!(*custom_actions.lock().unwrap(), ["Counter", "Counter"]);
+        assert_eq!(timer.read().unwrap().current_split_index(), Some(0));
+    }
+}
@@ -1,106 +1,333 @@
 #![allow(clippy::trivially_copy_pass_by_ref)]
 
 use crate::{
-    hotkey::Hotkey,
+    hotkey::{device::DeviceTrigger, midi::MidiTrigger, Hotkey, KeyCode},
     platform::prelude::*,
     settings::{Field, SettingsDescription, Value},
+    HotkeyAction, HotkeyBinding,
 };
 use serde_derive::{Deserialize, Serialize};
 
 /// The configuration to use for a [`HotkeySystem`](crate::HotkeySystem). It describes which [`Hotkey`](livesplit_hotkey::Hotkey) to use as hotkeys for the different actions.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+/// Additionally there can be multiple named [`HotkeyProfile`]s that can be
+/// switched between. The bindings stored directly in the configuration are the
+/// ones currently in use.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(default)]
 pub struct HotkeyConfig {
-    /// The key to use for splitting and starting a new attempt.
-    pub split: Option<Hotkey>,
-    /// The key to use for resetting the current attempt.
-    pub reset: Option<Hotkey>,
-    /// The key to use for undoing the last split.
-    pub undo: Option<Hotkey>,
-    /// The key to use for skipping the current split.
-    pub skip: Option<Hotkey>,
-    /// The key to use for pausing the current attempt. It can also be used for
-    /// starting a new attempt.
-    pub pause: Option<Hotkey>,
-    /// The key to use for removing all the pause times from the current time.
-    pub undo_all_pauses: Option<Hotkey>,
-    /// The key to use for switching to the previous comparison.
-    pub previous_comparison: Option<Hotkey>,
-    /// The key to use for switching to the next comparison.
-    pub next_comparison: Option<Hotkey>,
-    /// The key to use for toggling between the `Real Time` and `Game Time`
+    /// The bindings that are currently in use.
+    #[serde(flatten)]
+    pub bindings: HotkeyBindings,
+    /// The name of the profile that is currently active, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    /// All the profiles that can be switched between.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<HotkeyProfile>,
+}
+
+/// All the hotkeys and other triggers of the actions. These are stored both in
+/// the [`HotkeyConfig`] and in each of its [`HotkeyProfile`]s, so switching
+/// profiles switches all of them.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeyBindings {
+    /// The binding to use for splitting and starting a new attempt.
+    pub split: ActionBinding,
+    /// The binding to use for resetting the current attempt.
+    pub reset: ActionBinding,
+    /// The binding to use for undoing the last split.
+    pub undo: ActionBinding,
+    /// The binding to use for skipping the current split.
+    pub skip: ActionBinding,
+    /// The binding to use for pausing the current attempt. It can also be used
+    /// for starting a new attempt.
+    pub pause: ActionBinding,
+    /// The binding to use for removing all the pause times from the current
+    /// time.
+    pub undo_all_pauses: ActionBinding,
+    /// The binding to use for switching to the previous comparison.
+    pub previous_comparison: ActionBinding,
+    /// The binding to use for switching to the next comparison.
+    pub next_comparison: ActionBinding,
+    /// The binding to use for toggling between the `Real Time` and `Game Time`
     /// timing methods.
-    pub toggle_timing_method: Option<Hotkey>,
+    pub toggle_timing_method: ActionBinding,
+    /// Additional hotkeys that can be bound to any [`HotkeyAction`], not just
+    /// the fixed set of actions above.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub additional: Vec<HotkeyBinding>,
+}
+
+/// Everything that triggers one of the fixed actions of a [`HotkeyConfig`].
+/// Bindings that only consist of a hotkey are stored as just the hotkey.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(from = "ActionBindingRepr", into = "ActionBindingRepr")]
+pub struct ActionBinding {
+    /// The hotkey that triggers the action.
+    pub hotkey: Option<Hotkey>,
+    /// The mode that describes how the hotkey needs to be pressed in order to
+    /// trigger the action.
+    pub mode: HotkeyMode,
+    /// Whether the key events of the hotkey are consumed, so they don't get
+    /// passed on to the application in focus. This prevents the hotkey from
+    /// also typing into a game or chat window. Consuming individual hotkeys is
+    /// only supported on Windows and on Linux when evdev is used.
+    pub consume: bool,
+    /// The MIDI message that triggers the action in addition to the hotkey.
+    /// This is only listened to if the `midi` feature is enabled.
+    pub midi: Option<MidiTrigger>,
+    /// The HID or serial device button, such as a foot pedal, that triggers
+    /// the action in addition to the hotkey. This is only listened to if the
+    /// `devices` feature is enabled.
+    pub device: Option<DeviceTrigger>,
+}
+
+impl From<Option<Hotkey>> for ActionBinding {
+    fn from(hotkey: Option<Hotkey>) -> Self {
+        Self {
+            hotkey,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ActionBindingRepr {
+    Hotkey(Option<Hotkey>),
+    Binding(ActionBindingFields),
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ActionBindingFields {
+    hotkey: Option<Hotkey>,
+    mode: HotkeyMode,
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    consume: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    midi: Option<MidiTrigger>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device: Option<DeviceTrigger>,
+}
+
+impl From<ActionBindingRepr> for ActionBinding {
+    fn from(repr: ActionBindingRepr) -> Self {
+        match repr {
+            ActionBindingRepr::Hotkey(hotkey) => hotkey.into(),
+            ActionBindingRepr::Binding(fields) => Self {
+                hotkey: fields.hotkey,
+                mode: fields.mode,
+                consume: fields.consume,
+                midi: fields.midi,
+                device: fields.device,
+            },
+        }
+    }
+}
+
+impl From<ActionBinding> for ActionBindingRepr {
+    fn from(binding: ActionBinding) -> Self {
+        if binding == ActionBinding::from(binding.hotkey) {
+            Self::Hotkey(binding.hotkey)
+        } else {
+            Self::Binding(ActionBindingFields {
+                hotkey: binding.hotkey,
+                mode: binding.mode,
+                consume: binding.consume,
+                midi: binding.midi,
+                device: binding.device,
+            })
+        }
+    }
+}
+
+/// A named set of bindings that a [`HotkeyConfig`] can switch to. This allows
+/// the runner to use different hotkeys for different games.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeyProfile {
+    /// The name of the profile.
+    pub name: String,
+    /// The names of the games that this profile should automatically be
+    /// switched to for.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub games: Vec<String>,
+    /// The bindings of the profile.
+    #[serde(flatten)]
+    pub bindings: HotkeyBindings,
+}
+
+/// Describes how a hotkey needs to be pressed in order to trigger its action.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum HotkeyMode {
+    /// The action is triggered as soon as the hotkey is pressed.
+    #[default]
+    Press,
+    /// The action is triggered once the hotkey has been held down for the
+    /// given amount of milliseconds. Releasing it any earlier does nothing.
+    /// This is useful for actions that can't be undone, such as resetting the
+    /// current attempt.
+    Hold(u32),
+    /// The action is triggered when the hotkey is pressed a second time within
+    /// the given amount of milliseconds.
+    DoubleTap(u32),
+    /// The action is triggered when the hotkey is pressed while the given key
+    /// is held down. The same key can be used for the chords of multiple
+    /// actions, but it can't be used as a hotkey on its own.
+    Chord(KeyCode),
 }
 
-impl Default for HotkeyConfig {
+/// The fixed actions of the [`HotkeyBindings`] in the order of the settings,
+/// each with the name and the description of its setting.
+pub(crate) const FIXED_ACTIONS: [(&str, &str, HotkeyAction); 9] = [
+    (
+        "Start / Split",
+        "The hotkey to use for splitting and starting a new attempt.",
+        HotkeyAction::SplitOrStart,
+    ),
+    (
+        "Reset",
+        "The hotkey to use for resetting the current attempt.",
+        HotkeyAction::Reset,
+    ),
+    (
+        "Undo Split",
+        "The hotkey to use for undoing the last split.",
+        HotkeyAction::UndoSplit,
+    ),
+    (
+        "Skip Split",
+        "The hotkey to use for skipping the current split.",
+        HotkeyAction::SkipSplit,
+    ),
+    (
+        "Pause",
+        "The hotkey to use for pausing the current attempt. It can also be used for starting a new attempt.",
+        HotkeyAction::TogglePauseOrStart,
+    ),
+    (
+        "Undo All Pauses",
+        "The hotkey to use for removing all the pause times from the current time. This is useful in case you accidentally paused and want to undo it.",
+        HotkeyAction::UndoAllPauses,
+    ),
+    (
+        "Previous Comparison",
+        "The hotkey to use for switching to the previous comparison.",
+        HotkeyAction::PreviousComparison,
+    ),
+    (
+        "Next Comparison",
+        "The hotkey to use for switching to the next comparison.",
+        HotkeyAction::NextComparison,
+    ),
+    (
+        "Toggle Timing Method",
+        r#"The hotkey to use for toggling between the "Real Time" and "Game Time" timing methods."#,
+        HotkeyAction::ToggleTimingMethod,
+    ),
+];
+
+impl Default for HotkeyBindings {
     fn default() -> Self {
         use crate::hotkey::KeyCode::*;
         Self {
-            split: Some(Numpad1.into()),
-            reset: Some(Numpad3.into()),
-            undo: Some(Numpad8.into()),
-            skip: Some(Numpad2.into()),
-            pause: Some(Numpad5.into()),
-            undo_all_pauses: None,
-            previous_comparison: Some(Numpad4.into()),
-            next_comparison: Some(Numpad6.into()),
-            toggle_timing_method: None,
+            split: Some(Numpad1.into()).into(),
+            reset: Some(Numpad3.into()).into(),
+            undo: Some(Numpad8.into()).into(),
+            skip: Some(Numpad2.into()).into(),
+            pause: Some(Numpad5.into()).into(),
+            undo_all_pauses: ActionBinding::default(),
+            previous_comparison: Some(Numpad4.into()).into(),
+            next_comparison: Some(Numpad6.into()).into(),
+            toggle_timing_method: ActionBinding::default(),
+            additional: Vec::new(),
         }
     }
 }
 
+impl HotkeyBindings {
+    /// Accesses the bindings of the fixed actions in the order of
+    /// [`FIXED_ACTIONS`].
+    pub(crate) const fn fixed(&self) -> [&ActionBinding; 9] {
+        [
+            &self.split,
+            &self.reset,
+            &self.undo,
+            &self.skip,
+            &self.pause,
+            &self.undo_all_pauses,
+            &self.previous_comparison,
+            &self.next_comparison,
+            &self.toggle_timing_method,
+        ]
+    }
+
+    /// Mutably accesses the bindings of the fixed actions in the order of
+    /// [`FIXED_ACTIONS`].
+    pub(crate) fn fixed_mut(&mut self) -> [&mut ActionBinding; 9] {
+        [
+            &mut self.split,
+            &mut self.reset,
+            &mut self.undo,
+            &mut self.skip,
+            &mut self.pause,
+            &mut self.undo_all_pauses,
+            &mut self.previous_comparison,
+            &mut self.next_comparison,
+            &mut self.toggle_timing_method,
+        ]
+    }
+
+    /// Collects the keys that are used for the chords of the actions. These
+    /// keys can't be used as hotkeys on their own.
+    pub(crate) fn chord_keys(&self) -> Vec<KeyCode> {
+        let mut chord_keys = Vec::new();
+        let modes = self
+            .fixed()
+            .into_iter()
+            .filter(|binding| binding.hotkey.is_some())
+            .map(|binding| binding.mode)
+            .chain(self.additional.iter().map(|b| b.mode));
+        for mode in modes {
+            if let HotkeyMode::Chord(key_code) = mode {
+                if !chord_keys.contains(&key_code) {
+                    chord_keys.push(key_code);
+                }
+            }
+        }
+        chord_keys
+    }
+
+    fn is_in_use(&self, hotkey: Hotkey, except_index: Option<usize>) -> bool {
+        self.fixed()
+            .into_iter()
+            .enumerate()
+            .any(|(i, b)| Some(i) != except_index && b.hotkey == Some(hotkey))
+            || self.additional.iter().any(|b| b.hotkey == hotkey)
+    }
+}
+
 impl HotkeyConfig {
     /// Accesses a generic description of the settings available for the hotkey
-    /// configuration and their current values.
+    /// configuration and their current values. Only the hotkeys of the fixed
+    /// actions are part of the settings. The [`HotkeyMode`], whether the key
+    /// events are consumed, the MIDI and device triggers, as well as the
+    /// additional bindings and the profiles can only be changed through the
+    /// API, for example via [`set_mode`](Self::set_mode),
+    /// [`binding_mut`](Self::binding_mut) and
+    /// [`add_binding`](Self::add_binding).
     pub fn settings_description(&self) -> SettingsDescription {
-        SettingsDescription::with_fields(vec![
-            Field::new(
-                "Start / Split".into(),
-                "The hotkey to use for splitting and starting a new attempt.".into(),
-                self.split.into(),
-            ),
-            Field::new(
-                "Reset".into(),
-                "The hotkey to use for resetting the current attempt.".into(),
-                self.reset.into(),
-            ),
-            Field::new(
-                "Undo Split".into(),
-                "The hotkey to use for undoing the last split.".into(),
-                self.undo.into(),
-            ),
-            Field::new(
-                "Skip Split".into(),
-                "The hotkey to use for skipping the current split.".into(),
-                self.skip.into(),
-            ),
-            Field::new(
-                "Pause".into(),
-                "The hotkey to use for pausing the current attempt. It can also be used for starting a new attempt.".into(),
-                self.pause.into(),
-            ),
-            Field::new(
-                "Undo All Pauses".into(),
-                "The hotkey to use for removing all the pause times from the current time. This is useful in case you accidentally paused and want to undo it.".into(),
-                self.undo_all_pauses.into(),
-            ),
-            Field::new(
-                "Previous Comparison".into(),
-                "The hotkey to use for switching to the previous comparison.".into(),
-                self.previous_comparison.into(),
-            ),
-            Field::new(
-                "Next Comparison".into(),
-                "The hotkey to use for switching to the next comparison.".into(),
-                self.next_comparison.into(),
-            ),
-            Field::new(
-                "Toggle Timing Method".into(),
-                r#"The hotkey to use for toggling between the "Real Time" and "Game Time" timing methods."#.into(),
-                self.toggle_timing_method.into(),
-            ),
-        ])
+        SettingsDescription::with_fields(
+            FIXED_ACTIONS
+                .iter()
+                .zip(self.bindings.fixed())
+                .map(|((name, description, _), binding)| {
+                    Field::new((*name).into(), (*description).into(), binding.hotkey.into())
+                })
+                .collect(),
+        )
     }
 
     /// Sets a setting's value by its index to the given value.
@@ -108,7 +335,7 @@ impl HotkeyConfig {
     /// # Errors
     ///
     /// An error is returned if a hotkey is already in use by a different
-    /// action.
+    /// action, including the additional bindings.
     ///
     /// # Panics
     ///
@@ -118,44 +345,178 @@ impl HotkeyConfig {
     pub fn set_value(&mut self, index: usize, value: Value) -> Result<(), ()> {
         let value: Option<Hotkey> = value.into();
 
-        if value.is_some() {
-            let any = [
-                self.split,
-                self.reset,
-                self.undo,
-                self.skip,
-                self.pause,
-                self.undo_all_pauses,
-                self.previous_comparison,
-                self.next_comparison,
-                self.toggle_timing_method,
-            ]
+        if let Some(hotkey) = value {
+            if self.bindings.is_in_use(hotkey, Some(index)) {
+                return Err(());
+            }
+        }
+
+        self.binding_mut(index).hotkey = value;
+
+        Ok(())
+    }
+
+    /// Binds the given hotkey to an arbitrary action.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the hotkey is already in use by a different
+    /// action.
+    pub fn add_binding(&mut self, binding: HotkeyBinding) -> Result<(), ()> {
+        if self.bindings.is_in_use(binding.hotkey, None) {
+            return Err(());
+        }
+        self.bindings.additional.push(binding);
+        Ok(())
+    }
+
+    /// Removes the additional binding of the given hotkey, if there is one.
+    pub fn remove_binding(&mut self, hotkey: Hotkey) {
+        self.bindings.additional.retain(|b| b.hotkey != hotkey);
+    }
+
+    /// Accesses the binding of the fixed action with the given setting index.
+    /// The indices are the same as the ones of the settings description.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the index of the setting provided is out of bounds.
+    pub fn binding(&self, index: usize) -> &ActionBinding {
+        self.bindings
+            .fixed()
             .into_iter()
-            .enumerate()
-            .filter(|&(i, _)| i != index)
-            .any(|(_, v)| v == value);
+            .nth(index)
+            .unwrap_or_else(|| panic!("Unsupported Setting Index"))
+    }
 
-            if any {
-                return Err(());
+    /// Mutably accesses the binding of the fixed action with the given setting
+    /// index. The indices are the same as the ones of the settings
+    /// description.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the index of the setting provided is out of bounds.
+    pub fn binding_mut(&mut self, index: usize) -> &mut ActionBinding {
+        self.bindings
+            .fixed_mut()
+            .into_iter()
+            .nth(index)
+            .unwrap_or_else(|| panic!("Unsupported Setting Index"))
+    }
+
+    /// Accesses the mode of the hotkey with the given setting index. The
+    /// indices are the same as the ones of the settings description.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the index of the setting provided is out of bounds.
+    pub fn mode(&self, index: usize) -> HotkeyMode {
+        self.binding(index).mode
+    }
+
+    /// Sets the mode of the hotkey with the given setting index. The indices
+    /// are the same as the ones of the settings description.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the index of the setting provided is out of bounds.
+    pub fn set_mode(&mut self, index: usize, mode: HotkeyMode) {
+        self.binding_mut(index).mode = mode;
+    }
+
+    /// Collects all the hotkeys that are in conflict with each other. A hotkey
+    /// is in conflict if it is bound to more than one action or if its key is
+    /// also used for the chord of an action, as such keys can't be used as
+    /// hotkeys on their own. Configurations loaded from files may contain such
+    /// conflicts, as only the changes made through the configuration itself
+    /// are validated.
+    pub fn conflicts(&self) -> Vec<Hotkey> {
+        let hotkeys = self
+            .bindings
+            .fixed()
+            .into_iter()
+            .filter_map(|b| b.hotkey)
+            .chain(self.bindings.additional.iter().map(|b| b.hotkey));
+
+        let chord_keys = self.bindings.chord_keys();
+
+        let mut seen = Vec::new();
+        let mut conflicts = Vec::new();
+        for hotkey in hotkeys {
+            if (seen.contains(&hotkey) || chord_keys.contains(&hotkey.key_code))
+                && !conflicts.contains(&hotkey)
+            {
+                conflicts.push(hotkey);
             }
+            seen.push(hotkey);
         }
+        conflicts
+    }
 
-        match index {
-            0 => self.split = value,
-            1 => self.reset = value,
-            2 => self.undo = value,
-            3 => self.skip = value,
-            4 => self.pause = value,
-            5 => self.undo_all_pauses = value,
-            6 => self.previous_comparison = value,
-            7 => self.next_comparison = value,
-            8 => self.toggle_timing_method = value,
-            _ => panic!("Unsupported Setting Index"),
+    /// Stores the bindings currently in use in the active profile, so
+    /// switching back to it later restores them.
+    fn store_active_profile(&mut self) {
+        if let Some(active) = &self.active_profile {
+            if let Some(profile) = self.profiles.iter_mut().find(|p| &p.name == active) {
+                profile.bindings = self.bindings.clone();
+            }
         }
+    }
 
+    /// Stores the bindings currently in use as a profile with the given name
+    /// and makes it the active profile. If there already is a profile with
+    /// that name, its bindings are replaced. The profile that was active
+    /// before keeps the bindings it was last stored with.
+    pub fn save_profile(&mut self, name: &str) {
+        let bindings = self.bindings.clone();
+        if let Some(profile) = self.profiles.iter_mut().find(|p| p.name == name) {
+            profile.bindings = bindings;
+        } else {
+            self.profiles.push(HotkeyProfile {
+                name: name.to_owned(),
+                games: Vec::new(),
+                bindings,
+            });
+        }
+        self.active_profile = Some(name.to_owned());
+    }
+
+    /// Switches to the profile with the given name. The bindings currently in
+    /// use are stored in the profile that was active before.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if there is no profile with the given name.
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), ()> {
+        let index = self
+            .profiles
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or(())?;
+        self.store_active_profile();
+        self.bindings = self.profiles[index].bindings.clone();
+        self.active_profile = Some(name.to_owned());
         Ok(())
     }
 
+    /// Removes the profile with the given name. If it is the active profile,
+    /// the bindings currently in use stay the same, but no profile is active
+    /// anymore.
+    pub fn remove_profile(&mut self, name: &str) {
+        self.profiles.retain(|p| p.name != name);
+        if self.active_profile.as_deref() == Some(name) {
+            self.active_profile = None;
+        }
+    }
+
+    /// Looks for the profile that should be used for the game with the given
+    /// name.
+    pub fn profile_for_game(&self, game_name: &str) -> Option<&HotkeyProfile> {
+        self.profiles
+            .iter()
+            .find(|p| p.games.iter().any(|g| g.eq_ignore_ascii_case(game_name)))
+    }
+
     /// Decodes the hotkey configuration from JSON.
     #[cfg(feature = "std")]
     pub fn from_json<R>(reader: R) -> serde_json::Result<Self>
@@ -174,3 +535,135 @@ impl HotkeyConfig {
         serde_json::to_writer(writer, self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotkey::KeyCode;
+
+    fn config_with_split(key_code: KeyCode) -> HotkeyConfig {
+        let mut config = HotkeyConfig::default();
+        config.bindings.split = ActionBinding {
+            hotkey: Some(key_code.into()),
+            mode: HotkeyMode::Hold(500),
+            consume: true,
+            midi: Some(MidiTrigger::Note(60)),
+            device: None,
+        };
+        config
+    }
+
+    #[test]
+    fn parses_plain_hotkeys() {
+        let config: HotkeyConfig =
+            serde_json::from_str(r#"{"split":"KeyA","reset":null}"#).unwrap();
+        assert_eq!(config.bindings.split.hotkey, Some(KeyCode::KeyA.into()));
+        assert_eq!(config.bindings.split.mode, HotkeyMode::Press);
+        assert_eq!(config.bindings.reset.hotkey, None);
+        assert_eq!(config.bindings.undo, HotkeyBindings::default().undo);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut config = config_with_split(KeyCode::KeyA);
+        config.save_profile("Game");
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(r#""reset":"Numpad3""#));
+        assert_eq!(serde_json::from_str::<HotkeyConfig>(&json).unwrap(), config);
+    }
+
+    #[test]
+    fn save_and_switch_profiles() {
+        let mut config = config_with_split(KeyCode::KeyA);
+        config.save_profile("A");
+
+        config.bindings = config_with_split(KeyCode::KeyB).bindings;
+        config.bindings.additional.push(HotkeyBinding {
+            hotkey: KeyCode::KeyC.into(),
+            mode: HotkeyMode::Press,
+            consume: false,
+            action: HotkeyAction::Custom("Counter".into()),
+        });
+        config.save_profile("B");
+        assert_eq!(config.profiles.len(), 2);
+        assert_eq!(config.active_profile.as_deref(), Some("B"));
+
+        config.switch_profile("A").unwrap();
+        assert_eq!(config.active_profile.as_deref(), Some("A"));
+        assert_eq!(config.bindings, config_with_split(KeyCode::KeyA).bindings);
+
+        // Changes to the active profile are kept when switching away from it.
+        config.bindings.reset.mode = HotkeyMode::DoubleTap(300);
+        config.switch_profile("B").unwrap();
+        assert_eq!(config.bindings.split.hotkey, Some(KeyCode::KeyB.into()));
+        assert_eq!(config.bindings.additional.len(), 1);
+        config.switch_profile("A").unwrap();
+        assert_eq!(config.bindings.reset.mode, HotkeyMode::DoubleTap(300));
+        assert!(config.bindings.additional.is_empty());
+
+        assert!(config.switch_profile("C").is_err());
+        assert_eq!(config.active_profile.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn save_profile_keeps_games() {
+        let mut config = HotkeyConfig::default();
+        config.save_profile("A");
+        config.profiles[0].games.push("Celeste".into());
+        config.bindings.split.hotkey = None;
+        config.save_profile("A");
+        assert_eq!(config.profiles.len(), 1);
+        assert_eq!(config.profiles[0].games, ["Celeste"]);
+        assert_eq!(config.profiles[0].bindings.split.hotkey, None);
+    }
+
+    #[test]
+    fn remove_profile() {
+        let mut config = HotkeyConfig::default();
+        config.save_profile("A");
+        config.save_profile("B");
+        config.remove_profile("A");
+        assert_eq!(config.active_profile.as_deref(), Some("B"));
+        config.remove_profile("B");
+        assert!(config.profiles.is_empty());
+        assert_eq!(config.active_profile, None);
+        assert_eq!(config.bindings, HotkeyBindings::default());
+    }
+
+    #[test]
+    fn profile_for_game() {
+        let mut config = HotkeyConfig::default();
+        config.save_profile("A");
+        config.save_profile("B");
+        config.profiles[1].games.push("Super Mario 64".into());
+        assert_eq!(
+            config.profile_for_game("super mario 64").map(|p| &*p.name),
+            Some("B"),
+        );
+        assert!(config.profile_for_game("Celeste").is_none());
+    }
+
+    #[test]
+    fn rejects_hotkeys_in_use() {
+        let mut config = HotkeyConfig::default();
+        let numpad1 = Some(Hotkey::from(KeyCode::Numpad1));
+        assert!(config.set_value(1, numpad1.into()).is_err());
+        assert!(config.set_value(0, numpad1.into()).is_ok());
+        assert!(config
+            .add_binding(HotkeyBinding {
+                hotkey: KeyCode::Numpad1.into(),
+                mode: HotkeyMode::Press,
+                consume: false,
+                action: HotkeyAction::Start,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn chord_keys_conflict() {
+        let mut config = HotkeyConfig::default();
+        config.set_mode(1, HotkeyMode::Chord(KeyCode::Numpad1));
+        assert_eq!(config.bindings.chord_keys(), [KeyCode::Numpad1]);
+        assert_eq!(config.conflicts(), [Hotkey::from(KeyCode::Numpad1)]);
+    }
+}
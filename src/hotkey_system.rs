@@ -1,98 +1,162 @@
 use alloc::borrow::Cow;
+use core::{mem, time::Duration};
+use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "devices")]
+use crate::hotkey::device::{DeviceHook, DeviceTrigger};
+#[cfg(feature = "midi")]
+use crate::hotkey::midi::{MidiHook, MidiTrigger};
 use crate::{
     event,
-    hotkey::{ConsumePreference, Hook, Hotkey, KeyCode},
-    HotkeyConfig,
+    hotkey::{ConsumePreference, Error, Hook, Hotkey, KeyCode},
+    hotkey_action::CustomActionHandler,
+    hotkey_config::FIXED_ACTIONS,
+    HotkeyAction, HotkeyBinding, HotkeyConfig, HotkeyMode, TimeSpan, TimeStamp,
 };
 
 pub use crate::hotkey::Result;
 
-// This enum might be better situated in hotkey_config, but the last method should stay in this file
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum Action {
-    Split,
-    /// The key to use for resetting the current attempt.
-    Reset,
-    /// The key to use for undoing the last split.
-    Undo,
-    /// The key to use for skipping the current split.
-    Skip,
-    /// The key to use for pausing the current attempt and starting a new
-    /// attempt.
-    Pause,
-    /// The key to use for removing all the pause times from the current time.
-    UndoAllPauses,
-    /// The key to use for switching to the previous comparison.
-    PreviousComparison,
-    /// The key to use for switching to the next comparison.
-    NextComparison,
-    /// The key to use for toggling between the `Real Time` and `Game Time`
-    /// timing methods.
-    ToggleTimingMethod,
+/// The error type for switching the hotkey profile of a [`HotkeySystem`].
+#[derive(Debug, snafu::Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum SwitchProfileError {
+    /// There is no profile with the given name.
+    #[snafu(display("There is no hotkey profile with the given name."))]
+    UnknownProfile,
+    /// The hotkeys of the profile couldn't be registered.
+    #[snafu(display("{source}"))]
+    Registration {
+        /// The underlying error.
+        source: Error,
+    },
+}
+
+/// The keys used for chords that are currently held down.
+type HeldKeys = Arc<Mutex<Vec<KeyCode>>>;
+
+type Callback = Arc<Mutex<Box<dyn FnMut() + Send + 'static>>>;
+
+/// Decides based on the hotkey's mode whether a press of the hotkey or holding
+/// it down triggers the action. The times are provided relative to an
+/// arbitrary point in time.
+struct Trigger {
+    mode: HotkeyMode,
+    held_keys: HeldKeys,
+    is_down: bool,
+    presses: u64,
+    last_press: Option<TimeSpan>,
 }
 
-impl Action {
-    fn set_hotkey(self, config: &mut HotkeyConfig, hotkey: Option<Hotkey>) {
-        match self {
-            Action::Split => config.split = hotkey,
-            Action::Reset => config.reset = hotkey,
-            Action::Undo => config.undo = hotkey,
-            Action::Skip => config.skip = hotkey,
-            Action::Pause => config.pause = hotkey,
-            Action::UndoAllPauses => config.undo_all_pauses = hotkey,
-            Action::PreviousComparison => config.previous_comparison = hotkey,
-            Action::NextComparison => config.next_comparison = hotkey,
-            Action::ToggleTimingMethod => config.toggle_timing_method = hotkey,
+impl Trigger {
+    const fn new(mode: HotkeyMode, held_keys: HeldKeys) -> Self {
+        Self {
+            mode,
+            held_keys,
+            is_down: false,
+            presses: 0,
+            last_press: None,
         }
     }
 
-    const fn get_hotkey(self, config: &HotkeyConfig) -> Option<Hotkey> {
-        match self {
-            Action::Split => config.split,
-            Action::Reset => config.reset,
-            Action::Undo => config.undo,
-            Action::Skip => config.skip,
-            Action::Pause => config.pause,
-            Action::UndoAllPauses => config.undo_all_pauses,
-            Action::PreviousComparison => config.previous_comparison,
-            Action::NextComparison => config.next_comparison,
-            Action::ToggleTimingMethod => config.toggle_timing_method,
+    /// Handles a press of the hotkey and returns whether it triggers the
+    /// action. Repeated presses while the key is held down are ignored.
+    fn press(&mut self, now: TimeSpan) -> bool {
+        if mem::replace(&mut self.is_down, true) {
+            return false;
         }
+        self.presses += 1;
+        let last_press = self.last_press.replace(now);
+
+        match self.mode {
+            HotkeyMode::Press => true,
+            HotkeyMode::Hold(_) => false,
+            HotkeyMode::DoubleTap(millis) => {
+                let is_double_tap = last_press
+                    .is_some_and(|last| now - last <= TimeSpan::from_milliseconds(millis as _));
+                if is_double_tap {
+                    // A third tap shouldn't count as another double tap.
+                    self.last_press = None;
+                }
+                is_double_tap
+            }
+            HotkeyMode::Chord(key_code) => self.held_keys.lock().unwrap().contains(&key_code),
+        }
+    }
+
+    /// Handles the hotkey being released.
+    fn release(&mut self) {
+        self.is_down = false;
     }
 
-    fn callback<S: event::CommandSink + Send + 'static>(
-        self,
-        command_sink: S,
-    ) -> Box<dyn FnMut() + Send + 'static> {
-        match self {
-            Action::Split => Box::new(move || {
-                drop(command_sink.split_or_start());
-            }),
-            Action::Reset => Box::new(move || {
-                drop(command_sink.reset(None));
-            }),
-            Action::Undo => Box::new(move || {
-                drop(command_sink.undo_split());
-            }),
-            Action::Skip => Box::new(move || {
-                drop(command_sink.skip_split());
-            }),
-            Action::Pause => Box::new(move || {
-                drop(command_sink.toggle_pause_or_start());
-            }),
-            Action::UndoAllPauses => Box::new(move || {
-                drop(command_sink.undo_all_pauses());
-            }),
-            Action::PreviousComparison => Box::new(move || {
-                drop(command_sink.switch_to_previous_comparison());
-            }),
-            Action::NextComparison => Box::new(move || {
-                drop(command_sink.switch_to_next_comparison());
-            }),
-            Action::ToggleTimingMethod => Box::new(move || {
-                drop(command_sink.toggle_timing_method());
-            }),
+    /// Returns whether the hotkey has been held down ever since the press with
+    /// the given number. For hotkeys with the hold mode, this is checked once
+    /// the time to hold them down has passed, which then triggers the action.
+    const fn is_held_since(&self, press: u64) -> bool {
+        matches!(self.mode, HotkeyMode::Hold(_)) && self.is_down && self.presses == press
+    }
+}
+
+/// Calls the function once the given amount of time has passed.
+fn run_after(delay: Duration, f: impl FnOnce() + Send + 'static) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_family = "wasm", target_os = "unknown", feature = "wasm-web"))] {
+            use wasm_bindgen::{closure::Closure, JsCast};
+            if let Some(window) = web_sys::window() {
+                let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                    Closure::once_into_js(f).unchecked_ref(),
+                    delay.as_millis() as i32,
+                );
+            }
+        } else if #[cfg(not(target_family = "wasm"))] {
+            use std::{
+                panic::{self, AssertUnwindSafe},
+                sync::{mpsc::{self, RecvTimeoutError, Sender}, OnceLock},
+                thread,
+                time::Instant,
+            };
+
+            type Scheduled = (Instant, Box<dyn FnOnce() + Send + 'static>);
+
+            // All the delayed functions are run by a single thread, so holding
+            // down hotkeys over and over again doesn't spawn more threads.
+            static SCHEDULER: OnceLock<Sender<Scheduled>> = OnceLock::new();
+
+            let scheduler = SCHEDULER.get_or_init(|| {
+                let (sender, receiver) = mpsc::channel::<Scheduled>();
+                thread::spawn(move || {
+                    let mut pending = Vec::<Scheduled>::new();
+                    loop {
+                        let received = match pending.iter().map(|(at, _)| *at).min() {
+                            Some(at) => receiver
+                                .recv_timeout(at.saturating_duration_since(Instant::now())),
+                            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                        };
+                        match received {
+                            Ok(scheduled) => pending.push(scheduled),
+                            Err(RecvTimeoutError::Timeout) => {}
+                            Err(RecvTimeoutError::Disconnected) => return,
+                        }
+
+                        let now = Instant::now();
+                        let mut i = 0;
+                        while let Some((at, _)) = pending.get(i) {
+                            if *at <= now {
+                                let (_, f) = pending.swap_remove(i);
+                                // A panicking action shouldn't stop all the
+                                // other ones from running.
+                                let _ = panic::catch_unwind(AssertUnwindSafe(f));
+                            } else {
+                                i += 1;
+                            }
+                        }
+                    }
+                });
+                sender
+            });
+            let _ = scheduler.send((Instant::now() + delay, Box::new(f)));
+        } else {
+            // There are no hotkeys on the other WebAssembly platforms anyway.
+            drop((delay, f));
         }
     }
 }
@@ -107,6 +171,18 @@ pub struct HotkeySystem<S> {
     hook: Hook,
     command_sink: S,
     is_active: bool,
+    epoch: TimeStamp,
+    held_keys: HeldKeys,
+    registered: Vec<Hotkey>,
+    custom_action_handler: CustomActionHandler,
+    #[cfg(feature = "midi")]
+    midi_hook: Option<MidiHook>,
+    #[cfg(feature = "midi")]
+    registered_midi: Vec<MidiTrigger>,
+    #[cfg(feature = "devices")]
+    device_hook: DeviceHook,
+    #[cfg(feature = "devices")]
+    registered_devices: Vec<DeviceTrigger>,
 }
 
 impl<S: event::CommandSink + Clone + Send + 'static> HotkeySystem<S> {
@@ -123,118 +199,250 @@ impl<S: event::CommandSink + Clone + Send + 'static> HotkeySystem<S> {
             hook: Hook::with_consume_preference(ConsumePreference::PreferNoConsume)?,
             command_sink,
             is_active: false,
+            epoch: TimeStamp::now(),
+            held_keys: Default::default(),
+            registered: Vec::new(),
+            custom_action_handler: Default::default(),
+            #[cfg(feature = "midi")]
+            midi_hook: None,
+            #[cfg(feature = "midi")]
+            registered_midi: Vec::new(),
+            #[cfg(feature = "devices")]
+            device_hook: DeviceHook::new(),
+            #[cfg(feature = "devices")]
+            registered_devices: Vec::new(),
         };
         hotkey_system.activate()?;
         Ok(hotkey_system)
     }
 
-    // This method should never be public, because it might mess up the internal
-    // state and we might leak a registered hotkey
-    fn register_inner(&self, action: Action) -> Result<()> {
-        let inner = self.command_sink.clone();
-        if let Some(hotkey) = action.get_hotkey(&self.config) {
-            self.hook.register(hotkey, action.callback(inner))?;
-        }
-        Ok(())
+    fn callback(&self, action: HotkeyAction) -> Box<dyn FnMut() + Send + 'static> {
+        action.callback(
+            self.command_sink.clone(),
+            self.custom_action_handler.clone(),
+        )
     }
 
-    fn register(&mut self, action: Action, hotkey: Option<Hotkey>) -> Result<()> {
-        action.set_hotkey(&mut self.config, hotkey);
-        self.register_inner(action)
+    fn register_hotkey(
+        &mut self,
+        hotkey: Hotkey,
+        mode: HotkeyMode,
+        consume: bool,
+        action: HotkeyAction,
+        chord_keys: &[KeyCode],
+    ) -> Result<()> {
+        // The keys used for chords can't be used as hotkeys on their own, so
+        // such hotkeys are only reported as conflicts by the configuration.
+        if hotkey.modifiers.is_empty() && chord_keys.contains(&hotkey.key_code) {
+            return Ok(());
+        }
+
+        match mode {
+            HotkeyMode::Press if consume => self
+                .hook
+                .register_consuming(hotkey, self.callback(action))?,
+            HotkeyMode::Press => self.hook.register(hotkey, self.callback(action))?,
+            mode => {
+                let trigger = Arc::new(Mutex::new(Trigger::new(mode, self.held_keys.clone())));
+                let callback: Callback = Arc::new(Mutex::new(self.callback(action)));
+                let release_trigger = trigger.clone();
+                let epoch = self.epoch;
+
+                let on_press = move || {
+                    let mut locked = trigger.lock().unwrap();
+                    if locked.press(TimeStamp::now() - epoch) {
+                        drop(locked);
+                        (callback.lock().unwrap())();
+                    } else if let HotkeyMode::Hold(millis) = locked.mode {
+                        let press = locked.presses;
+                        let (trigger, callback) = (trigger.clone(), callback.clone());
+                        run_after(Duration::from_millis(millis.into()), move || {
+                            if trigger.lock().unwrap().is_held_since(press) {
+                                (callback.lock().unwrap())();
+                            }
+                        });
+                    }
+                };
+                let on_release = move || release_trigger.lock().unwrap().release();
+
+                if consume {
+                    self.hook
+                        .register_consuming_with_release(hotkey, on_press, on_release)?
+                } else {
+                    self.hook
+                        .register_with_release(hotkey, on_press, on_release)?
+                }
+            }
+        }
+
+        self.registered.push(hotkey);
+        Ok(())
     }
 
     // This method should never be public, because it might mess up the internal
     // state and we might leak a registered hotkey
-    fn unregister_inner(&self, action: Action) -> Result<()> {
-        if let Some(hotkey) = action.get_hotkey(&self.config) {
-            self.hook.unregister(hotkey)?;
+    fn register_all(&mut self) -> Result<()> {
+        let bindings = self.config.bindings.clone();
+
+        // Connecting to the MIDI devices is deferred until there actually is a
+        // MIDI message that needs to be listened to.
+        #[cfg(feature = "midi")]
+        if self.midi_hook.is_none() && bindings.fixed().iter().any(|b| b.midi.is_some()) {
+            self.midi_hook = MidiHook::new().ok();
+        }
+
+        let chord_keys = bindings.chord_keys();
+        for &key_code in &chord_keys {
+            let pressed = self.held_keys.clone();
+            let released = self.held_keys.clone();
+            self.hook.register_with_release(
+                key_code.into(),
+                move || pressed.lock().unwrap().push(key_code),
+                move || released.lock().unwrap().retain(|&k| k != key_code),
+            )?;
+            self.registered.push(key_code.into());
         }
+
+        for (binding, (_, _, action)) in bindings.fixed().into_iter().zip(&FIXED_ACTIONS) {
+            // Issues with the MIDI devices should not prevent the keyboard from
+            // being used, so any errors are ignored.
+            #[cfg(feature = "midi")]
+            if let (Some(trigger), Some(midi_hook)) = (binding.midi, &self.midi_hook) {
+                if midi_hook
+                    .register(trigger, self.callback(action.clone()))
+                    .is_ok()
+                {
+                    self.registered_midi.push(trigger);
+                }
+            }
+
+            // The same goes for devices that are not connected.
+            #[cfg(feature = "devices")]
+            if let Some(trigger) = &binding.device {
+                if self
+                    .device_hook
+                    .register(trigger.clone(), self.callback(action.clone()))
+                    .is_ok()
+                {
+                    self.registered_devices.push(trigger.clone());
+                }
+            }
+
+            if let Some(hotkey) = binding.hotkey {
+                self.register_hotkey(
+                    hotkey,
+                    binding.mode,
+                    binding.consume,
+                    action.clone(),
+                    &chord_keys,
+                )?;
+            }
+        }
+
+        for binding in &bindings.additional {
+            self.register_hotkey(
+                binding.hotkey,
+                binding.mode,
+                binding.consume,
+                binding.action.clone(),
+                &chord_keys,
+            )?;
+        }
+
         Ok(())
     }
 
-    fn unregister(&mut self, action: Action) -> Result<()> {
-        self.unregister_inner(action)?;
-        action.set_hotkey(&mut self.config, None);
-        Ok(())
+    // This method should never be public, because it might mess up the internal
+    // state and we might leak a registered hotkey
+    fn unregister_all(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        for hotkey in self.registered.drain(..) {
+            if let Err(e) = self.hook.unregister(hotkey) {
+                result = Err(e);
+            }
+        }
+
+        #[cfg(feature = "midi")]
+        for trigger in self.registered_midi.drain(..) {
+            if let Some(midi_hook) = &self.midi_hook {
+                let _ = midi_hook.unregister(trigger);
+            }
+        }
+
+        #[cfg(feature = "devices")]
+        for trigger in self.registered_devices.drain(..) {
+            let _ = self.device_hook.unregister(&trigger);
+        }
+
+        self.held_keys.lock().unwrap().clear();
+        result
     }
 
-    fn set_hotkey(&mut self, action: Action, hotkey: Option<Hotkey>) -> Result<()> {
+    fn set_hotkey(&mut self, index: usize, hotkey: Option<Hotkey>) -> Result<()> {
         // FIXME: We do not check whether the hotkey is already in use
-        if action.get_hotkey(&self.config) == hotkey {
+        if self.config.binding(index).hotkey == hotkey {
             return Ok(());
         }
-        if self.is_active {
-            self.unregister(action)?;
-            self.register(action, hotkey)?;
-        } else {
-            action.set_hotkey(&mut self.config, hotkey);
-        }
-        Ok(())
+        let mut config = self.config.clone();
+        config.binding_mut(index).hotkey = hotkey;
+        self.set_config(config)
     }
 
     /// Sets the key to use for splitting and starting a new attempt.
     pub fn set_split(&mut self, hotkey: Option<Hotkey>) -> Result<()> {
-        self.set_hotkey(Action::Split, hotkey)
+        self.set_hotkey(0, hotkey)
     }
 
     /// Sets the key to use for resetting the current attempt.
     pub fn set_reset(&mut self, hotkey: Option<Hotkey>) -> Result<()> {
-        self.set_hotkey(Action::Reset, hotkey)
+        self.set_hotkey(1, hotkey)
     }
 
     /// Sets the key to use for pausing the current attempt and starting a new
     /// attempt.
     pub fn set_pause(&mut self, hotkey: Option<Hotkey>) -> Result<()> {
-        self.set_hotkey(Action::Pause, hotkey)
+        self.set_hotkey(4, hotkey)
     }
 
     /// Sets the key to use for skipping the current split.
     pub fn set_skip(&mut self, hotkey: Option<Hotkey>) -> Result<()> {
-        self.set_hotkey(Action::Skip, hotkey)
+        self.set_hotkey(3, hotkey)
     }
 
     /// Sets the key to use for undoing the last split.
     pub fn set_undo(&mut self, hotkey: Option<Hotkey>) -> Result<()> {
-        self.set_hotkey(Action::Undo, hotkey)
+        self.set_hotkey(2, hotkey)
     }
 
     /// Sets the key to use for switching to the previous comparison.
     pub fn set_previous_comparison(&mut self, hotkey: Option<Hotkey>) -> Result<()> {
-        self.set_hotkey(Action::PreviousComparison, hotkey)
+        self.set_hotkey(6, hotkey)
     }
 
     /// Sets the key to use for switching to the next comparison.
     pub fn set_next_comparison(&mut self, hotkey: Option<Hotkey>) -> Result<()> {
-        self.set_hotkey(Action::NextComparison, hotkey)
+        self.set_hotkey(7, hotkey)
     }
 
     /// Sets the key to use for removing all the pause times from the current
     /// time.
     pub fn set_undo_all_pauses(&mut self, hotkey: Option<Hotkey>) -> Result<()> {
-        self.set_hotkey(Action::UndoAllPauses, hotkey)
+        self.set_hotkey(5, hotkey)
     }
 
     /// Sets the key to use for toggling between the `Real Time` and `Game Time`
     /// timing methods.
     pub fn set_toggle_timing_method(&mut self, hotkey: Option<Hotkey>) -> Result<()> {
-        self.set_hotkey(Action::ToggleTimingMethod, hotkey)
+        self.set_hotkey(8, hotkey)
     }
 
     /// Deactivates the Hotkey System. No hotkeys will go through until it gets
     /// activated again. If it's already deactivated, nothing happens.
     pub fn deactivate(&mut self) -> Result<()> {
         if self.is_active {
-            self.unregister_inner(Action::Split)?;
-            self.unregister_inner(Action::Reset)?;
-            self.unregister_inner(Action::Undo)?;
-            self.unregister_inner(Action::Skip)?;
-            self.unregister_inner(Action::Pause)?;
-            self.unregister_inner(Action::UndoAllPauses)?;
-            self.unregister_inner(Action::PreviousComparison)?;
-            self.unregister_inner(Action::NextComparison)?;
-            self.unregister_inner(Action::ToggleTimingMethod)?;
+            self.is_active = false;
+            self.unregister_all()?;
         }
-        self.is_active = false;
         Ok(())
     }
 
@@ -242,17 +450,12 @@ impl<S: event::CommandSink + Clone + Send + 'static> HotkeySystem<S> {
     /// active, nothing happens.
     pub fn activate(&mut self) -> Result<()> {
         if !self.is_active {
-            self.register_inner(Action::Split)?;
-            self.register_inner(Action::Reset)?;
-            self.register_inner(Action::Undo)?;
-            self.register_inner(Action::Skip)?;
-            self.register_inner(Action::Pause)?;
-            self.register_inner(Action::UndoAllPauses)?;
-            self.register_inner(Action::PreviousComparison)?;
-            self.register_inner(Action::NextComparison)?;
-            self.register_inner(Action::ToggleTimingMethod)?;
+            if let Err(e) = self.register_all() {
+                let _ = self.unregister_all();
+                return Err(e);
+            }
+            self.is_active = true;
         }
-        self.is_active = true;
         Ok(())
     }
 
@@ -262,30 +465,190 @@ impl<S: event::CommandSink + Clone + Send + 'static> HotkeySystem<S> {
     }
 
     /// Returns the hotkey configuration currently in use by the Hotkey System.
-    pub const fn config(&self) -> HotkeyConfig {
-        self.config
+    pub const fn config(&self) -> &HotkeyConfig {
+        &self.config
     }
 
     /// Applies a new hotkey configuration to the Hotkey System. Each hotkey is
     /// changed to the one specified in the configuration. This operation may
     /// fail if you provide a hotkey configuration where a hotkey is used for
-    /// multiple operations.
+    /// multiple operations. In that case the previous configuration stays in
+    /// use.
     pub fn set_config(&mut self, config: HotkeyConfig) -> Result<()> {
-        self.set_split(config.split)?;
-        self.set_reset(config.reset)?;
-        self.set_undo(config.undo)?;
-        self.set_skip(config.skip)?;
-        self.set_pause(config.pause)?;
-        self.set_previous_comparison(config.previous_comparison)?;
-        self.set_next_comparison(config.next_comparison)?;
-        self.set_undo_all_pauses(config.undo_all_pauses)?;
-        self.set_toggle_timing_method(config.toggle_timing_method)?;
+        if !self.is_active {
+            self.config = config;
+            return Ok(());
+        }
 
+        // The modes and all the other settings of the bindings are baked into
+        // the registered callbacks, so everything needs to be registered
+        // again.
+        self.deactivate()?;
+        let previous = mem::replace(&mut self.config, config);
+        if let Err(e) = self.activate() {
+            self.config = previous;
+            let _ = self.activate();
+            return Err(e);
+        }
         Ok(())
     }
 
+    /// Switches to the hotkey profile with the given name. The hotkeys
+    /// currently in use are stored in the profile that was active before. This
+    /// operation fails if there is no profile with the given name or if the
+    /// hotkeys of the profile can't be registered. In that case the previous
+    /// hotkeys stay in use.
+    pub fn switch_profile(&mut self, name: &str) -> core::result::Result<(), SwitchProfileError> {
+        let mut config = self.config.clone();
+        config
+            .switch_profile(name)
+            .map_err(|()| SwitchProfileError::UnknownProfile)?;
+        self.set_config(config)
+            .map_err(|source| SwitchProfileError::Registration { source })
+    }
+
+    /// Switches to the hotkey profile that is meant to be used for the game
+    /// with the given name. This is meant to be called whenever a different
+    /// [`Run`](crate::Run) is loaded. If there is no such profile, the hotkeys
+    /// stay the same.
+    pub fn switch_profile_for_game(&mut self, game_name: &str) -> Result<()> {
+        if let Some(name) = self
+            .config
+            .profile_for_game(game_name)
+            .map(|p| p.name.clone())
+        {
+            if self.config.active_profile.as_deref() != Some(&*name) {
+                let mut config = self.config.clone();
+                if config.switch_profile(&name).is_ok() {
+                    self.set_config(config)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Binds the given hotkey to an arbitrary action. This operation may fail
+    /// if the hotkey is already in use.
+    pub fn add_binding(&mut self, binding: HotkeyBinding) -> Result<()> {
+        let mut config = self.config.clone();
+        config
+            .add_binding(binding)
+            .map_err(|()| Error::AlreadyRegistered)?;
+        self.set_config(config)
+    }
+
+    /// Removes the additional binding of the given hotkey, if there is one.
+    pub fn remove_binding(&mut self, hotkey: Hotkey) -> Result<()> {
+        if !self
+            .config
+            .bindings
+            .additional
+            .iter()
+            .any(|b| b.hotkey == hotkey)
+        {
+            return Ok(());
+        }
+        let mut config = self.config.clone();
+        config.remove_binding(hotkey);
+        self.set_config(config)
+    }
+
+    /// Sets the handler that is called with the name of a
+    /// [`HotkeyAction::Custom`] action whenever its hotkey is triggered. This
+    /// allows the application to bind actions that the timer doesn't know
+    /// about, such as toggling a component of the layout.
+    pub fn set_custom_action_handler<F>(&mut self, handler: F)
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        *self.custom_action_handler.lock().unwrap() = Some(Box::new(handler));
+    }
+
     /// Resolves the key according to the current keyboard layout.
     pub fn resolve(&self, key_code: KeyCode) -> Cow<'static, str> {
         key_code.resolve(&self.hook)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger(mode: HotkeyMode) -> Trigger {
+        Trigger::new(mode, Default::default())
+    }
+
+    fn ms(millis: u32) -> TimeSpan {
+        TimeSpan::from_milliseconds(millis as _)
+    }
+
+    #[test]
+    fn run_after_runs_the_functions_in_order() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        for (index, delay) in [(2, 60), (0, 0), (1, 30)] {
+            let sender = sender.clone();
+            run_after(Duration::from_millis(delay), move || {
+                sender.send(index).unwrap();
+            });
+        }
+        let order: Vec<i32> = receiver.iter().take(3).collect();
+        assert_eq!(order, [0, 1, 2]);
+    }
+
+    #[test]
+    fn press_ignores_key_repeat() {
+        let mut trigger = trigger(HotkeyMode::Press);
+        assert!(trigger.press(ms(0)));
+        assert!(!trigger.press(ms(30)));
+        trigger.release();
+        assert!(trigger.press(ms(60)));
+    }
+
+    #[test]
+    fn hold_triggers_while_still_held() {
+        let mut trigger = trigger(HotkeyMode::Hold(500));
+        assert!(!trigger.press(ms(0)));
+        let press = trigger.presses;
+        // Key repeats don't restart the time to hold the key down.
+        assert!(!trigger.press(ms(100)));
+        assert!(trigger.is_held_since(press));
+
+        trigger.release();
+        assert!(!trigger.is_held_since(press));
+
+        // Pressing the key again in the meantime doesn't count either.
+        assert!(!trigger.press(ms(200)));
+        assert!(!trigger.is_held_since(press));
+        assert!(trigger.is_held_since(trigger.presses));
+    }
+
+    #[test]
+    fn double_tap() {
+        let mut trigger = trigger(HotkeyMode::DoubleTap(300));
+        assert!(!trigger.press(ms(0)));
+        trigger.release();
+        assert!(trigger.press(ms(200)));
+        trigger.release();
+        // A third tap doesn't count as another double tap.
+        assert!(!trigger.press(ms(400)));
+        trigger.release();
+        // Taps that are too far apart don't count.
+        assert!(!trigger.press(ms(800)));
+        trigger.release();
+        assert!(trigger.press(ms(1000)));
+    }
+
+    #[test]
+    fn chord_requires_held_key() {
+        let mut trigger = trigger(HotkeyMode::Chord(KeyCode::ShiftLeft));
+        assert!(!trigger.press(ms(0)));
+        trigger.release();
+
+        trigger.held_keys.lock().unwrap().push(KeyCode::ShiftLeft);
+        assert!(trigger.press(ms(100)));
+        trigger.release();
+
+        trigger.held_keys.lock().unwrap().clear();
+        assert!(!trigger.press(ms(200)));
+    }
+}
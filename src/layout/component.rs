@@ -1,9 +1,9 @@
 use super::{ComponentSettings, ComponentState, GeneralSettings};
 use crate::{
     component::{
-        blank_space, current_comparison, current_pace, delta, detailed_timer, graph, pb_chance,
-        possible_time_save, previous_segment, segment_time, separator, splits, sum_of_best, text,
-        timer, title, total_playtime,
+        blank_space, current_comparison, current_pace, delta, detailed_timer, graph, host,
+        pb_chance, possible_time_save, previous_segment, segment_time, separator, splits,
+        sum_of_best, text, timer, title, total_playtime,
     },
     platform::prelude::*,
     settings::{ImageCache, SettingsDescription, Value},
@@ -27,6 +27,8 @@ pub enum Component {
     DetailedTimer(Box<detailed_timer::Component>),
     /// The Graph Component.
     Graph(graph::Component),
+    /// A component defined by the host of this library.
+    Host(host::Component),
     /// The PB Chance Component.
     PbChance(pb_chance::Component),
     /// The Possible Time Save Component.
@@ -87,6 +89,12 @@ impl From<graph::Component> for Component {
     }
 }
 
+impl From<host::Component> for Component {
+    fn from(component: host::Component) -> Self {
+        Self::Host(component)
+    }
+}
+
 impl From<pb_chance::Component> for Component {
     fn from(component: pb_chance::Component) -> Self {
         Self::PbChance(component)
@@ -253,6 +261,7 @@ impl Component {
             Component::Graph(component) => {
                 ComponentState::Graph(component.state(timer, layout_settings))
             }
+            Component::Host(component) => component.state(timer),
             Component::PbChance(component) => ComponentState::KeyValue(component.state(timer)),
             Component::PossibleTimeSave(component) => {
                 ComponentState::KeyValue(component.state(timer))
@@ -297,6 +306,7 @@ impl Component {
                 ComponentSettings::DetailedTimer(Box::new(component.settings().clone()))
             }
             Component::Graph(component) => ComponentSettings::Graph(component.settings().clone()),
+            Component::Host(component) => ComponentSettings::Host(component.settings()),
             Component::PbChance(component) => {
                 ComponentSettings::PbChance(component.settings().clone())
             }
@@ -332,6 +342,7 @@ impl Component {
             Component::Delta(component) => component.name(),
             Component::DetailedTimer(component) => component.name().into(),
             Component::Graph(component) => component.name(),
+            Component::Host(component) => component.name().into(),
             Component::PbChance(component) => component.name().into(),
             Component::PossibleTimeSave(component) => component.name(),
             Component::PreviousSegment(component) => component.name(),
@@ -374,6 +385,7 @@ impl Component {
             Component::Delta(component) => component.settings_description(),
             Component::DetailedTimer(component) => component.settings_description(),
             Component::Graph(component) => component.settings_description(),
+            Component::Host(component) => component.settings_description(),
             Component::PbChance(component) => component.settings_description(),
             Component::PossibleTimeSave(component) => component.settings_description(),
             Component::PreviousSegment(component) => component.settings_description(),
@@ -404,6 +416,7 @@ impl Component {
             Component::Delta(component) => component.set_value(index, value),
             Component::DetailedTimer(component) => component.set_value(index, value),
             Component::Graph(component) => component.set_value(index, value),
+            Component::Host(component) => component.set_value(index, value),
             Component::PbChance(component) => component.set_value(index, value),
             Component::PossibleTimeSave(component) => component.set_value(index, value),
             Component::PreviousSegment(component) => component.set_value(index, value),
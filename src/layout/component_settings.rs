@@ -1,9 +1,9 @@
 use super::Component;
 use crate::{
     component::{
-        blank_space, current_comparison, current_pace, delta, detailed_timer, graph, pb_chance,
-        possible_time_save, previous_segment, segment_time, separator, splits, sum_of_best, text,
-        timer, title, total_playtime,
+        blank_space, current_comparison, current_pace, delta, detailed_timer, graph, host,
+        pb_chance, possible_time_save, previous_segment, segment_time, separator, splits,
+        sum_of_best, text, timer, title, total_playtime,
     },
     platform::prelude::*,
 };
@@ -24,6 +24,8 @@ pub enum ComponentSettings {
     DetailedTimer(Box<detailed_timer::Settings>),
     /// The Settings for the Graph Component.
     Graph(graph::Settings),
+    /// The Settings for a component defined by the host of this library.
+    Host(host::Settings),
     /// The Settings for the PB Chance Component.
     PbChance(pb_chance::Settings),
     /// The Settings for the Possible Time Save Component.
@@ -69,6 +71,9 @@ impl From<ComponentSettings> for Component {
             ComponentSettings::Graph(settings) => {
                 Component::Graph(graph::Component::with_settings(settings))
             }
+            ComponentSettings::Host(settings) => {
+                Component::Host(host::Component::with_settings(settings))
+            }
             ComponentSettings::PbChance(settings) => {
                 Component::PbChance(pb_chance::Component::with_settings(settings))
             }
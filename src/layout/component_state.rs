@@ -8,7 +8,7 @@ use crate::{
 };
 
 /// The state object for one of the components available.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum ComponentState {
     /// The state object for the Blank Space Component.
     BlankSpace(blank_space::State),
@@ -12,6 +12,8 @@ use crate::{
 use core::result::Result as StdResult;
 
 mod state;
+#[cfg(test)]
+mod tests;
 
 pub use self::state::{Buttons as ButtonsState, State};
 
@@ -170,6 +172,44 @@ impl Editor {
         }
     }
 
+    /// Moves the component at the source index to the destination index. The
+    /// moved component becomes the selected component. You may not provide
+    /// invalid indices.
+    pub fn move_component_from(&mut self, src_index: usize, dst_index: usize) {
+        if src_index < self.layout.components.len() {
+            self.select(src_index);
+            self.move_component(dst_index);
+        }
+    }
+
+    /// Checks if the component at the source index can be dropped into the gap
+    /// with the given index when reordering the components via drag and drop.
+    /// The gaps are numbered from 0, which is before the first component, up
+    /// to the number of components, which is after the last component. The
+    /// gaps directly before and after the component itself are not valid drop
+    /// targets, as dropping it there doesn't change anything.
+    pub fn can_drop_component(&self, src_index: usize, gap_index: usize) -> bool {
+        src_index < self.layout.components.len()
+            && gap_index <= self.layout.components.len()
+            && gap_index != src_index
+            && gap_index != src_index + 1
+    }
+
+    /// Drops the component at the source index into the gap with the given
+    /// index. See [`can_drop_component`](Self::can_drop_component) for how the
+    /// gaps are numbered. The dropped component becomes the selected
+    /// component. Nothing happens if the gap is not a valid drop target.
+    pub fn drop_component(&mut self, src_index: usize, gap_index: usize) {
+        if self.can_drop_component(src_index, gap_index) {
+            let dst_index = if gap_index > src_index {
+                gap_index - 1
+            } else {
+                gap_index
+            };
+            self.move_component_from(src_index, dst_index);
+        }
+    }
+
     /// Duplicates the currently selected component. The copy gets placed right
     /// after the selected component and becomes the newly selected component.
     pub fn duplicate_component(&mut self) {
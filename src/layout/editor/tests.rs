@@ -0,0 +1,57 @@
+use super::Editor;
+use crate::{
+    component::{BlankSpace, Separator, Title},
+    platform::prelude::*,
+    Layout,
+};
+
+fn editor() -> Editor {
+    let mut layout = Layout::new();
+    layout.push(Title::new());
+    layout.push(Separator::new());
+    layout.push(BlankSpace::new());
+    Editor::new(layout).unwrap()
+}
+
+fn names(editor: Editor) -> Vec<String> {
+    editor
+        .close()
+        .components
+        .iter()
+        .map(|c| c.name().into_owned())
+        .collect()
+}
+
+#[test]
+fn move_component_from() {
+    let mut editor = editor();
+    editor.move_component_from(2, 0);
+    assert_eq!(editor.selected_component, 0);
+    assert_eq!(names(editor), ["Blank Space", "Title", "Separator"]);
+}
+
+#[test]
+fn gaps_around_the_component_are_no_drop_targets() {
+    let editor = editor();
+    assert!(editor.can_drop_component(1, 0));
+    assert!(!editor.can_drop_component(1, 1));
+    assert!(!editor.can_drop_component(1, 2));
+    assert!(editor.can_drop_component(1, 3));
+    assert!(!editor.can_drop_component(1, 4));
+}
+
+#[test]
+fn drop_component_after_the_last_component() {
+    let mut editor = editor();
+    editor.drop_component(0, 3);
+    assert_eq!(editor.selected_component, 2);
+    assert_eq!(names(editor), ["Separator", "Blank Space", "Title"]);
+}
+
+#[test]
+fn drop_component_before_the_first_component() {
+    let mut editor = editor();
+    editor.drop_component(1, 0);
+    assert_eq!(editor.selected_component, 0);
+    assert_eq!(names(editor), ["Separator", "Title", "Blank Space"]);
+}
@@ -4,6 +4,7 @@ use crate::{
     settings::{
         Color, Field, Font, Gradient, ImageCache, LayoutBackground, SettingsDescription, Value,
     },
+    timing::formatter::TimePattern,
 };
 use serde_derive::{Deserialize, Serialize};
 
@@ -50,6 +51,24 @@ pub struct GeneralSettings {
     pub separators_color: Color,
     /// The text color to use for text that doesn't specify its own color.
     pub text_color: Color,
+    /// The radius of the rounded corners of the components' backgrounds. The
+    /// radius is specified in the same unit as the size of a blank space
+    /// component. A radius of 0 means the corners are not rounded.
+    pub corner_radius: u32,
+    /// The color of the drop shadow shown behind all of the text. `None` means
+    /// no drop shadow is shown.
+    pub drop_shadow: Option<Color>,
+    /// The thickness of the separators in percent of their default thickness.
+    pub separator_thickness: u32,
+    /// The horizontal padding of the components in percent of their default
+    /// padding.
+    pub padding_scale: u32,
+    /// The pattern to format the times shown by the Timer and Detailed Timer
+    /// components with. `None` means their accuracy and digits format are used
+    /// instead. The timers may override this with their own pattern. The other
+    /// components that show times and the columns of the Splits Component only
+    /// have their own pattern.
+    pub time_pattern: Option<TimePattern>,
 }
 
 impl Default for GeneralSettings {
@@ -73,6 +92,11 @@ impl Default for GeneralSettings {
             thin_separators_color: Color::hsla(0.0, 0.0, 1.0, 0.09),
             separators_color: Color::hsla(0.0, 0.0, 1.0, 0.35),
             text_color: Color::hsla(0.0, 0.0, 1.0, 1.0),
+            corner_radius: 0,
+            drop_shadow: None,
+            separator_thickness: 100,
+            padding_scale: 100,
+            time_pattern: None,
         }
     }
 }
@@ -173,6 +197,35 @@ impl GeneralSettings {
                 "The color to use for text that doesn't specify its own color.".into(),
                 self.text_color.into(),
             ),
+            Field::new(
+                "Corner Radius".into(),
+                "The radius of the rounded corners of the components' backgrounds. A radius of 0 means the corners are not rounded."
+                    .into(),
+                u64::from(self.corner_radius).into(),
+            ),
+            Field::new(
+                "Drop Shadow".into(),
+                "The color of the drop shadow shown behind all of the text. If this is not set, no drop shadow is shown."
+                    .into(),
+                self.drop_shadow.into(),
+            ),
+            Field::new(
+                "Separator Thickness".into(),
+                "The thickness of the separators in percent of their default thickness.".into(),
+                u64::from(self.separator_thickness).into(),
+            ),
+            Field::new(
+                "Padding Scale".into(),
+                "The horizontal padding of the components in percent of their default padding."
+                    .into(),
+                u64::from(self.padding_scale).into(),
+            ),
+            Field::new(
+                "Time Pattern".into(),
+                "The pattern to format the times shown by the Timer and Detailed Timer components with, like \"H:mm:ss.ff\". If this is not set, the accuracy and digits format of each timer are used instead. The other components that show times and the columns of the splits have their own time pattern settings instead."
+                    .into(),
+                self.time_pattern.clone().into(),
+            ),
         ])
     }
 
@@ -201,6 +254,11 @@ impl GeneralSettings {
             13 => self.thin_separators_color = value.into(),
             14 => self.separators_color = value.into(),
             15 => self.text_color = value.into(),
+            16 => self.corner_radius = value.into_uint().unwrap() as _,
+            17 => self.drop_shadow = value.into(),
+            18 => self.separator_thickness = value.into_uint().unwrap() as _,
+            19 => self.padding_scale = value.into_uint().unwrap() as _,
+            20 => self.time_pattern = value.into(),
             _ => panic!("Unsupported Setting Index"),
         }
     }
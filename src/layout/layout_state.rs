@@ -1,13 +1,15 @@
+use alloc::borrow::Cow;
 use serde_derive::{Deserialize, Serialize};
 
 use super::{ComponentState, LayoutDirection};
 use crate::{
+    localization::Localization,
     platform::prelude::*,
     settings::{Color, Font, ImageId, LayoutBackground},
 };
 
 /// The state object describes the information to visualize for the layout.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct LayoutState {
     /// The state objects for all of the components in the layout.
     pub components: Vec<ComponentState>,
@@ -30,6 +32,86 @@ pub struct LayoutState {
     pub separators_color: Color,
     /// The text color to use for text that doesn't specify its own color.
     pub text_color: Color,
+    /// The radius of the rounded corners of the components' backgrounds. The
+    /// radius is specified in the same unit as the size of a blank space
+    /// component. A radius of 0 means the corners are not rounded.
+    pub corner_radius: u32,
+    /// The color of the drop shadow shown behind all of the text. `None` means
+    /// no drop shadow is shown.
+    pub drop_shadow: Option<Color>,
+    /// The thickness of the separators in percent of their default thickness.
+    pub separator_thickness: u32,
+    /// The horizontal padding of the components in percent of their default
+    /// padding.
+    pub padding_scale: u32,
+}
+
+impl Default for LayoutState {
+    fn default() -> Self {
+        Self {
+            components: Vec::new(),
+            direction: LayoutDirection::default(),
+            timer_font: None,
+            times_font: None,
+            text_font: None,
+            background: LayoutBackground::default(),
+            thin_separators_color: Color::default(),
+            separators_color: Color::default(),
+            text_color: Color::default(),
+            corner_radius: 0,
+            drop_shadow: None,
+            separator_thickness: 100,
+            padding_scale: 100,
+        }
+    }
+}
+
+impl LayoutState {
+    /// Localizes the labels that are produced by the components, such as the
+    /// keys of the key value based components, the column labels of the
+    /// Splits Component and the comparison names of the Detailed Timer
+    /// Component. Texts provided by the user, like the title of the run, are
+    /// not localized.
+    pub fn localize(&mut self, localization: &Localization) {
+        if localization.is_empty() {
+            return;
+        }
+
+        let translate_in_place = |text: &mut String| {
+            if let Some(translated) = translate(localization, text) {
+                *text = translated;
+            }
+        };
+
+        for component in &mut self.components {
+            match component {
+                ComponentState::KeyValue(state) => {
+                    translate_in_place(&mut state.key);
+                    for abbreviation in &mut state.key_abbreviations {
+                        if let Some(translated) = translate(localization, abbreviation) {
+                            *abbreviation = translated.into();
+                        }
+                    }
+                }
+                ComponentState::Splits(state) => {
+                    if let Some(labels) = &mut state.column_labels {
+                        for label in labels.iter_mut() {
+                            translate_in_place(label);
+                        }
+                    }
+                }
+                ComponentState::DetailedTimer(state) => {
+                    for comparison in [&mut state.comparison1, &mut state.comparison2]
+                        .into_iter()
+                        .flatten()
+                    {
+                        translate_in_place(&mut comparison.name);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
 }
 
 #[cfg(feature = "std")]
@@ -42,3 +124,10 @@ impl LayoutState {
         serde_json::to_writer(writer, self)
     }
 }
+
+fn translate(localization: &Localization, text: &str) -> Option<String> {
+    match localization.translate_label(text) {
+        Cow::Borrowed(translated) if translated == text => None,
+        translated => Some(translated.into_owned()),
+    }
+}
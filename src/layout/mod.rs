@@ -118,6 +118,10 @@ impl Layout {
         state.thin_separators_color = settings.thin_separators_color;
         state.separators_color = settings.separators_color;
         state.text_color = settings.text_color;
+        state.corner_radius = settings.corner_radius;
+        state.drop_shadow = settings.drop_shadow;
+        state.separator_thickness = settings.separator_thickness;
+        state.padding_scale = settings.padding_scale;
         state.direction = settings.direction;
     }
 
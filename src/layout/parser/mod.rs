@@ -672,6 +672,7 @@ where
                         Component::Delta(c) => delta::settings(reader, c),
                         Component::DetailedTimer(c) => detailed_timer::settings(reader, c),
                         Component::Graph(c) => graph::settings(reader, c),
+                        Component::Host(_) => end_tag(reader),
                         Component::PbChance(c) => pb_chance::settings(reader, c),
                         Component::PossibleTimeSave(c) => possible_time_save::settings(reader, c),
                         Component::PreviousSegment(c) => previous_segment::settings(reader, c),
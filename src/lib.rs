@@ -68,10 +68,15 @@ pub mod comparison;
 pub mod component;
 pub mod event;
 #[cfg(feature = "std")]
+mod hotkey_action;
+#[cfg(feature = "std")]
 mod hotkey_config;
 #[cfg(feature = "std")]
 mod hotkey_system;
 pub mod layout;
+pub mod localization;
+#[cfg(feature = "log-watcher")]
+pub mod log_watcher;
 pub mod networking;
 #[cfg(feature = "rendering")]
 pub mod rendering;
@@ -95,4 +100,12 @@ pub use livesplit_hotkey as hotkey;
 pub use crate::platform::{register_clock, Clock, Duration};
 
 #[cfg(feature = "std")]
-pub use crate::{hotkey_config::HotkeyConfig, hotkey_system::HotkeySystem, timing::SharedTimer};
+pub use crate::{
+    hotkey_action::{HotkeyAction, HotkeyBinding},
+    hotkey_config::{ActionBinding, HotkeyBindings, HotkeyConfig, HotkeyMode, HotkeyProfile},
+    hotkey_system::{HotkeySystem, SwitchProfileError},
+    timing::SharedTimer,
+};
+
+/// The version of livesplit-core.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
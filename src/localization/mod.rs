@@ -0,0 +1,134 @@
+//! The localization module provides a localization layer for the user visible
+//! strings that are produced by livesplit-core, such as the names of the
+//! comparisons, the labels of the components and the phases of the timer.
+//! livesplit-core itself only produces English strings. Hosts can supply
+//! translation tables that map these English strings to the language of their
+//! choice.
+//!
+//! # Examples
+//!
+//! ```
+//! use livesplit_core::localization::Localization;
+//!
+//! let mut localization = Localization::new("de");
+//! localization.add_translation("Best Segments", "Beste Segmente");
+//! localization.add_translation("Current Pace", "Aktuelles Tempo");
+//!
+//! assert_eq!(localization.translate("Best Segments"), "Beste Segmente");
+//! assert_eq!(localization.translate("Latest Run"), "Latest Run");
+//! assert_eq!(
+//!     localization.translate_label("Current Pace (Best Segments)"),
+//!     "Aktuelles Tempo (Beste Segmente)",
+//! );
+//! ```
+
+use crate::platform::prelude::*;
+use alloc::{borrow::Cow, collections::BTreeMap};
+use serde_derive::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+/// A Localization stores the language to localize the strings into and the
+/// translation table that maps the English strings produced by livesplit-core
+/// to that language. Strings that are not part of the translation table stay
+/// untranslated. The default Localization is English without any
+/// translations.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Localization {
+    language: String,
+    translations: BTreeMap<String, String>,
+}
+
+impl Localization {
+    /// Creates a new Localization for the language provided without any
+    /// translations. The language should be specified as an IETF BCP 47
+    /// language tag, such as `de` or `pt-BR`.
+    pub fn new<S>(language: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            language: language.into(),
+            translations: BTreeMap::new(),
+        }
+    }
+
+    /// Accesses the language the strings are localized into. This is empty
+    /// for the default Localization.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Sets the language the strings are localized into.
+    pub fn set_language<S>(&mut self, language: S)
+    where
+        S: Into<String>,
+    {
+        self.language = language.into();
+    }
+
+    /// Adds a translation of the English string provided to the translation
+    /// table. Any previous translation of the same string is replaced.
+    pub fn add_translation<S, T>(&mut self, source: S, translation: T)
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        self.translations.insert(source.into(), translation.into());
+    }
+
+    /// Removes all the translations from the translation table.
+    pub fn clear_translations(&mut self) {
+        self.translations.clear();
+    }
+
+    /// Translates the English string provided. If there is no translation for
+    /// it, the string itself is returned.
+    pub fn translate<'a>(&'a self, text: &'a str) -> &'a str {
+        self.translations.get(text).map_or(text, String::as_str)
+    }
+
+    /// Translates a label produced by livesplit-core. In addition to what
+    /// [`translate`](Self::translate) does, labels that refer to a comparison
+    /// in parentheses, like `Current Pace (Best Segments)`, get both of their
+    /// parts translated individually if there is no translation for the whole
+    /// label.
+    pub fn translate_label<'a>(&'a self, text: &'a str) -> Cow<'a, str> {
+        if let Some(translation) = self.translations.get(text) {
+            return translation.as_str().into();
+        }
+
+        if let Some((label, comparison)) = text
+            .strip_suffix(')')
+            .and_then(|text| text.split_once(" ("))
+        {
+            let (translated_label, translated_comparison) =
+                (self.translate(label), self.translate(comparison));
+            if translated_label != label || translated_comparison != comparison {
+                return format!("{translated_label} ({translated_comparison})").into();
+            }
+        }
+
+        text.into()
+    }
+
+    /// Returns `true` if the translation table is empty, meaning that no
+    /// string gets translated.
+    pub fn is_empty(&self) -> bool {
+        self.translations.is_empty()
+    }
+
+    /// Decodes a Localization from its JSON representation. The JSON is an
+    /// object with the `language` and the `translations`, which is an object
+    /// mapping the English strings to their translations.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Encodes the Localization as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
@@ -0,0 +1,66 @@
+use super::Localization;
+use crate::{
+    component::key_value,
+    layout::{ComponentState, LayoutState},
+    TimerPhase,
+};
+
+fn german() -> Localization {
+    let mut localization = Localization::new("de");
+    localization.add_translation("Best Segments", "Beste Segmente");
+    localization.add_translation("Previous Segment", "Vorheriges Segment");
+    localization.add_translation("Paused", "Pausiert");
+    localization
+}
+
+#[test]
+fn keeps_unknown_strings() {
+    let localization = german();
+    assert_eq!(localization.translate("Latest Run"), "Latest Run");
+    assert_eq!(
+        localization.translate_label("Sum of Best (PB)"),
+        "Sum of Best (PB)"
+    );
+    assert_eq!(Localization::default().translate("Paused"), "Paused");
+}
+
+#[test]
+fn translates_timer_phases() {
+    let localization = german();
+    assert_eq!(
+        localization.translate(TimerPhase::Paused.name()),
+        "Pausiert"
+    );
+    assert_eq!(
+        localization.translate(TimerPhase::Running.name()),
+        "Running"
+    );
+}
+
+#[test]
+fn localizes_layout_state() {
+    let mut state = LayoutState::default();
+    state
+        .components
+        .push(ComponentState::KeyValue(key_value::State {
+            key: "Previous Segment (Best Segments)".into(),
+            key_abbreviations: vec!["Previous Segment".into()],
+            ..Default::default()
+        }));
+
+    state.localize(&german());
+
+    let ComponentState::KeyValue(state) = &state.components[0] else {
+        unreachable!()
+    };
+    assert_eq!(state.key, "Vorheriges Segment (Beste Segmente)");
+    assert_eq!(state.key_abbreviations, ["Vorheriges Segment"]);
+}
+
+#[test]
+fn roundtrips_through_json() {
+    let localization = german();
+    let decoded = Localization::from_json(&localization.to_json()).unwrap();
+    assert_eq!(decoded, localization);
+    assert_eq!(decoded.language(), "de");
+}
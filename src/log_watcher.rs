@@ -0,0 +1,292 @@
+//! The log_watcher module allows controlling the timer based on the lines a
+//! game writes to a log file. Many games log when they start loading or when
+//! the player enters a new level, so matching these lines with regular
+//! expressions is often enough to split and remove loads without reading the
+//! game's memory. The [`LogWatcher`] can be used on its own or be polled by
+//! an auto splitter.
+//!
+//! The rules are configured per game via JSON:
+//! ```json
+//! {
+//!     "games": [
+//!         {
+//!             "game": "Some Game",
+//!             "path": "C:/Users/Runner/AppData/LocalLow/Some Game/Player.log",
+//!             "rules": [
+//!                 { "pattern": "^Loading level 1$", "action": "Start" },
+//!                 { "pattern": "^Loading level \\d+$", "action": "Split" },
+//!                 { "pattern": "^Begin loading", "action": "PauseGameTime" },
+//!                 { "pattern": "^Finished loading", "action": "ResumeGameTime" }
+//!             ]
+//!         }
+//!     ]
+//! }
+//! ```
+
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::PathBuf,
+};
+
+use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::event::CommandSink;
+
+/// Describes an error that happened when setting up a [`LogWatcher`].
+#[derive(Debug, snafu::Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum Error {
+    /// One of the patterns is not a valid regular expression.
+    InvalidPattern {
+        /// The pattern that is invalid.
+        pattern: String,
+        /// The underlying error.
+        source: regex::Error,
+    },
+}
+
+/// The action that is executed on the timer when a line matches a rule.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    /// Starts the timer.
+    Start,
+    /// Splits.
+    Split,
+    /// Splits or starts the timer if it isn't running yet.
+    SplitOrStart,
+    /// Skips the current split.
+    SkipSplit,
+    /// Undoes the previous split.
+    UndoSplit,
+    /// Resets the timer.
+    Reset,
+    /// Pauses the timer.
+    Pause,
+    /// Resumes the timer.
+    Resume,
+    /// Pauses the game time, which is usually done when the game starts
+    /// loading.
+    PauseGameTime,
+    /// Resumes the game time, which is usually done when the game finished
+    /// loading.
+    ResumeGameTime,
+}
+
+impl Action {
+    /// Executes the action on the timer.
+    pub fn execute<S: CommandSink>(self, timer: &S) {
+        match self {
+            Action::Start => drop(timer.start()),
+            Action::Split => drop(timer.split()),
+            Action::SplitOrStart => drop(timer.split_or_start()),
+            Action::SkipSplit => drop(timer.skip_split()),
+            Action::UndoSplit => drop(timer.undo_split()),
+            Action::Reset => drop(timer.reset(None)),
+            Action::Pause => drop(timer.pause()),
+            Action::Resume => drop(timer.resume()),
+            Action::PauseGameTime => drop(timer.pause_game_time()),
+            Action::ResumeGameTime => drop(timer.resume_game_time()),
+        }
+    }
+}
+
+/// A regular expression and the action to execute when a line matches it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rule {
+    /// The regular expression that the lines are matched against.
+    pub pattern: String,
+    /// The action to execute when a line matches.
+    pub action: Action,
+}
+
+/// The log file of a game and the rules to apply to its lines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameConfig {
+    /// The name of the game.
+    pub game: String,
+    /// The path of the log file.
+    pub path: PathBuf,
+    /// The rules to apply to the lines. Only the first rule that matches a
+    /// line is applied.
+    pub rules: Vec<Rule>,
+}
+
+/// The log watching configurations of all the games.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settings {
+    /// The configurations of the games.
+    pub games: Vec<GameConfig>,
+}
+
+impl Settings {
+    /// Parses the settings from their JSON representation.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Encodes the settings as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// Looks up the configuration of the game with the name provided. The
+    /// names are compared case-insensitively.
+    pub fn for_game(&self, game_name: &str) -> Option<&GameConfig> {
+        self.games
+            .iter()
+            .find(|config| config.game.eq_ignore_ascii_case(game_name.trim()))
+    }
+}
+
+/// Tails a log file and matches the lines that get appended against the
+/// rules of a [`GameConfig`]. Only lines that are written after the watcher
+/// got created are considered. If the file gets truncated or replaced by a
+/// shorter file, which is common when a game starts, it is read from the
+/// beginning again.
+#[derive(Debug)]
+pub struct LogWatcher {
+    path: PathBuf,
+    rules: Vec<(Regex, Action)>,
+    file: Option<File>,
+    position: u64,
+    partial_line: Vec<u8>,
+}
+
+impl LogWatcher {
+    /// Creates a new log watcher for the configuration provided.
+    pub fn new(config: &GameConfig) -> Result<Self, Error> {
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let regex = Regex::new(&rule.pattern).context(InvalidPattern {
+                    pattern: rule.pattern.as_str(),
+                })?;
+                Ok((regex, rule.action))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let position = std::fs::metadata(&config.path).map_or(0, |m| m.len());
+
+        Ok(Self {
+            path: config.path.clone(),
+            rules,
+            file: None,
+            position,
+            partial_line: Vec::new(),
+        })
+    }
+
+    /// Reads the lines that got appended to the log file since the last time
+    /// and returns the actions of the rules that they match. If the log file
+    /// doesn't exist yet, no actions are returned.
+    pub fn poll(&mut self) -> io::Result<Vec<Action>> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            file => match File::open(&self.path) {
+                Ok(opened) => file.insert(opened),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(e),
+            },
+        };
+
+        let len = match std::fs::metadata(&self.path) {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.file = None;
+                self.position = 0;
+                self.partial_line.clear();
+                return Ok(Vec::new());
+            }
+            Err(e) => return Err(e),
+        };
+        if len < self.position {
+            // The file got truncated or replaced, so we need to reopen it.
+            *file = File::open(&self.path)?;
+            self.position = 0;
+            self.partial_line.clear();
+        }
+
+        file.seek(SeekFrom::Start(self.position))?;
+        let mut data = Vec::new();
+        self.position += file.read_to_end(&mut data)? as u64;
+
+        self.partial_line.extend_from_slice(&data);
+        let Some(end) = self.partial_line.iter().rposition(|&b| b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let rest = self.partial_line.split_off(end + 1);
+        let complete = core::mem::replace(&mut self.partial_line, rest);
+
+        Ok(String::from_utf8_lossy(&complete)
+            .lines()
+            .filter_map(|line| {
+                self.rules
+                    .iter()
+                    .find(|(regex, _)| regex.is_match(line))
+                    .map(|&(_, action)| action)
+            })
+            .collect())
+    }
+
+    /// Reads the lines that got appended to the log file since the last time
+    /// and executes the actions of the rules that they match on the timer.
+    pub fn update<S: CommandSink>(&mut self, timer: &S) -> io::Result<()> {
+        for action in self.poll()? {
+            action.execute(timer);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn tails_file() {
+        let path = std::env::temp_dir().join(format!("log-watcher-{}.log", std::process::id()));
+        std::fs::write(&path, "Loading level 1\n").unwrap();
+
+        let settings = Settings::from_json(&format!(
+            r#"{{
+                "games": [
+                    {{
+                        "game": "Some Game",
+                        "path": {:?},
+                        "rules": [
+                            {{ "pattern": "^Loading level 1$", "action": "Start" }},
+                            {{ "pattern": "^Loading level \\d+$", "action": "Split" }}
+                        ]
+                    }}
+                ]
+            }}"#,
+            path.to_str().unwrap(),
+        ))
+        .unwrap();
+        let mut watcher = LogWatcher::new(settings.for_game("some game").unwrap()).unwrap();
+
+        // Lines that were there before are ignored.
+        assert!(watcher.poll().unwrap().is_empty());
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b"Noise\nLoading level 2\nLoading le")
+            .unwrap();
+        assert_eq!(watcher.poll().unwrap(), [Action::Split]);
+        file.write_all(b"vel 3\r\n").unwrap();
+        assert_eq!(watcher.poll().unwrap(), [Action::Split]);
+
+        // The game restarted and created a new log file.
+        std::fs::write(&path, "Loading level 1\n").unwrap();
+        assert_eq!(watcher.poll().unwrap(), [Action::Start]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
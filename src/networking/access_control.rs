@@ -0,0 +1,124 @@
+//! Controls which clients are allowed to control the timer through the
+//! servers. Every client needs to know the token of the server. Browsers
+//! additionally send the origin of the web page that issues the request, which
+//! needs to be allowed explicitly. Otherwise any web page the runner visits
+//! could control the timer through the server running on their machine.
+
+use std::sync::Arc;
+
+/// The settings for who is allowed to control the timer through a server. It
+/// can be cloned to be shared between threads.
+#[derive(Clone, Debug)]
+pub struct AccessControl {
+    token: Arc<str>,
+    allowed_origins: Arc<[Box<str>]>,
+}
+
+impl AccessControl {
+    /// Creates new access control settings where the clients need to provide
+    /// the token specified. The token should be long and random, as anyone who
+    /// knows it can control the timer. No web page is allowed to access the
+    /// server until its origin is allowed via
+    /// [`with_allowed_origins`](Self::with_allowed_origins).
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.into(),
+            allowed_origins: Arc::new([]),
+        }
+    }
+
+    /// Allows the web pages with the origins specified to access the server,
+    /// for example `https://example.com` or `http://localhost:8080`.
+    pub fn with_allowed_origins<I, O>(mut self, origins: I) -> Self
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<Box<str>>,
+    {
+        self.allowed_origins = origins.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Checks whether the client provided the token. The token can either be
+    /// the URL encoded `token` parameter of the query or a bearer token in the
+    /// value of the `Authorization` header.
+    pub fn is_authorized(&self, query: Option<&str>, authorization: Option<&[u8]>) -> bool {
+        let query_token = query
+            .into_iter()
+            .flat_map(|query| query.split('&'))
+            .find_map(|pair| pair.strip_prefix("token="))
+            .and_then(url_decode);
+
+        let header_token = authorization.and_then(|value| value.strip_prefix(b"Bearer "));
+
+        // The tokens are compared in constant time, so the time it takes to
+        // refuse a client doesn't reveal how much of the token is correct.
+        let token = self.token.as_bytes();
+        !token.is_empty()
+            && (query_token.is_some_and(|t| constant_time_eq(&t, token))
+                | header_token.is_some_and(|t| constant_time_eq(t, token)))
+    }
+
+    /// Checks whether the value of the `Origin` header is allowed. Requests
+    /// without an `Origin` header don't come from a web page, so they are
+    /// allowed.
+    pub fn is_origin_allowed(&self, origin: Option<&[u8]>) -> bool {
+        origin.map_or(true, |origin| {
+            self.allowed_origins
+                .iter()
+                .any(|allowed| allowed.as_bytes().eq_ignore_ascii_case(origin))
+        })
+    }
+}
+
+/// Decodes a URL encoded query parameter. Returns [`None`] if it's not encoded
+/// properly.
+fn url_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut bytes = encoded.bytes();
+    while let Some(byte) = bytes.next() {
+        decoded.push(match byte {
+            b'%' => {
+                let high = (bytes.next()? as char).to_digit(16)?;
+                let low = (bytes.next()? as char).to_digit(16)?;
+                (high << 4 | low) as u8
+            }
+            b'+' => b' ',
+            byte => byte,
+        });
+    }
+    Some(decoded)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_decodes_tokens() {
+        assert_eq!(url_decode("a+b%26c%2b").as_deref(), Some(&b"a b&c+"[..]));
+        assert_eq!(url_decode("%2"), None);
+        assert_eq!(url_decode("%zz"), None);
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"tokens"));
+    }
+
+    #[test]
+    fn requires_the_token_and_an_allowed_origin() {
+        let access = AccessControl::new("a b&c").with_allowed_origins(["https://example.com"]);
+        assert!(access.is_authorized(Some("x=1&token=a+b%26c"), None));
+        assert!(access.is_authorized(None, Some(b"Bearer a b&c")));
+        assert!(!access.is_authorized(Some("token=a+b"), Some(b"Bearer a b")));
+        assert!(!access.is_authorized(None, None));
+        assert!(!AccessControl::new("").is_authorized(Some("token="), None));
+
+        assert!(access.is_origin_allowed(None));
+        assert!(access.is_origin_allowed(Some(b"https://example.com")));
+        assert!(!access.is_origin_allowed(Some(b"https://evil.example")));
+        assert!(!access.is_origin_allowed(Some(b"null")));
+    }
+}
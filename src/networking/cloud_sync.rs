@@ -0,0 +1,466 @@
+//! The `cloud_sync` module synchronizes splits between multiple devices through
+//! a storage backend that is accessed via plain HTTP `GET` and `PUT` requests,
+//! such as a WebDAV server or an S3 compatible bucket.
+//!
+//! The backend versions the splits file through its entity tag (`ETag`).
+//! Uploads are conditional on the version that was downloaded, so a device can
+//! never overwrite changes it didn't see. If both the local splits and the
+//! splits stored by the backend changed since the last synchronization, the
+//! two are merged with the splits from the last synchronization as the common
+//! base. This way the attempts of both devices end up in the attempt history.
+//!
+//! The [`SyncState`] needs to be stored alongside the splits, so the changes
+//! can be detected when synchronizing the next time.
+
+use serde_derive::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    comparison::personal_best,
+    run::{parser::livesplit, saver},
+    Attempt, Run, TimingMethod,
+};
+
+/// How often the synchronization is retried when another device uploaded its
+/// changes at the same time.
+const MAX_TRIES: usize = 3;
+
+/// Describes an error that happened when synchronizing the splits.
+#[derive(Debug, snafu::Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum Error {
+    /// Failed to send the request or to receive the response.
+    Request {
+        /// The underlying error.
+        source: reqwest::Error,
+    },
+    /// The response indicated that the request was unsuccessful.
+    Status {
+        /// The HTTP status code of the response.
+        status: u16,
+    },
+    /// The splits stored by the backend changed while uploading the splits.
+    Conflict,
+    /// The splits stored by the backend could not be parsed.
+    Parse {
+        /// The underlying error.
+        source: livesplit::Error,
+    },
+    /// The splits could not be saved.
+    Save,
+    /// The splits can't be merged, because their segments differ.
+    SegmentsDiffer,
+}
+
+/// The result type for synchronizing splits.
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// How to authenticate with the backend.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Auth {
+    /// The backend doesn't require authentication, or the URL itself is
+    /// authenticated, like a presigned URL of an S3 bucket.
+    #[default]
+    None,
+    /// HTTP basic authentication, as commonly used by WebDAV servers.
+    Basic {
+        /// The name of the user.
+        username: String,
+        /// The password of the user.
+        password: String,
+    },
+    /// A bearer token.
+    Bearer(String),
+}
+
+/// A storage backend that stores a single splits file at a URL. It can be
+/// cloned cheaply, as the connections are shared between the clones.
+#[derive(Debug, Clone)]
+pub struct Backend {
+    client: reqwest::Client,
+    url: String,
+    auth: Auth,
+}
+
+/// The splits file as stored by the backend.
+struct Remote {
+    contents: String,
+    etag: Option<String>,
+}
+
+impl Backend {
+    /// Creates a backend that stores the splits file at the URL provided, such
+    /// as `https://dav.example.com/splits/Celeste - Any%.lss`.
+    pub fn new(url: String, auth: Auth) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            auth,
+        }
+    }
+
+    fn authenticate(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.auth {
+            Auth::None => request,
+            Auth::Basic { username, password } => request.basic_auth(username, Some(password)),
+            Auth::Bearer(token) => request.bearer_auth(token),
+        }
+    }
+
+    async fn download(&self) -> Result<Option<Remote>> {
+        let response = self
+            .authenticate(self.client.get(&self.url))
+            .send()
+            .await
+            .context(Request)?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        ensure!(
+            status.is_success(),
+            Status {
+                status: status.as_u16()
+            }
+        );
+        let etag = etag(&response);
+        let contents = response.text().await.context(Request)?;
+        Ok(Some(Remote { contents, etag }))
+    }
+
+    /// Uploads the splits file, as long as the backend still stores the
+    /// version that was downloaded. Returns the version of the uploaded file,
+    /// if the backend reports it.
+    async fn upload(
+        &self,
+        contents: String,
+        downloaded: Option<&Remote>,
+    ) -> Result<Option<String>> {
+        let request = self.authenticate(self.client.put(&self.url));
+        let request = match downloaded {
+            None => request.header(reqwest::header::IF_NONE_MATCH, "*"),
+            Some(Remote {
+                etag: Some(etag), ..
+            }) => request.header(reqwest::header::IF_MATCH, etag),
+            // The backend doesn't version the file, so there's nothing we can
+            // make the upload conditional on.
+            Some(_) => request,
+        };
+        let response = request.body(contents).send().await.context(Request)?;
+        let status = response.status();
+        ensure!(status != reqwest::StatusCode::PRECONDITION_FAILED, Conflict);
+        ensure!(
+            status.is_success(),
+            Status {
+                status: status.as_u16()
+            }
+        );
+        Ok(etag(&response))
+    }
+}
+
+fn etag(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(reqwest::header::ETAG)?
+        .to_str()
+        .ok()
+        .map(Into::into)
+}
+
+/// The state of the last synchronization. It needs to be stored alongside the
+/// splits, so the next synchronization can detect which side changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncState {
+    etag: Option<String>,
+    base: Option<String>,
+}
+
+/// The outcome of a synchronization.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Neither side changed since the last synchronization.
+    UpToDate,
+    /// Only the local splits changed, so they got uploaded.
+    Pushed,
+    /// Only the splits stored by the backend changed, so they replaced the
+    /// local splits.
+    Pulled,
+    /// Both sides changed, so the splits got merged and the merged splits got
+    /// uploaded.
+    Merged,
+}
+
+/// Synchronizes the run with the splits file stored by the backend. If the run
+/// got replaced or merged, it is marked as modified, so the frontend knows it
+/// needs to save it. The run should not be in use by a timer with an active
+/// attempt while synchronizing.
+pub async fn sync(backend: &Backend, run: &mut Run, state: &mut SyncState) -> Result<Outcome> {
+    for _ in 0..MAX_TRIES {
+        let local = save(run)?;
+        let remote = backend.download().await?;
+
+        let (outcome, contents, merged) = match &remote {
+            None => (Outcome::Pushed, local, None),
+            Some(remote) if remote.etag.is_some() && remote.etag == state.etag => {
+                if state.base.as_ref() == Some(&local) {
+                    return Ok(Outcome::UpToDate);
+                }
+                (Outcome::Pushed, local, None)
+            }
+            Some(remote) => {
+                if remote.contents == local {
+                    state.etag.clone_from(&remote.etag);
+                    state.base = Some(local);
+                    return Ok(Outcome::UpToDate);
+                }
+                let remote_run = livesplit::parse(&remote.contents).context(Parse)?;
+                if state.base.as_ref() == Some(&local) {
+                    *run = remote_run;
+                    run.mark_as_modified();
+                    state.etag.clone_from(&remote.etag);
+                    state.base = Some(remote.contents.clone());
+                    return Ok(Outcome::Pulled);
+                }
+                let base = match &state.base {
+                    Some(base) => Some(livesplit::parse(base).context(Parse)?),
+                    None => None,
+                };
+                let merged = merge(base.as_ref(), run, &remote_run)?;
+                (Outcome::Merged, save(&merged)?, Some(merged))
+            }
+        };
+
+        match backend.upload(contents.clone(), remote.as_ref()).await {
+            Ok(etag) => {
+                if let Some(mut merged) = merged {
+                    merged.mark_as_modified();
+                    *run = merged;
+                }
+                state.etag = etag;
+                state.base = Some(contents);
+                return Ok(outcome);
+            }
+            Err(Error::Conflict) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(Error::Conflict)
+}
+
+fn save(run: &Run) -> Result<String> {
+    let mut buf = String::new();
+    saver::livesplit::save_run(run, &mut buf).map_err(|_| Error::Save)?;
+    Ok(buf)
+}
+
+/// Attempts on different devices get different indices when they are merged,
+/// so they are identified by when they happened instead.
+fn is_same_attempt(a: &Attempt, b: &Attempt) -> bool {
+    match (a.started(), b.started()) {
+        (Some(a_started), Some(b_started)) => a_started == b_started && a.ended() == b.ended(),
+        _ => a.index() == b.index() && a.time() == b.time(),
+    }
+}
+
+fn contains_attempt(run: &Run, attempt: &Attempt) -> bool {
+    run.attempt_history()
+        .iter()
+        .any(|a| is_same_attempt(a, attempt))
+}
+
+fn copy_attempt(merged: &mut Run, source: &Run, attempt: &Attempt, index: i32) {
+    merged.add_attempt_with_index(
+        attempt.time(),
+        index,
+        attempt.started(),
+        attempt.ended(),
+        attempt.pause_time(),
+    );
+    for (segment, source) in merged.segments_mut().iter_mut().zip(source.segments()) {
+        if let Some(time) = source.segment_history().get(attempt.index()) {
+            segment.segment_history_mut().insert(index, time);
+        }
+    }
+}
+
+/// Merges the attempt histories of two runs that diverged from a common base.
+/// The attempts that only one of the runs added are kept, while the attempts
+/// that one of the runs removed, for example by clearing the history, are
+/// removed. The attempts of the remote run get new indices after the ones of
+/// the local run. The best segments and the personal best are the better ones
+/// of both runs. Everything else, such as the metadata and the custom
+/// comparisons, is taken from the local run. Without a base, the runs are
+/// assumed to not have removed any attempts.
+pub fn merge(base: Option<&Run>, local: &Run, remote: &Run) -> Result<Run> {
+    ensure!(
+        local.len() == remote.len()
+            && local
+                .segments()
+                .iter()
+                .zip(remote.segments())
+                .all(|(l, r)| l.name() == r.name()),
+        SegmentsDiffer
+    );
+
+    let is_in_base = |attempt: &Attempt| base.is_some_and(|base| contains_attempt(base, attempt));
+
+    let mut merged = local.clone();
+    merged.clear_history();
+
+    // The history elements that don't belong to an attempt are kept.
+    for (segment, local) in merged.segments_mut().iter_mut().zip(local.segments()) {
+        for &(index, time) in local.segment_history() {
+            if index <= 0 {
+                segment.segment_history_mut().insert(index, time);
+            }
+        }
+    }
+
+    for attempt in local.attempt_history() {
+        if contains_attempt(remote, attempt) || !is_in_base(attempt) {
+            copy_attempt(&mut merged, local, attempt, attempt.index());
+        }
+    }
+
+    let mut index = local.max_attempt_history_index().unwrap_or(0).max(0) + 1;
+    for attempt in remote.attempt_history() {
+        if !contains_attempt(local, attempt) && !is_in_base(attempt) {
+            copy_attempt(&mut merged, remote, attempt, index);
+            index += 1;
+        }
+    }
+
+    merged.set_attempt_count(match base {
+        Some(base) => {
+            local.attempt_count() + remote.attempt_count().saturating_sub(base.attempt_count())
+        }
+        None => local.attempt_count().max(remote.attempt_count()),
+    });
+
+    for method in TimingMethod::all() {
+        for (segment, remote) in merged.segments_mut().iter_mut().zip(remote.segments()) {
+            let best = &mut segment.best_segment_time_mut()[method];
+            if let Some(remote) = remote.best_segment_time()[method] {
+                if best.map_or(true, |best| remote < best) {
+                    *best = Some(remote);
+                }
+            }
+        }
+
+        let final_time = |run: &Run| {
+            run.segments()
+                .last()
+                .and_then(|s| s.personal_best_split_time()[method])
+        };
+        if let Some(remote_time) = final_time(remote) {
+            if final_time(local).map_or(true, |local_time| remote_time < local_time) {
+                for (segment, remote) in merged.segments_mut().iter_mut().zip(remote.segments()) {
+                    segment.comparison_mut(personal_best::NAME)[method] =
+                        remote.personal_best_split_time()[method];
+                }
+            }
+        }
+    }
+
+    merged.fix_splits();
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        platform::{DateTime, Duration},
+        AtomicDateTime, Segment, Time, TimeSpan,
+    };
+
+    fn run() -> Run {
+        let mut run = Run::new();
+        run.push_segment(Segment::new("A"));
+        run.push_segment(Segment::new("B"));
+        run
+    }
+
+    fn add_attempt(run: &mut Run, minute: u8, splits: [f64; 2]) {
+        let started = AtomicDateTime::new(
+            DateTime::UNIX_EPOCH + Duration::minutes(minute.into()),
+            true,
+        );
+        run.add_attempt(
+            Time::new().with_real_time(Some(TimeSpan::from_seconds(splits[1]))),
+            Some(started),
+            Some(started),
+            None,
+        );
+        let index = run.max_attempt_history_index().unwrap();
+        let mut previous = 0.0;
+        for (segment, split) in run.segments_mut().iter_mut().zip(splits) {
+            let time = Some(TimeSpan::from_seconds(split - previous));
+            segment
+                .segment_history_mut()
+                .insert(index, Time::new().with_real_time(time));
+            let best = &mut segment.best_segment_time_mut().real_time;
+            if best.map_or(true, |best| time < Some(best)) {
+                *best = time;
+            }
+            previous = split;
+        }
+        run.set_attempt_count(run.attempt_count() + 1);
+    }
+
+    #[test]
+    fn merges_diverged_attempts() {
+        let mut base = run();
+        add_attempt(&mut base, 0, [10.0, 20.0]);
+
+        let mut local = base.clone();
+        add_attempt(&mut local, 1, [9.0, 19.0]);
+
+        let mut remote = base.clone();
+        add_attempt(&mut remote, 2, [8.0, 21.0]);
+        add_attempt(&mut remote, 3, [11.0, 22.0]);
+
+        let merged = merge(Some(&base), &local, &remote).unwrap();
+        assert_eq!(merged.attempt_count(), 4);
+        let indices: Vec<_> = merged
+            .attempt_history()
+            .iter()
+            .map(Attempt::index)
+            .collect();
+        assert_eq!(indices, [1, 2, 3, 4]);
+        assert_eq!(
+            merged
+                .segment(0)
+                .segment_history()
+                .get(3)
+                .unwrap()
+                .real_time,
+            Some(TimeSpan::from_seconds(8.0)),
+        );
+        assert_eq!(
+            merged.segment(0).best_segment_time().real_time,
+            Some(TimeSpan::from_seconds(8.0)),
+        );
+
+        // Merging again doesn't duplicate any attempts.
+        let again = merge(Some(&remote), &merged, &remote).unwrap();
+        assert_eq!(again.attempt_history().len(), 4);
+    }
+
+    #[test]
+    fn keeps_removed_attempts_removed() {
+        let mut base = run();
+        add_attempt(&mut base, 0, [10.0, 20.0]);
+
+        let mut local = base.clone();
+        local.clear_history();
+
+        let mut remote = base.clone();
+        add_attempt(&mut remote, 1, [9.0, 19.0]);
+
+        let merged = merge(Some(&base), &local, &remote).unwrap();
+        assert_eq!(merged.attempt_history().len(), 1);
+        assert_eq!(merged.attempt_history()[0].index(), 1);
+    }
+}
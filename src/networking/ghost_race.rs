@@ -0,0 +1,360 @@
+//! The `ghost_race` module allows multiple timers to race each other over the
+//! network, without having to watch each other's streams. Each racer shares
+//! their splits live, while the splits of the opponents are provided as
+//! comparisons named `[Race]` followed by the name of the opponent. This way
+//! all the components that work with comparisons, such as the splits and the
+//! delta component, can show the pace of the opponents.
+//!
+//! One of the racers hosts the race, while all the others join it via
+//! WebSocket. The host relays the progress of every racer to all the other
+//! racers. Each time the splits of a racer change, their progress is sent as a
+//! text message:
+//! ```json
+//! {
+//!     "runner": "Alice",
+//!     "phase": "Running",
+//!     "splits": [{ "realTime": 83.5, "gameTime": 80.25 }, { "realTime": null, "gameTime": null }]
+//! }
+//! ```
+//!
+//! The times are the split times in seconds.
+
+use std::{
+    collections::BTreeMap,
+    io,
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde_derive::{Deserialize, Serialize};
+use tungstenite::{Message, WebSocket};
+
+use crate::{
+    comparison::{ComparisonGenerator, RACE_COMPARISON_PREFIX},
+    Attempt, Segment, Time, TimeSpan, Timer, TimerPhase,
+};
+
+/// How often the connections check for progress to send while waiting for
+/// messages.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Progress {
+    runner: String,
+    phase: Phase,
+    splits: Vec<SplitTime>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum Phase {
+    NotRunning,
+    Running,
+    Ended,
+    Paused,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SplitTime {
+    real_time: Option<f64>,
+    game_time: Option<f64>,
+}
+
+impl From<SplitTime> for Time {
+    fn from(time: SplitTime) -> Self {
+        Time::new()
+            .with_real_time(time.real_time.map(TimeSpan::from_seconds))
+            .with_game_time(time.game_time.map(TimeSpan::from_seconds))
+    }
+}
+
+type Opponents = Arc<Mutex<BTreeMap<String, Progress>>>;
+
+/// A race against other timers over the network. It can be cloned to be shared
+/// between threads.
+#[derive(Clone)]
+pub struct GhostRace {
+    inner: Arc<Mutex<Inner>>,
+    opponents: Opponents,
+}
+
+struct Inner {
+    runner: String,
+    progress: Option<Progress>,
+    peers: Vec<Sender<String>>,
+    opponents_changed: bool,
+}
+
+/// The Comparison Generator that provides the splits of an opponent as a
+/// comparison. The opponent's splits that are not known yet are empty.
+#[derive(Clone, Debug)]
+pub struct RaceComparison {
+    name: String,
+    runner: String,
+    opponents: Opponents,
+}
+
+impl ComparisonGenerator for RaceComparison {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn generate(&mut self, segments: &mut [Segment], _: &[Attempt]) {
+        let opponents = self.opponents.lock().unwrap();
+        let splits = opponents
+            .get(&self.runner)
+            .map_or(&[][..], |p| &p.splits[..]);
+        for (index, segment) in segments.iter_mut().enumerate() {
+            *segment.comparison_mut(&self.name) = splits
+                .get(index)
+                .copied()
+                .map(Into::into)
+                .unwrap_or_default();
+        }
+    }
+}
+
+impl GhostRace {
+    /// Creates a new race for the runner with the name provided. The name needs
+    /// to be unique among the racers.
+    pub fn new(runner: String) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                runner,
+                progress: None,
+                peers: Vec::new(),
+                opponents_changed: false,
+            })),
+            opponents: Default::default(),
+        }
+    }
+
+    /// Returns the name of the comparison that provides the splits of the
+    /// opponent.
+    pub fn comparison_name(runner: &str) -> String {
+        format!("{RACE_COMPARISON_PREFIX}{runner}")
+    }
+
+    /// Returns the names of the opponents that joined the race so far.
+    pub fn opponents(&self) -> Vec<String> {
+        self.opponents.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Publishes the progress of the timer to the opponents and updates the
+    /// comparisons of the opponents. This is meant to be called regularly, for
+    /// example whenever an [`Event`](crate::event::Event) happened or once per
+    /// frame. The progress is only sent if it actually changed.
+    pub fn update(&self, timer: &mut Timer) {
+        let splits = timer.run().segments().iter().map(|s| {
+            let time = s.split_time();
+            SplitTime {
+                real_time: time.real_time.map(|t| t.total_seconds()),
+                game_time: time.game_time.map(|t| t.total_seconds()),
+            }
+        });
+
+        let mut inner = self.inner.lock().unwrap();
+        let progress = Progress {
+            runner: inner.runner.clone(),
+            phase: match timer.current_phase() {
+                TimerPhase::NotRunning => Phase::NotRunning,
+                TimerPhase::Running => Phase::Running,
+                TimerPhase::Ended => Phase::Ended,
+                TimerPhase::Paused => Phase::Paused,
+            },
+            splits: splits.collect(),
+        };
+        if inner.progress.as_ref() != Some(&progress) {
+            let message = serde_json::to_string(&progress).unwrap();
+            inner.progress = Some(progress);
+            inner.broadcast(message);
+        }
+
+        if !inner.opponents_changed {
+            return;
+        }
+        inner.opponents_changed = false;
+        drop(inner);
+
+        let opponents = self.opponents();
+        let current_generators: Vec<String> = timer
+            .run()
+            .comparison_generators()
+            .iter()
+            .map(|g| g.name().to_owned())
+            .collect();
+        for runner in opponents {
+            let name = Self::comparison_name(&runner);
+            if !current_generators.contains(&name) {
+                timer.set_comparison_generator(Box::new(RaceComparison {
+                    name,
+                    runner,
+                    opponents: self.opponents.clone(),
+                }));
+            }
+        }
+        timer.regenerate_comparisons();
+    }
+
+    /// Removes the comparisons of all the opponents from the timer. This is
+    /// meant to be called when leaving the race.
+    pub fn remove_comparisons(&self, timer: &mut Timer) {
+        for runner in self.opponents() {
+            timer.remove_comparison_generator(&Self::comparison_name(&runner));
+        }
+    }
+
+    fn handle_message(&self, message: &str) -> bool {
+        let Ok(progress) = serde_json::from_str::<Progress>(message) else {
+            return false;
+        };
+        let mut inner = self.inner.lock().unwrap();
+        if progress.runner == inner.runner {
+            return false;
+        }
+        inner.opponents_changed = true;
+        self.opponents
+            .lock()
+            .unwrap()
+            .insert(progress.runner.clone(), progress);
+        true
+    }
+
+    fn subscribe(&self) -> Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(progress) = &inner.progress {
+            let _ = sender.send(serde_json::to_string(progress).unwrap());
+        }
+        for progress in self.opponents.lock().unwrap().values() {
+            let _ = sender.send(serde_json::to_string(progress).unwrap());
+        }
+        inner.peers.push(sender);
+        receiver
+    }
+
+    /// Hosts the race, accepting the other racers on the listener. Each
+    /// connection is handled on its own thread. This blocks the current thread
+    /// until accepting a connection fails.
+    pub fn host(&self, listener: TcpListener) -> io::Result<()> {
+        loop {
+            let (stream, _) = listener.accept()?;
+            let race = self.clone();
+            thread::spawn(move || -> tungstenite::Result<()> {
+                let socket = tungstenite::accept(stream).map_err(|e| match e {
+                    tungstenite::HandshakeError::Failure(e) => e,
+                    tungstenite::HandshakeError::Interrupted(_) => {
+                        tungstenite::Error::Io(io::ErrorKind::WouldBlock.into())
+                    }
+                })?;
+                race.run_connection(socket, true)
+            });
+        }
+    }
+
+    /// Joins the race hosted at the URL provided, such as
+    /// `ws://192.168.0.10:8080`. This blocks the current thread until the
+    /// connection gets closed.
+    pub fn join(&self, url: &str) -> tungstenite::Result<()> {
+        let (socket, _) = tungstenite::connect(url)?;
+        self.run_connection(socket, false)
+    }
+
+    fn run_connection<S>(&self, mut socket: WebSocket<S>, relay: bool) -> tungstenite::Result<()>
+    where
+        S: io::Read + io::Write + ReadTimeout,
+    {
+        socket.get_ref().set_poll_timeout()?;
+        let outgoing = self.subscribe();
+
+        loop {
+            for message in outgoing.try_iter() {
+                socket.send(Message::text(message))?;
+            }
+
+            match socket.read() {
+                Ok(message) if message.is_text() => {
+                    let message = message.to_text()?;
+                    if self.handle_message(message) && relay {
+                        // The racer that sent the progress doesn't need it
+                        // back, but it also ignores its own progress anyway.
+                        self.inner.lock().unwrap().broadcast(message.to_owned());
+                    }
+                }
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e))
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) => {}
+                Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Inner {
+    fn broadcast(&mut self, message: String) {
+        // Peers that disconnected dropped their receiver.
+        self.peers.retain(|peer| peer.send(message.clone()).is_ok());
+    }
+}
+
+trait ReadTimeout {
+    fn set_poll_timeout(&self) -> io::Result<()>;
+}
+
+impl ReadTimeout for TcpStream {
+    fn set_poll_timeout(&self) -> io::Result<()> {
+        self.set_read_timeout(Some(POLL_INTERVAL))
+    }
+}
+
+impl ReadTimeout for tungstenite::stream::MaybeTlsStream<TcpStream> {
+    fn set_poll_timeout(&self) -> io::Result<()> {
+        match self {
+            Self::Plain(stream) => stream.set_poll_timeout(),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::{create_timer, span};
+
+    #[test]
+    fn opponent_comparison() {
+        let mut timer = create_timer(&["A", "B"]);
+        let race = GhostRace::new("Alice".into());
+
+        assert!(race.handle_message(
+            r#"{
+                "runner": "Bob",
+                "phase": "Running",
+                "splits": [{ "realTime": 12.5, "gameTime": null }, { "realTime": null, "gameTime": null }]
+            }"#
+        ));
+        assert!(!race.handle_message(r#"{ "runner": "Alice", "phase": "Running", "splits": [] }"#));
+        race.update(&mut timer);
+
+        let name = GhostRace::comparison_name("Bob");
+        assert_eq!(name, "[Race]Bob");
+        assert!(timer.run().comparisons().any(|c| c == name));
+        assert_eq!(
+            timer.run().segment(0).comparison(&name).real_time,
+            Some(span(12.5)),
+        );
+        assert_eq!(timer.run().segment(1).comparison(&name).real_time, None);
+
+        race.remove_comparisons(&mut timer);
+        assert!(!timer.run().comparisons().any(|c| c == name));
+    }
+}
@@ -0,0 +1,447 @@
+//! The HTTP server is a tiny server that allows OBS browser sources and
+//! scripts to consume and control the timer without any bindings to the
+//! library. It provides the following endpoints:
+//!
+//! - `GET /state`: The state of the timer and the layout as JSON:
+//!   ```json
+//!   { "timer": { "phase": "Running", ... }, "layout": { "components": [...], ... } }
+//!   ```
+//!   The layout is `null` until the frontend publishes its state.
+//! - `GET /state/timer`: Only the state of the timer.
+//! - `GET /state/layout`: Only the state of the layout.
+//! - `POST /command`: Executes a command of the
+//!   [`server_protocol`](super::server_protocol) that is sent as the body of
+//!   the request. The response of the command is sent back as the body of the
+//!   response. The body either needs a `Content-Length` or needs to be sent
+//!   with the `chunked` transfer encoding.
+//!
+//! The state endpoints allow cross origin requests, so they can be used from
+//! any web page. Commands on the other hand need the token of the server,
+//! either as a URL encoded `token` query parameter or as a bearer token in the
+//! `Authorization` header. If they are sent by a web page, its origin also
+//! needs to be allowed by the [`AccessControl`].
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde_json::Value;
+
+use super::{access_control::AccessControl, block_on, server_protocol, timer_state};
+use crate::{
+    event::{CommandSink, TimerQuery},
+    layout::LayoutState,
+};
+
+/// The maximum size of a request body that is accepted.
+const MAX_BODY_LEN: usize = 64 << 10;
+/// The maximum length of the request line and each header line.
+const MAX_LINE_LEN: usize = 8 << 10;
+/// The maximum amount of header lines of a request.
+const MAX_HEADERS: usize = 64;
+/// The maximum amount of connections that are handled at the same time.
+/// Further connections are closed right away.
+const MAX_CONNECTIONS: usize = 32;
+/// How long reading or writing may block before the connection is closed.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Caches the state of the layout, so the server can provide it. The frontend
+/// is expected to publish the state whenever it updates it, for example once
+/// per frame. It can be cloned to be shared between threads.
+#[derive(Clone, Default)]
+pub struct LayoutStateCache {
+    state: Arc<Mutex<Value>>,
+}
+
+impl LayoutStateCache {
+    /// Creates a new cache that doesn't have a state yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes the current state of the layout.
+    pub fn publish_layout_state(&self, state: &LayoutState) {
+        if let Ok(state) = serde_json::to_value(state) {
+            *self.state.lock().unwrap() = state;
+        }
+    }
+}
+
+struct Response {
+    status: &'static str,
+    body: String,
+    /// The value of the `Access-Control-Allow-Origin` header, if any.
+    allow_origin: Option<String>,
+}
+
+impl Response {
+    const fn json(body: String) -> Self {
+        Self {
+            status: "200 OK",
+            body,
+            allow_origin: None,
+        }
+    }
+
+    const fn error(status: &'static str) -> Self {
+        Self {
+            status,
+            body: String::new(),
+            allow_origin: None,
+        }
+    }
+}
+
+/// Decrements the amount of active connections when the connection is done.
+struct ConnectionGuard(Arc<AtomicUsize>);
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Accepts connections on the listener and handles the requests sent through
+/// them. Each connection is handled on its own thread, but only a limited
+/// amount of connections are handled at the same time. Commands are only
+/// accepted from the clients that are allowed by the access control. This
+/// blocks the current thread until accepting a connection fails.
+pub fn serve<S>(
+    listener: TcpListener,
+    command_sink: S,
+    layout: LayoutStateCache,
+    access: AccessControl,
+) -> io::Result<()>
+where
+    S: CommandSink + TimerQuery + Clone + Send + 'static,
+{
+    let connections = Arc::new(AtomicUsize::new(0));
+    loop {
+        let (stream, _) = listener.accept()?;
+        if connections.fetch_add(1, Ordering::Relaxed) >= MAX_CONNECTIONS {
+            connections.fetch_sub(1, Ordering::Relaxed);
+            continue;
+        }
+        let guard = ConnectionGuard(connections.clone());
+        let (command_sink, layout, access) = (command_sink.clone(), layout.clone(), access.clone());
+        thread::spawn(move || {
+            let _guard = guard;
+            serve_connection(stream, &command_sink, &layout, &access)
+        });
+    }
+}
+
+fn serve_connection<S: CommandSink + TimerQuery>(
+    stream: TcpStream,
+    command_sink: &S,
+    layout: &LayoutStateCache,
+    access: &AccessControl,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let response = handle_request(&mut reader, command_sink, layout, access)?;
+    write_response(&mut writer, response)
+}
+
+fn handle_request<S: CommandSink + TimerQuery>(
+    reader: &mut impl BufRead,
+    command_sink: &S,
+    layout: &LayoutStateCache,
+    access: &AccessControl,
+) -> io::Result<Response> {
+    let request_line = match read_line(reader)? {
+        Ok(line) => line,
+        Err(response) => return Ok(response),
+    };
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(Response::error("400 Bad Request"));
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (target, None),
+    };
+
+    let (mut content_length, mut is_chunked) = (None, false);
+    let (mut origin, mut authorization) = (None, None);
+    for header_count in 0.. {
+        if header_count == MAX_HEADERS {
+            return Ok(Response::error("431 Request Header Fields Too Large"));
+        }
+        let header = match read_line(reader)? {
+            Ok(line) => line,
+            Err(response) => return Ok(response),
+        };
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.parse().ok();
+            } else if name.eq_ignore_ascii_case("Transfer-Encoding") {
+                is_chunked = value
+                    .split(',')
+                    .any(|coding| coding.trim().eq_ignore_ascii_case("chunked"));
+            } else if name.eq_ignore_ascii_case("Origin") {
+                origin = Some(value.to_owned());
+            } else if name.eq_ignore_ascii_case("Authorization") {
+                authorization = Some(value.to_owned());
+            }
+        }
+    }
+
+    if path == "/command" {
+        // Only the allowed origins may send commands from a web page, so
+        // wildcard cross origin requests are never allowed here.
+        if !access.is_origin_allowed(origin.as_deref().map(str::as_bytes)) {
+            return Ok(Response::error("403 Forbidden"));
+        }
+        let mut response = match method {
+            "OPTIONS" => Response::json(String::new()),
+            "POST" => {
+                if access.is_authorized(query, authorization.as_deref().map(str::as_bytes)) {
+                    match read_body(reader, content_length, is_chunked)? {
+                        Ok(body) => match String::from_utf8(body) {
+                            Ok(command) => Response::json(block_on(
+                                server_protocol::handle_command(&command, command_sink),
+                            )),
+                            Err(_) => Response::error("400 Bad Request"),
+                        },
+                        Err(response) => response,
+                    }
+                } else {
+                    Response::error("401 Unauthorized")
+                }
+            }
+            _ => Response::error("405 Method Not Allowed"),
+        };
+        response.allow_origin = origin;
+        return Ok(response);
+    }
+
+    let mut response = match (method, path) {
+        ("OPTIONS", _) => Response::json(String::new()),
+        ("GET", "/state") => {
+            let timer = timer_state(&command_sink.get_timer());
+            let layout = layout.state.lock().unwrap().clone();
+            Response::json(serde_json::json!({ "timer": timer, "layout": layout }).to_string())
+        }
+        ("GET", "/state/timer") => {
+            Response::json(timer_state(&command_sink.get_timer()).to_string())
+        }
+        ("GET", "/state/layout") => Response::json(layout.state.lock().unwrap().to_string()),
+        (_, "/state" | "/state/timer" | "/state/layout") => {
+            Response::error("405 Method Not Allowed")
+        }
+        _ => Response::error("404 Not Found"),
+    };
+    response.allow_origin = Some("*".into());
+    Ok(response)
+}
+
+/// Reads a single line of the request. If the line is too long, the response
+/// to send instead is returned.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Result<String, Response>> {
+    let mut line = String::new();
+    reader
+        .by_ref()
+        .take(MAX_LINE_LEN as u64)
+        .read_line(&mut line)?;
+    Ok(if line.len() == MAX_LINE_LEN && !line.ends_with('\n') {
+        Err(Response::error("431 Request Header Fields Too Large"))
+    } else {
+        Ok(line)
+    })
+}
+
+/// Reads the body of a request. If the body can't be accepted, the response
+/// to send instead is returned.
+fn read_body(
+    reader: &mut impl BufRead,
+    content_length: Option<usize>,
+    is_chunked: bool,
+) -> io::Result<Result<Vec<u8>, Response>> {
+    if is_chunked {
+        return read_chunked_body(reader);
+    }
+    Ok(match content_length {
+        None => Err(Response::error("411 Length Required")),
+        Some(len) if len > MAX_BODY_LEN => Err(Response::error("413 Payload Too Large")),
+        Some(len) => {
+            let mut body = vec![0; len];
+            reader.read_exact(&mut body)?;
+            Ok(body)
+        }
+    })
+}
+
+fn read_chunked_body(reader: &mut impl BufRead) -> io::Result<Result<Vec<u8>, Response>> {
+    let mut body = Vec::new();
+    loop {
+        let mut line = match read_line(reader)? {
+            Ok(line) => line,
+            Err(_) => return Ok(Err(Response::error("400 Bad Request"))),
+        };
+        // Chunk extensions are separated by a semicolon and get ignored.
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let Ok(size) = usize::from_str_radix(size, 16) else {
+            return Ok(Err(Response::error("400 Bad Request")));
+        };
+        if size == 0 {
+            break;
+        }
+        if size > MAX_BODY_LEN - body.len() {
+            return Ok(Err(Response::error("413 Payload Too Large")));
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        line.clear();
+        reader
+            .by_ref()
+            .take(MAX_LINE_LEN as u64)
+            .read_line(&mut line)?;
+    }
+
+    // The trailer fields are ignored.
+    for _ in 0..MAX_HEADERS {
+        match read_line(reader)? {
+            Ok(line) if line.trim_end().is_empty() => return Ok(Ok(body)),
+            Ok(_) => {}
+            Err(response) => return Ok(Err(response)),
+        }
+    }
+    Ok(Err(Response::error("431 Request Header Fields Too Large")))
+}
+
+fn write_response(writer: &mut TcpStream, response: Response) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\n\
+        Content-Type: application/json\r\n\
+        Content-Length: {}\r\n",
+        response.status,
+        response.body.len(),
+    )?;
+    if let Some(origin) = &response.allow_origin {
+        write!(
+            writer,
+            "Access-Control-Allow-Origin: {origin}\r\n\
+            Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+            Access-Control-Allow-Headers: Authorization, Content-Type\r\n\
+            Vary: Origin\r\n",
+        )?;
+    }
+    writer.write_all(b"Connection: close\r\n\r\n")?;
+    writer.write_all(response.body.as_bytes())?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::create_timer;
+    use std::io::Read;
+
+    fn request(address: std::net::SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_requests_over_a_loopback_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let timer = create_timer(&["A", "B"]).into_shared();
+        {
+            let timer = timer.clone();
+            let access = AccessControl::new("secret").with_allowed_origins(["https://example.com"]);
+            thread::spawn(move || serve(listener, timer, LayoutStateCache::new(), access));
+        }
+
+        let response = request(address, "GET /state/timer HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(r#""phase":"NotRunning""#));
+
+        assert!(response.contains("Access-Control-Allow-Origin: *\r\n"));
+
+        let response = request(address, "GET /unknown HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        let response = request(address, &format!("GET /{}", "a".repeat(MAX_LINE_LEN - 5)));
+        assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"));
+
+        let response = request(address, "POST /command HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+        assert!(!response.contains("Access-Control-Allow-Origin"));
+
+        let response = request(
+            address,
+            "POST /command?token=secret HTTP/1.1\r\nOrigin: https://evil.example\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden\r\n"));
+        assert!(!response.contains("Access-Control-Allow-Origin"));
+
+        let response = request(
+            address,
+            "OPTIONS /command HTTP/1.1\r\nOrigin: https://example.com\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Access-Control-Allow-Origin: https://example.com\r\n"));
+
+        let response = request(address, "POST /command?token=secret HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 411 Length Required\r\n"));
+
+        let response = request(
+            address,
+            "POST /command HTTP/1.1\r\nAuthorization: Bearer secret\r\n\
+            Content-Length: 1000000\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+
+        let command = r#"{ "command": "splitOrStart" }"#;
+        let response = request(
+            address,
+            &format!(
+                "POST /command HTTP/1.1\r\nauthorization: Bearer secret\r\n\
+                content-length: {}\r\n\r\n{command}",
+                command.len(),
+            ),
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(r#""success""#));
+        assert_eq!(timer.read().unwrap().current_split_index(), Some(0));
+
+        let response = request(
+            address,
+            "POST /command?token=secret HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n\
+            a;ext=1\r\n{ \"command\r\n\
+            10\r\n\": \"splitOrStart\r\n\
+            3\r\n\" }\r\n\
+            0\r\nTrailer: ignored\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(r#""success""#));
+        assert_eq!(timer.read().unwrap().current_split_index(), Some(1));
+
+        let response = request(
+            address,
+            "POST /command?token=secret HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    }
+}
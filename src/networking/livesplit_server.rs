@@ -0,0 +1,252 @@
+//! The LiveSplit Server protocol is the simple text based protocol that the
+//! LiveSplit Server component speaks. A lot of tools, such as auto splitters,
+//! stream bots and input displays, use it to remotely control the timer. This
+//! module implements the protocol, so these tools work with any frontend that
+//! is based on livesplit-core.
+//!
+//! Each command is sent as a single line of text, with the arguments separated
+//! by spaces:
+//! ```text
+//! starttimer
+//! setgametime 1:23:45.67
+//! getcurrenttime
+//! ```
+//!
+//! Only the commands that query information from the timer receive a
+//! response, which is again a single line of text. Times are formatted as
+//! `hh:mm:ss.fffffffff` and a missing time is indicated by a `-`. Commands
+//! that fail are silently ignored, just like in the original implementation.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+use super::block_on;
+use crate::{
+    analysis,
+    comparison::best_segments,
+    event::{CommandSink, TimerQuery},
+    timing::formatter::{self, TimeFormatter, ASCII_MINUS},
+    TimeSpan, TimerPhase, TimingMethod,
+};
+
+/// The port that the LiveSplit Server component listens on by default.
+pub const DEFAULT_PORT: u16 = 16834;
+
+/// Handles an incoming command and returns the response to be sent, if the
+/// command has one.
+pub async fn handle_command<S: CommandSink + TimerQuery>(
+    command: &str,
+    command_sink: &S,
+) -> Option<String> {
+    let (name, argument) = match command.trim().split_once(' ') {
+        Some((name, argument)) => (name, Some(argument.trim())),
+        None => (command.trim(), None),
+    };
+
+    // The results of the commands are ignored, as the protocol has no way of
+    // reporting errors.
+    match name {
+        "starttimer" => drop(command_sink.start().await),
+        "startorsplit" => drop(command_sink.split_or_start().await),
+        "split" => drop(command_sink.split().await),
+        "unsplit" => drop(command_sink.undo_split().await),
+        "skipsplit" => drop(command_sink.skip_split().await),
+        "pause" => drop(command_sink.pause().await),
+        "resume" => drop(command_sink.resume().await),
+        "reset" => drop(command_sink.reset(None).await),
+        "initgametime" => drop(command_sink.initialize_game_time().await),
+        "setgametime" => {
+            if let Some(time) = argument.and_then(|a| a.parse::<TimeSpan>().ok()) {
+                let _ = command_sink.set_game_time(time).await;
+            }
+        }
+        "setloadingtimes" => {
+            if let Some(time) = argument.and_then(|a| a.parse::<TimeSpan>().ok()) {
+                let _ = command_sink.set_loading_times(time).await;
+            }
+        }
+        "pausegametime" => drop(command_sink.pause_game_time().await),
+        "unpausegametime" => drop(command_sink.resume_game_time().await),
+        "setcomparison" => {
+            if let Some(comparison) = argument {
+                let _ = command_sink.set_current_comparison(comparison.into()).await;
+            }
+        }
+        "switchto" => {
+            let timing_method = match argument {
+                Some("realtime") => TimingMethod::RealTime,
+                Some("gametime") => TimingMethod::GameTime,
+                _ => return None,
+            };
+            let _ = command_sink.set_current_timing_method(timing_method).await;
+        }
+        "ping" => return Some("pong".into()),
+        _ => return query(name, argument, command_sink),
+    }
+
+    None
+}
+
+fn query<S: TimerQuery>(name: &str, argument: Option<&str>, command_sink: &S) -> Option<String> {
+    let guard = command_sink.get_timer();
+    let timer = &*guard;
+    let snapshot = timer.snapshot();
+    let method = timer.current_timing_method();
+    let phase = timer.current_phase();
+    let segments = timer.run().segments();
+    let comparison = argument.unwrap_or(timer.current_comparison());
+
+    Some(match name {
+        "getdelta" => {
+            let (delta, _) = analysis::delta::calculate(&snapshot, comparison);
+            match delta {
+                Some(delta) if delta >= TimeSpan::zero() => format!("+{}", format_time(delta)),
+                delta => format_optional_time(delta),
+            }
+        }
+        "getlastsplittime" => {
+            let index = match phase {
+                TimerPhase::Ended => segments.len(),
+                _ => timer.current_split_index()?,
+            };
+            let time = index
+                .checked_sub(1)
+                .and_then(|i| segments[i].split_time()[method]);
+            format_optional_time(time)
+        }
+        "getcomparisonsplittime" => {
+            let time = timer
+                .current_split()
+                .and_then(|s| s.comparison(comparison)[method]);
+            format_optional_time(time)
+        }
+        "getcurrenttime" => format_optional_time(snapshot.current_time()[method]),
+        "getcurrentrealtime" => format_optional_time(snapshot.current_time().real_time),
+        "getcurrentgametime" => format_optional_time(snapshot.current_time().game_time),
+        "getfinaltime" => {
+            let last_segment = segments.last()?;
+            let time = if phase == TimerPhase::Ended {
+                last_segment.split_time()[method]
+            } else {
+                last_segment.comparison(comparison)[method]
+            };
+            format_optional_time(time)
+        }
+        "getpredictedtime" => {
+            format_optional_time(analysis::current_pace::calculate(&snapshot, comparison).0)
+        }
+        "getbestpossibletime" => format_optional_time(
+            analysis::current_pace::calculate(&snapshot, best_segments::NAME).0,
+        ),
+        "getsplitindex" => timer
+            .current_split_index()
+            .map_or(-1, |i| i as isize)
+            .to_string(),
+        "getcurrentsplitname" => timer.current_split()?.name().into(),
+        "getprevioussplitname" => {
+            let index = match phase {
+                TimerPhase::Ended => segments.len(),
+                _ => timer.current_split_index()?,
+            };
+            segments[index.checked_sub(1)?].name().into()
+        }
+        "getcurrenttimerphase" => match phase {
+            TimerPhase::NotRunning => "NotRunning",
+            TimerPhase::Running => "Running",
+            TimerPhase::Ended => "Ended",
+            TimerPhase::Paused => "Paused",
+        }
+        .into(),
+        "getattemptcount" => timer.run().attempt_count().to_string(),
+        "getcompletedcount" => timer
+            .run()
+            .attempt_history()
+            .iter()
+            .filter(|a| a.time()[method].is_some())
+            .count()
+            .to_string(),
+        _ => return None,
+    })
+}
+
+fn format_time(time: TimeSpan) -> String {
+    formatter::Complete::new().format(time).to_string()
+}
+
+fn format_optional_time(time: Option<TimeSpan>) -> String {
+    formatter::none_wrapper::NoneWrapper::new(formatter::Complete::new(), ASCII_MINUS)
+        .format(time)
+        .to_string()
+}
+
+/// Accepts connections on the listener and handles the commands sent through
+/// them. Each connection is handled on its own thread. This blocks the current
+/// thread until accepting a connection fails.
+pub fn serve<S>(listener: TcpListener, command_sink: S) -> io::Result<()>
+where
+    S: CommandSink + TimerQuery + Clone + Send + 'static,
+{
+    loop {
+        let (stream, _) = listener.accept()?;
+        let command_sink = command_sink.clone();
+        thread::spawn(move || serve_connection(stream, &command_sink));
+    }
+}
+
+/// Handles the commands sent through a single connection until it gets
+/// closed.
+pub fn serve_connection<S: CommandSink + TimerQuery>(
+    stream: TcpStream,
+    command_sink: &S,
+) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        if let Some(response) = block_on(handle_command(&line?, command_sink)) {
+            writer.write_all(response.as_bytes())?;
+            writer.write_all(b"\r\n")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::create_timer;
+
+    fn send(command: &str, timer: &crate::SharedTimer) -> Option<String> {
+        block_on(handle_command(command, timer))
+    }
+
+    #[test]
+    fn commands() {
+        let timer = create_timer(&["A", "B"]).into_shared();
+
+        assert_eq!(send("ping", &timer).as_deref(), Some("pong"));
+        assert_eq!(
+            send("getcurrenttimerphase", &timer).as_deref(),
+            Some("NotRunning"),
+        );
+        assert_eq!(send("getsplitindex", &timer).as_deref(), Some("-1"));
+
+        assert_eq!(send("starttimer", &timer), None);
+        assert_eq!(send("initgametime", &timer), None);
+        assert_eq!(send("pausegametime", &timer), None);
+        assert_eq!(send("setgametime 1:23.5", &timer), None);
+        assert_eq!(
+            send("getcurrentgametime", &timer).as_deref(),
+            Some("00:01:23.500000000"),
+        );
+
+        assert_eq!(send("split", &timer), None);
+        assert_eq!(send("getsplitindex", &timer).as_deref(), Some("1"));
+        assert_eq!(send("getcurrentsplitname", &timer).as_deref(), Some("B"));
+        assert_eq!(send("getprevioussplitname", &timer).as_deref(), Some("A"));
+        assert_eq!(send("getcomparisonsplittime", &timer).as_deref(), Some("-"));
+
+        assert_eq!(send("unknowncommand", &timer), None);
+    }
+}
@@ -0,0 +1,323 @@
+//! The `metrics` module exports the events of the timer and counters about the
+//! grind, such as the attempts that were started and how often each segment
+//! got reset, so they can be tracked in external dashboards. The counters can
+//! be exposed in the text format of [Prometheus](https://prometheus.io), while
+//! the individual events can be sent to a [StatsD](https://github.com/statsd/statsd)
+//! server or appended to a file as JSON Lines:
+//! ```json
+//! {"timestamp":1700000000.25,"event":"Splitted","game":"Celeste","category":"Any%","attempt":42,"segment":"Forsaken City","isGold":true,"isPersonalBest":false}
+//! ```
+
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    net::{ToSocketAddrs, UdpSocket},
+    time::Instant,
+};
+
+use serde_derive::Serialize;
+
+use crate::{
+    analysis,
+    event::Event,
+    platform::{utc_now, DateTime},
+    Timer, TimerPhase,
+};
+
+/// The counters about the timer's events since the metrics were created.
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    session_start: Instant,
+    events: u64,
+    attempts_started: u64,
+    attempts_finished: u64,
+    personal_bests: u64,
+    golds: u64,
+    resets: Vec<(String, u64)>,
+    split_index: Option<usize>,
+}
+
+/// A single event that happened to the timer, including the context it
+/// happened in.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Record {
+    /// The point in time the event happened at as seconds since the Unix
+    /// epoch.
+    pub timestamp: f64,
+    /// The event that happened.
+    pub event: Event,
+    /// The name of the game that is being run.
+    pub game: String,
+    /// The name of the category that is being run.
+    pub category: String,
+    /// The number of the attempt the event happened in.
+    pub attempt: u32,
+    /// The name of the segment the event is about, if it is about a segment,
+    /// such as the segment that got split or reset in.
+    pub segment: Option<String>,
+    /// Whether a split resulted in a new best segment.
+    pub is_gold: bool,
+    /// Whether finishing the run resulted in a new personal best.
+    pub is_personal_best: bool,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            session_start: Instant::now(),
+            events: 0,
+            attempts_started: 0,
+            attempts_finished: 0,
+            personal_bests: 0,
+            golds: 0,
+            resets: Vec::new(),
+            split_index: None,
+        }
+    }
+}
+
+impl Metrics {
+    /// Creates new metrics. The session starts right now.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an event that happened to the timer and returns the record of
+    /// it. This is meant to be called by the [`CommandSink`](crate::event::CommandSink)
+    /// of the frontend for every event, as the resets are attributed to the
+    /// segment that was active before the reset. The timer needs to be in the
+    /// state right after the event.
+    pub fn record(&mut self, event: Event, timer: &Timer) -> Record {
+        let run = timer.run();
+        let method = timer.current_timing_method();
+        let mut record = Record {
+            timestamp: (utc_now() - DateTime::UNIX_EPOCH).as_seconds_f64(),
+            event,
+            game: run.game_name().to_owned(),
+            category: run.category_name().to_owned(),
+            attempt: run.attempt_count(),
+            segment: None,
+            is_gold: false,
+            is_personal_best: false,
+        };
+        self.events += 1;
+
+        match event {
+            Event::Started => self.attempts_started += 1,
+            Event::Splitted | Event::Finished => {
+                let index = match timer.current_phase() {
+                    TimerPhase::Ended => Some(run.len() - 1),
+                    _ => timer.current_split_index().and_then(|i| i.checked_sub(1)),
+                };
+                if let Some(index) = index {
+                    record.segment = Some(run.segment(index).name().to_owned());
+                    record.is_gold = analysis::check_best_segment(timer, index, method);
+                    self.golds += u64::from(record.is_gold);
+                }
+                if event == Event::Finished {
+                    let last_segment = run.segment(run.len() - 1);
+                    record.is_personal_best =
+                        last_segment.split_time()[method].is_some_and(|time| {
+                            last_segment.personal_best_split_time()[method]
+                                .map_or(true, |pb| time < pb)
+                        });
+                    self.attempts_finished += 1;
+                    self.personal_bests += u64::from(record.is_personal_best);
+                }
+            }
+            Event::Reset => {
+                // The attempt already got reset, so the segment is the one
+                // that was active before.
+                if let Some(segment) = self.split_index.and_then(|i| run.segments().get(i)) {
+                    let name = segment.name();
+                    record.segment = Some(name.to_owned());
+                    match self.resets.iter_mut().find(|(n, _)| n == name) {
+                        Some((_, count)) => *count += 1,
+                        None => self.resets.push((name.to_owned(), 1)),
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        self.split_index = timer.current_split_index();
+        record
+    }
+
+    /// Formats the counters in the text format of Prometheus, so they can be
+    /// served to Prometheus on an HTTP endpoint.
+    pub fn to_prometheus(&self) -> String {
+        let mut text = String::new();
+        let counters = [
+            (
+                "livesplit_events_total",
+                "The number of events that happened to the timer.",
+                self.events,
+            ),
+            (
+                "livesplit_attempts_started_total",
+                "The number of attempts that were started.",
+                self.attempts_started,
+            ),
+            (
+                "livesplit_attempts_finished_total",
+                "The number of attempts that were finished.",
+                self.attempts_finished,
+            ),
+            (
+                "livesplit_personal_bests_total",
+                "The number of personal bests that were achieved.",
+                self.personal_bests,
+            ),
+            (
+                "livesplit_golds_total",
+                "The number of best segments that were achieved.",
+                self.golds,
+            ),
+        ];
+        for (name, help, value) in counters {
+            let _ = write!(
+                text,
+                "# HELP {name} {help}\n# TYPE {name} counter\n{name} {value}\n"
+            );
+        }
+
+        text.push_str(
+            "# HELP livesplit_resets_total The number of attempts that were reset in each segment.\n\
+            # TYPE livesplit_resets_total counter\n",
+        );
+        for (segment, count) in &self.resets {
+            text.push_str("livesplit_resets_total{segment=\"");
+            for c in segment.chars() {
+                match c {
+                    '\\' => text.push_str(r"\\"),
+                    '"' => text.push_str("\\\""),
+                    '\n' => text.push_str(r"\n"),
+                    c => text.push(c),
+                }
+            }
+            let _ = writeln!(text, "\"}} {count}");
+        }
+
+        let _ = write!(
+            text,
+            "# HELP livesplit_session_seconds The duration of the session so far.\n\
+            # TYPE livesplit_session_seconds gauge\n\
+            livesplit_session_seconds {}\n",
+            self.session_start.elapsed().as_secs_f64(),
+        );
+
+        text
+    }
+}
+
+impl Record {
+    /// Formats the record as a single line of JSON, including the line
+    /// terminator.
+    pub fn to_json_line(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap();
+        line.push('\n');
+        line
+    }
+
+    /// Appends the record as a single line of JSON to the writer.
+    pub fn write_json_line<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_json_line().as_bytes())
+    }
+
+    /// Formats the counters that the record increments in the StatsD format,
+    /// each on its own line. The names of the counters start with the prefix
+    /// provided, such as `livesplit`.
+    pub fn to_statsd(&self, prefix: &str) -> String {
+        let mut lines = String::new();
+        let mut increment = |name: &str| {
+            let _ = writeln!(lines, "{prefix}.{name}:1|c");
+        };
+
+        let event = format!("{:?}", self.event).to_lowercase();
+        increment(&format!("events.{event}"));
+        match self.event {
+            Event::Started => increment("attempts_started"),
+            Event::Finished => increment("attempts_finished"),
+            _ => {}
+        }
+        if self.is_gold {
+            increment("golds");
+        }
+        if self.is_personal_best {
+            increment("personal_bests");
+        }
+        if let (Event::Reset, Some(segment)) = (self.event, &self.segment) {
+            // StatsD doesn't support labels, so the segment becomes part of
+            // the name.
+            let segment: String = segment
+                .chars()
+                .map(|c| {
+                    if c.is_alphanumeric() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            increment(&format!("resets.{segment}"));
+        }
+
+        lines
+    }
+}
+
+/// Sends the records to a StatsD server via UDP.
+#[derive(Debug)]
+pub struct StatsdClient {
+    socket: UdpSocket,
+    prefix: String,
+}
+
+impl StatsdClient {
+    /// Creates a client that sends the records to the StatsD server at the
+    /// address provided, such as `127.0.0.1:8125`. The names of the counters
+    /// start with the prefix provided, such as `livesplit`.
+    pub fn connect<A: ToSocketAddrs>(address: A, prefix: String) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+        socket.connect(address)?;
+        Ok(Self { socket, prefix })
+    }
+
+    /// Sends the counters that the record increments to the server.
+    pub fn send(&self, record: &Record) -> io::Result<()> {
+        self.socket
+            .send(record.to_statsd(&self.prefix).as_bytes())
+            .map(drop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::{create_timer, start_run};
+
+    #[test]
+    fn counts_resets_per_segment() {
+        let mut timer = create_timer(&["A", "B"]);
+        let mut metrics = Metrics::new();
+
+        start_run(&mut timer);
+        metrics.record(Event::Started, &timer);
+        timer.split().unwrap();
+        metrics.record(Event::Splitted, &timer);
+        timer.reset(true).unwrap();
+        let record = metrics.record(Event::Reset, &timer);
+
+        assert_eq!(record.segment.as_deref(), Some("B"));
+        assert_eq!(
+            record.to_statsd("livesplit"),
+            "livesplit.events.reset:1|c\nlivesplit.resets.b:1|c\n"
+        );
+
+        let text = metrics.to_prometheus();
+        assert!(text.contains("livesplit_attempts_started_total 1\n"));
+        assert!(text.contains("livesplit_resets_total{segment=\"B\"} 1\n"));
+    }
+}
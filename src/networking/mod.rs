@@ -3,7 +3,93 @@
 //! and Speedrun.com to query and submit to the leaderboards of most games. The
 //! module is optional and is not compiled in by default.
 
+#[cfg(any(feature = "http-server", feature = "websocket-server"))]
+pub mod access_control;
+#[cfg(feature = "networking")]
+pub mod cloud_sync;
+#[cfg(feature = "ghost-racing")]
+pub mod ghost_race;
+#[cfg(feature = "http-server")]
+pub mod http_server;
+#[cfg(feature = "livesplit-server")]
+pub mod livesplit_server;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "obs")]
+pub mod obs;
+#[cfg(feature = "racetime")]
+pub mod racetime;
 #[cfg(feature = "std")]
 pub mod server_protocol;
 #[cfg(feature = "networking")]
+pub mod speedrun_com;
+#[cfg(feature = "networking")]
 pub mod splits_io;
+#[cfg(feature = "srl")]
+pub mod srl;
+#[cfg(feature = "timer-mirroring")]
+pub mod timer_mirror;
+#[cfg(any(feature = "obs", feature = "webhooks"))]
+mod trigger;
+#[cfg(feature = "networking")]
+pub mod twitch;
+#[cfg(feature = "webhooks")]
+pub mod webhooks;
+#[cfg(feature = "websocket-server")]
+pub mod websocket_server;
+
+/// The servers and clients handle each connection on its own thread, so it's
+/// fine to just block on the commands until they are done.
+#[cfg(any(
+    feature = "http-server",
+    feature = "livesplit-server",
+    feature = "racetime",
+    feature = "srl",
+    feature = "websocket-server"
+))]
+use crate::event::block_on;
+
+/// Encodes the state of the timer as JSON for the servers that provide it to
+/// browser based clients.
+#[cfg(any(feature = "http-server", feature = "websocket-server"))]
+fn timer_state(timer: &crate::Timer) -> serde_json::Value {
+    use crate::{
+        timing::formatter::{self, TimeFormatter, ASCII_MINUS},
+        TimerPhase, TimingMethod,
+    };
+
+    #[derive(serde_derive::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct TimerState<'a> {
+        phase: &'static str,
+        current_split_index: Option<usize>,
+        current_comparison: &'a str,
+        current_timing_method: TimingMethod,
+        real_time: String,
+        game_time: String,
+        game_name: &'a str,
+        category_name: &'a str,
+        attempt_count: u32,
+    }
+
+    let formatter =
+        formatter::none_wrapper::NoneWrapper::new(formatter::Complete::new(), ASCII_MINUS);
+    let current_time = timer.snapshot().current_time();
+    let state = TimerState {
+        phase: match timer.current_phase() {
+            TimerPhase::NotRunning => "NotRunning",
+            TimerPhase::Running => "Running",
+            TimerPhase::Ended => "Ended",
+            TimerPhase::Paused => "Paused",
+        },
+        current_split_index: timer.current_split_index(),
+        current_comparison: timer.current_comparison(),
+        current_timing_method: timer.current_timing_method(),
+        real_time: formatter.format(current_time.real_time).to_string(),
+        game_time: formatter.format(current_time.game_time).to_string(),
+        game_name: timer.run().game_name(),
+        category_name: timer.run().category_name(),
+        attempt_count: timer.run().attempt_count(),
+    };
+    serde_json::to_value(state).unwrap_or_default()
+}
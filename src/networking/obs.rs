@@ -0,0 +1,391 @@
+//! The `obs` module automates [OBS Studio](https://obsproject.com) based on the
+//! events of the timer. It connects to the
+//! [obs-websocket](https://github.com/obsproject/obs-websocket) server that is
+//! built into OBS and can switch scenes, show and hide sources, and control the
+//! replay buffer and recording.
+//!
+//! Which actions get executed is configured via a JSON mapping from the
+//! triggers to the actions:
+//! ```json
+//! {
+//!     "Started": [{ "type": "SwitchScene", "scene": "Gameplay" }],
+//!     "Gold": [{ "type": "SaveReplayBuffer" }],
+//!     "Finished": [
+//!         { "type": "SetSourceVisibility", "scene": "Gameplay", "source": "Confetti", "visible": true }
+//!     ]
+//! }
+//! ```
+
+use std::{collections::BTreeMap, net::TcpStream};
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use snafu::ResultExt;
+use tungstenite::{stream::MaybeTlsStream, Message};
+
+pub use super::trigger::Trigger;
+
+use super::trigger::triggers;
+use crate::{event::Event, Timer};
+
+/// The URL that obs-websocket listens on by default.
+pub const DEFAULT_URL: &str = "ws://localhost:4455";
+
+/// The version of the RPC protocol of obs-websocket that is spoken.
+const RPC_VERSION: u32 = 1;
+
+/// Describes an error that happened when communicating with OBS.
+#[derive(Debug, snafu::Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum Error {
+    /// The WebSocket connection failed.
+    WebSocket {
+        /// The underlying error.
+        #[snafu(source(from(tungstenite::Error, Box::new)))]
+        source: Box<tungstenite::Error>,
+    },
+    /// OBS sent a message that couldn't be understood.
+    InvalidMessage,
+    /// OBS requires a password, but none was provided.
+    PasswordRequired,
+    /// The connection got closed, most likely because the password is wrong.
+    ConnectionClosed,
+    /// OBS couldn't execute the request.
+    Request {
+        /// The status code of the request.
+        code: u32,
+        /// The comment that OBS provided about the failure.
+        comment: String,
+    },
+}
+
+/// The result type for communicating with OBS.
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// An action that can be executed in OBS.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Action {
+    /// Switches the program to the scene with the given name.
+    SwitchScene {
+        /// The name of the scene.
+        scene: String,
+    },
+    /// Shows or hides a source in a scene.
+    SetSourceVisibility {
+        /// The name of the scene the source is in.
+        scene: String,
+        /// The name of the source.
+        source: String,
+        /// Whether the source should be visible.
+        visible: bool,
+    },
+    /// Saves the contents of the replay buffer.
+    SaveReplayBuffer,
+    /// Starts the recording.
+    StartRecording,
+    /// Stops the recording.
+    StopRecording,
+}
+
+/// The mapping from the triggers to the actions that get executed in OBS.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Mapping {
+    actions: BTreeMap<Trigger, Vec<Action>>,
+}
+
+impl Mapping {
+    /// Parses the mapping from its JSON representation.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Encodes the mapping as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// Adds an action to execute whenever the trigger happens.
+    pub fn add(&mut self, trigger: Trigger, action: Action) {
+        self.actions.entry(trigger).or_default().push(action);
+    }
+
+    /// Removes all the actions of the trigger.
+    pub fn clear(&mut self, trigger: Trigger) {
+        self.actions.remove(&trigger);
+    }
+
+    /// Returns the actions that need to be executed because of the event that
+    /// happened to the timer. The timer needs to be in the state right after
+    /// the event.
+    pub fn actions_for(&self, event: Event, timer: &Timer) -> Vec<&Action> {
+        triggers(event, timer)
+            .into_iter()
+            .filter_map(|trigger| self.actions.get(&trigger))
+            .flatten()
+            .collect()
+    }
+}
+
+/// A connection to obs-websocket.
+pub struct Connection {
+    socket: tungstenite::WebSocket<MaybeTlsStream<TcpStream>>,
+    next_request_id: u64,
+}
+
+#[derive(Deserialize)]
+struct Hello {
+    authentication: Option<Authentication>,
+}
+
+#[derive(Deserialize)]
+struct Authentication {
+    challenge: String,
+    salt: String,
+}
+
+#[derive(Deserialize)]
+struct IncomingMessage {
+    op: u8,
+    d: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RequestResponse {
+    request_id: String,
+    request_status: RequestStatus,
+    #[serde(default)]
+    response_data: Value,
+}
+
+#[derive(Deserialize)]
+struct RequestStatus {
+    result: bool,
+    code: u32,
+    #[serde(default)]
+    comment: String,
+}
+
+mod op {
+    pub const HELLO: u8 = 0;
+    pub const IDENTIFY: u8 = 1;
+    pub const IDENTIFIED: u8 = 2;
+    pub const REQUEST: u8 = 6;
+    pub const REQUEST_RESPONSE: u8 = 7;
+}
+
+fn authentication_string(password: &str, auth: &Authentication) -> String {
+    let secret = base64_simd::STANDARD.encode_to_string(Sha256::digest(
+        format!("{password}{}", auth.salt).as_bytes(),
+    ));
+    base64_simd::STANDARD.encode_to_string(Sha256::digest(
+        format!("{secret}{}", auth.challenge).as_bytes(),
+    ))
+}
+
+/// Connects to obs-websocket at the URL provided, such as the
+/// [`DEFAULT_URL`]. The password is only needed if authentication is enabled
+/// in OBS.
+pub fn connect(url: &str, password: Option<&str>) -> Result<Connection> {
+    let (socket, _) = tungstenite::connect(url).context(WebSocket)?;
+    let mut connection = Connection {
+        socket,
+        next_request_id: 0,
+    };
+
+    let hello: Hello = serde_json::from_value(connection.receive(op::HELLO)?)
+        .map_err(|_| Error::InvalidMessage)?;
+
+    let mut identify = json!({ "rpcVersion": RPC_VERSION, "eventSubscriptions": 0 });
+    if let Some(auth) = &hello.authentication {
+        let password = password.ok_or(Error::PasswordRequired)?;
+        identify["authentication"] = authentication_string(password, auth).into();
+    }
+    connection.send(op::IDENTIFY, identify)?;
+    connection.receive(op::IDENTIFIED)?;
+
+    Ok(connection)
+}
+
+impl Connection {
+    fn send(&mut self, op: u8, d: Value) -> Result<()> {
+        self.socket
+            .send(Message::text(json!({ "op": op, "d": d }).to_string()))
+            .context(WebSocket)
+    }
+
+    /// Receives messages until one with the given op code arrives and returns
+    /// its data.
+    fn receive(&mut self, op: u8) -> Result<Value> {
+        loop {
+            let message = match self.socket.read() {
+                Ok(message) => message,
+                Err(tungstenite::Error::ConnectionClosed) => return Err(Error::ConnectionClosed),
+                Err(source) => return Err(source).context(WebSocket),
+            };
+            if message.is_close() {
+                return Err(Error::ConnectionClosed);
+            }
+            if !message.is_text() {
+                continue;
+            }
+            let message: IncomingMessage =
+                serde_json::from_str(message.to_text().context(WebSocket)?)
+                    .map_err(|_| Error::InvalidMessage)?;
+            if message.op == op {
+                return Ok(message.d);
+            }
+        }
+    }
+
+    fn request(&mut self, request_type: &str, request_data: Value) -> Result<Value> {
+        let request_id = self.next_request_id.to_string();
+        self.next_request_id += 1;
+        self.send(
+            op::REQUEST,
+            json!({
+                "requestType": request_type,
+                "requestId": request_id,
+                "requestData": request_data,
+            }),
+        )?;
+
+        loop {
+            let response: RequestResponse =
+                serde_json::from_value(self.receive(op::REQUEST_RESPONSE)?)
+                    .map_err(|_| Error::InvalidMessage)?;
+            if response.request_id != request_id {
+                continue;
+            }
+            if !response.request_status.result {
+                return Err(Error::Request {
+                    code: response.request_status.code,
+                    comment: response.request_status.comment,
+                });
+            }
+            return Ok(response.response_data);
+        }
+    }
+
+    /// Executes the action in OBS.
+    pub fn execute(&mut self, action: &Action) -> Result<()> {
+        match action {
+            Action::SwitchScene { scene } => {
+                self.request("SetCurrentProgramScene", json!({ "sceneName": scene }))?;
+            }
+            Action::SetSourceVisibility {
+                scene,
+                source,
+                visible,
+            } => {
+                let response = self.request(
+                    "GetSceneItemId",
+                    json!({ "sceneName": scene, "sourceName": source }),
+                )?;
+                let item_id = response["sceneItemId"]
+                    .as_i64()
+                    .ok_or(Error::InvalidMessage)?;
+                self.request(
+                    "SetSceneItemEnabled",
+                    json!({
+                        "sceneName": scene,
+                        "sceneItemId": item_id,
+                        "sceneItemEnabled": visible,
+                    }),
+                )?;
+            }
+            Action::SaveReplayBuffer => {
+                self.request("SaveReplayBuffer", Value::Null)?;
+            }
+            Action::StartRecording => {
+                self.request("StartRecord", Value::Null)?;
+            }
+            Action::StopRecording => {
+                self.request("StopRecord", Value::Null)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes all the actions that the mapping specifies for the event that
+    /// happened to the timer. The timer needs to be in the state right after
+    /// the event. All the actions are attempted, even if some of them fail, in
+    /// which case the first error is returned.
+    pub fn handle_event(&mut self, mapping: &Mapping, event: Event, timer: &Timer) -> Result<()> {
+        let mut result = Ok(());
+        for action in mapping.actions_for(event, timer) {
+            if let Err(e) = self.execute(action) {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::{
+        create_timer, make_progress_run_with_splits_opt, span, start_run,
+    };
+
+    #[test]
+    fn mapping() {
+        let mapping = Mapping::from_json(
+            r#"{
+                "Started": [{ "type": "SwitchScene", "scene": "Gameplay" }],
+                "Gold": [{ "type": "SaveReplayBuffer" }],
+                "PersonalBest": [
+                    { "type": "SetSourceVisibility", "scene": "Gameplay", "source": "Confetti", "visible": true }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut timer = create_timer(&["A"]);
+        timer.start().unwrap();
+        assert_eq!(
+            mapping.actions_for(Event::Started, &timer),
+            [&Action::SwitchScene {
+                scene: "Gameplay".into()
+            }],
+        );
+
+        timer.reset(true).unwrap();
+        start_run(&mut timer);
+        make_progress_run_with_splits_opt(&mut timer, &[Some(1.0)]);
+        assert_eq!(
+            timer.run().segment(0).split_time().game_time,
+            Some(span(1.0))
+        );
+        assert_eq!(
+            mapping.actions_for(Event::Finished, &timer),
+            [
+                &Action::SaveReplayBuffer,
+                &Action::SetSourceVisibility {
+                    scene: "Gameplay".into(),
+                    source: "Confetti".into(),
+                    visible: true,
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn authentication() {
+        // The example from the obs-websocket protocol documentation.
+        let auth = Authentication {
+            challenge: "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY=".into(),
+            salt: "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=".into(),
+        };
+        assert_eq!(
+            authentication_string("supersecretpassword", &auth),
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4=",
+        );
+    }
+}
@@ -0,0 +1,365 @@
+//! The racetime module connects to a race room on
+//! [racetime.gg](https://racetime.gg), so racers don't need a separate bridge
+//! application. The events of the race are applied to the timer:
+//!
+//! - When the race starts, the timer is started at the start time of the race.
+//! - When the race gets cancelled, or the racer forfeits or gets disqualified,
+//!   the timer is reset.
+//!
+//! The other way around, the events of the timer are reported to the race
+//! room. Finishing the run marks the racer as done and undoing the final split
+//! undoes that again. Optionally each split is reported as a chat message.
+//!
+//! The protocol handling itself is independent of the connection, so it can
+//! be driven by a different WebSocket implementation, for example in the
+//! browser, via [`Race`].
+
+use std::{
+    net::TcpStream,
+    sync::mpsc::{self, Receiver, Sender},
+    time::Duration,
+};
+
+use serde_derive::Deserialize;
+use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use tungstenite::{stream::MaybeTlsStream, Message, WebSocket};
+
+use crate::{
+    event::{CommandSink, Event, TimerQuery},
+    platform::{utc_now, DateTime},
+    timing::formatter::{self, TimeFormatter, ASCII_MINUS},
+    TimerPhase,
+};
+
+use super::block_on;
+
+/// The base URL of the WebSocket API of racetime.gg.
+pub const BASE_URL: &str = "wss://racetime.gg";
+
+/// How often the connection checks whether the race started and whether there
+/// are events to report while waiting for messages.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The configuration of the connection to a race room.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The name of the race room, consisting of the category and the room
+    /// slug, such as `sm64/clever-wario-1234`.
+    pub room: String,
+    /// The OAuth2 access token of the racer.
+    pub access_token: String,
+    /// The id of the racer's racetime.gg user. This is used to find the racer
+    /// among the entrants of the race.
+    pub user_id: String,
+    /// Whether each split should be reported as a chat message to the race
+    /// room.
+    pub report_splits: bool,
+}
+
+/// An action that needs to be applied to the timer because of a change in the
+/// race.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TimerAction {
+    /// The race starts at the given time. The timer needs to be started once
+    /// that time is reached.
+    Start(DateTime),
+    /// The race got cancelled or the racer forfeited or got disqualified, so
+    /// the timer needs to be reset.
+    Reset,
+}
+
+#[derive(Deserialize)]
+struct IncomingMessage<'a> {
+    #[serde(rename = "type", borrow)]
+    kind: &'a str,
+    race: Option<RaceData<'a>>,
+}
+
+#[derive(Deserialize)]
+struct RaceData<'a> {
+    #[serde(borrow)]
+    status: Status<'a>,
+    #[serde(borrow)]
+    started_at: Option<&'a str>,
+    #[serde(default, borrow)]
+    entrants: Vec<Entrant<'a>>,
+}
+
+#[derive(Deserialize)]
+struct Entrant<'a> {
+    #[serde(borrow)]
+    user: User<'a>,
+    #[serde(borrow)]
+    status: Status<'a>,
+}
+
+#[derive(Deserialize)]
+struct User<'a> {
+    #[serde(borrow)]
+    id: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Status<'a> {
+    #[serde(borrow)]
+    value: &'a str,
+}
+
+/// The state of a race as seen by a single racer. It turns the messages of
+/// the race room into [`TimerAction`]s and the [`Event`]s of the timer into
+/// messages for the race room.
+#[derive(Debug)]
+pub struct Race {
+    user_id: String,
+    report_splits: bool,
+    start: Option<DateTime>,
+    is_cancelled: bool,
+    has_forfeited: bool,
+    is_done: bool,
+}
+
+impl Race {
+    /// Creates the state of a race for the racer with the given user id.
+    pub const fn new(user_id: String, report_splits: bool) -> Self {
+        Self {
+            user_id,
+            report_splits,
+            start: None,
+            is_cancelled: false,
+            has_forfeited: false,
+            is_done: false,
+        }
+    }
+
+    /// Handles a message received from the race room and returns the action
+    /// that needs to be applied to the timer, if any.
+    pub fn handle_message(&mut self, message: &str) -> Option<TimerAction> {
+        let message: IncomingMessage<'_> = serde_json::from_str(message).ok()?;
+        if message.kind != "race.data" {
+            return None;
+        }
+        let race = message.race?;
+
+        let entrant_status = race
+            .entrants
+            .iter()
+            .find(|e| e.user.id == self.user_id)
+            .map(|e| e.status.value);
+        self.is_done = entrant_status == Some("done");
+
+        let has_forfeited = matches!(entrant_status, Some("dnf" | "dq"));
+        let is_cancelled = race.status.value == "cancelled";
+        let should_reset = self.start.is_some()
+            && ((has_forfeited && !self.has_forfeited) || (is_cancelled && !self.is_cancelled));
+        self.has_forfeited = has_forfeited;
+        self.is_cancelled = is_cancelled;
+        if should_reset {
+            return Some(TimerAction::Reset);
+        }
+
+        if self.start.is_none() && matches!(race.status.value, "pending" | "in_progress") {
+            let start = DateTime::parse(race.started_at?, &Rfc3339).ok()?;
+            self.start = Some(start);
+            return Some(TimerAction::Start(start));
+        }
+
+        None
+    }
+
+    /// Reports an event that happened to the timer and returns the message
+    /// to send to the race room, if any.
+    pub fn report_event<S: TimerQuery>(&mut self, event: Event, timer: &S) -> Option<String> {
+        if self.start.is_none() || self.is_cancelled || self.has_forfeited {
+            return None;
+        }
+        let message = match event {
+            Event::Finished if !self.is_done => {
+                self.is_done = true;
+                json!({ "action": "done" })
+            }
+            Event::SplitUndone if self.is_done => {
+                let timer = timer.get_timer();
+                if timer.current_phase() == TimerPhase::Ended {
+                    return None;
+                }
+                self.is_done = false;
+                json!({ "action": "undone" })
+            }
+            Event::Splitted if self.report_splits => {
+                let timer = timer.get_timer();
+                let index = timer.current_split_index()?.checked_sub(1)?;
+                let segment = &timer.run().segments()[index];
+                let time = segment.split_time()[timer.current_timing_method()];
+                let formatter = formatter::none_wrapper::NoneWrapper::new(
+                    formatter::Regular::new(),
+                    ASCII_MINUS,
+                );
+                let time = formatter.format(time);
+                json!({
+                    "action": "message",
+                    "data": { "message": format!("{}: {time}", segment.name()) },
+                })
+            }
+            _ => return None,
+        };
+        Some(message.to_string())
+    }
+}
+
+/// A handle to report the events of the timer to a race room. It can be
+/// cloned to be shared between threads.
+#[derive(Clone)]
+pub struct Reporter {
+    sender: Sender<Event>,
+}
+
+impl Reporter {
+    /// Reports an event that happened to the timer. This is meant to be called
+    /// by the [`CommandSink`] of the frontend for every command it processes.
+    pub fn report_event(&self, event: Event) {
+        // The connection may already be closed, in which case there's nobody
+        // to report to anymore.
+        let _ = self.sender.send(event);
+    }
+}
+
+/// A connection to a race room on racetime.gg.
+pub struct Connection {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    race: Race,
+    events: Receiver<Event>,
+}
+
+/// Connects to the race room described by the configuration. The returned
+/// [`Reporter`] is used to report the events of the timer to the race room.
+#[allow(clippy::result_large_err)]
+pub fn connect(config: Config) -> tungstenite::Result<(Connection, Reporter)> {
+    let url = format!(
+        "{BASE_URL}/ws/o/race/{}?token={}",
+        config.room, config.access_token,
+    );
+    let (socket, _) = tungstenite::connect(url)?;
+
+    let stream = match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => Some(stream),
+        MaybeTlsStream::Rustls(stream) => Some(stream.get_ref()),
+        _ => None,
+    };
+    if let Some(stream) = stream {
+        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    }
+
+    let (sender, events) = mpsc::channel();
+    Ok((
+        Connection {
+            socket,
+            race: Race::new(config.user_id, config.report_splits),
+            events,
+        },
+        Reporter { sender },
+    ))
+}
+
+impl Connection {
+    /// Applies the race's events to the timer and reports the timer's events
+    /// to the race room until the connection gets closed. This blocks the
+    /// current thread.
+    #[allow(clippy::result_large_err)]
+    pub fn run<S: CommandSink + TimerQuery>(mut self, command_sink: &S) -> tungstenite::Result<()> {
+        let mut pending_start = None;
+
+        loop {
+            if let Some(start) = pending_start {
+                if utc_now() >= start {
+                    pending_start = None;
+                    let _ = block_on(command_sink.start());
+                }
+            }
+
+            for event in self.events.try_iter() {
+                if let Some(message) = self.race.report_event(event, command_sink) {
+                    self.socket.send(Message::text(message))?;
+                }
+            }
+
+            match self.socket.read() {
+                Ok(message) if message.is_text() => {
+                    match self.race.handle_message(message.to_text()?) {
+                        Some(TimerAction::Start(start)) => pending_start = Some(start),
+                        Some(TimerAction::Reset) => {
+                            pending_start = None;
+                            let _ = block_on(command_sink.reset(None));
+                        }
+                        None => {}
+                    }
+                }
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e))
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) => {}
+                Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::create_timer;
+
+    fn race_data(status: &str, entrant_status: &str) -> String {
+        json!({
+            "type": "race.data",
+            "race": {
+                "status": { "value": status },
+                "started_at": "2024-05-01T12:00:15.000Z",
+                "entrants": [
+                    { "user": { "id": "other" }, "status": { "value": "in_progress" } },
+                    { "user": { "id": "me" }, "status": { "value": entrant_status } },
+                ],
+            },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn race_events() {
+        let mut race = Race::new("me".into(), false);
+        assert_eq!(race.handle_message(&race_data("open", "not_ready")), None);
+        assert!(matches!(
+            race.handle_message(&race_data("pending", "ready")),
+            Some(TimerAction::Start(_)),
+        ));
+        assert_eq!(
+            race.handle_message(&race_data("in_progress", "in_progress")),
+            None,
+        );
+        assert_eq!(
+            race.handle_message(&race_data("in_progress", "dnf")),
+            Some(TimerAction::Reset),
+        );
+        assert_eq!(race.handle_message(&race_data("in_progress", "dnf")), None);
+    }
+
+    #[test]
+    fn reports_finish() {
+        let timer = create_timer(&["A"]).into_shared();
+        let mut race = Race::new("me".into(), false);
+        assert_eq!(race.report_event(Event::Finished, &timer), None);
+        race.handle_message(&race_data("in_progress", "in_progress"));
+        assert_eq!(
+            race.report_event(Event::Finished, &timer).as_deref(),
+            Some(r#"{"action":"done"}"#),
+        );
+        assert_eq!(race.report_event(Event::Finished, &timer), None);
+        assert_eq!(
+            race.report_event(Event::SplitUndone, &timer).as_deref(),
+            Some(r#"{"action":"undone"}"#),
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! The `speedrun_com` module provides access to the games, categories and
+//! leaderboards of [Speedrun.com](https://www.speedrun.com). This allows the
+//! users to choose the game, category, platform, region and variables of their
+//! splits from the actual values used on the leaderboards instead of having to
+//! type them in manually. The chosen values can then be applied to the
+//! [`RunEditor`](crate::RunEditor) via a [`Selection`].
+
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde_derive::Deserialize;
+use snafu::{OptionExt, ResultExt};
+
+use crate::{util::caseless, Run, RunEditor, TimeSpan};
+
+/// The base URL of the REST API of Speedrun.com.
+pub const API_URL: &str = "https://www.speedrun.com/api/v1";
+
+/// Describes an error that happened when querying Speedrun.com.
+#[derive(Debug, snafu::Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum Error {
+    /// Failed to send the request or to receive the response.
+    Request {
+        /// The underlying error.
+        source: reqwest::Error,
+    },
+    /// The response indicated that the request was unsuccessful.
+    Status {
+        /// The HTTP status code of the response.
+        status: u16,
+    },
+    /// The game of the run could not be found.
+    GameNotFound,
+    /// The category of the run could not be found.
+    CategoryNotFound,
+}
+
+/// The result type for querying Speedrun.com.
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// A game listed on Speedrun.com.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    /// The id of the game.
+    pub id: String,
+    /// The international name of the game.
+    pub name: String,
+    /// The abbreviation of the game, which is used in its URL.
+    pub abbreviation: String,
+}
+
+/// A category of a game on Speedrun.com.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Category {
+    /// The id of the category.
+    pub id: String,
+    /// The name of the category.
+    pub name: String,
+    /// The variables that apply to the category.
+    pub variables: Vec<Variable>,
+}
+
+/// A platform or a region on Speedrun.com.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Named {
+    /// The id of the platform or region.
+    pub id: String,
+    /// The name of the platform or region.
+    pub name: String,
+}
+
+/// A variable, such as the version of the game, that runs can specify on
+/// Speedrun.com.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    /// The id of the variable.
+    pub id: String,
+    /// The name of the variable.
+    pub name: String,
+    /// Whether the variable splits the category into multiple leaderboards.
+    pub is_subcategory: bool,
+    /// The values the variable can have.
+    pub values: Vec<VariableValue>,
+}
+
+/// A value a [`Variable`] can have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableValue {
+    /// The id of the value.
+    pub id: String,
+    /// The label of the value that is shown to the users.
+    pub label: String,
+}
+
+/// All the values that can be chosen for the splits of a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameMetadata {
+    /// The game itself.
+    pub game: Game,
+    /// The full game categories of the game.
+    pub categories: Vec<Category>,
+    /// The platforms the game is available on.
+    pub platforms: Vec<Named>,
+    /// The regions the game is available in.
+    pub regions: Vec<Named>,
+}
+
+/// The runs on the leaderboard of a category.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leaderboard {
+    /// The URL of the leaderboard on Speedrun.com.
+    pub weblink: String,
+    /// The runs on the leaderboard, ordered by their place.
+    pub runs: Vec<LeaderboardRun>,
+}
+
+/// A run on a [`Leaderboard`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeaderboardRun {
+    /// The place of the run on the leaderboard. Runs that tie share the same
+    /// place.
+    pub place: u32,
+    /// The id of the run.
+    pub id: String,
+    /// The URL of the run on Speedrun.com.
+    pub weblink: String,
+    /// The time of the run in the leaderboard's primary timing method.
+    pub time: TimeSpan,
+}
+
+/// The values chosen for the splits. Applying the selection to the
+/// [`RunEditor`] changes the game and category and replaces the platform,
+/// region and variables of the run's metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection<'a> {
+    /// The game of the run.
+    pub game: Option<&'a Game>,
+    /// The category of the run.
+    pub category: Option<&'a Category>,
+    /// The platform the run is played on.
+    pub platform: Option<&'a Named>,
+    /// The region of the game that is used for the run.
+    pub region: Option<&'a Named>,
+    /// The values of the category's variables that apply to the run.
+    pub variables: Vec<(&'a Variable, &'a VariableValue)>,
+}
+
+impl Selection<'_> {
+    /// Applies the selection to the run that is being edited.
+    pub fn apply_to(&self, editor: &mut RunEditor) {
+        if let Some(game) = self.game {
+            editor.set_game_name(game.name.as_str());
+        }
+        if let Some(category) = self.category {
+            editor.set_category_name(category.name.as_str());
+        }
+        editor.set_platform_name(self.platform.map_or("", |p| p.name.as_str()));
+        editor.set_region_name(self.region.map_or("", |r| r.name.as_str()));
+
+        let existing: Vec<String> = editor
+            .run()
+            .metadata()
+            .speedrun_com_variables()
+            .map(|(name, _)| name.to_owned())
+            .collect();
+        for name in existing {
+            editor.remove_speedrun_com_variable(&name);
+        }
+        for (variable, value) in &self.variables {
+            editor.set_speedrun_com_variable(variable.name.as_str(), value.label.as_str());
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Data<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct ApiGame {
+    id: String,
+    names: ApiNames,
+    abbreviation: String,
+}
+
+#[derive(Deserialize)]
+struct ApiNames {
+    international: String,
+}
+
+#[derive(Deserialize)]
+struct ApiGameMetadata {
+    #[serde(flatten)]
+    game: ApiGame,
+    categories: Data<Vec<ApiCategory>>,
+    platforms: Data<Vec<Named>>,
+    regions: Data<Vec<Named>>,
+}
+
+#[derive(Deserialize)]
+struct ApiCategory {
+    id: String,
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    variables: Data<Vec<ApiVariable>>,
+}
+
+#[derive(Deserialize)]
+struct ApiVariable {
+    id: String,
+    name: String,
+    #[serde(rename = "is-subcategory")]
+    is_subcategory: bool,
+    values: ApiVariableValues,
+}
+
+#[derive(Deserialize)]
+struct ApiVariableValues {
+    values: BTreeMap<String, ApiVariableValue>,
+}
+
+#[derive(Deserialize)]
+struct ApiVariableValue {
+    label: String,
+}
+
+#[derive(Deserialize)]
+struct ApiLeaderboard {
+    weblink: String,
+    runs: Vec<ApiPlacedRun>,
+}
+
+#[derive(Deserialize)]
+struct ApiPlacedRun {
+    place: u32,
+    run: ApiRun,
+}
+
+#[derive(Deserialize)]
+struct ApiRun {
+    id: String,
+    weblink: String,
+    times: ApiTimes,
+}
+
+#[derive(Deserialize)]
+struct ApiTimes {
+    primary_t: f64,
+}
+
+impl From<ApiGame> for Game {
+    fn from(game: ApiGame) -> Self {
+        Self {
+            id: game.id,
+            name: game.names.international,
+            abbreviation: game.abbreviation,
+        }
+    }
+}
+
+impl From<ApiVariable> for Variable {
+    fn from(variable: ApiVariable) -> Self {
+        Self {
+            id: variable.id,
+            name: variable.name,
+            is_subcategory: variable.is_subcategory,
+            values: variable
+                .values
+                .values
+                .into_iter()
+                .map(|(id, value)| VariableValue {
+                    id,
+                    label: value.label,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A client for the REST API of Speedrun.com. It can be cloned cheaply, as the
+/// connections are shared between the clones.
+#[derive(Debug, Clone, Default)]
+pub struct Client {
+    client: reqwest::Client,
+}
+
+impl Client {
+    /// Creates a new client for the REST API of Speedrun.com.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: String) -> Result<T> {
+        let response = self.client.get(url).send().await.context(Request)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Error::Status {
+                status: status.as_u16(),
+            });
+        }
+        let Data { data } = response.json().await.context(Request)?;
+        Ok(data)
+    }
+
+    /// Searches for the games whose names match the given name.
+    pub async fn search_games(&self, name: &str) -> Result<Vec<Game>> {
+        let url = reqwest::Url::parse_with_params(&format!("{API_URL}/games"), [("name", name)])
+            .expect("The URL is always valid");
+        let games: Vec<ApiGame> = self.get(url.into()).await?;
+        Ok(games.into_iter().map(Into::into).collect())
+    }
+
+    /// Queries the categories, platforms and regions of the game with the
+    /// given id. Only the full game categories are included, as the splits
+    /// are usually for those.
+    pub async fn game_metadata(&self, game_id: &str) -> Result<GameMetadata> {
+        let metadata: ApiGameMetadata = self
+            .get(format!(
+                "{API_URL}/games/{game_id}?embed=categories.variables,platforms,regions"
+            ))
+            .await?;
+
+        Ok(GameMetadata {
+            game: metadata.game.into(),
+            categories: metadata
+                .categories
+                .data
+                .into_iter()
+                .filter(|c| c.kind == "per-game")
+                .map(|c| Category {
+                    id: c.id,
+                    name: c.name,
+                    variables: c.variables.data.into_iter().map(Into::into).collect(),
+                })
+                .collect(),
+            platforms: metadata.platforms.data,
+            regions: metadata.regions.data,
+        })
+    }
+
+    /// Queries the top runs of the leaderboard of a category. The values of
+    /// the variables are specified as pairs of variable and value ids.
+    pub async fn leaderboard(
+        &self,
+        game_id: &str,
+        category_id: &str,
+        variables: &[(&str, &str)],
+        top: u32,
+    ) -> Result<Leaderboard> {
+        let mut url = format!("{API_URL}/leaderboards/{game_id}/category/{category_id}?top={top}");
+        for (variable, value) in variables {
+            url.push_str(&format!("&var-{variable}={value}"));
+        }
+        let leaderboard: ApiLeaderboard = self.get(url).await?;
+
+        Ok(Leaderboard {
+            weblink: leaderboard.weblink,
+            runs: leaderboard
+                .runs
+                .into_iter()
+                .map(|r| LeaderboardRun {
+                    place: r.place,
+                    id: r.run.id,
+                    weblink: r.run.weblink,
+                    time: TimeSpan::from_seconds(r.run.times.primary_t),
+                })
+                .collect(),
+        })
+    }
+
+    /// Queries the top runs of the leaderboard the run belongs to. The game,
+    /// category and subcategory variables are looked up by the names stored in
+    /// the run.
+    pub async fn leaderboard_for_run(&self, run: &Run, top: u32) -> Result<Leaderboard> {
+        let game = self
+            .search_games(run.game_name())
+            .await?
+            .into_iter()
+            .find(|g| caseless::eq(&g.name, run.game_name()))
+            .context(GameNotFound)?;
+        let metadata = self.game_metadata(&game.id).await?;
+        let category = metadata
+            .categories
+            .iter()
+            .find(|c| caseless::eq(&c.name, run.category_name()))
+            .context(CategoryNotFound)?;
+
+        let variables: Vec<(&str, &str)> = category
+            .variables
+            .iter()
+            .filter(|v| v.is_subcategory)
+            .filter_map(|variable| {
+                let (_, label) = run
+                    .metadata()
+                    .speedrun_com_variables()
+                    .find(|&(name, _)| name == variable.name)?;
+                let value = variable.values.iter().find(|v| &v.label == label)?;
+                Some((variable.id.as_str(), value.id.as_str()))
+            })
+            .collect();
+
+        self.leaderboard(&game.id, &category.id, &variables, top)
+            .await
+    }
+}
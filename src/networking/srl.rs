@@ -0,0 +1,350 @@
+//! The `srl` module takes part in races on
+//! [SpeedRunsLive](https://www.speedrunslive.com) through their IRC server. The
+//! races are coordinated by the `RaceBot` in the race's channel, which is
+//! controlled with chat commands such as `.enter`, `.ready` and `.done`.
+//!
+//! When `RaceBot` counts down the start of the race, the start time is
+//! calculated from the countdown, so the timer is started in sync with the
+//! other racers, regardless of when the final `GO!` message arrives. Finishing
+//! the run automatically sends `.done` and undoing the final split sends
+//! `.undone`.
+//!
+//! The protocol handling itself is independent of the connection, so it can
+//! be driven by a different IRC implementation via [`Race`].
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::TcpStream,
+    sync::mpsc::{self, Receiver, Sender},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    event::{CommandSink, Event, TimerQuery},
+    TimerPhase,
+};
+
+use super::block_on;
+
+/// The address of the IRC server of SpeedRunsLive.
+pub const SERVER: &str = "irc.speedrunslive.com:6667";
+
+/// The nickname of the bot that coordinates the races.
+const RACE_BOT: &str = "RaceBot";
+
+/// How often the connection checks whether the race started and whether there
+/// are events to report while waiting for messages.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A command for `RaceBot` that can be sent to the race channel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RaceCommand {
+    /// Enters the race.
+    Enter,
+    /// Leaves the race before it started.
+    Unenter,
+    /// Marks the racer as ready.
+    Ready,
+    /// Marks the racer as not ready anymore.
+    Unready,
+    /// Marks the racer as done.
+    Done,
+    /// Marks the racer as not done anymore.
+    Undone,
+    /// Forfeits the race.
+    Forfeit,
+}
+
+impl RaceCommand {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Enter => ".enter",
+            Self::Unenter => ".unenter",
+            Self::Ready => ".ready",
+            Self::Unready => ".unready",
+            Self::Done => ".done",
+            Self::Undone => ".undone",
+            Self::Forfeit => ".forfeit",
+        }
+    }
+}
+
+/// An action that needs to be applied to the timer because of a message in
+/// the race channel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TimerAction {
+    /// The race starts in the given amount of time. The timer needs to be
+    /// started once that time passed.
+    StartIn(Duration),
+    /// The race started right now.
+    Start,
+}
+
+/// The state of a race in a race channel as seen by a single racer.
+#[derive(Debug)]
+pub struct Race {
+    channel: String,
+    has_started: bool,
+    is_done: bool,
+}
+
+impl Race {
+    /// Creates the state of a race in the channel provided, such as
+    /// `#srl-abcde`.
+    pub const fn new(channel: String) -> Self {
+        Self {
+            channel,
+            has_started: false,
+            is_done: false,
+        }
+    }
+
+    /// Encodes a command for `RaceBot` as an IRC message to send.
+    pub fn command(&self, command: RaceCommand) -> String {
+        format!("PRIVMSG {} :{}\r\n", self.channel, command.as_str())
+    }
+
+    /// Handles a line received from the IRC server and returns the action that
+    /// needs to be applied to the timer, if any.
+    pub fn handle_line(&mut self, line: &str) -> Option<TimerAction> {
+        let line = line.trim_end();
+        let (prefix, rest) = line.strip_prefix(':')?.split_once(' ')?;
+        let nick = prefix.split('!').next()?;
+        let (command, rest) = rest.split_once(' ')?;
+        let (target, text) = rest.split_once(" :")?;
+        if nick != RACE_BOT
+            || !matches!(command, "PRIVMSG" | "NOTICE")
+            || !target.eq_ignore_ascii_case(&self.channel)
+        {
+            return None;
+        }
+
+        // RaceBot formats its messages with IRC color codes.
+        let text: String = text
+            .split('\x03')
+            .enumerate()
+            .map(|(i, part)| {
+                if i == 0 {
+                    part
+                } else {
+                    part.trim_start_matches(|c: char| c.is_ascii_digit() || c == ',')
+                }
+            })
+            .collect::<String>()
+            .replace('\x02', "");
+        let text = text.trim();
+
+        if text == "GO!" {
+            return (!self.has_started).then(|| {
+                self.has_started = true;
+                TimerAction::Start
+            });
+        }
+
+        if let Some(seconds) = text
+            .strip_prefix("The race will begin in ")
+            .and_then(|rest| rest.strip_suffix(" seconds!"))
+            .and_then(|seconds| seconds.parse().ok())
+        {
+            self.has_started = true;
+            self.is_done = false;
+            return Some(TimerAction::StartIn(Duration::from_secs(seconds)));
+        }
+
+        if text.starts_with("Race terminated") || text.starts_with("Rematch!") {
+            self.has_started = false;
+            self.is_done = false;
+        }
+
+        None
+    }
+
+    /// Reports an event that happened to the timer and returns the command to
+    /// send to `RaceBot`, if any.
+    pub fn report_event<S: TimerQuery>(&mut self, event: Event, timer: &S) -> Option<RaceCommand> {
+        if !self.has_started {
+            return None;
+        }
+        match event {
+            Event::Finished if !self.is_done => {
+                self.is_done = true;
+                Some(RaceCommand::Done)
+            }
+            Event::SplitUndone
+                if self.is_done && timer.get_timer().current_phase() != TimerPhase::Ended =>
+            {
+                self.is_done = false;
+                Some(RaceCommand::Undone)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A handle to report the events of the timer and send commands to the race
+/// channel. It can be cloned to be shared between threads.
+#[derive(Clone)]
+pub struct Reporter {
+    sender: Sender<Message>,
+}
+
+enum Message {
+    Event(Event),
+    Command(RaceCommand),
+}
+
+impl Reporter {
+    /// Reports an event that happened to the timer. This is meant to be called
+    /// by the [`CommandSink`] of the frontend for every command it processes.
+    pub fn report_event(&self, event: Event) {
+        // The connection may already be closed, in which case there's nobody
+        // to report to anymore.
+        let _ = self.sender.send(Message::Event(event));
+    }
+
+    /// Sends a command to `RaceBot`, such as entering the race or marking the
+    /// racer as ready.
+    pub fn send_command(&self, command: RaceCommand) {
+        let _ = self.sender.send(Message::Command(command));
+    }
+}
+
+/// A connection to a race channel on the IRC server of SpeedRunsLive.
+pub struct Connection {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+    race: Race,
+    messages: Receiver<Message>,
+}
+
+/// Connects to the IRC server and joins the race channel, such as
+/// `#srl-abcde`. The password is the one registered with the nickname, if
+/// there is one. The returned [`Reporter`] is used to report the events of the
+/// timer and to send commands to `RaceBot`.
+pub fn connect(
+    nickname: &str,
+    password: Option<&str>,
+    channel: &str,
+) -> io::Result<(Connection, Reporter)> {
+    let mut stream = TcpStream::connect(SERVER)?;
+    if let Some(password) = password {
+        write!(stream, "PASS {password}\r\n")?;
+    }
+    write!(
+        stream,
+        "NICK {nickname}\r\nUSER {nickname} 0 * :{nickname}\r\nJOIN {channel}\r\n"
+    )?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+
+    let (sender, messages) = mpsc::channel();
+    Ok((
+        Connection {
+            reader: BufReader::new(stream.try_clone()?),
+            stream,
+            race: Race::new(channel.into()),
+            messages,
+        },
+        Reporter { sender },
+    ))
+}
+
+impl Connection {
+    /// Applies the race's countdown to the timer and reports the timer's
+    /// events to the race channel until the connection gets closed. This
+    /// blocks the current thread.
+    pub fn run<S: CommandSink + TimerQuery>(mut self, command_sink: &S) -> io::Result<()> {
+        let mut pending_start = None;
+        let mut line = String::new();
+
+        loop {
+            if pending_start.is_some_and(|start| Instant::now() >= start) {
+                pending_start = None;
+                let _ = block_on(command_sink.start());
+            }
+
+            for message in self.messages.try_iter() {
+                let command = match message {
+                    Message::Event(event) => self.race.report_event(event, command_sink),
+                    Message::Command(command) => Some(command),
+                };
+                if let Some(command) = command {
+                    self.stream
+                        .write_all(self.race.command(command).as_bytes())?;
+                }
+            }
+
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return Ok(()),
+                Ok(_) => {
+                    if let Some(server) = line.strip_prefix("PING ") {
+                        write!(self.stream, "PONG {}\r\n", server.trim_end())?;
+                    }
+                    match self.race.handle_line(&line) {
+                        Some(TimerAction::StartIn(delay)) => {
+                            pending_start = Some(Instant::now() + delay);
+                        }
+                        Some(TimerAction::Start) => {
+                            pending_start = None;
+                            let _ = block_on(command_sink.start());
+                        }
+                        None => {}
+                    }
+                    line.clear();
+                }
+                // Whatever got read so far stays in the line, so we can just
+                // continue reading.
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::create_timer;
+
+    #[test]
+    fn countdown() {
+        let mut race = Race::new("#srl-abcde".into());
+        assert_eq!(
+            race.handle_line(":Someone!a@b PRIVMSG #srl-abcde :GO!\r\n"),
+            None,
+        );
+        assert_eq!(
+            race.handle_line(
+                ":RaceBot!RaceBot@srl NOTICE #srl-abcde :\x0304The race will begin in 10 seconds!\r\n"
+            ),
+            Some(TimerAction::StartIn(Duration::from_secs(10))),
+        );
+        assert_eq!(
+            race.handle_line(":RaceBot!RaceBot@srl PRIVMSG #srl-abcde :\x0304GO!\r\n"),
+            None,
+        );
+    }
+
+    #[test]
+    fn reports_done() {
+        let timer = create_timer(&["A"]).into_shared();
+        let mut race = Race::new("#srl-abcde".into());
+        assert_eq!(race.report_event(Event::Finished, &timer), None);
+        race.handle_line(":RaceBot!RaceBot@srl PRIVMSG #srl-abcde :GO!");
+        assert_eq!(
+            race.report_event(Event::Finished, &timer),
+            Some(RaceCommand::Done),
+        );
+        assert_eq!(
+            race.command(RaceCommand::Done),
+            "PRIVMSG #srl-abcde :.done\r\n"
+        );
+        assert_eq!(
+            race.report_event(Event::SplitUndone, &timer),
+            Some(RaceCommand::Undone),
+        );
+    }
+}
@@ -0,0 +1,356 @@
+//! The `timer_mirror` module allows a timer to be mirrored exactly on other
+//! devices. One timer is the leader, which is the authoritative source of the
+//! attempt, while any number of followers mirror it, for example for the
+//! layouts of commentators or for restreams that need to show exactly what the
+//! runner's timer shows.
+//!
+//! The followers connect to the leader via WebSocket. Whenever the attempt
+//! changes, and additionally once per second to correct any drift, the leader
+//! sends the state of the attempt:
+//! ```json
+//! {
+//!     "state": {
+//!         "phase": "Running",
+//!         "currentSplitIndex": 1,
+//!         "splitTimes": [{ "realTime": 83.5, "gameTime": null }, { "realTime": null, "gameTime": null }],
+//!         "currentTime": { "realTime": 95.25, "gameTime": null },
+//!         "pauseTime": null,
+//!         "isGameTimePaused": false,
+//!         "currentComparison": "Personal Best",
+//!         "currentTimingMethod": "RealTime"
+//!     }
+//! }
+//! ```
+//!
+//! The followers regularly measure the round trip time to the leader by
+//! sending `{ "ping": 1 }` messages, which the leader answers with
+//! `{ "pong": 1 }`. Half of the round trip time is used to compensate for the
+//! latency of the state messages.
+
+use std::{
+    io,
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_derive::{Deserialize, Serialize};
+use tungstenite::{stream::MaybeTlsStream, Message, WebSocket};
+
+use crate::{timing::AttemptState, SharedTimer, Time, TimeSpan, Timer, TimerPhase, TimingMethod};
+
+/// How often the connections check for messages to send while waiting for
+/// incoming messages.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// How often the state is sent, even if the attempt didn't change.
+const RESYNC_INTERVAL: Duration = Duration::from_secs(1);
+/// How often the followers measure the round trip time.
+const PING_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WireState {
+    phase: TimerPhase,
+    current_split_index: Option<usize>,
+    split_times: Vec<WireTime>,
+    current_time: WireTime,
+    pause_time: Option<f64>,
+    is_game_time_paused: bool,
+    current_comparison: String,
+    current_timing_method: TimingMethod,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WireTime {
+    real_time: Option<f64>,
+    game_time: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum WireMessage {
+    State(WireState),
+    Ping(u64),
+    Pong(u64),
+}
+
+impl From<Time> for WireTime {
+    fn from(time: Time) -> Self {
+        Self {
+            real_time: time.real_time.map(|t| t.total_seconds()),
+            game_time: time.game_time.map(|t| t.total_seconds()),
+        }
+    }
+}
+
+impl From<WireTime> for Time {
+    fn from(time: WireTime) -> Self {
+        Time::new()
+            .with_real_time(time.real_time.map(TimeSpan::from_seconds))
+            .with_game_time(time.game_time.map(TimeSpan::from_seconds))
+    }
+}
+
+impl From<AttemptState> for WireState {
+    fn from(state: AttemptState) -> Self {
+        Self {
+            phase: state.phase,
+            current_split_index: state.current_split_index,
+            split_times: state.split_times.into_iter().map(Into::into).collect(),
+            current_time: state.current_time.into(),
+            pause_time: state.pause_time.map(|t| t.total_seconds()),
+            is_game_time_paused: state.is_game_time_paused,
+            current_comparison: state.current_comparison,
+            current_timing_method: state.current_timing_method,
+        }
+    }
+}
+
+impl From<WireState> for AttemptState {
+    fn from(state: WireState) -> Self {
+        Self {
+            phase: state.phase,
+            current_split_index: state.current_split_index,
+            split_times: state.split_times.into_iter().map(Into::into).collect(),
+            current_time: state.current_time.into(),
+            pause_time: state.pause_time.map(TimeSpan::from_seconds),
+            is_game_time_paused: state.is_game_time_paused,
+            current_comparison: state.current_comparison,
+            current_timing_method: state.current_timing_method,
+        }
+    }
+}
+
+impl WireState {
+    /// Whether the states describe the same attempt, apart from the time
+    /// that passed in between.
+    fn is_same_attempt(&self, other: &Self) -> bool {
+        self.phase == other.phase
+            && self.current_split_index == other.current_split_index
+            && self.split_times == other.split_times
+            && self.is_game_time_paused == other.is_game_time_paused
+            && self.current_time.game_time.is_some() == other.current_time.game_time.is_some()
+            && self.current_comparison == other.current_comparison
+            && self.current_timing_method == other.current_timing_method
+    }
+}
+
+/// The leader is the authoritative timer that gets mirrored by the followers.
+/// It can be cloned to be shared between threads.
+#[derive(Clone, Default)]
+pub struct Leader {
+    inner: Arc<Mutex<LeaderInner>>,
+}
+
+#[derive(Default)]
+struct LeaderInner {
+    followers: Vec<Sender<String>>,
+    last_state: Option<(WireState, Instant)>,
+}
+
+impl Leader {
+    /// Creates a new leader that doesn't have any followers yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes the state of the timer to the followers. This is meant to be
+    /// called regularly, for example once per frame. The state is only sent if
+    /// the attempt changed or if it hasn't been sent for a while.
+    pub fn publish(&self, timer: &Timer) {
+        let state = WireState::from(timer.attempt_state());
+        let now = Instant::now();
+
+        let mut inner = self.inner.lock().unwrap();
+        if let Some((last_state, sent_at)) = &inner.last_state {
+            if last_state.is_same_attempt(&state) && now - *sent_at < RESYNC_INTERVAL {
+                return;
+            }
+        }
+
+        let message = serde_json::to_string(&WireMessage::State(state.clone())).unwrap();
+        // Followers that disconnected dropped their receiver.
+        inner
+            .followers
+            .retain(|follower| follower.send(message.clone()).is_ok());
+        inner.last_state = Some((state, now));
+    }
+
+    fn subscribe(&self) -> Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        let mut inner = self.inner.lock().unwrap();
+        // Forces the next publish to send the state to the new follower.
+        inner.last_state = None;
+        inner.followers.push(sender);
+        receiver
+    }
+
+    /// Accepts the followers on the listener. Each connection is handled on its
+    /// own thread. This blocks the current thread until accepting a
+    /// connection fails.
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        loop {
+            let (stream, _) = listener.accept()?;
+            let leader = self.clone();
+            thread::spawn(move || leader.serve_connection(stream));
+        }
+    }
+
+    fn serve_connection(&self, stream: TcpStream) -> tungstenite::Result<()> {
+        let mut socket = tungstenite::accept(stream).map_err(|e| match e {
+            tungstenite::HandshakeError::Failure(e) => e,
+            tungstenite::HandshakeError::Interrupted(_) => {
+                tungstenite::Error::Io(io::ErrorKind::WouldBlock.into())
+            }
+        })?;
+        socket.get_ref().set_read_timeout(Some(POLL_INTERVAL))?;
+        let states = self.subscribe();
+
+        loop {
+            for message in states.try_iter() {
+                socket.send(Message::text(message))?;
+            }
+
+            match read(&mut socket)? {
+                Some(WireMessage::Ping(id)) => {
+                    let pong = serde_json::to_string(&WireMessage::Pong(id)).unwrap();
+                    socket.send(Message::text(pong))?;
+                }
+                Some(_) | None => {}
+            }
+        }
+    }
+}
+
+/// Reads the next message, if there is one. Closed connections are reported
+/// as errors.
+fn read<S: io::Read + io::Write>(
+    socket: &mut WebSocket<S>,
+) -> tungstenite::Result<Option<WireMessage>> {
+    match socket.read() {
+        Ok(message) if message.is_text() => Ok(serde_json::from_str(message.to_text()?).ok()),
+        Ok(_) => Ok(None),
+        Err(tungstenite::Error::Io(e))
+            if matches!(
+                e.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Estimates the latency from the round trip times that were measured.
+#[derive(Debug, Default)]
+struct LatencyEstimator {
+    latency: Option<TimeSpan>,
+}
+
+impl LatencyEstimator {
+    fn add_round_trip(&mut self, round_trip: Duration) {
+        let sample = TimeSpan::from_seconds(round_trip.as_secs_f64() / 2.0);
+        // Smooth out the samples, so a single slow round trip doesn't make
+        // the mirrored timer jump.
+        self.latency = Some(match self.latency {
+            Some(latency) => {
+                TimeSpan::from_seconds(0.8 * latency.total_seconds() + 0.2 * sample.total_seconds())
+            }
+            None => sample,
+        });
+    }
+
+    fn latency(&self) -> TimeSpan {
+        self.latency.unwrap_or_default()
+    }
+}
+
+/// Follows the leader at the URL provided, such as `ws://192.168.0.10:8080`,
+/// mirroring its attempt on the timer. This blocks the current thread until
+/// the connection gets closed.
+pub fn follow(url: &str, timer: &SharedTimer) -> tungstenite::Result<()> {
+    let (mut socket, _) = tungstenite::connect(url)?;
+    if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    }
+
+    let mut estimator = LatencyEstimator::default();
+    let mut next_ping_id = 0;
+    let mut pending_ping: Option<(u64, Instant)> = None;
+    let mut last_ping_at: Option<Instant> = None;
+
+    loop {
+        if last_ping_at.map_or(true, |at| at.elapsed() >= PING_INTERVAL) {
+            let ping = serde_json::to_string(&WireMessage::Ping(next_ping_id)).unwrap();
+            socket.send(Message::text(ping))?;
+            let now = Instant::now();
+            pending_ping = Some((next_ping_id, now));
+            last_ping_at = Some(now);
+            next_ping_id += 1;
+        }
+
+        match read(&mut socket) {
+            Ok(Some(WireMessage::State(state))) => {
+                timer
+                    .write()
+                    .unwrap()
+                    .mirror_attempt_state(&state.into(), estimator.latency());
+            }
+            Ok(Some(WireMessage::Pong(id))) => {
+                if let Some((ping_id, sent_at)) = pending_ping {
+                    if ping_id == id {
+                        estimator.add_round_trip(sent_at.elapsed());
+                        pending_ping = None;
+                    }
+                }
+            }
+            Ok(Some(WireMessage::Ping(_)) | None) => {}
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::{create_timer, span, start_run};
+
+    #[test]
+    fn mirrors_attempt() {
+        let mut leader = create_timer(&["A", "B"]);
+        start_run(&mut leader);
+        leader.set_game_time(span(5.0)).unwrap();
+        leader.split().unwrap();
+        leader.set_game_time(span(7.0)).unwrap();
+
+        let state = WireState::from(leader.attempt_state());
+        let json = serde_json::to_string(&WireMessage::State(state)).unwrap();
+        let Ok(WireMessage::State(state)) = serde_json::from_str(&json) else {
+            panic!("The state should be parsed again");
+        };
+
+        let mut follower = create_timer(&["A", "B"]);
+        follower.mirror_attempt_state(&state.into(), span(1.0));
+
+        assert_eq!(follower.current_phase(), TimerPhase::Running);
+        assert_eq!(follower.current_split_index(), Some(1));
+        assert_eq!(
+            follower.run().segment(0).split_time().game_time,
+            Some(span(5.0)),
+        );
+        // The game time is paused, so the latency doesn't affect it.
+        assert_eq!(
+            follower.snapshot().current_time().game_time,
+            Some(span(7.0)),
+        );
+        assert!(follower.is_game_time_paused());
+        assert_eq!(follower.current_timing_method(), TimingMethod::GameTime);
+    }
+}
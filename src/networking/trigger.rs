@@ -0,0 +1,67 @@
+//! Detects the triggers that the events of the timer result in, so the
+//! integrations can react to them in the same way.
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{analysis, event::Event, Timer, TimerPhase};
+
+/// Something that happens to the timer that can trigger actions, such as
+/// the actions in OBS or the webhooks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Trigger {
+    /// A new attempt has been started.
+    Started,
+    /// A split happened that isn't the final split.
+    Split,
+    /// A split happened and the segment is a new best segment.
+    Gold,
+    /// The final split happened.
+    Finished,
+    /// The final split happened and the run is a new personal best.
+    PersonalBest,
+    /// The attempt has been reset.
+    Reset,
+    /// The timer has been paused.
+    Paused,
+    /// The timer has been resumed.
+    Resumed,
+}
+
+/// Returns the triggers that the event results in. The timer needs to be in
+/// the state right after the event.
+pub(crate) fn triggers(event: Event, timer: &Timer) -> Vec<Trigger> {
+    let method = timer.current_timing_method();
+    let is_gold = |index: usize| analysis::check_best_segment(timer, index, method);
+
+    match event {
+        Event::Started => vec![Trigger::Started],
+        Event::Splitted => {
+            let mut triggers = vec![Trigger::Split];
+            if let Some(index) = timer.current_split_index().and_then(|i| i.checked_sub(1)) {
+                if is_gold(index) {
+                    triggers.push(Trigger::Gold);
+                }
+            }
+            triggers
+        }
+        Event::Finished if timer.current_phase() == TimerPhase::Ended => {
+            let mut triggers = vec![Trigger::Finished];
+            let last_index = timer.run().len() - 1;
+            if is_gold(last_index) {
+                triggers.push(Trigger::Gold);
+            }
+            let last_segment = timer.run().segment(last_index);
+            let is_pb = last_segment.split_time()[method].is_some_and(|time| {
+                last_segment.personal_best_split_time()[method].map_or(true, |pb| time < pb)
+            });
+            if is_pb {
+                triggers.push(Trigger::PersonalBest);
+            }
+            triggers
+        }
+        Event::Reset => vec![Trigger::Reset],
+        Event::Paused => vec![Trigger::Paused],
+        Event::Resumed | Event::PausesUndoneAndResumed => vec![Trigger::Resumed],
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,322 @@
+//! The `twitch` module keeps the title and category of a
+//! [Twitch](https://www.twitch.tv) stream in sync with the run and the state of
+//! the timer. The frontend only needs to provide the credentials of the
+//! channel and regularly pass the timer to the [`ChannelSync`].
+//!
+//! The title is generated from a template, where the following placeholders
+//! are replaced:
+//!
+//! - `{game}`: The name of the game.
+//! - `{category}`: The name of the category.
+//! - `{attempt}`: The number of the current attempt.
+//! - `{status}`: The status text that is configured for the current
+//!   [`TimerPhase`].
+//!
+//! Twitch limits how often the channel information can be updated, so changes
+//! that happen in quick succession, such as resetting and immediately starting
+//! a new attempt, are batched into a single update.
+
+use serde_derive::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt};
+
+use crate::{
+    platform::{Duration, Instant},
+    Timer, TimerPhase,
+};
+
+/// The base URL of the Helix API of Twitch.
+pub const API_URL: &str = "https://api.twitch.tv/helix";
+
+/// Describes an error that happened when updating the Twitch channel.
+#[derive(Debug, snafu::Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum Error {
+    /// Failed to send the request or to receive the response.
+    Request {
+        /// The underlying error.
+        source: reqwest::Error,
+    },
+    /// The response indicated that the request was unsuccessful.
+    Status {
+        /// The HTTP status code of the response.
+        status: u16,
+    },
+    /// Twitch doesn't know the game of the run.
+    GameNotFound,
+}
+
+/// The result type for updating the Twitch channel.
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// The settings for generating the channel information.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The template of the stream title.
+    pub title_template: String,
+    /// The status text while no attempt is in progress.
+    pub not_running: String,
+    /// The status text while an attempt is in progress.
+    pub running: String,
+    /// The status text while the attempt is paused.
+    pub paused: String,
+    /// The status text after the attempt finished.
+    pub ended: String,
+    /// Whether the category of the stream is changed to the game of the run.
+    pub sync_category: bool,
+    /// The minimum amount of time between two updates of the channel.
+    pub min_interval_secs: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            title_template: "{game} - {category} {status}".into(),
+            not_running: String::new(),
+            running: "(Attempt #{attempt})".into(),
+            paused: "(Paused)".into(),
+            ended: "(Finished)".into(),
+            sync_category: true,
+            min_interval_secs: 30,
+        }
+    }
+}
+
+/// The information of the channel that needs to be sent to Twitch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelUpdate {
+    /// The new title of the stream.
+    pub title: String,
+    /// The name of the game to use as the category of the stream, if it should
+    /// be changed.
+    pub game_name: Option<String>,
+}
+
+/// Tracks the run and the timer and decides when the channel information needs
+/// to be updated. It doesn't communicate with Twitch by itself, so the updates
+/// can be sent in whatever way fits the frontend, such as with the
+/// [`Client`].
+#[derive(Debug)]
+pub struct ChannelSync {
+    settings: Settings,
+    sent: Option<ChannelUpdate>,
+    pending: Option<ChannelUpdate>,
+    last_sent_at: Option<Instant>,
+}
+
+impl ChannelSync {
+    /// Creates a new channel sync with the settings provided. The first update
+    /// is sent as soon as the timer is passed to it.
+    pub const fn new(settings: Settings) -> Self {
+        Self {
+            settings,
+            sent: None,
+            pending: None,
+            last_sent_at: None,
+        }
+    }
+
+    /// Accesses the settings.
+    pub const fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Changes the settings. The channel gets updated with the new settings
+    /// the next time the timer is passed in.
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+        self.sent = None;
+    }
+
+    /// Passes in the current state of the timer. This should be called
+    /// regularly, for example whenever an [`Event`](crate::event::Event)
+    /// happened or once per frame. It returns the update that needs to be sent
+    /// to Twitch, if there is one that is due. Updates are only returned if
+    /// the minimum interval since the last update has passed. Until then the
+    /// changes are accumulated, so only the latest state is sent.
+    pub fn update(&mut self, timer: &Timer, now: Instant) -> Option<ChannelUpdate> {
+        let update = self.channel_update(timer);
+        if self.sent.as_ref() == Some(&update) {
+            self.pending = None;
+        } else {
+            self.pending = Some(update);
+        }
+
+        if let Some(last_sent_at) = self.last_sent_at {
+            let min_interval = Duration::seconds(self.settings.min_interval_secs.into());
+            if now - last_sent_at < min_interval {
+                return None;
+            }
+        }
+
+        let update = self.pending.take()?;
+        let mut to_send = update.clone();
+        if self.sent.as_ref().and_then(|s| s.game_name.as_ref()) == update.game_name.as_ref() {
+            // The category is unchanged, so there's no need to look it up again.
+            to_send.game_name = None;
+        }
+        self.sent = Some(update);
+        self.last_sent_at = Some(now);
+        Some(to_send)
+    }
+
+    fn channel_update(&self, timer: &Timer) -> ChannelUpdate {
+        let run = timer.run();
+        let status = match timer.current_phase() {
+            TimerPhase::NotRunning => &self.settings.not_running,
+            TimerPhase::Running => &self.settings.running,
+            TimerPhase::Paused => &self.settings.paused,
+            TimerPhase::Ended => &self.settings.ended,
+        };
+        let attempt = run.attempt_count().to_string();
+        let title = self
+            .settings
+            .title_template
+            .replace("{status}", status)
+            .replace("{game}", run.game_name())
+            .replace("{category}", run.category_name())
+            .replace("{attempt}", &attempt);
+
+        ChannelUpdate {
+            title: title.split_whitespace().collect::<Vec<_>>().join(" "),
+            game_name: (self.settings.sync_category && !run.game_name().is_empty())
+                .then(|| run.game_name().into()),
+        }
+    }
+}
+
+/// The credentials needed to update the information of a Twitch channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    /// The client id of the application that is registered with Twitch.
+    pub client_id: String,
+    /// The user access token with the `channel:manage:broadcast` scope.
+    pub access_token: String,
+    /// The id of the channel's broadcaster.
+    pub broadcaster_id: String,
+}
+
+#[derive(Deserialize)]
+struct Data<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct Game {
+    id: String,
+}
+
+#[derive(Serialize)]
+struct ModifyChannel<'a> {
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    game_id: Option<&'a str>,
+}
+
+/// A client for updating the information of a Twitch channel.
+#[derive(Debug, Clone)]
+pub struct Client {
+    client: reqwest::Client,
+    credentials: Credentials,
+}
+
+impl Client {
+    /// Creates a new client that updates the channel with the credentials
+    /// provided.
+    pub fn new(credentials: Credentials) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            credentials,
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, url)
+            .header("Client-Id", &self.credentials.client_id)
+            .bearer_auth(&self.credentials.access_token)
+    }
+
+    async fn game_id(&self, name: &str) -> Result<String> {
+        let response = self
+            .request(reqwest::Method::GET, &format!("{API_URL}/games"))
+            .query(&[("name", name)])
+            .send()
+            .await
+            .context(Request)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Error::Status {
+                status: status.as_u16(),
+            });
+        }
+        let Data::<Vec<Game>> { data } = response.json().await.context(Request)?;
+        data.into_iter().next().map(|g| g.id).context(GameNotFound)
+    }
+
+    /// Sends the update to Twitch.
+    pub async fn update_channel(&self, update: &ChannelUpdate) -> Result<()> {
+        let game_id = match &update.game_name {
+            Some(name) => Some(self.game_id(name).await?),
+            None => None,
+        };
+
+        let response = self
+            .request(reqwest::Method::PATCH, &format!("{API_URL}/channels"))
+            .query(&[("broadcaster_id", &self.credentials.broadcaster_id)])
+            .json(&ModifyChannel {
+                title: &update.title,
+                game_id: game_id.as_deref(),
+            })
+            .send()
+            .await
+            .context(Request)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Error::Status {
+                status: status.as_u16(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::{create_run, start_run};
+
+    #[test]
+    fn batches_updates() {
+        let mut run = create_run(&["A"]);
+        run.set_game_name("Game");
+        run.set_category_name("Any%");
+        let mut timer = Timer::new(run).unwrap();
+        let mut sync = ChannelSync::new(Settings::default());
+
+        assert_eq!(
+            sync.update(&timer, Instant::now()),
+            Some(ChannelUpdate {
+                title: "Game - Any%".into(),
+                game_name: Some("Game".into()),
+            }),
+        );
+
+        start_run(&mut timer);
+        assert_eq!(sync.update(&timer, Instant::now()), None);
+        assert!(sync.pending.is_some());
+
+        sync.set_settings(Settings {
+            min_interval_secs: 0,
+            ..Settings::default()
+        });
+        assert_eq!(
+            sync.update(&timer, Instant::now()),
+            Some(ChannelUpdate {
+                title: "Game - Any% (Attempt #1)".into(),
+                game_name: Some("Game".into()),
+            }),
+        );
+        assert_eq!(sync.update(&timer, Instant::now()), None);
+    }
+}
@@ -0,0 +1,299 @@
+//! The `webhooks` module sends JSON payloads to user defined URLs whenever
+//! chosen [`Trigger`]s happen, such as finishing a run, achieving a personal
+//! best or getting a gold split. This allows Discord bots and home automation
+//! to react to the timer without a custom frontend.
+//!
+//! The webhooks are configured via JSON:
+//! ```json
+//! {
+//!     "webhooks": [
+//!         {
+//!             "url": "https://discord.com/api/webhooks/...",
+//!             "triggers": ["PersonalBest"],
+//!             "payload": { "content": "New personal best in {game} {category}: {time}" }
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! All the strings in the payload can use the following placeholders:
+//!
+//! - `{trigger}`: The name of the trigger, such as `PersonalBest`.
+//! - `{game}`: The name of the game.
+//! - `{category}`: The name of the category.
+//! - `{attempt}`: The number of the current attempt.
+//! - `{segment}`: The name of the segment that got split, if any.
+//! - `{time}`: The current time of the attempt.
+//!
+//! If a webhook doesn't specify a payload, a JSON object with all of these
+//! values is sent.
+
+use std::time::Duration;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use snafu::{ensure, ResultExt};
+
+pub use super::trigger::Trigger;
+
+use super::trigger::triggers;
+use crate::{
+    event::Event,
+    timing::formatter::{Regular, TimeFormatter},
+    Timer, TimerPhase,
+};
+
+/// Describes an error that happened when delivering a webhook.
+#[derive(Debug, snafu::Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum Error {
+    /// Failed to send the request or to receive the response.
+    Request {
+        /// The underlying error.
+        source: reqwest::Error,
+    },
+    /// The response indicated that the request was unsuccessful.
+    Status {
+        /// The HTTP status code of the response.
+        status: u16,
+    },
+}
+
+/// The result type for delivering webhooks.
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// A URL that a payload gets sent to whenever one of the triggers happens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Webhook {
+    /// The URL to send the payload to.
+    pub url: String,
+    /// The triggers that the payload gets sent for.
+    pub triggers: Vec<Trigger>,
+    /// The payload to send. If there is none, a JSON object that describes
+    /// the trigger is sent.
+    #[serde(default)]
+    pub payload: Option<Value>,
+}
+
+/// The settings of the webhooks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The webhooks to send the payloads to.
+    pub webhooks: Vec<Webhook>,
+    /// How often a delivery is retried if it fails.
+    pub max_retries: u32,
+    /// How long to wait before the first retry. The delay doubles with every
+    /// retry.
+    pub initial_backoff_ms: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            webhooks: Vec::new(),
+            max_retries: 5,
+            initial_backoff_ms: 1000,
+        }
+    }
+}
+
+impl Settings {
+    /// Parses the settings from their JSON representation.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Encodes the settings as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// A payload that needs to be sent to a URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery {
+    /// The URL to send the payload to.
+    pub url: String,
+    /// The payload to send.
+    pub payload: Value,
+}
+
+/// Sends the payloads of the webhooks. It can be cloned cheaply, as the
+/// connections are shared between the clones.
+#[derive(Debug, Clone)]
+pub struct Emitter {
+    client: reqwest::Client,
+    settings: Settings,
+}
+
+impl Emitter {
+    /// Creates a new emitter with the settings provided.
+    pub fn new(settings: Settings) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            settings,
+        }
+    }
+
+    /// Accesses the settings.
+    pub const fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Changes the settings.
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+    }
+
+    /// Returns the payloads that need to be delivered because of the event
+    /// that happened to the timer. The timer needs to be in the state right
+    /// after the event.
+    pub fn deliveries_for(&self, event: Event, timer: &Timer) -> Vec<Delivery> {
+        let mut deliveries = Vec::new();
+        for trigger in triggers(event, timer) {
+            let values = values(trigger, timer);
+            for webhook in &self.settings.webhooks {
+                if webhook.triggers.contains(&trigger) {
+                    let payload = match &webhook.payload {
+                        Some(payload) => fill_in(payload, &values),
+                        None => Value::Object(
+                            values
+                                .iter()
+                                .map(|(key, value)| ((*key).into(), value.clone().into()))
+                                .collect(),
+                        ),
+                    };
+                    deliveries.push(Delivery {
+                        url: webhook.url.clone(),
+                        payload,
+                    });
+                }
+            }
+        }
+        deliveries
+    }
+
+    /// Delivers the payload. Failed deliveries are retried with an
+    /// exponential backoff. Requests that are rejected by the server, other
+    /// than because of rate limiting, are not retried, as they would be
+    /// rejected again.
+    pub async fn deliver(&self, delivery: &Delivery) -> Result<()> {
+        let mut backoff = Duration::from_millis(self.settings.initial_backoff_ms.into());
+        let mut retries = 0;
+        loop {
+            let result = self.try_deliver(delivery).await;
+            let is_permanent = matches!(
+                result,
+                Err(Error::Status { status }) if (400..500).contains(&status) && status != 429
+            );
+            if result.is_ok() || is_permanent || retries >= self.settings.max_retries {
+                return result;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+            retries += 1;
+        }
+    }
+
+    async fn try_deliver(&self, delivery: &Delivery) -> Result<()> {
+        let response = self
+            .client
+            .post(&delivery.url)
+            .json(&delivery.payload)
+            .send()
+            .await
+            .context(Request)?;
+        let status = response.status();
+        ensure!(
+            status.is_success(),
+            Status {
+                status: status.as_u16()
+            }
+        );
+        Ok(())
+    }
+}
+
+fn values(trigger: Trigger, timer: &Timer) -> [(&'static str, String); 6] {
+    let run = timer.run();
+    let segment = match timer.current_phase() {
+        TimerPhase::Ended => run.segments().last(),
+        _ => timer
+            .current_split_index()
+            .and_then(|i| i.checked_sub(1))
+            .map(|i| run.segment(i)),
+    };
+    let time = timer.snapshot().current_time()[timer.current_timing_method()];
+    [
+        ("trigger", format!("{trigger:?}")),
+        ("game", run.game_name().to_owned()),
+        ("category", run.category_name().to_owned()),
+        ("attempt", run.attempt_count().to_string()),
+        (
+            "segment",
+            segment.map(|s| s.name().to_owned()).unwrap_or_default(),
+        ),
+        ("time", Regular::new().format(time).to_string()),
+    ]
+}
+
+fn fill_in(template: &Value, values: &[(&str, String)]) -> Value {
+    match template {
+        Value::String(text) => {
+            let mut text = text.clone();
+            for (key, value) in values {
+                text = text.replace(&format!("{{{key}}}"), value);
+            }
+            Value::String(text)
+        }
+        Value::Array(items) => items.iter().map(|item| fill_in(item, values)).collect(),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), fill_in(value, values)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::{create_timer, start_run};
+    use serde_json::json;
+
+    #[test]
+    fn fills_in_payload() {
+        let emitter = Emitter::new(
+            Settings::from_json(
+                r#"{
+                    "webhooks": [
+                        {
+                            "url": "https://example.com/split",
+                            "triggers": ["Split"],
+                            "payload": { "content": "Split {segment} of {game}", "tts": false }
+                        },
+                        { "url": "https://example.com/finish", "triggers": ["Finished"] }
+                    ]
+                }"#,
+            )
+            .unwrap(),
+        );
+
+        let mut timer = create_timer(&["A", "B"]);
+        start_run(&mut timer);
+        timer.split().unwrap();
+
+        let deliveries = emitter.deliveries_for(Event::Splitted, &timer);
+        assert_eq!(
+            deliveries,
+            [Delivery {
+                url: "https://example.com/split".into(),
+                payload: json!({ "content": "Split A of ", "tts": false }),
+            }],
+        );
+        assert!(emitter.deliveries_for(Event::Paused, &timer).is_empty());
+    }
+}
@@ -0,0 +1,391 @@
+//! The WebSocket server allows browser based dashboards and companion apps to
+//! remotely control and mirror the timer. The commands are the ones of the
+//! [`server_protocol`](super::server_protocol), sent as text messages, and
+//! their responses are sent back the same way.
+//!
+//! Additionally the state of the timer and the layout is streamed to every
+//! client. When a client connects, it receives the full states:
+//! ```json
+//! { "timer": { "phase": "Running", ... }, "generation": 7 }
+//! { "layout": { "components": [...], ... }, "generation": 3 }
+//! ```
+//!
+//! Afterwards only the changes are sent, encoded as a JSON Merge Patch (RFC
+//! 7386) that can be applied to the previous state:
+//! ```json
+//! { "timerDelta": { "realTime": "00:01:23.456000000" }, "generation": 8 }
+//! ```
+//!
+//! As a JSON Merge Patch can't set a value to `null`, the full state is sent
+//! again whenever a value changes to `null`.
+//!
+//! The generation increases by one for every change of the respective state,
+//! just like in the [`state_stream`](crate::util::state_stream) protocol.
+//!
+//! The events of the timer are sent just like in the server protocol:
+//! ```json
+//! { "event": "Splitted" }
+//! ```
+//!
+//! The client needs to provide the token of the server either as a URL encoded
+//! `token` query parameter of the URL or as a bearer token in the
+//! `Authorization` header. Browsers don't apply any cross origin restrictions
+//! to WebSockets, so connections from web pages are only accepted if their
+//! origin is allowed by the [`AccessControl`]. Otherwise the connection is
+//! refused.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use serde_json::{Map, Value};
+use tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response},
+    http::StatusCode,
+    Message,
+};
+
+use super::{access_control::AccessControl, block_on, server_protocol, timer_state};
+use crate::{
+    event::{CommandSink, Event, TimerQuery},
+    layout::LayoutState,
+    util::state_stream::{StateStream, Update},
+    Timer,
+};
+
+/// The broadcaster distributes the state of the timer and the layout, as well
+/// as the events of the timer, to all the connected clients. The frontend
+/// is expected to publish the states whenever it updates them, for example
+/// once per frame. It can be cloned to be shared between threads.
+#[derive(Clone, Default)]
+pub struct Broadcaster {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    clients: Vec<Sender<Input>>,
+    timer: StateStream,
+    layout: StateStream,
+}
+
+/// Everything a connection needs to react to. The connection waits for any of
+/// these, so it wakes up as soon as there is something to do.
+enum Input {
+    /// A message to send to the client.
+    Message(String),
+    /// Data received from the client.
+    Received(Vec<u8>),
+    /// The client disconnected.
+    Disconnected,
+}
+
+impl Broadcaster {
+    /// Creates a new broadcaster that doesn't have any states to distribute
+    /// yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes the current state of the timer.
+    pub fn publish_timer(&self, timer: &Timer) {
+        let state = timer_state(timer);
+        self.inner.lock().unwrap().publish_state(state, true);
+    }
+
+    /// Publishes the current state of the layout.
+    pub fn publish_layout_state(&self, state: &LayoutState) {
+        if let Ok(state) = serde_json::to_value(state) {
+            self.inner.lock().unwrap().publish_state(state, false);
+        }
+    }
+
+    /// Publishes an event that happened to the timer. This is meant to be
+    /// called by the [`CommandSink`] of the frontend for every command it
+    /// processes, regardless of where the command came from.
+    pub fn publish_event(&self, event: Event) {
+        self.inner
+            .lock()
+            .unwrap()
+            .broadcast(server_protocol::encode_event(event));
+    }
+
+    fn subscribe(&self, sender: Sender<Input>) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(timer) = inner.timer.snapshot() {
+            let _ = sender.send(Input::Message(encode("timer", timer)));
+        }
+        if let Some(layout) = inner.layout.snapshot() {
+            let _ = sender.send(Input::Message(encode("layout", layout)));
+        }
+        inner.clients.push(sender);
+    }
+}
+
+impl Inner {
+    fn publish_state(&mut self, state: Value, is_timer: bool) {
+        let (stream, kind) = if is_timer {
+            (&mut self.timer, "timer")
+        } else {
+            (&mut self.layout, "layout")
+        };
+        if let Some(update) = stream.push_value(state) {
+            self.broadcast(encode(kind, update));
+        }
+    }
+
+    fn broadcast(&mut self, message: String) {
+        // Clients that disconnected dropped their receiver.
+        self.clients
+            .retain(|client| client.send(Input::Message(message.clone())).is_ok());
+    }
+}
+
+fn encode(kind: &str, update: Update) -> String {
+    let (key, generation, value) = match update {
+        Update::Snapshot { generation, state } => (kind.to_owned(), generation, state),
+        Update::Delta { generation, patch } => (format!("{kind}Delta"), generation, patch),
+    };
+    let mut message = Map::new();
+    message.insert(key, value);
+    message.insert("generation".into(), generation.into());
+    Value::Object(message).to_string()
+}
+
+/// Accepts connections on the listener and handles them. Each connection is
+/// handled on its own thread. Only the clients that are allowed by the access
+/// control are able to connect. This blocks the current thread until accepting
+/// a connection fails.
+pub fn serve<S>(
+    listener: TcpListener,
+    command_sink: S,
+    broadcaster: Broadcaster,
+    access: AccessControl,
+) -> io::Result<()>
+where
+    S: CommandSink + TimerQuery + Clone + Send + 'static,
+{
+    loop {
+        let (stream, _) = listener.accept()?;
+        let (command_sink, broadcaster, access) =
+            (command_sink.clone(), broadcaster.clone(), access.clone());
+        thread::spawn(move || serve_connection(stream, &command_sink, &broadcaster, &access));
+    }
+}
+
+/// The stream the WebSocket is using. During the handshake it reads from the
+/// TCP stream directly. Afterwards a separate thread reads from the TCP stream,
+/// so that the connection can wait for data from the client and new states at
+/// the same time. The data it receives is provided through a buffer then.
+struct Connection {
+    stream: TcpStream,
+    received: VecDeque<u8>,
+    is_handshake_done: bool,
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.is_handshake_done {
+            self.stream.read(buf)
+        } else if self.received.is_empty() {
+            Err(io::ErrorKind::WouldBlock.into())
+        } else {
+            self.received.read(buf)
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+fn serve_connection<S: CommandSink + TimerQuery>(
+    stream: TcpStream,
+    command_sink: &S,
+    broadcaster: &Broadcaster,
+    access: &AccessControl,
+) -> tungstenite::Result<()> {
+    let connection = Connection {
+        stream: stream.try_clone()?,
+        received: VecDeque::new(),
+        is_handshake_done: false,
+    };
+    let mut socket =
+        tungstenite::accept_hdr(connection, |request: &Request, response: Response| {
+            let headers = request.headers();
+            if !access.is_origin_allowed(headers.get("Origin").map(|v| v.as_bytes())) {
+                Err(refuse(StatusCode::FORBIDDEN, "Origin not allowed"))
+            } else if !access.is_authorized(
+                request.uri().query(),
+                headers.get("Authorization").map(|v| v.as_bytes()),
+            ) {
+                Err(refuse(StatusCode::UNAUTHORIZED, "Invalid token"))
+            } else {
+                Ok(response)
+            }
+        })
+        .map_err(|e| match e {
+            tungstenite::HandshakeError::Failure(e) => e,
+            tungstenite::HandshakeError::Interrupted(_) => {
+                tungstenite::Error::Io(io::ErrorKind::WouldBlock.into())
+            }
+        })?;
+    socket.get_mut().is_handshake_done = true;
+
+    let (sender, inputs) = mpsc::channel();
+    broadcaster.subscribe(sender.clone());
+
+    let mut reader = stream;
+    thread::spawn(move || {
+        let mut buf = [0; 4 << 10];
+        loop {
+            let input = match reader.read(&mut buf) {
+                Ok(0) | Err(_) => Input::Disconnected,
+                Ok(len) => Input::Received(buf[..len].to_vec()),
+            };
+            let is_disconnected = matches!(input, Input::Disconnected);
+            if sender.send(input).is_err() || is_disconnected {
+                break;
+            }
+        }
+    });
+
+    let result = (|| -> tungstenite::Result<()> {
+        for input in inputs {
+            match input {
+                Input::Message(message) => socket.send(Message::text(message))?,
+                Input::Received(data) => {
+                    socket.get_mut().received.extend(data);
+                    loop {
+                        match socket.read() {
+                            Ok(message) if message.is_text() => {
+                                let command = message.to_text()?;
+                                let response = block_on(server_protocol::handle_command(
+                                    command,
+                                    command_sink,
+                                ));
+                                socket.send(Message::text(response))?;
+                            }
+                            Ok(_) => {}
+                            Err(tungstenite::Error::Io(e))
+                                if e.kind() == io::ErrorKind::WouldBlock =>
+                            {
+                                break
+                            }
+                            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+                            Err(e) => return Err(e),
+                        }
+                    }
+                }
+                Input::Disconnected => break,
+            }
+        }
+        Ok(())
+    })();
+
+    // This stops the thread reading from the connection.
+    let _ = socket.get_ref().stream.shutdown(Shutdown::Both);
+    result
+}
+
+fn refuse(status: StatusCode, reason: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(reason.into()));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::create_timer;
+    use serde_json::json;
+    use tungstenite::client::IntoClientRequest;
+
+    fn read_json(socket: &mut tungstenite::WebSocket<impl Read + Write>) -> Value {
+        serde_json::from_str(socket.read().unwrap().to_text().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn encodes_updates_with_their_generation() {
+        let mut stream = StateStream::new();
+        let snapshot = stream.push(&json!({ "a": 1 })).unwrap();
+        assert_eq!(
+            encode("timer", snapshot),
+            r#"{"generation":1,"timer":{"a":1}}"#
+        );
+        let delta = stream.push(&json!({ "a": 2 })).unwrap();
+        assert_eq!(
+            encode("timer", delta),
+            r#"{"generation":2,"timerDelta":{"a":2}}"#
+        );
+    }
+
+    #[test]
+    fn streams_the_timer_over_a_loopback_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let timer = create_timer(&["A", "B"]).into_shared();
+        let broadcaster = Broadcaster::new();
+        broadcaster.publish_timer(&timer.read().unwrap());
+        {
+            let (timer, broadcaster) = (timer.clone(), broadcaster.clone());
+            let access = AccessControl::new("a b&c").with_allowed_origins(["https://example.com"]);
+            thread::spawn(move || serve(listener, timer, broadcaster, access));
+        }
+
+        assert!(tungstenite::connect(format!("ws://{address}/")).is_err());
+        assert!(tungstenite::connect(format!("ws://{address}/?token=a+b")).is_err());
+        let mut request = format!("ws://{address}/?token=a+b%26c")
+            .into_client_request()
+            .unwrap();
+        request
+            .headers_mut()
+            .insert("Origin", "https://evil.example".parse().unwrap());
+        assert!(tungstenite::connect(request).is_err());
+
+        let (mut socket, _) =
+            tungstenite::connect(format!("ws://{address}/?token=a+b%26c")).unwrap();
+
+        let snapshot = read_json(&mut socket);
+        assert_eq!(snapshot["generation"], 1);
+        assert_eq!(snapshot["timer"]["phase"], "NotRunning");
+        assert_eq!(snapshot["timer"]["currentSplitIndex"], Value::Null);
+
+        socket
+            .send(Message::text(r#"{ "command": "splitOrStart" }"#))
+            .unwrap();
+        assert!(read_json(&mut socket).get("success").is_some());
+
+        // The connection wakes up as soon as a new state is published.
+        broadcaster.publish_timer(&timer.read().unwrap());
+        let delta = read_json(&mut socket);
+        assert_eq!(delta["generation"], 2);
+        assert_eq!(delta["timerDelta"]["phase"], "Running");
+        assert_eq!(delta["timerDelta"]["currentSplitIndex"], 0);
+
+        // Resetting the split index to null needs the full state again.
+        socket
+            .send(Message::text(r#"{ "command": "reset" }"#))
+            .unwrap();
+        assert!(read_json(&mut socket).get("success").is_some());
+        broadcaster.publish_timer(&timer.read().unwrap());
+        let snapshot = read_json(&mut socket);
+        assert_eq!(snapshot["generation"], 3);
+        assert_eq!(snapshot["timer"]["currentSplitIndex"], Value::Null);
+
+        socket.close(None).unwrap();
+    }
+}
@@ -10,10 +10,25 @@ cfg_if::cfg_if! {
             pub fn powf(x: f32, y: f32) -> f32 {
                 x.powf(y)
             }
+
+            #[inline(always)]
+            pub fn sqrt(x: f32) -> f32 {
+                x.sqrt()
+            }
+
+            #[inline(always)]
+            pub fn sin(x: f32) -> f32 {
+                x.sin()
+            }
+
+            #[inline(always)]
+            pub fn cos(x: f32) -> f32 {
+                x.cos()
+            }
         }
     } else {
         pub mod f32 {
-            pub use libm::{fabsf as abs, powf};
+            pub use libm::{cosf as cos, fabsf as abs, powf, sinf as sin, sqrtf as sqrt};
         }
     }
 }
@@ -2,12 +2,8 @@ use crate::{
     component::detailed_timer::State,
     layout::LayoutState,
     rendering::{
-        component::timer,
-        consts::{vertical_padding, BOTH_PADDINGS, PADDING},
-        font::CachedLabel,
-        resource::ResourceAllocator,
-        scene::Layer,
-        solid, RenderContext,
+        component::timer, consts::vertical_padding, font::CachedLabel, resource::ResourceAllocator,
+        scene::Layer, solid, RenderContext,
     },
 };
 
@@ -44,14 +40,15 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
 ) {
     context.render_background([width, height], &component.background);
 
+    let padding = context.style.padding;
     let vertical_padding = vertical_padding(height);
     let icon_size = height - 2.0 * vertical_padding;
 
     let left_side = if let Some(icon) = context.create_image(&component.icon) {
-        context.render_image([PADDING, vertical_padding], [icon_size, icon_size], icon);
-        BOTH_PADDINGS + icon_size
+        context.render_image([padding, vertical_padding], [icon_size, icon_size], icon);
+        2.0 * padding + icon_size
     } else {
-        PADDING
+        padding
     };
 
     let total_height = component.timer.height + component.segment_timer.height;
@@ -150,7 +147,7 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
             .max(time_width);
     }
 
-    let time_x = name_end + PADDING + time_width;
+    let time_x = name_end + padding + time_width;
 
     let comparison_times_color = solid(
         &component
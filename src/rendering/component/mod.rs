@@ -1,9 +1,9 @@
 use crate::layout::{ComponentState, LayoutState};
 
 use super::{
-    consts::{DEFAULT_COMPONENT_HEIGHT, PSEUDO_PIXELS, SEPARATOR_THICKNESS, TWO_ROW_HEIGHT},
+    consts::{DEFAULT_COMPONENT_HEIGHT, PSEUDO_PIXELS, TWO_ROW_HEIGHT},
     resource::ResourceAllocator,
-    RenderContext,
+    RenderContext, Style,
 };
 
 pub mod blank_space;
@@ -69,21 +69,21 @@ impl<L> Cache<L> {
     }
 }
 
-pub fn layout_width(layout: &LayoutState) -> f32 {
-    layout.components.iter().map(width).sum()
+pub fn layout_width(layout: &LayoutState, style: &Style) -> f32 {
+    layout.components.iter().map(|c| width(c, style)).sum()
 }
 
-pub fn layout_height(layout: &LayoutState) -> f32 {
-    layout.components.iter().map(height).sum()
+pub fn layout_height(layout: &LayoutState, style: &Style) -> f32 {
+    layout.components.iter().map(|c| height(c, style)).sum()
 }
 
-pub fn width(component: &ComponentState) -> f32 {
+pub fn width(component: &ComponentState, style: &Style) -> f32 {
     match component {
         ComponentState::BlankSpace(state) => state.size as f32 * PSEUDO_PIXELS,
         ComponentState::DetailedTimer(_) => 7.0,
         ComponentState::Graph(_) => 7.0,
         ComponentState::KeyValue(_) => 6.0,
-        ComponentState::Separator(_) => SEPARATOR_THICKNESS,
+        ComponentState::Separator(_) => style.separator_thickness,
         ComponentState::Splits(state) => {
             let column_count = 2.0; // FIXME: Not always 2.
             let column_width = 2.75; // FIXME: Not always 2.75; difficult to calculate without a renderer.
@@ -96,7 +96,7 @@ pub fn width(component: &ComponentState) -> f32 {
     }
 }
 
-pub fn height(component: &ComponentState) -> f32 {
+pub fn height(component: &ComponentState, style: &Style) -> f32 {
     match component {
         ComponentState::BlankSpace(state) => state.size as f32 * PSEUDO_PIXELS,
         ComponentState::DetailedTimer(state) => {
@@ -110,7 +110,7 @@ pub fn height(component: &ComponentState) -> f32 {
                 DEFAULT_COMPONENT_HEIGHT
             }
         }
-        ComponentState::Separator(_) => SEPARATOR_THICKNESS,
+        ComponentState::Separator(_) => style.separator_thickness,
         ComponentState::Splits(state) => {
             state.splits.len() as f32
                 * if state.display_two_rows {
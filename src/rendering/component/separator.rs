@@ -11,5 +11,10 @@ pub(in crate::rendering) fn render(
     _component: &State,
     layout_state: &LayoutState,
 ) {
-    context.render_background(dim, &Gradient::Plain(layout_state.separators_color));
+    // Separators are too thin to have rounded corners.
+    context.render_rectangle(
+        [0.0, 0.0],
+        dim,
+        &Gradient::Plain(layout_state.separators_color),
+    );
 }
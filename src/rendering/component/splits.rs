@@ -4,8 +4,8 @@ use crate::{
     platform::prelude::*,
     rendering::{
         consts::{
-            vertical_padding, BOTH_PADDINGS, DEFAULT_COMPONENT_HEIGHT, DEFAULT_TEXT_SIZE, PADDING,
-            TEXT_ALIGN_BOTTOM, TEXT_ALIGN_TOP, THIN_SEPARATOR_THICKNESS, TWO_ROW_HEIGHT,
+            vertical_padding, DEFAULT_COMPONENT_HEIGHT, DEFAULT_TEXT_SIZE, TEXT_ALIGN_BOTTOM,
+            TEXT_ALIGN_TOP, TWO_ROW_HEIGHT,
         },
         font::CachedLabel,
         resource::ResourceAllocator,
@@ -143,7 +143,7 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
 
     let split_background = match component.background {
         ListGradient::Same(gradient) => {
-            context.render_rectangle([0.0, 0.0], [width, height], &gradient);
+            context.render_background([width, height], &gradient);
             None
         }
         ListGradient::Alternating(even, odd) => Some((Gradient::Plain(even), Gradient::Plain(odd))),
@@ -158,6 +158,8 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
         DEFAULT_COMPONENT_HEIGHT
     };
 
+    let padding = context.style.padding;
+    let thin_separator_thickness = context.style.thin_separator_thickness;
     let vertical_padding = vertical_padding(split_height);
 
     let (split_width, (delta_x, delta_y), separator_pos, split_background_bottom_right, icon_y) =
@@ -166,17 +168,17 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
             (
                 split_width,
                 (split_width, 0.0),
-                [split_width - THIN_SEPARATOR_THICKNESS, 0.0],
-                [split_width - THIN_SEPARATOR_THICKNESS, split_height],
+                [split_width - thin_separator_thickness, 0.0],
+                [split_width - thin_separator_thickness, split_height],
                 vertical_padding,
             )
         } else {
             (
                 width,
                 (0.0, split_height),
-                [0.0, split_height - THIN_SEPARATOR_THICKNESS],
-                [width, split_height - THIN_SEPARATOR_THICKNESS],
-                vertical_padding - 0.5 * THIN_SEPARATOR_THICKNESS,
+                [0.0, split_height - thin_separator_thickness],
+                [width, split_height - thin_separator_thickness],
+                vertical_padding - 0.5 * thin_separator_thickness,
             )
         };
 
@@ -189,7 +191,7 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
                 .column_labels
                 .resize_with(column_labels.len(), CachedLabel::new);
 
-            let mut right_x = width - PADDING;
+            let mut right_x = width - padding;
             for ((label, column_cache), (max_width, _)) in column_labels
                 .iter()
                 .zip(&mut cache.column_labels)
@@ -207,13 +209,13 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
                 if label_width > *max_width {
                     *max_width = label_width;
                 }
-                right_x -= *max_width + PADDING;
+                right_x -= *max_width + padding;
             }
 
             context.translate(0.0, DEFAULT_COMPONENT_HEIGHT);
             context.render_rectangle(
-                [0.0, -THIN_SEPARATOR_THICKNESS],
-                [width, THIN_SEPARATOR_THICKNESS],
+                [0.0, -thin_separator_thickness],
+                [width, thin_separator_thickness],
                 &Gradient::Plain(layout_state.separators_color),
             );
         }
@@ -221,9 +223,9 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
 
     let icon_size = split_height - 2.0 * vertical_padding;
     let icon_right = if component.has_icons {
-        BOTH_PADDINGS + icon_size
+        2.0 * padding + icon_size
     } else {
-        PADDING
+        padding
     };
 
     cache
@@ -251,10 +253,10 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
 
         {
             if let Some(icon) = context.create_image(&split.icon) {
-                context.render_image([PADDING, icon_y], [icon_size, icon_size], icon);
+                context.render_image([padding, icon_y], [icon_size, icon_size], icon);
             }
 
-            let mut left_x = split_width - PADDING;
+            let mut left_x = split_width - padding;
             let mut right_x = left_x;
 
             split_cache
@@ -277,7 +279,7 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
                         solid(&column.visual_color),
                     );
                 }
-                right_x -= max_width + PADDING;
+                right_x -= max_width + padding;
             }
 
             if display_two_rows {
@@ -290,7 +292,7 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
                 [icon_right, TEXT_ALIGN_TOP],
                 DEFAULT_TEXT_SIZE,
                 text_color,
-                left_x - PADDING,
+                left_x - padding,
             );
         }
         context.translate(delta_x, delta_y);
@@ -299,13 +301,13 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
     if component.show_final_separator {
         let (pos, end) = if layout_state.direction == LayoutDirection::Horizontal {
             (
-                [-split_width - THIN_SEPARATOR_THICKNESS, 0.0],
-                [-split_width + THIN_SEPARATOR_THICKNESS, split_height],
+                [-split_width - thin_separator_thickness, 0.0],
+                [-split_width + thin_separator_thickness, split_height],
             )
         } else {
             (
-                [0.0, -split_height - THIN_SEPARATOR_THICKNESS],
-                [split_width, -split_height + THIN_SEPARATOR_THICKNESS],
+                [0.0, -split_height - thin_separator_thickness],
+                [split_width, -split_height + thin_separator_thickness],
             )
         };
         context.render_rectangle(pos, end, &Gradient::Plain(layout_state.separators_color));
@@ -2,7 +2,7 @@ use crate::{
     component::text::{State, TextState},
     layout::{LayoutDirection, LayoutState},
     rendering::{
-        consts::{DEFAULT_TEXT_SIZE, PADDING, TEXT_ALIGN_TOP},
+        consts::{DEFAULT_TEXT_SIZE, TEXT_ALIGN_TOP},
         font::{AbbreviatedLabel, CachedLabel},
         resource::ResourceAllocator,
         solid, RenderContext,
@@ -31,12 +31,13 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
     layout_state: &LayoutState,
 ) {
     context.render_background([width, height], &component.background);
+    let padding = context.style.padding;
     match &component.text {
         TextState::Center(text) => context.render_text_centered(
             text,
             &mut cache.label2,
-            PADDING,
-            width - PADDING,
+            padding,
+            width - padding,
             [0.5 * width, TEXT_ALIGN_TOP],
             DEFAULT_TEXT_SIZE,
             solid(
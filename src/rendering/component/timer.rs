@@ -1,8 +1,7 @@
 use crate::{
     component::timer::State,
     rendering::{
-        consts::PADDING, font::CachedLabel, resource::ResourceAllocator, scene::Layer, FillShader,
-        RenderContext,
+        font::CachedLabel, resource::ResourceAllocator, scene::Layer, FillShader, RenderContext,
     },
 };
 
@@ -32,11 +31,12 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
         component.bottom_color.to_array(),
     );
     let render_target = Layer::from_updates_frequently(component.updates_frequently);
+    let padding = context.style.padding;
     let x = context.render_timer(
         &component.fraction,
         &mut cache.fraction,
         render_target,
-        [width - PADDING, 0.85 * height],
+        [width - padding, 0.85 * height],
         0.7 * height,
         shader,
     );
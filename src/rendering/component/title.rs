@@ -6,8 +6,8 @@ use crate::{
     platform::prelude::*,
     rendering::{
         consts::{
-            vertical_padding, BOTH_PADDINGS, DEFAULT_TEXT_SIZE, PADDING, TEXT_ALIGN_BOTTOM,
-            TEXT_ALIGN_CENTER, TEXT_ALIGN_TOP,
+            vertical_padding, DEFAULT_TEXT_SIZE, TEXT_ALIGN_BOTTOM, TEXT_ALIGN_CENTER,
+            TEXT_ALIGN_TOP,
         },
         font::{AbbreviatedLabel, CachedLabel},
         resource::ResourceAllocator,
@@ -43,14 +43,15 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
     context.render_background([width, height], &component.background);
     let text_color = component.text_color.unwrap_or(layout_state.text_color);
     let text_color = solid(&text_color);
+    let padding = context.style.padding;
 
     let left_bound = if let Some(icon) = context.create_image(&component.icon) {
         let vertical_padding = vertical_padding(height);
         let icon_size = height - 2.0 * vertical_padding;
-        context.render_image([PADDING, vertical_padding], [icon_size, icon_size], icon);
-        BOTH_PADDINGS + icon_size
+        context.render_image([padding, vertical_padding], [icon_size, icon_size], icon);
+        2.0 * padding + icon_size
     } else {
-        PADDING
+        padding
     };
 
     let line_x = if component.is_centered {
@@ -77,10 +78,10 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
         attempts,
         &mut cache.attempts,
         Layer::Bottom,
-        [width - PADDING, height + TEXT_ALIGN_BOTTOM],
+        [width - padding, height + TEXT_ALIGN_BOTTOM],
         DEFAULT_TEXT_SIZE,
         text_color,
-    ) - PADDING;
+    ) - padding;
 
     let (line1_y, line1_end_x) = if !component.line2.is_empty() {
         context.render_abbreviated_text_align(
@@ -93,7 +94,7 @@ pub(in crate::rendering) fn render<A: ResourceAllocator>(
             component.is_centered,
             text_color,
         );
-        (TEXT_ALIGN_TOP, width - PADDING)
+        (TEXT_ALIGN_TOP, width - padding)
     } else {
         (0.5 * height + TEXT_ALIGN_CENTER, line2_end_x)
     };
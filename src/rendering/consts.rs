@@ -1,5 +1,4 @@
 pub const PADDING: f32 = 0.35;
-pub const BOTH_PADDINGS: f32 = 2.0 * PADDING;
 const BOTH_VERTICAL_PADDINGS: f32 = DEFAULT_COMPONENT_HEIGHT - DEFAULT_TEXT_ASCENT_DESCENT_DISTANCE;
 const VERTICAL_PADDING: f32 = BOTH_VERTICAL_PADDINGS / 2.0;
 const ICON_MIN_VERTICAL_PADDING: f32 = 0.1;
@@ -15,6 +14,7 @@ pub const TEXT_ALIGN_CENTER: f32 = DEFAULT_TEXT_ASCENT - DEFAULT_TEXT_ASCENT_DES
 pub const SEPARATOR_THICKNESS: f32 = 0.1;
 pub const THIN_SEPARATOR_THICKNESS: f32 = SEPARATOR_THICKNESS / 2.0;
 pub const PSEUDO_PIXELS: f32 = 1.0 / 24.0;
+pub const DROP_SHADOW_OFFSET: f32 = 2.0 * PSEUDO_PIXELS;
 pub const DEFAULT_VERTICAL_WIDTH: f32 = 11.5;
 
 pub fn vertical_padding(height: f32) -> f32 {
@@ -12,7 +12,8 @@ use crate::{
 use cosmic_text::{
     fontdb::{Database, Query, Source, ID},
     rustybuzz::ttf_parser::{GlyphId, OutlineBuilder},
-    Attrs, AttrsList, Family, FontSystem, ShapeBuffer, ShapeLine, Shaping, Stretch, Style, Weight,
+    Attrs, AttrsList, Family, FontSystem, ShapeBuffer, ShapeLine, ShapeWord, Shaping, Stretch,
+    Style, Weight,
 };
 use hashbrown::HashMap;
 
@@ -64,6 +65,21 @@ impl<P: SharedOwnership> TextEngine<P> {
         }
     }
 
+    /// Loads the font data provided, so that its fonts can be referred to by
+    /// their family name in the font settings of a layout. This way a layout
+    /// can bundle its fonts and render the same on every machine. TrueType and
+    /// OpenType fonts and font collections are supported. Returns whether the
+    /// data contained any fonts. Fonts that already got created are not
+    /// affected, so they need to be created again for the newly loaded fonts to
+    /// be used.
+    pub fn load_font(&mut self, data: Vec<u8>) -> bool {
+        !self
+            .font_system
+            .db_mut()
+            .load_font_source(Source::Binary(Arc::new(data)))
+            .is_empty()
+    }
+
     /// Creates a new font. You can call this directly from a
     /// [`ResourceAllocator`](super::ResourceAllocator).
     pub fn create_font(&mut self, font: Option<&settings::Font>, kind: FontKind) -> Font {
@@ -235,99 +251,96 @@ impl<P: SharedOwnership> TextEngine<P> {
         );
         let [mut x, mut y] = [0.0; 2];
 
+        let words = visual_words(&shape_line);
+
         if let Some(monotonic) = &font.monotonic {
-            for span in &shape_line.spans {
-                for word in &span.words {
-                    if !word.blank {
-                        let mut glyphs = word.glyphs.iter();
-                        while let Some(glyph) = if shape_line.rtl {
-                            glyphs.next_back()
-                        } else {
-                            glyphs.next()
-                        } {
-                            let cached_glyph = cache_glyph(
-                                &mut self.glyph_cache,
-                                &mut self.font_system,
-                                glyph.font_id,
-                                glyph.glyph_id,
-                                &mut path_builder,
-                            );
-
-                            // FIXME: We use the x advance of the individual
-                            // glyph to remove any kerning that happened during
-                            // shaping. This is a workaround for the fact that
-                            // cosmic-text doesn't provide a way to turn off
-                            // kerning (and / or enable tabular nums) at the
-                            // moment.
-                            // https://github.com/pop-os/cosmic-text/issues/229
-                            let unkerned_x_advance = cached_glyph.unkerned_x_advance;
-
-                            let (x_advance, x_offset) = if monotonic
-                                .digit_glyphs
-                                .contains(&(glyph.font_id, glyph.glyph_id))
-                            {
-                                (
-                                    monotonic.digit_width,
-                                    0.5 * (monotonic.digit_width - unkerned_x_advance)
-                                        + glyph.x_offset,
-                                )
-                            } else {
-                                (unkerned_x_advance, glyph.x_offset)
-                            };
-
-                            label
-                                .glyphs
-                                .extend(cached_glyph.paths.iter().map(|(color, path)| Glyph {
-                                    color: *color,
-                                    x: x + x_offset,
-                                    y: y - glyph.y_offset,
-                                    path: path.share(),
-                                    scale: cached_glyph.scale,
-                                }));
-
-                            x += x_advance;
-                            y -= glyph.y_advance;
-                        }
+            for word in &words {
+                if !word.blank {
+                    let mut glyphs = word.glyphs.iter();
+                    while let Some(glyph) = if shape_line.rtl {
+                        glyphs.next_back()
                     } else {
-                        x += word.width(1.0);
+                        glyphs.next()
+                    } {
+                        let cached_glyph = cache_glyph(
+                            &mut self.glyph_cache,
+                            &mut self.font_system,
+                            glyph.font_id,
+                            glyph.glyph_id,
+                            &mut path_builder,
+                        );
+
+                        // FIXME: We use the x advance of the individual
+                        // glyph to remove any kerning that happened during
+                        // shaping. This is a workaround for the fact that
+                        // cosmic-text doesn't provide a way to turn off
+                        // kerning (and / or enable tabular nums) at the
+                        // moment.
+                        // https://github.com/pop-os/cosmic-text/issues/229
+                        let unkerned_x_advance = cached_glyph.unkerned_x_advance;
+
+                        let (x_advance, x_offset) = if monotonic
+                            .digit_glyphs
+                            .contains(&(glyph.font_id, glyph.glyph_id))
+                        {
+                            (
+                                monotonic.digit_width,
+                                0.5 * (monotonic.digit_width - unkerned_x_advance) + glyph.x_offset,
+                            )
+                        } else {
+                            (unkerned_x_advance, glyph.x_offset)
+                        };
+
+                        label
+                            .glyphs
+                            .extend(cached_glyph.paths.iter().map(|(color, path)| Glyph {
+                                color: *color,
+                                x: x + x_offset,
+                                y: y - glyph.y_offset,
+                                path: path.share(),
+                                scale: cached_glyph.scale,
+                            }));
+
+                        x += x_advance;
+                        y -= glyph.y_advance;
                     }
+                } else {
+                    x += word.width(1.0);
                 }
             }
         } else {
-            for span in &shape_line.spans {
-                for word in &span.words {
-                    if !word.blank {
-                        let [mut glyph_x, mut glyph_y] = [x, y];
-                        let mut glyphs = word.glyphs.iter();
-                        while let Some(glyph) = if shape_line.rtl {
-                            glyphs.next_back()
-                        } else {
-                            glyphs.next()
-                        } {
-                            let cached_glyph = cache_glyph(
-                                &mut self.glyph_cache,
-                                &mut self.font_system,
-                                glyph.font_id,
-                                glyph.glyph_id,
-                                &mut path_builder,
-                            );
-
-                            label
-                                .glyphs
-                                .extend(cached_glyph.paths.iter().map(|(color, path)| Glyph {
-                                    color: *color,
-                                    x: glyph_x + glyph.x_offset,
-                                    y: glyph_y - glyph.y_offset,
-                                    path: path.share(),
-                                    scale: cached_glyph.scale,
-                                }));
-
-                            glyph_x += glyph.x_advance;
-                            glyph_y -= glyph.y_advance;
-                        }
+            for word in &words {
+                if !word.blank {
+                    let [mut glyph_x, mut glyph_y] = [x, y];
+                    let mut glyphs = word.glyphs.iter();
+                    while let Some(glyph) = if shape_line.rtl {
+                        glyphs.next_back()
+                    } else {
+                        glyphs.next()
+                    } {
+                        let cached_glyph = cache_glyph(
+                            &mut self.glyph_cache,
+                            &mut self.font_system,
+                            glyph.font_id,
+                            glyph.glyph_id,
+                            &mut path_builder,
+                        );
+
+                        label
+                            .glyphs
+                            .extend(cached_glyph.paths.iter().map(|(color, path)| Glyph {
+                                color: *color,
+                                x: glyph_x + glyph.x_offset,
+                                y: glyph_y - glyph.y_offset,
+                                path: path.share(),
+                                scale: cached_glyph.scale,
+                            }));
+
+                        glyph_x += glyph.x_advance;
+                        glyph_y -= glyph.y_advance;
                     }
-                    x += word.width(1.0);
                 }
+                x += word.width(1.0);
             }
         }
 
@@ -377,6 +390,45 @@ impl<P: SharedOwnership> TextEngine<P> {
     }
 }
 
+/// Returns the words of the line in the order they need to be laid out from
+/// left to right. cosmic-text keeps the words in the order of the paragraph's
+/// direction, except for the spans that go against it, which it reverses. So we
+/// bring them back into logical order and then apply the reordering of the
+/// Unicode Bidirectional Algorithm (rule L2), so mixed left-to-right and
+/// right-to-left text, such as Arabic or Hebrew segment names with Latin
+/// numbers in them, shows up in the correct order.
+fn visual_words(shape_line: &ShapeLine) -> Vec<&ShapeWord> {
+    let mut words = Vec::new();
+    for span in &shape_line.spans {
+        let level = span.level.number();
+        if span.level.is_rtl() != shape_line.rtl {
+            words.extend(span.words.iter().rev().map(|word| (level, word)));
+        } else {
+            words.extend(span.words.iter().map(|word| (level, word)));
+        }
+    }
+    reorder_visually(&mut words);
+    words.into_iter().map(|(_, word)| word).collect()
+}
+
+/// Reverses every sequence of items that is at a certain embedding level or
+/// higher, from the highest level down to the lowest odd level, which turns
+/// the logical order into the visual order.
+fn reorder_visually<T>(items: &mut [(u8, T)]) {
+    let Some(lowest_odd_level) = items.iter().map(|&(l, _)| l).filter(|l| l % 2 == 1).min() else {
+        return;
+    };
+    let highest_level = items.iter().map(|&(l, _)| l).max().unwrap_or_default();
+
+    for level in (lowest_odd_level..=highest_level).rev() {
+        for run in items.chunk_by_mut(|(a, _), (b, _)| (*a >= level) == (*b >= level)) {
+            if run[0].0 >= level {
+                run.reverse();
+            }
+        }
+    }
+}
+
 fn cache_glyph<'gc, P, PB: PathBuilder<Path = P>>(
     glyph_cache: &'gc mut HashMap<(ID, u16), CachedGlyph<P>>,
     font_system: &mut FontSystem,
@@ -484,3 +536,24 @@ pub struct Glyph<P> {
     /// The scale of the glyph.
     pub scale: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::reorder_visually;
+
+    #[test]
+    fn bidi_reordering() {
+        // Latin text with an embedded Arabic part that contains a number.
+        let mut items = [(0, 'a'), (1, 'b'), (2, 'c'), (2, 'd'), (1, 'e'), (0, 'f')];
+        reorder_visually(&mut items);
+        assert_eq!(items.map(|(_, c)| c), ['a', 'e', 'c', 'd', 'b', 'f']);
+
+        let mut items = [(1, 'a'), (1, 'b'), (1, 'c')];
+        reorder_visually(&mut items);
+        assert_eq!(items.map(|(_, c)| c), ['c', 'b', 'a']);
+
+        let mut items = [(0, 'a'), (0, 'b')];
+        reorder_visually(&mut items);
+        assert_eq!(items.map(|(_, c)| c), ['a', 'b']);
+    }
+}
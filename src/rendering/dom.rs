@@ -0,0 +1,489 @@
+//! Provides a renderer that describes the layout as a tree of DOM elements.
+//! Unlike the other renderers, this one doesn't position anything itself.
+//! Every component is turned into elements with stable class names and inline
+//! styles for the colors, so web frontends can map the tree straight into
+//! their own virtual DOM and leave the positioning to CSS. The tree only
+//! depends on the layout state, so rendering the same state twice results in
+//! the same tree, which keeps the diffs of the virtual DOM small.
+//!
+//! The classes that are used are the following:
+//!
+//! - `layout` with either `vertical` or `horizontal` for the root element.
+//! - `component` with the kind of component, such as `timer` or `splits`.
+//! - `two-rows` for components that are shown in two rows.
+//! - `key`, `value`, `time`, `fraction`, `name`, `icon`, `line`, `labels`,
+//!   `label`, `split`, `column`, `current-split`, `thin-separator` and
+//!   `final-separator` for the parts of the components.
+//! - A semantic color, such as `ahead-gaining-time` or `best-segment`, for
+//!   values that have one.
+//!
+//! Images are described by `img` elements with a `data-image-id` attribute
+//! that can be looked up in the [`ImageCache`](crate::settings::ImageCache).
+//!
+//! The style parameters of the layout are provided as CSS variables on the
+//! root element, so the stylesheet of the frontend can honor them:
+//! `--corner-radius` in pixels of a layout with rows that are 24 pixels high,
+//! and `--separator-thickness` and `--padding-scale` as factors of their
+//! defaults. The drop shadow is applied as a `text-shadow` directly.
+
+use alloc::collections::BTreeMap;
+use core::fmt::Write;
+
+use serde_derive::Serialize;
+
+use crate::{
+    component::{
+        blank_space, detailed_timer, graph, key_value, splits,
+        text::{self, TextState},
+        timer, title,
+    },
+    layout::{ComponentState, LayoutDirection, LayoutState},
+    platform::prelude::*,
+    settings::{Color, Gradient, ImageId, LayoutBackground, ListGradient, SemanticColor},
+};
+
+use super::consts::{DEFAULT_TEXT_SIZE, DROP_SHADOW_OFFSET};
+
+/// An element of the DOM tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Element {
+    /// The tag of the element, such as `div`.
+    pub tag: &'static str,
+    /// The classes of the element.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<String>,
+    /// The attributes of the element, other than the classes and the style.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<&'static str, String>,
+    /// The inline styles of the element, mapping CSS properties to their
+    /// values.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub style: BTreeMap<&'static str, String>,
+    /// The text content of the element.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// The child elements.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Element>,
+}
+
+impl Element {
+    fn new(tag: &'static str, classes: &[&str]) -> Self {
+        Self {
+            tag,
+            classes: classes.iter().map(|&class| class.into()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn div(classes: &[&str]) -> Self {
+        Self::new("div", classes)
+    }
+
+    fn text(classes: &[&str], text: &str) -> Self {
+        Self {
+            text: Some(text.into()),
+            ..Self::div(classes)
+        }
+    }
+
+    fn icon(icon: &ImageId) -> Option<Self> {
+        if icon.is_empty() {
+            return None;
+        }
+        let mut element = Self::new("img", &["icon"]);
+        element.attributes.insert("data-image-id", icon.to_string());
+        Some(element)
+    }
+
+    fn class(mut self, class: &str) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+
+    fn semantic_color(self, semantic_color: SemanticColor) -> Self {
+        match semantic_color_class(semantic_color) {
+            Some(class) => self.class(class),
+            None => self,
+        }
+    }
+
+    fn color(mut self, color: Option<Color>) -> Self {
+        if let Some(color) = color {
+            self.style.insert("color", css_color(color));
+        }
+        self
+    }
+
+    fn background(mut self, gradient: &Gradient) -> Self {
+        if let Some(background) = css_gradient(gradient) {
+            self.style.insert("background", background);
+        }
+        self
+    }
+
+    fn height(mut self, height: u32) -> Self {
+        self.style.insert("height", format!("{height}px"));
+        self
+    }
+
+    fn child(mut self, child: Element) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    fn children(mut self, children: impl IntoIterator<Item = Element>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Encodes the element and all of its children as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// Describes the layout state as a tree of DOM elements.
+pub fn render(state: &LayoutState) -> Element {
+    let mut root = Element::div(&[
+        "layout",
+        match state.direction {
+            LayoutDirection::Vertical => "vertical",
+            LayoutDirection::Horizontal => "horizontal",
+        },
+    ])
+    .color(Some(state.text_color));
+
+    match &state.background {
+        LayoutBackground::Gradient(gradient) => root = root.background(gradient),
+        LayoutBackground::Image(image) => {
+            if let Some(mut element) = Element::icon(&image.image) {
+                element.classes[0] = "background".into();
+                element.style.insert("opacity", image.opacity.to_string());
+                element
+                    .style
+                    .insert("filter", format!("brightness({})", image.brightness));
+                root = root.child(element);
+            }
+        }
+    }
+
+    if let Some(font) = &state.text_font {
+        root.style.insert("font-family", font.family.clone());
+    }
+    for (variable, color) in [
+        ("--separators-color", state.separators_color),
+        ("--thin-separators-color", state.thin_separators_color),
+    ] {
+        root.style.insert(variable, css_color(color));
+    }
+    root.style
+        .insert("--corner-radius", state.corner_radius.to_string());
+    for (variable, percent) in [
+        ("--separator-thickness", state.separator_thickness),
+        ("--padding-scale", state.padding_scale),
+    ] {
+        root.style
+            .insert(variable, (percent as f32 / 100.0).to_string());
+    }
+    if let Some(shadow) = state.drop_shadow {
+        let offset = DROP_SHADOW_OFFSET / DEFAULT_TEXT_SIZE;
+        root.style.insert(
+            "text-shadow",
+            format!("{offset}em {offset}em {}", css_color(shadow)),
+        );
+    }
+
+    root.children(state.components.iter().map(|component| match component {
+        ComponentState::BlankSpace(component) => blank_space_element(component),
+        ComponentState::DetailedTimer(component) => detailed_timer_element(component, state),
+        ComponentState::Graph(component) => graph_element(component),
+        ComponentState::KeyValue(component) => key_value_element(component, state),
+        ComponentState::Separator(_) => Element::div(&["component", "separator"]),
+        ComponentState::Splits(component) => splits_element(component, state),
+        ComponentState::Text(component) => text_element(component),
+        ComponentState::Timer(component) => timer_element(component, state).class("component"),
+        ComponentState::Title(component) => title_element(component),
+    }))
+}
+
+fn blank_space_element(component: &blank_space::State) -> Element {
+    Element::div(&["component", "blank-space"])
+        .background(&component.background)
+        .height(component.size)
+}
+
+fn detailed_timer_element(component: &detailed_timer::State, state: &LayoutState) -> Element {
+    let mut element =
+        Element::div(&["component", "detailed-timer"]).background(&component.background);
+    element.children.extend(Element::icon(&component.icon));
+    if let Some(segment_name) = &component.segment_name {
+        element = element
+            .child(Element::text(&["name"], segment_name).color(component.segment_name_color));
+    }
+    element
+        .children(
+            [&component.comparison1, &component.comparison2]
+                .into_iter()
+                .flatten()
+                .map(|comparison| {
+                    Element::div(&["comparison"])
+                        .child(
+                            Element::text(&["key"], &comparison.name)
+                                .color(component.comparison_names_color),
+                        )
+                        .child(
+                            Element::text(&["value"], &comparison.time)
+                                .color(component.comparison_times_color),
+                        )
+                }),
+        )
+        .child(timer_element(&component.timer, state))
+        .child(timer_element(&component.segment_timer, state).class("segment-timer"))
+}
+
+fn graph_element(component: &graph::State) -> Element {
+    let mut svg = Element::new("svg", &["component", "graph"]).height(component.height);
+    svg.attributes.insert("viewBox", "0 0 1 1".into());
+    svg.attributes.insert("preserveAspectRatio", "none".into());
+
+    let rect = |y: f32, height: f32, color: Color| {
+        let mut rect = Element::new("rect", &[]);
+        rect.attributes.insert("y", y.to_string());
+        rect.attributes.insert("width", "1".into());
+        rect.attributes.insert("height", height.to_string());
+        rect.attributes.insert("fill", css_color(color));
+        rect
+    };
+    svg = svg
+        .child(rect(0.0, component.middle, component.top_background_color))
+        .child(rect(
+            component.middle,
+            1.0 - component.middle,
+            component.bottom_background_color,
+        ));
+
+    let mut points = String::new();
+    for point in &component.points {
+        let _ = write!(points, "{},{} ", point.x, point.y);
+    }
+    let mut line = Element::new("polyline", &["line"]);
+    line.attributes.insert("points", points.trim_end().into());
+    line.attributes.insert("fill", "none".into());
+    line.attributes
+        .insert("stroke", css_color(component.graph_lines_color));
+    line.attributes
+        .insert("vector-effect", "non-scaling-stroke".into());
+
+    svg.child(line)
+}
+
+fn key_value_element(component: &key_value::State, state: &LayoutState) -> Element {
+    let mut element = Element::div(&["component", "key-value"]).background(&component.background);
+    if component.display_two_rows {
+        element = element.class("two-rows");
+    }
+    let mut value = Element::text(&["value"], &component.value)
+        .semantic_color(component.semantic_color)
+        .color(component.value_color);
+    if let Some(font) = &state.times_font {
+        value.style.insert("font-family", font.family.clone());
+    }
+    element
+        .child(Element::text(&["key"], &component.key).color(component.key_color))
+        .child(value)
+}
+
+fn splits_element(component: &splits::State, state: &LayoutState) -> Element {
+    let mut element = Element::div(&["component", "splits"]);
+    if component.display_two_rows {
+        element = element.class("two-rows");
+    }
+
+    if let Some(labels) = &component.column_labels {
+        element = element.child(
+            Element::div(&["labels"])
+                .children(labels.iter().map(|label| Element::text(&["label"], label))),
+        );
+    }
+
+    let len = component.splits.len();
+    element = element.children(component.splits.iter().enumerate().map(|(i, split)| {
+        let background = match component.background {
+            ListGradient::Same(gradient) => gradient,
+            ListGradient::Alternating(even, odd) => {
+                Gradient::Plain(if split.index % 2 == 0 { even } else { odd })
+            }
+        };
+        let mut row = Element::div(&["split"]).background(&background);
+        row.attributes.insert("data-index", split.index.to_string());
+        if split.is_current_split {
+            row = row
+                .class("current-split")
+                .background(&component.current_split_gradient);
+        }
+        if component.show_thin_separators && i + 1 < len {
+            row = row.class("thin-separator");
+        }
+        if component.show_final_separator && i + 1 == len {
+            row = row.class("final-separator");
+        }
+        if component.has_icons {
+            row.children.extend(Element::icon(&split.icon));
+        }
+        row.child(Element::text(&["name"], &split.name))
+            .children(split.columns.iter().map(|column| {
+                let mut element = Element::text(&["column"], &column.value)
+                    .semantic_color(column.semantic_color)
+                    .color(Some(column.visual_color));
+                if let Some(font) = &state.times_font {
+                    element.style.insert("font-family", font.family.clone());
+                }
+                element
+            }))
+    }));
+
+    element
+}
+
+fn text_element(component: &text::State) -> Element {
+    let mut element = Element::div(&["component", "text"]).background(&component.background);
+    if component.display_two_rows {
+        element = element.class("two-rows");
+    }
+    match &component.text {
+        TextState::Center(text) => {
+            element.child(Element::text(&["center"], text).color(component.left_center_color))
+        }
+        TextState::Split(left, right) => element
+            .child(Element::text(&["key"], left).color(component.left_center_color))
+            .child(Element::text(&["value"], right).color(component.right_color)),
+    }
+}
+
+fn timer_element(component: &timer::State, state: &LayoutState) -> Element {
+    let mut element = Element::div(&["timer"])
+        .background(&component.background)
+        .semantic_color(component.semantic_color)
+        .height(component.height);
+    // The text of the timer is colored with a vertical gradient. CSS can only
+    // apply that to text with a background clip, so the colors are provided
+    // as variables.
+    element
+        .style
+        .insert("--top-color", css_color(component.top_color));
+    element
+        .style
+        .insert("--bottom-color", css_color(component.bottom_color));
+    if let Some(font) = &state.timer_font {
+        element.style.insert("font-family", font.family.clone());
+    }
+    element
+        .child(Element::text(&["time"], &component.time))
+        .child(Element::text(&["fraction"], &component.fraction))
+}
+
+fn title_element(component: &title::State) -> Element {
+    let mut element = Element::div(&["component", "title"])
+        .background(&component.background)
+        .color(component.text_color);
+    if component.is_centered {
+        element = element.class("centered");
+    }
+    element.children.extend(Element::icon(&component.icon));
+    for line in [&component.line1, &component.line2] {
+        // The first variant of the line is the unabbreviated one.
+        if let Some(text) = line.first() {
+            element = element.child(Element::text(&["line"], text));
+        }
+    }
+    for (class, count) in [
+        ("finished-runs", component.finished_runs),
+        ("attempts", component.attempts),
+    ] {
+        if let Some(count) = count {
+            element = element.child(Element::text(&[class], &count.to_string()));
+        }
+    }
+    element
+}
+
+fn semantic_color_class(semantic_color: SemanticColor) -> Option<&'static str> {
+    Some(match semantic_color {
+        SemanticColor::Default => return None,
+        SemanticColor::AheadGainingTime => "ahead-gaining-time",
+        SemanticColor::AheadLosingTime => "ahead-losing-time",
+        SemanticColor::BehindLosingTime => "behind-losing-time",
+        SemanticColor::BehindGainingTime => "behind-gaining-time",
+        SemanticColor::BestSegment => "best-segment",
+        SemanticColor::NotRunning => "not-running",
+        SemanticColor::Paused => "paused",
+        SemanticColor::PersonalBest => "personal-best",
+    })
+}
+
+fn css_color(color: Color) -> String {
+    let [r, g, b, _] = color.to_rgba8();
+    format!("rgba({r}, {g}, {b}, {})", color.alpha)
+}
+
+fn css_gradient(gradient: &Gradient) -> Option<String> {
+    Some(match *gradient {
+        Gradient::Transparent => return None,
+        Gradient::Plain(color) => css_color(color),
+        Gradient::Vertical(top, bottom) => format!(
+            "linear-gradient(to bottom, {}, {})",
+            css_color(top),
+            css_color(bottom),
+        ),
+        Gradient::Horizontal(left, right) => format!(
+            "linear-gradient(to right, {}, {})",
+            css_color(left),
+            css_color(right),
+        ),
+        Gradient::Linear(angle, start, end) => format!(
+            "linear-gradient({angle}deg, {}, {})",
+            css_color(start),
+            css_color(end),
+        ),
+        Gradient::Radial(center, edge) => format!(
+            "radial-gradient(circle, {}, {})",
+            css_color(center),
+            css_color(edge),
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{layout::Layout, settings::ImageCache, util::tests_helper::create_timer};
+
+    #[test]
+    fn describes_components() {
+        let timer = create_timer(&["A", "B"]);
+        let mut layout = Layout::default_layout();
+        let mut image_cache = ImageCache::new();
+        let state = layout.state(&mut image_cache, &timer.snapshot());
+
+        let root = render(&state);
+        assert_eq!(root.classes, ["layout", "vertical"]);
+        assert_eq!(root.children.len(), state.components.len());
+
+        let splits = root
+            .children
+            .iter()
+            .find(|element| element.classes.iter().any(|class| class == "splits"))
+            .unwrap();
+        let names = splits
+            .children
+            .iter()
+            .flat_map(|split| &split.children)
+            .filter(|element| element.classes == ["name"])
+            .map(|element| element.text.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["A", "B"]);
+
+        assert_eq!(render(&state).to_json(), root.to_json());
+    }
+}
@@ -131,6 +131,15 @@ fn hash_shader(shader: &FillShader, state: &mut impl Hasher) {
             hash_floats(l, state);
             hash_floats(r, state);
         }
+        FillShader::LinearGradient(angle, start, end) => {
+            hash_float(*angle, state);
+            hash_floats(start, state);
+            hash_floats(end, state);
+        }
+        FillShader::RadialGradient(center, edge) => {
+            hash_floats(center, state);
+            hash_floats(edge, state);
+        }
     }
 }
 
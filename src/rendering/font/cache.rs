@@ -28,6 +28,14 @@ impl<F> CachedFont<F> {
             self.setting.clone_from(font_to_use);
         }
     }
+
+    fn reload(
+        &mut self,
+        allocator: &mut impl ResourceAllocator<Font = Handle<F>>,
+        font_kind: FontKind,
+    ) {
+        self.font = allocator.create_font(self.setting.as_ref(), font_kind);
+    }
 }
 
 pub struct FontCache<F> {
@@ -57,4 +65,10 @@ impl<F> FontCache<F> {
         self.text
             .maybe_reload(allocator, &state.text_font, FontKind::Text);
     }
+
+    pub fn reload(&mut self, allocator: &mut impl ResourceAllocator<Font = Handle<F>>) {
+        self.timer.reload(allocator, FontKind::Timer);
+        self.times.reload(allocator, FontKind::Times);
+        self.text.reload(allocator, FontKind::Text);
+    }
 }
@@ -9,7 +9,9 @@
 //! fonts and labels. An optional software renderer is available behind the
 //! `software-rendering` feature that uses tiny-skia to efficiently render the
 //! paths on the CPU. It is surprisingly fast and can be considered the default
-//! renderer.
+//! renderer. There is also a renderer behind the `wgpu-rendering` feature that
+//! renders on the GPU via wgpu. The `shared-frame-buffer` feature allows
+//! rendering into memory shared with other processes, such as capture plugins.
 
 // # Coordinate spaces used in this module
 //
@@ -68,7 +70,10 @@
 // layout. Sometimes components are rendered in two row mode. The height of
 // these components is 1.725. All components also need to be able to render with
 // this height in horizontal mode. Separators have a thickness of 0.1, while
-// thin separators have half of this thickness.
+// thin separators have half of this thickness. The general settings of the
+// layout can scale both the horizontal padding and the thickness of the
+// separators, which is why the components need to look these up in the
+// context's style instead of using the constants directly.
 
 mod component;
 mod consts;
@@ -81,16 +86,23 @@ mod scene;
 #[cfg(feature = "default-text-engine")]
 pub mod default_text_engine;
 
+#[cfg(feature = "dom-rendering")]
+pub mod dom;
+#[cfg(feature = "shared-frame-buffer")]
+pub mod shared_frame_buffer;
 #[cfg(feature = "software-rendering")]
 pub mod software;
 #[cfg(feature = "svg-rendering")]
 pub mod svg;
 #[cfg(all(target_family = "wasm", feature = "web-rendering"))]
 pub mod web;
+#[cfg(feature = "wgpu-rendering")]
+pub mod wgpu;
 
 use self::{
     consts::{
-        DEFAULT_TEXT_SIZE, DEFAULT_VERTICAL_WIDTH, PADDING, TEXT_ALIGN_BOTTOM, TEXT_ALIGN_TOP,
+        DEFAULT_TEXT_SIZE, DEFAULT_VERTICAL_WIDTH, DROP_SHADOW_OFFSET, PADDING, PSEUDO_PIXELS,
+        SEPARATOR_THICKNESS, TEXT_ALIGN_BOTTOM, TEXT_ALIGN_TOP, THIN_SEPARATOR_THICKNESS,
         TWO_ROW_HEIGHT,
     },
     font::{AbbreviatedLabel, CachedLabel, FontCache},
@@ -104,7 +116,7 @@ use crate::{
 };
 use alloc::borrow::Cow;
 use bytemuck_derive::{Pod, Zeroable};
-use core::iter;
+use core::{iter, mem};
 
 pub use self::{
     entity::Entity,
@@ -146,6 +158,14 @@ pub enum FillShader {
     VerticalGradient(Rgba, Rgba),
     /// Use a horizontal gradient (left, right) to fill the path.
     HorizontalGradient(Rgba, Rgba),
+    /// Use a linear gradient (angle, start, end) to fill the path. The angle
+    /// is in degrees, with 0° going from the bottom to the top and 90° going
+    /// from the left to the right. The gradient spans the bounds of the path.
+    LinearGradient(f32, Rgba, Rgba),
+    /// Use a radial gradient (center, edge) to fill the path. The gradient
+    /// starts in the center of the bounds of the path and reaches its edge
+    /// color at the corners.
+    RadialGradient(Rgba, Rgba),
 }
 
 /// The background of the bottom layer of the scene.
@@ -178,6 +198,7 @@ pub struct SceneManager<P, I, F, L> {
     cached_size: Option<CachedSize>,
     fonts: FontCache<F>,
     images: ImageCache<CachedImage<I>>,
+    rounded_rectangles: Vec<CachedRoundedRectangle<P>>,
 }
 
 impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager<P, I, F, L> {
@@ -196,6 +217,7 @@ impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager
             cached_size: None,
             fonts,
             images: ImageCache::new(),
+            rounded_rectangles: Vec::new(),
         }
     }
 
@@ -204,6 +226,20 @@ impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager
         &self.scene
     }
 
+    /// Recreates all the fonts, even if the font settings didn't change. This
+    /// needs to be called whenever the fonts that are available to the
+    /// [`ResourceAllocator`] changed, such as when new font data got loaded, so
+    /// the fonts get resolved again and all the labels get laid out again on
+    /// the next update.
+    pub fn reload_fonts(
+        &mut self,
+        allocator: impl ResourceAllocator<Path = P, Image = I, Font = F, Label = L>,
+    ) {
+        let mut handles = Handles::new(self.next_id, allocator);
+        self.fonts.reload(&mut handles);
+        self.next_id = handles.into_next_id();
+    }
+
     /// Updates the [`Scene`] by updating the [`Entities`](Entity) according to
     /// the [`LayoutState`] provided. The [`ResourceAllocator`] is used to
     /// allocate the resources necessary that the [`Entities`](Entity) use. A
@@ -239,8 +275,11 @@ impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager
         };
 
         self.scene.recalculate_if_bottom_layer_changed();
+        self.scene.recalculate_if_top_layer_changed();
 
         self.images.collect();
+        self.rounded_rectangles
+            .retain_mut(|rectangle| mem::take(&mut rectangle.is_used));
 
         new_dimensions
     }
@@ -252,7 +291,8 @@ impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager
         state: &LayoutState,
         image_cache: &ImageCache,
     ) -> Option<[f32; 2]> {
-        let total_height = component::layout_height(state);
+        let style = Style::new(state);
+        let total_height = component::layout_height(state, &style);
 
         let cached_total_size = self
             .cached_size
@@ -283,7 +323,9 @@ impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager
             scene: &mut self.scene,
             fonts: &mut self.fonts,
             images: &mut self.images,
+            rounded_rectangles: &mut self.rounded_rectangles,
             image_cache,
+            style,
         };
 
         let background = context.decode_layout_background(&state.background, resolution);
@@ -305,7 +347,7 @@ impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager
         let width = aspect_ratio * total_height;
 
         for (component, cache) in state.components.iter().zip(&mut self.components) {
-            let height = component::height(component);
+            let height = component::height(component, &context.style);
             let dim = [width, height];
             component::render(cache, &mut context, component, state, dim);
             // We translate the coordinate space to the Component Coordinate
@@ -326,7 +368,8 @@ impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager
         state: &LayoutState,
         image_cache: &ImageCache,
     ) -> Option<[f32; 2]> {
-        let total_width = component::layout_width(state);
+        let style = Style::new(state);
+        let total_width = component::layout_width(state, &style);
 
         let cached_total_size = self
             .cached_size
@@ -356,7 +399,9 @@ impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager
             scene: &mut self.scene,
             fonts: &mut self.fonts,
             images: &mut self.images,
+            rounded_rectangles: &mut self.rounded_rectangles,
             image_cache,
+            style,
         };
 
         let background = context.decode_layout_background(&state.background, resolution);
@@ -381,7 +426,7 @@ impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager
         let width_scaling = TWO_ROW_HEIGHT * aspect_ratio / total_width;
 
         for (component, cache) in state.components.iter().zip(&mut self.components) {
-            let width = component::width(component) * width_scaling;
+            let width = component::width(component, &context.style) * width_scaling;
             let height = TWO_ROW_HEIGHT;
             let dim = [width, height];
             component::render(cache, &mut context, component, state, dim);
@@ -397,13 +442,46 @@ impl<P: SharedOwnership, I: SharedOwnership, F, L: SharedOwnership> SceneManager
     }
 }
 
+/// The style parameters from the general settings of the layout, converted
+/// into the Component Coordinate Space.
+struct Style {
+    padding: f32,
+    separator_thickness: f32,
+    thin_separator_thickness: f32,
+    corner_radius: f32,
+    drop_shadow: Option<Color>,
+}
+
+impl Style {
+    fn new(state: &LayoutState) -> Self {
+        let padding_scale = state.padding_scale as f32 / 100.0;
+        let separator_scale = state.separator_thickness as f32 / 100.0;
+        Self {
+            padding: PADDING * padding_scale,
+            separator_thickness: SEPARATOR_THICKNESS * separator_scale,
+            thin_separator_thickness: THIN_SEPARATOR_THICKNESS * separator_scale,
+            corner_radius: state.corner_radius as f32 * PSEUDO_PIXELS,
+            drop_shadow: state.drop_shadow,
+        }
+    }
+}
+
+struct CachedRoundedRectangle<P> {
+    size: [u32; 2],
+    radius: u32,
+    path: Handle<P>,
+    is_used: bool,
+}
+
 struct RenderContext<'b, A: ResourceAllocator> {
     transform: Transform,
     handles: Handles<A>,
     scene: &'b mut Scene<A::Path, A::Image, A::Label>,
     fonts: &'b mut FontCache<A::Font>,
     images: &'b mut ImageCache<CachedImage<A::Image>>,
+    rounded_rectangles: &'b mut Vec<CachedRoundedRectangle<A::Path>>,
     image_cache: &'b ImageCache,
+    style: Style,
 }
 
 impl<A: ResourceAllocator> RenderContext<'_, A> {
@@ -413,13 +491,69 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
 
     fn render_background(&mut self, [w, h]: Pos, gradient: &Gradient) {
         if let Some(shader) = decode_gradient(gradient) {
-            let rectangle = self.rectangle();
-            self.scene.bottom_layer_mut().push(Entity::FillPath(
-                rectangle,
-                shader,
-                self.transform.pre_scale(w, h),
+            if self.style.corner_radius > 0.0 {
+                let rectangle = self.rounded_rectangle([w, h]);
+                self.scene.bottom_layer_mut().push(Entity::FillPath(
+                    rectangle,
+                    shader,
+                    self.transform,
+                ));
+            } else {
+                let rectangle = self.rectangle();
+                self.scene.bottom_layer_mut().push(Entity::FillPath(
+                    rectangle,
+                    shader,
+                    self.transform.pre_scale(w, h),
+                ));
+            }
+        }
+    }
+
+    fn rounded_rectangle(&mut self, [w, h]: Pos) -> Handle<A::Path> {
+        let radius = self.style.corner_radius.min(0.5 * w).min(0.5 * h);
+        let (size, radius_bits) = ([w.to_bits(), h.to_bits()], radius.to_bits());
+
+        if let Some(cached) = self
+            .rounded_rectangles
+            .iter_mut()
+            .find(|r| r.size == size && r.radius == radius_bits)
+        {
+            cached.is_used = true;
+            return cached.path.share();
+        }
+
+        let path = self.handles.build_rounded_rectangle(w, h, radius);
+        self.rounded_rectangles.push(CachedRoundedRectangle {
+            size,
+            radius: radius_bits,
+            path: path.share(),
+            is_used: true,
+        });
+        path
+    }
+
+    fn push_label(
+        &mut self,
+        layer: Layer,
+        label: &LabelHandle<A::Label>,
+        shader: FillShader,
+        transform: Transform,
+    ) {
+        if let Some(shadow) = &self.style.drop_shadow {
+            let shadow_transform = Transform {
+                x: transform.x + DROP_SHADOW_OFFSET * self.transform.scale_x,
+                y: transform.y + DROP_SHADOW_OFFSET * self.transform.scale_y,
+                ..transform
+            };
+            self.scene.layer_mut(layer).push(Entity::Label(
+                label.share(),
+                solid(shadow),
+                shadow_transform,
             ));
         }
+        self.scene
+            .layer_mut(layer)
+            .push(Entity::Label(label.share(), shader, transform));
     }
 
     fn backend_render_rectangle(&mut self, [x1, y1]: Pos, [x2, y2]: Pos, shader: FillShader) {
@@ -563,11 +697,12 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
         value_color: Color,
         display_two_rows: bool,
     ) {
+        let padding = self.style.padding;
         let left_of_value_x = self.render_numbers(
             value,
             value_label,
             Layer::from_updates_frequently(updates_frequently),
-            [width - PADDING, height + TEXT_ALIGN_BOTTOM],
+            [width - padding, height + TEXT_ALIGN_BOTTOM],
             DEFAULT_TEXT_SIZE,
             solid(&value_color),
         );
@@ -580,10 +715,10 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
         self.render_abbreviated_text_ellipsis(
             iter::once(key).chain(abbreviations.iter().map(|x| &**x)),
             key_label,
-            [PADDING, TEXT_ALIGN_TOP],
+            [padding, TEXT_ALIGN_TOP],
             DEFAULT_TEXT_SIZE,
             solid(&key_color),
-            end_x - PADDING,
+            end_x - padding,
         );
     }
 
@@ -603,11 +738,12 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
             (max_x - x) / scale,
         );
 
-        self.scene.bottom_layer_mut().push(Entity::Label(
-            label.share(),
+        self.push_label(
+            Layer::Bottom,
+            label,
             shader,
             font::left_aligned(&self.transform, pos, scale),
-        ));
+        );
 
         x + label.width(scale)
     }
@@ -628,11 +764,12 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
             Some((max_x - x) / scale),
         );
 
-        self.scene.bottom_layer_mut().push(Entity::Label(
-            label.share(),
+        self.push_label(
+            Layer::Bottom,
+            label,
             shader,
             font::left_aligned(&self.transform, pos, scale),
-        ));
+        );
 
         x + label.width(scale)
     }
@@ -654,8 +791,9 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
             Some((max_x - min_x) / scale),
         );
 
-        self.scene.bottom_layer_mut().push(Entity::Label(
-            label.share(),
+        self.push_label(
+            Layer::Bottom,
+            label,
             shader,
             font::centered(
                 &self.transform,
@@ -665,7 +803,7 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
                 min_x,
                 max_x,
             ),
-        ));
+        );
     }
 
     fn render_abbreviated_text_centered<'a>(
@@ -685,8 +823,9 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
             (max_x - min_x) / scale,
         );
 
-        self.scene.bottom_layer_mut().push(Entity::Label(
-            label.share(),
+        self.push_label(
+            Layer::Bottom,
+            label,
             shader,
             font::centered(
                 &self.transform,
@@ -696,7 +835,7 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
                 min_x,
                 max_x,
             ),
-        ));
+        );
     }
 
     fn render_text_right_align(
@@ -711,11 +850,12 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
         let label = label.update(text, &mut self.handles, &mut self.fonts.text.font, None);
         let width = label.width(scale);
 
-        self.scene.layer_mut(layer).push(Entity::Label(
-            label.share(),
+        self.push_label(
+            layer,
+            label,
             shader,
             font::right_aligned(&self.transform, pos, scale, width),
-        ));
+        );
 
         x - width
     }
@@ -758,11 +898,12 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
         let label = label.update(text, &mut self.handles, &mut self.fonts.times.font, None);
         let width = label.width(scale);
 
-        self.scene.layer_mut(layer).push(Entity::Label(
-            label.share(),
+        self.push_label(
+            layer,
+            label,
             shader,
             font::right_aligned(&self.transform, pos, scale, width),
-        ));
+        );
 
         x - width
     }
@@ -779,11 +920,12 @@ impl<A: ResourceAllocator> RenderContext<'_, A> {
         let label = label.update(text, &mut self.handles, &mut self.fonts.timer.font, None);
         let width = label.width(scale);
 
-        self.scene.layer_mut(layer).push(Entity::Label(
-            label.share(),
+        self.push_label(
+            layer,
+            label,
             shader,
             font::right_aligned(&self.transform, pos, scale, width),
-        ));
+        );
 
         x - width
     }
@@ -848,9 +990,108 @@ const fn decode_gradient(gradient: &Gradient) -> Option<FillShader> {
             FillShader::VerticalGradient(top.to_array(), bottom.to_array())
         }
         Gradient::Plain(plain) => FillShader::SolidColor(plain.to_array()),
+        Gradient::Linear(angle, start, end) => {
+            FillShader::LinearGradient(*angle, start.to_array(), end.to_array())
+        }
+        Gradient::Radial(center, edge) => {
+            FillShader::RadialGradient(center.to_array(), edge.to_array())
+        }
     })
 }
 
+/// Calculates the start and end point of a linear gradient with the angle
+/// provided in degrees for the bounds (left, top, right, bottom) provided. Just
+/// like in CSS, the gradient line goes through the center of the bounds and is
+/// long enough for the corners to be exactly at the start and end colors.
+#[cfg(any(
+    feature = "software-rendering",
+    feature = "svg-rendering",
+    feature = "web-rendering",
+    feature = "wgpu-rendering"
+))]
+fn linear_gradient_points(angle: f32, [left, top, right, bottom]: [f32; 4]) -> [[f32; 2]; 2] {
+    use crate::platform::math::f32::{abs, cos, sin};
+
+    let angle = angle.to_radians();
+    let [dx, dy] = [sin(angle), -cos(angle)];
+    let half_length = 0.5 * (abs((right - left) * dx) + abs((bottom - top) * dy));
+    let [center_x, center_y] = [0.5 * (left + right), 0.5 * (top + bottom)];
+    [
+        [center_x - dx * half_length, center_y - dy * half_length],
+        [center_x + dx * half_length, center_y + dy * half_length],
+    ]
+}
+
+/// Calculates the center and the radius of a radial gradient for the bounds
+/// (left, top, right, bottom) provided, such that the gradient reaches the
+/// corners.
+#[cfg(any(
+    feature = "software-rendering",
+    feature = "web-rendering",
+    feature = "wgpu-rendering"
+))]
+fn radial_gradient_circle([left, top, right, bottom]: [f32; 4]) -> ([f32; 2], f32) {
+    let [width, height] = [right - left, bottom - top];
+    (
+        [0.5 * (left + right), 0.5 * (top + bottom)],
+        0.5 * crate::platform::math::f32::sqrt(width * width + height * height),
+    )
+}
+
+/// Blurs the image of a background with the blur provided. The sigma of the
+/// blur is derived from the dimensions of the image as described by
+/// [`BLUR_FACTOR`](settings::BLUR_FACTOR).
+#[cfg(all(
+    feature = "image",
+    any(feature = "software-rendering", feature = "wgpu-rendering"),
+))]
+fn blur_background_image(
+    original_image: image::ImageBuffer<image::Rgba<u8>, &[u8]>,
+    blur: f32,
+) -> image::RgbaImage {
+    use image::{imageops::FilterType, ImageBuffer};
+    use settings::BLUR_FACTOR;
+
+    // Formula to calculate the sigma as specified
+    let dim = original_image.width().max(original_image.height()) as f32;
+    let sigma = BLUR_FACTOR * blur * dim;
+
+    // For large blurs the calculation is actually very expensive,
+    // but we can get around that because large blurs don't require
+    // high resolutions in the first place. So we simply scale down
+    // the image based on the sigma to a smaller size and then blur
+    // the image. For the scaled down image we always use a sigma of
+    // 2.0, so scaling the image by 2.0 / sigma should resulting in
+    // the same amount of blur. Of course we never want to scale the
+    // image up, so in case the scale factor would end up in >= 1x,
+    // we simply don't do any scaling and keep the original sigma.
+    const SIGMA_WHEN_SCALED: f32 = 2.0;
+    let scale = SIGMA_WHEN_SCALED / sigma;
+
+    let scaled;
+    let (image, sigma) = if scale < 1.0 {
+        // The image needs to at least be 1x1, because tiny-skia
+        // doesn't allow images to be smaller than that. A triangle
+        // filter is probably fine, the blur will hide most scaling
+        // artifacts anyway.
+        scaled = image::imageops::resize(
+            &original_image,
+            ((scale * original_image.width() as f32) as u32).max(1),
+            ((scale * original_image.height() as f32) as u32).max(1),
+            FilterType::Triangle,
+        );
+        (
+            ImageBuffer::<image::Rgba<u8>, _>::from_raw(scaled.width(), scaled.height(), &*scaled)
+                .unwrap(),
+            SIGMA_WHEN_SCALED,
+        )
+    } else {
+        (original_image, sigma)
+    };
+
+    image::imageops::blur(&image, sigma)
+}
+
 const fn solid(color: &Color) -> FillShader {
     FillShader::SolidColor(color.to_array())
 }
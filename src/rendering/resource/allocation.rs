@@ -80,6 +80,31 @@ pub trait ResourceAllocator {
         builder.finish()
     }
 
+    /// Builds a new rectangle with rounded corners. The rectangle spans from
+    /// `(0, 0)` to `(width, height)` and each of its corners is a quarter
+    /// circle with the radius provided, which is approximated with a cubic
+    /// bézier curve. The radius is never larger than half of the width or
+    /// height.
+    fn build_rounded_rectangle(&mut self, width: f32, height: f32, radius: f32) -> Self::Path {
+        const KAPPA: f32 = 0.5522848;
+
+        let (w, h, r) = (width, height, radius);
+        let c = r * (1.0 - KAPPA);
+
+        let mut builder = self.path_builder();
+        builder.move_to(r, 0.0);
+        builder.line_to(w - r, 0.0);
+        builder.curve_to(w - c, 0.0, w, c, w, r);
+        builder.line_to(w, h - r);
+        builder.curve_to(w, h - c, w - c, h, w - r, h);
+        builder.line_to(r, h);
+        builder.curve_to(c, h, 0.0, h - c, 0.0, h - r);
+        builder.line_to(0.0, r);
+        builder.curve_to(0.0, c, c, 0.0, r, 0.0);
+        builder.close();
+        builder.finish()
+    }
+
     /// Creates an image out of the image data provided. The data represents the
     /// image in its original file format. It needs to be parsed in order to be
     /// visualized. The parsed image is returned in case it was successfully
@@ -224,6 +249,10 @@ impl<A: ResourceAllocator> ResourceAllocator for &mut A {
         (*self).build_square()
     }
 
+    fn build_rounded_rectangle(&mut self, width: f32, height: f32, radius: f32) -> Self::Path {
+        (*self).build_rounded_rectangle(width, height, radius)
+    }
+
     fn create_image(&mut self, data: &[u8]) -> Option<Self::Image> {
         (*self).create_image(data)
     }
@@ -83,6 +83,13 @@ impl<A: ResourceAllocator> ResourceAllocator for Handles<A> {
         self.next(square)
     }
 
+    fn build_rounded_rectangle(&mut self, width: f32, height: f32, radius: f32) -> Self::Path {
+        let rectangle = self
+            .allocator
+            .build_rounded_rectangle(width, height, radius);
+        self.next(rectangle)
+    }
+
     fn create_image(&mut self, data: &[u8]) -> Option<Self::Image> {
         let image = self.allocator.create_image(data)?;
         Some(self.next(image))
@@ -40,6 +40,8 @@ pub struct Scene<P, I, L> {
     background: Option<Background<I>>,
     bottom_hash: u64,
     bottom_layer_changed: bool,
+    top_hash: u64,
+    top_layer_changed: bool,
     bottom_layer: Vec<Entity<P, I, L>>,
     top_layer: Vec<Entity<P, I, L>>,
 }
@@ -53,6 +55,8 @@ impl<P: SharedOwnership, I: SharedOwnership, L: SharedOwnership> Scene<P, I, L>
             background: None,
             bottom_hash: calculate_hash::<P, I, L>(&None, &[]),
             bottom_layer_changed: false,
+            top_hash: calculate_hash::<P, I, L>(&None, &[]),
+            top_layer_changed: false,
             bottom_layer: Vec::new(),
             top_layer: Vec::new(),
         }
@@ -73,6 +77,14 @@ impl<P: SharedOwnership, I: SharedOwnership, L: SharedOwnership> Scene<P, I, L>
         self.bottom_layer_changed
     }
 
+    /// Check if the scene's top [`Layer`] changed. If neither [`Layer`]
+    /// changed, the previous frame can be reused as is. Use this method
+    /// together with [`bottom_layer_changed`](Self::bottom_layer_changed) to
+    /// only render frames when something visually changed.
+    pub const fn top_layer_changed(&self) -> bool {
+        self.top_layer_changed
+    }
+
     /// Get a reference to the scene's bottom [`Layer`]. This [`Layer`] is
     /// intended to infrequently change, so it doesn't need to be rerendered
     /// every frame.
@@ -121,6 +133,13 @@ impl<P: SharedOwnership, I: SharedOwnership, L: SharedOwnership> Scene<P, I, L>
         self.bottom_hash = new_hash;
     }
 
+    /// Recalculates the hash of the top [`Layer`] and checks if it changed.
+    pub fn recalculate_if_top_layer_changed(&mut self) {
+        let new_hash = calculate_hash(&None, &self.top_layer);
+        self.top_layer_changed = new_hash != self.top_hash;
+        self.top_hash = new_hash;
+    }
+
     /// Accesses the [`Layer`] specified mutably.
     pub fn layer_mut(&mut self, layer: Layer) -> &mut Vec<Entity<P, I, L>> {
         match layer {
@@ -0,0 +1,197 @@
+//! Provides a frame buffer that lives in memory shared with other processes.
+//! The software renderer renders directly into it, so capture plugins, such as
+//! a source in OBS, can consume the frames without any copies.
+//!
+//! The memory is backed by a file. On Linux the file should be placed on a
+//! `tmpfs`, such as `/dev/shm`, so it never touches the disk. Windows and macOS
+//! keep the pages of memory mapped files in memory as long as they are mapped.
+//!
+//! # Layout
+//!
+//! The file starts with a header of [`HEADER_LEN`] bytes that consists of
+//! native-endian 32-bit integers:
+//!
+//! | Offset | Field                                                       |
+//! |--------|-------------------------------------------------------------|
+//! | 0      | The bytes `LSFB`.                                           |
+//! | 4      | The version of the layout. This is currently `1`.           |
+//! | 8      | The sequence number. It is odd while a frame is written.    |
+//! | 12     | The width of the frame in pixels.                           |
+//! | 16     | The height of the frame in pixels.                          |
+//! | 20     | The stride of the frame in bytes.                           |
+//! | 24     | The first row that changed in the last frame.               |
+//! | 28     | The end of the rows that changed in the last frame.         |
+//!
+//! The pixels of the frame follow the header. They are stored the same way as
+//! the image of the [software renderer](super::software), as RGBA8 encoded
+//! pixels with premultiplied alpha. The file grows when the frame gets larger,
+//! so consumers need to map it again if it grew past their mapping.
+//!
+//! The sequence number allows consumers to read consistent frames without any
+//! locks. A consumer reads the sequence number, copies or uploads the frame if
+//! it is even and then reads the sequence number again. If it changed, a new
+//! frame got written in the meantime and the frame needs to be read again. If
+//! the sequence number didn't change since the last frame the consumer read,
+//! there is no new frame.
+
+use super::software::BorrowedRenderer;
+use crate::{layout::LayoutState, settings::ImageCache};
+use core::{
+    mem, slice,
+    sync::atomic::{self, AtomicU32, Ordering},
+};
+use memmap2::MmapMut;
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    path::Path,
+};
+
+/// The length of the header in bytes. The pixels of the frame start at this
+/// offset.
+pub const HEADER_LEN: usize = 64;
+
+const MAGIC: [u8; 4] = *b"LSFB";
+const VERSION: u32 = 1;
+
+#[repr(C)]
+struct Header {
+    magic: AtomicU32,
+    version: AtomicU32,
+    sequence: AtomicU32,
+    width: AtomicU32,
+    height: AtomicU32,
+    stride: AtomicU32,
+    dirty_start: AtomicU32,
+    dirty_end: AtomicU32,
+}
+
+const _: () = assert!(mem::size_of::<Header>() <= HEADER_LEN);
+
+/// A frame buffer in shared memory that the layout gets rendered into. Check
+/// the [module level documentation](self) for how the memory is laid out.
+pub struct SharedFrameBuffer {
+    file: File,
+    map: MmapMut,
+    renderer: BorrowedRenderer,
+    resolution: [u32; 2],
+}
+
+impl SharedFrameBuffer {
+    /// Creates the file at the path provided and maps it into memory. An
+    /// existing file gets truncated.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(HEADER_LEN as u64)?;
+
+        // SAFETY: The file is truncated, so there is no content that could be
+        // misinterpreted. Other processes may modify the file while it is
+        // mapped, but the header is only ever accessed via atomics and the
+        // pixels are plain bytes, so that can't result in undefined behavior.
+        let map = unsafe { MmapMut::map_mut(&file)? };
+
+        let this = Self {
+            file,
+            map,
+            renderer: BorrowedRenderer::new(),
+            resolution: [0; 2],
+        };
+
+        let header = this.header();
+        header.version.store(VERSION, Ordering::Relaxed);
+        header
+            .magic
+            .store(u32::from_ne_bytes(MAGIC), Ordering::Release);
+
+        Ok(this)
+    }
+
+    fn header(&self) -> &Header {
+        // SAFETY: The mapping is page aligned and at least HEADER_LEN bytes
+        // large. The header only consists of atomics, which may be accessed
+        // through a shared reference.
+        unsafe { &*self.map.as_ptr().cast::<Header>() }
+    }
+
+    /// Renders the layout state provided with the chosen resolution into the
+    /// shared memory. It may detect that the layout got resized. In that case
+    /// it returns the new ideal size. This is just a hint and can be ignored
+    /// entirely. The image is always rendered with the resolution provided.
+    /// Only the rows that changed get redrawn. Growing the file or mapping it
+    /// again may fail, in which case an error is returned.
+    pub fn render(
+        &mut self,
+        state: &LayoutState,
+        image_cache: &ImageCache,
+        [width, height]: [u32; 2],
+    ) -> io::Result<Option<[f32; 2]>> {
+        let frame_len = 4 * width as usize * height as usize;
+
+        let mut force_redraw = [width, height] != self.resolution;
+        if self.map.len() < HEADER_LEN + frame_len {
+            self.file.set_len((HEADER_LEN + frame_len) as u64)?;
+            // SAFETY: See `create`.
+            self.map = unsafe { MmapMut::map_mut(&self.file)? };
+            force_redraw = true;
+        }
+        self.resolution = [width, height];
+
+        let ptr = self.map.as_mut_ptr();
+        // SAFETY: The mapping is at least HEADER_LEN + frame_len bytes large.
+        // The header and the pixels don't overlap, so they can be accessed
+        // independently.
+        let (header, pixels) = unsafe {
+            (
+                &*ptr.cast::<Header>(),
+                slice::from_raw_parts_mut(ptr.add(HEADER_LEN), frame_len),
+            )
+        };
+
+        // The sequence number is odd while the frame is being written.
+        header.sequence.fetch_add(1, Ordering::Acquire);
+        atomic::fence(Ordering::Release);
+
+        header.width.store(width, Ordering::Relaxed);
+        header.height.store(height, Ordering::Relaxed);
+        header.stride.store(4 * width, Ordering::Relaxed);
+
+        let new_resolution = self.renderer.render(
+            state,
+            image_cache,
+            pixels,
+            [width, height],
+            width,
+            force_redraw,
+        );
+
+        let dirty_rows = self.renderer.dirty_rows().unwrap_or(0..0);
+        header
+            .dirty_start
+            .store(dirty_rows.start, Ordering::Relaxed);
+        header.dirty_end.store(dirty_rows.end, Ordering::Relaxed);
+
+        header.sequence.fetch_add(1, Ordering::Release);
+
+        Ok(new_resolution)
+    }
+
+    /// Loads the font data provided, so that its fonts can be referred to by
+    /// their family name in the font settings of a layout. TrueType and
+    /// OpenType fonts and font collections are supported. Returns whether the
+    /// data contained any fonts.
+    pub fn load_font(&mut self, data: Vec<u8>) -> bool {
+        self.renderer.load_font(data)
+    }
+
+    /// Accesses the pixels of the last frame as a byte slice of RGBA8 encoded
+    /// pixels (red, green, blue, alpha with each channel being an u8).
+    pub fn image_data(&self) -> &[u8] {
+        let [width, height] = self.resolution;
+        &self.map[HEADER_LEN..][..4 * width as usize * height as usize]
+    }
+}
@@ -4,6 +4,7 @@
 use super::{
     default_text_engine::{Font, Label, TextEngine},
     entity::Entity,
+    linear_gradient_points, radial_gradient_circle,
     resource::{self, ResourceAllocator},
     FillShader, FontKind, Scene, SceneManager, SharedOwnership, Transform,
 };
@@ -12,17 +13,23 @@ use crate::{
     settings::ImageCache,
 };
 use alloc::rc::Rc;
-use core::{mem, ops::Deref};
+use core::{
+    mem,
+    ops::{Deref, Range},
+};
 use tiny_skia::{
     BlendMode, Color, FillRule, FilterQuality, GradientStop, LinearGradient, Paint, Path,
-    PathBuilder, Pattern, Pixmap, PixmapMut, Point, Rect, Shader, SpreadMode, Stroke,
+    PathBuilder, Pattern, Pixmap, PixmapMut, Point, RadialGradient, Rect, Shader, SpreadMode,
+    Stroke,
 };
 use tiny_skia_path::NormalizedF32;
 
 #[cfg(feature = "image")]
-use crate::settings::{BackgroundImage, BLUR_FACTOR};
+use super::blur_background_image;
+#[cfg(feature = "image")]
+use crate::settings::BackgroundImage;
 #[cfg(feature = "image")]
-use image::{imageops::FilterType, ImageBuffer};
+use image::ImageBuffer;
 #[cfg(feature = "image")]
 use tiny_skia_path::IntSize;
 
@@ -184,6 +191,7 @@ pub struct BorrowedRenderer {
     background: Pixmap,
     min_y: f32,
     max_y: f32,
+    dirty_rows: Option<[u32; 2]>,
 }
 
 struct UnsafeRc<T>(Rc<T>);
@@ -249,6 +257,7 @@ impl BorrowedRenderer {
             background: Pixmap::new(1, 1).unwrap(),
             min_y: f32::INFINITY,
             max_y: f32::NEG_INFINITY,
+            dirty_rows: None,
         }
     }
 
@@ -307,6 +316,12 @@ impl BorrowedRenderer {
             render_layer(&mut background, scene.bottom_layer(), rectangle);
         }
 
+        if !force_redraw && !bottom_layer_changed && !scene.top_layer_changed() {
+            // Nothing visually changed, so the image is still up to date.
+            self.dirty_rows = None;
+            return new_resolution;
+        }
+
         let top_layer = scene.top_layer();
 
         let [min_y, max_y] = calculate_bounds(top_layer);
@@ -317,19 +332,59 @@ impl BorrowedRenderer {
             frame_buffer
                 .data_mut()
                 .copy_from_slice(background.data_mut());
+            self.dirty_rows = Some([0, height]);
         } else if min_y <= max_y {
-            let stride = 4 * stride as usize;
-            let min_y = stride * (min_y - 1.0) as usize;
-            let max_y = stride * ((max_y + 2.0) as usize).min(height as usize);
+            let min_y = ((min_y - 1.0) as u32).min(height);
+            let max_y = ((max_y + 2.0) as u32).min(height);
 
-            frame_buffer.data_mut()[min_y..max_y]
-                .copy_from_slice(&background.data_mut()[min_y..max_y]);
+            let stride = 4 * stride as usize;
+            let [start, end] = [min_y, max_y].map(|y| stride * y as usize);
+            frame_buffer.data_mut()[start..end].copy_from_slice(&background.data_mut()[start..end]);
+            self.dirty_rows = Some([min_y, max_y]);
+        } else {
+            self.dirty_rows = None;
         }
 
         render_layer(&mut frame_buffer, top_layer, rectangle);
 
         new_resolution
     }
+
+    /// Returns the range of rows of the image that got redrawn by the last
+    /// call to [`render`](Self::render). The rows outside of this range are
+    /// unchanged, so only this region of the image needs to be presented
+    /// again. If nothing changed at all, [`None`] is returned and the image
+    /// doesn't need to be presented again.
+    pub fn dirty_rows(&self) -> Option<Range<u32>> {
+        let [start, end] = self.dirty_rows?;
+        Some(start..end)
+    }
+
+    /// Loads the font data provided, so that its fonts can be referred to by
+    /// their family name in the font settings of a layout. TrueType and
+    /// OpenType fonts and font collections are supported. Returns whether the
+    /// data contained any fonts.
+    pub fn load_font(&mut self, data: Vec<u8>) -> bool {
+        let loaded = self.allocator.text_engine.load_font(data);
+        if loaded {
+            self.scene_manager.reload_fonts(&mut self.allocator);
+        }
+        loaded
+    }
+
+    /// Renders the layout state provided with the chosen resolution and
+    /// encodes the image as a PNG file. This always redraws the whole image.
+    #[cfg(feature = "image")]
+    pub fn render_png(
+        &mut self,
+        state: &LayoutState,
+        image_cache: &ImageCache,
+        [width, height]: [u32; 2],
+    ) -> Vec<u8> {
+        let mut image = vec![0; 4 * width as usize * height as usize];
+        self.render(state, image_cache, &mut image, [width, height], width, true);
+        encode_png(&image, [width, height])
+    }
 }
 
 /// The software renderer allows rendering layouts entirely on the CPU. This is
@@ -380,6 +435,21 @@ impl Renderer {
         )
     }
 
+    /// Loads the font data provided, so that its fonts can be referred to by
+    /// their family name in the font settings of a layout. TrueType and
+    /// OpenType fonts and font collections are supported. Returns whether the
+    /// data contained any fonts.
+    pub fn load_font(&mut self, data: Vec<u8>) -> bool {
+        self.renderer.load_font(data)
+    }
+
+    /// Returns the range of rows of the image that got redrawn by the last
+    /// call to [`render`](Self::render). If nothing changed at all, [`None`]
+    /// is returned.
+    pub fn dirty_rows(&self) -> Option<Range<u32>> {
+        self.renderer.dirty_rows()
+    }
+
     /// Accesses the image as a byte slice of RGBA8 encoded pixels (red, green,
     /// blue, alpha with each channel being an u8).
     pub fn image_data(&self) -> &[u8] {
@@ -413,6 +483,43 @@ impl Renderer {
         )
         .unwrap()
     }
+
+    /// Encodes the image as a PNG file.
+    #[cfg(feature = "image")]
+    pub fn encode_png(&self) -> Vec<u8> {
+        encode_png(
+            self.frame_buffer.data(),
+            [self.frame_buffer.width(), self.frame_buffer.height()],
+        )
+    }
+}
+
+/// Encodes the image as a PNG file. The pixels of the image are stored with
+/// premultiplied alpha, while PNG expects straight alpha, so they need to be
+/// converted first.
+#[cfg(feature = "image")]
+fn encode_png(image: &[u8], [width, height]: [u32; 2]) -> Vec<u8> {
+    use image::{codecs::png::PngEncoder, ExtendedColorType, ImageEncoder};
+
+    let image = image
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            if a == 0 || a == 255 {
+                [r, g, b, a]
+            } else {
+                let demultiply =
+                    |c: u8| ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8;
+                [demultiply(r), demultiply(g), demultiply(b), a]
+            }
+        })
+        .collect::<Vec<u8>>();
+
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png)
+        .write_image(&image, width, height, ExtendedColorType::Rgba8)
+        .unwrap();
+    png
 }
 
 fn render_layer(
@@ -590,6 +697,39 @@ fn convert_shader<T>(
             )
             .unwrap()
         }
+        FillShader::LinearGradient(angle, start, end) => {
+            let [top, bottom] = calculate_top_bottom(has_bounds);
+            let [left, right] = calculate_left_right(has_bounds);
+            let [[x1, y1], [x2, y2]] = linear_gradient_points(*angle, [left, top, right, bottom]);
+            LinearGradient::new(
+                Point::from_xy(x1, y1),
+                Point::from_xy(x2, y2),
+                vec![
+                    GradientStop::new(0.0, convert_color(start)),
+                    GradientStop::new(1.0, convert_color(end)),
+                ],
+                SpreadMode::Pad,
+                tiny_skia::Transform::identity(),
+            )
+            .unwrap_or(Shader::SolidColor(convert_color(end)))
+        }
+        FillShader::RadialGradient(center, edge) => {
+            let [top, bottom] = calculate_top_bottom(has_bounds);
+            let [left, right] = calculate_left_right(has_bounds);
+            let ([x, y], radius) = radial_gradient_circle([left, top, right, bottom]);
+            RadialGradient::new(
+                Point::from_xy(x, y),
+                Point::from_xy(x, y),
+                radius,
+                vec![
+                    GradientStop::new(0.0, convert_color(center)),
+                    GradientStop::new(1.0, convert_color(edge)),
+                ],
+                SpreadMode::Pad,
+                tiny_skia::Transform::identity(),
+            )
+            .unwrap_or(Shader::SolidColor(convert_color(edge)))
+        }
     };
 
     Paint {
@@ -665,6 +805,23 @@ fn fill_background(
                         None,
                     );
                 }
+                FillShader::LinearGradient(..) | FillShader::RadialGradient(..) => {
+                    let bounds = [0.0, 0.0, width as f32, height as f32];
+                    background_layer.fill_rect(
+                        Rect::from_ltrb(bounds[0], bounds[1], bounds[2], bounds[3]).unwrap(),
+                        &Paint {
+                            blend_mode: BlendMode::Source,
+                            ..convert_shader(
+                                shader,
+                                &bounds,
+                                |&[_, top, _, bottom]| [top, bottom],
+                                |&[left, _, right, _]| [left, right],
+                            )
+                        },
+                        tiny_skia::Transform::identity(),
+                        None,
+                    );
+                }
             },
             Background::Image(image, transform) => {
                 #[cfg(feature = "image")]
@@ -743,48 +900,7 @@ fn update_blurred_background_image(
                 )
                 .unwrap();
 
-                // Formula to calculate the sigma as specified
-                let dim = original_image.width().max(original_image.height()) as f32;
-                let sigma = BLUR_FACTOR * image.blur * dim;
-
-                // For large blurs the calculation is actually very expensive,
-                // but we can get around that because large blurs don't require
-                // high resolutions in the first place. So we simply scale down
-                // the image based on the sigma to a smaller size and then blur
-                // the image. For the scaled down image we always use a sigma of
-                // 2.0, so scaling the image by 2.0 / sigma should resulting in
-                // the same amount of blur. Of course we never want to scale the
-                // image up, so in case the scale factor would end up in >= 1x,
-                // we simply don't do any scaling and keep the original sigma.
-                const SIGMA_WHEN_SCALED: f32 = 2.0;
-                let scale = SIGMA_WHEN_SCALED / sigma;
-
-                let scaled;
-                let (image, sigma) = if scale < 1.0 {
-                    // The image needs to at least be 1x1, because tiny-skia
-                    // doesn't allow images to be smaller than that. A triangle
-                    // filter is probably fine, the blur will hide most scaling
-                    // artifacts anyway.
-                    scaled = image::imageops::resize(
-                        &original_image,
-                        ((scale * original_image.width() as f32) as u32).max(1),
-                        ((scale * original_image.height() as f32) as u32).max(1),
-                        FilterType::Triangle,
-                    );
-                    (
-                        ImageBuffer::<image::Rgba<u8>, _>::from_raw(
-                            scaled.width(),
-                            scaled.height(),
-                            &*scaled,
-                        )
-                        .unwrap(),
-                        SIGMA_WHEN_SCALED,
-                    )
-                } else {
-                    (original_image, sigma)
-                };
-
-                let image_buffer = image::imageops::blur(&image, sigma);
+                let image_buffer = blur_background_image(original_image, image.blur);
                 let size = IntSize::from_wh(image_buffer.width(), image_buffer.height()).unwrap();
                 let pixmap = Pixmap::from_vec(image_buffer.into_raw(), size).unwrap();
                 *blurred_background_image = Some((current_key, pixmap));
@@ -18,8 +18,8 @@ use crate::{
 
 use super::{
     default_text_engine::{self, TextEngine},
-    resource, Background, Entity, FillShader, FontKind, ResourceAllocator, SceneManager,
-    SharedOwnership, Transform,
+    linear_gradient_points, resource, Background, Entity, FillShader, FontKind, ResourceAllocator,
+    SceneManager, SharedOwnership, Transform,
 };
 
 type SvgImage = Rc<Image>;
@@ -56,6 +56,18 @@ impl Renderer {
         }
     }
 
+    /// Loads the font data provided, so that its fonts can be referred to by
+    /// their family name in the font settings of a layout. TrueType and
+    /// OpenType fonts and font collections are supported. Returns whether the
+    /// data contained any fonts.
+    pub fn load_font(&mut self, data: Vec<u8>) -> bool {
+        let loaded = self.allocator.text_engine.load_font(data);
+        if loaded {
+            self.scene_manager.reload_fonts(&mut self.allocator);
+        }
+        loaded
+    }
+
     /// Renders the layout state with the chosen dimensions to the writer
     /// provided. It may detect that the layout got resized. In that case it
     /// returns the new ideal size. This is just a hint and can be ignored
@@ -386,35 +398,46 @@ fn visit_shader<W: Write>(
     writer: &mut Writer<W>,
     shader: &FillShader,
 ) -> fmt::Result {
-    let (vertical, start, end) = match shader {
-        FillShader::SolidColor(_) => return Ok(()),
-        FillShader::VerticalGradient(top, bottom) => (true, top, bottom),
-        FillShader::HorizontalGradient(left, right) => (false, left, right),
+    let Some((kind, start, end)) = decode_gradient(shader) else {
+        return Ok(());
     };
 
-    let gradient = defs.add_gradient(vertical, start, end);
+    let gradient = defs.add_gradient(kind, start, end);
 
     if defs.ptr_lookup.insert(Rc::as_ptr(&gradient) as usize) {
         gradient.id.set(*current_id);
         *current_id += 1;
 
-        writer.tag_with_content(
-            "linearGradient",
-            [
-                (
-                    "id",
-                    DisplayAlreadyEscaped(format_args!("{}", gradient.id.get())),
-                ),
-                (
-                    "x2",
-                    DisplayAlreadyEscaped(format_args!("{}", if vertical { "0" } else { "1" })),
-                ),
-                (
-                    "y2",
-                    DisplayAlreadyEscaped(format_args!("{}", if vertical { "1" } else { "0" })),
-                ),
-            ],
-            |writer| {
+        let tag = match kind {
+            GradientKind::Linear(_) => "linearGradient",
+            GradientKind::Radial => "radialGradient",
+        };
+
+        writer.tag(tag, |mut writer| {
+            writer.attribute(
+                "id",
+                DisplayAlreadyEscaped(format_args!("{}", gradient.id.get())),
+            )?;
+            match kind {
+                GradientKind::Linear([x1, y1, x2, y2]) => {
+                    // The start point defaults to the origin.
+                    if x1 != 0.0 {
+                        writer.attribute("x1", DisplayAlreadyEscaped(x1))?;
+                    }
+                    if y1 != 0.0 {
+                        writer.attribute("y1", DisplayAlreadyEscaped(y1))?;
+                    }
+                    writer.attribute("x2", DisplayAlreadyEscaped(x2))?;
+                    writer.attribute("y2", DisplayAlreadyEscaped(y2))?;
+                }
+                GradientKind::Radial => {
+                    // The radius needs to reach the corners of the bounding
+                    // box, which are half of the square root of 2 away from
+                    // the center.
+                    writer.attribute("r", Text::new_escaped("0.7071"))?;
+                }
+            }
+            writer.content(|writer| {
                 writer.tag("stop", |mut writer| {
                     let (start_rgb, start_a) = convert_color_or_transparent(start);
                     writer.attribute("stop-color", start_rgb)?;
@@ -432,28 +455,65 @@ fn visit_shader<W: Write>(
                     }
                     Ok(())
                 })
-            },
-        )?;
+            })
+        })?;
     }
 
     Ok(())
 }
 
+/// Splits a shader into the kind of gradient and its start and end colors. For
+/// solid colors [`None`] is returned. The linear gradients are described by
+/// their start and end point in the bounding box of the element they fill.
+fn decode_gradient(shader: &FillShader) -> Option<(GradientKind, &[f32; 4], &[f32; 4])> {
+    Some(match shader {
+        FillShader::SolidColor(_) => return None,
+        FillShader::VerticalGradient(top, bottom) => {
+            (GradientKind::Linear([0.0, 0.0, 0.0, 1.0]), top, bottom)
+        }
+        FillShader::HorizontalGradient(left, right) => {
+            (GradientKind::Linear([0.0, 0.0, 1.0, 0.0]), left, right)
+        }
+        FillShader::LinearGradient(angle, start, end) => {
+            let [[x1, y1], [x2, y2]] = linear_gradient_points(*angle, [0.0, 0.0, 1.0, 1.0]);
+            (GradientKind::Linear([x1, y1, x2, y2]), start, end)
+        }
+        FillShader::RadialGradient(center, edge) => (GradientKind::Radial, center, edge),
+    })
+}
+
 struct SvgAllocator {
     text_engine: TextEngine<SvgPath>,
     defs: Rc<RefCell<Defs>>,
 }
 
+#[derive(Copy, Clone)]
+enum GradientKind {
+    /// A linear gradient from (x1, y1) to (x2, y2).
+    Linear([f32; 4]),
+    /// A radial gradient from the center to the corners.
+    Radial,
+}
+
+impl GradientKind {
+    fn bits(self) -> Option<[u32; 4]> {
+        match self {
+            Self::Linear(points) => Some(points.map(f32::to_bits)),
+            Self::Radial => None,
+        }
+    }
+}
+
 struct Gradient {
     id: Cell<usize>,
-    vertical: bool,
+    kind: GradientKind,
     start: [f32; 4],
     end: [f32; 4],
 }
 
 impl core::hash::Hash for Gradient {
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
-        self.vertical.hash(state);
+        self.kind.bits().hash(state);
         self.start.map(f32::to_bits).hash(state);
         self.end.map(f32::to_bits).hash(state);
     }
@@ -461,7 +521,7 @@ impl core::hash::Hash for Gradient {
 
 impl PartialEq for Gradient {
     fn eq(&self, other: &Self) -> bool {
-        self.vertical == other.vertical
+        self.kind.bits() == other.kind.bits()
             && self.start.map(f32::to_bits) == other.start.map(f32::to_bits)
             && self.end.map(f32::to_bits) == other.end.map(f32::to_bits)
     }
@@ -475,12 +535,17 @@ struct Defs {
 }
 
 impl Defs {
-    fn add_gradient(&mut self, vertical: bool, start: &[f32; 4], end: &[f32; 4]) -> Rc<Gradient> {
+    fn add_gradient(
+        &mut self,
+        kind: GradientKind,
+        start: &[f32; 4],
+        end: &[f32; 4],
+    ) -> Rc<Gradient> {
         let hasher = foldhash::fast::FixedState::default();
         let hasher = |val: &Gradient| hasher.hash_one(val);
         let gradient = Gradient {
             id: Cell::new(0),
-            vertical,
+            kind,
             start: *start,
             end: *end,
         };
@@ -917,12 +982,9 @@ fn convert_shader(shader: &FillShader, defs: &Rc<RefCell<Defs>>) -> Option<(Fill
             let (rgb, a) = convert_color(c)?;
             (Fill::Rgb(rgb), a)
         }
-        FillShader::VerticalGradient(top, bottom) => {
-            let gradient = defs.borrow_mut().add_gradient(true, top, bottom);
-            (Fill::Url(gradient.id.get()), None)
-        }
-        FillShader::HorizontalGradient(left, right) => {
-            let gradient = defs.borrow_mut().add_gradient(false, left, right);
+        _ => {
+            let (kind, start, end) = decode_gradient(shader)?;
+            let gradient = defs.borrow_mut().add_gradient(kind, start, end);
             (Fill::Url(gradient.id.get()), None)
         }
     })
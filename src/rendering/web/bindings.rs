@@ -40,6 +40,16 @@ extern "C" {
         x1: f64,
         y1: f64,
     ) -> web_sys::CanvasGradient;
+    #[wasm_bindgen(catch, method, structural, js_class = "CanvasRenderingContext2D", js_name = createRadialGradient)]
+    pub fn create_radial_gradient(
+        this: &CanvasRenderingContext2d,
+        x0: f64,
+        y0: f64,
+        r0: f64,
+        x1: f64,
+        y1: f64,
+        r1: f64,
+    ) -> Result<web_sys::CanvasGradient, JsValue>;
     #[wasm_bindgen(method, structural, js_class = "CanvasRenderingContext2D", js_name = clearRect)]
     pub fn clear_rect(this: &CanvasRenderingContext2d, x: f64, y: f64, w: f64, h: f64);
     #[wasm_bindgen(method, structural, js_class = "CanvasRenderingContext2D", js_name = fillRect)]
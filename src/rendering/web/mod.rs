@@ -24,8 +24,8 @@ use crate::{
 use self::bindings::CanvasRenderingContext2d;
 
 use super::{
-    Background, Entity, FillShader, FontKind, Label, PathBuilder, ResourceAllocator, SceneManager,
-    SharedOwnership, Transform,
+    linear_gradient_points, radial_gradient_circle, Background, Entity, FillShader, FontKind,
+    Label, PathBuilder, ResourceAllocator, SceneManager, SharedOwnership, Transform,
 };
 
 mod bindings;
@@ -709,6 +709,8 @@ enum HashShader {
     SolidColor([u32; 4]),
     VerticalGradient([u32; 4], [u32; 4], [u32; 2]),
     HorizontalGradient([u32; 4], [u32; 4], [u32; 2]),
+    LinearGradient(u32, [u32; 4], [u32; 4], [u32; 4]),
+    RadialGradient([u32; 4], [u32; 4], [u32; 4]),
 }
 
 trait HasBounds {
@@ -716,6 +718,12 @@ trait HasBounds {
     fn bounds_y(&self) -> [f32; 2];
 }
 
+fn bounds(handle: &impl HasBounds) -> [f32; 4] {
+    let [left, right] = handle.bounds_x();
+    let [top, bottom] = handle.bounds_y();
+    [left, top, right, bottom]
+}
+
 impl HasBounds for Path {
     fn bounds_x(&self) -> [f32; 2] {
         [self.min_x, self.max_x]
@@ -766,6 +774,12 @@ fn set_fill_style(
         FillShader::HorizontalGradient(l, r) => {
             HashShader::HorizontalGradient(cast(l), cast(r), cast(handle.bounds_x()))
         }
+        FillShader::LinearGradient(angle, start, end) => {
+            HashShader::LinearGradient(cast(angle), cast(start), cast(end), cast(bounds(handle)))
+        }
+        FillShader::RadialGradient(center, edge) => {
+            HashShader::RadialGradient(cast(center), cast(edge), cast(bounds(handle)))
+        }
     };
     let style = cache.entry(hash_shader).or_insert_with(|| match shader {
         FillShader::SolidColor(c) => JsValue::from_str(color(str_buf, c)),
@@ -783,6 +797,26 @@ fn set_fill_style(
             let _ = gradient.add_color_stop(1.0, color(str_buf, r));
             gradient.unchecked_into()
         }
+        FillShader::LinearGradient(angle, start, end) => {
+            let [[x1, y1], [x2, y2]] = linear_gradient_points(*angle, bounds(handle));
+            let gradient = ctx.create_linear_gradient(x1 as _, y1 as _, x2 as _, y2 as _);
+            let _ = gradient.add_color_stop(0.0, color(str_buf, start));
+            let _ = gradient.add_color_stop(1.0, color(str_buf, end));
+            gradient.unchecked_into()
+        }
+        FillShader::RadialGradient(center, edge) => {
+            let ([x, y], radius) = radial_gradient_circle(bounds(handle));
+            match ctx.create_radial_gradient(x as _, y as _, 0.0, x as _, y as _, radius as _) {
+                Ok(gradient) => {
+                    let _ = gradient.add_color_stop(0.0, color(str_buf, center));
+                    let _ = gradient.add_color_stop(1.0, color(str_buf, edge));
+                    gradient.unchecked_into()
+                }
+                // The radius is negative or not finite if the bounds are
+                // degenerate.
+                Err(_) => JsValue::from_str(color(str_buf, edge)),
+            }
+        }
     });
     ctx.set_fill_style(style);
 }
@@ -0,0 +1,1014 @@
+//! Provides a renderer that renders the layout on the GPU via wgpu. The paths
+//! are tessellated into triangles once and stay cached on the GPU, so the CPU
+//! barely needs to do any work for rendering a frame. This makes it suitable
+//! for frontends that render with a high refresh rate.
+
+use super::{
+    default_text_engine::{Font, Label, TextEngine},
+    entity::Entity,
+    linear_gradient_points, radial_gradient_circle,
+    resource::{self, ResourceAllocator},
+    Background, FillShader, FontKind, Rgba, SceneManager, Transform,
+};
+use crate::{layout::LayoutState, platform::prelude::*, settings, settings::ImageCache};
+use alloc::rc::Rc;
+use bytemuck_derive::{Pod, Zeroable};
+use core::{cell::RefCell, mem};
+use lyon_tessellation::{
+    math::point,
+    path::{path::Builder, Path as LyonPath},
+    BuffersBuilder, FillOptions, FillRule, FillTessellator, FillVertex, StrokeOptions,
+    StrokeTessellator, StrokeVertex, VertexBuffers,
+};
+use wgpu::util::DeviceExt;
+
+#[cfg(feature = "image")]
+use super::blur_background_image;
+#[cfg(feature = "image")]
+use crate::settings::BackgroundImage;
+
+/// The maximum distance in pixels the tessellated triangles may deviate from
+/// the curves of the paths.
+const TOLERANCE: f32 = 0.1;
+
+/// The number of samples used for anti-aliasing the edges of the triangles.
+const SAMPLE_COUNT: u32 = 4;
+
+const KIND_SOLID: u32 = 0;
+const KIND_LINEAR: u32 = 1;
+const KIND_RADIAL: u32 = 2;
+const KIND_IMAGE: u32 = 3;
+
+type WgpuPath = Option<Rc<Path>>;
+type WgpuImage = Rc<Image>;
+type WgpuLabel = Label<WgpuPath>;
+
+struct Mesh {
+    vertices: wgpu::Buffer,
+    indices: wgpu::Buffer,
+    index_count: u32,
+}
+
+impl Mesh {
+    fn new(device: &wgpu::Device, buffers: &VertexBuffers<[f32; 2], u32>) -> Option<Rc<Self>> {
+        if buffers.indices.is_empty() {
+            return None;
+        }
+        Some(Rc::new(Self {
+            vertices: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("livesplit-core vertices"),
+                contents: bytemuck::cast_slice(&buffers.vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+            indices: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("livesplit-core indices"),
+                contents: bytemuck::cast_slice(&buffers.indices),
+                usage: wgpu::BufferUsages::INDEX,
+            }),
+            index_count: buffers.indices.len() as u32,
+        }))
+    }
+}
+
+/// A path that is tessellated the first time it gets drawn. The triangles
+/// depend on how large the path ends up on the screen, so it is tessellated
+/// again if it gets drawn larger than the cached triangles allow.
+struct Path {
+    path: LyonPath,
+    bounds: [f32; 4],
+    fill: RefCell<Option<(f32, Option<Rc<Mesh>>)>>,
+    stroke: RefCell<Option<(f32, f32, Option<Rc<Mesh>>)>>,
+}
+
+impl Path {
+    fn fill_mesh(
+        &self,
+        device: &wgpu::Device,
+        tessellator: &mut FillTessellator,
+        tolerance: f32,
+    ) -> Option<Rc<Mesh>> {
+        let mut cached = self.fill.borrow_mut();
+        if !cached
+            .as_ref()
+            .is_some_and(|&(cached_tolerance, _)| cached_tolerance <= 2.0 * tolerance)
+        {
+            let mut buffers = VertexBuffers::new();
+            let _ = tessellator.tessellate_path(
+                &self.path,
+                &FillOptions::tolerance(tolerance).with_fill_rule(FillRule::NonZero),
+                &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex<'_>| {
+                    vertex.position().to_array()
+                }),
+            );
+            *cached = Some((tolerance, Mesh::new(device, &buffers)));
+        }
+        cached.as_ref()?.1.clone()
+    }
+
+    fn stroke_mesh(
+        &self,
+        device: &wgpu::Device,
+        tessellator: &mut StrokeTessellator,
+        width: f32,
+        tolerance: f32,
+    ) -> Option<Rc<Mesh>> {
+        let mut cached = self.stroke.borrow_mut();
+        if !cached
+            .as_ref()
+            .is_some_and(|&(cached_width, cached_tolerance, _)| {
+                cached_width == width && cached_tolerance <= 2.0 * tolerance
+            })
+        {
+            let mut buffers = VertexBuffers::new();
+            let _ = tessellator.tessellate_path(
+                &self.path,
+                &StrokeOptions::tolerance(tolerance).with_line_width(width),
+                &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex<'_, '_>| {
+                    vertex.position().to_array()
+                }),
+            );
+            *cached = Some((width, tolerance, Mesh::new(device, &buffers)));
+        }
+        cached.as_ref()?.2.clone()
+    }
+}
+
+struct PathBuilder {
+    builder: Builder,
+    start: [f32; 2],
+    is_in_contour: bool,
+    is_empty: bool,
+    bounds: [f32; 4],
+}
+
+impl PathBuilder {
+    fn add_point(&mut self, [x, y]: [f32; 2]) {
+        let [left, top, right, bottom] = &mut self.bounds;
+        *left = left.min(x);
+        *top = top.min(y);
+        *right = right.max(x);
+        *bottom = bottom.max(y);
+    }
+
+    /// Ensures there is a contour to add the next segment to. Just like with
+    /// the other renderers, a segment that follows a closed contour starts at
+    /// the start of that contour.
+    fn ensure_contour(&mut self) {
+        if !self.is_in_contour {
+            let [x, y] = self.start;
+            self.builder.begin(point(x, y));
+            self.is_in_contour = true;
+        }
+    }
+}
+
+impl resource::PathBuilder for PathBuilder {
+    type Path = WgpuPath;
+
+    fn move_to(&mut self, x: f32, y: f32) {
+        if self.is_in_contour {
+            self.builder.end(false);
+        }
+        self.builder.begin(point(x, y));
+        self.start = [x, y];
+        self.is_in_contour = true;
+        self.add_point([x, y]);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.ensure_contour();
+        self.builder.line_to(point(x, y));
+        self.is_empty = false;
+        self.add_point([x, y]);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.ensure_contour();
+        self.builder.quadratic_bezier_to(point(x1, y1), point(x, y));
+        self.is_empty = false;
+        self.add_point([x1, y1]);
+        self.add_point([x, y]);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.ensure_contour();
+        self.builder
+            .cubic_bezier_to(point(x1, y1), point(x2, y2), point(x, y));
+        self.is_empty = false;
+        self.add_point([x1, y1]);
+        self.add_point([x2, y2]);
+        self.add_point([x, y]);
+    }
+
+    fn close(&mut self) {
+        if self.is_in_contour {
+            self.builder.end(true);
+            self.is_in_contour = false;
+        }
+    }
+
+    fn finish(mut self) -> Self::Path {
+        if self.is_in_contour {
+            self.builder.end(false);
+        }
+        if self.is_empty {
+            return None;
+        }
+        Some(Rc::new(Path {
+            path: self.builder.build(),
+            bounds: self.bounds,
+            fill: RefCell::new(None),
+            stroke: RefCell::new(None),
+        }))
+    }
+}
+
+fn path_builder() -> PathBuilder {
+    PathBuilder {
+        builder: LyonPath::builder(),
+        start: [0.0; 2],
+        is_in_contour: false,
+        is_empty: true,
+        bounds: [
+            f32::INFINITY,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::NEG_INFINITY,
+        ],
+    }
+}
+
+/// An image that is uploaded to the GPU the first time it gets drawn. The
+/// pixels are stored with premultiplied alpha.
+struct Image {
+    size: [u32; 2],
+    data: Vec<u8>,
+    bind_group: RefCell<Option<Rc<wgpu::BindGroup>>>,
+}
+
+impl Image {
+    fn bind_group(
+        &self,
+        pipeline: &Pipeline,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Rc<wgpu::BindGroup> {
+        self.bind_group
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                Rc::new(bind_image(
+                    device,
+                    queue,
+                    &pipeline.bind_group_layout,
+                    &pipeline.sampler,
+                    self.size,
+                    &self.data,
+                ))
+            })
+            .clone()
+    }
+}
+
+impl resource::Image for WgpuImage {
+    fn aspect_ratio(&self) -> f32 {
+        let [width, height] = self.size;
+        width as f32 / height as f32
+    }
+}
+
+struct WgpuAllocator {
+    text_engine: TextEngine<WgpuPath>,
+}
+
+impl ResourceAllocator for WgpuAllocator {
+    type PathBuilder = PathBuilder;
+    type Path = WgpuPath;
+    type Image = WgpuImage;
+    type Font = Font;
+    type Label = WgpuLabel;
+
+    fn path_builder(&mut self) -> Self::PathBuilder {
+        path_builder()
+    }
+
+    fn create_image(&mut self, _data: &[u8]) -> Option<Self::Image> {
+        #[cfg(feature = "image")]
+        {
+            let mut buf = image::load_from_memory(_data).ok()?.to_rgba8();
+
+            for [r, g, b, a] in bytemuck::cast_slice_mut::<u8, [u8; 4]>(&mut buf) {
+                let a = *a as u16;
+                *r = ((*r as u16 * a) / 255) as u8;
+                *g = ((*g as u16 * a) / 255) as u8;
+                *b = ((*b as u16 * a) / 255) as u8;
+            }
+
+            let size = [buf.width(), buf.height()];
+            if size.contains(&0) {
+                return None;
+            }
+
+            Some(Rc::new(Image {
+                size,
+                data: buf.into_raw(),
+                bind_group: RefCell::new(None),
+            }))
+        }
+        #[cfg(not(feature = "image"))]
+        {
+            None
+        }
+    }
+
+    fn create_font(&mut self, font: Option<&settings::Font>, kind: FontKind) -> Self::Font {
+        self.text_engine.create_font(font, kind)
+    }
+
+    fn create_label(
+        &mut self,
+        text: &str,
+        font: &mut Self::Font,
+        max_width: Option<f32>,
+    ) -> Self::Label {
+        self.text_engine
+            .create_label(path_builder, text, font, max_width)
+    }
+
+    fn update_label(
+        &mut self,
+        label: &mut Self::Label,
+        text: &str,
+        font: &mut Self::Font,
+        max_width: Option<f32>,
+    ) {
+        self.text_engine
+            .update_label(path_builder, label, text, font, max_width)
+    }
+}
+
+/// Describes a single draw call. This is stored in the instance buffer.
+#[derive(Copy, Clone, Pod, Zeroable)]
+#[repr(C)]
+struct Instance {
+    transform: [f32; 4],
+    gradient: [f32; 4],
+    start_color: Rgba,
+    end_color: Rgba,
+    shader_scale: [f32; 2],
+    kind: u32,
+    _padding: u32,
+}
+
+struct Draw {
+    mesh: Rc<Mesh>,
+    bind_group: Rc<wgpu::BindGroup>,
+}
+
+/// The GPU resources that stay the same for every frame.
+struct Pipeline {
+    format: wgpu::TextureFormat,
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    empty_bind_group: Rc<wgpu::BindGroup>,
+    square: Rc<Mesh>,
+}
+
+impl Pipeline {
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 1] =
+            wgpu::vertex_attr_array![0 => Float32x2];
+        const INSTANCE_ATTRIBUTES: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+            4 => Float32x4,
+            5 => Float32x2,
+            6 => Uint32,
+        ];
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("livesplit-core shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("livesplit-core image"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("livesplit-core pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("livesplit-core pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[
+                    wgpu::VertexBufferLayout {
+                        array_stride: mem::size_of::<[f32; 2]>() as _,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &VERTEX_ATTRIBUTES,
+                    },
+                    wgpu::VertexBufferLayout {
+                        array_stride: mem::size_of::<Instance>() as _,
+                        step_mode: wgpu::VertexStepMode::Instance,
+                        attributes: &INSTANCE_ATTRIBUTES,
+                    },
+                ],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: SAMPLE_COUNT,
+                ..Default::default()
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("livesplit-core sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        // Every draw call needs an image bound, even if it doesn't use it.
+        let empty_bind_group = Rc::new(bind_image(
+            device,
+            queue,
+            &bind_group_layout,
+            &sampler,
+            [1, 1],
+            &[0; 4],
+        ));
+
+        Self {
+            format,
+            pipeline,
+            bind_group_layout,
+            sampler,
+            empty_bind_group,
+            square: Mesh::new(
+                device,
+                &VertexBuffers {
+                    vertices: vec![[0.0, 0.0], [0.0, 1.0], [1.0, 1.0], [1.0, 0.0]],
+                    indices: vec![0, 1, 2, 0, 2, 3],
+                },
+            )
+            .unwrap(),
+        }
+    }
+}
+
+fn bind_image(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    [width, height]: [u32; 2],
+    data: &[u8],
+) -> wgpu::BindGroup {
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("livesplit-core image"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        data,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * width),
+            rows_per_image: Some(height),
+        },
+        size,
+    );
+    let view = texture.create_view(&Default::default());
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("livesplit-core image"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
+}
+
+/// Collects the draw calls of a frame.
+struct Frame<'a> {
+    pipeline: &'a Pipeline,
+    device: &'a wgpu::Device,
+    queue: &'a wgpu::Queue,
+    resolution: [f32; 2],
+    instances: &'a mut Vec<Instance>,
+    draws: &'a mut Vec<Draw>,
+    fill_tessellator: &'a mut FillTessellator,
+    stroke_tessellator: &'a mut StrokeTessellator,
+}
+
+impl Frame<'_> {
+    fn push(
+        &mut self,
+        mesh: Rc<Mesh>,
+        bind_group: Rc<wgpu::BindGroup>,
+        transform: &Transform,
+        shading: Shading,
+    ) {
+        let [width, height] = self.resolution;
+        self.instances.push(Instance {
+            transform: [
+                2.0 * transform.scale_x / width,
+                -2.0 * transform.scale_y / height,
+                2.0 * transform.x / width - 1.0,
+                1.0 - 2.0 * transform.y / height,
+            ],
+            gradient: shading.gradient,
+            start_color: shading.start_color,
+            end_color: shading.end_color,
+            shader_scale: shading.shader_scale,
+            kind: shading.kind,
+            _padding: 0,
+        });
+        self.draws.push(Draw { mesh, bind_group });
+    }
+
+    fn fill_path(&mut self, path: &Path, transform: &Transform, shading: Shading) {
+        if let Some(mesh) = path.fill_mesh(self.device, self.fill_tessellator, tolerance(transform))
+        {
+            self.push(
+                mesh,
+                self.pipeline.empty_bind_group.clone(),
+                transform,
+                shading,
+            );
+        }
+    }
+
+    fn stroke_path(&mut self, path: &Path, width: f32, color: &Rgba, transform: &Transform) {
+        if let Some(mesh) = path.stroke_mesh(
+            self.device,
+            self.stroke_tessellator,
+            width,
+            tolerance(transform),
+        ) {
+            self.push(
+                mesh,
+                self.pipeline.empty_bind_group.clone(),
+                transform,
+                Shading::solid(color),
+            );
+        }
+    }
+
+    fn image(&mut self, image: &Image, multiplier: Rgba, transform: &Transform) {
+        let bind_group = image.bind_group(self.pipeline, self.device, self.queue);
+        self.push(
+            self.pipeline.square.clone(),
+            bind_group,
+            transform,
+            Shading {
+                gradient: [0.0; 4],
+                start_color: multiplier,
+                end_color: multiplier,
+                shader_scale: [1.0; 2],
+                kind: KIND_IMAGE,
+            },
+        );
+    }
+
+    fn layer(&mut self, layer: &[Entity<WgpuPath, WgpuImage, WgpuLabel>]) {
+        for entity in layer {
+            match entity {
+                Entity::FillPath(path, shader, transform) => {
+                    if let Some(path) = path.as_deref() {
+                        self.fill_path(path, transform, Shading::new(shader, path.bounds));
+                    }
+                }
+                Entity::StrokePath(path, stroke_width, color, transform) => {
+                    if let Some(path) = path.as_deref() {
+                        self.stroke_path(path, *stroke_width, color, transform);
+                    }
+                }
+                Entity::Image(image, transform) => {
+                    self.image(image, [1.0; 4], transform);
+                }
+                Entity::Label(label, shader, transform) => {
+                    let label = label.read().unwrap();
+
+                    let mut bounds = [
+                        f32::INFINITY,
+                        f32::INFINITY,
+                        f32::NEG_INFINITY,
+                        f32::NEG_INFINITY,
+                    ];
+                    for path in label
+                        .glyphs()
+                        .iter()
+                        .filter_map(|glyph| glyph.path.as_deref())
+                    {
+                        let [left, top, right, bottom] = path.bounds;
+                        bounds = [
+                            bounds[0].min(left),
+                            bounds[1].min(top),
+                            bounds[2].max(right),
+                            bounds[3].max(bottom),
+                        ];
+                    }
+                    let shading = Shading::new(shader, bounds);
+
+                    for glyph in label.glyphs() {
+                        if let Some(path) = glyph.path.as_deref() {
+                            let transform = transform
+                                .pre_translate(glyph.x, glyph.y)
+                                .pre_scale(glyph.scale, glyph.scale);
+
+                            let shading = match &glyph.color {
+                                Some(color) => Shading::solid(color),
+                                None => shading,
+                            };
+
+                            self.fill_path(path, &transform, shading);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The tolerance in the local coordinate space of a path that results in
+/// triangles that deviate at most by [`TOLERANCE`] pixels from its curves.
+fn tolerance(transform: &Transform) -> f32 {
+    TOLERANCE / transform.scale_x.abs().max(transform.scale_y.abs())
+}
+
+/// Describes how to color the triangles of a draw call.
+#[derive(Copy, Clone)]
+struct Shading {
+    gradient: [f32; 4],
+    start_color: Rgba,
+    end_color: Rgba,
+    shader_scale: [f32; 2],
+    kind: u32,
+}
+
+impl Shading {
+    const fn solid(color: &Rgba) -> Self {
+        Self {
+            gradient: [0.0; 4],
+            start_color: *color,
+            end_color: *color,
+            shader_scale: [1.0; 2],
+            kind: KIND_SOLID,
+        }
+    }
+
+    /// Describes the shader for a path with the bounds (left, top, right,
+    /// bottom) provided in its local coordinate space. Just like with the
+    /// other renderers, the gradients span these bounds.
+    fn new(shader: &FillShader, bounds @ [left, top, right, bottom]: [f32; 4]) -> Self {
+        let ([[x1, y1], [x2, y2]], start, end) = match shader {
+            FillShader::SolidColor(color) => return Self::solid(color),
+            FillShader::VerticalGradient(top_color, bottom_color) => {
+                ([[0.0, top], [0.0, bottom]], top_color, bottom_color)
+            }
+            FillShader::HorizontalGradient(left_color, right_color) => {
+                ([[left, 0.0], [right, 0.0]], left_color, right_color)
+            }
+            FillShader::LinearGradient(angle, start, end) => {
+                (linear_gradient_points(*angle, bounds), start, end)
+            }
+            FillShader::RadialGradient(center, edge) => {
+                let ([x, y], radius) = radial_gradient_circle(bounds);
+                if radius.is_nan() || radius <= 0.0 {
+                    return Self::solid(edge);
+                }
+                return Self {
+                    gradient: [x, y, radius, 0.0],
+                    start_color: *center,
+                    end_color: *edge,
+                    shader_scale: [1.0; 2],
+                    kind: KIND_RADIAL,
+                };
+            }
+        };
+
+        if (x1 == x2 && y1 == y2) || !(x1 + y1 + x2 + y2).is_finite() {
+            return Self::solid(end);
+        }
+
+        Self {
+            gradient: [x1, y1, x2, y2],
+            start_color: *start,
+            end_color: *end,
+            shader_scale: [1.0; 2],
+            kind: KIND_LINEAR,
+        }
+    }
+}
+
+/// The wgpu renderer renders layouts on the GPU. It renders into a texture
+/// view provided by the caller, such as the texture of a surface. The
+/// renderer needs to be used with the same device it got created with. The
+/// colors are blended in the color space of the texture. In order to look the
+/// same as the software renderer, the texture should use a format that isn't
+/// sRGB, such as [`Rgba8Unorm`](wgpu::TextureFormat::Rgba8Unorm) or
+/// [`Bgra8Unorm`](wgpu::TextureFormat::Bgra8Unorm).
+pub struct Renderer {
+    allocator: WgpuAllocator,
+    scene_manager: SceneManager<WgpuPath, WgpuImage, Font, WgpuLabel>,
+    pipeline: Pipeline,
+    instances: Vec<Instance>,
+    draws: Vec<Draw>,
+    instance_buffer: Option<wgpu::Buffer>,
+    multisampled: Option<([u32; 2], wgpu::TextureView)>,
+    fill_tessellator: FillTessellator,
+    stroke_tessellator: StrokeTessellator,
+    #[cfg(feature = "image")]
+    blurred_background_image: Option<(BackgroundImage<usize>, WgpuImage)>,
+}
+
+impl Renderer {
+    /// Creates a new wgpu renderer that renders into textures of the format
+    /// provided.
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        let mut allocator = WgpuAllocator {
+            text_engine: TextEngine::new(),
+        };
+        let scene_manager = SceneManager::new(&mut allocator);
+        Self {
+            allocator,
+            scene_manager,
+            pipeline: Pipeline::new(device, queue, format),
+            instances: Vec::new(),
+            draws: Vec::new(),
+            instance_buffer: None,
+            multisampled: None,
+            fill_tessellator: FillTessellator::new(),
+            stroke_tessellator: StrokeTessellator::new(),
+            #[cfg(feature = "image")]
+            blurred_background_image: None,
+        }
+    }
+
+    /// Renders the layout state provided into the texture view provided. The
+    /// resolution needs to match the size of the texture. It may detect that
+    /// the layout got resized. In that case it returns the new ideal size.
+    /// This is just a hint and can be ignored entirely. The image is always
+    /// rendered with the resolution provided. The whole texture is always
+    /// redrawn, so it doesn't need to keep its contents between the frames.
+    /// The texture may also be one that is shared with other processes, such
+    /// as capture plugins, if it got imported into the device by the caller.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &wgpu::TextureView,
+        state: &LayoutState,
+        image_cache: &ImageCache,
+        [width, height]: [u32; 2],
+    ) -> Option<[f32; 2]> {
+        let new_resolution = self.scene_manager.update_scene(
+            &mut self.allocator,
+            [width as _, height as _],
+            state,
+            image_cache,
+        );
+
+        let scene = self.scene_manager.scene();
+
+        #[cfg(feature = "image")]
+        update_blurred_background_image(scene.background(), &mut self.blurred_background_image);
+
+        self.instances.clear();
+        self.draws.clear();
+
+        let mut frame = Frame {
+            pipeline: &self.pipeline,
+            device,
+            queue,
+            resolution: [width as _, height as _],
+            instances: &mut self.instances,
+            draws: &mut self.draws,
+            fill_tessellator: &mut self.fill_tessellator,
+            stroke_tessellator: &mut self.stroke_tessellator,
+        };
+
+        match scene.background() {
+            Some(Background::Shader(shader)) => {
+                // The gradient of the background spans the whole texture.
+                let [width, height] = frame.resolution;
+                let shading = Shading {
+                    shader_scale: [width, height],
+                    ..Shading::new(shader, [0.0, 0.0, width, height])
+                };
+                frame.push(
+                    frame.pipeline.square.clone(),
+                    frame.pipeline.empty_bind_group.clone(),
+                    &Transform::scale(width, height),
+                    shading,
+                );
+            }
+            Some(Background::Image(image, transform)) => {
+                #[cfg(feature = "image")]
+                let texture = match &self.blurred_background_image {
+                    Some((_, blurred)) if image.blur != 0.0 => blurred,
+                    _ => &*image.image,
+                };
+                #[cfg(not(feature = "image"))]
+                let texture = &*image.image;
+
+                let brightness = image.brightness.clamp(0.0, 1.0) * image.opacity;
+                frame.image(
+                    texture,
+                    [brightness, brightness, brightness, image.opacity],
+                    transform,
+                );
+            }
+            None => {}
+        }
+
+        frame.layer(scene.bottom_layer());
+        frame.layer(scene.top_layer());
+
+        let instance_size = mem::size_of_val(self.instances.as_slice()) as wgpu::BufferAddress;
+        if self
+            .instance_buffer
+            .as_ref()
+            .map_or(true, |buffer| buffer.size() < instance_size)
+        {
+            self.instance_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("livesplit-core instances"),
+                size: instance_size.next_power_of_two().max(1 << 12),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+        let instance_buffer = self.instance_buffer.as_ref().unwrap();
+        queue.write_buffer(instance_buffer, 0, bytemuck::cast_slice(&self.instances));
+
+        if self
+            .multisampled
+            .as_ref()
+            .map_or(true, |(size, _)| *size != [width, height])
+        {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("livesplit-core multisampled"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: SAMPLE_COUNT,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.pipeline.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+            self.multisampled = Some(([width, height], texture.create_view(&Default::default())));
+        }
+        let (_, multisampled) = self.multisampled.as_ref().unwrap();
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("livesplit-core"),
+        });
+
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("livesplit-core"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: multisampled,
+                    resolve_target: Some(target),
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Discard,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            pass.set_pipeline(&self.pipeline.pipeline);
+            pass.set_vertex_buffer(1, instance_buffer.slice(..));
+
+            let mut bound: Option<&Rc<wgpu::BindGroup>> = None;
+            for (draw, index) in self.draws.iter().zip(0..) {
+                if !bound.is_some_and(|bound| Rc::ptr_eq(bound, &draw.bind_group)) {
+                    pass.set_bind_group(0, &*draw.bind_group, &[]);
+                    bound = Some(&draw.bind_group);
+                }
+                pass.set_vertex_buffer(0, draw.mesh.vertices.slice(..));
+                pass.set_index_buffer(draw.mesh.indices.slice(..), wgpu::IndexFormat::Uint32);
+                pass.draw_indexed(0..draw.mesh.index_count, 0, index..index + 1);
+            }
+        }
+
+        queue.submit([encoder.finish()]);
+
+        new_resolution
+    }
+
+    /// Loads the font data provided, so that its fonts can be referred to by
+    /// their family name in the font settings of a layout. TrueType and
+    /// OpenType fonts and font collections are supported. Returns whether the
+    /// data contained any fonts.
+    pub fn load_font(&mut self, data: Vec<u8>) -> bool {
+        let loaded = self.allocator.text_engine.load_font(data);
+        if loaded {
+            self.scene_manager.reload_fonts(&mut self.allocator);
+        }
+        loaded
+    }
+}
+
+#[cfg(feature = "image")]
+fn update_blurred_background_image(
+    background: &Option<Background<WgpuImage>>,
+    blurred_background_image: &mut Option<(BackgroundImage<usize>, WgpuImage)>,
+) {
+    match background {
+        Some(Background::Image(image, _)) if image.blur != 0.0 => {
+            let current_key = image.map(image.image.id);
+            if !blurred_background_image
+                .as_ref()
+                .is_some_and(|(key, _)| &current_key == key)
+            {
+                let [width, height] = image.image.size;
+                let original_image = image::ImageBuffer::<image::Rgba<u8>, _>::from_raw(
+                    width,
+                    height,
+                    &*image.image.data,
+                )
+                .unwrap();
+                let blurred = blur_background_image(original_image, image.blur);
+                let blurred = Rc::new(Image {
+                    size: [blurred.width(), blurred.height()],
+                    data: blurred.into_raw(),
+                    bind_group: RefCell::new(None),
+                });
+                *blurred_background_image = Some((current_key, blurred));
+            }
+        }
+        _ => {
+            *blurred_background_image = None;
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// The paths are tessellated into triangles in their local coordinate space.
+// Each draw is an instance that describes how to transform the triangles into
+// clip space and how to color them.
+
+const KIND_SOLID: u32 = 0u;
+const KIND_LINEAR: u32 = 1u;
+const KIND_RADIAL: u32 = 2u;
+const KIND_IMAGE: u32 = 3u;
+
+struct Instance {
+    // The scale (x, y) and the offset (z, w) that transform the local
+    // coordinates into clip space.
+    @location(1) transform: vec4<f32>,
+    // Either the start (x, y) and end (z, w) of a linear gradient or the
+    // center (x, y) and radius (z) of a radial gradient.
+    @location(2) gradient: vec4<f32>,
+    // For images the start color is multiplied with the image.
+    @location(3) start_color: vec4<f32>,
+    @location(4) end_color: vec4<f32>,
+    // Scales the local coordinates into the space the gradient is described
+    // in.
+    @location(5) shader_scale: vec2<f32>,
+    @location(6) kind: u32,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) shader_position: vec2<f32>,
+    @location(1) @interpolate(flat) gradient: vec4<f32>,
+    @location(2) @interpolate(flat) start_color: vec4<f32>,
+    @location(3) @interpolate(flat) end_color: vec4<f32>,
+    @location(4) @interpolate(flat) kind: u32,
+}
+
+@group(0) @binding(0) var image: texture_2d<f32>;
+@group(0) @binding(1) var image_sampler: sampler;
+
+@vertex
+fn vs_main(@location(0) position: vec2<f32>, instance: Instance) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(position * instance.transform.xy + instance.transform.zw, 0.0, 1.0);
+    out.shader_position = position * instance.shader_scale;
+    out.gradient = instance.gradient;
+    out.start_color = instance.start_color;
+    out.end_color = instance.end_color;
+    out.kind = instance.kind;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // The images are stored with premultiplied alpha already.
+    if in.kind == KIND_IMAGE {
+        return textureSampleLevel(image, image_sampler, in.shader_position, 0.0) * in.start_color;
+    }
+
+    var t = 0.0;
+    if in.kind == KIND_LINEAR {
+        let start = in.gradient.xy;
+        let direction = in.gradient.zw - start;
+        t = dot(in.shader_position - start, direction) / dot(direction, direction);
+    } else if in.kind == KIND_RADIAL {
+        t = distance(in.shader_position, in.gradient.xy) / in.gradient.z;
+    }
+
+    let color = mix(in.start_color, in.end_color, clamp(t, 0.0, 1.0));
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
@@ -65,6 +65,13 @@ impl Attempt {
         self.time
     }
 
+    /// Sets the split time of the last segment. If the attempt got reset
+    /// early and didn't finish, this should be empty.
+    #[inline]
+    pub fn set_time(&mut self, time: Time) {
+        self.time = time;
+    }
+
     /// Accesses the amount of time the attempt has been paused for. If it is
     /// not known, this returns `None`. This means that it may not necessarily
     /// be possible to differentiate whether a Run has not been paused or it
@@ -0,0 +1,92 @@
+use crate::{
+    platform::{prelude::*, Duration},
+    settings::Image,
+    Time, TimeSpan, TimingMethod,
+};
+use serde_derive::{Deserialize, Serialize};
+
+/// The Segment Clipboard stores segments that got copied out of a Run Editor,
+/// so they can be pasted into the same or any other Run Editor. Alongside the
+/// names and icons of the segments, their Personal Best segment times, their
+/// best segment times and their Segment Histories are copied. Custom
+/// comparisons are not copied, as they are specific to the run the segments
+/// were copied from. The clipboard can be encoded as JSON, so it can be
+/// stored or transferred between different instances of an application.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SegmentClipboard {
+    pub(super) segments: Vec<ClipboardSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct ClipboardSegment {
+    pub name: String,
+    pub icon: Image,
+    pub personal_best_segment_time: ClipboardTime,
+    pub best_segment_time: ClipboardTime,
+    pub segment_history: Vec<(i32, ClipboardTime)>,
+}
+
+/// A time that is stored losslessly as whole seconds and the nanoseconds past
+/// the last full second for each timing method.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(super) struct ClipboardTime {
+    real_time: Option<(i64, i32)>,
+    game_time: Option<(i64, i32)>,
+}
+
+impl SegmentClipboard {
+    /// Returns the number of segments stored in the clipboard.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns `true` if there are no segments stored in the clipboard.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Accesses the names of the segments stored in the clipboard.
+    pub fn segment_names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.segments.iter().map(|s| s.name.as_str())
+    }
+
+    /// Decodes the clipboard from its JSON representation.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Encodes the clipboard as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+impl From<Time> for ClipboardTime {
+    fn from(time: Time) -> Self {
+        Self {
+            real_time: time
+                .real_time
+                .map(|t| t.to_seconds_and_subsec_nanoseconds()),
+            game_time: time
+                .game_time
+                .map(|t| t.to_seconds_and_subsec_nanoseconds()),
+        }
+    }
+}
+
+impl From<ClipboardTime> for Time {
+    fn from(time: ClipboardTime) -> Self {
+        let convert = |t: Option<(i64, i32)>| {
+            t.map(|(seconds, nanos)| TimeSpan::from(Duration::new(seconds, nanos)))
+        };
+        Time::new()
+            .with_real_time(convert(time.real_time))
+            .with_game_time(convert(time.game_time))
+    }
+}
+
+impl ClipboardTime {
+    pub fn get(self, method: TimingMethod) -> Option<TimeSpan> {
+        Time::from(self)[method]
+    }
+}
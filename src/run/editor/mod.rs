@@ -4,20 +4,24 @@
 //! current state of the editor as state objects that can be visualized by any
 //! kind of User Interface.
 
+use self::clipboard::ClipboardSegment;
 use super::{AddComparisonError, CopyComparisonError, LinkedLayout};
 use crate::{
     comparison,
     platform::prelude::*,
-    settings::Image,
+    settings::{Image, ImageTransform},
     timing::ParseError as ParseTimeSpanError,
     util::{caseless, PopulateString},
     Run, Segment, Time, TimeSpan, TimingMethod,
 };
 use core::{mem::swap, num::ParseIntError};
+use hashbrown::HashMap;
 use snafu::{OptionExt, ResultExt};
 
 pub mod cleaning;
+mod clipboard;
 mod fuzzy_list;
+mod segment_import;
 mod segment_row;
 mod state;
 #[cfg(test)]
@@ -25,9 +29,14 @@ mod tests;
 
 pub use self::{
     cleaning::SumOfBestCleaner,
+    clipboard::SegmentClipboard,
     fuzzy_list::FuzzyList,
+    segment_import::{ImportColumn, SegmentImport},
     segment_row::SegmentRow,
-    state::{Buttons as ButtonsState, Segment as SegmentState, SelectionState, State},
+    state::{
+        Buttons as ButtonsState, Segment as SegmentState, SegmentImport as SegmentImportState,
+        SelectionState, State,
+    },
 };
 
 /// Describes an Error that occurred while parsing a time.
@@ -67,6 +76,40 @@ pub enum RenameError {
     },
 }
 
+/// Describes an Error that occurred while editing an Attempt.
+#[derive(Debug, snafu::Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum AttemptError {
+    /// There is no attempt with the given index in the Attempt History.
+    UnknownAttempt,
+    /// The attempt didn't reach the segment, so it has no segment time that
+    /// could be edited.
+    SegmentNotReached,
+    /// The segment time couldn't be parsed.
+    InvalidSegmentTime {
+        /// The underlying error.
+        source: ParseError,
+    },
+}
+
+/// Describes an Error that occurred while finishing a Segment Import.
+#[derive(Debug, snafu::Snafu)]
+#[snafu(context(suffix(false)))]
+pub enum ImportError {
+    /// There is no Segment Import in progress.
+    NoImportInProgress,
+    /// The table doesn't contain any segments.
+    NoSegments,
+    /// A time in the table couldn't be parsed.
+    InvalidTime {
+        /// The index of the row that contains the time, not counting the
+        /// header.
+        row: usize,
+        /// The underlying error.
+        source: ParseError,
+    },
+}
+
 /// The Run Editor allows modifying Runs while ensuring that all the different
 /// invariants of the Run objects are upheld no matter what kind of operations
 /// are being applied to the Run. It provides the current state of the editor as
@@ -77,6 +120,7 @@ pub struct Editor {
     selected_segments: Vec<usize>,
     previous_personal_best_time: Time,
     segment_times: Vec<Option<TimeSpan>>,
+    segment_import: Option<SegmentImport>,
 }
 
 impl Editor {
@@ -97,6 +141,7 @@ impl Editor {
             selected_segments: vec![0],
             previous_personal_best_time: personal_best_time,
             segment_times: Vec::with_capacity(len),
+            segment_import: None,
         };
 
         editor.update_segment_list();
@@ -297,6 +342,26 @@ impl Editor {
         self.raise_run_edited();
     }
 
+    /// Transforms the game's icon and the icons of all the segments as
+    /// described by the transformation provided. This can be used to shrink
+    /// icons that were chosen before the transformation got applied when
+    /// setting icons, so they don't unnecessarily increase the size of the
+    /// splits file.
+    pub fn transform_icons(&mut self, transform: &ImageTransform) {
+        let transform_icon = |icon: &Image| {
+            if icon.is_empty() {
+                icon.clone()
+            } else {
+                Image::with_transform(icon.data().into(), transform)
+            }
+        };
+        self.run.set_game_icon(transform_icon(self.run.game_icon()));
+        for segment in self.run.segments_mut() {
+            segment.set_icon(transform_icon(segment.icon()));
+        }
+        self.raise_run_edited();
+    }
+
     /// Sets the [`LinkedLayout`] of the [`Run`]. If a [`Layout`](crate::Layout)
     /// is linked, it is supposed to be loaded to visualize the [`Run`].
     pub fn set_linked_layout(&mut self, linked_layout: Option<LinkedLayout>) {
@@ -742,6 +807,182 @@ impl Editor {
         self.fix();
     }
 
+    /// Checks if the currently selected segments can be moved by the given
+    /// number of positions. Negative offsets move the segments up, positive
+    /// offsets move them down. The segments can't be moved if any one of them
+    /// would be moved beyond the first or the last segment.
+    pub fn can_move_segments_by(&self, offset: isize) -> bool {
+        self.selected_segments.iter().all(|&s| {
+            s.checked_add_signed(offset)
+                .is_some_and(|i| i < self.run.len())
+        })
+    }
+
+    /// Moves all the selected segments by the given number of positions,
+    /// unless any one of them would be moved beyond the first or the last
+    /// segment. Negative offsets move the segments up, positive offsets move
+    /// them down. The run's information is automatically adjusted properly.
+    /// The active segment stays the active segment.
+    pub fn move_segments_by(&mut self, offset: isize) {
+        if !self.can_move_segments_by(offset) {
+            return;
+        }
+
+        for _ in 0..offset.unsigned_abs() {
+            if offset < 0 {
+                self.move_segments_up();
+            } else {
+                self.move_segments_down();
+            }
+        }
+    }
+
+    /// Removes all the segments with the given indices, unless that would
+    /// remove all the segments or any of the indices is out of bounds. The
+    /// segments are selected before getting removed, so the same rules as for
+    /// [`remove_segments`](Self::remove_segments) apply for choosing the new
+    /// active segment, with the last index provided being the active segment.
+    pub fn remove_segments_at(&mut self, indices: &[usize]) {
+        if indices.is_empty() || indices.iter().any(|&i| i >= self.run.len()) {
+            return;
+        }
+
+        let previous_selection = core::mem::take(&mut self.selected_segments);
+        for &index in indices {
+            self.select_additionally(index);
+        }
+        if self.can_remove_segments() {
+            self.remove_segments();
+        } else {
+            self.selected_segments = previous_selection;
+        }
+    }
+
+    fn personal_best_segment_time(&self, index: usize, method: TimingMethod) -> Option<TimeSpan> {
+        let segments = self.run.segments();
+        let previous_time = segments[..index]
+            .iter()
+            .rev()
+            .find_map(|s| s.personal_best_split_time()[method])
+            .unwrap_or_else(TimeSpan::zero);
+        catch! { segments[index].personal_best_split_time()[method]? - previous_time }
+    }
+
+    /// Copies the selected segments into a Segment Clipboard, so they can be
+    /// pasted into this or any other Run Editor. The segments are copied in the
+    /// order they appear in the run, regardless of the order they got selected
+    /// in.
+    pub fn copy_segments(&self) -> SegmentClipboard {
+        let mut indices = self.selected_segments.clone();
+        indices.sort_unstable();
+
+        let segments = indices
+            .into_iter()
+            .map(|index| {
+                let segment = self.run.segment(index);
+                let mut personal_best_segment_time = Time::new();
+                for method in TimingMethod::all() {
+                    personal_best_segment_time[method] =
+                        self.personal_best_segment_time(index, method);
+                }
+                ClipboardSegment {
+                    name: segment.name().to_owned(),
+                    icon: segment.icon().clone(),
+                    personal_best_segment_time: personal_best_segment_time.into(),
+                    best_segment_time: segment.best_segment_time().into(),
+                    segment_history: segment
+                        .segment_history()
+                        .iter()
+                        .map(|&(index, time)| (index, time.into()))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        SegmentClipboard { segments }
+    }
+
+    /// Copies the selected segments into a Segment Clipboard and removes them
+    /// afterwards. If all the segments are selected, they can't be removed, so
+    /// nothing happens and `None` is returned.
+    pub fn cut_segments(&mut self) -> Option<SegmentClipboard> {
+        if !self.can_remove_segments() {
+            return None;
+        }
+        let clipboard = self.copy_segments();
+        self.remove_segments();
+        Some(clipboard)
+    }
+
+    /// Pastes the segments stored in the Segment Clipboard below the active
+    /// segment. The split times of all the following segments are shifted by
+    /// the Personal Best segment times of the pasted segments. The Segment
+    /// Histories of the pasted segments are kept together, but they are not
+    /// associated with any of the attempts of this run. The pasted segments
+    /// become the selected segments, with the last one of them being the
+    /// active segment.
+    pub fn paste_segments(&mut self, clipboard: &SegmentClipboard) {
+        if clipboard.is_empty() {
+            return;
+        }
+
+        let insert_index = self.active_segment_index() + 1;
+
+        let segment_times = TimingMethod::all().map(|method| {
+            let mut times = (0..self.run.len())
+                .map(|index| self.personal_best_segment_time(index, method))
+                .collect::<Vec<_>>();
+            times.splice(
+                insert_index..insert_index,
+                clipboard
+                    .segments
+                    .iter()
+                    .map(|s| s.personal_best_segment_time.get(method)),
+            );
+            times
+        });
+
+        // The histories of the pasted segments get new indices below all the
+        // existing ones, so they don't get mixed up with this run's attempts.
+        let mut next_history_index = self.run.min_segment_history_index().unwrap();
+        let mut history_indices = HashMap::new();
+
+        for (offset, pasted) in clipboard.segments.iter().enumerate() {
+            let mut segment = Segment::new(pasted.name.as_str());
+            segment.set_icon(pasted.icon.clone());
+            segment.set_best_segment_time(pasted.best_segment_time.into());
+            for &(index, time) in &pasted.segment_history {
+                let index = *history_indices.entry(index).or_insert_with(|| {
+                    next_history_index -= 1;
+                    next_history_index
+                });
+                segment.segment_history_mut().insert(index, time.into());
+            }
+            self.run
+                .segments_mut()
+                .insert(insert_index + offset, segment);
+        }
+
+        for (method, segment_times) in TimingMethod::all().into_iter().zip(segment_times) {
+            let mut previous_time = Some(TimeSpan::zero());
+            for (segment_time, segment) in segment_times.into_iter().zip(self.run.segments_mut()) {
+                let split_time = catch! { previous_time? + segment_time? };
+                segment.personal_best_split_time_mut()[method] = split_time;
+                if segment_time.is_some() {
+                    previous_time = split_time;
+                }
+            }
+        }
+
+        self.select_only(insert_index);
+        for index in insert_index + 1..insert_index + clipboard.len() {
+            self.select_additionally(index);
+        }
+
+        self.times_modified();
+        self.fix();
+    }
+
     /// Adds a new custom comparison. It can't be added if it starts with
     /// `[Race]` or it already exists.
     pub fn add_comparison<S: PopulateString>(
@@ -915,6 +1156,49 @@ impl Editor {
         Ok(())
     }
 
+    /// Returns whether the Comparison Generator with the name provided is
+    /// active for the Run.
+    pub fn is_comparison_generator_active(&self, name: &str) -> bool {
+        self.run
+            .comparison_generators()
+            .iter()
+            .any(|generator| generator.name() == name)
+    }
+
+    /// Activates or deactivates the Comparison Generator with the name
+    /// provided. Only the Comparison Generators that are active by default are
+    /// known. The comparison times of a Comparison Generator that gets
+    /// activated are generated right away. Returns `false` if there is no such
+    /// Comparison Generator or if a custom comparison with the same name
+    /// prevents it from being activated.
+    pub fn set_comparison_generator_active(&mut self, name: &str, active: bool) -> bool {
+        let Some(generator) = comparison::default_generators()
+            .into_iter()
+            .find(|generator| generator.name() == name)
+        else {
+            return false;
+        };
+
+        if active == self.is_comparison_generator_active(name) {
+            return true;
+        }
+
+        if active {
+            if self.run.custom_comparisons().iter().any(|c| c == name) {
+                return false;
+            }
+            self.run.comparison_generators_mut().push(generator);
+            self.run.regenerate_comparisons();
+        } else {
+            self.run
+                .comparison_generators_mut()
+                .retain(|generator| generator.name() != name);
+        }
+
+        self.raise_run_edited();
+        true
+    }
+
     /// Copies a comparison with the given name as a new custom comparison with
     /// the new name provided. It can't be added if it starts with `[Race]` or
     /// it already exists. The old comparison needs to exist.
@@ -956,6 +1240,233 @@ impl Editor {
         self.fix();
     }
 
+    fn attempt_position(&self, attempt_index: i32) -> Result<usize, AttemptError> {
+        self.run
+            .attempt_history()
+            .iter()
+            .position(|a| a.index() == attempt_index)
+            .context(UnknownAttempt)
+    }
+
+    /// Accesses the segment times of the attempt with the given index from
+    /// the Attempt History. There is an element for each segment. If the
+    /// attempt didn't reach a segment, its element is `None`. If a segment got
+    /// skipped, its times are empty. Returns `None` if there is no such
+    /// attempt.
+    pub fn attempt_segment_times(&self, attempt_index: i32) -> Option<Vec<Option<Time>>> {
+        self.attempt_position(attempt_index).ok()?;
+        Some(
+            self.run
+                .segments()
+                .iter()
+                .map(|s| s.segment_history().get(attempt_index))
+                .collect(),
+        )
+    }
+
+    /// Removes the attempt with the given index from the Attempt History.
+    /// Its times are removed from the Segment Histories as well. The best
+    /// segment times are kept, even if they were achieved in this attempt.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if there is no such attempt.
+    pub fn remove_attempt(&mut self, attempt_index: i32) -> Result<(), AttemptError> {
+        let position = self.attempt_position(attempt_index)?;
+        self.run.attempt_history_mut().remove(position);
+        for segment in self.run.segments_mut() {
+            segment.segment_history_mut().remove(attempt_index);
+        }
+        self.fix();
+        Ok(())
+    }
+
+    /// Marks the attempt with the given index as invalid. The attempt stays in
+    /// the Attempt History, so it still counts towards the playtime, but it
+    /// is treated as if it got reset before reaching the first split. This
+    /// means that its times are removed from the Segment Histories and its
+    /// final time is cleared. The best segment times are kept, even if they
+    /// were achieved in this attempt.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if there is no such attempt.
+    pub fn invalidate_attempt(&mut self, attempt_index: i32) -> Result<(), AttemptError> {
+        let position = self.attempt_position(attempt_index)?;
+        self.run.attempt_history_mut()[position].set_time(Time::default());
+        for segment in self.run.segments_mut() {
+            segment.segment_history_mut().remove(attempt_index);
+        }
+        self.fix();
+        Ok(())
+    }
+
+    /// Sets the segment time of the segment with the given index in the
+    /// attempt with the given index for the currently selected timing method.
+    /// An empty time marks the segment as skipped. If the attempt got
+    /// finished, its final time is adjusted accordingly. If the segment time
+    /// is faster than the best segment time, it becomes the new best segment
+    /// time. The Personal Best split times are not affected.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if there is no such attempt or the attempt didn't
+    /// reach the segment.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the index of the segment provided is out of bounds.
+    pub fn set_attempt_segment_time(
+        &mut self,
+        attempt_index: i32,
+        segment_index: usize,
+        time: Option<TimeSpan>,
+    ) -> Result<(), AttemptError> {
+        let position = self.attempt_position(attempt_index)?;
+        let method = self.selected_method;
+
+        let segment = self.run.segment_mut(segment_index);
+        let history_time = segment
+            .segment_history_mut()
+            .get_mut(attempt_index)
+            .context(SegmentNotReached)?;
+        history_time[method] = time;
+        if let Some(time) = time {
+            let best_segment_time = &mut segment.best_segment_time_mut()[method];
+            if best_segment_time.map_or(true, |best| time < best) {
+                *best_segment_time = Some(time);
+            }
+        }
+
+        if self.run.attempt_history()[position].time()[method].is_some() {
+            let final_time = self
+                .run
+                .segments()
+                .iter()
+                .filter_map(|s| s.segment_history().get(attempt_index)?[method])
+                .fold(TimeSpan::zero(), |sum, time| sum + time);
+            let attempt = &mut self.run.attempt_history_mut()[position];
+            let mut attempt_time = attempt.time();
+            attempt_time[method] = Some(final_time);
+            attempt.set_time(attempt_time);
+        }
+
+        self.times_modified();
+        self.fix();
+        Ok(())
+    }
+
+    /// Parses a segment time from a string and sets it as the segment time of
+    /// the segment with the given index in the attempt with the given index
+    /// for the currently selected timing method. An empty string marks the
+    /// segment as skipped.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the time couldn't be parsed, there is no such
+    /// attempt or the attempt didn't reach the segment.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the index of the segment provided is out of bounds.
+    pub fn parse_and_set_attempt_segment_time(
+        &mut self,
+        attempt_index: i32,
+        segment_index: usize,
+        time: &str,
+    ) -> Result<(), AttemptError> {
+        let time = parse_positive(time).context(InvalidSegmentTime)?;
+        self.set_attempt_segment_time(attempt_index, segment_index, time)
+    }
+
+    /// Starts importing segments from the table provided. The table can be
+    /// provided as comma separated values (CSV), tab separated values (TSV) or
+    /// text pasted from a spreadsheet application. What each of the columns is
+    /// imported as is guessed, but can be adjusted before finishing the
+    /// import. Until then, the import is part of the Run Editor's state, so it
+    /// can be previewed. Any import that is already in progress is replaced.
+    pub fn start_segment_import(&mut self, text: &str) {
+        self.segment_import = Some(SegmentImport::parse(text));
+    }
+
+    /// Grants mutable access to the Segment Import in progress, if there is
+    /// one, so what each of the columns is imported as can be adjusted.
+    pub fn segment_import_mut(&mut self) -> Option<&mut SegmentImport> {
+        self.segment_import.as_mut()
+    }
+
+    /// Cancels the Segment Import in progress without changing the run.
+    pub fn cancel_segment_import(&mut self) {
+        self.segment_import = None;
+    }
+
+    /// Finishes the Segment Import in progress by adding a segment below the
+    /// last segment for each row of the table. The times are imported for the
+    /// active timing method. If you choose to replace the segments, all the
+    /// segments that existed before are removed afterwards. The first segment
+    /// becomes the active segment.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if there is no import in progress, the table
+    /// doesn't contain any segments or any of the times can't be parsed. The
+    /// run stays unchanged in that case, and the import stays in progress, so
+    /// it can be adjusted.
+    pub fn finish_segment_import(&mut self, replace_segments: bool) -> Result<(), ImportError> {
+        let import = self.segment_import.as_ref().context(NoImportInProgress)?;
+        let rows = import.rows();
+        if rows.is_empty() {
+            return Err(ImportError::NoSegments);
+        }
+
+        let mut segments = Vec::with_capacity(rows.len());
+        for (index, row) in rows.iter().enumerate() {
+            let time = |column| -> Result<_, ImportError> {
+                if import.columns().contains(&column) {
+                    let parsed = parse_positive(import.cell(row, column));
+                    Ok(Some(parsed.context(InvalidTime { row: index })?))
+                } else {
+                    Ok(None)
+                }
+            };
+            segments.push((
+                import.cell(row, ImportColumn::Name).to_owned(),
+                time(ImportColumn::SplitTime)?,
+                time(ImportColumn::SegmentTime)?,
+                time(ImportColumn::BestSegmentTime)?,
+            ));
+        }
+        self.segment_import = None;
+
+        let previous_len = self.run.len();
+        for (name, split_time, segment_time, best_segment_time) in segments {
+            self.select_only(self.run.len() - 1);
+            self.insert_segment_below();
+            let mut row = self.active_segment();
+            row.set_name(name);
+            if let Some(time) = split_time {
+                row.set_split_time(time);
+            }
+            if let Some(time) = segment_time {
+                row.set_segment_time(time);
+            }
+            if let Some(time) = best_segment_time {
+                row.set_best_segment_time(time);
+            }
+        }
+
+        if replace_segments {
+            self.select_only(0);
+            for index in 1..previous_len {
+                self.select_additionally(index);
+            }
+            self.remove_segments();
+        }
+        self.select_only(0);
+
+        Ok(())
+    }
+
     /// Creates a Sum of Best Cleaner which allows you to interactively remove
     /// potential issues in the segment history that lead to an inaccurate Sum
     /// of Best. If you skip a split, whenever you will do the next split, the
@@ -0,0 +1,219 @@
+use crate::{platform::prelude::*, TimeSpan};
+use serde_derive::{Deserialize, Serialize};
+
+/// Describes what a column of the table being imported is used for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportColumn {
+    /// The column is not imported.
+    Ignored,
+    /// The column contains the names of the segments.
+    Name,
+    /// The column contains the split times of the segments.
+    SplitTime,
+    /// The column contains the segment times of the segments.
+    SegmentTime,
+    /// The column contains the best segment times of the segments.
+    BestSegmentTime,
+}
+
+/// A Segment Import describes a table of segments that is about to be imported
+/// into the Run Editor. The table can be provided as comma separated values
+/// (CSV), tab separated values (TSV) or text pasted from a spreadsheet
+/// application. Each row of the table becomes a segment and each column can be
+/// mapped to what it should be imported as. When the import gets started, the
+/// mapping is guessed from the table's header, if there is one, and its
+/// contents.
+#[derive(Debug, Clone)]
+pub struct SegmentImport {
+    rows: Vec<Vec<String>>,
+    columns: Vec<ImportColumn>,
+    has_header: bool,
+}
+
+impl SegmentImport {
+    /// Parses the table provided as CSV, TSV or text pasted from a spreadsheet
+    /// application. Tabs are used as the delimiter if there are any, commas or
+    /// semicolons otherwise. Cells may be quoted with double quotes.
+    pub fn parse(text: &str) -> Self {
+        let rows = parse_table(text);
+        let column_count = rows.iter().map(Vec::len).max().unwrap_or_default();
+
+        // The first row is considered a header if none of its cells are times
+        // and at least one of them describes a column that can be imported.
+        let header_columns = rows
+            .first()
+            .filter(|header| {
+                !header
+                    .iter()
+                    .any(|c| matches!(TimeSpan::parse_opt(c.trim()), Ok(Some(_))))
+            })
+            .map(|header| {
+                let mut columns = Vec::with_capacity(column_count);
+                for index in 0..column_count {
+                    let column = header
+                        .get(index)
+                        .map_or(ImportColumn::Ignored, |c| guess_column(c));
+                    columns.push(if columns.contains(&column) {
+                        ImportColumn::Ignored
+                    } else {
+                        column
+                    });
+                }
+                columns
+            })
+            .filter(|columns| columns.iter().any(|&c| c != ImportColumn::Ignored));
+
+        let (columns, has_header) = if let Some(columns) = header_columns {
+            (columns, true)
+        } else {
+            let mut columns = vec![ImportColumn::Ignored; column_count];
+            if let Some(first) = columns.first_mut() {
+                *first = ImportColumn::Name;
+            }
+            if let Some(second) = columns.get_mut(1) {
+                *second = ImportColumn::SplitTime;
+            }
+            (columns, false)
+        };
+
+        Self {
+            rows,
+            columns,
+            has_header,
+        }
+    }
+
+    /// Accesses what each of the columns is imported as.
+    pub fn columns(&self) -> &[ImportColumn] {
+        &self.columns
+    }
+
+    /// Sets what the column with the given index is imported as. Only a single
+    /// column can be imported as each kind, so any other column that was
+    /// imported as the same kind is ignored from now on. You may not provide an
+    /// invalid index.
+    pub fn set_column(&mut self, index: usize, column: ImportColumn) {
+        if index >= self.columns.len() {
+            return;
+        }
+        if column != ImportColumn::Ignored {
+            for other in &mut self.columns {
+                if *other == column {
+                    *other = ImportColumn::Ignored;
+                }
+            }
+        }
+        self.columns[index] = column;
+    }
+
+    /// Returns whether the first row of the table is a header that is not
+    /// imported as a segment.
+    pub const fn has_header(&self) -> bool {
+        self.has_header
+    }
+
+    /// Sets whether the first row of the table is a header that is not
+    /// imported as a segment.
+    pub fn set_has_header(&mut self, has_header: bool) {
+        self.has_header = has_header;
+    }
+
+    /// Accesses the header of the table. This is empty if the table has no
+    /// header.
+    pub fn header(&self) -> &[String] {
+        match self.rows.first() {
+            Some(header) if self.has_header => header,
+            _ => &[],
+        }
+    }
+
+    /// Accesses the rows of the table that are imported as segments.
+    pub fn rows(&self) -> &[Vec<String>] {
+        let skip = if self.has_header {
+            self.rows.len().min(1)
+        } else {
+            0
+        };
+        &self.rows[skip..]
+    }
+
+    /// Accesses the cell of the row provided that is imported as the given
+    /// kind of column. An empty string is returned if no column is imported as
+    /// that kind.
+    pub fn cell<'a>(&self, row: &'a [String], column: ImportColumn) -> &'a str {
+        self.columns
+            .iter()
+            .position(|&c| c == column)
+            .and_then(|index| row.get(index))
+            .map_or("", |cell| cell.trim())
+    }
+}
+
+fn guess_column(header: &str) -> ImportColumn {
+    let header = header.trim().to_ascii_lowercase();
+    if header.contains("best") || header.contains("gold") {
+        ImportColumn::BestSegmentTime
+    } else if header.contains("split") || header == "time" || header.contains("pb") {
+        ImportColumn::SplitTime
+    } else if header.contains("segment") && header.contains("time") {
+        ImportColumn::SegmentTime
+    } else if header.contains("name") || header.contains("segment") {
+        ImportColumn::Name
+    } else {
+        ImportColumn::Ignored
+    }
+}
+
+fn parse_table(text: &str) -> Vec<Vec<String>> {
+    let delimiter = if text.contains('\t') {
+        '\t'
+    } else if text
+        .lines()
+        .next()
+        .is_some_and(|l| l.contains(';') && !l.contains(','))
+    {
+        ';'
+    } else {
+        ','
+    };
+
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    cell.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if cell.is_empty() => in_quotes = true,
+            c if in_quotes => cell.push(c),
+            c if c == delimiter => row.push(core::mem::take(&mut cell)),
+            '\r' => {}
+            '\n' => {
+                row.push(core::mem::take(&mut cell));
+                push_row(&mut rows, core::mem::take(&mut row));
+            }
+            c => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        push_row(&mut rows, row);
+    }
+
+    rows
+}
+
+fn push_row(rows: &mut Vec<Vec<String>>, row: Vec<String>) {
+    if row.iter().any(|cell| !cell.trim().is_empty()) {
+        rows.push(row);
+    }
+}
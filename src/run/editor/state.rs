@@ -1,4 +1,4 @@
-use super::{Editor, SegmentRow, TimingMethod};
+use super::{Editor, ImportColumn, SegmentRow, TimingMethod};
 use crate::{
     comparison::personal_best,
     platform::prelude::*,
@@ -38,6 +38,23 @@ pub struct State {
     /// Additional metadata of this Run, like the platform and region of the
     /// game.
     pub metadata: RunMetadata,
+    /// The Segment Import that is currently in progress, if there is one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment_import: Option<SegmentImport>,
+}
+
+/// Describes the current state of a Segment Import, so it can be previewed
+/// before it gets finished.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SegmentImport {
+    /// What each of the columns of the table is imported as.
+    pub columns: Vec<ImportColumn>,
+    /// The header of the table. This is empty if the table has no header.
+    pub header: Vec<String>,
+    /// The first few rows of the table that are imported as segments.
+    pub preview: Vec<Vec<String>>,
+    /// The total number of rows that are imported as segments.
+    pub row_count: u32,
 }
 
 /// Describes which actions are currently available. Depending on how many
@@ -189,6 +206,12 @@ impl Editor {
             comparison_names,
             buttons,
             metadata: self.run.metadata().clone(),
+            segment_import: self.segment_import.as_ref().map(|import| SegmentImport {
+                columns: import.columns().to_vec(),
+                header: import.header().to_vec(),
+                preview: import.rows().iter().take(10).cloned().collect(),
+                row_count: import.rows().len() as u32,
+            }),
         }
     }
 }
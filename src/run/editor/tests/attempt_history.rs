@@ -0,0 +1,84 @@
+use super::super::{AttemptError, Editor};
+use crate::{
+    util::tests_helper::{create_timer, run_with_splits, run_with_splits_opt, span},
+    TimingMethod,
+};
+
+fn editor() -> Editor {
+    let mut timer = create_timer(&["A", "B", "C"]);
+    run_with_splits(&mut timer, &[1.0, 3.0, 6.0]);
+    run_with_splits(&mut timer, &[2.0, 4.0, 7.0]);
+    run_with_splits_opt(&mut timer, &[Some(1.5)]);
+    let mut editor = Editor::new(timer.into_run(true)).unwrap();
+    editor.select_timing_method(TimingMethod::GameTime);
+    editor
+}
+
+#[test]
+fn lists_segment_times_of_attempt() {
+    let editor = editor();
+    let times = editor.attempt_segment_times(3).unwrap();
+    assert_eq!(times.len(), 3);
+    assert_eq!(times[0].unwrap().game_time, Some(span(1.5)));
+    assert!(times[1].is_none());
+    assert!(editor.attempt_segment_times(4).is_none());
+}
+
+#[test]
+fn removing_attempt_removes_segment_times() {
+    let mut editor = editor();
+    editor.remove_attempt(2).unwrap();
+
+    let run = editor.run();
+    assert!(run.attempt_history().iter().all(|a| a.index() != 2));
+    assert!(run
+        .segments()
+        .iter()
+        .all(|s| s.segment_history().get(2).is_none()));
+    assert!(matches!(
+        editor.remove_attempt(2),
+        Err(AttemptError::UnknownAttempt)
+    ));
+}
+
+#[test]
+fn invalidating_attempt_keeps_it_in_history() {
+    let mut editor = editor();
+    editor.invalidate_attempt(1).unwrap();
+
+    let run = editor.run();
+    let attempt = run
+        .attempt_history()
+        .iter()
+        .find(|a| a.index() == 1)
+        .unwrap();
+    assert_eq!(attempt.time().game_time, None);
+    assert!(run
+        .segments()
+        .iter()
+        .all(|s| s.segment_history().get(1).is_none()));
+}
+
+#[test]
+fn fixing_segment_time_updates_final_time_and_gold() {
+    let mut editor = editor();
+    editor
+        .parse_and_set_attempt_segment_time(2, 1, "1.5")
+        .unwrap();
+
+    let run = editor.run();
+    let attempt = run
+        .attempt_history()
+        .iter()
+        .find(|a| a.index() == 2)
+        .unwrap();
+    assert_eq!(attempt.time().game_time, Some(span(6.5)));
+    assert_eq!(
+        run.segment(1).best_segment_time().game_time,
+        Some(span(1.5))
+    );
+    assert!(matches!(
+        editor.parse_and_set_attempt_segment_time(3, 2, "1.0"),
+        Err(AttemptError::SegmentNotReached)
+    ));
+}
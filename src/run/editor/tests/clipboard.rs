@@ -0,0 +1,116 @@
+use super::super::{Editor, SegmentClipboard};
+use crate::{
+    util::tests_helper::{create_timer, run_with_splits, span},
+    TimingMethod,
+};
+
+fn editor(names: &[&str], splits: &[f64]) -> Editor {
+    let mut timer = create_timer(names);
+    run_with_splits(&mut timer, splits);
+    Editor::new(timer.into_run(true)).unwrap()
+}
+
+fn names(editor: &Editor) -> Vec<&str> {
+    editor.run().segments().iter().map(|s| s.name()).collect()
+}
+
+fn game_time_splits(editor: &Editor) -> Vec<Option<f64>> {
+    editor
+        .run()
+        .segments()
+        .iter()
+        .map(|s| s.personal_best_split_time()[TimingMethod::GameTime].map(|t| t.total_seconds()))
+        .collect()
+}
+
+#[test]
+fn copies_selected_segments_in_order() {
+    let mut editor = editor(&["A", "B", "C"], &[1.0, 3.0, 6.0]);
+    editor.select_only(2);
+    editor.select_additionally(1);
+
+    let clipboard = editor.copy_segments();
+    assert_eq!(clipboard.segment_names().collect::<Vec<_>>(), ["B", "C"]);
+
+    let decoded = SegmentClipboard::from_json(&clipboard.to_json()).unwrap();
+    assert_eq!(decoded, clipboard);
+}
+
+#[test]
+fn pastes_segments_into_other_run() {
+    let mut source = editor(&["A", "B", "C"], &[1.0, 3.0, 6.0]);
+    source.select_only(1);
+    source.select_additionally(2);
+    let clipboard = source.copy_segments();
+
+    let mut target = editor(&["X", "Y"], &[10.0, 20.0]);
+    target.select_only(0);
+    target.paste_segments(&clipboard);
+
+    assert_eq!(names(&target), ["X", "B", "C", "Y"]);
+    assert_eq!(
+        game_time_splits(&target),
+        [Some(10.0), Some(12.0), Some(15.0), Some(25.0)],
+    );
+
+    let run = target.run();
+    let (b_index, b_time) = run
+        .segment(1)
+        .segment_history()
+        .iter()
+        .next()
+        .copied()
+        .unwrap();
+    let (c_index, c_time) = run
+        .segment(2)
+        .segment_history()
+        .iter()
+        .next()
+        .copied()
+        .unwrap();
+    assert_eq!(b_index, c_index);
+    assert!(b_index <= 0);
+    assert_eq!(b_time.game_time, Some(span(2.0)));
+    assert_eq!(c_time.game_time, Some(span(3.0)));
+}
+
+#[test]
+fn cutting_all_segments_does_nothing() {
+    let mut editor = editor(&["A", "B"], &[1.0, 2.0]);
+    editor.select_only(0);
+    editor.select_additionally(1);
+    assert!(editor.cut_segments().is_none());
+    assert_eq!(names(&editor), ["A", "B"]);
+
+    editor.select_only(0);
+    assert!(editor.cut_segments().is_some());
+    assert_eq!(names(&editor), ["B"]);
+}
+
+#[test]
+fn moves_multiple_segments_by_offset() {
+    let mut editor = editor(&["A", "B", "C", "D"], &[1.0, 2.0, 3.0, 4.0]);
+    editor.select_only(0);
+    editor.select_additionally(1);
+
+    assert!(!editor.can_move_segments_by(3));
+    assert!(!editor.can_move_segments_by(-1));
+    editor.move_segments_by(2);
+
+    assert_eq!(names(&editor), ["C", "D", "A", "B"]);
+    assert_eq!(
+        editor.copy_segments().segment_names().collect::<Vec<_>>(),
+        ["A", "B"]
+    );
+}
+
+#[test]
+fn removes_segments_at_indices() {
+    let mut editor = editor(&["A", "B", "C", "D"], &[1.0, 2.0, 3.0, 4.0]);
+    editor.remove_segments_at(&[0, 2]);
+    assert_eq!(names(&editor), ["B", "D"]);
+
+    editor.remove_segments_at(&[0, 1]);
+    editor.remove_segments_at(&[5]);
+    assert_eq!(names(&editor), ["B", "D"]);
+}
@@ -156,3 +156,24 @@ fn reordering_works() {
         ["D", "A", "C", "B"]
     );
 }
+
+#[test]
+fn toggling_a_comparison_generator_works() {
+    let mut run = Run::new();
+    run.push_segment(Segment::new("s"));
+    let mut editor = Editor::new(run).unwrap();
+
+    assert!(editor.is_comparison_generator_active(best_segments::NAME));
+    assert!(editor.set_comparison_generator_active(best_segments::NAME, false));
+    assert!(!editor.is_comparison_generator_active(best_segments::NAME));
+    assert!(!editor.run().comparisons().any(|c| c == best_segments::NAME));
+
+    // A custom comparison with the same name prevents reactivating it.
+    editor.add_comparison(best_segments::NAME).unwrap();
+    assert!(!editor.set_comparison_generator_active(best_segments::NAME, true));
+    editor.remove_comparison(best_segments::NAME);
+
+    assert!(editor.set_comparison_generator_active(best_segments::NAME, true));
+    assert!(editor.is_comparison_generator_active(best_segments::NAME));
+    assert!(!editor.set_comparison_generator_active("Unknown", true));
+}
@@ -4,10 +4,13 @@ use crate::{
     Run, Segment,
 };
 
+mod attempt_history;
+mod clipboard;
 mod comparison;
 mod custom_variables;
 mod dissociate_run;
 mod mark_as_modified;
+mod segment_import;
 
 #[test]
 fn new_best_segment() {
@@ -0,0 +1,62 @@
+use super::super::{Editor, ImportColumn, ImportError};
+use crate::{Run, Segment, TimeSpan, TimingMethod};
+
+fn editor() -> Editor {
+    let mut run = Run::new();
+    run.push_segment(Segment::new(""));
+    Editor::new(run).unwrap()
+}
+
+#[test]
+fn guesses_columns_from_header() {
+    let mut editor = editor();
+    editor.start_segment_import("Segment,Notes,Split Time,Best Segment\nA,x,1:00,1:00\n");
+    let import = editor.segment_import_mut().unwrap();
+    assert!(import.has_header());
+    assert_eq!(
+        import.columns(),
+        [
+            ImportColumn::Name,
+            ImportColumn::Ignored,
+            ImportColumn::SplitTime,
+            ImportColumn::BestSegmentTime,
+        ]
+    );
+    assert_eq!(import.rows().len(), 1);
+}
+
+#[test]
+fn imports_pasted_spreadsheet_without_header() {
+    let mut editor = editor();
+    editor.start_segment_import("Split 1\t1:00\r\nSplit 2\t2:30\r\n\"Split, 3\"\t4:00\r\n");
+    assert!(!editor.segment_import_mut().unwrap().has_header());
+    editor.finish_segment_import(true).unwrap();
+
+    let run = editor.close();
+    let names = run.segments().iter().map(|s| s.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["Split 1", "Split 2", "Split, 3"]);
+    assert_eq!(
+        run.segment(1).personal_best_split_time()[TimingMethod::RealTime],
+        Some(TimeSpan::from_seconds(150.0)),
+    );
+}
+
+#[test]
+fn keeps_existing_segments_unless_replaced() {
+    let mut editor = editor();
+    editor.start_segment_import("A,1:00\nB,2:00");
+    editor.finish_segment_import(false).unwrap();
+    assert_eq!(editor.run().len(), 3);
+}
+
+#[test]
+fn invalid_times_dont_change_the_run() {
+    let mut editor = editor();
+    editor.start_segment_import("A,1:00\nB,foo");
+    assert!(matches!(
+        editor.finish_segment_import(true),
+        Err(ImportError::InvalidTime { row: 1, .. })
+    ));
+    assert_eq!(editor.run().len(), 1);
+    assert!(editor.segment_import_mut().is_some());
+}
@@ -266,6 +266,14 @@ impl Run {
         &self.attempt_history
     }
 
+    /// Grants mutable access to the history of all the runs that have been
+    /// attempted. You need to ensure that the Segment Histories stay
+    /// consistent with the Attempt History when modifying it.
+    #[inline]
+    pub fn attempt_history_mut(&mut self) -> &mut Vec<Attempt> {
+        &mut self.attempt_history
+    }
+
     /// Accesses the custom comparisons that are stored in this Run. This
     /// includes `Personal Best` but excludes all the other Comparison
     /// Generators.
@@ -540,7 +548,9 @@ impl Run {
 
     /// Applies some fixing algorithms on the Run. This includes fixing the
     /// comparison times and history, removing duplicates in the segment
-    /// histories and removing empty times.
+    /// histories and removing empty times. Each segment history is only passed
+    /// over a constant number of times, so this scales linearly with the total
+    /// size of the segment histories.
     pub fn fix_splits(&mut self) {
         for method in TimingMethod::all() {
             self.fix_comparison_times_and_history(method);
@@ -550,6 +560,29 @@ impl Run {
         self.reattach_unattached_segment_history_elements();
     }
 
+    /// Checks whether applying [`fix_splits`](Self::fix_splits) would change
+    /// the Run. This can be used to find out whether the splits contain any
+    /// inconsistencies that need to be repaired.
+    pub fn needs_fixing(&self) -> bool {
+        let mut fixed = self.clone();
+        fixed.fix_splits();
+        fixed != *self
+    }
+
+    /// Removes all the Custom Comparisons that are listed more than once or
+    /// that have the same name as one of the Comparison Generators. Returns
+    /// whether any Custom Comparisons got removed.
+    pub fn remove_duplicate_comparisons(&mut self) -> bool {
+        let len = self.custom_comparisons.len();
+        let mut names = HashSet::new();
+        let generators = &self.comparison_generators.0;
+        self.custom_comparisons.retain(|comparison| {
+            !generators.iter().any(|g| g.name() == comparison.as_str())
+                && names.insert(comparison.clone())
+        });
+        self.custom_comparisons.len() != len
+    }
+
     /// Clears out the Attempt History and the Segment Histories of all the segments.
     pub fn clear_history(&mut self) {
         self.attempt_history.clear();
@@ -615,25 +648,23 @@ impl Run {
     }
 
     fn remove_none_values(&mut self) {
-        let mut cache = Vec::new();
-        if let Some(min_index) = self.min_segment_history_index() {
-            let max_index = self.max_attempt_history_index().unwrap_or(0) + 1;
-            for run_index in min_index..max_index {
-                for index in 0..self.len() {
-                    if let Some(element) = self.segments[index].segment_history().get(run_index) {
-                        if element.real_time.is_none() && element.game_time.is_none() {
-                            cache.push(run_index);
-                        } else {
-                            cache.clear();
-                        }
-                    } else {
-                        // Remove None times in history that aren't followed by a non-None time
-                        self.remove_items_from_cache(index, &mut cache);
-                    }
-                }
-                let len = self.len();
-                self.remove_items_from_cache(len, &mut cache);
-            }
+        let max_index = self.max_attempt_history_index().unwrap_or(0) + 1;
+
+        // A segment history element without a time is only kept if the next
+        // segment has an element for the same attempt. As the last segment is
+        // fixed first, that element already got fixed itself. This only needs
+        // a single pass over the segment histories, so it scales with the size
+        // of the histories rather than the number of segments times the number
+        // of attempts.
+        for segment_index in (0..self.len()).rev() {
+            let (current, following) = self.segments[segment_index..].split_at_mut(1);
+            let next_history = following.first().map(Segment::segment_history);
+            current[0].segment_history_mut().retain(|&(index, time)| {
+                index >= max_index
+                    || time.real_time.is_some()
+                    || time.game_time.is_some()
+                    || next_history.is_some_and(|h| h.get(index).is_some())
+            });
         }
     }
 
@@ -677,13 +708,6 @@ impl Run {
         }
     }
 
-    fn remove_items_from_cache(&mut self, index: usize, cache: &mut Vec<i32>) {
-        let ind = index - cache.len();
-        for (index, segment) in cache.drain(..).zip(self.segments_mut()[ind..].iter_mut()) {
-            segment.segment_history_mut().remove(index);
-        }
-    }
-
     /// Returns the minimum index in use by all the Segment Histories. `None` is
     /// returned if the Run has no segments.
     pub fn min_segment_history_index(&self) -> Option<i32> {
@@ -777,25 +801,53 @@ impl Run {
 
     fn reattach_unattached_segment_history_elements(&mut self) {
         let max_id = self.max_attempt_history_index().unwrap_or_default();
-        let mut min_id = self.min_segment_history_index().unwrap_or_default();
+        let min_id = self.min_segment_history_index().unwrap_or_default();
+
+        // The unattached elements are at the end of each segment history, as
+        // they are ordered by their index. The largest unattached index gets
+        // reassigned to the index right below the current minimum, the next
+        // largest one to the index below that and so on.
+        let mut unattached_ids = Vec::new();
+        for segment in &self.segments {
+            unattached_ids.extend(
+                segment
+                    .segment_history()
+                    .iter()
+                    .rev()
+                    .map(|&(index, _)| index)
+                    .take_while(|&index| index > max_id),
+            );
+        }
 
-        while let Some(unattached_id) = self
-            .segments
-            .iter()
-            .filter_map(|s| s.segment_history().try_get_max_index())
-            .filter(|&i| i > max_id)
-            .max()
-        {
-            let reassign_id = min_id - 1;
+        if unattached_ids.is_empty() {
+            return;
+        }
+
+        unattached_ids.sort_unstable_by(|a, b| b.cmp(a));
+        unattached_ids.dedup();
 
-            for segment in self.segments_mut() {
-                let history = segment.segment_history_mut();
-                if let Some(time) = history.remove(unattached_id) {
-                    history.insert(reassign_id, time);
+        // Inserting the elements one by one at the front of the history would
+        // be quadratic, so the indices get reassigned in place instead. The
+        // reassigned elements then form a falling run at the end, which the
+        // sort moves to the front in linear time.
+        for segment in self.segments_mut() {
+            let history = segment.segment_history_mut();
+            if history
+                .try_get_max_index()
+                .map_or(true, |index| index <= max_id)
+            {
+                continue;
+            }
+            for (index, _) in history.iter_mut().rev() {
+                if *index <= max_id {
+                    break;
                 }
+                let position = unattached_ids
+                    .binary_search_by(|probe| probe.cmp(index).reverse())
+                    .unwrap_or_default();
+                *index = min_id - 1 - position as i32;
             }
-
-            min_id = reassign_id;
+            history.sort_by_index();
         }
     }
 }
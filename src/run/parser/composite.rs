@@ -42,6 +42,9 @@ use core::{result::Result as StdResult, str};
 pub enum Error {
     /// No parser was able to parse the splits file.
     NoParserParsedIt,
+    /// Parsing got cancelled before any parser was able to parse the splits
+    /// file.
+    Cancelled,
 }
 
 /// The Result type for the Composite Parser.
@@ -95,50 +98,74 @@ pub fn parse_and_fix<'source>(
 pub fn parse<'source>(
     source: &'source [u8],
     load_files_path: Option<&Path>,
+) -> Result<ParsedRun<'source>> {
+    parse_cancellable(source, load_files_path, || false)
+}
+
+/// Attempts to parse a splits file just like [`parse`], but checks whether
+/// parsing got cancelled before trying each of the parsers. This allows
+/// applications to abort parsing large files that aren't splits files without
+/// waiting for every parser to reject them. [`Error::Cancelled`] is returned
+/// once `is_cancelled` returns `true`.
+pub fn parse_cancellable<'source>(
+    source: &'source [u8],
+    load_files_path: Option<&Path>,
+    is_cancelled: impl Fn() -> bool,
 ) -> Result<ParsedRun<'source>> {
     if let Ok(source) = simdutf8::basic::from_utf8(source) {
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = livesplit::parse(source) {
             return Ok(parsed(run, TimerKind::LiveSplit));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = wsplit::parse(source, load_files_path.is_some()) {
             return Ok(parsed(run, TimerKind::WSplit));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = splitterz::parse(source, load_files_path.is_some()) {
             return Ok(parsed(run, TimerKind::SplitterZ));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = shit_split::parse(source) {
             return Ok(parsed(run, TimerKind::ShitSplit));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = splitty::parse(source) {
             return Ok(parsed(run, TimerKind::Splitty));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = time_split_tracker::parse(source, load_files_path) {
             return Ok(parsed(run, TimerKind::TimeSplitTracker));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = portal2_live_timer::parse(source) {
             return Ok(parsed(run, TimerKind::Portal2LiveTimer));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = face_split::parse(source, load_files_path.is_some()) {
             return Ok(parsed(run, TimerKind::FaceSplit));
         }
 
+        check_cancelled(&is_cancelled)?;
         // Should be parsed after LiveSplit's parser, as it also parses all
         // LiveSplit files with the current implementation.
         if let Ok(run) = llanfair_gered::parse(source) {
             return Ok(parsed(run, TimerKind::LlanfairGered));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok((run, timer)) = splits_io::parse(source) {
             return Ok(parsed(run, TimerKind::Generic(timer)));
         }
 
+        check_cancelled(&is_cancelled)?;
         // Splitterino, SourceLiveTimer, Flitter, and SpeedRunIGT need to be
         // before Urn because of a false positive due to the nature of parsing
         // JSON files.
@@ -146,27 +173,40 @@ pub fn parse<'source>(
             return Ok(parsed(run, TimerKind::Splitterino));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = flitter::parse(source) {
             return Ok(parsed(run, TimerKind::Flitter));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = source_live_timer::parse(source) {
             return Ok(parsed(run, TimerKind::SourceLiveTimer));
         }
 
+        check_cancelled(&is_cancelled)?;
         if let Ok(run) = speedrun_igt::parse(source) {
             return Ok(parsed(run, TimerKind::SpeedRunIGT));
         }
 
+        check_cancelled(&is_cancelled)?;
         // Urn accepts entirely empty JSON files.
         if let Ok(run) = urn::parse(source) {
             return Ok(parsed(run, TimerKind::Urn));
         }
     }
 
+    check_cancelled(&is_cancelled)?;
     if let Ok(run) = llanfair::parse(source) {
         return Ok(parsed(run, TimerKind::Llanfair));
     }
 
     Err(Error::NoParserParsedIt)
 }
+
+fn check_cancelled(is_cancelled: impl Fn() -> bool) -> Result<()> {
+    if is_cancelled() {
+        Err(Error::Cancelled)
+    } else {
+        Ok(())
+    }
+}
@@ -1,13 +1,13 @@
 //! Provides the parser for Time Split Tracker splits files.
 
 use super::super::AddComparisonError;
+#[cfg(feature = "std")]
+use crate::settings::Image;
 use crate::{
     comparison::RACE_COMPARISON_PREFIX,
     platform::{path::Path, prelude::*},
-    timing, RealTime, Run, Segment, Time, TimeSpan,
+    timing, AtomicDateTime, RealTime, Run, Segment, Time, TimeSpan,
 };
-#[cfg(feature = "std")]
-use crate::{settings::Image, AtomicDateTime};
 use alloc::borrow::Cow;
 use core::{fmt::Write, num::ParseIntError, result::Result as StdResult};
 use snafu::{OptionExt, ResultExt};
@@ -55,6 +55,8 @@ pub enum Error {
     },
     /// Expected a line containing the icon of a segment, but didn't find it.
     ExpectedIconLine,
+    /// The run log containing the attempt history couldn't be parsed.
+    InvalidRunLog,
 }
 
 /// The Result type for the Time Split Tracker parser.
@@ -74,6 +76,8 @@ fn parse_time_optional(time: &str) -> StdResult<Option<TimeSpan>, timing::ParseE
 /// use to load the run log file from the file system. This is entirely
 /// optional. If you are using livesplit-core in a server-like environment, set
 /// this to `None`. Only client-side applications should provide the path here.
+/// On platforms without a file system, the run log can be loaded by other
+/// means and applied with [`parse_run_log`] instead.
 pub fn parse(
     source: &str,
     #[allow(unused)] path_for_loading_other_files: Option<&Path>,
@@ -195,12 +199,38 @@ fn parse_history(run: &mut Run, path: Option<std::path::PathBuf>) -> StdResult<(
         let path = std::path::PathBuf::from(path);
 
         let file = std::fs::read_to_string(path).map_err(drop)?;
-        let mut lines = file.lines();
-        let mut attempt_id = 1;
+        parse_run_log(run, &file).map_err(drop)?;
+    }
+    Ok(())
+}
 
-        lines.next(); // Skip the first line
+/// Applies a Time Split Tracker run log to a run that got parsed with
+/// [`parse`]. The run log is stored next to the splits file, with the same
+/// name, but ending in `-RunLog.txt`, and contains the attempt history. The
+/// parser only loads it by itself if the `std` feature is active and a path is
+/// provided, so this allows providing it on any other platform. If the run log
+/// is invalid, the run is left unchanged.
+pub fn parse_run_log(run: &mut Run, source: &str) -> Result<()> {
+    let attempts = parse_run_log_lines(source).map_err(|_| Error::InvalidRunLog)?;
+    for (index, attempt) in attempts.into_iter().enumerate() {
+        apply_attempt(run, index as i32 + 1, attempt);
+    }
+    Ok(())
+}
 
-        for line in lines {
+struct RunLogAttempt {
+    started: crate::DateTime,
+    final_time: Time,
+    ended: Option<AtomicDateTime>,
+    split_times: Vec<Option<TimeSpan>>,
+}
+
+fn parse_run_log_lines(source: &str) -> StdResult<Vec<RunLogAttempt>, ()> {
+    // Skip the first line
+    source
+        .lines()
+        .skip(1)
+        .map(|line| {
             let mut splits = line.split('\t');
             let time_stamp = splits.next().ok_or(())?;
             let started = parse_date(time_stamp).ok_or(())?;
@@ -220,45 +250,47 @@ fn parse_history(run: &mut Run, path: Option<std::path::PathBuf>) -> StdResult<(
                     ended = Some(AtomicDateTime::new(ended_date, false));
                 };
             }
-
-            run.add_attempt_with_index(
+            Ok(RunLogAttempt {
+                started,
                 final_time,
-                attempt_id,
-                Some(AtomicDateTime::new(started, false)),
                 ended,
-                None,
-            );
-
-            let mut last_split = TimeSpan::zero();
-            for (segment, current_split) in
-                run.segments_mut().iter_mut().zip(split_times.into_iter())
-            {
-                let mut segment_time = Time::default();
-                if let Some(current_split) = current_split {
-                    segment_time.real_time = Some(current_split - last_split);
-                    last_split = current_split;
-                }
+                split_times,
+            })
+        })
+        .collect()
+}
 
-                segment
-                    .segment_history_mut()
-                    .insert(attempt_id, segment_time);
+fn apply_attempt(run: &mut Run, attempt_id: i32, attempt: RunLogAttempt) {
+    run.add_attempt_with_index(
+        attempt.final_time,
+        attempt_id,
+        Some(AtomicDateTime::new(attempt.started, false)),
+        attempt.ended,
+        None,
+    );
 
-                if catch! {
-                    segment_time.real_time? < segment.best_segment_time().real_time?
-                }
-                .unwrap_or(false)
-                {
-                    segment.set_best_segment_time(segment_time);
-                }
-            }
+    let mut last_split = TimeSpan::zero();
+    for (segment, current_split) in run.segments_mut().iter_mut().zip(attempt.split_times) {
+        let mut segment_time = Time::default();
+        if let Some(current_split) = current_split {
+            segment_time.real_time = Some(current_split - last_split);
+            last_split = current_split;
+        }
+
+        segment
+            .segment_history_mut()
+            .insert(attempt_id, segment_time);
 
-            attempt_id += 1;
+        if catch! {
+            segment_time.real_time? < segment.best_segment_time().real_time?
+        }
+        .unwrap_or(false)
+        {
+            segment.set_best_segment_time(segment_time);
         }
     }
-    Ok(())
 }
 
-#[cfg(feature = "std")]
 fn parse_date(text: &str) -> Option<crate::DateTime> {
     let (year, rem) = text.split_once('/')?;
     let (month, rem) = rem.split_once('/')?;
@@ -107,6 +107,13 @@ impl SegmentHistory {
         self.0.iter_mut()
     }
 
+    /// Restores the rising order of the indices after they got changed via
+    /// [`iter_mut`](Self::iter_mut). This is linear if the indices consist of
+    /// only a few rising or falling runs.
+    pub(crate) fn sort_by_index(&mut self) {
+        self.0.sort_by_key(|&(index, _)| index);
+    }
+
     /// Iterates over the actual segment times achieved by the runner. Segment
     /// times created by route changes or other algorithmic changes are filtered
     /// out.
@@ -1,6 +1,6 @@
 use crate::{
     util::tests_helper::{create_timer, run_with_splits},
-    Timer,
+    Time, TimeSpan, Timer,
 };
 
 #[test]
@@ -25,6 +25,63 @@ fn reattaches_unattached_segment_history_elements_by_using_negative_ids() {
     assert_eq!(segments[1].segment_history().try_get_max_index(), Some(1));
 }
 
+#[test]
+fn reattaches_multiple_unattached_attempts_in_order() {
+    let mut timer = create_timer(&["A", "B"]);
+    run_with_splits(&mut timer, &[3.0, 6.0]);
+    run_with_splits(&mut timer, &[2.0, 4.0]);
+    run_with_splits(&mut timer, &[1.0, 5.0]);
+    let mut run = timer.into_run(true);
+
+    run.attempt_history.truncate(1);
+
+    run.fix_splits();
+
+    let history = run.segments()[1].segment_history();
+    assert_eq!(history.try_get_min_index(), Some(-1));
+    assert_eq!(history.try_get_max_index(), Some(1));
+    // The most recent attempt gets the index closest to the attached ones.
+    assert_eq!(
+        history.get(0).unwrap().game_time,
+        Some(TimeSpan::from_seconds(4.0))
+    );
+    assert_eq!(
+        history.get(-1).unwrap().game_time,
+        Some(TimeSpan::from_seconds(2.0))
+    );
+}
+
+#[test]
+fn removes_empty_segment_history_elements_not_followed_by_a_time() {
+    let mut run = create_timer(&["A", "B", "C"]).into_run(true);
+    run.add_attempt_with_index(Time::default(), 1, None, None, None);
+    run.add_attempt_with_index(Time::default(), 2, None, None, None);
+
+    // Attempt 1 skipped the first segment and split on the second one.
+    let time = Time::new().with_real_time(Some(TimeSpan::from_seconds(5.0)));
+    run.segments[0]
+        .segment_history_mut()
+        .insert(1, Time::default());
+    run.segments[1].segment_history_mut().insert(1, time);
+    run.segments[1].set_best_segment_time(time);
+
+    // Attempt 2 skipped the first two segments and got reset afterwards.
+    run.segments[0]
+        .segment_history_mut()
+        .insert(2, Time::default());
+    run.segments[1]
+        .segment_history_mut()
+        .insert(2, Time::default());
+
+    run.fix_splits();
+
+    let segments = run.segments();
+    assert!(segments[0].segment_history().get(1).is_some());
+    assert!(segments[1].segment_history().get(1).is_some());
+    assert!(segments[0].segment_history().get(2).is_none());
+    assert!(segments[1].segment_history().get(2).is_none());
+}
+
 // The below tests should be in crate::timing::timer::tests, but we ended up
 // having to put them here due to run.attempt_history being private.
 
@@ -75,3 +132,32 @@ fn timer_fix_run_upon_replacement() {
     assert_eq!(segments[1].segment_history().try_get_min_index(), Some(0));
     assert_eq!(segments[1].segment_history().try_get_max_index(), Some(1));
 }
+
+#[test]
+fn detects_whether_the_splits_need_fixing() {
+    let mut timer = create_timer(&["A", "B"]);
+    run_with_splits(&mut timer, &[3.0, 6.0]);
+    run_with_splits(&mut timer, &[2.0, 4.0]);
+    let mut run = timer.into_run(true);
+    run.fix_splits();
+    assert!(!run.needs_fixing());
+
+    run.attempt_history.pop().unwrap();
+    assert!(run.needs_fixing());
+
+    run.fix_splits();
+    assert!(!run.needs_fixing());
+}
+
+#[test]
+fn removes_duplicate_comparisons() {
+    let mut run = create_timer(&["A"]).into_run(true);
+    run.custom_comparisons.push("Custom".into());
+    run.custom_comparisons.push("Custom".into());
+    run.custom_comparisons
+        .push(crate::comparison::best_segments::NAME.into());
+
+    assert!(run.remove_duplicate_comparisons());
+    assert_eq!(run.custom_comparisons(), ["Personal Best", "Custom"]);
+    assert!(!run.remove_duplicate_comparisons());
+}
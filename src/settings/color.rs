@@ -183,6 +183,57 @@ impl Color {
 
         [hue, saturation, value, a]
     }
+
+    /// Converts the [`Color`] into hue (0 - 360), saturation (0 - 1),
+    /// lightness (0 - 1) and alpha (0 - 1).
+    pub fn to_hsla(&self) -> [f32; 4] {
+        let [hue, saturation, value, alpha] = self.to_hsva();
+
+        let lightness = value * (1.0 - 0.5 * saturation);
+        let saturation = if lightness == 0.0 || lightness == 1.0 {
+            0.0
+        } else {
+            (value - lightness) / lightness.min(1.0 - lightness)
+        };
+
+        [hue, saturation, lightness, alpha]
+    }
+
+    /// Creates a copy of the [`Color`] with the alpha (0 - 1) provided.
+    pub const fn with_alpha(&self, alpha: f32) -> Self {
+        Self {
+            red: self.red,
+            green: self.green,
+            blue: self.blue,
+            alpha,
+        }
+    }
+
+    /// Creates a lighter copy of the [`Color`] by increasing its lightness by
+    /// the amount (0 - 1) provided. The alpha stays the same.
+    pub fn lighten(&self, amount: f32) -> Self {
+        let [hue, saturation, lightness, alpha] = self.to_hsla();
+        Self::hsla(hue, saturation, (lightness + amount).clamp(0.0, 1.0), alpha)
+    }
+
+    /// Creates a darker copy of the [`Color`] by decreasing its lightness by
+    /// the amount (0 - 1) provided. The alpha stays the same.
+    pub fn darken(&self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Creates a more saturated copy of the [`Color`] by increasing its
+    /// saturation by the amount (0 - 1) provided. The alpha stays the same.
+    pub fn saturate(&self, amount: f32) -> Self {
+        let [hue, saturation, lightness, alpha] = self.to_hsla();
+        Self::hsla(hue, (saturation + amount).clamp(0.0, 1.0), lightness, alpha)
+    }
+
+    /// Creates a less saturated copy of the [`Color`] by decreasing its
+    /// saturation by the amount (0 - 1) provided. The alpha stays the same.
+    pub fn desaturate(&self, amount: f32) -> Self {
+        self.saturate(-amount)
+    }
 }
 
 impl From<[f32; 4]> for Color {
@@ -284,4 +335,25 @@ mod tests {
             [255, 0, 255, 255],
         );
     }
+
+    #[test]
+    fn hsla_roundtrip() {
+        let color = Color::rgba8(51, 153, 102, 128);
+        let [hue, saturation, lightness, alpha] = color.to_hsla();
+        assert_eq!(
+            Color::hsla(hue, saturation, lightness, alpha).to_rgba8(),
+            [51, 153, 102, 128],
+        );
+    }
+
+    #[test]
+    fn manipulation_preserves_alpha() {
+        let color = Color::rgba8(51, 153, 102, 128);
+        assert_eq!(color.lighten(0.2).to_rgba8()[3], 128);
+        assert_eq!(color.darken(0.2).to_rgba8()[3], 128);
+        assert_eq!(color.saturate(0.2).to_rgba8()[3], 128);
+        assert_eq!(color.desaturate(0.2).to_rgba8()[3], 128);
+        assert_eq!(Color::white().darken(1.0).to_rgba8(), [0, 0, 0, 255]);
+        assert_eq!(Color::black().lighten(1.0).to_rgba8(), [255, 255, 255, 255]);
+    }
 }
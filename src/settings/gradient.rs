@@ -14,6 +14,14 @@ pub enum Gradient {
     Vertical(Color, Color),
     /// Use a horizontal gradient (Left, Right).
     Horizontal(Color, Color),
+    /// Use a linear gradient in the direction of the angle provided (Angle,
+    /// Start, End). The angle is in degrees and follows the CSS convention,
+    /// so 0° goes from the bottom to the top and 90° goes from the left to the
+    /// right.
+    Linear(f32, Color, Color),
+    /// Use a radial gradient that spreads from the center to the corners
+    /// (Center, Edge).
+    Radial(Color, Color),
 }
 
 /// Describes an extended form of a gradient, specifically made for use with
@@ -31,3 +39,35 @@ impl Default for ListGradient {
         ListGradient::Same(Gradient::Transparent)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::{Layout, LayoutSettings};
+    use crate::settings::LayoutBackground;
+
+    #[test]
+    fn round_trips_through_layout_files() {
+        let (red, blue) = (
+            Color::rgba(1.0, 0.0, 0.0, 1.0),
+            Color::rgba(0.0, 0.0, 1.0, 0.5),
+        );
+
+        for gradient in [
+            Gradient::Linear(135.0, red, blue),
+            Gradient::Radial(red, blue),
+        ] {
+            let mut layout = Layout::new();
+            layout.general_settings_mut().background = LayoutBackground::Gradient(gradient);
+
+            let mut json = Vec::new();
+            layout.settings().write_json(&mut json).unwrap();
+            let layout = Layout::from_settings(LayoutSettings::from_json(&*json).unwrap());
+
+            assert_eq!(
+                layout.general_settings().background,
+                LayoutBackground::Gradient(gradient),
+            );
+        }
+    }
+}
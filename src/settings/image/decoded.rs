@@ -0,0 +1,67 @@
+use std::sync::Mutex;
+
+use crate::platform::{prelude::*, Arc};
+
+/// An image that got decoded into its pixels. The pixels are stored as `RGBA8`
+/// (red, green, blue, alpha with each channel being an u8) with straight
+/// alpha, row by row from the top left to the bottom right.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DecodedImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl DecodedImage {
+    /// The width of the image in pixels.
+    #[inline]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image in pixels.
+    #[inline]
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Accesses the pixels of the image.
+    #[inline]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+}
+
+/// Caches the decoded variants of an image per maximum size. The image data
+/// never changes, so the variants never need to be invalidated.
+#[derive(Default)]
+pub struct Variants(Mutex<Vec<(u32, Option<Arc<DecodedImage>>)>>);
+
+impl Variants {
+    pub fn get_or_decode(&self, data: &[u8], max_size: u32) -> Option<Arc<DecodedImage>> {
+        let mut variants = self.0.lock().unwrap();
+        if let Some((_, decoded)) = variants.iter().find(|(size, _)| *size == max_size) {
+            return decoded.clone();
+        }
+        let decoded = decode(data, max_size).map(Arc::new);
+        variants.push((max_size, decoded.clone()));
+        decoded
+    }
+}
+
+fn decode(data: &[u8], max_size: u32) -> Option<DecodedImage> {
+    if max_size == 0 {
+        return None;
+    }
+    // For animated images, such as GIFs, this only decodes the first frame.
+    let mut image = image::load_from_memory(data).ok()?;
+    if image.width() > max_size || image.height() > max_size {
+        image = image.thumbnail(max_size, max_size);
+    }
+    let image = image.into_rgba8();
+    Some(DecodedImage {
+        width: image.width(),
+        height: image.height(),
+        pixels: image.into_raw(),
+    })
+}
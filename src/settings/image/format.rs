@@ -0,0 +1,50 @@
+/// The format of an image's data. The format is detected from the signature at
+/// the start of the data, so it is available even without decoding the image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ImageFormat {
+    /// Portable Network Graphics.
+    Png,
+    /// JPEG.
+    Jpeg,
+    /// Graphics Interchange Format.
+    Gif,
+    /// WebP.
+    WebP,
+    /// Windows Bitmap.
+    Bmp,
+    /// Windows Icon.
+    Ico,
+    /// Tagged Image File Format.
+    Tiff,
+}
+
+impl ImageFormat {
+    /// Detects the format of the image data provided. If the format is not
+    /// known, [`None`] is returned.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        Some(match data {
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n', ..] => Self::Png,
+            [0xFF, 0xD8, 0xFF, ..] => Self::Jpeg,
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Self::Gif,
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Self::WebP,
+            [b'B', b'M', ..] => Self::Bmp,
+            [0, 0, 1, 0, ..] => Self::Ico,
+            [b'I', b'I', b'*', 0, ..] | [b'M', b'M', 0, b'*', ..] => Self::Tiff,
+            _ => return None,
+        })
+    }
+
+    /// Returns the media type (MIME type) of the format, such as `image/png`.
+    pub const fn media_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::WebP => "image/webp",
+            Self::Bmp => "image/bmp",
+            Self::Ico => "image/x-icon",
+            Self::Tiff => "image/tiff",
+        }
+    }
+}
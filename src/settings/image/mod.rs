@@ -10,12 +10,20 @@ use sha2::{Digest, Sha256};
 mod tests;
 
 mod cache;
+#[cfg(all(feature = "std", feature = "image"))]
+mod decoded;
+mod format;
 mod image_id;
 #[cfg(all(feature = "std", feature = "image-shrinking"))]
 mod shrinking;
+mod transform;
 
 pub use cache::{HasImageId, ImageCache};
+#[cfg(all(feature = "std", feature = "image"))]
+pub use decoded::DecodedImage;
+pub use format::ImageFormat;
 pub use image_id::ImageId;
+pub use transform::ImageTransform;
 
 /// Images can be used to store segment and game icons. Each image object comes
 /// with a strong hash to quickly compare images. There's no specific image
@@ -24,6 +32,8 @@ pub use image_id::ImageId;
 pub struct Image {
     data: Option<Arc<[u8]>>,
     id: ImageId,
+    #[cfg(all(feature = "std", feature = "image"))]
+    variants: Option<Arc<decoded::Variants>>,
 }
 
 impl fmt::Debug for Image {
@@ -135,6 +145,8 @@ impl Image {
     pub const EMPTY: &'static Self = &Self {
         data: None,
         id: *ImageId::EMPTY,
+        #[cfg(all(feature = "std", feature = "image"))]
+        variants: None,
     };
 
     fn new_inner(data: Arc<[u8]>) -> Self {
@@ -142,6 +154,8 @@ impl Image {
         Self {
             data: Some(data),
             id: ImageId(hash.as_slice().try_into().unwrap()),
+            #[cfg(all(feature = "std", feature = "image"))]
+            variants: Some(Default::default()),
         }
     }
 
@@ -158,6 +172,19 @@ impl Image {
         Self::new_inner(data)
     }
 
+    /// Creates a new image with the image data provided, after transforming
+    /// it as described by the transformation provided. If the image data can't
+    /// be transformed, for example because its format is unknown, it is only
+    /// scaled down to the maximum size of the transformation, just like
+    /// [`new`](Self::new) does.
+    pub fn with_transform(data: Arc<[u8]>, transform: &ImageTransform) -> Self {
+        #[cfg(all(feature = "std", feature = "image-shrinking"))]
+        if let Some(data) = transform::transform(&data, transform) {
+            return Self::new_inner(data.into());
+        }
+        Self::new(data, transform.max_size)
+    }
+
     /// Loads an image from the file system. You need to provide a buffer used
     /// for temporarily storing the image's data.
     #[cfg(feature = "std")]
@@ -193,4 +220,20 @@ impl Image {
     pub fn is_empty(&self) -> bool {
         self.data().is_empty()
     }
+
+    /// Detects the format of the image's data. If the image is empty or its
+    /// format is not known, [`None`] is returned.
+    pub fn format(&self) -> Option<ImageFormat> {
+        ImageFormat::detect(self.data())
+    }
+
+    /// Decodes the image into its pixels and scales it down to fit into a
+    /// square of the maximum size provided, keeping its aspect ratio. Images
+    /// are never scaled up. The decoded image is cached per maximum size and
+    /// shared by all the clones of the image, so frontends can call this on
+    /// every frame. If the image can't be decoded, [`None`] is returned.
+    #[cfg(all(feature = "std", feature = "image"))]
+    pub fn decode(&self, max_size: u32) -> Option<Arc<DecodedImage>> {
+        self.variants.as_ref()?.get_or_decode(self.data(), max_size)
+    }
 }
@@ -1,7 +1,73 @@
-use super::Image;
+use super::{Image, ImageFormat};
 
 #[test]
 fn serializes_to_json_as_data_url() {
     let json = serde_json::to_string(&Image::new([1, 2, 3].into(), Image::ICON)).unwrap();
     assert_eq!(r#""AQID""#, json);
 }
+
+#[test]
+fn detects_format_from_signature() {
+    let png = Image::new((*b"\x89PNG\r\n\x1a\n\0\0").into(), Image::ICON);
+    assert_eq!(png.format(), Some(ImageFormat::Png));
+    assert_eq!(ImageFormat::Png.media_type(), "image/png");
+
+    assert_eq!(
+        ImageFormat::detect(b"RIFF\0\0\0\0WEBPVP8 "),
+        Some(ImageFormat::WebP),
+    );
+    assert_eq!(ImageFormat::detect(b"GIF89a"), Some(ImageFormat::Gif));
+    assert_eq!(ImageFormat::detect(b"not an image"), None);
+    assert_eq!(Image::EMPTY.format(), None);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn decodes_and_caches_variants() {
+    use crate::platform::Arc;
+    use image::{codecs::png::PngEncoder, ExtendedColorType, ImageEncoder};
+
+    let mut data = Vec::new();
+    PngEncoder::new(&mut data)
+        .write_image(&[255; 4 * 4 * 2], 4, 2, ExtendedColorType::Rgba8)
+        .unwrap();
+    let image = Image::new(data.into(), Image::ICON);
+
+    let full = image.decode(Image::ICON).unwrap();
+    assert_eq!((full.width(), full.height()), (4, 2));
+    assert_eq!(full.pixels(), &[255; 4 * 4 * 2]);
+
+    let small = image.decode(2).unwrap();
+    assert_eq!((small.width(), small.height()), (2, 1));
+    assert!(Arc::ptr_eq(&small, &image.clone().decode(2).unwrap()));
+
+    assert!(Image::EMPTY.decode(Image::ICON).is_none());
+}
+
+#[cfg(feature = "image-shrinking")]
+#[test]
+fn transforms_into_small_square() {
+    use super::ImageTransform;
+    use image::{codecs::png::PngEncoder, ExtendedColorType, ImageEncoder};
+
+    let mut data = Vec::new();
+    PngEncoder::new(&mut data)
+        .write_image(&[255; 4 * 400 * 200], 400, 200, ExtendedColorType::Rgba8)
+        .unwrap();
+
+    let image = Image::with_transform(data.into(), &ImageTransform::ICON);
+    let decoded = image.decode(Image::LARGE).unwrap();
+    assert_eq!(
+        (decoded.width(), decoded.height()),
+        (Image::ICON, Image::ICON)
+    );
+
+    let uncropped = ImageTransform {
+        crop_to_square: false,
+        max_size: 100,
+        strip_metadata: false,
+    };
+    let image = Image::with_transform(image.data().into(), &uncropped);
+    let decoded = image.decode(Image::LARGE).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (100, 100));
+}
@@ -0,0 +1,79 @@
+/// Describes how the data of an image gets transformed when creating an image
+/// with [`Image::with_transform`](super::Image::with_transform). This is useful for keeping splits files small when users choose large
+/// screenshots as their icons.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImageTransform {
+    /// Crops the image to the largest square in its center.
+    pub crop_to_square: bool,
+    /// The maximum width and height of the image. Larger images are scaled
+    /// down to fit, keeping their aspect ratio. Images are never scaled up.
+    pub max_size: u32,
+    /// Re-encodes the image, which strips all of its metadata, such as the
+    /// camera information and embedded thumbnails.
+    pub strip_metadata: bool,
+}
+
+impl ImageTransform {
+    /// The transformation that is used for icons. The images are cropped to a
+    /// square, scaled down to [`Image::ICON`](super::Image::ICON) and their
+    /// metadata is stripped.
+    pub const ICON: Self = Self {
+        crop_to_square: true,
+        max_size: super::Image::ICON,
+        strip_metadata: true,
+    };
+}
+
+impl Default for ImageTransform {
+    fn default() -> Self {
+        Self::ICON
+    }
+}
+
+#[cfg(all(feature = "std", feature = "image-shrinking"))]
+pub fn transform(data: &[u8], transform: &ImageTransform) -> Option<Vec<u8>> {
+    use crate::util::image::create_reencoder;
+    use image::{guess_format, load_from_memory_with_format, ImageEncoder, ImageFormat};
+
+    let format = guess_format(data).ok()?;
+    if format == ImageFormat::Gif {
+        // FIXME: Just like for shrinking, we can't properly transform the
+        // whole animation.
+        return None;
+    }
+
+    let mut image = load_from_memory_with_format(data, format).ok()?;
+    let mut is_modified = transform.strip_metadata || format == ImageFormat::Bmp;
+
+    if transform.crop_to_square && image.width() != image.height() {
+        let size = image.width().min(image.height());
+        image = image.crop_imm(
+            (image.width() - size) / 2,
+            (image.height() - size) / 2,
+            size,
+            size,
+        );
+        is_modified = true;
+    }
+
+    if image.width() > transform.max_size || image.height() > transform.max_size {
+        image = image.thumbnail(transform.max_size, transform.max_size);
+        is_modified = true;
+    }
+
+    if !is_modified {
+        return None;
+    }
+
+    let mut data = Vec::new();
+    create_reencoder(&mut data)
+        .write_image(
+            image.as_bytes(),
+            image.width(),
+            image.height(),
+            image.color().into(),
+        )
+        .ok()?;
+
+    Some(data)
+}
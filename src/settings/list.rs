@@ -0,0 +1,108 @@
+use super::{Value, ValueError, ValueResult};
+use crate::platform::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+
+/// A list of setting values that all share the same type. The template
+/// describes that type and is the value that newly added items start out with.
+/// This allows settings UIs to offer adding items even if the list is empty.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct List {
+    template: Box<Value>,
+    items: Vec<Value>,
+}
+
+impl List {
+    /// Creates a new empty list whose items have the same type as the template
+    /// provided.
+    pub fn new(template: Value) -> Self {
+        Self {
+            template: Box::new(template),
+            items: Vec::new(),
+        }
+    }
+
+    /// Accesses the value that newly added items start out with.
+    pub fn template(&self) -> &Value {
+        &self.template
+    }
+
+    /// Accesses the items of the list.
+    pub fn items(&self) -> &[Value] {
+        &self.items
+    }
+
+    /// Appends the item provided to the end of the list.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the item's type doesn't match the type of the
+    /// list.
+    pub fn push(&mut self, item: Value) -> ValueResult<()> {
+        self.check_type(&item)?;
+        self.items.push(item);
+        Ok(())
+    }
+
+    /// Replaces the item at the given index with the item provided. You may
+    /// not provide an invalid index.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the item's type doesn't match the type of the
+    /// list.
+    pub fn set(&mut self, index: usize, item: Value) -> ValueResult<()> {
+        self.check_type(&item)?;
+        self.items[index] = item;
+        Ok(())
+    }
+
+    /// Removes the item at the given index. You may not provide an invalid
+    /// index.
+    pub fn remove(&mut self, index: usize) -> Value {
+        self.items.remove(index)
+    }
+
+    fn check_type(&self, item: &Value) -> ValueResult<()> {
+        if self.template.has_same_type(item) {
+            Ok(())
+        } else {
+            Err(ValueError::WrongType)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_accepts_items_of_the_same_type() {
+        let mut list = List::new(Value::String(String::new()));
+        list.push(Value::String("Foo".into())).unwrap();
+        assert!(list.push(Value::Bool(true)).is_err());
+        assert!(list.set(0, Value::UInt(5)).is_err());
+        assert_eq!(list.items().len(), 1);
+    }
+
+    #[test]
+    fn struct_items_need_the_same_fields() {
+        let template = Value::Struct(vec![
+            ("Name".into(), Value::String(String::new())),
+            ("Done".into(), Value::Bool(false)),
+        ]);
+        let mut list = List::new(template.clone());
+        list.push(template).unwrap();
+        assert!(list
+            .push(Value::Struct(vec![(
+                "Name".into(),
+                Value::String("Foo".into())
+            )]))
+            .is_err());
+        assert!(list
+            .push(Value::Struct(vec![
+                ("Name".into(), Value::String("Foo".into())),
+                ("Done".into(), Value::UInt(1)),
+            ]))
+            .is_err());
+    }
+}
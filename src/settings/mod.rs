@@ -8,18 +8,24 @@ mod font;
 mod gradient;
 mod image;
 mod layout_background;
+mod list;
+mod palette_color;
 mod semantic_color;
 mod settings_description;
 mod value;
 
+#[cfg(all(feature = "std", feature = "image"))]
+pub use self::image::DecodedImage;
 pub use self::{
     alignment::Alignment,
     color::Color,
     field::Field,
     font::{Font, Stretch as FontStretch, Style as FontStyle, Weight as FontWeight},
     gradient::{Gradient, ListGradient},
-    image::{HasImageId, Image, ImageCache, ImageId},
+    image::{HasImageId, Image, ImageCache, ImageFormat, ImageId, ImageTransform},
     layout_background::{BackgroundImage, LayoutBackground, BLUR_FACTOR},
+    list::List,
+    palette_color::PaletteColor,
     semantic_color::SemanticColor,
     settings_description::SettingsDescription,
     value::{ColumnKind, Error as ValueError, Result as ValueResult, Value},
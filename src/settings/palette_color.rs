@@ -0,0 +1,75 @@
+use super::{Color, SemanticColor};
+use crate::layout::GeneralSettings;
+use serde_derive::{Deserialize, Serialize};
+
+/// A Palette Color is either a literal [`Color`] or a reference to one of the
+/// named colors of the palette that the [`GeneralSettings`] of a layout
+/// provide, such as the color used for best segments. A reference follows the
+/// palette whenever it changes, which allows settings to stay consistent with
+/// the rest of the layout. Literal colors are stored the same way as a
+/// [`Color`], while references are stored by the name of the
+/// [`SemanticColor`].
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PaletteColor {
+    /// A literal color.
+    Color(Color),
+    /// A reference to the color of the palette with the given name.
+    Named(SemanticColor),
+}
+
+impl PaletteColor {
+    /// Resolves the Palette Color to an actual [`Color`] by looking up
+    /// references in the palette of the [`GeneralSettings`] provided.
+    pub const fn resolve(self, settings: &GeneralSettings) -> Color {
+        match self {
+            PaletteColor::Color(color) => color,
+            PaletteColor::Named(name) => name.visualize(settings),
+        }
+    }
+}
+
+impl Default for PaletteColor {
+    fn default() -> Self {
+        PaletteColor::Named(SemanticColor::Default)
+    }
+}
+
+impl From<Color> for PaletteColor {
+    fn from(color: Color) -> Self {
+        PaletteColor::Color(color)
+    }
+}
+
+impl From<SemanticColor> for PaletteColor {
+    fn from(name: SemanticColor) -> Self {
+        PaletteColor::Named(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_references() {
+        let settings = GeneralSettings::default();
+        assert_eq!(
+            PaletteColor::Named(SemanticColor::BestSegment).resolve(&settings),
+            settings.best_segment_color,
+        );
+        let color = Color::rgba8(1, 2, 3, 4);
+        assert_eq!(PaletteColor::Color(color).resolve(&settings), color);
+    }
+
+    #[test]
+    fn roundtrips_through_json() {
+        for color in [
+            PaletteColor::Named(SemanticColor::PersonalBest),
+            PaletteColor::Color(Color::rgba(0.5, 0.25, 1.0, 0.75)),
+        ] {
+            let json = serde_json::to_string(&color).unwrap();
+            assert_eq!(serde_json::from_str::<PaletteColor>(&json).unwrap(), color);
+        }
+    }
+}
@@ -6,11 +6,14 @@ use crate::{
     hotkey::Hotkey,
     layout::LayoutDirection,
     platform::prelude::*,
-    settings::{Alignment, Color, Font, Gradient, ImageId, LayoutBackground, ListGradient},
-    timing::formatter::{Accuracy, DigitsFormat},
+    settings::{
+        Alignment, Color, Font, Gradient, ImageId, LayoutBackground, List, ListGradient,
+        PaletteColor,
+    },
+    timing::formatter::{Accuracy, DigitsFormat, TimePattern},
     TimingMethod,
 };
-use core::result::Result as StdResult;
+use core::{mem, result::Result as StdResult};
 use serde_derive::{Deserialize, Serialize};
 
 /// Describes the kind of a column.
@@ -42,6 +45,9 @@ pub enum Value {
     /// A digits format, describing how many digits to show for the main part of
     /// a time.
     DigitsFormat(DigitsFormat),
+    /// An optional time pattern, describing how to format a time. [`None`] if
+    /// the accuracy and digits format settings should be used instead.
+    OptionalTimePattern(Option<TimePattern>),
     /// An optional timing method.
     OptionalTimingMethod(Option<TimingMethod>),
     /// A color.
@@ -76,6 +82,14 @@ pub enum Value {
     DeltaGradient(DeltaGradient),
     /// A value describing the background of a layout.
     LayoutBackground(LayoutBackground<ImageId>),
+    /// A color that is either a literal color or a reference to one of the
+    /// named colors of the layout's palette.
+    PaletteColor(PaletteColor),
+    /// A list of values that all share the same type.
+    List(List),
+    /// A struct made up of named fields, each of which can have a value of
+    /// any type.
+    Struct(Vec<(String, Value)>),
 }
 
 impl From<bool> for Value {
@@ -120,6 +134,12 @@ impl From<DigitsFormat> for Value {
     }
 }
 
+impl From<Option<TimePattern>> for Value {
+    fn from(x: Option<TimePattern>) -> Self {
+        Value::OptionalTimePattern(x)
+    }
+}
+
 impl From<Option<TimingMethod>> for Value {
     fn from(x: Option<TimingMethod>) -> Self {
         Value::OptionalTimingMethod(x)
@@ -210,6 +230,24 @@ impl From<LayoutBackground<ImageId>> for Value {
     }
 }
 
+impl From<PaletteColor> for Value {
+    fn from(x: PaletteColor) -> Self {
+        Value::PaletteColor(x)
+    }
+}
+
+impl From<List> for Value {
+    fn from(x: List) -> Self {
+        Value::List(x)
+    }
+}
+
+impl From<Vec<(String, Value)>> for Value {
+    fn from(x: Vec<(String, Value)>) -> Self {
+        Value::Struct(x)
+    }
+}
+
 /// The Error type for values that couldn't be converted.
 #[derive(Debug, snafu::Snafu)]
 pub enum Error {
@@ -278,6 +316,14 @@ impl Value {
         }
     }
 
+    /// Tries to convert the value into an optional time pattern.
+    pub fn into_optional_time_pattern(self) -> Result<Option<TimePattern>> {
+        match self {
+            Value::OptionalTimePattern(v) => Ok(v),
+            _ => Err(Error::WrongType),
+        }
+    }
+
     /// Tries to convert the value into an optional timing method.
     pub fn into_optional_timing_method(self) -> Result<Option<TimingMethod>> {
         match self {
@@ -410,6 +456,50 @@ impl Value {
             _ => Err(Error::WrongType),
         }
     }
+
+    /// Tries to convert the value into a palette color.
+    pub fn into_palette_color(self) -> Result<PaletteColor> {
+        match self {
+            Value::Color(v) => Ok(PaletteColor::Color(v)),
+            Value::PaletteColor(v) => Ok(v),
+            _ => Err(Error::WrongType),
+        }
+    }
+
+    /// Tries to convert the value into a list.
+    pub fn into_list(self) -> Result<List> {
+        match self {
+            Value::List(v) => Ok(v),
+            _ => Err(Error::WrongType),
+        }
+    }
+
+    /// Tries to convert the value into the fields of a struct.
+    pub fn into_struct(self) -> Result<Vec<(String, Value)>> {
+        match self {
+            Value::Struct(v) => Ok(v),
+            _ => Err(Error::WrongType),
+        }
+    }
+
+    /// Checks whether both values have the same type. Lists have the same type
+    /// if their templates do. Structs have the same type if they have the same
+    /// fields in the same order and the values of the fields have the same
+    /// types.
+    pub fn has_same_type(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::List(a), Value::List(b)) => a.template().has_same_type(b.template()),
+            (Value::Struct(a), Value::Struct(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|((a_name, a_value), (b_name, b_value))| {
+                            a_name == b_name && a_value.has_same_type(b_value)
+                        })
+            }
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
 }
 
 impl From<Value> for bool {
@@ -454,6 +544,12 @@ impl From<Value> for DigitsFormat {
     }
 }
 
+impl From<Value> for Option<TimePattern> {
+    fn from(value: Value) -> Self {
+        value.into_optional_time_pattern().unwrap()
+    }
+}
+
 impl From<Value> for Option<TimingMethod> {
     fn from(value: Value) -> Self {
         value.into_optional_timing_method().unwrap()
@@ -543,3 +639,21 @@ impl From<Value> for LayoutBackground<ImageId> {
         value.into_layout_background().unwrap()
     }
 }
+
+impl From<Value> for PaletteColor {
+    fn from(value: Value) -> Self {
+        value.into_palette_color().unwrap()
+    }
+}
+
+impl From<Value> for List {
+    fn from(value: Value) -> Self {
+        value.into_list().unwrap()
+    }
+}
+
+impl From<Value> for Vec<(String, Value)> {
+    fn from(value: Value) -> Self {
+        value.into_struct().unwrap()
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    platform::{prelude::*, DateTime},
+    TimeSpan,
+};
+
+/// The amount of samples that are kept around to estimate the offset.
+const MAX_SAMPLES: usize = 8;
+
+/// Estimates the offset between the local clock and the clock of a remote
+/// machine, similar to how NTP does it. This allows the participants of a race
+/// or a co-op run to agree on a point in time to start their timers at, even if
+/// their clocks are out of sync.
+///
+/// Each sample is a request sent to the remote machine that is answered with
+/// the time it received the request and the time it sent the response. The
+/// sample with the shortest round trip is the one least affected by network
+/// delays, so its offset is used as the estimate.
+#[derive(Debug, Clone, Default)]
+pub struct ClockSync {
+    samples: Vec<ClockSample>,
+}
+
+/// A single measurement of the offset between the clocks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ClockSample {
+    /// How far the remote clock is ahead of the local clock.
+    pub offset: TimeSpan,
+    /// The time it took for the request and the response to travel over the
+    /// network, excluding the time the remote machine took to respond.
+    pub round_trip: TimeSpan,
+}
+
+impl ClockSample {
+    /// Calculates the sample from the time the request was sent and the
+    /// response was received according to the local clock, and the time the
+    /// request was received and the response was sent according to the remote
+    /// clock.
+    pub fn new(
+        request_sent: DateTime,
+        remote_received: DateTime,
+        remote_sent: DateTime,
+        response_received: DateTime,
+    ) -> Self {
+        let outgoing = remote_received - request_sent;
+        let incoming = remote_sent - response_received;
+        Self {
+            offset: TimeSpan::from((outgoing + incoming) / 2),
+            round_trip: TimeSpan::from(
+                (response_received - request_sent) - (remote_sent - remote_received),
+            ),
+        }
+    }
+}
+
+impl ClockSync {
+    /// Creates a new clock synchronization without any samples.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sample. Only the most recent samples are kept around, so the
+    /// estimate follows clocks that drift apart over time.
+    pub fn add_sample(&mut self, sample: ClockSample) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.remove(0);
+        }
+        self.samples.push(sample);
+    }
+
+    fn best_sample(&self) -> Option<&ClockSample> {
+        self.samples.iter().min_by_key(|s| s.round_trip)
+    }
+
+    /// Returns how far the remote clock is ahead of the local clock. If there
+    /// are no samples yet, `None` is returned.
+    pub fn offset(&self) -> Option<TimeSpan> {
+        Some(self.best_sample()?.offset)
+    }
+
+    /// Returns the round trip of the sample the estimate is based on. Half of
+    /// it is the maximum error of the estimate.
+    pub fn round_trip(&self) -> Option<TimeSpan> {
+        Some(self.best_sample()?.round_trip)
+    }
+
+    /// Converts a point in time of the remote clock to the local clock. If
+    /// there are no samples yet, the clocks are assumed to be in sync.
+    pub fn to_local(&self, remote: DateTime) -> DateTime {
+        remote - self.offset().unwrap_or_default().to_duration()
+    }
+
+    /// Converts a point in time of the local clock to the remote clock. If
+    /// there are no samples yet, the clocks are assumed to be in sync.
+    pub fn to_remote(&self, local: DateTime) -> DateTime {
+        local + self.offset().unwrap_or_default().to_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::Duration;
+
+    #[test]
+    fn estimates_offset() {
+        let t0 = DateTime::UNIX_EPOCH;
+        let ms = Duration::milliseconds;
+        let mut sync = ClockSync::new();
+
+        // The remote clock is 500ms ahead, the request takes 20ms and the
+        // response 30ms.
+        sync.add_sample(ClockSample::new(
+            t0,
+            t0 + ms(520),
+            t0 + ms(530),
+            t0 + ms(60),
+        ));
+        // A slow round trip with asymmetric delays is ignored.
+        sync.add_sample(ClockSample::new(
+            t0,
+            t0 + ms(900),
+            t0 + ms(900),
+            t0 + ms(450),
+        ));
+
+        assert_eq!(sync.round_trip(), Some(ms(50).into()));
+        assert_eq!(sync.offset(), Some(ms(495).into()));
+        assert_eq!(sync.to_local(t0 + ms(495)), t0);
+    }
+}
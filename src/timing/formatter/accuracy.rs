@@ -20,6 +20,17 @@ pub enum Accuracy {
 }
 
 impl Accuracy {
+    /// The amount of nanoseconds between two consecutive values that can be
+    /// shown with the accuracy.
+    pub const fn nanoseconds_per_step(self) -> u32 {
+        match self {
+            Accuracy::Seconds => 1_000_000_000,
+            Accuracy::Tenths => NANOS_PER_TENTH,
+            Accuracy::Hundredths => NANOS_PER_HUNDREDTH,
+            Accuracy::Milliseconds => NANOS_PER_MILLI,
+        }
+    }
+
     /// Formats the nanoseconds provided with the chosen accuracy.
     pub const fn format_nanoseconds(self, nanoseconds: u32) -> FractionalPart {
         FractionalPart {
@@ -26,13 +26,20 @@ mod days;
 mod delta;
 mod digits_format;
 pub mod none_wrapper;
+mod pattern;
 mod regular;
 mod segment_time;
 pub mod timer;
 
 pub use self::{
-    accuracy::Accuracy, complete::Complete, days::Days, delta::Delta, digits_format::DigitsFormat,
-    regular::Regular, segment_time::SegmentTime,
+    accuracy::Accuracy,
+    complete::Complete,
+    days::Days,
+    delta::Delta,
+    digits_format::DigitsFormat,
+    pattern::{PatternError, TimePattern},
+    regular::Regular,
+    segment_time::SegmentTime,
 };
 
 use crate::TimeSpan;
@@ -0,0 +1,522 @@
+use super::{
+    format_padded, TimeFormatter, DASH, MINUS, PLUS, SECONDS_PER_DAY, SECONDS_PER_HOUR,
+    SECONDS_PER_MINUTE,
+};
+use crate::{platform::prelude::*, TimeSpan};
+use core::{
+    fmt::{self, Display, Formatter},
+    ops::Range,
+    str::FromStr,
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The Error type for a [`TimePattern`] that couldn't be parsed.
+#[derive(Debug, snafu::Snafu, PartialEq, Eq)]
+#[snafu(context(suffix(false)))]
+pub enum PatternError {
+    /// A quoted literal is missing its closing quote.
+    UnterminatedLiteral,
+    /// A backslash is not followed by the character to escape.
+    DanglingEscape,
+    /// An optional section is missing its closing bracket.
+    UnterminatedSection,
+    /// An optional section got closed without being opened.
+    UnexpectedSectionEnd,
+    /// Optional sections can't be nested.
+    NestedSection,
+    /// There can be at most 9 fractional digits.
+    TooManyFractionDigits,
+    /// The pattern doesn't contain any days, hours, minutes, seconds or
+    /// fractional digits.
+    NoTimeUnits,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Unit {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Unit { unit: Unit, padded: bool },
+    Fraction(u8),
+    Literal(String),
+    Optional(Vec<Token>),
+}
+
+/// A Time Pattern is a Time Formatter that formats a [`TimeSpan`] based on a
+/// pattern provided by the user. This allows formatting times in the exact
+/// way that is needed, for example to match the rest of a stream layout.
+///
+/// The following placeholders are supported:
+///
+/// * `d` The days.
+/// * `H` / `HH` The hours, optionally padded to two digits.
+/// * `m` / `mm` The minutes, optionally padded to two digits.
+/// * `s` / `ss` The seconds, optionally padded to two digits.
+/// * `f` to `fffffffff` The fractional part of the seconds with the given
+///   number of digits. The digits are truncated, not rounded.
+///
+/// Each unit contains all the larger units that are not part of the pattern,
+/// so `m:ss` shows 75 minutes for a time of 1 hour and 15 minutes. Parts of
+/// the pattern surrounded by square brackets are only shown if any of the
+/// units in them are not zero, so `[H:]mm:ss` doesn't show the hours for times
+/// below an hour. All other characters are shown as is. Characters that would
+/// otherwise be interpreted as placeholders can be surrounded by single quotes
+/// or prefixed with a backslash. As the separators are shown as is, they can
+/// be chosen to match the locale of the user, like `H:mm:ss,ff`.
+///
+/// Negative times are prefixed with a minus and empty times are shown as a
+/// dash.
+///
+/// # Example Formatting
+///
+/// * `H:mm:ss.ff` `1:04:23.50`
+/// * `[H:]m:ss` `4:23`
+/// * `m:ss.f` `64:23.5`
+/// * `ss's'` `23s`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimePattern {
+    source: String,
+    tokens: Vec<Token>,
+    units: Vec<Unit>,
+}
+
+/// A Time Span to be formatted by a [`TimePattern`].
+pub struct Inner<'a> {
+    pattern: &'a TimePattern,
+    time: Option<TimeSpan>,
+    tokens: Range<usize>,
+    show_sign: bool,
+    show_plus: bool,
+}
+
+struct Values {
+    days: u64,
+    hours: u64,
+    minutes: u64,
+    seconds: u64,
+    nanoseconds: u32,
+}
+
+impl TimePattern {
+    /// Accesses the pattern as it was provided.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Formats the time without its fractional part. This is everything up to
+    /// the first fractional digits placeholder, excluding the separator right
+    /// in front of it. If the time is negative, it is prefixed with a minus.
+    /// This can be used together with [`format_fraction`](Self::format_fraction)
+    /// for visualizing the two parts of the time differently.
+    pub fn format_main<T>(&self, time: T) -> Inner<'_>
+    where
+        T: Into<Option<TimeSpan>>,
+    {
+        Inner {
+            pattern: self,
+            time: time.into(),
+            tokens: 0..self.fraction_start(),
+            show_sign: true,
+            show_plus: false,
+        }
+    }
+
+    /// Formats the fractional part of the time, including the separator right
+    /// in front of it. This is the rest of the pattern that is not formatted
+    /// by [`format_main`](Self::format_main). Empty times are shown as an
+    /// empty string.
+    pub fn format_fraction<T>(&self, time: T) -> Inner<'_>
+    where
+        T: Into<Option<TimeSpan>>,
+    {
+        Inner {
+            pattern: self,
+            time: time.into(),
+            tokens: self.fraction_start()..self.tokens.len(),
+            show_sign: false,
+            show_plus: false,
+        }
+    }
+
+    /// Formats the time as a delta, just like the [`Delta`](super::Delta)
+    /// formatter does. Positive times are prefixed with a plus and negative
+    /// times with a minus.
+    pub fn format_delta<T>(&self, time: T) -> Inner<'_>
+    where
+        T: Into<Option<TimeSpan>>,
+    {
+        Inner {
+            pattern: self,
+            time: time.into(),
+            tokens: 0..self.tokens.len(),
+            show_sign: true,
+            show_plus: true,
+        }
+    }
+
+    fn fraction_start(&self) -> usize {
+        let Some(index) = self.tokens.iter().position(|t| match t {
+            Token::Fraction(_) => true,
+            Token::Optional(tokens) => tokens.iter().any(|t| matches!(t, Token::Fraction(_))),
+            _ => false,
+        }) else {
+            return self.tokens.len();
+        };
+        // An optional section already contains its own separator.
+        match (
+            &self.tokens[index],
+            index.checked_sub(1).map(|i| &self.tokens[i]),
+        ) {
+            (Token::Fraction(_), Some(Token::Literal(_))) => index - 1,
+            _ => index,
+        }
+    }
+}
+
+impl FromStr for TimePattern {
+    type Err = PatternError;
+
+    fn from_str(source: &str) -> Result<Self, PatternError> {
+        let mut tokens = Vec::new();
+        let mut section: Option<Vec<Token>> = None;
+        let mut chars = source.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            let current = section.as_mut().unwrap_or(&mut tokens);
+            let token = match c {
+                'd' | 'H' | 'm' | 's' => {
+                    let padded = chars.next_if_eq(&c).is_some();
+                    let unit = match c {
+                        'd' => Unit::Days,
+                        'H' => Unit::Hours,
+                        'm' => Unit::Minutes,
+                        _ => Unit::Seconds,
+                    };
+                    Token::Unit { unit, padded }
+                }
+                'f' => {
+                    let mut digits = 1;
+                    while chars.next_if_eq(&'f').is_some() {
+                        digits += 1;
+                    }
+                    if digits > 9 {
+                        return Err(PatternError::TooManyFractionDigits);
+                    }
+                    Token::Fraction(digits)
+                }
+                '[' => {
+                    if section.is_some() {
+                        return Err(PatternError::NestedSection);
+                    }
+                    section = Some(Vec::new());
+                    continue;
+                }
+                ']' => {
+                    let section = section.take().ok_or(PatternError::UnexpectedSectionEnd)?;
+                    tokens.push(Token::Optional(section));
+                    continue;
+                }
+                '\'' => {
+                    let mut literal = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('\'') => break,
+                            Some(c) => literal.push(c),
+                            None => return Err(PatternError::UnterminatedLiteral),
+                        }
+                    }
+                    push_literal(current, &literal);
+                    continue;
+                }
+                '\\' => {
+                    let c = chars.next().ok_or(PatternError::DanglingEscape)?;
+                    push_literal(current, c.encode_utf8(&mut [0; 4]));
+                    continue;
+                }
+                c => {
+                    push_literal(current, c.encode_utf8(&mut [0; 4]));
+                    continue;
+                }
+            };
+            current.push(token);
+        }
+
+        if section.is_some() {
+            return Err(PatternError::UnterminatedSection);
+        }
+
+        let mut units = tokens
+            .iter()
+            .flat_map(|t| match t {
+                Token::Optional(tokens) => tokens.as_slice(),
+                t => core::slice::from_ref(t),
+            })
+            .filter_map(|t| match t {
+                Token::Unit { unit, .. } => Some(*unit),
+                Token::Fraction(_) => Some(Unit::Seconds),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if units.is_empty() {
+            return Err(PatternError::NoTimeUnits);
+        }
+        units.sort_unstable();
+        units.dedup();
+
+        Ok(Self {
+            source: source.to_owned(),
+            tokens,
+            units,
+        })
+    }
+}
+
+fn push_literal(tokens: &mut Vec<Token>, literal: &str) {
+    if let Some(Token::Literal(previous)) = tokens.last_mut() {
+        previous.push_str(literal);
+    } else {
+        tokens.push(Token::Literal(literal.to_owned()));
+    }
+}
+
+impl Display for TimePattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Serialize for TimePattern {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimePattern {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let source = String::deserialize(deserializer)?;
+        source
+            .parse()
+            .map_err(|e| de::Error::custom(format!("Not a valid time pattern: {e}")))
+    }
+}
+
+impl<'a> TimeFormatter<'a> for TimePattern {
+    type Inner = Inner<'a>;
+
+    fn format<T>(&'a self, time: T) -> Self::Inner
+    where
+        T: Into<Option<TimeSpan>>,
+    {
+        Inner {
+            pattern: self,
+            time: time.into(),
+            tokens: 0..self.tokens.len(),
+            show_sign: true,
+            show_plus: false,
+        }
+    }
+}
+
+impl Unit {
+    const fn seconds(self) -> u64 {
+        match self {
+            Unit::Days => SECONDS_PER_DAY,
+            Unit::Hours => SECONDS_PER_HOUR,
+            Unit::Minutes => SECONDS_PER_MINUTE,
+            Unit::Seconds => 1,
+        }
+    }
+}
+
+impl Values {
+    const fn get(&self, unit: Unit) -> u64 {
+        match unit {
+            Unit::Days => self.days,
+            Unit::Hours => self.hours,
+            Unit::Minutes => self.minutes,
+            Unit::Seconds => self.seconds,
+        }
+    }
+}
+
+impl Display for Inner<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Some(time) = self.time else {
+            return if self.show_sign {
+                f.write_str(DASH)
+            } else {
+                Ok(())
+            };
+        };
+
+        let (total_seconds, nanoseconds) = time.to_seconds_and_subsec_nanoseconds();
+        let (total_seconds, nanoseconds) = if (total_seconds | nanoseconds as i64) < 0 {
+            if self.show_sign {
+                f.write_str(MINUS)?;
+            }
+            (total_seconds.wrapping_neg() as u64, (-nanoseconds) as u32)
+        } else {
+            if self.show_plus && (total_seconds | nanoseconds as i64) != 0 {
+                f.write_str(PLUS)?;
+            }
+            (total_seconds as u64, nanoseconds as u32)
+        };
+
+        // Each unit contains all the larger units that are not part of the
+        // pattern.
+        let value = |unit: Unit| match self.pattern.units.iter().find(|&&u| u > unit) {
+            Some(larger) => (total_seconds % larger.seconds()) / unit.seconds(),
+            None => total_seconds / unit.seconds(),
+        };
+        let values = Values {
+            days: value(Unit::Days),
+            hours: value(Unit::Hours),
+            minutes: value(Unit::Minutes),
+            seconds: value(Unit::Seconds),
+            nanoseconds,
+        };
+
+        for token in &self.pattern.tokens[self.tokens.clone()] {
+            if let Token::Optional(tokens) = token {
+                let is_shown = tokens.iter().any(|t| match t {
+                    Token::Unit { unit, .. } => values.get(*unit) != 0,
+                    Token::Fraction(_) => values.nanoseconds != 0,
+                    _ => false,
+                });
+                if is_shown {
+                    for token in tokens {
+                        write_token(f, token, &values)?;
+                    }
+                }
+            } else {
+                write_token(f, token, &values)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn write_token(f: &mut Formatter<'_>, token: &Token, values: &Values) -> fmt::Result {
+    match token {
+        Token::Unit { unit, padded } => {
+            let value = values.get(*unit);
+            if *padded && value < 100 {
+                f.write_str(format_padded(value as u8))
+            } else {
+                f.write_str(itoa::Buffer::new().format(value))
+            }
+        }
+        Token::Fraction(digits) => {
+            let fraction = values.nanoseconds / 10u32.pow(9 - u32::from(*digits));
+            let mut buffer = itoa::Buffer::new();
+            let fraction = buffer.format(fraction);
+            f.write_str(&"000000000"[fraction.len() + 9 - *digits as usize..])?;
+            f.write_str(fraction)
+        }
+        Token::Literal(literal) => f.write_str(literal),
+        Token::Optional(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(pattern: &str, time: &str) -> String {
+        let pattern: TimePattern = pattern.parse().unwrap();
+        pattern
+            .format(TimeSpan::from_str(time).unwrap())
+            .to_string()
+    }
+
+    #[test]
+    fn units() {
+        assert_eq!(format("H:mm:ss.ff", "1:04:23.5"), "1:04:23.50");
+        assert_eq!(format("H:mm:ss.ff", "4:23.5"), "0:04:23.50");
+        assert_eq!(format("m:ss.f", "1:04:23.56"), "64:23.5");
+        assert_eq!(format("s.fff", "1:00.1234"), "60.123");
+        assert_eq!(format("d.HH:mm:ss", "49:00:01"), "2.01:00:01");
+        assert_eq!(format("H's' s", "1:01:05"), "1s 65");
+    }
+
+    #[test]
+    fn optional_sections() {
+        assert_eq!(format("[H:]mm:ss", "4:23"), "04:23");
+        assert_eq!(format("[H:]mm:ss", "2:04:23"), "2:04:23");
+        assert_eq!(format("ss[.ff]", "23"), "23");
+        assert_eq!(format("ss[.ff]", "23.5"), "23.50");
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(format("m'm' s's'", "4:23"), "4m 23s");
+        assert_eq!(format("m\\m ss,ff", "4:23.5"), "4m 23,50");
+    }
+
+    #[test]
+    fn signs_and_empty_times() {
+        let pattern: TimePattern = "m:ss.ff".parse().unwrap();
+        let time = TimeSpan::from_str("-4:23.5").unwrap();
+        assert_eq!(pattern.format(time).to_string(), "−4:23.50");
+        assert_eq!(pattern.format_main(time).to_string(), "−4:23");
+        assert_eq!(pattern.format_fraction(time).to_string(), ".50");
+        assert_eq!(pattern.format(None).to_string(), "—");
+        assert_eq!(pattern.format_fraction(None).to_string(), "");
+
+        let pattern: TimePattern = "ss[.ff]".parse().unwrap();
+        let time = TimeSpan::from_str("23.5").unwrap();
+        assert_eq!(pattern.format_main(time).to_string(), "23");
+        assert_eq!(pattern.format_fraction(time).to_string(), ".50");
+    }
+
+    #[test]
+    fn deltas() {
+        let pattern: TimePattern = "[m:]ss.f".parse().unwrap();
+        let delta = |time| {
+            pattern
+                .format_delta(TimeSpan::from_str(time).unwrap())
+                .to_string()
+        };
+        assert_eq!(delta("4:23.5"), "+4:23.5");
+        assert_eq!(delta("-23.5"), "−23.5");
+        assert_eq!(delta("0"), "00.0");
+        assert_eq!(pattern.format_delta(None).to_string(), "—");
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert_eq!(
+            "ss.ffffffffff".parse::<TimePattern>(),
+            Err(PatternError::TooManyFractionDigits)
+        );
+        assert_eq!(
+            "[H:[m]]".parse::<TimePattern>(),
+            Err(PatternError::NestedSection)
+        );
+        assert_eq!(
+            "[H:".parse::<TimePattern>(),
+            Err(PatternError::UnterminatedSection)
+        );
+        assert_eq!(
+            "H]".parse::<TimePattern>(),
+            Err(PatternError::UnexpectedSectionEnd)
+        );
+        assert_eq!(
+            "'H".parse::<TimePattern>(),
+            Err(PatternError::UnterminatedLiteral)
+        );
+        assert_eq!("abc".parse::<TimePattern>(), Err(PatternError::NoTimeUnits));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::TimeSpan;
+
+/// How many seconds the game time needs to stay the same to be considered
+/// stopped. Shorter pauses are expected, as the game time usually only changes
+/// once per frame.
+const STALL_THRESHOLD: f64 = 0.1;
+
+/// Smooths the game time that is reported by a game or an auto splitter at
+/// irregular intervals. Reading the game time from the game's memory is
+/// subject to jitter, as the game only updates it every frame and the samples
+/// are taken at a different rate. Setting these samples directly causes the
+/// game time that is shown to stutter or even jump backwards slightly.
+///
+/// The smoother tracks the offset between the real time and the game time and
+/// only slowly adjusts it towards the offset of each sample. The smoothed game
+/// time never moves backwards, unless the game time jumps by more than the
+/// maximum drift, such as when the game reloads a save file. Those jumps are
+/// applied immediately. If the game time stops advancing for longer than a
+/// frame or two, like while the game is loading, the smoothed game time stops
+/// as well.
+#[derive(Debug, Clone)]
+pub struct GameTimeSmoother {
+    smoothing: f64,
+    max_drift: TimeSpan,
+    last: Option<Sample>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Sample {
+    real_time: TimeSpan,
+    game_time: TimeSpan,
+    changed_at: TimeSpan,
+    offset: TimeSpan,
+    output: TimeSpan,
+}
+
+impl Sample {
+    fn new(real_time: TimeSpan, game_time: TimeSpan) -> Self {
+        Self {
+            real_time,
+            game_time,
+            changed_at: real_time,
+            offset: game_time - real_time,
+            output: game_time,
+        }
+    }
+}
+
+impl Default for GameTimeSmoother {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameTimeSmoother {
+    /// Creates a new smoother that moves the offset a tenth of the way towards
+    /// each sample and applies jumps of more than half a second immediately.
+    pub fn new() -> Self {
+        Self::with_settings(0.1, TimeSpan::from_milliseconds(500.0))
+    }
+
+    /// Creates a new smoother. The smoothing specifies which fraction of the
+    /// difference between the tracked offset and the offset of a sample is
+    /// applied per sample. A smoothing of 1 disables the smoothing. Jumps of
+    /// more than the maximum drift are applied immediately.
+    pub fn with_settings(smoothing: f64, max_drift: TimeSpan) -> Self {
+        Self {
+            smoothing: smoothing.clamp(0.0, 1.0),
+            max_drift,
+            last: None,
+        }
+    }
+
+    /// Forgets about all the previous samples. This should be called whenever
+    /// a new attempt starts.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+
+    /// Processes the game time reported at the real time provided and returns
+    /// the game time that should be shown.
+    pub fn push(&mut self, real_time: TimeSpan, game_time: TimeSpan) -> TimeSpan {
+        let raw_offset = game_time - real_time;
+        let sample = match self.last {
+            Some(last) if real_time >= last.real_time => {
+                if game_time == last.game_time {
+                    if (real_time - last.changed_at).total_seconds() > STALL_THRESHOLD {
+                        // The game time stopped, so the shown game time stops
+                        // as well. When it continues, it continues from where
+                        // it stopped.
+                        Sample {
+                            offset: last.output - real_time,
+                            real_time,
+                            ..last
+                        }
+                    } else {
+                        // The game most likely just didn't render a new frame
+                        // yet.
+                        Sample {
+                            real_time,
+                            output: (real_time + last.offset).max(last.output),
+                            ..last
+                        }
+                    }
+                } else {
+                    let error = raw_offset - last.offset;
+                    if error.to_duration().abs() > self.max_drift.to_duration() {
+                        Sample::new(real_time, game_time)
+                    } else {
+                        let offset = last.offset
+                            + TimeSpan::from_seconds(error.total_seconds() * self.smoothing);
+                        Sample {
+                            real_time,
+                            game_time,
+                            changed_at: real_time,
+                            offset,
+                            output: (real_time + offset).max(last.output),
+                        }
+                    }
+                }
+            }
+            _ => Sample::new(real_time, game_time),
+        };
+
+        self.last = Some(sample);
+        sample.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::span;
+
+    #[test]
+    fn never_moves_backwards() {
+        let mut smoother = GameTimeSmoother::new();
+        assert_eq!(smoother.push(span(10.0), span(5.0)), span(5.0));
+
+        // The game time lags behind by a frame, which is just jitter.
+        let time = smoother.push(span(10.1), span(5.08));
+        assert!(time > span(5.08) && time <= span(5.1));
+        // The game didn't render a new frame yet.
+        assert!(smoother.push(span(10.11), span(5.08)) > time);
+
+        // The game loads, so the game time stops.
+        let loading = smoother.push(span(10.2), span(5.08));
+        assert_eq!(smoother.push(span(12.0), span(5.08)), loading);
+
+        // The game reloads a save file from further back.
+        assert_eq!(smoother.push(span(12.1), span(3.0)), span(3.0));
+    }
+}
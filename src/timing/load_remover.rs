@@ -0,0 +1,101 @@
+use crate::{
+    event::{CommandSink, TimerQuery},
+    TimeSpan, TimerPhase,
+};
+
+/// Removes the time the game spends loading from the game time, based on a
+/// signal that tells whether the game is currently loading. This is separate
+/// from any splitting logic, so a load remover can be used on its own while
+/// the runner splits manually, or be combined with an auto splitter.
+///
+/// The time spent loading is added to the loading times of the timer, which
+/// the game time is derived from. Any loading times that were there before,
+/// such as ones that were set manually, are kept.
+#[derive(Debug, Clone, Default)]
+pub struct LoadRemover {
+    load: Option<Load>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Load {
+    started_at: TimeSpan,
+    loading_times_before: TimeSpan,
+}
+
+impl LoadRemover {
+    /// Creates a new load remover.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the game is considered to be loading.
+    pub const fn is_loading(&self) -> bool {
+        self.load.is_some()
+    }
+
+    /// Updates the loading times of the timer based on whether the game is
+    /// currently loading. This is meant to be called regularly, such as on
+    /// every tick of an auto splitter.
+    pub fn update<S: CommandSink + TimerQuery>(&mut self, is_loading: bool, timer: &S) {
+        let (phase, real_time, loading_times) = {
+            let timer = timer.get_timer();
+            (
+                timer.current_phase(),
+                timer.snapshot().current_time().real_time,
+                timer.loading_times(),
+            )
+        };
+
+        let Some(real_time) = real_time.filter(|_| phase != TimerPhase::NotRunning) else {
+            self.load = None;
+            return;
+        };
+
+        if is_loading {
+            let load = *self.load.get_or_insert(Load {
+                started_at: real_time,
+                loading_times_before: loading_times,
+            });
+            drop(
+                timer.set_loading_times(load.loading_times_before + (real_time - load.started_at)),
+            );
+        } else if let Some(load) = self.load.take() {
+            drop(
+                timer.set_loading_times(load.loading_times_before + (real_time - load.started_at)),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests_helper::create_timer;
+
+    #[test]
+    fn accumulates_loading_times() {
+        let timer = create_timer(&["A"]).into_shared();
+        let mut load_remover = LoadRemover::new();
+
+        load_remover.update(true, &timer);
+        assert!(!load_remover.is_loading());
+
+        timer.write().unwrap().start().unwrap();
+        timer
+            .write()
+            .unwrap()
+            .set_loading_times(TimeSpan::from_seconds(1.0))
+            .unwrap();
+
+        load_remover.update(true, &timer);
+        assert!(load_remover.is_loading());
+        load_remover.update(true, &timer);
+        load_remover.update(false, &timer);
+        assert!(!load_remover.is_loading());
+
+        let timer = timer.read().unwrap();
+        let loading_times = timer.loading_times();
+        assert!(loading_times >= TimeSpan::from_seconds(1.0));
+        assert!(loading_times < TimeSpan::from_seconds(2.0));
+    }
+}
@@ -2,7 +2,10 @@
 //! measuring them.
 
 mod atomic_date_time;
+mod clock_sync;
 pub mod formatter;
+mod game_time_smoother;
+mod load_remover;
 mod time;
 mod time_span;
 mod time_stamp;
@@ -14,10 +17,13 @@ mod timing_method;
 pub use self::timer::SharedTimer;
 pub use self::{
     atomic_date_time::AtomicDateTime,
+    clock_sync::{ClockSample, ClockSync},
+    game_time_smoother::GameTimeSmoother,
+    load_remover::LoadRemover,
     time::{GameTime, RealTime, Time},
     time_span::{ParseError, TimeSpan},
     time_stamp::TimeStamp,
-    timer::{CreationError as TimerCreationError, Snapshot, Timer},
+    timer::{AttemptState, CreationError as TimerCreationError, ListenerId, Snapshot, Timer},
     timer_phase::TimerPhase,
     timing_method::TimingMethod,
 };
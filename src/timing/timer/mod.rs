@@ -1,14 +1,15 @@
 use crate::{
     analysis::check_best_segment,
-    comparison::personal_best,
+    comparison::{personal_best, ComparisonGenerator},
     event::{Error, Event},
-    platform::prelude::*,
+    platform::{prelude::*, Duration},
+    timing::formatter::Accuracy,
     util::PopulateString,
-    AtomicDateTime, Run, Segment, Time, TimeSpan, TimeStamp,
+    AtomicDateTime, DateTime, Run, Segment, Time, TimeSpan, TimeStamp,
     TimerPhase::{self, *},
     TimingMethod,
 };
-use core::{mem, ops::Deref};
+use core::{fmt, mem, ops::Deref};
 
 #[cfg(test)]
 mod tests;
@@ -54,6 +55,48 @@ pub struct Timer {
     current_comparison: String,
     current_timing_method: TimingMethod,
     active_attempt: Option<ActiveAttempt>,
+    listeners: Listeners,
+}
+
+/// Identifies an event listener that got registered on a [`Timer`], so that it
+/// can be removed again.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+impl From<ListenerId> for u64 {
+    fn from(id: ListenerId) -> Self {
+        id.0
+    }
+}
+
+impl From<u64> for ListenerId {
+    fn from(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+type Listener = Box<dyn FnMut(Event) + Send + Sync>;
+
+#[derive(Default)]
+struct Listeners {
+    next_id: u64,
+    listeners: Vec<(ListenerId, Listener)>,
+}
+
+// The listeners belong to the timer they got registered on, so a clone of the
+// timer starts out without any.
+impl Clone for Listeners {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for Listeners {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Listeners")
+            .field("len", &self.listeners.len())
+            .finish()
+    }
 }
 
 /// A snapshot represents a specific point in time that the timer was observed
@@ -70,6 +113,42 @@ impl Snapshot<'_> {
     pub const fn current_time(&self) -> Time {
         self.time
     }
+
+    /// Returns how long it takes until the times shown with the accuracy
+    /// provided visually change next. This allows frontends to only render
+    /// when something actually changes, instead of rendering continuously.
+    /// While the timer is not running, the times only change when the timer
+    /// is interacted with, so [`None`] is returned.
+    pub fn time_until_next_change(&self, accuracy: Accuracy) -> Option<TimeSpan> {
+        if self.current_phase() != Running {
+            return None;
+        }
+        let step = accuracy.nanoseconds_per_step() as i128;
+        let real_time = self
+            .time
+            .real_time
+            .map(|time| time_until_next_step(time, step));
+        let game_time = self
+            .time
+            .game_time
+            .filter(|_| !self.is_game_time_paused())
+            .map(|time| time_until_next_step(time, step));
+        real_time.into_iter().chain(game_time).min()
+    }
+}
+
+/// The formatters truncate the fractional part of the absolute value of a
+/// time, so positive times change when the next step is reached, while
+/// negative times change when the previous step is left.
+fn time_until_next_step(time: TimeSpan, step: i128) -> TimeSpan {
+    let (seconds, nanoseconds) = time.to_seconds_and_subsec_nanoseconds();
+    let nanoseconds = seconds as i128 * 1_000_000_000 + nanoseconds as i128;
+    let remaining = if nanoseconds >= 0 {
+        step - nanoseconds % step
+    } else {
+        -nanoseconds % step
+    };
+    TimeSpan::from(Duration::nanoseconds(remaining as i64))
 }
 
 impl Deref for Snapshot<'_> {
@@ -79,6 +158,29 @@ impl Deref for Snapshot<'_> {
     }
 }
 
+/// The state of the current attempt of a [`Timer`]. It contains everything
+/// that is needed for another timer to mirror the attempt exactly, for example
+/// on a different device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttemptState {
+    /// The phase the timer is in.
+    pub phase: TimerPhase,
+    /// The index of the current split, if there is an attempt in progress.
+    pub current_split_index: Option<usize>,
+    /// The split times of all the segments.
+    pub split_times: Vec<Time>,
+    /// The current time of the timer.
+    pub current_time: Time,
+    /// The total amount of time the attempt has been paused for.
+    pub pause_time: Option<TimeSpan>,
+    /// Whether the game time is paused.
+    pub is_game_time_paused: bool,
+    /// The name of the current comparison.
+    pub current_comparison: String,
+    /// The current timing method.
+    pub current_timing_method: TimingMethod,
+}
+
 /// A `SharedTimer` is a wrapper around the [`Timer`](crate::timing::Timer) that can be shared across multiple threads with multiple owners.
 #[cfg(feature = "std")]
 pub type SharedTimer = alloc::sync::Arc<std::sync::RwLock<Timer>>;
@@ -111,9 +213,40 @@ impl Timer {
             current_comparison: personal_best::NAME.into(),
             current_timing_method: TimingMethod::RealTime,
             active_attempt: None,
+            listeners: Listeners::default(),
         })
     }
 
+    /// Registers a listener that gets called with every [`Event`] the timer
+    /// emits, such as a split happening or the timer getting paused. This
+    /// allows frontends to react to changes right away instead of polling the
+    /// timer. The listener is called while the timer is being modified, so it
+    /// can't access the timer itself.
+    pub fn add_event_listener<F>(&mut self, listener: F) -> ListenerId
+    where
+        F: FnMut(Event) + Send + Sync + 'static,
+    {
+        let id = ListenerId(self.listeners.next_id);
+        self.listeners.next_id += 1;
+        self.listeners.listeners.push((id, Box::new(listener)));
+        id
+    }
+
+    /// Removes the event listener with the ID provided. Returns whether there
+    /// was such a listener.
+    pub fn remove_event_listener(&mut self, id: ListenerId) -> bool {
+        let len = self.listeners.listeners.len();
+        self.listeners.listeners.retain(|(i, _)| *i != id);
+        self.listeners.listeners.len() != len
+    }
+
+    fn emit(&mut self, event: Event) -> Event {
+        for (_, listener) in &mut self.listeners.listeners {
+            listener(event);
+        }
+        event
+    }
+
     /// Consumes the Timer and creates a Shared Timer that can be shared across
     /// multiple threads with multiple owners.
     #[cfg(feature = "std")]
@@ -222,6 +355,7 @@ impl Timer {
     #[inline]
     pub fn set_current_timing_method(&mut self, method: TimingMethod) {
         self.current_timing_method = method;
+        self.emit(Event::TimingMethodChanged);
     }
 
     /// Toggles between the `Real Time` and `Game Time` timing methods.
@@ -231,6 +365,7 @@ impl Timer {
             TimingMethod::RealTime => TimingMethod::GameTime,
             TimingMethod::GameTime => TimingMethod::RealTime,
         };
+        self.emit(Event::TimingMethodChanged);
     }
 
     /// Returns the current comparison that is being compared against. This may
@@ -247,7 +382,7 @@ impl Timer {
         let as_str = comparison.as_str();
         if self.run.comparisons().any(|c| c == as_str) {
             comparison.populate(&mut self.current_comparison);
-            Ok(Event::ComparisonChanged)
+            Ok(self.emit(Event::ComparisonChanged))
         } else {
             Err(Error::ComparisonDoesntExist)
         }
@@ -279,10 +414,28 @@ impl Timer {
     /// Starts the Timer if there is no attempt in progress. If that's not the
     /// case, nothing happens.
     pub fn start(&mut self) -> Result {
+        self.start_with_offset(AtomicDateTime::now(), self.run.offset())
+    }
+
+    /// Starts the Timer at the point in time provided if there is no attempt in
+    /// progress. If that's not the case, nothing happens. This allows multiple
+    /// timers to start in sync, for example when the point in time got agreed
+    /// on through a [`ClockSync`](crate::timing::ClockSync). If the point in
+    /// time is in the future, the timer counts up from a negative time until
+    /// the run's offset is reached at that point in time. If it is in the
+    /// past, the timer starts as if it was started back then.
+    pub fn start_at(&mut self, start: DateTime) -> Result {
+        let now = AtomicDateTime::now();
+        let offset = self.run.offset() + (now - start);
+        self.start_with_offset(
+            AtomicDateTime::new(start, now.synced_with_atomic_clock),
+            offset,
+        )
+    }
+
+    fn start_with_offset(&mut self, attempt_started: AtomicDateTime, offset: TimeSpan) -> Result {
         if self.active_attempt.is_none() {
-            let attempt_started = AtomicDateTime::now();
             let start_time = TimeStamp::now();
-            let offset = self.run.offset();
 
             self.active_attempt = Some(ActiveAttempt {
                 state: State::NotEnded {
@@ -298,7 +451,7 @@ impl Timer {
             });
             self.run.start_next_run();
 
-            Ok(Event::Started)
+            Ok(self.emit(Event::Started))
         } else {
             Err(Error::RunAlreadyInProgress)
         }
@@ -325,7 +478,7 @@ impl Timer {
 
         self.run.mark_as_modified();
 
-        Ok(event)
+        Ok(self.emit(event))
     }
 
     /// Starts a new attempt or stores the current time as the time of the
@@ -356,7 +509,7 @@ impl Timer {
 
             self.run.mark_as_modified();
 
-            Ok(Event::SplitSkipped)
+            Ok(self.emit(Event::SplitSkipped))
         } else {
             Err(Error::CantSkipLastSplit)
         }
@@ -388,7 +541,7 @@ impl Timer {
 
             self.run.mark_as_modified();
 
-            Ok(Event::SplitUndone)
+            Ok(self.emit(Event::SplitUndone))
         } else {
             Err(Error::CantUndoFirstSplit)
         }
@@ -448,7 +601,7 @@ impl Timer {
         if self.active_attempt.is_some() {
             self.reset_state(update_splits);
             self.reset_splits();
-            Ok(Event::Reset)
+            Ok(self.emit(Event::Reset))
         } else {
             Err(Error::NoRunInProgress)
         }
@@ -462,7 +615,7 @@ impl Timer {
             self.reset_state(true);
             set_run_as_pb(&mut self.run);
             self.reset_splits();
-            Ok(Event::Reset)
+            Ok(self.emit(Event::Reset))
         } else {
             Err(Error::NoRunInProgress)
         }
@@ -499,7 +652,7 @@ impl Timer {
         if time_paused_at.is_none() {
             *time_paused_at =
                 Some(TimeStamp::now() - active_attempt.start_time + active_attempt.adjusted_offset);
-            Ok(Event::Paused)
+            Ok(self.emit(Event::Paused))
         } else {
             Err(Error::AlreadyPaused)
         }
@@ -507,6 +660,11 @@ impl Timer {
 
     /// Resumes an attempt that is paused.
     pub fn resume(&mut self) -> Result {
+        self.resume_attempt()?;
+        Ok(self.emit(Event::Resumed))
+    }
+
+    fn resume_attempt(&mut self) -> Result<()> {
         let active_attempt = self.active_attempt.as_mut().ok_or(Error::NoRunInProgress)?;
 
         let State::NotEnded { time_paused_at, .. } = &mut active_attempt.state else {
@@ -517,7 +675,7 @@ impl Timer {
             active_attempt.adjusted_offset =
                 pause_time - (TimeStamp::now() - active_attempt.start_time);
             *time_paused_at = None;
-            Ok(Event::Resumed)
+            Ok(())
         } else {
             Err(Error::NotPaused)
         }
@@ -557,7 +715,7 @@ impl Timer {
     pub fn undo_all_pauses(&mut self) -> Result {
         let event = match self.current_phase() {
             Paused => {
-                self.resume()?;
+                self.resume_attempt()?;
                 Event::PausesUndoneAndResumed
             }
             Ended => {
@@ -582,7 +740,7 @@ impl Timer {
 
         if let Some(active_attempt) = &mut self.active_attempt {
             active_attempt.adjusted_offset = active_attempt.original_offset;
-            Ok(event)
+            Ok(self.emit(event))
         } else {
             Err(Error::NoRunInProgress)
         }
@@ -601,6 +759,7 @@ impl Timer {
             .nth(index)
             .unwrap()
             .populate(&mut self.current_comparison);
+        self.emit(Event::ComparisonChanged);
     }
 
     /// Switches the current comparison to the previous comparison in the list.
@@ -616,6 +775,116 @@ impl Timer {
             .nth(index)
             .unwrap()
             .populate(&mut self.current_comparison);
+        self.emit(Event::ComparisonChanged);
+    }
+
+    /// Adds a Comparison Generator to the Run, replacing the Comparison
+    /// Generator with the same name if there already is one. The comparisons
+    /// are regenerated immediately.
+    pub fn set_comparison_generator(&mut self, generator: Box<dyn ComparisonGenerator>) {
+        let generators = self.run.comparison_generators_mut();
+        if let Some(existing) = generators.iter_mut().find(|g| g.name() == generator.name()) {
+            *existing = generator;
+        } else {
+            generators.push(generator);
+        }
+        self.run.regenerate_comparisons();
+    }
+
+    /// Removes the Comparison Generator with the name provided from the Run.
+    /// If it is the current comparison, the timer switches to the Personal
+    /// Best comparison.
+    pub fn remove_comparison_generator(&mut self, name: &str) {
+        self.run
+            .comparison_generators_mut()
+            .retain(|g| g.name() != name);
+        if self.current_comparison == name {
+            self.current_comparison = personal_best::NAME.into();
+        }
+    }
+
+    /// Recalculates all the comparison times the Comparison Generators
+    /// provide. Usually this happens automatically between attempts, but
+    /// Comparison Generators that receive new information during an attempt,
+    /// such as the splits of a race opponent, need it to happen right away.
+    pub fn regenerate_comparisons(&mut self) {
+        self.run.regenerate_comparisons();
+    }
+
+    /// Captures the state of the current attempt, so that another timer can
+    /// mirror it.
+    pub fn attempt_state(&self) -> AttemptState {
+        AttemptState {
+            phase: self.current_phase(),
+            current_split_index: self.current_split_index(),
+            split_times: self.run.segments().iter().map(|s| s.split_time()).collect(),
+            current_time: self.snapshot().current_time(),
+            pause_time: self.get_pause_time(),
+            is_game_time_paused: self.is_game_time_paused(),
+            current_comparison: self.current_comparison.clone(),
+            current_timing_method: self.current_timing_method,
+        }
+    }
+
+    /// Mirrors the state of an attempt of another timer. The latency specifies
+    /// how long ago the state was captured, so the time of a running timer can
+    /// be compensated accordingly. The attempt is not stored in the history of
+    /// the Run, as the timer that is mirrored is responsible for that. The
+    /// comparison is only changed if the Run has a comparison with that name.
+    pub fn mirror_attempt_state(&mut self, state: &AttemptState, latency: TimeSpan) {
+        self.current_timing_method = state.current_timing_method;
+        if self
+            .run
+            .comparisons()
+            .any(|c| c == state.current_comparison)
+        {
+            state
+                .current_comparison
+                .as_str()
+                .populate(&mut self.current_comparison);
+        }
+
+        for (segment, &time) in self.run.segments_mut().iter_mut().zip(&state.split_times) {
+            segment.set_split_time(time);
+        }
+
+        if state.phase == NotRunning {
+            self.active_attempt = None;
+            return;
+        }
+
+        let is_running = state.phase == Running;
+        let compensate = |time: TimeSpan| if is_running { time + latency } else { time };
+        let real_time = compensate(state.current_time.real_time.unwrap_or_default());
+        let game_time = if state.is_game_time_paused {
+            state.current_time.game_time
+        } else {
+            state.current_time.game_time.map(compensate)
+        };
+
+        let attempt_started = self
+            .active_attempt
+            .as_ref()
+            .map_or_else(AtomicDateTime::now, |a| a.attempt_started);
+
+        self.active_attempt = Some(ActiveAttempt {
+            state: if state.phase == Ended {
+                State::Ended {
+                    attempt_ended: AtomicDateTime::now(),
+                }
+            } else {
+                State::NotEnded {
+                    current_split_index: state.current_split_index.unwrap_or_default(),
+                    time_paused_at: (state.phase == Paused).then_some(real_time),
+                }
+            },
+            attempt_started,
+            start_time: TimeStamp::now(),
+            original_offset: real_time + state.pause_time.unwrap_or_default(),
+            adjusted_offset: real_time,
+            game_time_paused_at: game_time.filter(|_| state.is_game_time_paused),
+            loading_times: game_time.map(|game_time| real_time - game_time),
+        });
     }
 
     /// Returns the total duration of the current attempt. This is not affected
@@ -661,7 +930,7 @@ impl Timer {
 
         if active_attempt.loading_times.is_none() {
             active_attempt.loading_times = Some(TimeSpan::zero());
-            Ok(Event::GameTimeInitialized)
+            Ok(self.emit(Event::GameTimeInitialized))
         } else {
             Err(Error::GameTimeAlreadyInitialized)
         }
@@ -696,7 +965,7 @@ impl Timer {
             active_attempt.game_time_paused_at =
                 current_time.game_time.or(Some(current_time.real_time));
 
-            Ok(Event::GameTimePaused)
+            Ok(self.emit(Event::GameTimePaused))
         } else {
             Err(Error::GameTimeAlreadyPaused)
         }
@@ -714,7 +983,7 @@ impl Timer {
             active_attempt.set_loading_times(diff.unwrap_or_default(), &self.run);
             active_attempt.game_time_paused_at = None;
 
-            Ok(Event::GameTimeResumed)
+            Ok(self.emit(Event::GameTimeResumed))
         } else {
             Err(Error::GameTimeNotPaused)
         }
@@ -734,7 +1003,7 @@ impl Timer {
         active_attempt.loading_times =
             Some(active_attempt.current_time(&self.run).real_time - game_time);
 
-        Ok(Event::GameTimeSet)
+        Ok(self.emit(Event::GameTimeSet))
     }
 
     /// Accesses the loading times. Loading times are defined as Game Time - Real Time.
@@ -753,7 +1022,7 @@ impl Timer {
     pub fn set_loading_times(&mut self, time: TimeSpan) -> Result {
         if let Some(active_attempt) = &mut self.active_attempt {
             active_attempt.set_loading_times(time, &self.run);
-            Ok(Event::LoadingTimesSet)
+            Ok(self.emit(Event::LoadingTimesSet))
         } else {
             Err(Error::NoRunInProgress)
         }
@@ -772,6 +1041,7 @@ impl Timer {
         if var.is_permanent {
             self.run.mark_as_modified();
         }
+        self.emit(Event::CustomVariableSet);
     }
 
     /// Notifies the `Timer` that the currently loaded [`Layout`](crate::Layout)
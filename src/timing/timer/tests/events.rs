@@ -884,3 +884,95 @@ mod set_loading_times {
 mod set_custom_variable {
     // Infallible
 }
+
+mod event_listeners {
+    use super::*;
+    use crate::timing::ListenerId;
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Received {
+        count: AtomicUsize,
+        last: AtomicU32,
+    }
+
+    impl Received {
+        fn get(&self) -> (usize, u32) {
+            (
+                self.count.load(Ordering::Relaxed),
+                self.last.load(Ordering::Relaxed),
+            )
+        }
+    }
+
+    fn listen(timer: &mut Timer) -> (Arc<Received>, ListenerId) {
+        let received = Arc::new(Received::default());
+        let id = timer.add_event_listener({
+            let received = received.clone();
+            move |event| {
+                received.count.fetch_add(1, Ordering::Relaxed);
+                received.last.store(event as u32, Ordering::Relaxed);
+            }
+        });
+        (received, id)
+    }
+
+    #[test]
+    fn receives_every_event() {
+        let mut timer = timer();
+        let (received, _) = listen(&mut timer);
+
+        timer.start().unwrap();
+        assert_eq!(received.get(), (1, Event::Started as u32));
+
+        timer.split().unwrap();
+        assert_eq!(received.get(), (2, Event::Splitted as u32));
+
+        timer.pause().unwrap();
+        assert_eq!(received.get(), (3, Event::Paused as u32));
+
+        timer.undo_all_pauses().unwrap();
+        assert_eq!(received.get(), (4, Event::PausesUndoneAndResumed as u32));
+
+        timer.switch_to_next_comparison();
+        assert_eq!(received.get(), (5, Event::ComparisonChanged as u32));
+
+        timer.reset(true).unwrap();
+        assert_eq!(received.get(), (6, Event::Reset as u32));
+    }
+
+    #[test]
+    fn failed_commands_emit_nothing() {
+        let mut timer = timer();
+        let (received, _) = listen(&mut timer);
+
+        timer.split().unwrap_err();
+        timer.resume().unwrap_err();
+
+        assert_eq!(received.get().0, 0);
+    }
+
+    #[test]
+    fn removed_listener_is_not_called() {
+        let mut timer = timer();
+        let (received, id) = listen(&mut timer);
+
+        assert!(timer.remove_event_listener(id));
+        assert!(!timer.remove_event_listener(id));
+        timer.start().unwrap();
+
+        assert_eq!(received.get().0, 0);
+    }
+
+    #[test]
+    fn clone_has_no_listeners() {
+        let mut timer = timer();
+        let (received, _) = listen(&mut timer);
+
+        let mut clone = timer.clone();
+        clone.start().unwrap();
+
+        assert_eq!(received.get().0, 0);
+    }
+}
@@ -689,3 +689,59 @@ fn paused_then_resumed_game_time_lags_behind_real_time() {
         .current_time(timer.run());
     assert!(time.game_time.unwrap() < time.real_time);
 }
+
+#[test]
+fn starting_at_a_future_point_in_time_counts_up_from_a_negative_time() {
+    let mut timer = timer();
+
+    let start = crate::platform::utc_now() + crate::platform::Duration::seconds(10);
+    timer.start_at(start).unwrap();
+
+    let real_time = timer.snapshot().current_time().real_time.unwrap();
+    assert!(real_time < TimeSpan::from_seconds(-9.0));
+    assert!(real_time > TimeSpan::from_seconds(-10.0));
+    assert_eq!(
+        timer.active_attempt.as_ref().unwrap().attempt_started.time,
+        start,
+    );
+}
+
+#[test]
+fn time_until_next_change() {
+    use super::time_until_next_step;
+    use crate::timing::formatter::Accuracy;
+
+    let step = Accuracy::Hundredths.nanoseconds_per_step() as i128;
+    assert_eq!(
+        time_until_next_step(TimeSpan::from_milliseconds(1234.0), step),
+        TimeSpan::from_milliseconds(6.0),
+    );
+    assert_eq!(
+        time_until_next_step(TimeSpan::from_milliseconds(1230.0), step),
+        TimeSpan::from_milliseconds(10.0),
+    );
+    assert_eq!(
+        time_until_next_step(TimeSpan::from_milliseconds(-1234.0), step),
+        TimeSpan::from_milliseconds(4.0),
+    );
+
+    let mut timer = timer();
+    assert_eq!(
+        timer.snapshot().time_until_next_change(Accuracy::Seconds),
+        None,
+    );
+
+    timer.start().unwrap();
+    let until_next_change = timer
+        .snapshot()
+        .time_until_next_change(Accuracy::Seconds)
+        .unwrap();
+    assert!(until_next_change > TimeSpan::zero());
+    assert!(until_next_change <= TimeSpan::from_seconds(1.0));
+
+    timer.pause().unwrap();
+    assert_eq!(
+        timer.snapshot().time_until_next_change(Accuracy::Seconds),
+        None,
+    );
+}
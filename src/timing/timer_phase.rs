@@ -16,6 +16,18 @@ pub enum TimerPhase {
 }
 
 impl TimerPhase {
+    /// Returns the English name of the phase that can be shown to the user.
+    /// It can be translated with a
+    /// [`Localization`](crate::localization::Localization).
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::NotRunning => "Not Running",
+            Self::Running => "Running",
+            Self::Ended => "Ended",
+            Self::Paused => "Paused",
+        }
+    }
+
     /// Returns [`true`] if the value is [`TimerPhase::NotRunning`].
     pub const fn is_not_running(&self) -> bool {
         matches!(self, Self::NotRunning)
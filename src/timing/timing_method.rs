@@ -2,7 +2,7 @@ use serde_derive::{Deserialize, Serialize};
 
 /// A `TimingMethod` describes which form of timing is used. This can either be
 /// [`TimingMethod::RealTime`] or [`TimingMethod::GameTime`].
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
 #[repr(u8)]
 pub enum TimingMethod {
     /// `Real Time` is the unmodified timing that is as close to an atomic clock
@@ -10,6 +10,7 @@ pub(crate) mod image;
 pub(crate) mod not_nan;
 pub mod ordered_map;
 mod populate_string;
+pub mod state_stream;
 #[cfg(test)]
 pub mod tests_helper;
 pub(crate) mod xml;
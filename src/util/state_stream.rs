@@ -0,0 +1,427 @@
+//! Provides a versioned protocol for propagating the changes of state objects,
+//! such as the [`LayoutState`](crate::layout::LayoutState), to consumers that
+//! don't have direct access to them, such as other processes, browser based
+//! clients or bindings that only consume JSON.
+//!
+//! The producer pushes the state into a [`StateStream`] whenever it updates it,
+//! for example once per frame. The first time this produces a full snapshot of
+//! the state. Afterwards only the changes are produced as a delta, encoded as a
+//! JSON Merge Patch (RFC 7386). No update is produced at all if nothing
+//! changed. Every update is tagged with a generation that increases by one for
+//! each update, so a consumer can detect whether it missed an update. The
+//! consumer applies the updates to a [`StateMirror`], which reconstructs the
+//! state. If the mirror detects a gap, the consumer should request a new
+//! snapshot from the producer.
+//!
+//! The updates are encoded as JSON like this:
+//! ```json
+//! { "generation": 1, "snapshot": { "components": [...], ... } }
+//! { "generation": 2, "delta": { "components": [...] } }
+//! ```
+//!
+//! As JSON Merge Patches use `null` to remove a value, they can't set a value
+//! to `null`. Whenever a value changes to `null`, a snapshot is produced
+//! instead of a delta.
+
+use crate::platform::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// An update produced by a [`StateStream`] that can be applied to a
+/// [`StateMirror`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "EncodedUpdate", into = "EncodedUpdate")]
+pub enum Update {
+    /// The full state. It replaces any state the consumer had before.
+    Snapshot {
+        /// The generation of the state.
+        generation: u64,
+        /// The full state.
+        state: Value,
+    },
+    /// The changes since the previous generation, encoded as a JSON Merge
+    /// Patch. It can only be applied to the state of the previous generation.
+    Delta {
+        /// The generation of the state after applying the changes.
+        generation: u64,
+        /// The changes to apply.
+        patch: Value,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncodedUpdate {
+    generation: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    snapshot: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    delta: Option<Value>,
+}
+
+impl From<Update> for EncodedUpdate {
+    fn from(update: Update) -> Self {
+        match update {
+            Update::Snapshot { generation, state } => Self {
+                generation,
+                snapshot: Some(state),
+                delta: None,
+            },
+            Update::Delta { generation, patch } => Self {
+                generation,
+                snapshot: None,
+                delta: Some(patch),
+            },
+        }
+    }
+}
+
+impl TryFrom<EncodedUpdate> for Update {
+    type Error = &'static str;
+
+    fn try_from(encoded: EncodedUpdate) -> Result<Self, Self::Error> {
+        let generation = encoded.generation;
+        match (encoded.snapshot, encoded.delta) {
+            (Some(state), None) => Ok(Update::Snapshot { generation, state }),
+            (None, Some(patch)) => Ok(Update::Delta { generation, patch }),
+            _ => Err("expected either a snapshot or a delta"),
+        }
+    }
+}
+
+impl Update {
+    /// Accesses the generation of the state after applying the update.
+    pub const fn generation(&self) -> u64 {
+        match self {
+            Update::Snapshot { generation, .. } | Update::Delta { generation, .. } => *generation,
+        }
+    }
+
+    /// Decodes an update from its JSON representation.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Encodes the update as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// A State Stream turns the states pushed into it into a stream of snapshots
+/// and deltas tagged with their generation. A separate State Stream is needed
+/// for each kind of state that is to be propagated.
+#[derive(Debug, Clone, Default)]
+pub struct StateStream {
+    generation: u64,
+    state: Option<Value>,
+}
+
+impl StateStream {
+    /// Creates a new State Stream that doesn't have any state yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accesses the generation of the most recent state. This is 0 if no state
+    /// has been pushed yet.
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Pushes the current state into the stream. Returns the update to send to
+    /// the consumers. This is a snapshot for the first state and a delta
+    /// afterwards, unless the delta would need to set a value to `null`. If the
+    /// state didn't change, [`None`] is returned and the generation stays the
+    /// same.
+    pub fn push<T: serde::Serialize + ?Sized>(&mut self, state: &T) -> Option<Update> {
+        self.push_value(serde_json::to_value(state).ok()?)
+    }
+
+    /// Pushes the current state, already encoded as a JSON value, into the
+    /// stream. This works just like [`push`](Self::push).
+    pub fn push_value(&mut self, state: Value) -> Option<Update> {
+        let update = match self.state.as_ref().map(|previous| diff(previous, &state)) {
+            Some(Ok(patch)) => Update::Delta {
+                generation: self.generation + 1,
+                patch: patch?,
+            },
+            Some(Err(SetsNull)) | None => Update::Snapshot {
+                generation: self.generation + 1,
+                state: state.clone(),
+            },
+        };
+        self.generation += 1;
+        self.state = Some(state);
+        Some(update)
+    }
+
+    /// Creates a snapshot of the most recent state. This is meant to be sent to
+    /// consumers that connect later on or that missed an update. Returns
+    /// [`None`] if no state has been pushed yet.
+    pub fn snapshot(&self) -> Option<Update> {
+        Some(Update::Snapshot {
+            generation: self.generation,
+            state: self.state.clone()?,
+        })
+    }
+}
+
+/// The Error type for updates that couldn't be applied to a [`StateMirror`].
+#[derive(Debug, snafu::Snafu, PartialEq, Eq)]
+#[snafu(context(suffix(false)))]
+pub enum ApplyError {
+    /// A delta can't be applied before a snapshot has been received.
+    MissingSnapshot,
+    /// The delta doesn't follow the state of the mirror. At least one update
+    /// has been missed, so a new snapshot is needed.
+    GenerationMismatch {
+        /// The generation the delta needs to have.
+        expected: u64,
+        /// The generation the delta actually has.
+        actual: u64,
+    },
+}
+
+/// A State Mirror reconstructs the state on the consumer's side by applying
+/// the updates produced by a [`StateStream`].
+#[derive(Debug, Clone, Default)]
+pub struct StateMirror {
+    generation: u64,
+    state: Option<Value>,
+}
+
+impl StateMirror {
+    /// Creates a new State Mirror that doesn't have any state yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accesses the generation of the mirrored state. This is 0 if no snapshot
+    /// has been applied yet.
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Accesses the mirrored state. Returns [`None`] if no snapshot has been
+    /// applied yet.
+    pub const fn state(&self) -> Option<&Value> {
+        self.state.as_ref()
+    }
+
+    /// Applies an update to the mirrored state. Snapshots can always be
+    /// applied, while deltas need to directly follow the generation of the
+    /// mirrored state. If a delta can't be applied, the state stays unchanged.
+    pub fn apply(&mut self, update: Update) -> Result<(), ApplyError> {
+        match update {
+            Update::Snapshot { generation, state } => {
+                self.generation = generation;
+                self.state = Some(state);
+            }
+            Update::Delta { generation, patch } => {
+                let state = self.state.as_mut().ok_or(ApplyError::MissingSnapshot)?;
+                if generation != self.generation + 1 {
+                    return Err(ApplyError::GenerationMismatch {
+                        expected: self.generation + 1,
+                        actual: generation,
+                    });
+                }
+                apply_patch(state, patch);
+                self.generation = generation;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A JSON Merge Patch can't set a value to `null`, as that removes the value
+/// instead.
+struct SetsNull;
+
+/// Calculates the JSON Merge Patch that turns the old value into the new one.
+/// Returns [`None`] if they are the same.
+fn diff(old: &Value, new: &Value) -> Result<Option<Value>, SetsNull> {
+    let (Value::Object(old), Value::Object(new)) = (old, new) else {
+        return if old == new {
+            Ok(None)
+        } else {
+            replacement(new).map(Some)
+        };
+    };
+
+    let mut patch = Map::new();
+    for (key, old_value) in old {
+        match new.get(key) {
+            Some(new_value) => {
+                if let Some(delta) = diff(old_value, new_value)? {
+                    patch.insert(key.clone(), delta);
+                }
+            }
+            None => {
+                patch.insert(key.clone(), Value::Null);
+            }
+        }
+    }
+    for (key, new_value) in new {
+        if !old.contains_key(key) {
+            patch.insert(key.clone(), replacement(new_value)?);
+        }
+    }
+
+    Ok((!patch.is_empty()).then_some(Value::Object(patch)))
+}
+
+/// Returns the value to put into the patch to replace a value with the new
+/// one. Objects get merged into the value they replace, so neither the value
+/// nor any of the fields of an object may be `null`.
+fn replacement(new: &Value) -> Result<Value, SetsNull> {
+    fn contains_null(value: &Value) -> bool {
+        match value {
+            Value::Null => true,
+            Value::Object(fields) => fields.values().any(contains_null),
+            _ => false,
+        }
+    }
+
+    if contains_null(new) {
+        Err(SetsNull)
+    } else {
+        Ok(new.clone())
+    }
+}
+
+fn apply_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(&key);
+        } else {
+            apply_patch(target.entry(key).or_insert(Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_patch() {
+        let old = json!({ "a": 1, "b": { "c": 2, "d": 3 }, "e": 4 });
+        let new = json!({ "a": 1, "b": { "c": 2, "d": 5 }, "f": [6] });
+        let patch = diff(&old, &new).ok().flatten().unwrap();
+        assert_eq!(patch, json!({ "b": { "d": 5 }, "e": null, "f": [6] }));
+        assert!(matches!(diff(&new, &new), Ok(None)));
+
+        let mut patched = old;
+        apply_patch(&mut patched, patch);
+        assert_eq!(patched, new);
+    }
+
+    #[test]
+    fn streams_snapshots_and_deltas() {
+        let mut stream = StateStream::new();
+        assert_eq!(stream.snapshot(), None);
+
+        let snapshot = stream.push(&json!({ "time": "1.00", "phase": "Running" }));
+        assert_eq!(
+            snapshot.as_ref().map(Update::to_json).as_deref(),
+            Some(r#"{"generation":1,"snapshot":{"phase":"Running","time":"1.00"}}"#),
+        );
+        assert_eq!(
+            stream.push(&json!({ "time": "1.00", "phase": "Running" })),
+            None
+        );
+
+        let delta = stream
+            .push(&json!({ "time": "1.50", "phase": "Running" }))
+            .unwrap();
+        assert_eq!(
+            delta,
+            Update::Delta {
+                generation: 2,
+                patch: json!({ "time": "1.50" }),
+            },
+        );
+
+        let mut mirror = StateMirror::new();
+        assert_eq!(
+            mirror.apply(delta.clone()),
+            Err(ApplyError::MissingSnapshot)
+        );
+        mirror.apply(snapshot.unwrap()).unwrap();
+        mirror
+            .apply(Update::from_json(&delta.to_json()).unwrap())
+            .unwrap();
+        assert_eq!(mirror.generation(), 2);
+        assert_eq!(
+            mirror.state(),
+            Some(&json!({ "time": "1.50", "phase": "Running" }))
+        );
+    }
+
+    #[test]
+    fn sends_snapshots_for_values_changing_to_null() {
+        let mut stream = StateStream::new();
+        let mut mirror = StateMirror::new();
+        mirror
+            .apply(stream.push(&json!({ "a": 1, "b": { "c": 2 } })).unwrap())
+            .unwrap();
+
+        let states = [
+            json!({ "a": null, "b": { "c": 2 } }),
+            json!({ "a": 1, "b": { "c": null } }),
+            json!({ "a": 1, "b": { "c": 2 }, "d": { "e": null } }),
+            json!({ "a": 1, "b": [null] }),
+        ];
+        for state in states {
+            let update = stream.push(&state).unwrap();
+            mirror.apply(update).unwrap();
+            assert_eq!(mirror.state(), Some(&state));
+        }
+
+        // Arrays are replaced as a whole, so they may contain null.
+        assert!(matches!(
+            stream.push(&json!({ "a": 1, "b": [null, 1] })),
+            Some(Update::Delta { .. }),
+        ));
+        assert!(matches!(
+            stream.push(&json!({ "a": null })),
+            Some(Update::Snapshot { generation: 7, .. }),
+        ));
+    }
+
+    #[test]
+    fn detects_missed_updates() {
+        let mut stream = StateStream::new();
+        let mut mirror = StateMirror::new();
+        mirror
+            .apply(stream.push(&json!({ "a": 1 })).unwrap())
+            .unwrap();
+        stream.push(&json!({ "a": 2 })).unwrap();
+        let delta = stream.push(&json!({ "a": 3 })).unwrap();
+
+        assert_eq!(
+            mirror.apply(delta),
+            Err(ApplyError::GenerationMismatch {
+                expected: 2,
+                actual: 3,
+            }),
+        );
+
+        mirror.apply(stream.snapshot().unwrap()).unwrap();
+        assert_eq!(mirror.generation(), 3);
+        assert_eq!(mirror.state(), Some(&json!({ "a": 3 })));
+    }
+}
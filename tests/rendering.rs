@@ -456,6 +456,325 @@ fn horizontal() {
     );
 }
 
+#[cfg(feature = "software-rendering")]
+#[test]
+fn png_snapshot() {
+    let timer = Timer::new(tests_helper::create_run(&["A", "B"])).unwrap();
+    let mut layout = Layout::default_layout();
+    let mut image_cache = ImageCache::new();
+    let state = layout.state(&mut image_cache, &timer.snapshot());
+
+    let mut renderer = rendering::software::Renderer::new();
+    renderer.render(&state, &image_cache, [300, 500]);
+    let png = renderer.encode_png();
+
+    let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+    assert_eq!(decoded.dimensions(), (300, 500));
+    assert_eq!(decoded.as_raw()[..], *renderer.image_data());
+
+    let mut renderer = rendering::software::BorrowedRenderer::new();
+    assert_eq!(renderer.render_png(&state, &image_cache, [300, 500]), png);
+}
+
+#[cfg(feature = "software-rendering")]
+#[test]
+fn dirty_rows() {
+    let mut timer = Timer::new(tests_helper::create_run(&["A", "B"])).unwrap();
+    let mut layout = Layout::default_layout();
+    let mut image_cache = ImageCache::new();
+    let mut renderer = rendering::software::Renderer::new();
+
+    let state = layout.state(&mut image_cache, &timer.snapshot());
+    renderer.render(&state, &image_cache, [300, 500]);
+    assert_eq!(renderer.dirty_rows(), Some(0..500));
+
+    renderer.render(&state, &image_cache, [300, 500]);
+    assert_eq!(renderer.dirty_rows(), None);
+
+    tests_helper::start_run(&mut timer);
+    tests_helper::make_progress_run_with_splits_opt(&mut timer, &[Some(5.0)]);
+    let state = layout.state(&mut image_cache, &timer.snapshot());
+    renderer.render(&state, &image_cache, [300, 500]);
+    assert!(renderer.dirty_rows().is_some());
+}
+
+#[cfg(feature = "shared-frame-buffer")]
+#[test]
+fn shared_frame_buffer() {
+    use rendering::shared_frame_buffer::{SharedFrameBuffer, HEADER_LEN};
+
+    let timer = Timer::new(tests_helper::create_run(&["A", "B"])).unwrap();
+    let mut layout = Layout::default_layout();
+    let mut image_cache = ImageCache::new();
+    let state = layout.state(&mut image_cache, &timer.snapshot());
+
+    let path = std::env::temp_dir().join(format!("livesplit-frame-{}", std::process::id()));
+    let mut frame_buffer = SharedFrameBuffer::create(&path).unwrap();
+    frame_buffer
+        .render(&state, &image_cache, [300, 500])
+        .unwrap();
+    frame_buffer
+        .render(&state, &image_cache, [300, 500])
+        .unwrap();
+
+    let mut renderer = rendering::software::Renderer::new();
+    renderer.render(&state, &image_cache, [300, 500]);
+    assert_eq!(frame_buffer.image_data(), renderer.image_data());
+
+    let file = fs::read(&path).unwrap();
+    let field = |i: usize| u32::from_ne_bytes(file[4 * i..][..4].try_into().unwrap());
+    assert_eq!(file[..4], *b"LSFB");
+    assert_eq!([field(1), field(2)], [1, 4]);
+    assert_eq!([field(3), field(4), field(5)], [300, 500, 1200]);
+    // Nothing changed in the second frame.
+    assert_eq!([field(6), field(7)], [0, 0]);
+    assert_eq!(file[HEADER_LEN..], *renderer.image_data());
+
+    frame_buffer
+        .render(&state, &image_cache, [200, 100])
+        .unwrap();
+    let file = fs::read(&path).unwrap();
+    assert_eq!(
+        file[HEADER_LEN..][..200 * 100 * 4],
+        *frame_buffer.image_data()
+    );
+    assert_eq!(file[20..32], [800, 0, 100].map(u32::to_ne_bytes).concat());
+
+    drop(frame_buffer);
+    fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "software-rendering")]
+#[test]
+fn load_font() {
+    let timer = Timer::new(tests_helper::create_run(&["A", "B"])).unwrap();
+    let mut layout = Layout::default_layout();
+    let mut image_cache = ImageCache::new();
+    let state = layout.state(&mut image_cache, &timer.snapshot());
+
+    let mut renderer = rendering::software::Renderer::new();
+    renderer.render(&state, &image_cache, [300, 500]);
+
+    assert!(!renderer.load_font(b"not a font".to_vec()));
+    assert!(renderer
+        .load_font(include_bytes!("../src/rendering/font/assets/FiraSans-Regular.ttf").to_vec()));
+
+    // The fonts resolve to the same faces, so the image doesn't change.
+    let before = renderer.image_data().to_vec();
+    renderer.render(&state, &image_cache, [300, 500]);
+    assert_eq!(renderer.image_data(), before);
+}
+
+#[cfg(feature = "software-rendering")]
+#[test]
+fn style_parameters() {
+    let timer = Timer::new(tests_helper::create_run(&["A", "B"])).unwrap();
+    let mut layout = Layout::default_layout();
+    let mut image_cache = ImageCache::new();
+    let mut renderer = rendering::software::Renderer::new();
+
+    let state = layout.state(&mut image_cache, &timer.snapshot());
+    renderer.render(&state, &image_cache, [300, 500]);
+    let default = renderer.image_data().to_vec();
+
+    let settings = layout.general_settings_mut();
+    settings.corner_radius = 6;
+    settings.drop_shadow = Some(livesplit_core::settings::Color::black());
+    settings.separator_thickness = 200;
+    settings.padding_scale = 150;
+
+    let state = layout.state(&mut image_cache, &timer.snapshot());
+    renderer.render(&state, &image_cache, [300, 500]);
+    assert_ne!(renderer.image_data(), default);
+
+    let settings = layout.general_settings_mut();
+    settings.corner_radius = 0;
+    settings.drop_shadow = None;
+    settings.separator_thickness = 100;
+    settings.padding_scale = 100;
+
+    let state = layout.state(&mut image_cache, &timer.snapshot());
+    renderer.render(&state, &image_cache, [300, 500]);
+    assert_eq!(renderer.image_data(), default);
+}
+
+#[cfg(feature = "software-rendering")]
+#[test]
+fn gradients() {
+    use livesplit_core::settings::{Color, Gradient, LayoutBackground};
+
+    let timer = Timer::new(tests_helper::create_run(&["A"])).unwrap();
+    let mut layout = Layout::new();
+    layout.push(component::blank_space::Component::new());
+    let mut image_cache = ImageCache::new();
+    let mut renderer = rendering::software::Renderer::new();
+
+    let mut render = |gradient| {
+        layout.general_settings_mut().background = LayoutBackground::Gradient(gradient);
+        let state = layout.state(&mut image_cache, &timer.snapshot());
+        renderer.render(&state, &image_cache, [100, 100]);
+        image::RgbaImage::from_raw(100, 100, renderer.image_data().to_vec()).unwrap()
+    };
+
+    let (red, blue) = (
+        Color::rgba(1.0, 0.0, 0.0, 1.0),
+        Color::rgba(0.0, 0.0, 1.0, 1.0),
+    );
+
+    // The angles follow CSS, so they go towards the right and the bottom.
+    let linear = render(Gradient::Linear(90.0, red, blue));
+    assert_eq!(linear.get_pixel(0, 50).0, [254, 0, 1, 255]);
+    assert_eq!(linear.get_pixel(99, 50).0, [1, 0, 254, 255]);
+    assert_eq!(linear, render(Gradient::Horizontal(red, blue)));
+    assert_eq!(
+        render(Gradient::Linear(180.0, red, blue)),
+        render(Gradient::Vertical(red, blue)),
+    );
+
+    // The radial gradient reaches the edge color at the corners.
+    let radial = render(Gradient::Radial(red, blue));
+    assert_eq!(radial.get_pixel(50, 50).0, [252, 0, 3, 255]);
+    assert_eq!(radial.get_pixel(0, 0).0, [3, 0, 252, 255]);
+    assert_eq!(radial.get_pixel(0, 50), radial.get_pixel(50, 0));
+}
+
+#[cfg(all(feature = "wgpu-rendering", feature = "software-rendering"))]
+#[test]
+fn wgpu() {
+    use std::{
+        future::Future,
+        pin::pin,
+        sync::Arc,
+        task::{Context, Poll, Wake},
+        thread::{self, Thread},
+    };
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            thread::park();
+        }
+    }
+
+    let instance = wgpu::Instance::default();
+    let Some(adapter) = block_on(instance.request_adapter(&Default::default())) else {
+        // Neither a GPU nor a software rasterizer may be available.
+        println!("Skipping the wgpu test, as there is no adapter available.");
+        return;
+    };
+    let (device, queue) = block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            required_limits: adapter.limits(),
+            ..Default::default()
+        },
+        None,
+    ))
+    .unwrap();
+
+    let mut timer = Timer::new(tests_helper::create_run(&["A", "B", "C"])).unwrap();
+    tests_helper::start_run(&mut timer);
+    tests_helper::make_progress_run_with_splits_opt(&mut timer, &[Some(5.0)]);
+    let mut layout = Layout::default_layout();
+    let mut image_cache = ImageCache::new();
+    let state = layout.state(&mut image_cache, &timer.snapshot());
+
+    let [width, height] = [300, 500];
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&Default::default());
+
+    let mut renderer =
+        rendering::wgpu::Renderer::new(&device, &queue, wgpu::TextureFormat::Rgba8Unorm);
+    // Render twice, so the cached meshes get used as well.
+    renderer.render(
+        &device,
+        &queue,
+        &view,
+        &state,
+        &image_cache,
+        [width, height],
+    );
+    let new_size = renderer.render(
+        &device,
+        &queue,
+        &view,
+        &state,
+        &image_cache,
+        [width, height],
+    );
+
+    let padded_row = (4 * width).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: (padded_row * height) as _,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&Default::default());
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row),
+                rows_per_image: None,
+            },
+        },
+        texture.size(),
+    );
+    queue.submit([encoder.finish()]);
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, Result::unwrap);
+    device.poll(wgpu::Maintain::Wait);
+    let gpu_image: Vec<u8> = buffer
+        .slice(..)
+        .get_mapped_range()
+        .chunks(padded_row as usize)
+        .flat_map(|row| &row[..4 * width as usize])
+        .copied()
+        .collect();
+
+    let mut software = rendering::software::Renderer::new();
+    let software_size = software.render(&state, &image_cache, [width, height]);
+    assert_eq!(new_size, software_size);
+
+    // The edges are anti-aliased differently, but everything else needs to
+    // look the same as with the software renderer.
+    let differing_pixels = gpu_image
+        .chunks(4)
+        .zip(software.image_data().chunks(4))
+        .filter(|(a, b)| a.iter().zip(*b).any(|(a, b)| a.abs_diff(*b) > 8))
+        .count();
+    assert!(differing_pixels < (width * height / 100) as usize);
+}
+
 #[track_caller]
 fn check(
     state: &LayoutState,
@@ -114,6 +114,28 @@ mod parse {
         );
     }
 
+    #[test]
+    fn time_split_tracker_run_log() {
+        let mut run = time_split_tracker::parse(run_files::TIME_SPLIT_TRACKER, None).unwrap();
+        time_split_tracker::parse_run_log(
+            &mut run,
+            "Started\tCompleted\n2014/05/17 13:37\tC\t6:00.00\t12:00.00\n2014/05/18 10:00\tR\t6:30.00\n",
+        )
+        .unwrap();
+        assert_eq!(run.attempt_history().len(), 2);
+        assert_eq!(
+            run.segment(1).segment_history().get(1).unwrap().real_time,
+            Some(TimeSpan::from_seconds(360.0))
+        );
+        assert!(time_split_tracker::parse_run_log(
+            &mut run,
+            "Header\n2014/05/19 10:00\tC\t5:00.00\t10:00.00\nInvalid\n",
+        )
+        .is_err());
+        assert_eq!(run.attempt_history().len(), 2);
+        assert_eq!(run.segment(1).segment_history().get(3), None);
+    }
+
     #[test]
     fn time_split_tracker_without_attempt_count() {
         time_split_tracker::parse(run_files::TIME_SPLIT_TRACKER_WITHOUT_ATTEMPT_COUNT, None)
@@ -216,4 +238,10 @@ mod parse {
         let run = composite::parse(run_files::FLITTER.as_bytes(), None).unwrap();
         assert_eq!(run.kind, TimerKind::Flitter);
     }
+
+    #[test]
+    fn cancelled_parsing_doesnt_parse() {
+        let result = composite::parse_cancellable(run_files::FLITTER.as_bytes(), None, || true);
+        assert!(matches!(result, Err(composite::Error::Cancelled)));
+    }
 }